@@ -0,0 +1,81 @@
+//! Compares building the urls of a directory with `format_url` against `UrlBuilder`
+//!
+//! Run with `cargo bench --bench url_building`; prints the time taken per url by each
+use feroxbuster::utils::{format_url, UrlBuilder};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// number of words per run, about the size of raft-medium-directories.txt
+const WORDS: usize = 30_000;
+
+/// number of runs, the fastest is kept
+const RUNS: usize = 10;
+
+/// Fastest of `RUNS` runs of the given function, per url built
+fn fastest<F: FnMut() -> usize>(mut run: F) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            let built = run();
+            start.elapsed() / built as u32
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Time both ways of building the urls of the given directory
+fn compare(name: &str, url: &str, queries: &[(String, String)], extensions: &[&str]) {
+    let words: Vec<String> = (0..WORDS).map(|i| format!("word{}", i)).collect();
+
+    let formatted = fastest(|| {
+        let mut built = 0;
+
+        for word in &words {
+            built += black_box(format_url(url, word, false, queries, None)).is_ok() as usize;
+
+            for ext in extensions {
+                built +=
+                    black_box(format_url(url, word, false, queries, Some(ext))).is_ok() as usize;
+            }
+        }
+
+        built
+    });
+
+    let builder = UrlBuilder::new(url, false, queries);
+
+    let built = fastest(|| {
+        let mut built = 0;
+
+        for word in &words {
+            built += black_box(builder.build(word, None)).is_ok() as usize;
+
+            for ext in extensions {
+                built += black_box(builder.build(word, Some(ext))).is_ok() as usize;
+            }
+        }
+
+        built
+    });
+
+    println!(
+        "{:<24} format_url {:>6}ns/url    UrlBuilder {:>6}ns/url    {:.1}x",
+        name,
+        formatted.as_nanos(),
+        built.as_nanos(),
+        formatted.as_secs_f64() / built.as_secs_f64()
+    );
+}
+
+fn main() {
+    let queries = [(String::from("token"), String::from("abc123"))];
+
+    compare("words", "http://localhost/js", &[], &[]);
+    compare(
+        "words + extensions",
+        "http://localhost/js",
+        &[],
+        &["php", "bak"],
+    );
+    compare("words + query", "http://localhost/js", &queries, &[]);
+}
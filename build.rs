@@ -0,0 +1,38 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// Embed the output of `git describe` in the binary, so that results can be attributed to the
+/// exact build that produced them; builds from outside of a git checkout (i.e. a crates.io
+/// tarball or a vendored copy) use the crate's version instead
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let git = Path::new(&manifest_dir).join(".git");
+
+    // git describe would otherwise describe whatever repository a vendored copy sits in
+    let describe = if git.exists() {
+        Command::new("git")
+            .args(["describe", "--tags", "--always", "--dirty"])
+            .current_dir(&manifest_dir)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|describe| !describe.is_empty())
+    } else {
+        None
+    };
+
+    let describe = describe.unwrap_or_else(|| env::var("CARGO_PKG_VERSION").unwrap_or_default());
+
+    println!("cargo:rustc-env=FEROX_GIT_DESCRIBE={}", describe);
+
+    // paths that don't exist make cargo rerun the build script on every build
+    for path in ["HEAD", "index"] {
+        if git.join(path).exists() {
+            println!("cargo:rerun-if-changed=.git/{}", path);
+        }
+    }
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
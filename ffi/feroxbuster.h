@@ -0,0 +1,49 @@
+/*
+ * C interface to feroxbuster's scanner, see src/ffi.rs
+ *
+ * Build the shared library with
+ *     cargo rustc --release --lib --features ffi --crate-type cdylib
+ * and link against target/release/libferoxbuster.so (.dylib on macOS, .dll on Windows)
+ */
+#ifndef FEROXBUSTER_H
+#define FEROXBUSTER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* values returned by ferox_status */
+#define FEROX_IDLE 0
+#define FEROX_RUNNING 1
+#define FEROX_FINISHED 2
+#define FEROX_STOPPED 3
+#define FEROX_FAILED -1
+
+/*
+ * Start a scan in the background, configured by the given json object (same keys as
+ * ferox-config.toml, plus target_url); returns 0 when the scan was started, -1 otherwise
+ *
+ * Only one scan can be started per process
+ */
+int ferox_start(const char *config);
+
+/* state of the scan, one of the FEROX_* values */
+int ferox_status(void);
+
+/*
+ * Take the oldest result not yet taken, as a json string to be freed with ferox_free_string;
+ * returns NULL when there's none at the moment
+ */
+char *ferox_poll(void);
+
+/* stop the running scan; results found so far can still be taken with ferox_poll */
+void ferox_stop(void);
+
+/* free a string returned by ferox_poll */
+void ferox_free_string(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FEROXBUSTER_H */
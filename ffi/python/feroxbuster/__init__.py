@@ -0,0 +1,170 @@
+"""
+Run feroxbuster's scanner in-process from Python, through the C interface of the shared library
+(see ../../feroxbuster.h)
+
+Build the shared library with
+    cargo rustc --release --lib --features ffi --crate-type cdylib
+
+The library is looked up in FEROXBUSTER_LIBRARY, next to this package, then wherever the
+system's loader finds it.
+
+    import feroxbuster
+
+    with feroxbuster.Scan("http://127.1", extensions=["php"], quiet=True) as scan:
+        for result in scan:
+            print(result["status"], result["url"])
+
+Only one scan can be started per process, the configuration is global to the library.
+"""
+import ctypes
+import ctypes.util
+import json
+import os
+import time
+
+__all__ = ["Scan", "ScanError", "IDLE", "RUNNING", "FINISHED", "STOPPED", "FAILED"]
+
+# values returned by ferox_status
+IDLE = 0
+RUNNING = 1
+FINISHED = 2
+STOPPED = 3
+FAILED = -1
+
+# seconds between polls for new results while the scan is running
+POLL_INTERVAL = 0.2
+
+_LIBRARY_NAMES = ("libferoxbuster.so", "libferoxbuster.dylib", "feroxbuster.dll")
+
+_library = None
+
+
+class ScanError(Exception):
+    """The scan couldn't be started, or failed"""
+
+
+def _find_library():
+    """Path of the shared library, or its name for the system's loader"""
+    if os.environ.get("FEROXBUSTER_LIBRARY"):
+        return os.environ["FEROXBUSTER_LIBRARY"]
+
+    here = os.path.dirname(os.path.abspath(__file__))
+
+    for name in _LIBRARY_NAMES:
+        path = os.path.join(here, name)
+
+        if os.path.exists(path):
+            return path
+
+    found = ctypes.util.find_library("feroxbuster")
+
+    if found is None:
+        raise ScanError(
+            "libferoxbuster wasn't found; build it with "
+            "`cargo rustc --release --lib --features ffi --crate-type cdylib` "
+            "and point FEROXBUSTER_LIBRARY at it"
+        )
+
+    return found
+
+
+def _load():
+    """The shared library, loaded once, with the signatures of its functions"""
+    global _library
+
+    if _library is None:
+        library = ctypes.CDLL(_find_library())
+
+        library.ferox_start.argtypes = [ctypes.c_char_p]
+        library.ferox_start.restype = ctypes.c_int
+        library.ferox_status.argtypes = []
+        library.ferox_status.restype = ctypes.c_int
+        # a plain pointer, so that the string can be freed once copied
+        library.ferox_poll.argtypes = []
+        library.ferox_poll.restype = ctypes.c_void_p
+        library.ferox_stop.argtypes = []
+        library.ferox_stop.restype = None
+        library.ferox_free_string.argtypes = [ctypes.c_void_p]
+        library.ferox_free_string.restype = None
+
+        _library = library
+
+    return _library
+
+
+class Scan:
+    """
+    A scan of target_url, configured by keyword arguments with the same names as the keys of
+    ferox-config.toml (i.e. extensions=["php"], threads=10, quiet=True)
+
+    Iterating over a scan starts it if needed, then yields every result as a dict like the
+    records written with --json, until the scan is done; leaving a `with` block stops it
+    """
+
+    def __init__(self, target_url, **config):
+        self.config = dict(config, target_url=target_url)
+        self._library = _load()
+        self._started = False
+
+    def start(self):
+        """Start the scan in the background; raises ScanError when it couldn't be started"""
+        if self._started:
+            return
+
+        if self._library.ferox_start(json.dumps(self.config).encode()) != 0:
+            raise ScanError(
+                "the scan couldn't be started: its configuration is invalid, "
+                "or a scan was already started in this process"
+            )
+
+        self._started = True
+
+    @property
+    def status(self):
+        """State of the scan, one of IDLE, RUNNING, FINISHED, STOPPED, or FAILED"""
+        return self._library.ferox_status()
+
+    def poll(self):
+        """Oldest result not yet taken, or None when there's none at the moment"""
+        pointer = self._library.ferox_poll()
+
+        if not pointer:
+            return None
+
+        try:
+            return json.loads(ctypes.string_at(pointer).decode())
+        finally:
+            self._library.ferox_free_string(pointer)
+
+    def stop(self):
+        """Stop the scan; results found so far can still be taken"""
+        self._library.ferox_stop()
+
+    def __iter__(self):
+        self.start()
+
+        while True:
+            # the status is read before polling, so that nothing queued before the scan ended
+            # is left behind
+            running = self.status == RUNNING
+            result = self.poll()
+
+            if result is not None:
+                yield result
+            elif running:
+                time.sleep(POLL_INTERVAL)
+            else:
+                break
+
+        if self.status == FAILED:
+            raise ScanError("the scan failed, see feroxbuster's log for why")
+
+    def __enter__(self):
+        self.start()
+        return self
+
+    def __exit__(self, *_):
+        if self.status == RUNNING:
+            self.stop()
+
+        return False
@@ -0,0 +1,321 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::error_log::ErrorClass;
+use crate::exit_codes;
+use crate::utils::ferox_print;
+use crate::FeroxResponse;
+use console::style;
+use lazy_static::lazy_static;
+use reqwest::StatusCode;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{delay_until, Instant};
+
+/// Policies that may be given to `--ban-policy`
+pub const POLICIES: [&str; 5] = ["off", "alert", "pause", "slow", "stop"];
+
+/// number of most recent requests examined for signs of blocking
+const WINDOW: usize = 50;
+
+/// share of the window that needs to be blocked or failed before the scan is considered blocked
+const BLOCKED_SHARE: f64 = 0.9;
+
+/// number of CAPTCHA pages within the window before the scan is considered blocked
+const CAPTCHA_PAGES: usize = 5;
+
+/// how long `pause` stops all requests
+const PAUSE: Duration = Duration::from_secs(60);
+
+/// delay added before each request by the first `slow` reaction; doubled by each one after it
+const FIRST_DELAY_MS: u64 = 250;
+
+/// longest delay added before each request by `slow`
+const MAX_DELAY_MS: u64 = 10_000;
+
+/// markers of the CAPTCHA / challenge pages served by common WAFs, see `markers::Markers`
+pub(crate) const CAPTCHA_PATTERN: &str =
+    r"(?i)g-recaptcha|h-captcha|cf-chl-|captcha-delivery|<title>[^<]*captcha";
+
+lazy_static! {
+    /// Detector shared by every scan
+    static ref DETECTOR: Mutex<Detector> = Mutex::new(Detector::default());
+
+    /// Earliest time at which requests may be made again, after `pause`
+    static ref PAUSED_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+/// Delay added before each request, after `slow`
+static DELAY_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Whether or not the user was already alerted; `alert` only does so once, the other policies
+/// react every time
+static ALERTED: AtomicBool = AtomicBool::new(false);
+
+/// What a single request says about whether or not the scan is blocked
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Outcome {
+    /// a response that doesn't look like blocking
+    Normal,
+
+    /// a 403 or 429 response
+    Blocked,
+
+    /// a CAPTCHA or challenge page
+    Captcha,
+
+    /// the request failed, i.e. the connection was reset or timed out
+    Failed,
+}
+
+impl Outcome {
+    /// Classify a response from its status code, headers and (possibly unread) body
+    pub fn from_response(response: &FeroxResponse) -> Self {
+        let challenged = response
+            .headers()
+            .get("cf-mitigated")
+            .is_some_and(|value| value == "challenge");
+
+        if challenged || response.markers().captcha {
+            Outcome::Captcha
+        } else if is_block_status(*response.status()) {
+            Outcome::Blocked
+        } else {
+            Outcome::Normal
+        }
+    }
+
+    /// Classify a failed request; redirect loops are the target's doing, not blocking
+    pub fn from_error(error: &(dyn Error + 'static)) -> Self {
+        match ErrorClass::from_error(error) {
+            ErrorClass::Redirection => Outcome::Normal,
+            _ => Outcome::Failed,
+        }
+    }
+}
+
+/// Whether or not the given status code is typical of a WAF or rate limiter blocking requests;
+/// the bodies of such responses are read to look for CAPTCHA pages
+pub fn is_block_status(status: StatusCode) -> bool {
+    status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Keeps track of the most recent outcomes and decides when they add up to the scan being blocked
+#[derive(Debug, Default)]
+pub struct Detector {
+    /// outcomes of the most recent requests, oldest first
+    window: VecDeque<Outcome>,
+
+    /// share of blocked responses among the scan's first `WINDOW` requests; `None` until that
+    /// many requests were made
+    baseline: Option<f64>,
+
+    /// number of requests recorded before the baseline was known
+    seen: usize,
+
+    /// blocked responses among those requests
+    seen_blocked: usize,
+}
+
+impl Detector {
+    /// Record the outcome of a request, returning a description of the blocking when the window
+    /// shows signs of it; the window starts over afterwards
+    pub fn record(&mut self, outcome: Outcome) -> Option<&'static str> {
+        if self.baseline.is_none() {
+            self.seen += 1;
+
+            if outcome == Outcome::Blocked {
+                self.seen_blocked += 1;
+            }
+
+            if self.seen == WINDOW {
+                self.baseline = Some(self.seen_blocked as f64 / WINDOW as f64);
+            }
+        }
+
+        self.window.push_back(outcome);
+
+        if self.window.len() > WINDOW {
+            self.window.pop_front();
+        }
+
+        let count = |kind| self.window.iter().filter(|o| **o == kind).count();
+
+        let reason = if count(Outcome::Captcha) >= CAPTCHA_PAGES {
+            Some("CAPTCHA pages")
+        } else if self.window.len() < WINDOW {
+            None
+        } else if count(Outcome::Failed) as f64 >= BLOCKED_SHARE * WINDOW as f64 {
+            Some("failed requests")
+        } else if count(Outcome::Blocked) as f64 >= BLOCKED_SHARE * WINDOW as f64
+            && self.baseline.is_some_and(|baseline| baseline < 0.5)
+        {
+            // a target that forbade most requests from the start isn't blocking the scan
+            Some("403/429 responses")
+        } else {
+            None
+        };
+
+        if reason.is_some() {
+            self.window.clear();
+        }
+
+        reason
+    }
+}
+
+/// Record the outcome of a request, reacting according to `--ban-policy` when the scan looks
+/// blocked
+pub fn record(outcome: Outcome) {
+    if CONFIGURATION.ban_policy == "off" {
+        return;
+    }
+
+    let reason = match DETECTOR.lock() {
+        Ok(mut detector) => detector.record(outcome),
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            None
+        }
+    };
+
+    if let Some(reason) = reason {
+        if CONFIGURATION.ban_policy == "alert" && ALERTED.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        react(reason);
+    }
+}
+
+/// Alert the user that the scan looks blocked and react according to `--ban-policy`
+fn react(reason: &str) {
+    log::trace!("enter: react({})", reason);
+
+    let action = match CONFIGURATION.ban_policy.as_str() {
+        "pause" => {
+            if let Ok(mut paused_until) = PAUSED_UNTIL.lock() {
+                *paused_until = Some(Instant::now() + PAUSE);
+            }
+            format!("pausing for {} seconds", PAUSE.as_secs())
+        }
+        "slow" => {
+            let delay = match DELAY_MS.load(Ordering::Relaxed) {
+                0 => FIRST_DELAY_MS,
+                delay => (delay * 2).min(MAX_DELAY_MS),
+            };
+            DELAY_MS.store(delay, Ordering::Relaxed);
+            format!("slowing down to one request per {}ms per thread", delay)
+        }
+        "stop" => String::from("stopping the scan"),
+        _ => String::from("results from here on may be missing"),
+    };
+
+    let msg = format!(
+        "{} {:>10} Requests look blocked ({}); {}\n",
+        style("BAN").red().bold(),
+        "-",
+        reason,
+        style(&action).yellow()
+    );
+
+    log::warn!("Requests look blocked ({}); {}", reason, action);
+
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(&msg, &PROGRESS_PRINTER);
+    }
+
+    if CONFIGURATION.ban_policy == "stop" {
+        process::exit(exit_codes::BANNED);
+    }
+
+    log::trace!("exit: react");
+}
+
+/// Wait out any pause or slowdown caused by `--ban-policy`
+pub async fn wait() {
+    let paused_until = match PAUSED_UNTIL.lock() {
+        Ok(paused_until) => *paused_until,
+        Err(_) => None,
+    };
+
+    if let Some(paused_until) = paused_until {
+        delay_until(paused_until).await;
+    }
+
+    let delay = DELAY_MS.load(Ordering::Relaxed);
+
+    if delay > 0 {
+        tokio::time::delay_for(Duration::from_millis(delay)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to record the same outcome `times` times, returning the last reason given
+    fn record_many(
+        detector: &mut Detector,
+        outcome: Outcome,
+        times: usize,
+    ) -> Option<&'static str> {
+        (0..times).fold(None, |_, _| detector.record(outcome))
+    }
+
+    #[test]
+    /// a sudden wall of 403s is blocking, a target that forbids everything from the start isn't
+    fn ban_detector_compares_blocked_responses_to_baseline() {
+        let mut detector = Detector::default();
+        assert_eq!(record_many(&mut detector, Outcome::Normal, WINDOW), None);
+        assert_eq!(
+            record_many(&mut detector, Outcome::Blocked, WINDOW - 5),
+            Some("403/429 responses")
+        );
+
+        // the window starts over after each detection
+        assert_eq!(record_many(&mut detector, Outcome::Blocked, 10), None);
+
+        let mut detector = Detector::default();
+        assert_eq!(
+            record_many(&mut detector, Outcome::Blocked, WINDOW * 3),
+            None
+        );
+    }
+
+    #[test]
+    /// failed requests and CAPTCHA pages don't need a baseline
+    fn ban_detector_catches_failures_and_captchas() {
+        let mut detector = Detector::default();
+        assert_eq!(
+            record_many(&mut detector, Outcome::Failed, WINDOW),
+            Some("failed requests")
+        );
+
+        let mut detector = Detector::default();
+        assert_eq!(
+            record_many(&mut detector, Outcome::Captcha, CAPTCHA_PAGES),
+            Some("CAPTCHA pages")
+        );
+
+        let mut detector = Detector::default();
+        for _ in 0..WINDOW {
+            assert_eq!(detector.record(Outcome::Normal), None);
+            assert_eq!(detector.record(Outcome::Failed), None);
+        }
+    }
+
+    #[test]
+    /// only 403 and 429 are typical of blocking
+    fn ban_is_block_status_checks_status() {
+        assert!(is_block_status(StatusCode::FORBIDDEN));
+        assert!(is_block_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_block_status(StatusCode::OK));
+        assert!(!is_block_status(StatusCode::NOT_FOUND));
+    }
+}
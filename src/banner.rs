@@ -1,7 +1,11 @@
+use crate::canary;
 use crate::config::{Configuration, CONFIGURATION};
+use crate::proxy;
 use crate::utils::{make_request, status_colorizer};
+use crate::wordlist::{self, Origin, Wordlist};
 use reqwest::{Client, Url};
 use serde_json::Value;
+use std::borrow::Cow;
 use std::io::Write;
 
 /// macro helper to abstract away repetitive string formatting
@@ -45,7 +49,8 @@ macro_rules! format_banner_entry {
 }
 
 /// Url used to query github's api; specifically used to look for the latest tagged release name
-const UPDATE_URL: &str = "https://api.github.com/repos/epi052/feroxbuster/releases/latest";
+pub(crate) const UPDATE_URL: &str =
+    "https://api.github.com/repos/epi052/feroxbuster/releases/latest";
 
 /// Simple enum to hold three different update states
 #[derive(Debug)]
@@ -80,7 +85,7 @@ async fn needs_update(client: &Client, url: &str, bin_version: &str) -> UpdateSt
         }
     };
 
-    if let Ok(response) = make_request(&client, &api_url).await {
+    if let Ok(response) = make_request(client, &api_url).await {
         let body = response.text().await.unwrap_or_default();
 
         let json_response: Value = serde_json::from_str(&body).unwrap_or_default();
@@ -141,141 +146,876 @@ by Ben "epi" Risher {}                  ver: {}"#,
         '\u{1F913}', version
     );
 
-    let status = needs_update(&CONFIGURATION.client, UPDATE_URL, version).await;
+    let status = if config.no_update_check {
+        // boxes without access to GitHub would otherwise wait on the request
+        UpdateStatus::Unknown
+    } else {
+        needs_update(&CONFIGURATION.client, UPDATE_URL, version).await
+    };
 
     let top = "───────────────────────────┬──────────────────────";
     let bottom = "───────────────────────────┴──────────────────────";
 
-    writeln!(&mut writer, "{}", artwork).unwrap_or_default();
-    writeln!(&mut writer, "{}", top).unwrap_or_default();
+    writeln!(&mut writer, "{}", artwork).unwrap_or_default();
+    writeln!(&mut writer, "{}", top).unwrap_or_default();
+
+    // begin with always printed items
+    for target in targets {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1F3af}", "Target Url", target)
+        )
+        .unwrap_or_default(); // 🎯
+    }
+
+    if !config.targets.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4cb}", "Targets File", config.targets)
+        )
+        .unwrap_or_default(); // 📋
+    }
+
+    if !config.import.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4e5}", "Imported From", config.import)
+        )
+        .unwrap_or_default(); // 📥
+    }
+
+    if config.respect_robots {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f916}", "Respect Robots", config.respect_robots)
+        )
+        .unwrap_or_default(); // 🤖
+    }
+
+    let mut codes = vec![];
+
+    for code in &config.statuscodes {
+        codes.push(status_colorizer(&code.to_string()))
+    }
+
+    writeln!(
+        &mut writer,
+        "{}",
+        format_banner_entry!("\u{1F680}", "Threads", config.threads)
+    )
+    .unwrap_or_default(); // 🚀
+
+    if config.rate_limit > 0 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f6a6}",
+                "Rate Limit",
+                format!("{} requests/second per target", config.rate_limit)
+            )
+        )
+        .unwrap_or_default(); // 🚦
+    }
+
+    if !config.rate_profiles.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f4c8}",
+                "Rate Profiles",
+                format!("[{}]", config.rate_profiles.join(", "))
+            )
+        )
+        .unwrap_or_default(); // 📈
+    }
+
+    if !config.statsd.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f4ca}",
+                "StatsD",
+                format!("{} ({}.*)", config.statsd, config.statsd_prefix)
+            )
+        )
+        .unwrap_or_default(); // 📊
+    }
+
+    if config.body_budget > 0 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f9fa}",
+                "Body Budget",
+                format!("{} MiB", config.body_budget)
+            )
+        )
+        .unwrap_or_default(); // 🧺
+    }
+
+    for wordlist in &config.wordlist {
+        let wordlist = match wordlist::embedded() {
+            Some(contents) if wordlist::is_embedded(wordlist) => {
+                let origin = Origin::Memory(Cow::Borrowed(contents.as_bytes()));
+                let words = Wordlist::read(origin).map_or(0, |words| words.len());
+                format!("{} (built-in, {} words)", wordlist, words)
+            }
+            _ => wordlist.to_string(),
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4d6}", "Wordlist", wordlist)
+        )
+        .unwrap_or_default(); // 📖
+    }
+
+    if !config.wordlist_cache.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4e6}", "Wordlist Cache", config.wordlist_cache)
+        )
+        .unwrap_or_default(); // 📦
+    }
+
+    if !config.wordlist_sha256.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f50f}", "Wordlist SHA-256", config.wordlist_sha256)
+        )
+        .unwrap_or_default(); // 🔏
+    }
+
+    if !config.word_prefixes.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f519}",
+                "Word Prefixes",
+                format!("[{}]", config.word_prefixes.join(", "))
+            )
+        )
+        .unwrap_or_default(); // 🔙
+    }
+
+    if !config.word_suffixes.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f51c}",
+                "Word Suffixes",
+                format!("[{}]", config.word_suffixes.join(", "))
+            )
+        )
+        .unwrap_or_default(); // 🔜
+    }
+
+    if !config.word_cases.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f520}",
+                "Word Cases",
+                format!("[{}]", config.word_cases.join(", "))
+            )
+        )
+        .unwrap_or_default(); // 🔠
+    }
+
+    writeln!(
+        &mut writer,
+        "{}",
+        format_banner_entry!(
+            "\u{1F197}",
+            "Status Codes",
+            format!("[{}]", codes.join(", "))
+        )
+    )
+    .unwrap_or_default(); // 🆗
+
+    writeln!(
+        &mut writer,
+        "{}",
+        format_banner_entry!("\u{1f4a5}", "Timeout (secs)", config.timeout)
+    )
+    .unwrap_or_default(); // 💥
+
+    writeln!(
+        &mut writer,
+        "{}",
+        format_banner_entry!("\u{1F9a1}", "User-Agent", config.useragent)
+    )
+    .unwrap_or_default(); // 🦡
+
+    // followed by the maybe printed or variably displayed values
+    if !config.config.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f489}", "Config File", config.config)
+        )
+        .unwrap_or_default(); // 💉
+    }
+
+    if !config.profile.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f3ad}", "Profile", config.profile)
+        )
+        .unwrap_or_default(); // 🎭
+    }
+
+    if !config.proxy.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f48e}", "Proxy", config.proxy)
+        )
+        .unwrap_or_default(); // 💎
+    }
+
+    if !config.proxy.is_empty() && !config.proxy_auth.is_empty() {
+        // only the user is shown, the password stays off the screen
+        let user = proxy::Credentials::parse(&config.proxy_auth)
+            .map(|credentials| credentials.login())
+            .unwrap_or_default();
+
+        let scheme = if config.proxy_ntlm {
+            "NTLM"
+        } else if config.proxy.starts_with("socks") {
+            "SOCKS5"
+        } else {
+            "Basic"
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f464}", "Proxy User", format!("{} ({})", user, scheme))
+        )
+        .unwrap_or_default(); // 👤
+    }
+
+    if !config.proxy_file.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f504}",
+                "Proxy File",
+                format!("{} ({})", config.proxy_file, config.proxy_rotation)
+            )
+        )
+        .unwrap_or_default(); // 🔄
+    }
+
+    if !config.replay_proxy.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f3a5}", "Replay Proxy", config.replay_proxy)
+        )
+        .unwrap_or_default(); // 🎥
+    }
+
+    if !config.header_order.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f500}", "Header Order", config.header_order)
+        )
+        .unwrap_or_default(); // 🔀
+    }
+
+    if !config.headers.is_empty() {
+        for (name, value) in &config.headers {
+            writeln!(
+                &mut writer,
+                "{}",
+                format_banner_entry!("\u{1f92f}", "Header", name, value)
+            )
+            .unwrap_or_default(); // 🤯
+        }
+    }
+
+    if !config.login_url.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f511}", "Login Url", config.login_url)
+        )
+        .unwrap_or_default(); // 🔑
+    }
+
+    if !config.oauth_token_url.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f3ab}", "OAuth Token Url", config.oauth_token_url)
+        )
+        .unwrap_or_default(); // 🎫
+    }
+
+    if !config.auth.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f6c2}", "Auth", config.auth)
+        )
+        .unwrap_or_default(); // 🛂
+    }
+
+    if !config.aws_sigv4.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f510}", "AWS SigV4", config.aws_sigv4)
+        )
+        .unwrap_or_default(); // 🔐
+    }
+
+    if !config.sizefilters.is_empty() {
+        for filter in &config.sizefilters {
+            writeln!(
+                &mut writer,
+                "{}",
+                format_banner_entry!("\u{1f4a2}", "Size Filter", filter)
+            )
+            .unwrap_or_default(); // 💢
+        }
+    }
+
+    for hash in &config.filter_hashes {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f648}", "Hash Filter", hash)
+        )
+        .unwrap_or_default(); // 🙈
+    }
+
+    if config.extract_links {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1F50E}", "Extract Links", config.extract_links)
+        )
+        .unwrap_or_default(); // 🔎
+    }
+
+    if !config.queries.is_empty() {
+        for query in &config.queries {
+            writeln!(
+                &mut writer,
+                "{}",
+                format_banner_entry!(
+                    "\u{1f914}",
+                    "Query Parameter",
+                    format!("{}={}", query.0, query.1)
+                )
+            )
+            .unwrap_or_default(); // 🤔
+        }
+    }
+
+    if !config.slack_webhook.is_empty() || !config.discord_webhook.is_empty() {
+        let mut services = vec![];
+
+        if !config.slack_webhook.is_empty() {
+            services.push("slack");
+        }
+
+        if !config.discord_webhook.is_empty() {
+            services.push("discord");
+        }
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f514}",
+                "Notifications",
+                format!(
+                    "[{}] every {} findings",
+                    services.join(", "),
+                    config.notify_batch
+                )
+            )
+        )
+        .unwrap_or_default(); // 🔔
+    }
+
+    if !config.syslog.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4e1}", "Syslog", config.syslog)
+        )
+        .unwrap_or_default(); // 📡
+    }
+
+    if !config.output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4be}", "Output File", config.output)
+        )
+        .unwrap_or_default(); // 💾
+    }
+
+    if config.format != "text" {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f9fe}", "Output Format", config.format)
+        )
+        .unwrap_or_default(); // 🧾
+    }
+
+    if !config.output_template.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f9e9}", "Output Template", config.output_template)
+        )
+        .unwrap_or_default(); // 🧩
+    }
+
+    if !config.output_max_size.is_empty() {
+        let output_max_size = format!(
+            "{} ({} rollover files)",
+            config.output_max_size, config.output_max_files
+        );
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f5c4}", "Output Max Size", output_max_size)
+        )
+        .unwrap_or_default(); // 🗄
+    }
+
+    if !config.encrypt_output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f512}", "Encrypt Output", config.encrypt_output)
+        )
+        .unwrap_or_default(); // 🔒
+    }
+
+    if !config.email_report.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4e7}", "Email Report", config.email_report.join(", "))
+        )
+        .unwrap_or_default(); // 📧
+    }
+
+    if config.append_runs {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4da}", "Append Runs", config.append_runs)
+        )
+        .unwrap_or_default(); // 📚
+    }
+
+    if config.output_errors {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4dd}", "Output Errors", config.output_errors)
+        )
+        .unwrap_or_default(); // 📝
+    }
+
+    if config.json {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1F9D4}", "JSON Output", config.json)
+        )
+        .unwrap_or_default(); // 🧔
+    }
+
+    if !config.body_hash.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f9ee}", "Body Hash", config.body_hash)
+        )
+        .unwrap_or_default(); // 🧮
+    }
+
+    if !config.compare.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1F4CA}", "Compare To", config.compare)
+        )
+        .unwrap_or_default(); // 📊
+    }
+
+    if !config.response_cache.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f5c3}", "Response Cache", config.response_cache)
+        )
+        .unwrap_or_default(); // 🗃
+    }
+
+    if !config.save_responses.is_empty() {
+        let saved = if config.compress_responses {
+            format!("{} (compressed)", config.save_responses)
+        } else {
+            config.save_responses.clone()
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4c2}", "Save Responses", saved)
+        )
+        .unwrap_or_default(); // 📂
+    }
+
+    if !config.screenshot.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4f8}", "Screenshots", config.screenshot)
+        )
+        .unwrap_or_default(); // 📸
+    }
+
+    if config.tree {
+        let tree = if config.tree_output.is_empty() {
+            String::from("true")
+        } else {
+            config.tree_output.clone()
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f333}", "Result Tree", tree)
+        )
+        .unwrap_or_default(); // 🌳
+    }
+
+    if !config.xml_output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f4f0}",
+                "XML Output",
+                format!("{} ({})", config.xml_output, config.xml_style)
+            )
+        )
+        .unwrap_or_default(); // 📰
+    }
+
+    if !config.junit_output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f9ea}",
+                "JUnit Output",
+                format!(
+                    "{} ({} polic{})",
+                    config.junit_output,
+                    config.policies.len(),
+                    if config.policies.len() == 1 {
+                        "y"
+                    } else {
+                        "ies"
+                    }
+                )
+            )
+        )
+        .unwrap_or_default(); // 🧪
+    }
+
+    if !config.export_output.is_empty() {
+        let status = if config.export_status.is_empty() {
+            String::new()
+        } else {
+            let codes: Vec<String> = config.export_status.iter().map(u16::to_string).collect();
+            format!(", status [{}]", codes.join(", "))
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f4e4}",
+                "Export Output",
+                format!("{} ({}{})", config.export_output, config.export, status)
+            )
+        )
+        .unwrap_or_default(); // 📤
+    }
+
+    if !config.curl_output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f501}", "Curl Output", config.curl_output)
+        )
+        .unwrap_or_default(); // 🔁
+    }
+
+    if !config.http_output.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f501}", "HTTP Output", config.http_output)
+        )
+        .unwrap_or_default(); // 🔁
+    }
 
-    // begin with always printed items
-    for target in targets {
+    if !config.highlight.is_empty() {
         writeln!(
             &mut writer,
             "{}",
-            format_banner_entry!("\u{1F3af}", "Target Url", target)
+            format_banner_entry!("\u{1f6a8}", "Highlight Rules", config.highlight.len())
         )
-        .unwrap_or_default(); // 🎯
+        .unwrap_or_default(); // 🚨
     }
 
-    let mut codes = vec![];
+    if !config.tags.is_empty() {
+        let tags: Vec<String> = config
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
 
-    for code in &config.statuscodes {
-        codes.push(status_colorizer(&code.to_string()))
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f516}", "Tags", format!("[{}]", tags.join(", ")))
+        )
+        .unwrap_or_default(); // 🔖
     }
 
-    writeln!(
-        &mut writer,
-        "{}",
-        format_banner_entry!("\u{1F680}", "Threads", config.threads)
-    )
-    .unwrap_or_default(); // 🚀
+    if !config.error_log.is_empty() {
+        let error_log = if config.log_filtered {
+            format!("{} (+ filtered)", config.error_log)
+        } else {
+            config.error_log.clone()
+        };
 
-    writeln!(
-        &mut writer,
-        "{}",
-        format_banner_entry!("\u{1f4d6}", "Wordlist", config.wordlist)
-    )
-    .unwrap_or_default(); // 📖
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4d3}", "Error Log", error_log)
+        )
+        .unwrap_or_default(); // 📓
+    }
 
-    writeln!(
-        &mut writer,
-        "{}",
-        format_banner_entry!(
-            "\u{1F197}",
-            "Status Codes",
-            format!("[{}]", codes.join(", "))
+    if !config.resume_from.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f502}", "Resume From", config.resume_from)
         )
-    )
-    .unwrap_or_default(); // 🆗
+        .unwrap_or_default(); // 🔂
+    }
 
-    writeln!(
-        &mut writer,
-        "{}",
-        format_banner_entry!("\u{1f4a5}", "Timeout (secs)", config.timeout)
-    )
-    .unwrap_or_default(); // 💥
+    if !config.state_file.is_empty() {
+        let state_file = if config.checkpoint_interval > 0 {
+            format!(
+                "{} (every {}s)",
+                config.state_file, config.checkpoint_interval
+            )
+        } else {
+            config.state_file.clone()
+        };
 
-    writeln!(
-        &mut writer,
-        "{}",
-        format_banner_entry!("\u{1F9a1}", "User-Agent", config.useragent)
-    )
-    .unwrap_or_default(); // 🦡
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4bd}", "State File", state_file)
+        )
+        .unwrap_or_default(); // 💽
+    }
+
+    if !config.stats_output.is_empty() {
+        let stats_output = if config.stats_interval > 0 {
+            format!("{} (every {}s)", config.stats_output, config.stats_interval)
+        } else {
+            config.stats_output.clone()
+        };
 
-    // followed by the maybe printed or variably displayed values
-    if !config.config.is_empty() {
         writeln!(
             &mut writer,
             "{}",
-            format_banner_entry!("\u{1f489}", "Config File", config.config)
+            format_banner_entry!("\u{1f4c8}", "Statistics Output", stats_output)
         )
-        .unwrap_or_default(); // 💉
+        .unwrap_or_default(); // 📈
     }
 
-    if !config.proxy.is_empty() {
+    if config.summary {
         writeln!(
             &mut writer,
             "{}",
-            format_banner_entry!("\u{1f48e}", "Proxy", config.proxy)
+            format_banner_entry!("\u{1f3c1}", "Summary", config.summary)
         )
-        .unwrap_or_default(); // 💎
+        .unwrap_or_default(); // 🏁
     }
 
-    if !config.headers.is_empty() {
-        for (name, value) in &config.headers {
-            writeln!(
-                &mut writer,
-                "{}",
-                format_banner_entry!("\u{1f92f}", "Header", name, value)
+    if !config.exit_on.is_empty() {
+        let conditions: Vec<String> = config
+            .exit_on
+            .iter()
+            .map(|condition| {
+                if condition == "errors" {
+                    format!("errors > {}", config.error_threshold)
+                } else {
+                    condition.to_string()
+                }
+            })
+            .collect();
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f6a6}",
+                "Exit On",
+                format!("[{}]", conditions.join(", "))
             )
-            .unwrap_or_default(); // 🤯
-        }
+        )
+        .unwrap_or_default(); // 🚦
     }
 
-    if !config.sizefilters.is_empty() {
-        for filter in &config.sizefilters {
-            writeln!(
-                &mut writer,
-                "{}",
-                format_banner_entry!("\u{1f4a2}", "Size Filter", filter)
+    if config.ban_policy != "alert" {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f6d1}", "Ban Policy", config.ban_policy)
+        )
+        .unwrap_or_default(); // 🛑
+    }
+
+    if config.method != "GET" {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1fa83}", "HTTP Method", config.method)
+        )
+        .unwrap_or_default(); // 🪃
+    }
+
+    if config.canary_interval > 0 || !config.canary.is_empty() {
+        let canary = if config.canary.is_empty() {
+            "first 2xx finding"
+        } else {
+            config.canary.as_str()
+        };
+
+        let interval = if config.canary_interval > 0 {
+            config.canary_interval
+        } else {
+            canary::DEFAULT_INTERVAL
+        };
+
+        let pause = if config.canary_pause {
+            ", pausing when tripped"
+        } else {
+            ""
+        };
+
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f424}",
+                "Canary",
+                format!("{} every {}s{}", canary, interval, pause)
             )
-            .unwrap_or_default(); // 💢
-        }
+        )
+        .unwrap_or_default(); // 🐤
     }
 
-    if config.extract_links {
+    if !config.start_at.is_empty() {
         writeln!(
             &mut writer,
             "{}",
-            format_banner_entry!("\u{1F50E}", "Extract Links", config.extract_links)
+            format_banner_entry!("\u{23f0}", "Start At", config.start_at)
         )
-        .unwrap_or_default(); // 🔎
+        .unwrap_or_default(); // ⏰
     }
 
-    if !config.queries.is_empty() {
-        for query in &config.queries {
-            writeln!(
-                &mut writer,
-                "{}",
-                format_banner_entry!(
-                    "\u{1f914}",
-                    "Query Parameter",
-                    format!("{}={}", query.0, query.1)
-                )
+    if !config.pause_outside.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f319}",
+                "Allowed Hours",
+                format!("[{}]", config.pause_outside.join(", "))
             )
-            .unwrap_or_default(); // 🤔
-        }
+        )
+        .unwrap_or_default(); // 🌙
     }
 
-    if !config.output.is_empty() {
+    if config.role == "coordinator" {
         writeln!(
             &mut writer,
             "{}",
-            format_banner_entry!("\u{1f4be}", "Output File", config.output)
+            format_banner_entry!(
+                "\u{1f477}",
+                "Workers",
+                format!("[{}]", config.workers.join(", "))
+            )
         )
-        .unwrap_or_default(); // 💾
+        .unwrap_or_default(); // 👷
+    }
+
+    if config.verify {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{2705}", "Verify Findings", config.verify)
+        )
+        .unwrap_or_default(); // ✅
+    }
+
+    if config.smart {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f9e0}", "Smart Fingerprinting", config.smart)
+        )
+        .unwrap_or_default(); // 🧠
+    } else if config.fingerprint {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f50d}", "Fingerprint", config.fingerprint)
+        )
+        .unwrap_or_default(); // 🔍
     }
 
     if !config.extensions.is_empty() {
@@ -291,6 +1031,19 @@ by Ben "epi" Risher {}                  ver: {}"#,
         .unwrap_or_default(); // 💲
     }
 
+    if !config.show_headers.is_empty() {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f4cb}",
+                "Show Headers",
+                format!("[{}]", config.show_headers.join(", "))
+            )
+        )
+        .unwrap_or_default(); // 📋
+    }
+
     if config.insecure {
         writeln!(
             &mut writer,
@@ -309,6 +1062,41 @@ by Ben "epi" Risher {}                  ver: {}"#,
         .unwrap_or_default(); // 📍
     }
 
+    if config.filter_redirects > 0 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f9ed}",
+                "Filter Redirects",
+                format!("more than {}", config.filter_redirects)
+            )
+        )
+        .unwrap_or_default(); // 🧭
+    }
+
+    if config.filter_redirect_loops {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f300}",
+                "Filter Redirect Loops",
+                config.filter_redirect_loops
+            )
+        )
+        .unwrap_or_default(); // 🌀
+    }
+
+    if config.detect_dynamic {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f3b2}", "Detect Dynamic", config.detect_dynamic)
+        )
+        .unwrap_or_default(); // 🎲
+    }
+
     if config.dontfilter {
         writeln!(
             &mut writer,
@@ -318,6 +1106,73 @@ by Ben "epi" Risher {}                  ver: {}"#,
         .unwrap_or_default(); // 🤪
     }
 
+    if config.soft_404 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f47b}", "Soft 404 Filter", config.soft_404)
+        )
+        .unwrap_or_default(); // 👻
+    }
+
+    if config.collapse_duplicates > 0 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!(
+                "\u{1f46f}",
+                "Collapse Duplicates",
+                format!("after {}", config.collapse_duplicates)
+            )
+        )
+        .unwrap_or_default(); // 👯
+    }
+
+    if config.use_listings {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f5c2}", "Use Listings", config.use_listings)
+        )
+        .unwrap_or_default(); // 🗂
+    }
+
+    if config.analyze_403 {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f50f}", "Analyze 403s", config.analyze_403)
+        )
+        .unwrap_or_default(); // 🔏
+    }
+
+    if config.security_headers {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f9f1}", "Security Headers", config.security_headers)
+        )
+        .unwrap_or_default(); // 🧱
+    }
+
+    if config.check_length {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4cf}", "Check Length", config.check_length)
+        )
+        .unwrap_or_default(); // 📏
+    }
+
+    if config.slash_probe {
+        writeln!(
+            &mut writer,
+            "{}",
+            format_banner_entry!("\u{1f4d0}", "Slash Probe", config.slash_probe)
+        )
+        .unwrap_or_default(); // 📐
+    }
+
     match config.verbosity {
         //speaker medium volume (increasing with verbosity to loudspeaker)
         1 => {
@@ -406,6 +1261,7 @@ by Ben "epi" Risher {}                  ver: {}"#,
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default, clippy::needless_borrow)]
 mod tests {
     use super::*;
     use crate::VERSION;
@@ -483,6 +1339,25 @@ mod tests {
         assert!(contents.contains("https://github.com/epi052/feroxbuster/releases/latest"));
     }
 
+    #[tokio::test(core_threads = 1)]
+    /// test to show that no new version is announced when the check is turned off
+    async fn banner_intialize_without_update_check() {
+        let config = Configuration {
+            no_update_check: true,
+            ..Default::default()
+        };
+        let file = NamedTempFile::new().unwrap();
+        initialize(
+            &[String::from("http://localhost")],
+            &config,
+            "mismatched-version",
+            &file,
+        )
+        .await;
+        let contents = read_to_string(file.path()).unwrap();
+        assert!(!contents.contains("New Version Available"));
+    }
+
     #[tokio::test(core_threads = 1)]
     /// test that
     async fn banner_needs_update_returns_unknown_with_bad_url() {
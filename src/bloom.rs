@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// number of items the first layer of a filter is sized for; each layer added after it holds
+/// twice as many as the one before
+const FIRST_CAPACITY: usize = 65_536;
+
+/// false positive rate of the first layer; halved for each layer added after it, which keeps the
+/// filter's overall rate under twice this however many layers are added
+const FIRST_FP_RATE: f64 = 0.0001;
+
+/// A fixed-size bloom filter, full once it holds `capacity` items
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Layer {
+    /// the filter's bits
+    bits: Vec<u64>,
+
+    /// number of bits set per item
+    hashes: u64,
+
+    /// number of items the filter was sized for
+    capacity: usize,
+
+    /// number of items inserted so far
+    count: usize,
+}
+
+impl Layer {
+    /// Filter sized for `capacity` items at the given false positive rate
+    fn new(capacity: usize, fp_rate: f64) -> Self {
+        // optimal size and number of hashes, see https://en.wikipedia.org/wiki/Bloom_filter
+        let ln2 = std::f64::consts::LN_2;
+        let size = (-(capacity as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as usize;
+        let hashes = ((size as f64 / capacity as f64) * ln2).round().max(1.0) as u64;
+
+        Self {
+            bits: vec![0; size / 64 + 1],
+            hashes,
+            capacity,
+            count: 0,
+        }
+    }
+
+    /// Indexes of the bits of the item with the given hashes
+    fn indexes(&self, (first, second): (u64, u64)) -> impl Iterator<Item = usize> {
+        let size = self.bits.len() as u64 * 64;
+
+        // double hashing, every index is derived from the same two hashes
+        (0..self.hashes).map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % size) as usize)
+    }
+
+    /// Whether or not the item with the given hashes was (probably) inserted
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.indexes(hashes)
+            .all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Insert the item with the given hashes
+    fn insert(&mut self, hashes: (u64, u64)) {
+        let indexes: Vec<usize> = self.indexes(hashes).collect();
+
+        for index in indexes {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+
+        self.count += 1;
+    }
+}
+
+/// Set of strings that uses a few bytes per item instead of the items themselves, at the cost
+/// of a small chance of reporting an item that was never inserted as inserted (false positive);
+/// items that were inserted are always reported as such
+///
+/// The filter grows by adding layers as it fills up, so it doesn't need to be sized up front; it
+/// can be saved and read back with serde (see `--state-file`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BloomFilter {
+    /// filters holding the inserted items, the last one is being filled
+    layers: Vec<Layer>,
+}
+
+impl Default for BloomFilter {
+    fn default() -> Self {
+        Self {
+            layers: vec![Layer::new(FIRST_CAPACITY, FIRST_FP_RATE)],
+        }
+    }
+}
+
+impl BloomFilter {
+    /// Two independent hashes of the given item
+    fn hashes(item: &str) -> (u64, u64) {
+        let mut first = DefaultHasher::new();
+        item.hash(&mut first);
+
+        let mut second = DefaultHasher::new();
+        (item, 0x9e37_79b9_7f4a_7c15_u64).hash(&mut second);
+
+        // an even step would only ever reach half the bits
+        (first.finish(), second.finish() | 1)
+    }
+
+    /// Whether or not the given item was (probably) inserted
+    pub fn contains(&self, item: &str) -> bool {
+        let hashes = Self::hashes(item);
+        self.layers.iter().any(|layer| layer.contains(hashes))
+    }
+
+    /// Insert the given item; returns false when it was (probably) inserted before, true otherwise
+    pub fn insert(&mut self, item: &str) -> bool {
+        let hashes = Self::hashes(item);
+
+        if self.layers.iter().any(|layer| layer.contains(hashes)) {
+            return false;
+        }
+
+        let full = self
+            .layers
+            .last()
+            .is_none_or(|layer| layer.count >= layer.capacity);
+
+        if full {
+            let depth = self.layers.len() as i32;
+            self.layers.push(Layer::new(
+                FIRST_CAPACITY << depth,
+                FIRST_FP_RATE * 0.5_f64.powi(depth),
+            ));
+        }
+
+        if let Some(layer) = self.layers.last_mut() {
+            layer.insert(hashes);
+        }
+
+        true
+    }
+
+    /// Number of bytes used by the filter
+    pub fn size(&self) -> usize {
+        self.layers.iter().map(|layer| layer.bits.len() * 8).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// inserted items are always found, and only inserted once
+    fn bloom_filter_finds_inserted_items() {
+        let mut filter = BloomFilter::default();
+
+        assert!(filter.insert("http://localhost/admin"));
+        assert!(!filter.insert("http://localhost/admin"));
+        assert!(filter.contains("http://localhost/admin"));
+        assert!(!filter.contains("http://localhost/login"));
+    }
+
+    #[test]
+    /// the filter grows past its first layer, with few false positives and far less memory than
+    /// the items themselves
+    fn bloom_filter_grows_with_few_false_positives() {
+        let count = FIRST_CAPACITY * 3;
+        let mut filter = BloomFilter::default();
+
+        for i in 0..count {
+            filter.insert(&format!("http://localhost/{}", i));
+        }
+
+        assert!(filter.layers.len() > 1);
+        assert!((0..count).all(|i| filter.contains(&format!("http://localhost/{}", i))));
+
+        let false_positives = (count..count * 2)
+            .filter(|i| filter.contains(&format!("http://localhost/{}", i)))
+            .count();
+
+        assert!(false_positives < count / 1000);
+        assert!(filter.size() < count * 4);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::buffers;
+use crate::config::CONFIGURATION;
+use lazy_static::lazy_static;
+use reqwest::Response;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+/// bytes in a mebibyte, the unit of `--body-budget`
+const MIB: u64 = 1024 * 1024;
+
+/// bytes reserved up front for a body of unknown length
+const UNKNOWN_LENGTH: usize = 64 * 1024;
+
+/// how long a request waits before checking again for room in the budget
+const WAIT: Duration = Duration::from_millis(10);
+
+lazy_static! {
+    /// Budget shared by every request whose body is read (see `--body-budget`)
+    static ref BUDGET: Budget = Budget::new((CONFIGURATION.body_budget * MIB) as usize);
+}
+
+/// Number of bytes that may be held by response bodies at once
+#[derive(Debug)]
+pub struct Budget {
+    /// bytes that may be held at once
+    capacity: usize,
+
+    /// bytes currently held
+    used: Mutex<usize>,
+}
+
+/// Body of a response, as much of it as was received
+#[derive(Debug)]
+pub struct Body {
+    /// bytes received, in a buffer taken from the pool (see `buffers`)
+    pub bytes: Vec<u8>,
+
+    /// whether or not the body was cut short to stay within the budget
+    pub over_budget: bool,
+
+    /// error that cut the body short, i.e. the connection closed before all of it was received
+    pub error: Option<reqwest::Error>,
+}
+
+/// Bytes held in a `Budget`, given back once dropped
+#[derive(Debug)]
+pub struct Reservation<'a> {
+    /// budget the bytes are held in
+    budget: &'a Budget,
+
+    /// bytes held
+    bytes: usize,
+}
+
+impl Budget {
+    /// Budget of `capacity` bytes
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            used: Mutex::new(0),
+        }
+    }
+
+    /// Hold the given number of bytes if there's room for them, returns whether or not there was
+    fn take(&self, bytes: usize) -> bool {
+        match self.used.lock() {
+            Ok(mut used) if *used + bytes <= self.capacity => {
+                *used += bytes;
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::error!("Budget of response bodies poisoned: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Wait until the given number of bytes (at most the whole budget) can be held, then hold
+    /// them until the returned reservation is dropped
+    ///
+    /// Only requests that hold nothing wait, which is what keeps them from waiting on each other
+    /// forever; a request that needs more than it reserved has to `grow` its reservation instead
+    pub async fn reserve(&self, bytes: usize) -> Reservation<'_> {
+        let bytes = bytes.min(self.capacity);
+
+        while !self.take(bytes) {
+            delay_for(WAIT).await;
+        }
+
+        Reservation {
+            budget: self,
+            bytes,
+        }
+    }
+}
+
+impl Reservation<'_> {
+    /// Hold the given number of additional bytes if there's room for them right away, returns
+    /// whether or not there was
+    pub fn grow(&mut self, bytes: usize) -> bool {
+        let grown = self.budget.take(bytes);
+
+        if grown {
+            self.bytes += bytes;
+        }
+
+        grown
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        match self.budget.used.lock() {
+            Ok(mut used) => *used -= self.bytes,
+            Err(e) => log::error!("Budget of response bodies poisoned: {}", e),
+        }
+    }
+}
+
+/// Read the given response's body into a buffer taken from the pool (see `buffers`)
+async fn read_body_pooled(mut response: Response) -> Body {
+    log::trace!("enter: read_body_pooled({:?})", response);
+
+    let mut body = Body {
+        bytes: buffers::take(),
+        over_budget: false,
+        error: None,
+    };
+
+    if let Some(length) = response.content_length() {
+        body.bytes.reserve(length as usize);
+    }
+
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.bytes.extend_from_slice(&chunk),
+            Ok(None) => break,
+            Err(e) => {
+                body.error = Some(e);
+                break;
+            }
+        }
+    }
+
+    log::trace!(
+        "exit: read_body_pooled -> body[{} bytes...]",
+        body.bytes.len()
+    );
+    body
+}
+
+/// Read the given response's body within the given budget, waiting for room in it first; the
+/// body is truncated to what the budget can hold once no more room can be made for it
+pub async fn read_body_within(budget: &Budget, mut response: Response) -> Body {
+    log::trace!("enter: read_body_within({:?}, {:?})", budget, response);
+
+    let expected = response
+        .content_length()
+        .map_or(UNKNOWN_LENGTH, |length| length as usize);
+
+    let mut reservation = budget.reserve(expected).await;
+
+    let mut body = Body {
+        bytes: buffers::take(),
+        over_budget: false,
+        error: None,
+    };
+
+    body.bytes.reserve(reservation.bytes);
+
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                body.error = Some(e);
+                break;
+            }
+        };
+
+        let needed = (body.bytes.len() + chunk.len()).saturating_sub(reservation.bytes);
+
+        if needed > 0 && !reservation.grow(needed) {
+            let room = reservation.bytes - body.bytes.len();
+            body.bytes.extend_from_slice(&chunk[..room]);
+            body.over_budget = true;
+
+            log::warn!(
+                "Body of {} truncated to {} bytes, the body budget is exhausted",
+                response.url(),
+                body.bytes.len()
+            );
+            break;
+        }
+
+        body.bytes.extend_from_slice(&chunk);
+    }
+
+    log::trace!(
+        "exit: read_body_within -> body[{} bytes...]",
+        body.bytes.len()
+    );
+    body
+}
+
+/// Read the given response's body into a pooled buffer, within the budget given by
+/// `--body-budget` if any; reading stops early when the connection fails, keeping whatever was
+/// received until then
+pub async fn read_body(response: Response) -> Body {
+    if CONFIGURATION.body_budget == 0 {
+        return read_body_pooled(response).await;
+    }
+
+    read_body_within(&BUDGET, response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(core_threads = 1)]
+    /// bytes are held until their reservation is dropped, reservations only grow when there's room
+    async fn budget_reservations_hold_bytes_until_dropped() {
+        let budget = Budget::new(100);
+
+        let mut first = budget.reserve(60).await;
+        assert!(!first.grow(50));
+        assert!(first.grow(40));
+        assert!(!budget.take(1));
+
+        drop(first);
+
+        // a reservation is capped at the whole budget
+        let second = budget.reserve(500).await;
+        assert_eq!(second.bytes, 100);
+        drop(second);
+
+        assert_eq!(*budget.used.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,137 @@
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+
+/// most buffers kept in the pool, enough for the bodies held at once by a busy scan
+const MAX_BUFFERS: usize = 512;
+
+/// largest capacity a buffer may have to go back to the pool; the memory of an unusually large
+/// body is given back to the allocator instead of staying around for the rest of the scan
+const MAX_CAPACITY: usize = 1024 * 1024;
+
+lazy_static! {
+    /// Buffers response bodies are read into
+    static ref BUFFERS: BufferPool = BufferPool::new(MAX_BUFFERS, MAX_CAPACITY);
+}
+
+/// Pool of byte buffers, reused from one response body to the next instead of allocating new
+/// ones; once the pool is warm, reading a body only allocates when it outgrows its buffer
+#[derive(Debug)]
+pub struct BufferPool {
+    /// buffers not in use, all empty
+    buffers: Mutex<Vec<Vec<u8>>>,
+
+    /// most buffers kept
+    max_buffers: usize,
+
+    /// largest capacity a kept buffer may have
+    max_capacity: usize,
+}
+
+impl BufferPool {
+    /// Pool keeping at most `max_buffers` buffers of at most `max_capacity` bytes
+    pub fn new(max_buffers: usize, max_capacity: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            max_buffers,
+            max_capacity,
+        }
+    }
+
+    /// Take an empty buffer from the pool, or a new one when the pool has none left
+    pub fn take(&self) -> Vec<u8> {
+        match self.buffers.lock() {
+            Ok(mut buffers) => buffers.pop().unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Give a buffer back to the pool once it's no longer used; it's dropped instead when it
+    /// outgrew the pool or the pool is full
+    pub fn give(&self, mut buffer: Vec<u8>) {
+        if buffer.capacity() == 0 || buffer.capacity() > self.max_capacity {
+            return;
+        }
+
+        buffer.clear();
+
+        if let Ok(mut buffers) = self.buffers.lock() {
+            if buffers.len() < self.max_buffers {
+                buffers.push(buffer);
+            }
+        }
+    }
+}
+
+/// Take an empty buffer to read a response body into
+pub fn take() -> Vec<u8> {
+    BUFFERS.take()
+}
+
+/// Take an empty string to copy a response body into
+pub fn take_string() -> String {
+    // an empty buffer is valid utf-8
+    String::from_utf8(take()).unwrap_or_default()
+}
+
+/// Give a body's buffer back once it's no longer used
+pub fn give(buffer: Vec<u8>) {
+    BUFFERS.give(buffer);
+}
+
+/// Turn a body read into a buffer into text, without copying it when it's valid utf-8; otherwise
+/// invalid sequences are replaced (see `String::from_utf8_lossy`) and the buffer goes back to the
+/// pool
+pub fn into_text(buffer: Vec<u8>) -> String {
+    match String::from_utf8(buffer) {
+        Ok(text) => text,
+        Err(e) => {
+            let buffer = e.into_bytes();
+            let text = String::from_utf8_lossy(&buffer).into_owned();
+            give(buffer);
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// buffers given back are handed out again, empty and with their capacity; oversized buffers
+    /// and buffers beyond the pool's size are dropped
+    fn buffers_are_reused() {
+        let pool = BufferPool::new(1, 64);
+
+        let mut buffer = pool.take();
+        assert_eq!(buffer.capacity(), 0);
+
+        buffer.extend_from_slice(b"hello");
+        let capacity = buffer.capacity();
+        pool.give(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), capacity);
+
+        pool.give(Vec::with_capacity(128));
+        assert_eq!(pool.take().capacity(), 0);
+
+        pool.give(Vec::with_capacity(8));
+        pool.give(Vec::with_capacity(16));
+        assert_eq!(pool.buffers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    /// valid utf-8 keeps its buffer, anything else is replaced
+    fn buffers_into_text_replaces_invalid_utf8() {
+        let buffer = b"caf\xc3\xa9".to_vec();
+        let pointer = buffer.as_ptr();
+
+        let text = into_text(buffer);
+        assert_eq!(text, "café");
+        assert_eq!(text.as_ptr(), pointer);
+
+        assert_eq!(into_text(b"caf\xe9".to_vec()), "caf\u{fffd}");
+    }
+}
@@ -0,0 +1,281 @@
+use crate::config::CONFIGURATION;
+use crate::hooks::{self, RequestHook};
+use crate::FeroxResult;
+use lazy_static::lazy_static;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Request, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    /// Cache given with `--response-cache`, empty without one
+    static ref CACHE: Arc<ResponseCache> =
+        Arc::new(ResponseCache::load(&CONFIGURATION.response_cache));
+}
+
+/// A finding of a previous run, along with the validators the server sent for it
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Entry {
+    /// status code of the finding
+    pub status: u16,
+
+    /// content-length of the finding
+    pub content_length: u64,
+
+    /// `ETag` header of the finding, empty without one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub etag: String,
+
+    /// `Last-Modified` header of the finding, empty without one
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub last_modified: String,
+}
+
+impl Entry {
+    /// Entry for a finding with the given status, size, and headers; `None` when the server
+    /// didn't send any validator, as the finding couldn't be requested conditionally
+    pub fn new(status: u16, content_length: u64, headers: &HeaderMap) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let entry = Self {
+            status,
+            content_length,
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
+        if entry.etag.is_empty() && entry.last_modified.is_empty() {
+            None
+        } else {
+            Some(entry)
+        }
+    }
+}
+
+/// Findings of previous runs by url, read from a json file, plus the ones of the current run;
+/// responses to urls found before are requested conditionally, so that unchanged ones are
+/// answered with a `304` instead of their whole body
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    /// file the cache is read from and saved to
+    path: String,
+
+    /// findings of previous runs
+    prior: HashMap<String, Entry>,
+
+    /// findings of the current run
+    found: Mutex<HashMap<String, Entry>>,
+}
+
+impl ResponseCache {
+    /// Read the cache from the given file; the cache starts empty when the file doesn't exist yet
+    /// or can't be read
+    pub fn load(path: &str) -> Self {
+        let prior = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::error!("Could not parse the response cache {}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                log::error!("Could not read the response cache {}: {}", path, e);
+                HashMap::new()
+            }
+        };
+
+        Self {
+            path: path.to_string(),
+            prior,
+            found: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Finding of a previous run at the given url, if any
+    pub fn prior(&self, url: &Url) -> Option<&Entry> {
+        self.prior.get(url.as_str())
+    }
+
+    /// Remember a finding of the current run; a `304` keeps the validators of the previous run
+    /// that the server didn't send again
+    pub fn record(&self, url: &Url, status: u16, content_length: u64, headers: &HeaderMap) {
+        let mut entry = match Entry::new(status, content_length, headers) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        if let Some(prior) = self.prior(url) {
+            if entry.etag.is_empty() {
+                entry.etag = prior.etag.clone();
+            }
+
+            if entry.last_modified.is_empty() {
+                entry.last_modified = prior.last_modified.clone();
+            }
+        }
+
+        if let Ok(mut found) = self.found.lock() {
+            found.insert(url.to_string(), entry);
+        }
+    }
+
+    /// Write the findings of previous runs, updated with the current run's, back to the file
+    pub fn save(&self) -> FeroxResult<()> {
+        let mut entries = self.prior.clone();
+
+        if let Ok(found) = self.found.lock() {
+            entries.extend(found.clone());
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    }
+}
+
+impl RequestHook for ResponseCache {
+    fn before_request(&self, request: &mut Request) {
+        let prior = match self.prior(request.url()) {
+            Some(prior) => prior,
+            None => return,
+        };
+
+        let validators = [
+            (IF_NONE_MATCH, &prior.etag),
+            (IF_MODIFIED_SINCE, &prior.last_modified),
+        ];
+
+        for (name, value) in validators.iter() {
+            if value.is_empty() || request.headers().contains_key(name) {
+                continue;
+            }
+
+            if let Ok(value) = value.parse() {
+                request.headers_mut().insert(name.clone(), value);
+            }
+        }
+    }
+}
+
+/// Requests made from here on are made conditionally when their url was found by a previous run
+/// (see `--response-cache`)
+pub fn initialize() {
+    if CONFIGURATION.response_cache.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "{} findings read from the response cache {}",
+        CACHE.prior.len(),
+        CONFIGURATION.response_cache
+    );
+
+    hooks::add_request_hook(CACHE.clone());
+}
+
+/// Status code and size of the finding of a previous run the given `304` response stands for;
+/// `None` for any other response
+pub fn revalidate(url: &Url, status: StatusCode) -> Option<(StatusCode, u64)> {
+    if status != StatusCode::NOT_MODIFIED || CONFIGURATION.response_cache.is_empty() {
+        return None;
+    }
+
+    let prior = CACHE.prior(url)?;
+    log::debug!("{} is unchanged since the previous run", url);
+
+    Some((
+        StatusCode::from_u16(prior.status).ok()?,
+        prior.content_length,
+    ))
+}
+
+/// Remember a reported finding, to be saved with `save`
+pub fn record(url: &Url, status: u16, content_length: u64, headers: &HeaderMap) {
+    if !CONFIGURATION.response_cache.is_empty() {
+        CACHE.record(url, status, content_length, headers);
+    }
+}
+
+/// Save the cache given with `--response-cache`, if any
+pub fn save() {
+    if CONFIGURATION.response_cache.is_empty() {
+        return;
+    }
+
+    if let Err(e) = CACHE.save() {
+        log::error!(
+            "Could not save the response cache {}: {}",
+            CONFIGURATION.response_cache,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Client;
+
+    #[test]
+    /// previous findings are requested conditionally, with the validators they were sent with
+    fn cache_adds_validators_of_prior_findings() {
+        let mut cache = ResponseCache::default();
+
+        cache.prior.insert(
+            String::from("http://localhost/admin"),
+            Entry {
+                status: 200,
+                content_length: 42,
+                etag: String::from("\"abc\""),
+                last_modified: String::new(),
+            },
+        );
+
+        let mut request = Client::new().get("http://localhost/admin").build().unwrap();
+        cache.before_request(&mut request);
+        assert_eq!(request.headers()[IF_NONE_MATCH], "\"abc\"");
+        assert!(!request.headers().contains_key(IF_MODIFIED_SINCE));
+
+        let mut request = Client::new().get("http://localhost/login").build().unwrap();
+        cache.before_request(&mut request);
+        assert!(request.headers().is_empty());
+    }
+
+    #[test]
+    /// only findings with validators are remembered; a 304 keeps the validators it didn't resend
+    fn cache_records_findings_with_validators() {
+        let mut cache = ResponseCache::default();
+        let url = Url::parse("http://localhost/admin").unwrap();
+
+        cache.prior.insert(
+            url.to_string(),
+            Entry {
+                status: 200,
+                content_length: 42,
+                etag: String::from("\"abc\""),
+                last_modified: String::from("Wed, 21 Oct 2015 07:28:00 GMT"),
+            },
+        );
+
+        let mut headers = HeaderMap::new();
+        cache.record(&url, 200, 42, &headers);
+        assert!(cache.found.lock().unwrap().is_empty());
+
+        headers.insert(ETAG, "\"def\"".parse().unwrap());
+        cache.record(&url, 200, 42, &headers);
+
+        let found = cache.found.lock().unwrap();
+        assert_eq!(found[url.as_str()].etag, "\"def\"");
+        assert_eq!(
+            found[url.as_str()].last_modified,
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+    }
+}
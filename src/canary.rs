@@ -0,0 +1,269 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::hooks;
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use console::style;
+use lazy_static::lazy_static;
+use reqwest::{Method, StatusCode, Url};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// seconds between canary requests when `--canary` is given without `--canary-interval`
+pub const DEFAULT_INTERVAL: u64 = 30;
+
+/// how often paused requests check whether the canary recovered, in milliseconds
+const PAUSE_CHECK_MS: u64 = 500;
+
+lazy_static! {
+    /// Known-good url that's re-requested on an interval; either given with `--canary` or the
+    /// first 2xx finding
+    static ref CANARY: RwLock<Option<Url>> = RwLock::new(None);
+}
+
+/// Whether or not requests are paused until the canary recovers (see `--canary-pause`)
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Change in the canary's responses
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Change {
+    /// the canary's status class differs from the expected class, given here
+    Tripped(u16),
+
+    /// the canary's status class is back to the expected class
+    Recovered,
+}
+
+/// Keeps track of the canary's status class; the class of the first response is expected from
+/// all that follow
+#[derive(Debug, Default)]
+pub struct Watch {
+    /// status class of the first response, i.e. 2 for a 200
+    expected: Option<u16>,
+
+    /// whether or not the last response was of a different class
+    tripped: bool,
+}
+
+impl Watch {
+    /// Watch a canary whose responses are expected to have the given status code's class
+    pub fn expecting(status: u16) -> Self {
+        Self {
+            expected: Some(status / 100),
+            tripped: false,
+        }
+    }
+
+    /// Record the status code of the canary's latest response (0 when the request failed),
+    /// returning how that changed things, if it did
+    pub fn observe(&mut self, status: u16) -> Option<Change> {
+        let class = status / 100;
+
+        let expected = match self.expected {
+            Some(expected) => expected,
+            None => {
+                self.expected = Some(class);
+                return None;
+            }
+        };
+
+        if class != expected && !self.tripped {
+            self.tripped = true;
+            Some(Change::Tripped(expected))
+        } else if class == expected && self.tripped {
+            self.tripped = false;
+            Some(Change::Recovered)
+        } else {
+            None
+        }
+    }
+}
+
+/// Offer a finding as the canary; the first 2xx finding is used when `--canary` isn't given
+pub fn offer(url: &Url, status: StatusCode) {
+    if !status.is_success() {
+        return;
+    }
+
+    if let Ok(mut canary) = CANARY.write() {
+        if canary.is_none() {
+            log::info!("Using {} as the canary", url);
+            *canary = Some(url.clone());
+        }
+    }
+}
+
+/// Wait for the canary to recover, when requests are paused (see `--canary-pause`)
+pub async fn wait() {
+    while PAUSED.load(Ordering::Relaxed) {
+        tokio::time::delay_for(Duration::from_millis(PAUSE_CHECK_MS)).await;
+    }
+}
+
+/// Print the given canary message; it's meant to be hard to miss
+fn alert(msg: &str) {
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(msg, &PROGRESS_PRINTER);
+    }
+}
+
+/// Request the canary and react to changes in its status class
+async fn check(url: &Url, watch: &mut Watch) {
+    log::trace!("enter: check({}, {:?})", url, watch);
+
+    // canary requests aren't part of the scan, so they skip make_request's statistics
+    let status = match hooks::send(&CONFIGURATION.client, &Method::GET, url).await {
+        Ok(response) => response.status().as_u16(),
+        Err(e) => {
+            log::warn!("Canary request to {} failed: {}", url, e);
+            0
+        }
+    };
+
+    match watch.observe(status) {
+        Some(Change::Tripped(expected)) => {
+            let got = if status == 0 {
+                String::from("no response")
+            } else {
+                status_colorizer(&status.to_string())
+            };
+
+            let action = if CONFIGURATION.canary_pause {
+                PAUSED.store(true, Ordering::Relaxed);
+                "pausing until it recovers"
+            } else {
+                "anything found from here on is suspect"
+            };
+
+            log::warn!(
+                "Canary {} returned {} instead of a {}xx",
+                url,
+                status,
+                expected
+            );
+
+            alert(&format!(
+                "{} {:>10} Canary {} returned {} instead of a {}xx; {}\n",
+                style("CNR").red().bold(),
+                "-",
+                url,
+                got,
+                expected,
+                style(action).yellow()
+            ));
+        }
+        Some(Change::Recovered) => {
+            PAUSED.store(false, Ordering::Relaxed);
+
+            log::warn!("Canary {} recovered", url);
+
+            alert(&format!(
+                "{} {:>10} Canary {} recovered with a {}\n",
+                style("CNR").green().bold(),
+                "-",
+                url,
+                status_colorizer(&status.to_string())
+            ));
+        }
+        None => {}
+    }
+
+    log::trace!("exit: check");
+}
+
+/// Spawn the task that re-requests the canary on an interval (see `--canary-interval`); returns
+/// the transmitter used to stop the task along with the task's JoinHandle to be awaited, or `None`
+/// when no canary is used
+pub fn initialize() -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize");
+
+    let seconds = match (
+        CONFIGURATION.canary_interval,
+        CONFIGURATION.canary.is_empty(),
+    ) {
+        (0, true) => {
+            log::trace!("exit: initialize -> None");
+            return None;
+        }
+        (0, false) => DEFAULT_INTERVAL,
+        (seconds, _) => seconds,
+    };
+
+    if !CONFIGURATION.canary.is_empty() {
+        match Url::parse(&CONFIGURATION.canary) {
+            Ok(url) => offer(&url, StatusCode::OK),
+            Err(e) => {
+                eprintln!(
+                    "{} {} Could not parse canary {}: {}",
+                    status_colorizer("ERROR"),
+                    module_colorizer("canary::initialize"),
+                    CONFIGURATION.canary,
+                    e
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let checker = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(seconds));
+
+        // a user-supplied canary's first response sets the class expected from it, a finding's
+        // status is already known
+        let mut watch = if CONFIGURATION.canary.is_empty() {
+            Watch::expecting(StatusCode::OK.as_u16())
+        } else {
+            Watch::default()
+        };
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let canary = CANARY.read().ok().and_then(|canary| canary.clone());
+
+                    if let Some(url) = canary {
+                        check(&url, &mut watch).await;
+                    }
+                }
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, checker);
+    Some((tx_stop, checker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// a change of class trips the watch once, going back to the expected class recovers it
+    fn canary_watch_trips_on_class_change() {
+        let mut watch = Watch::expecting(200);
+
+        assert_eq!(watch.observe(204), None);
+        assert_eq!(watch.observe(403), Some(Change::Tripped(2)));
+        assert_eq!(watch.observe(429), None);
+        assert_eq!(watch.observe(0), None);
+        assert_eq!(watch.observe(200), Some(Change::Recovered));
+        assert_eq!(watch.observe(200), None);
+    }
+
+    #[test]
+    /// without an expected class, the first response sets it
+    fn canary_watch_learns_expected_class() {
+        let mut watch = Watch::default();
+
+        assert_eq!(watch.observe(301), None);
+        assert_eq!(watch.observe(302), None);
+        assert_eq!(watch.observe(200), Some(Change::Tripped(3)));
+    }
+}
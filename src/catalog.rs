@@ -0,0 +1,305 @@
+use crate::wordlist;
+use crate::FeroxResult;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where the lists of SecLists' Discovery/Web-Content are downloaded from
+const SECLISTS: &str =
+    "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content";
+
+/// A wordlist that can be referred to by name, i.e. `-w raft-medium`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Curated {
+    /// name the list is referred to by
+    pub name: &'static str,
+
+    /// file of the list, within SecLists' Discovery/Web-Content
+    pub file: &'static str,
+
+    /// what the list is good for
+    pub description: &'static str,
+}
+
+/// Every curated wordlist (see `feroxbuster wordlists list`)
+pub const CATALOG: [Curated; 11] = [
+    Curated {
+        name: "common",
+        file: "common.txt",
+        description: "common files and directories, a quick first pass",
+    },
+    Curated {
+        name: "quickhits",
+        file: "quickhits.txt",
+        description: "sensitive files that are often left behind (backups, configs, dumps)",
+    },
+    Curated {
+        name: "raft-small",
+        file: "raft-small-directories.txt",
+        description: "directories from the RAFT project, small",
+    },
+    Curated {
+        name: "raft-medium",
+        file: "raft-medium-directories.txt",
+        description: "directories from the RAFT project, medium (the default wordlist)",
+    },
+    Curated {
+        name: "raft-large",
+        file: "raft-large-directories.txt",
+        description: "directories from the RAFT project, large",
+    },
+    Curated {
+        name: "raft-small-files",
+        file: "raft-small-files.txt",
+        description: "files from the RAFT project, small",
+    },
+    Curated {
+        name: "raft-medium-files",
+        file: "raft-medium-files.txt",
+        description: "files from the RAFT project, medium",
+    },
+    Curated {
+        name: "raft-large-files",
+        file: "raft-large-files.txt",
+        description: "files from the RAFT project, large",
+    },
+    Curated {
+        name: "raft-medium-words",
+        file: "raft-medium-words.txt",
+        description: "words from the RAFT project, for files and directories alike",
+    },
+    Curated {
+        name: "directory-list-medium",
+        file: "directory-list-2.3-medium.txt",
+        description: "directories from DirBuster's list, medium",
+    },
+    Curated {
+        name: "api-endpoints",
+        file: "api/api-endpoints.txt",
+        description: "paths of common api endpoints",
+    },
+];
+
+impl Curated {
+    /// Url the list is downloaded from
+    pub fn url(&self) -> String {
+        format!("{}/{}", SECLISTS, self.file)
+    }
+
+    /// Where the list is kept within the given directory
+    pub fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}.txt", self.name))
+    }
+}
+
+/// The curated wordlist with the given name, if any
+pub fn find(name: &str) -> Option<&'static Curated> {
+    CATALOG.iter().find(|curated| curated.name == name)
+}
+
+/// Directory the curated wordlists are downloaded to, i.e. ~/.local/share/feroxbuster/wordlists
+pub fn directory() -> FeroxResult<PathBuf> {
+    let dir = dirs::data_dir().ok_or("Could not find a data directory for the wordlists")?;
+    Ok(dir.join("feroxbuster").join("wordlists"))
+}
+
+/// Download the given list into the given directory, replacing the copy already there, if any;
+/// returns where the list was saved
+pub async fn install(curated: &Curated, dir: &Path) -> FeroxResult<PathBuf> {
+    log::trace!("enter: install({:?}, {})", curated, dir.display());
+
+    let contents = wordlist::fetch(&curated.url(), "", "").await?;
+
+    // written next to the list then renamed over it, so that a scan reading the list never sees
+    // half of it
+    let path = curated.path(dir);
+    let partial = path.with_extension("txt.partial");
+
+    fs::create_dir_all(dir)?;
+    fs::write(&partial, &contents)?;
+    fs::rename(&partial, &path)?;
+
+    log::trace!("exit: install -> {}", path.display());
+    Ok(path)
+}
+
+/// Path of the curated wordlist given to `-w` by name, downloading it first when it isn't yet;
+/// `None` when the wordlist isn't one of the curated ones, or a file by that name exists, which is
+/// used instead
+pub async fn resolve(wordlist: &str) -> FeroxResult<Option<PathBuf>> {
+    let curated = match find(wordlist) {
+        Some(curated) if !Path::new(wordlist).exists() => curated,
+        _ => return Ok(None),
+    };
+
+    let dir = directory()?;
+    let path = curated.path(&dir);
+
+    if path.exists() {
+        return Ok(Some(path));
+    }
+
+    log::info!(
+        "Downloading the {} wordlist to {}",
+        curated.name,
+        dir.display()
+    );
+    Ok(Some(install(curated, &dir).await?))
+}
+
+/// Curated wordlists with the given names, or an error naming the first unknown one
+fn find_all(names: &[String]) -> FeroxResult<Vec<&'static Curated>> {
+    names
+        .iter()
+        .map(|name| {
+            find(name).ok_or_else(|| {
+                format!(
+                    "Unknown wordlist {}, see `feroxbuster wordlists list`",
+                    name
+                )
+                .into()
+            })
+        })
+        .collect()
+}
+
+/// Write every curated wordlist, whether or not it was downloaded to the given directory, and
+/// what it's good for
+fn list<W: Write>(dir: &Path, mut writer: W) -> FeroxResult<()> {
+    writeln!(writer, "{:<24}{:<12}DESCRIPTION", "NAME", "DOWNLOADED")?;
+
+    for curated in CATALOG.iter() {
+        let downloaded = if curated.path(dir).exists() {
+            "yes"
+        } else {
+            "no"
+        };
+
+        writeln!(
+            writer,
+            "{:<24}{:<12}{}",
+            curated.name, downloaded, curated.description
+        )?;
+    }
+
+    writeln!(writer, "\nWordlists are downloaded to {}", dir.display())?;
+    Ok(())
+}
+
+/// Run `feroxbuster wordlists`, given its subcommand followed by the names given to it, i.e.
+/// `["download", "raft-medium", "common"]`
+///
+/// - **list**: every curated wordlist, and whether it was downloaded
+/// - **download**: download the given wordlists, replacing the copies already there
+/// - **update**: download every wordlist downloaded before again
+/// - **path**: where the given wordlist is kept, i.e. to use it with other tools
+pub async fn run(command: &[String]) -> FeroxResult<()> {
+    log::trace!("enter: run({:?})", command);
+
+    let dir = directory()?;
+    let (action, names) = command
+        .split_first()
+        .ok_or("No wordlists subcommand given")?;
+
+    match action.as_str() {
+        "list" => list(&dir, std::io::stdout())?,
+        "download" => {
+            for curated in find_all(names)? {
+                let path = install(curated, &dir).await?;
+                println!("Downloaded {} to {}", curated.name, path.display());
+            }
+        }
+        "update" => {
+            let downloaded: Vec<_> = CATALOG
+                .iter()
+                .filter(|curated| curated.path(&dir).exists())
+                .collect();
+
+            if downloaded.is_empty() {
+                println!("No wordlist was downloaded yet, see `feroxbuster wordlists list`");
+            }
+
+            for curated in downloaded {
+                install(curated, &dir).await?;
+                println!("Updated {}", curated.name);
+            }
+        }
+        "path" => {
+            for curated in find_all(names)? {
+                let path = curated.path(&dir);
+
+                if !path.exists() {
+                    return Err(format!(
+                        "{} wasn't downloaded yet, see `feroxbuster wordlists download {}`",
+                        curated.name, curated.name
+                    )
+                    .into());
+                }
+
+                println!("{}", path.display());
+            }
+        }
+        _ => return Err(format!("Unknown wordlists subcommand: {}", action).into()),
+    }
+
+    log::trace!("exit: run");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// names are unique, and lists are kept under their name
+    fn catalog_names_are_unique() {
+        for (i, curated) in CATALOG.iter().enumerate() {
+            assert_eq!(find(curated.name), Some(&CATALOG[i]));
+            assert!(curated.url().starts_with("https://"));
+        }
+
+        let raft = find("raft-medium").unwrap();
+        assert_eq!(
+            raft.path(Path::new("/lists")),
+            Path::new("/lists/raft-medium.txt")
+        );
+        assert!(raft
+            .url()
+            .ends_with("/Web-Content/raft-medium-directories.txt"));
+
+        assert!(find("raft-huge").is_none());
+        assert!(find_all(&[String::from("common"), String::from("raft-huge")]).is_err());
+    }
+
+    #[test]
+    /// downloaded lists are told apart from the others
+    fn catalog_lists_downloaded_wordlists() {
+        let dir = std::env::temp_dir().join(format!("ferox-catalog-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(find("common").unwrap().path(&dir), "admin\n").unwrap();
+
+        let mut written = vec![];
+        list(&dir, &mut written).unwrap();
+        let written = String::from_utf8(written).unwrap();
+
+        let common = written
+            .lines()
+            .find(|line| line.starts_with("common "))
+            .unwrap();
+        let raft = written
+            .lines()
+            .find(|line| line.starts_with("raft-medium "))
+            .unwrap();
+        assert!(common.contains(" yes "));
+        assert!(raft.contains(" no "));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test(core_threads = 1)]
+    /// only curated names that aren't files are resolved
+    async fn catalog_resolves_only_curated_names() {
+        assert!(resolve("/wordlists/common.txt").await.unwrap().is_none());
+        assert!(resolve("src").await.unwrap().is_none());
+    }
+}
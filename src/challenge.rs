@@ -0,0 +1,295 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::metadata::tag;
+use crate::utils::{ferox_print, status_colorizer};
+use reqwest::header::{HeaderMap, WWW_AUTHENTICATE};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Authentication schemes with a well-known spelling, used to normalize the scheme of a challenge
+const KNOWN_SCHEMES: [&str; 5] = ["Basic", "Bearer", "Digest", "Negotiate", "NTLM"];
+
+/// Single challenge of a `WWW-Authenticate` header, i.e. `Basic realm="Admin"`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Challenge {
+    /// authentication scheme, i.e. `Basic` or `NTLM`
+    pub scheme: String,
+
+    /// realm of the challenge, when given
+    pub realm: Option<String>,
+}
+
+impl fmt::Display for Challenge {
+    /// Challenge as it would appear in a `WWW-Authenticate` header, without its other parameters
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.realm {
+            Some(realm) => write!(f, "{} realm=\"{}\"", self.scheme, realm),
+            None => write!(f, "{}", self.scheme),
+        }
+    }
+}
+
+impl Challenge {
+    /// How to retry with the scheme of this challenge, when feroxbuster can
+    pub fn hint(&self) -> Option<&'static str> {
+        match self.scheme.as_str() {
+            "Basic" => Some("retry with -H \"Authorization: Basic <base64 of user:password>\""),
+            "Bearer" => Some("retry with -H \"Authorization: Bearer <token>\""),
+            "Digest" | "Negotiate" | "NTLM" => {
+                Some("retry through a proxy that handles the handshake (--proxy)")
+            }
+            _ => None,
+        }
+    }
+
+    /// Create the entry of a `www_authenticate` list in `--json` records
+    pub fn as_json(&self) -> Value {
+        json!({
+            "scheme": self.scheme,
+            "realm": self.realm,
+        })
+    }
+}
+
+/// Split a header value on commas that aren't within a quoted string
+fn split_unquoted(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                parts.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&value[start..]);
+    parts
+}
+
+/// The given scheme spelled the well-known way, i.e. `NTLM` for `ntlm`
+fn normalize_scheme(scheme: &str) -> String {
+    KNOWN_SCHEMES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(scheme))
+        .map_or_else(|| scheme.to_string(), |known| known.to_string())
+}
+
+/// Set the realm of the given challenge, if the given auth-param is one (i.e. `realm="Admin"`)
+fn read_param(challenge: &mut Challenge, param: &str) {
+    if let Some((name, value)) = param.split_once('=') {
+        if name.trim().eq_ignore_ascii_case("realm") {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+
+            challenge.realm = Some(value.replace("\\\"", "\"").replace("\\\\", "\\"));
+        }
+    }
+}
+
+/// Parse the challenges of a `WWW-Authenticate` header value (see RFC 7235)
+///
+/// A single value may hold several challenges, i.e. `Negotiate, NTLM` or
+/// `Basic realm="Admin", Bearer realm="api", error="invalid_token"`; only the scheme and realm of
+/// each are kept
+pub fn parse(value: &str) -> Vec<Challenge> {
+    let mut challenges: Vec<Challenge> = Vec::new();
+
+    for part in split_unquoted(value) {
+        let part = part.trim();
+
+        if part.is_empty() {
+            continue;
+        }
+
+        // a challenge starts with its scheme, followed by a space unless it has no parameters;
+        // anything else is another parameter of the current challenge
+        let scheme_end = part.find(' ').unwrap_or(part.len());
+
+        match part.find('=') {
+            Some(equals) if equals < scheme_end => {
+                if let Some(challenge) = challenges.last_mut() {
+                    read_param(challenge, part);
+                }
+            }
+            _ => {
+                let mut challenge = Challenge {
+                    scheme: normalize_scheme(&part[..scheme_end]),
+                    realm: None,
+                };
+
+                read_param(&mut challenge, part[scheme_end..].trim());
+                challenges.push(challenge);
+            }
+        }
+    }
+
+    challenges
+}
+
+/// Challenges of every `WWW-Authenticate` header in the given headers, in order
+pub fn challenges_of(headers: &HeaderMap) -> Vec<Challenge> {
+    headers
+        .get_all(WWW_AUTHENTICATE)
+        .iter()
+        .flat_map(|value| parse(&String::from_utf8_lossy(value.as_bytes())))
+        .collect()
+}
+
+/// Describe the given challenges, as shown next to a result; i.e. `Negotiate, NTLM`
+pub fn describe(challenges: &[Challenge]) -> String {
+    challenges
+        .iter()
+        .map(|challenge| challenge.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Number of reported 401s per challenge, summarized once the scan completes
+#[derive(Debug, Default)]
+pub struct ChallengeSummary {
+    /// number of 401s that offered each challenge
+    counts: BTreeMap<Challenge, usize>,
+}
+
+impl ChallengeSummary {
+    /// Count the challenges offered by a reported 401
+    pub fn insert(&mut self, challenges: &[Challenge]) {
+        for challenge in challenges {
+            *self.counts.entry(challenge.clone()).or_insert(0) += 1;
+        }
+    }
+
+    /// Whether or not any challenge was counted
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Single line describing how many 401s offered the given challenge, and how to retry
+    ///
+    /// example:
+    ///     AUT          - 3 401s challenged with Basic realm="Admin"; retry with -H "Authorization: Basic <base64 of user:password>"
+    fn format(challenge: &Challenge, count: usize) -> String {
+        format!(
+            "{} {:>10} {} 401{} challenged with {}{}\n",
+            status_colorizer("AUT"),
+            "-",
+            count,
+            if count == 1 { "" } else { "s" },
+            challenge,
+            challenge
+                .hint()
+                .map(|hint| format!("; {}", hint))
+                .unwrap_or_default()
+        )
+    }
+
+    /// Print every counted challenge, and send them to the output file if one is in use
+    pub fn report(&self, tx_file: &UnboundedSender<String>, save_output: bool) {
+        log::trace!("enter: report({:?}, {:?}, {})", self, tx_file, save_output);
+
+        for (challenge, count) in &self.counts {
+            let msg = Self::format(challenge, *count);
+
+            if !CONFIGURATION.quiet {
+                ferox_print(&msg, &PROGRESS_PRINTER);
+            }
+
+            if save_output {
+                let file_msg = if CONFIGURATION.json {
+                    let mut record = json!({
+                        "type": "authentication",
+                        "scheme": challenge.scheme,
+                        "realm": challenge.realm,
+                        "count": count,
+                        "hint": challenge.hint(),
+                    });
+
+                    tag(&mut record);
+
+                    format!("{}\n", record)
+                } else {
+                    msg
+                };
+
+                if let Err(e) = tx_file.send(file_msg) {
+                    log::error!("Could not send {} to file handler: {}", challenge, e);
+                }
+            }
+        }
+
+        log::trace!("exit: report");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// each challenge keeps its scheme and realm, whatever else the header holds
+    fn challenge_parse_multiple_challenges() {
+        let challenges = parse(
+            "basic realm=\"Admin, \\\"staff\\\"\", Bearer realm=\"api\", error=\"invalid_token\", Negotiate, NTLM TlRMTVNTUAACAAAA==",
+        );
+
+        assert_eq!(
+            describe(&challenges),
+            "Basic realm=\"Admin, \"staff\"\", Bearer realm=\"api\", Negotiate, NTLM"
+        );
+    }
+
+    #[test]
+    /// realms aren't required to be quoted, parameters before any scheme are ignored
+    fn challenge_parse_unquoted_realm() {
+        assert_eq!(
+            parse("realm=nothing, Digest realm=intranet, nonce=\"abc\""),
+            vec![Challenge {
+                scheme: String::from("Digest"),
+                realm: Some(String::from("intranet")),
+            }]
+        );
+    }
+
+    #[test]
+    /// every header is parsed, in order
+    fn challenge_challenges_of_reads_every_header() {
+        let mut headers = HeaderMap::new();
+        headers.append(WWW_AUTHENTICATE, "Negotiate".parse().unwrap());
+        headers.append(WWW_AUTHENTICATE, "Basic realm=\"x\"".parse().unwrap());
+
+        assert_eq!(
+            describe(&challenges_of(&headers)),
+            "Negotiate, Basic realm=\"x\""
+        );
+    }
+
+    #[test]
+    /// the summary counts 401s per challenge, with a hint when there's one
+    fn challenge_summary_counts_challenges() {
+        let mut summary = ChallengeSummary::default();
+        let basic = parse("Basic realm=\"Admin\"");
+
+        summary.insert(&basic);
+        summary.insert(&basic);
+
+        assert_eq!(summary.counts.get(&basic[0]), Some(&2));
+
+        let line = console::strip_ansi_codes(&ChallengeSummary::format(&basic[0], 2)).to_string();
+        assert!(line.ends_with(
+            "2 401s challenged with Basic realm=\"Admin\"; retry with -H \"Authorization: Basic <base64 of user:password>\"\n"
+        ));
+    }
+}
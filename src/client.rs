@@ -1,8 +1,6 @@
+use crate::redirects;
 use crate::utils::{module_colorizer, status_colorizer};
-use reqwest::header::HeaderMap;
 use reqwest::{redirect::Policy, Client, Proxy};
-use std::collections::HashMap;
-use std::convert::TryInto;
 #[cfg(not(test))]
 use std::process::exit;
 use std::time::Duration;
@@ -13,27 +11,22 @@ pub fn initialize(
     useragent: &str,
     redirects: bool,
     insecure: bool,
-    headers: &HashMap<String, String>,
     proxy: Option<&str>,
 ) -> Client {
     let policy = if redirects {
-        Policy::limited(10)
+        redirects::policy()
     } else {
         Policy::none()
     };
 
-    // try_into returns infallible as its error, unwrap is safe here
-    let header_map: HeaderMap = headers.try_into().unwrap();
-
     let client = Client::builder()
         .timeout(Duration::new(timeout, 0))
         .user_agent(useragent)
         .danger_accept_invalid_certs(insecure)
-        .default_headers(header_map)
         .redirect(policy);
 
-    let client = if proxy.is_some() && !proxy.unwrap().is_empty() {
-        match Proxy::all(proxy.unwrap()) {
+    let client = match proxy.filter(|p| !p.is_empty()) {
+        Some(proxy) => match Proxy::all(proxy) {
             Ok(proxy_obj) => client.proxy(proxy_obj),
             Err(e) => {
                 eprintln!(
@@ -54,9 +47,8 @@ pub fn initialize(
                 #[cfg(not(test))]
                 exit(1);
             }
-        }
-    } else {
-        client
+        },
+        None => client,
     };
 
     match client.build() {
@@ -90,15 +82,13 @@ mod tests {
     #[should_panic]
     /// create client with a bad proxy, expect panic
     fn client_with_bad_proxy() {
-        let headers = HashMap::new();
-        initialize(0, "stuff", true, false, &headers, Some("not a valid proxy"));
+        initialize(0, "stuff", true, false, Some("not a valid proxy"));
     }
 
     #[test]
     /// create client with a proxy, expect no error
     fn client_with_good_proxy() {
-        let headers = HashMap::new();
         let proxy = "http://127.0.0.1:8080";
-        initialize(0, "stuff", true, true, &headers, Some(proxy));
+        initialize(0, "stuff", true, true, Some(proxy));
     }
 }
@@ -0,0 +1,614 @@
+use crate::digest::{BodyHash, BODY_HASHES};
+use crate::history;
+use crate::utils::status_colorizer;
+use crate::FeroxResult;
+use console::style;
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::read_to_string;
+
+/// How a reported result relates to the results of a previous run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// url wasn't part of the previous run's results
+    New,
+
+    /// url was found previously, but its status code and/or size differ
+    Changed,
+
+    /// url was found previously with the same status code and size
+    Unchanged,
+
+    /// url was found previously, but not during the current run
+    Missing,
+}
+
+impl Change {
+    /// Lowercase name of the `Change`, used in json output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Change::New => "new",
+            Change::Changed => "changed",
+            Change::Unchanged => "unchanged",
+            Change::Missing => "missing",
+        }
+    }
+
+    /// Fixed width, colored tag prepended to results printed to the terminal
+    pub fn tag(self) -> String {
+        match self {
+            Change::New => style(format!("{:<8}", "NEW")).green().to_string(),
+            Change::Changed => style(format!("{:<8}", "CHANGED")).yellow().to_string(),
+            Change::Unchanged => format!("{:<8}", ""),
+            Change::Missing => style(format!("{:<8}", "MISSING")).red().to_string(),
+        }
+    }
+}
+
+/// Status code and size of a single result from a previous run
+///
+/// Results saved with `--quiet` only contain the url, in which case both are `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorResult {
+    /// status code of the previous result, if known
+    pub status: Option<u16>,
+
+    /// content-length of the previous result, if known
+    pub content_length: Option<u64>,
+
+    /// hash of the previous result's body, if recorded with `--body-hash` in `--json` output
+    pub body_hash: Option<BodyHash>,
+}
+
+impl PriorResult {
+    /// Whether or not the given result differs from this one; only what's known of both is
+    /// compared, and body hashes only when they were made with the same algorithm
+    pub fn differs(&self, other: &PriorResult) -> bool {
+        let status_differs = self.status.zip(other.status).is_some_and(|(a, b)| a != b);
+        let size_differs = self
+            .content_length
+            .zip(other.content_length)
+            .is_some_and(|(a, b)| a != b);
+        let body_differs = self
+            .body_hash
+            .as_ref()
+            .zip(other.body_hash.as_ref())
+            .is_some_and(|(a, b)| a.algorithm == b.algorithm && a.hex != b.hex);
+
+        status_differs || size_differs || body_differs
+    }
+}
+
+/// Results of a previous run, used to flag results of the current run as new/changed and to
+/// determine which previous results went missing
+#[derive(Default)]
+pub struct Baseline {
+    /// previous results, keyed by url
+    prior: HashMap<String, PriorResult>,
+
+    /// urls reported during the current run
+    seen: HashSet<String>,
+}
+
+impl Baseline {
+    /// Read the results of a previous run from `--output` file
+    ///
+    /// Both `--json` output and normal text output are understood
+    pub fn from_file(path: &str) -> FeroxResult<Self> {
+        log::trace!("enter: from_file({})", path);
+
+        let contents = read_to_string(path)?;
+        let baseline = Self::parse(&contents);
+
+        log::info!(
+            "Loaded {} previous results from {}",
+            baseline.prior.len(),
+            path
+        );
+        log::trace!("exit: from_file -> {:?}", baseline);
+
+        Ok(baseline)
+    }
+
+    /// Parse the results from the contents of an output file; lines that aren't results (log
+    /// messages, wildcard notices, etc) are ignored
+    ///
+    /// Only the results of the last run are kept when the file holds the history of several
+    /// (see `--append-runs`)
+    pub fn parse(contents: &str) -> Self {
+        let mut prior = HashMap::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+
+            if history::is_header(line) {
+                prior.clear();
+                continue;
+            }
+
+            let parsed = if line.starts_with('{') {
+                parse_json_line(line)
+            } else {
+                parse_text_line(line)
+            };
+
+            if let Some((url, result)) = parsed {
+                prior.insert(url, result);
+            }
+        }
+
+        Baseline {
+            prior,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Determine how the given result relates to the previous run and remember it as seen
+    pub fn classify(&mut self, url: &str, status: u16, content_length: u64) -> Change {
+        self.seen.insert(url.to_string());
+
+        let current = PriorResult {
+            status: Some(status),
+            content_length: Some(content_length),
+            body_hash: None,
+        };
+
+        match self.prior.get(url) {
+            None => Change::New,
+            Some(prior) if prior.differs(&current) => Change::Changed,
+            Some(_) => Change::Unchanged,
+        }
+    }
+
+    /// Previous results that weren't seen during the current run, sorted by url
+    pub fn missing(&self) -> Vec<(&str, PriorResult)> {
+        let mut missing: Vec<(&str, PriorResult)> = self
+            .prior
+            .iter()
+            .filter(|(url, _)| !self.seen.contains(*url))
+            .map(|(url, result)| (url.as_str(), result.clone()))
+            .collect();
+
+        missing.sort_by(|a, b| a.0.cmp(b.0));
+        missing
+    }
+}
+
+/// previous runs can have a *lot* of results, only the counts are shown
+impl fmt::Debug for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Baseline")
+            .field("prior", &self.prior.len())
+            .field("seen", &self.seen.len())
+            .finish()
+    }
+}
+
+/// Status code and size of a result, with placeholders for unknown values
+fn status_and_size(result: &PriorResult) -> (String, String) {
+    let status = result
+        .status
+        .map_or_else(|| String::from("-"), |s| s.to_string());
+    let content_length = result
+        .content_length
+        .map_or_else(|| String::from("-"), |c| c.to_string());
+
+    (status, content_length)
+}
+
+/// Format a result that went missing for the terminal / text output file
+pub fn format_missing(url: &str, result: &PriorResult) -> String {
+    let (status, content_length) = status_and_size(result);
+
+    format!(
+        "{}{} {:>10} {}\n",
+        Change::Missing.tag(),
+        status_colorizer(&status),
+        content_length,
+        url
+    )
+}
+
+/// Single difference between the results of two runs (see `feroxbuster compare`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// url of the result
+    pub url: String,
+
+    /// `New`, `Changed`, or `Missing`; unchanged results aren't differences
+    pub change: Change,
+
+    /// result in the new run, or in the old run when it went missing
+    pub result: PriorResult,
+
+    /// result in the old run, only when it changed
+    pub previous: Option<PriorResult>,
+}
+
+impl Difference {
+    /// Format the difference for the terminal, changed results are followed by what they were
+    pub fn format(&self) -> String {
+        let (status, content_length) = status_and_size(&self.result);
+
+        let mut line = format!(
+            "  {}{} {:>10} {}",
+            self.change.tag(),
+            status_colorizer(&status),
+            content_length,
+            self.url
+        );
+
+        if let Some(previous) = &self.previous {
+            let (status, content_length) = status_and_size(previous);
+            line.push_str(&format!(" (was {}, {})", status, content_length));
+        }
+
+        line.push('\n');
+        line
+    }
+}
+
+/// Host (scheme, host, and port) the given url belongs to, i.e. http://localhost:8000
+fn host_of(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) => parsed.origin().ascii_serialization(),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Differences between the results of an old and a new run, grouped by host; hosts and the
+/// differences within them are sorted by url
+pub fn diff(old: &Baseline, new: &Baseline) -> BTreeMap<String, Vec<Difference>> {
+    let mut differences: Vec<Difference> = Vec::new();
+
+    for (url, result) in &new.prior {
+        match old.prior.get(url) {
+            None => differences.push(Difference {
+                url: url.to_string(),
+                change: Change::New,
+                result: result.clone(),
+                previous: None,
+            }),
+            Some(previous) if previous.differs(result) => differences.push(Difference {
+                url: url.to_string(),
+                change: Change::Changed,
+                result: result.clone(),
+                previous: Some(previous.clone()),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (url, result) in &old.prior {
+        if !new.prior.contains_key(url) {
+            differences.push(Difference {
+                url: url.to_string(),
+                change: Change::Missing,
+                result: result.clone(),
+                previous: None,
+            });
+        }
+    }
+
+    differences.sort_by(|a, b| a.url.cmp(&b.url));
+
+    let mut hosts: BTreeMap<String, Vec<Difference>> = BTreeMap::new();
+
+    for difference in differences {
+        hosts
+            .entry(host_of(&difference.url))
+            .or_default()
+            .push(difference);
+    }
+
+    hosts
+}
+
+/// Render the differences of every host, followed by a summary of them
+pub fn render_diff(hosts: &BTreeMap<String, Vec<Difference>>) -> String {
+    let mut rendered = String::new();
+    let mut counts = HashMap::new();
+
+    for (host, differences) in hosts {
+        rendered.push_str(&format!("{}\n", style(host).bright().bold()));
+
+        for difference in differences {
+            *counts.entry(difference.change).or_insert(0) += 1;
+            rendered.push_str(&difference.format());
+        }
+    }
+
+    let count = |change| counts.get(&change).copied().unwrap_or(0);
+
+    rendered.push_str(&format!(
+        "{} new, {} changed, {} missing across {} host{}\n",
+        count(Change::New),
+        count(Change::Changed),
+        count(Change::Missing),
+        hosts.len(),
+        if hosts.len() == 1 { "" } else { "s" }
+    ));
+
+    rendered
+}
+
+/// Run `feroxbuster compare`, given the old and new result files; both `--json` and normal text
+/// output files are understood
+pub fn run(files: &[String]) -> FeroxResult<()> {
+    log::trace!("enter: run({:?})", files);
+
+    let (old, new) = match files {
+        [old, new] => (old, new),
+        _ => return Err("compare takes exactly two result files".into()),
+    };
+
+    let old = Baseline::from_file(old).map_err(|e| format!("Could not read {}: {}", old, e))?;
+    let new = Baseline::from_file(new).map_err(|e| format!("Could not read {}: {}", new, e))?;
+
+    print!("{}", render_diff(&diff(&old, &new)));
+
+    log::trace!("exit: run");
+    Ok(())
+}
+
+/// Parse a single `--json` record; only `response` records are results
+fn parse_json_line(line: &str) -> Option<(String, PriorResult)> {
+    let record: Value = serde_json::from_str(line).ok()?;
+
+    if record["type"] != "response" || record["change"] == Change::Missing.as_str() {
+        return None;
+    }
+
+    let url = record["url"].as_str()?.to_string();
+
+    // recorded under the name of its algorithm, see --body-hash
+    let body_hash = BODY_HASHES.iter().find_map(|algorithm| {
+        record[*algorithm].as_str().map(|hex| BodyHash {
+            algorithm,
+            hex: hex.to_string(),
+        })
+    });
+
+    let result = PriorResult {
+        status: record["status"].as_u64().map(|s| s as u16),
+        content_length: record["content_length"].as_u64(),
+        body_hash,
+    };
+
+    Some((url, result))
+}
+
+/// Parse a single line of normal text output
+///
+/// expected formats (an optional change tag may precede either):
+///     200       3280 https://localhost.com/FAQ
+///     https://localhost.com/FAQ
+fn parse_text_line(line: &str) -> Option<(String, PriorResult)> {
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+
+    match tokens.first() {
+        Some(&"MISSING") => return None,
+        Some(&"NEW") | Some(&"CHANGED") => {
+            tokens.remove(0);
+        }
+        _ => {}
+    }
+
+    match tokens.as_slice() {
+        [url] => {
+            Url::parse(url).ok()?;
+
+            let result = PriorResult {
+                status: None,
+                content_length: None,
+                body_hash: None,
+            };
+
+            Some((url.to_string(), result))
+        }
+        [status, content_length, url, ..] => {
+            let status = status.parse::<u16>().ok()?;
+            let content_length = content_length.parse::<u64>().ok()?;
+            Url::parse(url).ok()?;
+
+            let result = PriorResult {
+                status: Some(status),
+                content_length: Some(content_length),
+                body_hash: None,
+            };
+
+            Some((url.to_string(), result))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// output file contents that contain every type of line the parser should handle
+    const PREVIOUS_RUN: &str = r#"200       3280 http://localhost/FAQ
+WRN      0.123 some warning about http://localhost/ignored
+WLD        100 Wildcard response is static; auto-filtering 100 responses
+NEW     301        0 http://localhost/js
+MISSING 200       10 http://localhost/gone
+http://localhost/quiet
+{"type":"response","url":"http://localhost/api","status":403,"content_length":12}
+{"type":"response","url":"http://localhost/old","status":200,"content_length":1,"change":"missing"}
+{"type":"log","message":"http://localhost/not-a-result"}
+"#;
+
+    #[test]
+    /// text, quiet, and json lines are all parsed; everything else is ignored
+    fn compare_baseline_parse_handles_all_formats() {
+        let baseline = Baseline::parse(PREVIOUS_RUN);
+
+        assert_eq!(baseline.prior.len(), 4);
+        assert_eq!(
+            baseline.prior["http://localhost/FAQ"],
+            PriorResult {
+                status: Some(200),
+                content_length: Some(3280),
+                body_hash: None,
+            }
+        );
+        assert_eq!(baseline.prior["http://localhost/js"].status, Some(301));
+        assert_eq!(baseline.prior["http://localhost/quiet"].status, None);
+        assert_eq!(
+            baseline.prior["http://localhost/api"].content_length,
+            Some(12)
+        );
+        assert!(!baseline.prior.contains_key("http://localhost/gone"));
+        assert!(!baseline.prior.contains_key("http://localhost/old"));
+    }
+
+    #[test]
+    /// only the last run of a file holding several is kept, in either format
+    fn compare_baseline_parse_keeps_last_run() {
+        let history = r#"# run 1111 | started 2026-10-14T10:00:00.000Z | config 3f2a9c0d1e4b5a6c | targets http://localhost
+200 10 http://localhost/first
+# run 2222 | started 2026-10-15T10:00:00.000Z | config 3f2a9c0d1e4b5a6c | targets http://localhost
+301 0 http://localhost/second
+"#;
+        let baseline = Baseline::parse(history);
+        assert_eq!(baseline.prior.len(), 1);
+        assert!(baseline.prior.contains_key("http://localhost/second"));
+
+        let history = r#"{"type":"run","run_id":"1111","targets":["http://localhost"]}
+{"type":"response","url":"http://localhost/first","status":200,"content_length":10}
+{"type":"run","run_id":"2222","targets":["http://localhost"]}
+{"type":"response","url":"http://localhost/second","status":301,"content_length":0}
+"#;
+        let baseline = Baseline::parse(history);
+        assert_eq!(baseline.prior.len(), 1);
+        assert_eq!(baseline.prior["http://localhost/second"].status, Some(301));
+    }
+
+    #[test]
+    /// results are classified based on the previous run and unseen results are missing
+    fn compare_baseline_classify_and_missing() {
+        let mut baseline = Baseline::parse(PREVIOUS_RUN);
+
+        assert_eq!(
+            baseline.classify("http://localhost/FAQ", 200, 3280),
+            Change::Unchanged
+        );
+        assert_eq!(
+            baseline.classify("http://localhost/api", 200, 12),
+            Change::Changed
+        );
+        assert_eq!(
+            baseline.classify("http://localhost/quiet", 500, 1),
+            Change::Unchanged
+        );
+        assert_eq!(
+            baseline.classify("http://localhost/brand-new", 200, 1),
+            Change::New
+        );
+
+        let missing = baseline.missing();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].0, "http://localhost/js");
+    }
+
+    #[test]
+    /// missing results are tagged and show placeholders for unknown values
+    fn compare_format_missing_with_unknown_values() {
+        let result = PriorResult {
+            status: None,
+            content_length: None,
+            body_hash: None,
+        };
+        let line =
+            console::strip_ansi_codes(&format_missing("http://localhost/a", &result)).to_string();
+
+        assert!(line.starts_with("MISSING"));
+        assert!(line.ends_with("- http://localhost/a\n"));
+    }
+
+    #[test]
+    /// runs are diffed per host; results only known by url, or with the same status and size,
+    /// aren't differences
+    fn compare_diff_groups_differences_by_host() {
+        let old = Baseline::parse(
+            "200 10 http://localhost/same\n\
+             200 10 http://localhost/resized\n\
+             200 10 http://localhost/gone\n\
+             http://localhost:8080/quiet\n",
+        );
+        let new = Baseline::parse(
+            "200 10 http://localhost/same\n\
+             200 20 http://localhost/resized\n\
+             403 1 http://localhost:8080/quiet\n\
+             301 0 https://localhost/admin\n",
+        );
+
+        let hosts = diff(&old, &new);
+
+        assert_eq!(
+            hosts.keys().collect::<Vec<_>>(),
+            vec!["http://localhost", "https://localhost"]
+        );
+
+        let changes: Vec<(&str, Change)> = hosts["http://localhost"]
+            .iter()
+            .map(|d| (d.url.as_str(), d.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("http://localhost/gone", Change::Missing),
+                ("http://localhost/resized", Change::Changed),
+            ]
+        );
+        assert_eq!(
+            hosts["http://localhost"][1]
+                .previous
+                .as_ref()
+                .unwrap()
+                .content_length,
+            Some(10)
+        );
+        assert_eq!(hosts["https://localhost"][0].change, Change::New);
+    }
+
+    #[test]
+    /// bodies hashed with the same algorithm differ when their hashes do, others aren't compared
+    fn compare_diff_compares_body_hashes() {
+        let old = Baseline::parse(
+            r#"{"type":"response","url":"http://localhost/a","status":200,"content_length":1,"md5":"aa"}
+{"type":"response","url":"http://localhost/b","status":200,"content_length":1,"md5":"aa"}"#,
+        );
+        let new = Baseline::parse(
+            r#"{"type":"response","url":"http://localhost/a","status":200,"content_length":1,"md5":"bb"}
+{"type":"response","url":"http://localhost/b","status":200,"content_length":1,"sha1":"bb"}"#,
+        );
+
+        let hosts = diff(&old, &new);
+
+        assert_eq!(hosts["http://localhost"].len(), 1);
+        assert_eq!(hosts["http://localhost"][0].url, "http://localhost/a");
+        assert_eq!(hosts["http://localhost"][0].change, Change::Changed);
+    }
+
+    #[test]
+    /// every host is listed with its differences, followed by their counts
+    fn compare_render_diff_summarizes_differences() {
+        let old = Baseline::parse("404 10 http://localhost/a\n200 1 http://localhost/gone\n");
+        let new = Baseline::parse("200 12 http://localhost/a\n");
+
+        let rendered = console::strip_ansi_codes(&render_diff(&diff(&old, &new))).to_string();
+
+        assert!(rendered.starts_with("http://localhost\n"));
+        assert!(rendered.contains("  CHANGED 200         12 http://localhost/a (was 404, 10)\n"));
+        assert!(rendered.contains("  MISSING 200          1 http://localhost/gone\n"));
+        assert!(rendered.ends_with("0 new, 1 changed, 1 missing across 1 host\n"));
+        assert_eq!(
+            render_diff(&BTreeMap::new()),
+            "0 new, 0 changed, 0 missing across 0 hosts\n"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::parser;
+use clap::Shell;
+use std::io::{self, Write};
+
+/// Shells that completion scripts can be generated for
+pub const SHELLS: [&str; 4] = ["bash", "zsh", "fish", "powershell"];
+
+/// Options whose value is a path to a file, as (short, long)
+///
+/// bash and fish complete file paths for any option that takes a value, but zsh needs to be told
+/// which options take a path
+const FILE_OPTIONS: [(Option<char>, &str); 21] = [
+    (Some('w'), "wordlist"),
+    (None, "targets"),
+    (None, "import"),
+    (Some('o'), "output"),
+    (None, "compare"),
+    (None, "response-cache"),
+    (None, "tree-output"),
+    (None, "xml-output"),
+    (None, "junit-output"),
+    (None, "export-output"),
+    (None, "curl-output"),
+    (None, "http-output"),
+    (None, "error-log"),
+    (None, "stats-output"),
+    (None, "state-file"),
+    (None, "resume-from"),
+    (None, "config"),
+    (None, "save-responses"),
+    (None, "screenshot"),
+    (None, "wordlist-cache"),
+    (None, "proxy-file"),
+];
+
+/// Options from `FILE_OPTIONS` whose value is a path to a directory
+const DIRECTORY_OPTIONS: [&str; 3] = ["save-responses", "screenshot", "wordlist-cache"];
+
+/// Whether or not the given line of a zsh completion script declares `option` (i.e. `--output=[`),
+/// which may be preceded by the script's quote, a `*` for options used multiple times, or a
+/// list of conflicting options
+fn declares(line: &str, option: &str) -> bool {
+    line.match_indices(option)
+        .any(|(index, _)| index > 0 && matches!(line.as_bytes()[index - 1], b'\'' | b'*' | b')'))
+}
+
+/// Add a file (or directory) completion action to each line of a zsh completion script that
+/// declares one of the `FILE_OPTIONS`
+fn complete_zsh_paths(script: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in script.lines() {
+        let option = FILE_OPTIONS.iter().find(|(short, long)| {
+            declares(line, &format!("--{}=[", long))
+                || short.is_some_and(|short| declares(line, &format!("-{}+[", short)))
+        });
+
+        match option {
+            Some((_, long)) if line.ends_with("]' \\") => {
+                let action = if DIRECTORY_OPTIONS.contains(long) {
+                    ":DIRECTORY:_files -/"
+                } else {
+                    ":FILE:_files"
+                };
+
+                // ...[help]' \ -> ...[help]:FILE:_files' \
+                let declaration = &line[..line.len() - "' \\".len()];
+                lines.push(format!("{}{}' \\", declaration, action));
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut completed = lines.join("\n");
+    completed.push('\n');
+    completed
+}
+
+/// Write the completion script for the given shell (one of `SHELLS`)
+///
+/// In bash, zsh, and fish, values of options with a fixed set of choices (i.e. `--exit-on`)
+/// complete to those choices and options that take a path complete file paths; powershell only
+/// completes the options themselves
+pub fn generate<W: Write>(shell: &str, mut writer: W) -> io::Result<()> {
+    log::trace!("enter: generate({})", shell);
+
+    let shell = shell
+        .parse::<Shell>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let mut script = Vec::new();
+    parser::initialize().gen_completions_to("feroxbuster", shell, &mut script);
+
+    let result = match shell {
+        Shell::Zsh => {
+            writer.write_all(complete_zsh_paths(&String::from_utf8_lossy(&script)).as_bytes())
+        }
+        _ => writer.write_all(&script),
+    };
+
+    log::trace!("exit: generate -> {:?}", result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to generate the completion script for the given shell as a String
+    fn script_for(shell: &str) -> String {
+        let mut script = Vec::new();
+        generate(shell, &mut script).unwrap();
+        String::from_utf8(script).unwrap()
+    }
+
+    #[test]
+    /// every supported shell produces a script that knows about feroxbuster's options, all but
+    /// powershell know about their possible values
+    fn completions_generate_for_every_shell() {
+        for shell in SHELLS.iter() {
+            let script = script_for(shell);
+            assert!(script.contains("wordlist"), "{}", shell);
+            assert_eq!(
+                script.contains("no-findings"),
+                *shell != "powershell",
+                "{}",
+                shell
+            );
+        }
+    }
+
+    #[test]
+    /// zsh completes paths for options that take files or directories
+    fn completions_zsh_completes_paths() {
+        let script = script_for("zsh");
+
+        // helper to find the line that declares the given option; options that may be used more
+        // than once are prefixed with a *
+        let declaration = |option: &str| {
+            script
+                .lines()
+                .find(|line| {
+                    line.starts_with(&format!("'{}", option))
+                        || line.starts_with(&format!("'*{}", option))
+                })
+                .unwrap()
+                .to_string()
+        };
+
+        assert!(declaration("-w+[").ends_with("]:FILE:_files' \\"));
+        assert!(declaration("--wordlist=[").ends_with("]:FILE:_files' \\"));
+        assert!(declaration("--save-responses=[").ends_with("]:DIRECTORY:_files -/' \\"));
+        assert!(declaration("--threads=[").ends_with("]' \\"));
+    }
+
+    #[test]
+    /// FILE_OPTIONS lists exactly the options that take a FILE or DIRECTORY
+    fn completions_file_options_match_parser() {
+        let mut help = Vec::new();
+        parser::initialize().write_long_help(&mut help).unwrap();
+        let help = String::from_utf8(help).unwrap();
+
+        let in_help: Vec<&str> = help
+            .lines()
+            .filter(|line| line.contains(" <FILE>") || line.contains(" <DIRECTORY>"))
+            .filter_map(|line| line.split("--").nth(1))
+            .filter_map(|option| option.split_whitespace().next())
+            .collect();
+
+        assert_eq!(in_help.len(), FILE_OPTIONS.len());
+
+        for option in in_help {
+            assert!(
+                FILE_OPTIONS.iter().any(|(_, long)| *long == option),
+                "{} is missing from FILE_OPTIONS",
+                option
+            );
+        }
+    }
+}
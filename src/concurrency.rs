@@ -0,0 +1,267 @@
+use reqwest::StatusCode;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+/// number of requests in flight when a scan starts
+const INITIAL_LIMIT: f64 = 4.0;
+
+/// how much slower than the fastest responses seen a response may be before the target is
+/// considered to be slowing down under the load
+const LATENCY_TOLERANCE: f64 = 2.0;
+
+/// factor applied to the limit when requests fail or are throttled by the target
+const BACKOFF: f64 = 0.5;
+
+/// factor applied to the limit when responses slow down
+const LATENCY_BACKOFF: f64 = 0.9;
+
+/// share of a slower response's latency taken into the baseline, so that the baseline follows
+/// targets that get slower over the course of a scan
+const BASELINE_DRIFT: f64 = 0.01;
+
+/// how long a request waits before checking again for a free slot
+const WAIT: Duration = Duration::from_millis(5);
+
+/// What a finished request says about the load on the target
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Signal {
+    /// the target answered normally
+    Ok,
+
+    /// the request failed, or the target asked for fewer requests (429, 503)
+    Overloaded,
+}
+
+impl Signal {
+    /// Signal given by a response with the given status code
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Signal::Overloaded,
+            _ => Signal::Ok,
+        }
+    }
+}
+
+/// State of an `AdaptiveConcurrency`
+#[derive(Debug)]
+struct State {
+    /// number of requests allowed in flight, fractional so that it can grow a little with every
+    /// response
+    limit: f64,
+
+    /// number of requests in flight
+    in_flight: usize,
+
+    /// latency of the fastest responses, `None` until the first response
+    baseline: Option<Duration>,
+
+    /// whether or not the limit is still doubling, i.e. it was never lowered
+    slow_start: bool,
+
+    /// number of responses since the limit was last lowered
+    since_backoff: usize,
+}
+
+/// Adjusts the number of requests in flight to a target as its responses come in (see
+/// `--adaptive-concurrency`), using additive increase / multiplicative decrease
+///
+/// The limit starts low and doubles every round of responses until the target slows down or
+/// pushes back; from then on it grows by one request per round and shrinks whenever responses
+/// take more than twice as long as the fastest ones (by 10%), or requests fail or are throttled
+/// (by half); it's lowered at most once per round, and stays between 1 and `max`
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    /// most requests ever allowed in flight
+    max: usize,
+
+    /// the controller's state
+    state: Mutex<State>,
+}
+
+/// A request in flight, counted against the limit until dropped
+#[derive(Debug)]
+pub struct Slot<'a> {
+    /// controller the slot was taken from
+    controller: &'a AdaptiveConcurrency,
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        match self.controller.state.lock() {
+            Ok(mut state) => state.in_flight -= 1,
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
+
+impl AdaptiveConcurrency {
+    /// Controller allowing at most `max` requests in flight
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+
+        Self {
+            max,
+            state: Mutex::new(State {
+                limit: INITIAL_LIMIT.min(max as f64),
+                in_flight: 0,
+                baseline: None,
+                slow_start: true,
+                since_backoff: 0,
+            }),
+        }
+    }
+
+    /// Number of requests currently allowed in flight
+    pub fn limit(&self) -> usize {
+        match self.state.lock() {
+            Ok(state) => state.limit as usize,
+            Err(_) => 1,
+        }
+    }
+
+    /// Take a slot if one is free right away
+    fn try_acquire(&self) -> Option<Slot<'_>> {
+        let mut state = self.state.lock().ok()?;
+
+        if state.in_flight >= state.limit as usize {
+            return None;
+        }
+
+        state.in_flight += 1;
+        Some(Slot { controller: self })
+    }
+
+    /// Wait until a slot is free, then take it; the request counts against the limit until the
+    /// slot is dropped
+    pub async fn acquire(&self) -> Slot<'_> {
+        loop {
+            if let Some(slot) = self.try_acquire() {
+                return slot;
+            }
+
+            delay_for(WAIT).await;
+        }
+    }
+
+    /// Adjust the limit to a finished request, which took `latency` to be answered
+    pub fn record(&self, latency: Duration, signal: Signal) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(e) => {
+                log::error!("{}", e);
+                return;
+            }
+        };
+
+        let baseline = match state.baseline {
+            Some(baseline) if latency > baseline => {
+                baseline + (latency - baseline).mul_f64(BASELINE_DRIFT)
+            }
+            _ => latency,
+        };
+
+        state.baseline = Some(baseline);
+        state.since_backoff += 1;
+
+        let slow = latency > baseline.mul_f64(LATENCY_TOLERANCE);
+
+        if signal == Signal::Overloaded || slow {
+            // once per round of responses, the others were already in flight at the old limit
+            if state.since_backoff < state.limit as usize {
+                return;
+            }
+
+            let factor = if signal == Signal::Overloaded {
+                BACKOFF
+            } else {
+                LATENCY_BACKOFF
+            };
+
+            state.limit = (state.limit * factor).max(1.0);
+            state.slow_start = false;
+            state.since_backoff = 0;
+
+            log::debug!(
+                "Concurrency lowered to {} ({:?}, {:?} vs {:?})",
+                state.limit as usize,
+                signal,
+                latency,
+                baseline
+            );
+        } else {
+            // a whole round of responses adds either as many requests (doubling), or one
+            let increase = if state.slow_start {
+                1.0
+            } else {
+                1.0 / state.limit
+            };
+
+            state.limit = (state.limit + increase).min(self.max as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to record the given number of responses
+    fn record(controller: &AdaptiveConcurrency, count: usize, millis: u64, signal: Signal) {
+        for _ in 0..count {
+            controller.record(Duration::from_millis(millis), signal);
+        }
+    }
+
+    #[test]
+    /// the limit doubles each round until pushed back, then grows by one per round
+    fn concurrency_grows_then_probes() {
+        let controller = AdaptiveConcurrency::new(50);
+        assert_eq!(controller.limit(), 4);
+
+        record(&controller, 4, 10, Signal::Ok);
+        assert_eq!(controller.limit(), 8);
+
+        // lowered once the round of 8 requests in flight is over
+        record(&controller, 3, 10, Signal::Overloaded);
+        assert_eq!(controller.limit(), 8);
+        record(&controller, 1, 10, Signal::Overloaded);
+        assert_eq!(controller.limit(), 4);
+
+        record(&controller, 4, 10, Signal::Ok);
+        assert_eq!(controller.limit(), 4);
+        record(&controller, 1, 10, Signal::Ok);
+        assert_eq!(controller.limit(), 5);
+
+        record(&controller, 2000, 10, Signal::Ok);
+        assert_eq!(controller.limit(), 50);
+    }
+
+    #[test]
+    /// slower responses lower the limit a little, once per round; it never drops under 1
+    fn concurrency_backs_off_on_latency() {
+        let controller = AdaptiveConcurrency::new(10);
+        record(&controller, 100, 10, Signal::Ok);
+        assert_eq!(controller.limit(), 10);
+
+        record(&controller, 5, 50, Signal::Ok);
+        assert_eq!(controller.limit(), 9);
+
+        record(&controller, 1000, 10, Signal::Overloaded);
+        assert_eq!(controller.limit(), 1);
+    }
+
+    #[test]
+    /// slots are only handed out up to the limit, and given back once dropped
+    fn concurrency_slots_respect_limit() {
+        let controller = AdaptiveConcurrency::new(2);
+
+        let first = controller.try_acquire();
+        let second = controller.try_acquire();
+        assert!(first.is_some() && second.is_some());
+        assert!(controller.try_acquire().is_none());
+
+        drop(first);
+        assert!(controller.try_acquire().is_some());
+    }
+}
@@ -0,0 +1,362 @@
+use crate::banner;
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::engine::{read_unique_words, Summary};
+use crate::reporter::{get_cached_file_handle, safe_file_write};
+use crate::server;
+use crate::statistics::STATISTICS;
+use crate::targets::Target;
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use crate::{FeroxResult, VERSION};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::stderr;
+use std::time::Instant;
+
+/// Part of the scan of a target, running on a worker
+#[derive(Debug, Clone)]
+struct Shard {
+    /// url of the worker running the shard
+    worker: String,
+
+    /// id of the worker's job running the shard
+    id: usize,
+
+    /// url being scanned
+    target_url: String,
+}
+
+/// Split the words into (at most) `count` shards of about the same size
+fn shard_words(words: HashSet<String>, count: usize) -> Vec<Vec<String>> {
+    let mut words: Vec<String> = words.into_iter().collect();
+    words.sort();
+
+    let count = count.max(1);
+    let mut shards = vec![Vec::new(); count];
+
+    for (index, word) in words.into_iter().enumerate() {
+        shards[index % count].push(word);
+    }
+
+    shards.retain(|shard| !shard.is_empty());
+    shards
+}
+
+/// Configuration of a worker's job scanning the given target with the given words; the target's
+/// own options (see `--targets`) take precedence
+fn job_config(target: &Target, words: Vec<String>) -> Value {
+    // as_json redacts the credentials and headers the worker needs to scan the way this process
+    // would
+    let mut config = serde_json::to_value(&*CONFIGURATION).unwrap_or_default();
+
+    // the words are sent along with each shard, and outputs are written by the coordinator from
+    // the merged results; the rest is what a worker lets a job set
+    if let Value::Object(keys) = &mut config {
+        keys.retain(|key, _| server::JOB_KEYS.contains(&key.as_str()));
+    }
+
+    for (name, value) in &target.headers {
+        config["headers"][name] = Value::from(value.as_str());
+    }
+
+    if !target.extensions.is_empty() {
+        config["extensions"] = Value::from(target.extensions.clone());
+    }
+
+    if let Some(depth) = target.depth {
+        config["depth"] = Value::from(depth);
+    }
+
+    if let Some(rate_limit) = target.rate_limit {
+        config["rate_limit"] = Value::from(rate_limit);
+    }
+
+    config["target_url"] = Value::from(target.url.as_str());
+    config["words"] = Value::from(words);
+
+    config
+}
+
+/// Submit a job to the given worker, returning its id
+async fn submit(client: &Client, worker: &str, job: &Value) -> FeroxResult<usize> {
+    log::trace!("enter: submit({}, {})", worker, job["target_url"]);
+
+    let response = client
+        .post(&format!("{}/scans", worker.trim_end_matches('/')))
+        .header(CONTENT_TYPE, "application/json")
+        .body(job.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    let body: Value = serde_json::from_str(&response.text().await?).unwrap_or_default();
+
+    let id = match body["id"].as_u64() {
+        Some(id) if status.is_success() => id as usize,
+        _ => return Err(format!("{} refused the job: {}", worker, body["error"]).into()),
+    };
+
+    log::trace!("exit: submit -> {}", id);
+    Ok(id)
+}
+
+/// Submit the job to the given worker, or to the next one that accepts it when it doesn't
+async fn place(client: &Client, first: usize, job: &Value) -> FeroxResult<Shard> {
+    let workers = &CONFIGURATION.workers;
+    let mut errors = vec![];
+
+    for offset in 0..workers.len() {
+        let worker = &workers[(first + offset) % workers.len()];
+
+        match submit(client, worker, job).await {
+            Ok(id) => {
+                return Ok(Shard {
+                    worker: worker.clone(),
+                    id,
+                    target_url: job["target_url"].as_str().unwrap_or_default().to_string(),
+                })
+            }
+            Err(e) => {
+                log::warn!("{}", e);
+                errors.push(e.to_string());
+            }
+        }
+    }
+
+    Err(errors.join(", ").into())
+}
+
+/// Report a result found by a worker, the same way the results of a local scan are
+fn report(record: &Value) {
+    let status = record["status"].as_u64().unwrap_or_default().to_string();
+
+    // example output
+    // 200        329 http://localhost/admin.php
+    let report = format!(
+        "{} {:>10} {}\n",
+        status_colorizer(&status),
+        record["content_length"].as_u64().unwrap_or_default(),
+        record["url"].as_str().unwrap_or_default()
+    );
+
+    ferox_print(&report, &PROGRESS_PRINTER);
+    STATISTICS.add_reported(record["url"].as_str().unwrap_or_default());
+
+    if CONFIGURATION.output.is_empty() {
+        return;
+    }
+
+    if let Some(file) = get_cached_file_handle(&CONFIGURATION.output) {
+        if CONFIGURATION.json {
+            safe_file_write(&format!("{}\n", record), file);
+        } else {
+            safe_file_write(&report, file);
+        }
+    }
+}
+
+/// Follow a shard's results as the worker streams them, until the shard is done; returns the
+/// worker's job as of then
+async fn follow(client: &Client, shard: &Shard) -> FeroxResult<Value> {
+    log::trace!("enter: follow({:?})", shard);
+
+    let mut response = client
+        .get(&format!(
+            "{}/scans/{}/results",
+            shard.worker.trim_end_matches('/'),
+            shard.id
+        ))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let mut buffered = String::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        buffered.push_str(&String::from_utf8_lossy(&chunk));
+
+        // server-sent events are separated by an empty line
+        while let Some(end) = buffered.find("\n\n") {
+            let event: String = buffered.drain(..end + 2).collect();
+
+            let mut name = "";
+            let mut data = "";
+
+            for line in event.lines() {
+                if let Some(value) = line.strip_prefix("event: ") {
+                    name = value;
+                } else if let Some(value) = line.strip_prefix("data: ") {
+                    data = value;
+                }
+            }
+
+            let data: Value = serde_json::from_str(data).unwrap_or_default();
+
+            match name {
+                "result" => report(&data),
+                "end" => {
+                    log::trace!("exit: follow -> {}", data["status"]);
+                    return Ok(data);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Err(format!("{} stopped streaming job {}", shard.worker, shard.id).into())
+}
+
+/// Cancel a shard's job on its worker
+async fn cancel(client: &Client, shard: &Shard) {
+    let url = format!("{}/scans/{}", shard.worker.trim_end_matches('/'), shard.id);
+
+    if let Err(e) = client.delete(&url).send().await {
+        log::warn!("Could not cancel {}: {}", url, e);
+    }
+}
+
+/// Print an error about the distributed scan
+fn print_error(function: &str, msg: &str) {
+    ferox_print(
+        &format!(
+            "{} {} {}\n",
+            status_colorizer("ERROR"),
+            module_colorizer(function),
+            msg
+        ),
+        &PROGRESS_PRINTER,
+    );
+}
+
+/// Shard every target's words across the workers (see `--worker`), then merge the results they
+/// stream back into this process's output; Ctrl+C cancels the shards still running
+///
+/// Each worker scans its shards with the coordinator's configuration, including its rate limit,
+/// so that the rate limit applies per worker (and per source ip); targets that no worker accepted
+/// are counted as unreachable
+pub async fn run(targets: Vec<Target>) -> FeroxResult<Summary> {
+    log::trace!("enter: run({:?})", targets);
+
+    if targets.is_empty() {
+        return Err("no targets were given to scan".into());
+    }
+
+    if CONFIGURATION.workers.is_empty() {
+        return Err("a coordinator needs at least one --worker".into());
+    }
+
+    let urls: Vec<String> = targets.iter().map(|target| target.url.clone()).collect();
+
+    if !CONFIGURATION.quiet {
+        banner::initialize(&urls, &CONFIGURATION, VERSION, stderr()).await;
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", CONFIGURATION.api_token))?,
+    );
+
+    let client = Client::builder().default_headers(headers).build()?;
+    let start = Instant::now();
+    let mut shards = vec![];
+    let mut unreachable = HashSet::new();
+
+    for target in &targets {
+        let words = read_unique_words(target.wordlist(&CONFIGURATION)).await?;
+
+        for (index, words) in shard_words(words, CONFIGURATION.workers.len())
+            .into_iter()
+            .enumerate()
+        {
+            match place(&client, index, &job_config(target, words)).await {
+                Ok(shard) => {
+                    log::info!("Shard {} of {} runs on {}", index, target.url, shard.worker);
+                    shards.push(shard);
+                }
+                Err(e) => {
+                    print_error("coordinator::place", &e.to_string());
+                    unreachable.insert(target.url.clone());
+                }
+            }
+        }
+    }
+
+    let following = futures::future::join_all(shards.iter().map(|shard| {
+        let client = client.clone();
+
+        async move {
+            match follow(&client, shard).await {
+                Ok(job) if job["status"] != "finished" => print_error(
+                    "coordinator::follow",
+                    &format!(
+                        "shard of {} on {} ended as {} ({})",
+                        shard.target_url, shard.worker, job["status"], job["exit_code"]
+                    ),
+                ),
+                Ok(_) => {}
+                Err(e) => print_error("coordinator::follow", &e.to_string()),
+            }
+        }
+    }));
+
+    tokio::select! {
+        _ = following => {}
+        _ = tokio::signal::ctrl_c() => {
+            log::warn!("Scan cancelled, cancelling the shards on every worker...");
+
+            futures::future::join_all(shards.iter().map(|shard| cancel(&client, shard))).await;
+        }
+    }
+
+    let summary = Summary {
+        unreachable: unreachable.len(),
+        elapsed: start.elapsed(),
+    };
+
+    log::trace!("exit: run -> {:?}", summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    /// words are dealt out evenly, without empty shards when there are fewer words than workers
+    fn coordinator_shard_words_deals_evenly() {
+        let words: HashSet<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+
+        let shards = shard_words(words.clone(), 2);
+        assert_eq!(shards, vec![vec!["a", "c", "e"], vec!["b", "d"]]);
+
+        let shards = shard_words(words, 8);
+        assert_eq!(shards.len(), 5);
+    }
+
+    #[test]
+    /// a shard's job carries its words and the target's options, but nothing local
+    fn coordinator_job_config_applies_target() {
+        let mut target = Target::from_url("http://localhost");
+        target.depth = Some(1);
+        target
+            .headers
+            .insert(String::from("Authorization"), String::from("Bearer abc"));
+
+        let job = job_config(&target, vec![String::from("admin")]);
+
+        assert_eq!(job["target_url"], "http://localhost");
+        assert_eq!(job["words"], json!(["admin"]));
+        assert_eq!(job["depth"], 1);
+        assert_eq!(job["headers"]["Authorization"], "Bearer abc");
+        assert!(job.get("wordlist").is_none());
+        assert!(job.get("output").is_none());
+        assert!(job.get("api_token").is_none());
+        assert!(job.get("role").is_none());
+    }
+}
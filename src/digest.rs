@@ -0,0 +1,123 @@
+use openssl::hash::{hash, MessageDigest};
+
+/// Algorithms response bodies can be hashed with (see `--body-hash`)
+pub const BODY_HASHES: [&str; 3] = ["md5", "sha1", "sha256"];
+
+/// Hash of a response's body, recorded in structured output under the name of its algorithm, i.e.
+/// `"sha256": "e3b0c442..."`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyHash {
+    /// one of `BODY_HASHES`
+    pub algorithm: &'static str,
+
+    /// lowercase hex digest of the body
+    pub hex: String,
+}
+
+/// Hash the given body with the given algorithm; `None` when the algorithm isn't one of
+/// `BODY_HASHES`, i.e. when `--body-hash` isn't used
+pub fn hash_body(algorithm: &str, body: &[u8]) -> Option<BodyHash> {
+    let (algorithm, digest) = match algorithm {
+        "md5" => ("md5", MessageDigest::md5()),
+        "sha1" => ("sha1", MessageDigest::sha1()),
+        "sha256" => ("sha256", MessageDigest::sha256()),
+        _ => return None,
+    };
+
+    let digest = match hash(digest, body) {
+        Ok(digest) => digest,
+        Err(e) => {
+            log::error!("Could not hash response body with {}: {}", algorithm, e);
+            return None;
+        }
+    };
+
+    let hex = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    Some(BodyHash { algorithm, hex })
+}
+
+/// Algorithm of a lowercase hex digest given with `--filter-hash`, going by its length; `None`
+/// when it isn't one of `BODY_HASHES`
+pub fn algorithm_of(hex: &str) -> Option<&'static str> {
+    if !hex
+        .chars()
+        .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    {
+        return None;
+    }
+
+    match hex.len() {
+        32 => Some("md5"),
+        40 => Some("sha1"),
+        64 => Some("sha256"),
+        _ => None,
+    }
+}
+
+/// Whether or not the given body has one of the given hashes; the body is hashed once per
+/// algorithm in use
+pub fn matches_any(body: &[u8], hashes: &[String]) -> bool {
+    BODY_HASHES.iter().any(|algorithm| {
+        let wanted: Vec<&String> = hashes
+            .iter()
+            .filter(|hash| algorithm_of(hash) == Some(algorithm))
+            .collect();
+
+        !wanted.is_empty()
+            && hash_body(algorithm, body).is_some_and(|digest| wanted.contains(&&digest.hex))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// digests match the well known ones, anything but the supported algorithms isn't hashed
+    fn digest_hash_body_matches_known_digests() {
+        assert_eq!(
+            hash_body("md5", b"").unwrap().hex,
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+        assert_eq!(
+            hash_body("sha1", b"abc").unwrap().hex,
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+
+        let sha256 = hash_body("sha256", b"abc").unwrap();
+        assert_eq!(sha256.algorithm, "sha256");
+        assert_eq!(
+            sha256.hex,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        assert!(hash_body("", b"abc").is_none());
+        assert!(hash_body("crc32", b"abc").is_none());
+    }
+
+    #[test]
+    /// digests are told apart by their length, bodies match any of the given hashes
+    fn digest_matches_any_of_the_given_hashes() {
+        assert_eq!(
+            algorithm_of("d41d8cd98f00b204e9800998ecf8427e"),
+            Some("md5")
+        );
+        assert_eq!(
+            algorithm_of("a9993e364706816aba3e25717850c26c9cd0d89d"),
+            Some("sha1")
+        );
+        assert_eq!(algorithm_of("d41d8cd98f00b204e9800998ecf8427"), None);
+        assert_eq!(algorithm_of("z41d8cd98f00b204e9800998ecf8427e"), None);
+
+        let hashes = vec![
+            String::from("d41d8cd98f00b204e9800998ecf8427e"),
+            String::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        ];
+
+        assert!(matches_any(b"", &hashes));
+        assert!(matches_any(b"abc", &hashes));
+        assert!(!matches_any(b"abcd", &hashes));
+        assert!(!matches_any(b"abc", &[]));
+    }
+}
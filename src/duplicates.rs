@@ -0,0 +1,217 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::digest::hash_body;
+use crate::metadata::tag;
+use crate::utils::{ferox_print, status_colorizer};
+use serde_json::json;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Number of hex digits of a body's hash shown in the collapsed result
+const SHORT_HASH: usize = 12;
+
+/// Reported responses with identical bodies, one group per status and body
+#[derive(Debug, Clone, PartialEq)]
+struct Group {
+    /// status of the responses
+    status: u16,
+
+    /// sha256 of the body of the responses
+    sha256: String,
+
+    /// url of every response, in the order they were reported
+    urls: Vec<String>,
+}
+
+/// Responses whose status and body were already reported `limit` times, collapsed into a single
+/// result per body once the scan completes (`--collapse-duplicates`)
+#[derive(Debug, Default)]
+pub struct Duplicates {
+    /// number of responses with the same status and body that are reported as usual
+    limit: usize,
+
+    /// groups in the order their first response was reported
+    groups: Vec<Group>,
+
+    /// index of each group in `groups`, by status and sha256 of the body
+    index: HashMap<(u16, String), usize>,
+}
+
+impl Duplicates {
+    /// Collapse the responses past the first `limit` with the same status and body
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// Count a response that's about to be reported; true when `limit` responses with the same
+    /// status and body were already reported, and it's collapsed instead
+    ///
+    /// Empty bodies, i.e. those of redirects or of responses whose body wasn't read, are never
+    /// collapsed
+    pub fn collapse(&mut self, status: u16, body: &str, url: &str) -> bool {
+        if body.is_empty() {
+            return false;
+        }
+
+        let sha256 = match hash_body("sha256", body.as_bytes()) {
+            Some(hash) => hash.hex,
+            None => return false,
+        };
+
+        let groups = &mut self.groups;
+
+        let index = *self
+            .index
+            .entry((status, sha256.clone()))
+            .or_insert_with(|| {
+                groups.push(Group {
+                    status,
+                    sha256,
+                    urls: Vec::new(),
+                });
+                groups.len() - 1
+            });
+
+        let urls = &mut self.groups[index].urls;
+        urls.push(url.to_string());
+
+        urls.len() > self.limit
+    }
+
+    /// Groups of which some responses were collapsed
+    fn collapsed(&self) -> impl Iterator<Item = &Group> {
+        self.groups
+            .iter()
+            .filter(move |group| group.urls.len() > self.limit)
+    }
+
+    /// Whether or not any response was collapsed
+    pub fn is_empty(&self) -> bool {
+        self.collapsed().next().is_none()
+    }
+
+    /// Single line describing a group of identical responses; the urls of the collapsed ones
+    /// follow it, one per line, with `list_urls`
+    ///
+    /// example:
+    ///     DUP          - 200 http://localhost/mirror/a and 37 identical responses (sha256 1f8ac10f23c5)
+    fn format(&self, group: &Group, list_urls: bool) -> String {
+        let collapsed = &group.urls[self.limit..];
+
+        let mut msg = format!(
+            "{} {:>10} {} {} and {} identical response{} (sha256 {})\n",
+            status_colorizer("DUP"),
+            "-",
+            status_colorizer(&group.status.to_string()),
+            group.urls[0],
+            group.urls.len() - 1,
+            if group.urls.len() == 2 { "" } else { "s" },
+            &group.sha256[..SHORT_HASH]
+        );
+
+        if list_urls {
+            for url in collapsed {
+                msg.push_str(&format!("    {}\n", url));
+            }
+        }
+
+        msg
+    }
+
+    /// Print every group of which some responses were collapsed, and send them to the output file
+    /// if one is in use; the file lists the url of every collapsed response
+    pub fn report(&self, tx_file: &UnboundedSender<String>, save_output: bool) {
+        log::trace!("enter: report({:?}, {:?}, {})", self, tx_file, save_output);
+
+        for group in self.collapsed() {
+            if !CONFIGURATION.quiet {
+                ferox_print(&self.format(group, false), &PROGRESS_PRINTER);
+            }
+
+            if save_output {
+                let file_msg = if CONFIGURATION.json {
+                    let mut record = json!({
+                        "type": "duplicates",
+                        "status": group.status,
+                        "sha256": group.sha256,
+                        "url": group.urls[0],
+                        "count": group.urls.len(),
+                        "collapsed": group.urls[self.limit..],
+                    });
+
+                    tag(&mut record);
+
+                    format!("{}\n", record)
+                } else {
+                    self.format(group, true)
+                };
+
+                if let Err(e) = tx_file.send(file_msg) {
+                    log::error!("Could not send {} to file handler: {}", group.urls[0], e);
+                }
+            }
+        }
+
+        log::trace!("exit: report");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// responses past the limit are collapsed, per status and body
+    fn duplicates_collapse_past_limit() {
+        let mut duplicates = Duplicates::new(2);
+        let page = "<html>mirror</html>";
+
+        assert!(!duplicates.collapse(200, page, "http://localhost/a"));
+        assert!(!duplicates.collapse(200, page, "http://localhost/b"));
+        assert!(duplicates.is_empty());
+
+        assert!(duplicates.collapse(200, page, "http://localhost/c"));
+        assert!(duplicates.collapse(200, page, "http://localhost/d"));
+        assert!(!duplicates.is_empty());
+
+        // same body with another status, another body, and empty bodies are their own
+        assert!(!duplicates.collapse(403, page, "http://localhost/e"));
+        assert!(!duplicates.collapse(200, "<html>other</html>", "http://localhost/f"));
+
+        for url in [
+            "http://localhost/g",
+            "http://localhost/h",
+            "http://localhost/i",
+        ] {
+            assert!(!duplicates.collapse(301, "", url));
+        }
+
+        let collapsed: Vec<&Group> = duplicates.collapsed().collect();
+        assert_eq!(collapsed.len(), 1);
+        assert_eq!(collapsed[0].urls.len(), 4);
+    }
+
+    #[test]
+    /// the collapsed result names the first url and counts the others, the file lists them
+    fn duplicates_format_lists_collapsed_urls() {
+        let mut duplicates = Duplicates::new(1);
+
+        for path in ["a", "b", "c"] {
+            duplicates.collapse(200, "same", &format!("http://localhost/{}", path));
+        }
+
+        let group = duplicates.collapsed().next().unwrap();
+        let line = console::strip_ansi_codes(&duplicates.format(group, false)).to_string();
+
+        assert!(line.starts_with("DUP"));
+        assert!(line.contains(" 200 http://localhost/a and 2 identical responses (sha256 "));
+        assert_eq!(line.lines().count(), 1);
+
+        let listed = console::strip_ansi_codes(&duplicates.format(group, true)).to_string();
+        let urls: Vec<&str> = listed.lines().skip(1).map(str::trim).collect();
+
+        assert_eq!(urls, ["http://localhost/b", "http://localhost/c"]);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::config::CONFIGURATION;
+use crate::heuristics::volatile_words;
+use crate::progress;
+use crate::scanner::ScanOptions;
+use crate::utils::make_request;
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+lazy_static! {
+    /// Pages whose body changed when requested again, by url
+    static ref DYNAMIC: RwLock<HashMap<String, Volatility>> = RwLock::new(HashMap::new());
+}
+
+/// How a page changed from one request to the next (see `--detect-dynamic`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volatility {
+    /// size of the body the first time and the second time it was requested
+    pub sizes: (u64, u64),
+
+    /// words whose number of appearances changed, i.e. timestamps or CSRF tokens
+    pub words: HashSet<String>,
+}
+
+impl Volatility {
+    /// Compare two bodies of the page served for `url`; `None` when they're the same
+    pub fn between(first: &str, second: &str, url: &Url) -> Option<Self> {
+        if first == second {
+            return None;
+        }
+
+        Some(Self {
+            sizes: (first.len() as u64, second.len() as u64),
+            words: volatile_words(first, second, url),
+        })
+    }
+
+    /// Description of the change, as shown next to a result; i.e. `dynamic: 3 volatile words` or
+    /// `dynamic: 3 volatile words, 5120-5135 bytes`
+    pub fn describe(&self) -> String {
+        let words = format!(
+            "dynamic: {} volatile word{}",
+            self.words.len(),
+            if self.words.len() == 1 { "" } else { "s" }
+        );
+
+        let (first, second) = self.sizes;
+
+        if first == second {
+            words
+        } else {
+            format!(
+                "{}, {}-{} bytes",
+                words,
+                first.min(second),
+                first.max(second)
+            )
+        }
+    }
+
+    /// Create the `dynamic` entry of a result's `--json` record; the volatile words are sorted
+    pub fn as_json(&self) -> Value {
+        let mut words: Vec<&String> = self.words.iter().collect();
+        words.sort();
+
+        json!({
+            "sizes": [self.sizes.0, self.sizes.1],
+            "volatile_words": words,
+        })
+    }
+}
+
+/// Volatility of an already compared page, if it was found to be dynamic; used when filtering
+/// and reporting
+pub fn volatility_of(url: &Url) -> Option<Volatility> {
+    match DYNAMIC.read() {
+        Ok(pages) => pages.get(url.as_str()).cloned(),
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            None
+        }
+    }
+}
+
+/// Whether or not the size of the page at the given url changed from one request to the next;
+/// such a size is left out of size comparisons
+pub fn has_volatile_size(url: &Url) -> bool {
+    match DYNAMIC.read() {
+        Ok(pages) => pages
+            .get(url.as_str())
+            .is_some_and(|volatility| volatility.sizes.0 != volatility.sizes.1),
+        Err(e) => {
+            log::error!("{}", e);
+            false
+        }
+    }
+}
+
+/// Request a potential finding once more, then remember how its body changed, if it did (see
+/// `--detect-dynamic`)
+///
+/// Only responses that may be reported and whose body was read are requested again
+pub async fn check_response(response: &FeroxResponse, options: &ScanOptions) -> Option<Volatility> {
+    log::trace!("enter: check_response({}, {:?})", response.url(), options);
+
+    if !CONFIGURATION
+        .statuscodes
+        .contains(&response.status().as_u16())
+        || response.text().is_empty()
+    {
+        log::trace!("exit: check_response -> None");
+        return None;
+    }
+
+    options.throttle().await;
+
+    // the request isn't part of any directory's bar, it's only counted toward the total
+    progress::inc_total_length(1);
+    let again = make_request(&options.client, response.url()).await;
+    progress::inc_total(1);
+
+    // a failed request says nothing about the page
+    let again = match again {
+        Ok(again) => FeroxResponse::from(again, true).await,
+        Err(_) => {
+            log::trace!("exit: check_response -> None");
+            return None;
+        }
+    };
+
+    let volatility = Volatility::between(response.text(), again.text(), response.url());
+
+    if let Some(volatility) = &volatility {
+        log::debug!("{} is dynamic: {:?}", response.url(), volatility);
+
+        match DYNAMIC.write() {
+            Ok(mut pages) => {
+                pages.insert(response.url().to_string(), volatility.clone());
+            }
+            Err(e) => {
+                log::error!("{}", e);
+            }
+        }
+    }
+
+    log::trace!("exit: check_response -> {:?}", volatility);
+    volatility
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// identical bodies aren't dynamic, the words that changed between two bodies are volatile
+    fn dynamic_volatility_between_bodies() {
+        let url = Url::parse("http://localhost/news").unwrap();
+
+        assert!(Volatility::between("<p>same</p>", "<p>same</p>", &url).is_none());
+
+        let volatility = Volatility::between(
+            "<p>News</p><input name=csrf value=a1b2c3><p>12:00:01</p>",
+            "<p>News</p><input name=csrf value=d4e5f6><p>12:00:02</p>",
+            &url,
+        )
+        .unwrap();
+
+        let mut words: Vec<&str> = volatility.words.iter().map(String::as_str).collect();
+        words.sort_unstable();
+
+        assert_eq!(words, ["01", "02", "a1b2c3", "d4e5f6"]);
+        assert_eq!(volatility.describe(), "dynamic: 4 volatile words");
+        assert_eq!(volatility.as_json()["volatile_words"][0], "01");
+
+        let resized = Volatility::between("<p>1 of 9</p>", "<p>10 of 99</p>", &url).unwrap();
+        assert_eq!(resized.describe(), "dynamic: 4 volatile words, 13-15 bytes");
+    }
+}
@@ -0,0 +1,852 @@
+//! The scanning engine behind the `feroxbuster` binary, which can be embedded in other tools
+//!
+//! ```no_run
+//! use feroxbuster::engine::Scanner;
+//!
+//! #[tokio::main]
+//! async fn main() -> feroxbuster::FeroxResult<()> {
+//!     let scanner = Scanner::builder()
+//!         .url("http://localhost")
+//!         .wordlist("/wordlists/raft-medium-directories.txt")
+//!         .extensions(&["php", "bak"])
+//!         .threads(20)
+//!         .quiet(true)
+//!         .build()?;
+//!
+//!     for response in scanner.scan().await? {
+//!         println!("{} {}", response.status(), response.url());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The scan can be followed as it happens through its events, which the binary emits as well
+//!
+//! ```no_run
+//! use feroxbuster::engine::Scanner;
+//! use feroxbuster::events::{self, Event};
+//! use futures::StreamExt;
+//!
+//! #[tokio::main]
+//! async fn main() -> feroxbuster::FeroxResult<()> {
+//!     let scanner = Scanner::builder().url("http://localhost").build()?;
+//!     let mut events = events::subscribe();
+//!
+//!     let printer = tokio::spawn(async move {
+//!         while let Some(event) = events.next().await {
+//!             match event {
+//!                 Event::ScanStarted { url, .. } => println!("scanning {}", url),
+//!                 Event::ResultReported(response) => println!("found {}", response.url()),
+//!                 _ => {}
+//!             }
+//!         }
+//!     });
+//!
+//!     scanner.scan().await?;
+//!     printer.await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The configuration is global to the process, so only one `Scanner` can be built per process
+use crate::banner;
+use crate::cache;
+use crate::canary;
+use crate::catalog;
+use crate::client;
+use crate::compare::Baseline;
+use crate::config::{self, Configuration, CONFIGURATION, PROGRESS_TOTAL};
+use crate::evasion;
+use crate::events::{self, Event};
+use crate::fingerprint;
+use crate::heuristics;
+use crate::history;
+use crate::hooks::{self, Headers};
+use crate::login;
+use crate::metadata::SCAN_TAGS;
+use crate::negotiate;
+use crate::notifier;
+use crate::oauth;
+use crate::proxy;
+use crate::reporter;
+use crate::rotation;
+use crate::scanner::scan_url;
+use crate::schedule;
+use crate::screenshot;
+use crate::security;
+use crate::signals;
+use crate::sigv4;
+use crate::state;
+use crate::statistics;
+use crate::statsd;
+use crate::summary;
+use crate::targets::Target;
+use crate::tui;
+use crate::utils::{get_current_depth, module_colorizer, rfc3339_timestamp, status_colorizer};
+use crate::wordlist::{self, Origin, Wordlist};
+use crate::{FeroxResponse, FeroxResult, VERSION};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::stderr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Everything a scan may be configured with; the same settings as the command line and
+/// `ferox-config.toml`
+pub type ScanConfig = Configuration;
+
+/// Index the unique words of the given wordlist file (see `Wordlist::add`), which are streamed
+/// from disk during the scan
+fn get_unique_words_from_wordlist(
+    path: &str,
+    words: &mut Wordlist,
+    seen: &mut HashSet<u64>,
+) -> FeroxResult<usize> {
+    log::trace!(
+        "enter: get_unique_words_from_wordlist({}, wordlist[{} words...], [{} seen...])",
+        path,
+        words.len(),
+        seen.len()
+    );
+
+    let added = match words.add(Origin::File(PathBuf::from(path)), seen) {
+        Ok(added) => added,
+        Err(e) => {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("engine::get_unique_words_from_wordlist"),
+                e
+            );
+            log::error!("Could not read wordlist: {}", e);
+            log::trace!("exit: get_unique_words_from_wordlist -> {}", e);
+
+            return Err(Box::new(e));
+        }
+    };
+
+    log::trace!("exit: get_unique_words_from_wordlist -> {}", added);
+    Ok(added)
+}
+
+/// Download the wordlist at the given url (see `--wordlist-cache` and `--wordlist-sha256`), then
+/// add its unique words to `words`; errors if it can't be fetched
+async fn get_unique_words_from_url(
+    url: &str,
+    words: &mut Wordlist,
+    seen: &mut HashSet<u64>,
+) -> FeroxResult<usize> {
+    log::trace!(
+        "enter: get_unique_words_from_url({}, wordlist[{} words...], [{} seen...])",
+        url,
+        words.len(),
+        seen.len()
+    );
+
+    let fetched = wordlist::fetch(
+        url,
+        &CONFIGURATION.wordlist_cache,
+        &CONFIGURATION.wordlist_sha256,
+    )
+    .await;
+
+    let added =
+        fetched.and_then(|contents| Ok(words.add(Origin::Memory(Cow::Owned(contents)), seen)?));
+
+    match added {
+        Ok(added) => {
+            log::trace!("exit: get_unique_words_from_url -> {}", added);
+            Ok(added)
+        }
+        Err(e) => {
+            log::error!("Could not fetch wordlist from {}: {}", url, e);
+            log::trace!("exit: get_unique_words_from_url -> {}", e);
+
+            Err(format!("Could not fetch wordlist from {}: {}", url, e).into())
+        }
+    }
+}
+
+/// Transformations to apply to the words, as configured; `%year%` is replaced with the current
+/// year and the four before it
+fn word_transforms() -> wordlist::Transforms<'static> {
+    let this_year = rfc3339_timestamp(SystemTime::now())[..4]
+        .parse::<u32>()
+        .unwrap_or(1970);
+
+    wordlist::Transforms {
+        prefixes: &CONFIGURATION.word_prefixes,
+        suffixes: &CONFIGURATION.word_suffixes,
+        cases: &CONFIGURATION.word_cases,
+        extensions: &CONFIGURATION.extensions,
+        years: this_year.saturating_sub(4)..=this_year,
+    }
+}
+
+/// Combine the unique words of every given wordlist (local or remote), as they're written; words
+/// found in more than one list are only kept once
+///
+/// Local lists are only indexed here, their words are streamed from disk whenever they're used
+async fn read_wordlist(wordlists: &[String]) -> FeroxResult<Wordlist> {
+    log::trace!("enter: read_wordlist({:?})", wordlists);
+
+    let mut words = Wordlist::default();
+
+    // hashes of the words added so far, only needed until every list is indexed
+    let mut seen = HashSet::new();
+
+    for wordlist in wordlists {
+        let found = if wordlist::is_embedded(wordlist) {
+            let contents = wordlist::embedded().unwrap_or_default().as_bytes();
+            words.add(Origin::Memory(Cow::Borrowed(contents)), &mut seen)?
+        } else if wordlist::is_remote(wordlist) {
+            get_unique_words_from_url(wordlist, &mut words, &mut seen).await?
+        } else if let Some(path) = catalog::resolve(wordlist).await? {
+            // a curated wordlist given by name, i.e. -w raft-medium
+            get_unique_words_from_wordlist(&path.to_string_lossy(), &mut words, &mut seen)?
+        } else {
+            get_unique_words_from_wordlist(wordlist, &mut words, &mut seen)?
+        };
+
+        log::info!("Read {} unique words from {}", found, wordlist);
+    }
+
+    log::trace!("exit: read_wordlist -> wordlist[{} words...]", words.len());
+
+    Ok(words)
+}
+
+/// Combine the unique words of every given wordlist (local or remote) into a set; words found in
+/// more than one list are only kept once
+pub async fn read_unique_words(wordlists: &[String]) -> FeroxResult<HashSet<String>> {
+    log::trace!("enter: read_unique_words({:?})", wordlists);
+
+    let words: HashSet<String> = read_wordlist(wordlists).await?.iter().collect();
+
+    log::trace!(
+        "exit: read_unique_words -> wordlist[{} words...]",
+        words.len()
+    );
+
+    Ok(words)
+}
+
+/// Combine the unique words of every given wordlist (local or remote), transform them (see
+/// `--word-prefix` and friends), then store them inside an Arc; words found in more than one list
+/// are only scanned once
+async fn get_unique_words(wordlists: &[String]) -> FeroxResult<Arc<Wordlist>> {
+    log::trace!("enter: get_unique_words({:?})", wordlists);
+
+    let words = read_wordlist(wordlists).await?;
+    let read = words.len();
+    let words = words.transform(&word_transforms());
+
+    if words.len() != read {
+        log::info!("Transformed {} unique words into {}", read, words.len());
+    }
+
+    log::trace!(
+        "exit: get_unique_words -> Arc<wordlist[{} words...]>",
+        words.len()
+    );
+
+    Ok(Arc::new(words))
+}
+
+/// Scan every target, each with its own wordlist and options (see `--targets`)
+async fn scan(
+    targets: Vec<Target>,
+    tx_term: UnboundedSender<FeroxResponse>,
+    tx_file: UnboundedSender<String>,
+) -> FeroxResult<()> {
+    log::trace!("enter: scan({:?}, {:?}, {:?})", targets, tx_term, tx_file);
+    // cloning an Arc is cheap (it's basically a pointer into the heap)
+    // so that will allow for cheap/safe sharing of a single wordlist across multi-target scans
+    // as well as additional directories found as part of recursion; each distinct wordlist is
+    // only read once, no matter how many targets use it
+    let mut wordlists: HashMap<&[String], Arc<Wordlist>> = HashMap::new();
+    let mut tasks = vec![];
+
+    for target in &targets {
+        let wordlist = target.wordlist(&CONFIGURATION);
+
+        if !wordlists.contains_key(wordlist) {
+            let words = get_unique_words(wordlist).await?;
+
+            if words.is_empty() {
+                log::trace!("exit: scan -> no words");
+                return Err(format!("Did not find any words in {}", wordlist.join(", ")).into());
+            }
+
+            // --state-file used, the words requested in each directory are recorded
+            state::add_words(&words);
+
+            wordlists.insert(wordlist, words);
+        }
+
+        let word_clone = wordlists[wordlist].clone();
+        let options = Arc::new(target.scan_options(&CONFIGURATION));
+        let term_clone = tx_term.clone();
+        let file_clone = tx_file.clone();
+        let url = target.url.clone();
+
+        let task = tokio::spawn(async move {
+            let base_depth = get_current_depth(&url);
+            scan_url(
+                &url, word_clone, base_depth, options, term_clone, file_clone,
+            )
+            .await;
+        });
+
+        tasks.push(task);
+    }
+
+    // drive execution of all accumulated futures
+    futures::future::join_all(tasks).await;
+    log::trace!("exit: scan");
+
+    Ok(())
+}
+
+/// Read the previous run's results when `--compare` is used
+fn get_baseline(path: &str) -> FeroxResult<Option<Baseline>> {
+    log::trace!("enter: get_baseline({})", path);
+
+    if path.is_empty() {
+        log::trace!("exit: get_baseline -> None");
+        return Ok(None);
+    }
+
+    let baseline = Baseline::from_file(path)?;
+
+    log::trace!("exit: get_baseline -> {:?}", baseline);
+    Ok(Some(baseline))
+}
+
+/// What running every scan amounted to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Summary {
+    /// number of targets that didn't respond and weren't scanned
+    pub unreachable: usize,
+
+    /// time spent scanning, from after the banner until all requests were complete
+    pub elapsed: Duration,
+}
+
+/// Scan the given targets according to `CONFIGURATION`, from printing the banner through writing
+/// every output once all scans are complete; this is everything the binary does once the targets
+/// are known
+///
+/// Events are emitted along the way to anyone subscribed (see
+/// [events::subscribe](../events/fn.subscribe.html)); their streams end once `run` returns
+pub async fn run(targets: Vec<Target>) -> FeroxResult<Summary> {
+    log::trace!("enter: run({:?})", targets);
+
+    // the scan's id and start time are fixed here, rather than whenever the first json record
+    // happens to be written
+    lazy_static::initialize(&SCAN_TAGS);
+
+    // was -o used? a sarif log is written by the terminal reporter once all results are in,
+    // rather than line by line
+    let save_output = !CONFIGURATION.output.is_empty() && CONFIGURATION.format != "sarif";
+
+    // only spawns a notification handler if a slack/discord webhook was given
+    let (tx_notify, notify_handle) = notifier::initialize(
+        notifier::webhooks_from_config(&CONFIGURATION),
+        CONFIGURATION.notify_batch,
+    );
+
+    // only loads previous results if --compare was used
+    let baseline = get_baseline(&CONFIGURATION.compare).map_err(|e| {
+        format!(
+            "Could not read previous results from {}: {}",
+            CONFIGURATION.compare, e
+        )
+    })?;
+
+    // only reads a saved state if --resume-from was used; the words its directories were requested
+    // with are skipped, and its results are kept
+    if !CONFIGURATION.resume_from.is_empty() {
+        state::resume(&CONFIGURATION.resume_from)
+            .map_err(|e| format!("Could not resume from {}: {}", CONFIGURATION.resume_from, e))?;
+    }
+
+    let urls: Vec<String> = targets.iter().map(|target| target.url.clone()).collect();
+
+    // only writes a header if --append-runs was used; ahead of anything else written to the file
+    if save_output {
+        history::write_header(&urls);
+    }
+
+    let (tx_term, tx_file, term_handle, file_handle) =
+        reporter::initialize(&CONFIGURATION.output, save_output, tx_notify, baseline);
+
+    if !CONFIGURATION.quiet && !tui::is_active() {
+        // only print banner if -q isn't used, the dashboard shows the scan instead
+        let std_stderr = stderr(); // std::io::stderr
+        banner::initialize(&urls, &CONFIGURATION, VERSION, std_stderr).await;
+    }
+
+    // the statistics line and total bar are created ahead of any scans so that they're
+    // displayed above the scans' bars
+    let (tx_stats, stats_handle) = statistics::initialize();
+    PROGRESS_TOTAL.reset_elapsed();
+
+    // only spawns a metrics pusher if --statsd was given
+    let statsd = statsd::initialize();
+
+    // only records the scan's progress if --state-file was given; the checkpoint writer is only
+    // spawned with a --checkpoint-interval
+    let checkpoints = state::initialize(&urls);
+
+    // exits when --screenshot was given but no screenshot can be taken
+    screenshot::initialize();
+
+    // only spawns a canary checker if --canary or --canary-interval was given
+    let canary = canary::initialize();
+
+    // only spawns a schedule checker if --start-at or --pause-outside was given; requests are
+    // paused from here on until the schedule allows them
+    let schedule = schedule::initialize();
+    let start = Instant::now();
+
+    // SIGUSR1 pauses requests and SIGUSR2 resumes them, for control from outside the terminal
+    let signals = signals::initialize(start);
+
+    // --header and the targets' headers are added to every request sent to the targets, from the
+    // connectivity test on
+    hooks::add_request_hook(Arc::new(Headers::new(&CONFIGURATION.headers, &targets)));
+
+    // only adds conditional requests if --response-cache was used
+    cache::initialize();
+
+    // only adds an Origin to requests if --security-headers was used
+    security::initialize();
+
+    // only builds a client per proxy if --proxy-file was given; requests rotate across them from
+    // the connectivity test on
+    rotation::initialize();
+
+    // only logs in if --login-url was given; the session's cookies are sent from the connectivity
+    // test on, and it's logged in again whenever it's logged out
+    let login = login::initialize().await;
+
+    // only requests a token if --oauth-token-url was given; it's sent from the connectivity test
+    // on, and a new one is requested before it expires
+    let oauth = oauth::initialize(&targets).await;
+
+    // only authenticates requests if --auth was given, exits without a Kerberos ticket
+    negotiate::initialize(&targets);
+
+    // only signs requests if --aws-sigv4 was given; added last, as the signature covers what the
+    // other hooks added
+    sigv4::initialize(&targets);
+
+    // only reorders headers if --header-order was given; after every hook that adds headers, a
+    // signature doesn't cover the order they're sent in
+    evasion::initialize();
+
+    // discard non-responsive targets
+    let live_urls = heuristics::connectivity_test(&targets).await;
+    let unreachable = urls.len() - live_urls.len();
+
+    let mut live_targets: Vec<Target> = targets
+        .into_iter()
+        .filter(|target| live_urls.contains(&target.url))
+        .collect();
+
+    if CONFIGURATION.fingerprint || CONFIGURATION.smart {
+        // fingerprint every target ahead of scanning, --smart applies the suggestions
+        let mut fingerprinted = vec![];
+
+        for target in live_targets {
+            fingerprinted.push(fingerprint::fingerprint(target, tx_file.clone()).await);
+        }
+
+        live_targets = fingerprinted;
+    }
+
+    // kick off a scan against any targets determined to be responsive
+    // an error is only returned once everything was torn down and reported
+    let scanned = scan(live_targets, tx_term.clone(), tx_file.clone()).await;
+
+    match &scanned {
+        Ok(_) => {
+            log::info!("All scans complete!");
+        }
+        Err(e) => log::error!("An error occurred: {}", e),
+    };
+
+    // all requests are complete, stop updating the statistics line
+    let elapsed = start.elapsed();
+    tx_stats.send(()).unwrap_or_default();
+
+    match stats_handle.await {
+        Ok(_) => {}
+        Err(e) => {
+            log::error!("error awaiting statistics line's updater: {}", e);
+        }
+    }
+
+    if let Some((tx_statsd, statsd_handle)) = statsd {
+        tx_statsd.send(()).unwrap_or_default();
+
+        if let Err(e) = statsd_handle.await {
+            log::error!("error awaiting metrics pusher: {}", e);
+        }
+    }
+
+    if let Some((tx_checkpoints, checkpoints_handle)) = checkpoints {
+        tx_checkpoints.send(()).unwrap_or_default();
+
+        if let Err(e) = checkpoints_handle.await {
+            log::error!("error awaiting checkpoint writer: {}", e);
+        }
+    }
+
+    if let Some((tx_canary, canary_handle)) = canary {
+        tx_canary.send(()).unwrap_or_default();
+
+        if let Err(e) = canary_handle.await {
+            log::error!("error awaiting canary checker: {}", e);
+        }
+    }
+
+    if let Some((tx_schedule, schedule_handle)) = schedule {
+        tx_schedule.send(()).unwrap_or_default();
+
+        if let Err(e) = schedule_handle.await {
+            log::error!("error awaiting schedule checker: {}", e);
+        }
+    }
+
+    if let Some((tx_login, login_handle)) = login {
+        tx_login.send(()).unwrap_or_default();
+
+        if let Err(e) = login_handle.await {
+            log::error!("error awaiting login task: {}", e);
+        }
+    }
+
+    if let Some((tx_oauth, oauth_handle)) = oauth {
+        tx_oauth.send(()).unwrap_or_default();
+
+        if let Err(e) = oauth_handle.await {
+            log::error!("error awaiting token refresher: {}", e);
+        }
+    }
+
+    if let Some((tx_signals, signals_handle)) = signals {
+        tx_signals.send(()).unwrap_or_default();
+
+        if let Err(e) = signals_handle.await {
+            log::error!("error awaiting signal listener: {}", e);
+        }
+    }
+
+    // manually drop tx in order for the rx task's while loops to eval to false
+    drop(tx_term);
+    log::trace!("dropped terminal output handler's transmitter");
+
+    log::trace!("awaiting terminal output handler's receiver");
+    // after dropping tx, we can await the future where rx lived
+    match term_handle.await {
+        Ok(_) => {}
+        Err(e) => {
+            log::error!("error awaiting terminal output handler's receiver: {}", e);
+        }
+    }
+    log::trace!("done awaiting terminal output handler's receiver");
+
+    log::trace!("tx_file: {:?}", tx_file);
+    // the same drop/await process used on the terminal handler is repeated for the file handler
+    // we drop the file transmitter every time, because it's created no matter what
+    drop(tx_file);
+
+    log::trace!("dropped file output handler's transmitter");
+    if save_output {
+        // but we only await if -o was specified
+        log::trace!("awaiting file output handler's receiver");
+        match file_handle.unwrap().await {
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("error awaiting file output handler's receiver: {}", e);
+            }
+        }
+        log::trace!("done awaiting file output handler's receiver");
+    }
+
+    if let Some(handle) = notify_handle {
+        // the terminal handler owned the only notification transmitter, which was dropped when
+        // the handler finished; awaiting here allows leftovers and the summary to be sent
+        log::trace!("awaiting notification handler's receiver");
+        match handle.await {
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("error awaiting notification handler's receiver: {}", e);
+            }
+        }
+        log::trace!("done awaiting notification handler's receiver");
+    }
+
+    // every finding was reported, their screenshots may still be being taken
+    screenshot::finish().await;
+
+    // every finding was reported, including their validators
+    cache::save();
+
+    // every finding was reported, the scan's final progress is saved
+    state::save();
+
+    // every finding was reported and counted
+    summary::report(elapsed);
+
+    if !CONFIGURATION.stats_output.is_empty() {
+        // written last so that, when written to stdout, the statistics follow all other output
+        if let Err(e) = statistics::write_statistics(&CONFIGURATION.stats_output, elapsed) {
+            log::error!(
+                "Could not write statistics to {}: {}",
+                CONFIGURATION.stats_output,
+                e
+            );
+        }
+    }
+
+    // all results were reported, end every subscriber's stream of events
+    events::close();
+
+    if let Err(e) = scanned {
+        log::trace!("exit: run -> {}", e);
+        return Err(e);
+    }
+
+    let summary = Summary {
+        unreachable,
+        elapsed,
+    };
+
+    log::trace!("exit: run -> {:?}", summary);
+    Ok(summary)
+}
+
+/// Builds a [Scanner](struct.Scanner.html); anything not given keeps its default, the same one
+/// the command line has
+#[derive(Debug, Default)]
+pub struct ScannerBuilder {
+    /// configuration being built
+    config: ScanConfig,
+
+    /// targets to scan
+    targets: Vec<Target>,
+
+    /// whether or not a wordlist was given, replacing the default one
+    wordlist_given: bool,
+}
+
+impl ScannerBuilder {
+    /// Start from the given configuration instead of the defaults
+    pub fn config(mut self, config: ScanConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Add a url to scan
+    pub fn url(mut self, url: &str) -> Self {
+        self.targets.push(Target::from_url(url));
+        self
+    }
+
+    /// Add a target to scan, along with the options it overrides (see `--targets`)
+    pub fn target(mut self, target: Target) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// Add a wordlist (path or url) to scan with; the first one replaces the default wordlist
+    pub fn wordlist(mut self, wordlist: &str) -> Self {
+        if !self.wordlist_given {
+            self.config.wordlist.clear();
+            self.wordlist_given = true;
+        }
+
+        self.config.wordlist.push(wordlist.to_string());
+        self
+    }
+
+    /// Extensions to append to each word
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.config.extensions = extensions.iter().map(|ext| ext.to_string()).collect();
+        self
+    }
+
+    /// Status codes of the responses to report
+    pub fn status_codes(mut self, status_codes: &[u16]) -> Self {
+        self.config.statuscodes = status_codes.to_vec();
+        self
+    }
+
+    /// Header to send with every request
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.config
+            .headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Proxy through which to send every request
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.config.proxy = proxy.to_string();
+        self
+    }
+
+    /// Number of concurrent threads
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads;
+        self
+    }
+
+    /// Maximum recursion depth, 0 for infinite
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.config.depth = depth;
+        self
+    }
+
+    /// Number of seconds before a request times out
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Maximum number of requests per second, 0 for no limit
+    pub fn rate_limit(mut self, rate_limit: usize) -> Self {
+        self.config.rate_limit = rate_limit;
+        self
+    }
+
+    /// Only print found urls, without the banner and other messages
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.config.quiet = quiet;
+        self
+    }
+
+    /// Check what was given and put the configuration together, with a client to match
+    fn finish(self) -> FeroxResult<(ScanConfig, Vec<Target>)> {
+        let mut config = self.config;
+
+        if self.targets.is_empty() {
+            return Err("no targets were given to scan".into());
+        }
+
+        if config.wordlist.is_empty() {
+            return Err("no wordlist was given to scan with".into());
+        }
+
+        config.derive_defaults();
+        config.validate()?;
+
+        let resolved = proxy::resolve(&config.proxy, &config.proxy_auth, config.proxy_ntlm);
+
+        config.client = client::initialize(
+            config.timeout,
+            &config.useragent,
+            config.redirects,
+            config.insecure,
+            Some(&resolved),
+        );
+
+        Ok((config, self.targets))
+    }
+
+    /// Build the scanner; fails when no target or wordlist was given, when a value isn't one of
+    /// its choices (see `Configuration::validate`), or when the configuration is already in use (i.e. a scanner was already built in this process)
+    pub fn build(self) -> FeroxResult<Scanner> {
+        log::trace!("enter: build({:?})", self);
+
+        let (config, targets) = self.finish()?;
+        config::preset(config)?;
+
+        let scanner = Scanner { targets };
+
+        log::trace!("exit: build -> {:?}", scanner);
+        Ok(scanner)
+    }
+}
+
+/// Scans its targets the same way the binary does, handing back the results
+#[derive(Debug)]
+pub struct Scanner {
+    /// targets to scan
+    targets: Vec<Target>,
+}
+
+impl Scanner {
+    /// Start building a scanner
+    pub fn builder() -> ScannerBuilder {
+        ScannerBuilder::default()
+    }
+
+    /// Scan every target, returning every reported response once all scans are complete; output
+    /// files, notifications, and the like are written along the way, as configured
+    ///
+    /// To follow the scan as it happens, subscribe to its events with `events::subscribe` and
+    /// consume them while awaiting `scan` (i.e. from a spawned task)
+    pub async fn scan(self) -> FeroxResult<Vec<FeroxResponse>> {
+        log::trace!("enter: scan({:?})", self);
+
+        let mut events = events::subscribe();
+
+        run(self.targets).await?;
+
+        // the bus was closed once all scans were complete, which ends the stream
+        let mut results = vec![];
+
+        while let Some(event) = events.recv().await {
+            if let Event::ResultReported(response) = event {
+                results.push(response);
+            }
+        }
+
+        log::trace!("exit: scan -> [{} results...]", results.len());
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the first wordlist given replaces the default, later ones are added to it
+    fn engine_builder_replaces_default_wordlist() {
+        let (config, targets) = Scanner::builder()
+            .url("http://localhost")
+            .wordlist("/one.txt")
+            .wordlist("/two.txt")
+            .extensions(&["php"])
+            .threads(3)
+            .header("X-Test", "yes")
+            .finish()
+            .unwrap();
+
+        assert_eq!(config.wordlist, ["/one.txt", "/two.txt"]);
+        assert_eq!(config.extensions, ["php"]);
+        assert_eq!(config.threads, 3);
+        assert_eq!(config.headers["X-Test"], "yes");
+        assert_eq!(targets, [Target::from_url("http://localhost")]);
+    }
+
+    #[test]
+    /// anything not given keeps its default, but there has to be something to scan
+    fn engine_builder_requires_targets() {
+        assert!(Scanner::builder().finish().is_err());
+
+        let (config, _) = Scanner::builder().url("http://localhost").finish().unwrap();
+
+        assert_eq!(config.wordlist, Configuration::default().wordlist);
+        assert_eq!(config.threads, Configuration::default().threads);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::config::CONFIGURATION;
+use crate::encryption::OutputFile;
+use crate::metadata::tag;
+use crate::reporter::{open_file, safe_file_write};
+use crate::utils::rfc3339_timestamp;
+use lazy_static::lazy_static;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::io::BufWriter;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+lazy_static! {
+    /// File given to `--error-log`, opened on first use; `None` when not in use
+    static ref ERROR_LOG: Option<Arc<RwLock<BufWriter<OutputFile>>>> =
+        if CONFIGURATION.error_log.is_empty() {
+            None
+        } else {
+            open_file(&CONFIGURATION.error_log)
+        };
+}
+
+/// Broad category of a failed request
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ErrorClass {
+    /// the request timed out
+    Timeout,
+
+    /// the connection couldn't be established
+    Connection,
+
+    /// too many redirects, or a redirect loop
+    Redirection,
+
+    /// anything else
+    Other,
+}
+
+impl ErrorClass {
+    /// Categorize the given error; anything that isn't a `reqwest::Error` is `Other`
+    pub fn from_error(error: &(dyn Error + 'static)) -> Self {
+        match error.downcast_ref::<reqwest::Error>() {
+            Some(error) if error.is_timeout() => ErrorClass::Timeout,
+            Some(error) if error.is_connect() => ErrorClass::Connection,
+            Some(error) if error.is_redirect() => ErrorClass::Redirection,
+            _ => ErrorClass::Other,
+        }
+    }
+
+    /// Name used for the error's class in the error log
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Connection => "connection",
+            ErrorClass::Redirection => "redirection",
+            ErrorClass::Other => "other",
+        }
+    }
+}
+
+/// Build the record written to the error log for a failed request
+///
+/// `word` is the wordlist entry that produced the url, if any (i.e. links found by
+/// `--extract-links` don't have one); requests aren't retried, so `attempt` is always 1
+fn error_record(url: &Url, word: Option<&str>, error: &(dyn Error + 'static)) -> Value {
+    json!({
+        "type": "error",
+        "time": rfc3339_timestamp(SystemTime::now()),
+        "url": url.as_str(),
+        "word": word,
+        "attempt": 1,
+        "class": ErrorClass::from_error(error).as_str(),
+        "message": error.to_string(),
+    })
+}
+
+/// Build the record written to the error log for a filtered response
+fn filtered_record(url: &Url, word: Option<&str>, size: u64, reason: &str) -> Value {
+    json!({
+        "type": "filtered",
+        "time": rfc3339_timestamp(SystemTime::now()),
+        "url": url.as_str(),
+        "word": word,
+        "size": size,
+        "reason": reason,
+    })
+}
+
+/// Write a single record to the error log, if one is in use
+fn write_record(mut record: Value) {
+    if let Some(file) = ERROR_LOG.as_ref() {
+        tag(&mut record);
+        safe_file_write(&format!("{}\n", record), file.clone());
+    }
+}
+
+/// Write a failed request to `--error-log`, if used
+pub fn log_request_error(url: &Url, word: Option<&str>, error: &(dyn Error + 'static)) {
+    if CONFIGURATION.error_log.is_empty() {
+        return;
+    }
+
+    write_record(error_record(url, word, error));
+}
+
+/// Write a filtered response to `--error-log`, if used along with `--log-filtered`
+pub fn log_filtered(url: &Url, word: Option<&str>, size: u64, reason: &str) {
+    if CONFIGURATION.error_log.is_empty() || !CONFIGURATION.log_filtered {
+        return;
+    }
+
+    write_record(filtered_record(url, word, size, reason));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    /// errors that aren't from reqwest are categorized as other
+    fn error_log_error_class_defaults_to_other() {
+        let error = io::Error::other("oops");
+        assert_eq!(ErrorClass::from_error(&error), ErrorClass::Other);
+        assert_eq!(ErrorClass::Other.as_str(), "other");
+    }
+
+    #[tokio::test(core_threads = 1)]
+    /// error records contain the url, word, attempt, and class of the failed request
+    async fn error_log_error_record_includes_context() {
+        // nothing should be listening on port 1
+        let url = Url::parse("http://127.0.0.1:1/admin").unwrap();
+        let error = reqwest::get(url.clone()).await.unwrap_err();
+
+        let record = error_record(&url, Some("admin"), &error);
+
+        assert_eq!(record["type"], "error");
+        assert_eq!(record["url"], "http://127.0.0.1:1/admin");
+        assert_eq!(record["word"], "admin");
+        assert_eq!(record["attempt"], 1);
+        assert_eq!(record["class"], "connection");
+    }
+
+    #[test]
+    /// filtered records contain the url, size, and reason; word is null when not known
+    fn error_log_filtered_record_includes_reason() {
+        let url = Url::parse("http://localhost/js").unwrap();
+        let record = filtered_record(&url, None, 42, "size");
+
+        assert_eq!(record["type"], "filtered");
+        assert_eq!(record["word"], Value::Null);
+        assert_eq!(record["size"], 42);
+        assert_eq!(record["reason"], "size");
+    }
+}
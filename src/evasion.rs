@@ -0,0 +1,227 @@
+use crate::config::CONFIGURATION;
+use crate::hooks::{self, RequestHook};
+use crate::utils::{module_colorizer, status_colorizer};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, HOST, USER_AGENT};
+use reqwest::{Request, Url};
+use std::process;
+use std::sync::Arc;
+
+/// Order in which a request's headers are sent (see `--header-order`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Order {
+    /// a new random order for every request
+    Random,
+
+    /// the given headers first, in the given order, followed by the others
+    Custom(Vec<HeaderName>),
+}
+
+impl Order {
+    /// Parse `random` or header names separated by commas
+    pub fn parse(order: &str) -> Result<Self, String> {
+        if order.trim().eq_ignore_ascii_case("random") {
+            return Ok(Order::Random);
+        }
+
+        let names = order
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|_| format!("{} isn't a header name", name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if names.is_empty() {
+            return Err(String::from("no header was named"));
+        }
+
+        Ok(Order::Custom(names))
+    }
+
+    /// Positions of the given names in the order they're sent, given the random number to pick
+    /// each next name with for `Random`
+    fn arrange(&self, names: &[HeaderName], mut random: impl FnMut() -> usize) -> Vec<usize> {
+        let mut positions: Vec<usize> = (0..names.len()).collect();
+
+        match self {
+            Order::Random => {
+                // fisher-yates
+                for i in (1..positions.len()).rev() {
+                    positions.swap(i, random() % (i + 1));
+                }
+            }
+            Order::Custom(order) => {
+                // sort is stable, headers that aren't named keep their order after the named ones
+                positions.sort_by_key(|position| {
+                    order
+                        .iter()
+                        .position(|name| *name == names[*position])
+                        .unwrap_or(order.len())
+                });
+            }
+        }
+
+        positions
+    }
+}
+
+/// Value of the `Host` header of a request to the given url, whose port is only given when it
+/// isn't the scheme's
+pub fn host(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+
+    match url.port() {
+        Some(port) => Some(format!("{}:{}", host, port)),
+        None => Some(host.to_string()),
+    }
+}
+
+/// A random number, to shuffle with
+fn random() -> usize {
+    let mut bytes = [0; 8];
+
+    if let Err(e) = openssl::rand::rand_bytes(&mut bytes) {
+        log::error!("{}", e);
+    }
+
+    u64::from_le_bytes(bytes) as usize
+}
+
+/// Sends every request's headers in the given order
+///
+/// The headers reqwest and hyper would add on their own (`User-Agent`, `Accept` and `Host`) are
+/// added here instead, as they'd otherwise always come last
+struct HeaderOrder {
+    /// order the headers are sent in
+    order: Order,
+
+    /// value of `--user-agent`
+    useragent: HeaderValue,
+}
+
+impl RequestHook for HeaderOrder {
+    fn before_request(&self, request: &mut Request) {
+        let host = host(request.url()).and_then(|host| HeaderValue::from_str(&host).ok());
+        let headers = request.headers_mut();
+
+        if !headers.contains_key(USER_AGENT) {
+            headers.insert(USER_AGENT, self.useragent.clone());
+        }
+
+        if !headers.contains_key(ACCEPT) {
+            headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
+        }
+
+        if let Some(host) = host {
+            if !headers.contains_key(HOST) {
+                headers.insert(HOST, host);
+            }
+        }
+
+        let names: Vec<HeaderName> = headers.keys().cloned().collect();
+        let mut ordered = HeaderMap::with_capacity(headers.len());
+
+        for position in self.order.arrange(&names, random) {
+            for value in headers.get_all(&names[position]) {
+                ordered.append(names[position].clone(), value.clone());
+            }
+        }
+
+        *headers = ordered;
+    }
+}
+
+/// Send the requests' headers in the order given by `--header-order`, when given; exits when it
+/// can't be parsed
+///
+/// The hook is added last, so that it orders the headers added by every other hook
+pub fn initialize() {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.header_order.is_empty() {
+        log::trace!("exit: initialize");
+        return;
+    }
+
+    let order = Order::parse(&CONFIGURATION.header_order).unwrap_or_else(|e| {
+        eprintln!(
+            "{} {} Could not parse --header-order {}: {}",
+            status_colorizer("ERROR"),
+            module_colorizer("evasion::initialize"),
+            CONFIGURATION.header_order,
+            e
+        );
+        process::exit(1);
+    });
+
+    let useragent = HeaderValue::from_str(&CONFIGURATION.useragent)
+        .unwrap_or_else(|_| HeaderValue::from_static("feroxbuster"));
+
+    hooks::add_request_hook(Arc::new(HeaderOrder { order, useragent }));
+
+    log::trace!("exit: initialize");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// header names of the given strings
+    fn names(names: &[&str]) -> Vec<HeaderName> {
+        names
+            .iter()
+            .map(|name| HeaderName::from_bytes(name.as_bytes()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    /// named headers come first in the given order, the others keep theirs
+    fn evasion_custom_order_puts_named_headers_first() {
+        let order = Order::parse("Host, accept,user-agent").unwrap();
+        assert_eq!(
+            order,
+            Order::Custom(names(&["host", "accept", "user-agent"]))
+        );
+
+        let sent = names(&["user-agent", "cookie", "accept", "x-api-key", "host"]);
+        let positions = order.arrange(&sent, || 0);
+        let arranged: Vec<&str> = positions.iter().map(|i| sent[*i].as_str()).collect();
+
+        assert_eq!(
+            arranged,
+            vec!["host", "accept", "user-agent", "cookie", "x-api-key"]
+        );
+    }
+
+    #[test]
+    /// random orders are permutations, and bad names are refused
+    fn evasion_random_order_is_a_permutation() {
+        let order = Order::parse("RANDOM").unwrap();
+        let sent = names(&["a", "b", "c", "d"]);
+
+        let mut positions = order.arrange(&sent, random);
+        positions.sort_unstable();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+
+        // always picking the first position rotates the headers
+        assert_eq!(order.arrange(&sent, || 0), vec![1, 2, 3, 0]);
+
+        assert!(Order::parse("host,bad header").is_err());
+        assert!(Order::parse(" , ").is_err());
+    }
+
+    #[test]
+    /// the port is only part of the host when it isn't the scheme's
+    fn evasion_host_skips_default_port() {
+        assert_eq!(
+            host(&Url::parse("https://example.com:443/a").unwrap()),
+            Some(String::from("example.com"))
+        );
+        assert_eq!(
+            host(&Url::parse("http://example.com:8080/a").unwrap()),
+            Some(String::from("example.com:8080"))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, Url};
+use std::sync::RwLock;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+lazy_static! {
+    /// Transmitters of everyone subscribed to the scan's events
+    static ref SUBSCRIBERS: RwLock<Vec<UnboundedSender<Event>>> = RwLock::new(Vec::new());
+}
+
+/// Something that happened during a scan
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// a scan of the given url, either a target or a directory found by recursion, started
+    ScanStarted {
+        /// url being scanned
+        url: String,
+
+        /// number of requests the scan is expected to make
+        requests: u64,
+    },
+
+    /// a response was received, before any filtering
+    ResponseReceived {
+        /// url requested
+        url: Url,
+
+        /// status code of the response
+        status: StatusCode,
+
+        /// size of the response
+        content_length: u64,
+    },
+
+    /// a response made it through filtering and was reported
+    ResultReported(FeroxResponse),
+
+    /// all of the requests of the scan of the given url were made; scans of directories found
+    /// under it may still be running
+    ScanFinished {
+        /// url that was scanned
+        url: String,
+    },
+
+    /// a request failed
+    Error {
+        /// url requested
+        url: Url,
+
+        /// description of the error
+        error: String,
+    },
+}
+
+/// Subscribe to the scan's events; every event emitted from here on is sent to the returned
+/// receiver, which is also a `Stream`, until all scans are complete (see `close`)
+pub fn subscribe() -> UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    match SUBSCRIBERS.write() {
+        Ok(mut subscribers) => subscribers.push(tx),
+        Err(e) => log::error!("{}", e),
+    }
+
+    rx
+}
+
+/// Send the event to every subscriber; subscribers that went away are forgotten
+pub fn emit(event: Event) {
+    let has_subscribers = SUBSCRIBERS
+        .read()
+        .map(|subscribers| !subscribers.is_empty())
+        .unwrap_or_default();
+
+    if !has_subscribers {
+        // nobody's listening, which is the usual case for the binary
+        return;
+    }
+
+    if let Ok(mut subscribers) = SUBSCRIBERS.write() {
+        subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+/// Forget every subscriber, which ends their streams; called once all scans are complete
+pub fn close() {
+    match SUBSCRIBERS.write() {
+        Ok(mut subscribers) => subscribers.clear(),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(core_threads = 1)]
+    /// every subscriber gets every event until the bus is closed, which ends their streams
+    async fn events_reach_every_subscriber_until_closed() {
+        let mut first = subscribe();
+        let mut second = subscribe();
+
+        emit(Event::ScanStarted {
+            url: String::from("http://localhost"),
+            requests: 2,
+        });
+        close();
+
+        for rx in [&mut first, &mut second].iter_mut() {
+            match rx.recv().await {
+                Some(Event::ScanStarted { url, .. }) => assert_eq!(url, "http://localhost"),
+                other => panic!("unexpected event {:?}", other),
+            }
+
+            assert!(rx.recv().await.is_none());
+        }
+    }
+}
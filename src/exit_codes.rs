@@ -0,0 +1,112 @@
+/// The scan completed and none of the conditions selected with `--exit-on` were met
+pub const SUCCESS: i32 = 0;
+
+/// A fatal error occurred, i.e. an unreadable wordlist or invalid configuration
+pub const ERROR: i32 = 1;
+
+/// The scan completed without reporting any results (`--exit-on no-findings`)
+pub const NO_FINDINGS: i32 = 2;
+
+/// At least one target couldn't be reached (`--exit-on unreachable`)
+pub const UNREACHABLE: i32 = 3;
+
+/// The scan was cancelled with Ctrl+C (`--exit-on cancelled`)
+pub const CANCELLED: i32 = 4;
+
+/// More requests failed than allowed by `--error-threshold` (`--exit-on errors`)
+pub const ERROR_THRESHOLD_EXCEEDED: i32 = 5;
+
+/// Requests looked blocked and `--ban-policy stop` ended the scan
+pub const BANNED: i32 = 6;
+
+/// The scan was cancelled with Ctrl+C without `--exit-on cancelled`, once the `--email-report` was
+/// sent; the same code a shell reports for a process ended by SIGINT
+pub const INTERRUPTED: i32 = 130;
+
+/// Conditions that may be given to `--exit-on`
+pub const CONDITIONS: [&str; 4] = ["no-findings", "unreachable", "cancelled", "errors"];
+
+/// Whether or not the given condition was selected with `--exit-on`
+pub fn exit_on(selected: &[String], condition: &str) -> bool {
+    selected.iter().any(|selection| selection == condition)
+}
+
+/// Determine the exit code of a completed scan
+///
+/// When more than one selected condition is met, the most severe wins: exceeding the error
+/// threshold, then unreachable targets, then a lack of findings
+pub fn determine(
+    selected: &[String],
+    error_threshold: usize,
+    errors: usize,
+    unreachable: usize,
+    findings: usize,
+) -> i32 {
+    log::trace!(
+        "enter: determine({:?}, {}, {}, {}, {})",
+        selected,
+        error_threshold,
+        errors,
+        unreachable,
+        findings
+    );
+
+    let code = if exit_on(selected, "errors") && errors > error_threshold {
+        ERROR_THRESHOLD_EXCEEDED
+    } else if exit_on(selected, "unreachable") && unreachable > 0 {
+        UNREACHABLE
+    } else if exit_on(selected, "no-findings") && findings == 0 {
+        NO_FINDINGS
+    } else {
+        SUCCESS
+    };
+
+    log::trace!("exit: determine -> {}", code);
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to build a list of selected conditions
+    fn selected(conditions: &[&str]) -> Vec<String> {
+        conditions.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    /// without any selected conditions, a completed scan is always a success
+    fn exit_codes_determine_without_conditions_succeeds() {
+        assert_eq!(determine(&[], 0, 100, 2, 0), SUCCESS);
+    }
+
+    #[test]
+    /// each selected condition maps to its own code, only when it's met
+    fn exit_codes_determine_maps_conditions_to_codes() {
+        assert_eq!(
+            determine(&selected(&["no-findings"]), 0, 0, 0, 0),
+            NO_FINDINGS
+        );
+        assert_eq!(determine(&selected(&["no-findings"]), 0, 0, 0, 1), SUCCESS);
+        assert_eq!(
+            determine(&selected(&["unreachable"]), 0, 0, 1, 1),
+            UNREACHABLE
+        );
+        assert_eq!(determine(&selected(&["unreachable"]), 0, 0, 0, 1), SUCCESS);
+        assert_eq!(
+            determine(&selected(&["errors"]), 0, 1, 0, 1),
+            ERROR_THRESHOLD_EXCEEDED
+        );
+        assert_eq!(determine(&selected(&["errors"]), 5, 5, 0, 1), SUCCESS);
+    }
+
+    #[test]
+    /// when multiple conditions are met, the most severe one determines the code
+    fn exit_codes_determine_prefers_most_severe() {
+        let all = selected(&CONDITIONS);
+
+        assert_eq!(determine(&all, 0, 1, 1, 0), ERROR_THRESHOLD_EXCEEDED);
+        assert_eq!(determine(&all, 0, 0, 1, 0), UNREACHABLE);
+        assert_eq!(determine(&all, 0, 0, 0, 0), NO_FINDINGS);
+    }
+}
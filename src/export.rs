@@ -0,0 +1,150 @@
+use crate::xml::parent_directory;
+use reqwest::Url;
+use std::collections::HashSet;
+
+/// Formats of target list that can be exported
+pub const EXPORT_FORMATS: [&str; 3] = ["plain", "httpx", "nuclei"];
+
+/// Reported urls, rendered as a list of targets for other tools once the scan completes; one url
+/// per line, each of them only once, in the order they were reported
+///
+/// - **plain**: every reported url, as reported
+/// - **httpx**: every reported url, where a redirect to the same url with a trailing slash (i.e.
+///   `/admin` to `/admin/`) is only listed once, with its slash
+/// - **nuclei**: every directory in which a result was found, along with the directories found
+///   themselves, without a trailing slash since templates add their own (`{{BaseURL}}/.git/config`)
+#[derive(Debug, Default)]
+pub struct Export {
+    /// reported urls, in the order they were reported
+    urls: Vec<Url>,
+}
+
+impl Export {
+    /// Add a reported url
+    pub fn insert(&mut self, url: &Url) {
+        self.urls.push(url.clone());
+    }
+
+    /// Render the list in the given format, anything other than `httpx` or `nuclei` is rendered
+    /// as `plain`
+    pub fn render(&self, format: &str) -> String {
+        let urls: Vec<String> = match format {
+            "httpx" => self.render_httpx(),
+            "nuclei" => self.render_nuclei(),
+            _ => self.urls.iter().map(Url::to_string).collect(),
+        };
+
+        let mut seen = HashSet::new();
+        let mut rendered = String::new();
+
+        for url in urls {
+            if seen.insert(url.clone()) {
+                rendered.push_str(&url);
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+
+    /// Urls in the `httpx` format
+    fn render_httpx(&self) -> Vec<String> {
+        let reported: HashSet<&str> = self.urls.iter().map(Url::as_str).collect();
+
+        self.urls
+            .iter()
+            .map(|url| {
+                let with_slash = format!("{}/", url);
+
+                if url.query().is_none() && reported.contains(with_slash.as_str()) {
+                    with_slash
+                } else {
+                    url.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Urls in the `nuclei` format
+    fn render_nuclei(&self) -> Vec<String> {
+        let mut directories = Vec::new();
+
+        for url in &self.urls {
+            directories.push(parent_directory(url));
+
+            if url.path().ends_with('/') && url.query().is_none() {
+                directories.push(url.to_string());
+            }
+        }
+
+        directories
+            .iter()
+            .map(|directory| directory.trim_end_matches('/').to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to build an export of the given urls
+    fn setup_export(urls: &[&str]) -> Export {
+        let mut export = Export::default();
+
+        for url in urls {
+            export.insert(&Url::parse(url).unwrap());
+        }
+
+        export
+    }
+
+    #[test]
+    /// plain lists every url once, in the order reported
+    fn export_render_plain_format() {
+        let export = setup_export(&[
+            "http://localhost/admin",
+            "http://localhost/admin/",
+            "http://localhost/index.php?id=1",
+            "http://localhost/admin",
+        ]);
+
+        assert_eq!(
+            export.render("plain"),
+            "http://localhost/admin\nhttp://localhost/admin/\nhttp://localhost/index.php?id=1\n"
+        );
+        assert!(Export::default().render("plain").is_empty());
+    }
+
+    #[test]
+    /// httpx lists a redirect to the same url with a slash once
+    fn export_render_httpx_format() {
+        let export = setup_export(&[
+            "http://localhost/admin",
+            "http://localhost/admin/",
+            "http://localhost/login",
+            "https://localhost:8443/js/app.js",
+        ]);
+
+        assert_eq!(
+            export.render("httpx"),
+            "http://localhost/admin/\nhttp://localhost/login\nhttps://localhost:8443/js/app.js\n"
+        );
+    }
+
+    #[test]
+    /// nuclei lists the directories results were found in, without their trailing slash
+    fn export_render_nuclei_format() {
+        let export = setup_export(&[
+            "http://localhost/admin",
+            "http://localhost/admin/",
+            "http://localhost/admin/login.php",
+            "https://localhost:8443/js/app.js?v=2",
+        ]);
+
+        assert_eq!(
+            export.render("nuclei"),
+            "http://localhost\nhttp://localhost/admin\nhttps://localhost:8443/js\n"
+        );
+    }
+}
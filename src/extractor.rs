@@ -1,3 +1,4 @@
+use crate::buffers;
 use crate::FeroxResponse;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -59,10 +60,10 @@ fn add_link_to_set_of_links(link: &str, url: &Url, links: &mut HashSet<String>)
     log::trace!(
         "enter: add_link_to_set_of_links({}, {}, {:?})",
         link,
-        url.to_string(),
+        url,
         links
     );
-    match url.join(&link) {
+    match url.join(link) {
         Ok(new_url) => {
             links.insert(new_url.to_string());
         }
@@ -83,23 +84,51 @@ fn add_link_to_set_of_links(link: &str, url: &Url, links: &mut HashSet<String>)
 ///         - homepage/assets/img/
 ///         - homepage/assets/
 ///         - homepage/
+///
+/// Matching a large body (i.e. a javascript bundle) takes long enough to stall the other requests
+/// sharing its worker thread, so the matching happens on tokio's blocking thread pool; the links
+/// come back through the blocking task's handle
 pub async fn get_links(response: &FeroxResponse) -> HashSet<String> {
     log::trace!("enter: get_links({})", response.url().as_str());
 
-    let mut links = HashSet::<String>::new();
+    // the body is copied into a pooled buffer, to be moved onto the blocking thread
+    let mut body = buffers::take_string();
+    body.push_str(response.text());
+    let url = response.url().clone();
+
+    let links = tokio::task::spawn_blocking(move || {
+        let links = find_links(&body, &url);
+        buffers::give(body.into_bytes());
+        links
+    });
+
+    let links = match links.await {
+        Ok(links) => links,
+        Err(e) => {
+            log::error!("Could not extract links from {}: {}", response.url(), e);
+            HashSet::new()
+        }
+    };
 
-    let body = response.text();
+    log::trace!("exit: get_links -> {:?}", links);
+    links
+}
 
-    for capture in REGEX.captures_iter(&body) {
+/// Find the links in the given body of the response to the given url, along with their sub-paths
+/// (see [get_links](fn.get_links.html)); blocks while the body is matched
+fn find_links(body: &str, url: &Url) -> HashSet<String> {
+    log::trace!("enter: find_links([{} bytes...], {})", body.len(), url);
+
+    let mut links = HashSet::<String>::new();
+
+    for capture in REGEX.captures_iter(body) {
         // remove single & double quotes from both ends of the capture
         // capture[0] is the entire match, additional capture groups start at [1]
         let link = capture[0].trim_matches(|c| c == '\'' || c == '"');
 
         match Url::parse(link) {
             Ok(absolute) => {
-                if absolute.domain() != response.url().domain()
-                    || absolute.host() != response.url().host()
-                {
+                if absolute.domain() != url.domain() || absolute.host() != url.host() {
                     // domains/ips are not the same, don't scan things that aren't part of the original
                     // target url
                     continue;
@@ -113,7 +142,7 @@ pub async fn get_links(response: &FeroxResponse) -> HashSet<String> {
                     //     - homepage/assets/
                     //     - homepage/
                     log::debug!("Adding {} to {:?}", sub_path, links);
-                    add_link_to_set_of_links(&sub_path, &response.url(), &mut links);
+                    add_link_to_set_of_links(&sub_path, url, &mut links);
                 }
             }
             Err(e) => {
@@ -124,7 +153,7 @@ pub async fn get_links(response: &FeroxResponse) -> HashSet<String> {
                     for sub_path in get_sub_paths_from_path(link) {
                         // incrementally save all sub-paths that led to the relative url's resource
                         log::debug!("Adding {} to {:?}", sub_path, links);
-                        add_link_to_set_of_links(&sub_path, &response.url(), &mut links);
+                        add_link_to_set_of_links(&sub_path, url, &mut links);
                     }
                 } else {
                     // unexpected error has occurred
@@ -134,11 +163,12 @@ pub async fn get_links(response: &FeroxResponse) -> HashSet<String> {
         }
     }
 
-    log::trace!("exit: get_links -> {:?}", links);
+    log::trace!("exit: find_links -> {:?}", links);
     links
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::needless_borrow)]
 mod tests {
     use super::*;
     use crate::utils::make_request;
@@ -237,6 +267,31 @@ mod tests {
         assert!(links.is_empty());
     }
 
+    #[test]
+    /// links on the same host are found along with their sub-paths, links elsewhere are skipped
+    fn extractor_find_links_keeps_same_host_links() {
+        let url = Url::parse("http://example.com/").unwrap();
+        let body = r#"<script src="/static/js/main.js"></script>
+            <a href="http://elsewhere.com/login/form.php">elsewhere</a>
+            <a href="http://example.com/admin/users.php">users</a>"#;
+
+        let links = find_links(body, &url);
+
+        for expected in [
+            "http://example.com/static",
+            "http://example.com/static/js",
+            "http://example.com/static/js/main.js",
+            "http://example.com/admin",
+            "http://example.com/admin/users.php",
+        ]
+        .iter()
+        {
+            assert!(links.contains(*expected), "missing {}", expected);
+        }
+
+        assert!(!links.iter().any(|link| link.contains("login")));
+    }
+
     #[tokio::test(core_threads = 1)]
     /// use make_request to generate a Response, and use the Response to test get_links;
     /// the response will contain an absolute path to a domain that is not part of the scanned
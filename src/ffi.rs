@@ -0,0 +1,261 @@
+//! C-compatible functions to run a scan from non-Rust tools; only compiled with the `ffi` feature
+//!
+//! A scan is started with `ferox_start`, given its configuration as a json object with the same
+//! keys as `ferox-config.toml` plus `target_url`.  Results are then taken one at a time with
+//! `ferox_poll`, as json strings like the ones written with `--json`, until `ferox_status` says
+//! the scan is no longer running and `ferox_poll` returns `NULL`
+//!
+//! ```c
+//! if (ferox_start("{\"target_url\": \"http://localhost\", \"quiet\": true}") != 0) {
+//!     return 1;
+//! }
+//!
+//! while (ferox_status() == FEROX_RUNNING) {
+//!     char *result;
+//!
+//!     while ((result = ferox_poll()) != NULL) {
+//!         puts(result);
+//!         ferox_free_string(result);
+//!     }
+//!
+//!     sleep(1);
+//! }
+//! ```
+//!
+//! The configuration is global to the process, so only one scan can be started per process
+use crate::config::CONFIGURATION;
+use crate::engine::{ScanConfig, Scanner};
+use crate::events::{self, Event};
+use crate::logger;
+use crate::metadata::tag;
+use crate::FeroxResult;
+use lazy_static::lazy_static;
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// no scan was started
+pub const FEROX_IDLE: c_int = 0;
+
+/// the scan is running
+pub const FEROX_RUNNING: c_int = 1;
+
+/// the scan is complete
+pub const FEROX_FINISHED: c_int = 2;
+
+/// the scan was stopped with `ferox_stop`
+pub const FEROX_STOPPED: c_int = 3;
+
+/// the scan couldn't be started or failed
+pub const FEROX_FAILED: c_int = -1;
+
+lazy_static! {
+    /// Results not yet taken with `ferox_poll`, as json strings, oldest first
+    static ref RESULTS: Mutex<VecDeque<CString>> = Mutex::new(VecDeque::new());
+
+    /// Transmitter used by `ferox_stop` to stop the running scan
+    static ref STOP: Mutex<Option<oneshot::Sender<()>>> = Mutex::new(None);
+}
+
+/// Current state of the scan, one of the `FEROX_*` values
+static STATUS: AtomicI32 = AtomicI32::new(FEROX_IDLE);
+
+/// Build the scanner described by the given json configuration
+fn scanner_from_json(json: &str) -> FeroxResult<Scanner> {
+    let config: ScanConfig = serde_json::from_str(json)?;
+    let url = config.target_url.clone();
+
+    if url.is_empty() {
+        return Err("no target_url was given to scan".into());
+    }
+
+    Scanner::builder().config(config).url(&url).build()
+}
+
+/// Queue a reported response to be taken with `ferox_poll`
+fn queue(event: Event) {
+    if let Event::ResultReported(response) = event {
+        let mut record = response.as_json();
+        tag(&mut record);
+
+        // json never contains a nul byte, serde_json escapes them
+        if let (Ok(result), Ok(mut results)) = (CString::new(record.to_string()), RESULTS.lock()) {
+            results.push_back(result);
+        }
+    }
+}
+
+/// Run the scan to completion, or until stopped, on its own runtime
+fn run(scanner: Scanner, stop: oneshot::Receiver<()>) {
+    // the same logging as the binary, per the configuration's verbosity
+    logger::initialize(CONFIGURATION.verbosity);
+
+    let mut runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            log::error!("Could not start the scan's runtime: {}", e);
+            STATUS.store(FEROX_FAILED, Ordering::SeqCst);
+            return;
+        }
+    };
+
+    let status = runtime.block_on(async move {
+        let mut events = events::subscribe();
+
+        let collector = tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                queue(event);
+            }
+        });
+
+        tokio::select! {
+            result = scanner.scan() => {
+                // every result was already queued by the collector, which ends with the scan
+                collector.await.unwrap_or_default();
+
+                match result {
+                    Ok(_) => FEROX_FINISHED,
+                    Err(e) => {
+                        log::error!("Scan failed: {}", e);
+                        FEROX_FAILED
+                    }
+                }
+            }
+            _ = stop => FEROX_STOPPED,
+        }
+    });
+
+    // cancels any requests still in flight after ferox_stop; the blocking task joining the
+    // progress bars never returns, the binary simply exits without waiting for it
+    runtime.shutdown_timeout(Duration::from_millis(100));
+    STATUS.store(status, Ordering::SeqCst);
+}
+
+/// Start a scan in the background, configured by the given json object (same keys as
+/// `ferox-config.toml`, plus `target_url`); returns 0 when the scan was started, -1 otherwise
+///
+/// # Safety
+///
+/// `config` has to be a valid, nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn ferox_start(config: *const c_char) -> c_int {
+    if config.is_null() {
+        return -1;
+    }
+
+    // claimed up front, so that only one of two concurrent calls can go on to start a scan
+    if STATUS
+        .compare_exchange(
+            FEROX_IDLE,
+            FEROX_RUNNING,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        )
+        .is_err()
+    {
+        return -1;
+    }
+
+    let json = match CStr::from_ptr(config).to_str() {
+        Ok(json) => json,
+        Err(_) => {
+            STATUS.store(FEROX_IDLE, Ordering::SeqCst);
+            return -1;
+        }
+    };
+
+    let scanner = match scanner_from_json(json) {
+        Ok(scanner) => scanner,
+        Err(e) => {
+            log::error!("Could not start scan: {}", e);
+            STATUS.store(FEROX_IDLE, Ordering::SeqCst);
+            return -1;
+        }
+    };
+
+    let (tx_stop, rx_stop) = oneshot::channel();
+
+    if let Ok(mut stop) = STOP.lock() {
+        *stop = Some(tx_stop);
+    }
+
+    thread::spawn(move || run(scanner, rx_stop));
+
+    0
+}
+
+/// State of the scan, one of the `FEROX_*` values
+#[no_mangle]
+pub extern "C" fn ferox_status() -> c_int {
+    STATUS.load(Ordering::SeqCst)
+}
+
+/// Take the oldest result not yet taken, as a json string to be freed with `ferox_free_string`;
+/// returns `NULL` when there's none at the moment
+#[no_mangle]
+pub extern "C" fn ferox_poll() -> *mut c_char {
+    match RESULTS
+        .lock()
+        .ok()
+        .and_then(|mut results| results.pop_front())
+    {
+        Some(result) => result.into_raw(),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Stop the running scan; results found so far can still be taken with `ferox_poll`
+#[no_mangle]
+pub extern "C" fn ferox_stop() {
+    if let Some(stop) = STOP.lock().ok().and_then(|mut stop| stop.take()) {
+        stop.send(()).unwrap_or_default();
+    }
+}
+
+/// Free a string returned by `ferox_poll`
+///
+/// # Safety
+///
+/// `string` has to be a string returned by `ferox_poll` that wasn't freed yet, or `NULL`
+#[no_mangle]
+pub unsafe extern "C" fn ferox_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the configuration has to be json, and name a target
+    fn ffi_scanner_from_json_needs_target() {
+        assert!(scanner_from_json("{}").is_err());
+        assert!(scanner_from_json("not json").is_err());
+    }
+
+    #[test]
+    /// nothing to take before a scan was started, freeing NULL is fine
+    fn ffi_poll_without_results_is_null() {
+        assert!(ferox_poll().is_null());
+        assert_eq!(ferox_status(), FEROX_IDLE);
+        unsafe { ferox_free_string(ptr::null_mut()) };
+    }
+
+    #[test]
+    /// a scan that can't be started leaves the status idle, so that another one can be
+    fn ffi_start_failure_stays_idle() {
+        let config = CString::new("not json").unwrap();
+
+        assert_eq!(unsafe { ferox_start(config.as_ptr()) }, -1);
+        assert_eq!(unsafe { ferox_start(ptr::null()) }, -1);
+        assert_eq!(ferox_status(), FEROX_IDLE);
+    }
+}
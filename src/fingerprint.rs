@@ -0,0 +1,554 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::heuristics::try_send_message_to_file;
+use crate::metadata::tag;
+use crate::targets::Target;
+use crate::utils::{ferox_print, make_request, status_colorizer};
+use console::style;
+use reqwest::{Client, Response, Url};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// directory in which the suggested wordlists are expected, as installed by the seclists package
+const SECLISTS: &str = "/usr/share/seclists/Discovery/Web-Content";
+
+/// Something a target serves that gives away a technology
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Signal {
+    /// a header (lowercase name) whose value contains the given lowercase text; an empty text
+    /// matches any value
+    Header(&'static str, &'static str),
+
+    /// a cookie whose name starts with the given text
+    Cookie(&'static str),
+
+    /// the target's page contains the given lowercase text
+    Body(&'static str),
+
+    /// the target's favicon has the given hash, the same one Shodan uses
+    Favicon(i32),
+
+    /// the given path exists and its page contains the given lowercase text; checking the
+    /// content keeps targets that answer every path from matching
+    Path(&'static str, &'static str),
+}
+
+/// A technology that may be fingerprinted, along with what's worth scanning it with
+#[derive(Debug)]
+pub struct Technology {
+    /// name shown to the user
+    pub name: &'static str,
+
+    /// extensions suggested for the technology
+    pub extensions: &'static [&'static str],
+
+    /// wordlists suggested for the technology, relative to `SECLISTS`
+    pub wordlists: &'static [&'static str],
+
+    /// signals that give the technology away; any one of them is enough
+    signals: &'static [Signal],
+}
+
+/// Technologies that may be fingerprinted
+pub const TECHNOLOGIES: [Technology; 9] = [
+    Technology {
+        name: "WordPress",
+        extensions: &["php"],
+        wordlists: &["CMS/wordpress.fuzz.txt"],
+        signals: &[
+            Signal::Body("/wp-content/"),
+            Signal::Body("/wp-includes/"),
+            Signal::Cookie("wordpress_"),
+            Signal::Path("/wp-login.php", "user_login"),
+        ],
+    },
+    Technology {
+        name: "Drupal",
+        extensions: &["php"],
+        wordlists: &["CMS/Drupal.txt"],
+        signals: &[
+            Signal::Header("x-generator", "drupal"),
+            Signal::Header("x-drupal-cache", ""),
+            Signal::Body("drupal-settings-json"),
+        ],
+    },
+    Technology {
+        name: "Laravel",
+        extensions: &["php"],
+        wordlists: &["PHP.fuzz.txt"],
+        signals: &[Signal::Cookie("laravel_session")],
+    },
+    Technology {
+        name: "PHP",
+        extensions: &["php"],
+        wordlists: &["PHP.fuzz.txt"],
+        signals: &[
+            Signal::Header("x-powered-by", "php"),
+            Signal::Cookie("PHPSESSID"),
+        ],
+    },
+    Technology {
+        name: "Tomcat",
+        extensions: &["jsp"],
+        wordlists: &["tomcat.txt"],
+        signals: &[
+            Signal::Header("server", "coyote"),
+            Signal::Body("apache tomcat"),
+            Signal::Favicon(-297069493),
+            Signal::Path("/manager/html", "tomcat"),
+        ],
+    },
+    Technology {
+        name: "Spring Boot",
+        extensions: &[],
+        wordlists: &["spring-boot.txt"],
+        signals: &[
+            Signal::Body("whitelabel error page"),
+            Signal::Favicon(116323821),
+            Signal::Path("/actuator/health", "\"status\""),
+        ],
+    },
+    Technology {
+        name: "Java",
+        extensions: &["jsp"],
+        wordlists: &[],
+        signals: &[Signal::Cookie("JSESSIONID")],
+    },
+    Technology {
+        name: "IIS / ASP.NET",
+        extensions: &["asp", "aspx"],
+        wordlists: &["IIS.fuzz.txt"],
+        signals: &[
+            Signal::Header("server", "microsoft-iis"),
+            Signal::Header("x-powered-by", "asp.net"),
+            Signal::Header("x-aspnet-version", ""),
+            Signal::Cookie("ASP.NET_SessionId"),
+            Signal::Cookie("ASPSESSIONID"),
+        ],
+    },
+    Technology {
+        name: "Jenkins",
+        extensions: &[],
+        wordlists: &[],
+        signals: &[Signal::Header("x-jenkins", ""), Signal::Favicon(81586312)],
+    },
+];
+
+/// What a target served while being fingerprinted
+#[derive(Debug, Default)]
+pub struct Evidence {
+    /// headers of the target's page, lowercase name to lowercase value
+    pub headers: Vec<(String, String)>,
+
+    /// names of the cookies set by the target's page
+    pub cookies: Vec<String>,
+
+    /// the target's page, lowercased
+    pub body: String,
+
+    /// hash of the target's favicon, if it has one
+    pub favicon: Option<i32>,
+
+    /// pages served for the paths of `Signal::Path`, lowercased, by path; paths that weren't
+    /// found are left out
+    pub paths: HashMap<&'static str, String>,
+}
+
+impl Signal {
+    /// Description of the signal when found in the given evidence, i.e. `header x-powered-by`
+    fn found_in(&self, evidence: &Evidence) -> Option<String> {
+        let found = match self {
+            Signal::Header(name, text) => evidence
+                .headers
+                .iter()
+                .any(|(header, value)| header == name && value.contains(text)),
+            Signal::Cookie(prefix) => evidence
+                .cookies
+                .iter()
+                .any(|cookie| cookie.starts_with(prefix)),
+            Signal::Body(text) => evidence.body.contains(text),
+            Signal::Favicon(hash) => evidence.favicon == Some(*hash),
+            Signal::Path(path, text) => evidence
+                .paths
+                .get(path)
+                .is_some_and(|page| page.contains(text)),
+        };
+
+        if !found {
+            return None;
+        }
+
+        let description = match self {
+            Signal::Header(name, _) => format!("header {}", name),
+            Signal::Cookie(prefix) => format!("cookie {}", prefix),
+            Signal::Body(_) => String::from("page content"),
+            Signal::Favicon(_) => String::from("favicon"),
+            Signal::Path(path, _) => format!("path {}", path),
+        };
+
+        Some(description)
+    }
+}
+
+/// 32-bit murmur3 hash (seed 0) of the given bytes, as a signed integer
+pub fn murmur3(data: &[u8]) -> i32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut hash: u32 = 0;
+    let mut chunks = data.chunks_exact(4);
+
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+        hash ^= k;
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+
+    let tail = chunks.remainder();
+
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .rev()
+            .fold(0u32, |k, byte| (k << 8) | u32::from(*byte));
+
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+
+    hash as i32
+}
+
+/// Hash of a favicon, computed the way Shodan does; murmur3 of the favicon base64-encoded into
+/// lines of 76 characters, each followed by a newline
+pub fn favicon_hash(favicon: &[u8]) -> i32 {
+    let encoded = openssl::base64::encode_block(favicon);
+
+    let mut lines = String::with_capacity(encoded.len() + encoded.len() / 76 + 1);
+
+    for line in encoded.as_bytes().chunks(76) {
+        lines.push_str(&String::from_utf8_lossy(line));
+        lines.push('\n');
+    }
+
+    murmur3(lines.as_bytes())
+}
+
+/// Whether or not the given url is a favicon, i.e. `/favicon.ico` or `/static/favicon.png`
+pub fn is_favicon(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .is_some_and(|name| name.to_lowercase().starts_with("favicon."))
+}
+
+/// Technologies given away by the evidence, each along with the signals found
+pub fn detect(evidence: &Evidence) -> Vec<(&'static Technology, Vec<String>)> {
+    TECHNOLOGIES
+        .iter()
+        .filter_map(|technology| {
+            let found: Vec<String> = technology
+                .signals
+                .iter()
+                .filter_map(|signal| signal.found_in(evidence))
+                .collect();
+
+            if found.is_empty() {
+                None
+            } else {
+                Some((technology, found))
+            }
+        })
+        .collect()
+}
+
+/// Request the given url, returning its response when it was found
+async fn fetch(client: &Client, url: &Url) -> Option<Response> {
+    match make_request(client, url).await {
+        Ok(response) if response.status().is_success() => Some(response),
+        _ => None,
+    }
+}
+
+/// Request the target's page, its favicon, and the paths of `Signal::Path` to gather evidence
+async fn gather(url: &Url, client: &Client) -> Evidence {
+    log::trace!("enter: gather({})", url);
+
+    let mut evidence = Evidence::default();
+
+    if let Ok(response) = make_request(client, url).await {
+        for (name, value) in response.headers() {
+            let value = value.to_str().unwrap_or_default();
+
+            if name == "set-cookie" {
+                if let Some(cookie) = value.split('=').next() {
+                    evidence.cookies.push(cookie.trim().to_string());
+                }
+            }
+
+            evidence
+                .headers
+                .push((name.as_str().to_string(), value.to_lowercase()));
+        }
+
+        evidence.body = response.text().await.unwrap_or_default().to_lowercase();
+    }
+
+    if let Ok(favicon_url) = url.join("/favicon.ico") {
+        if let Some(response) = fetch(client, &favicon_url).await {
+            if let Ok(favicon) = response.bytes().await {
+                if !favicon.is_empty() {
+                    evidence.favicon = Some(favicon_hash(&favicon));
+                }
+            }
+        }
+    }
+
+    let paths = TECHNOLOGIES
+        .iter()
+        .flat_map(|technology| technology.signals.iter())
+        .filter_map(|signal| match signal {
+            Signal::Path(path, _) => Some(*path),
+            _ => None,
+        });
+
+    for path in paths {
+        if let Ok(path_url) = url.join(path) {
+            if let Some(response) = fetch(client, &path_url).await {
+                let page = response.text().await.unwrap_or_default().to_lowercase();
+                evidence.paths.insert(path, page);
+            }
+        }
+    }
+
+    log::trace!("exit: gather -> {:?}", evidence);
+    evidence
+}
+
+/// Add the items to the list, skipping those already in it
+fn merge(list: &mut Vec<String>, items: impl Iterator<Item = String>) {
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
+
+/// Fingerprint the given target, printing what was detected and the extensions and wordlists
+/// suggested for it; with `--smart`, returns the target with those applied
+///
+/// Suggested wordlists that aren't found on disk are left out when applying suggestions
+pub async fn fingerprint(target: Target, tx_file: UnboundedSender<String>) -> Target {
+    log::trace!("enter: fingerprint({:?}, {:?})", target, tx_file);
+
+    let url = match Url::parse(&target.url) {
+        Ok(url) => url,
+        Err(_) => {
+            log::trace!("exit: fingerprint -> {:?}", target);
+            return target;
+        }
+    };
+
+    let options = target.scan_options(&CONFIGURATION);
+    let evidence = gather(&url, &options.client).await;
+    let detected = detect(&evidence);
+
+    let mut extensions = vec![];
+    let mut wordlists = vec![];
+
+    for (technology, _) in &detected {
+        merge(
+            &mut extensions,
+            technology.extensions.iter().map(|ext| ext.to_string()),
+        );
+        merge(
+            &mut wordlists,
+            technology
+                .wordlists
+                .iter()
+                .map(|wordlist| format!("{}/{}", SECLISTS, wordlist)),
+        );
+    }
+
+    let names: Vec<String> = detected
+        .iter()
+        .map(|(technology, found)| format!("{} ({})", technology.name, found.join(", ")))
+        .collect();
+
+    let mut suggestion = if detected.is_empty() {
+        String::from("nothing detected")
+    } else {
+        let verb = if CONFIGURATION.smart {
+            "applying"
+        } else {
+            "suggesting"
+        };
+
+        format!(
+            "{}; {} extensions [{}] and wordlists [{}]",
+            names.join(", "),
+            style(verb).yellow(),
+            extensions.join(", "),
+            wordlists.join(", ")
+        )
+    };
+
+    if let Some(hash) = evidence.favicon {
+        // a pivot for finding related hosts, i.e. http.favicon.hash:<hash> on Shodan
+        suggestion.push_str(&format!("; favicon hash {}", hash));
+    }
+
+    log::info!("Fingerprinted {}: {:?}", url, names);
+
+    if !CONFIGURATION.quiet {
+        // example output
+        // FPR          - http://localhost fingerprinted as Tomcat (favicon); suggesting extensions [jsp] and wordlists [...]; favicon hash 116323821
+        let msg = format!(
+            "{} {:>10} {} fingerprinted as {}\n",
+            status_colorizer("FPR"),
+            "-",
+            url,
+            suggestion
+        );
+
+        ferox_print(&msg, &PROGRESS_PRINTER);
+
+        let file_msg = if CONFIGURATION.json {
+            let technologies: Vec<_> = detected
+                .iter()
+                .map(|(technology, found)| json!({"name": technology.name, "signals": found}))
+                .collect();
+
+            let mut record = json!({
+                "type": "fingerprint",
+                "url": url.as_str(),
+                "technologies": technologies,
+                "extensions": extensions,
+                "wordlists": wordlists,
+                "applied": CONFIGURATION.smart,
+                "favicon_hash": evidence.favicon,
+            });
+
+            tag(&mut record);
+
+            format!("{}\n", record)
+        } else {
+            msg
+        };
+
+        try_send_message_to_file(&file_msg, tx_file, !CONFIGURATION.output.is_empty());
+    }
+
+    if !CONFIGURATION.smart || detected.is_empty() {
+        log::trace!("exit: fingerprint -> {:?}", target);
+        return target;
+    }
+
+    let mut applied = target.clone();
+
+    applied.extensions = target.extensions(&CONFIGURATION).to_vec();
+    merge(&mut applied.extensions, extensions.into_iter());
+
+    applied.wordlist = target.wordlist(&CONFIGURATION).to_vec();
+
+    for wordlist in wordlists {
+        if Path::new(&wordlist).is_file() {
+            merge(&mut applied.wordlist, std::iter::once(wordlist));
+        } else {
+            log::warn!("Suggested wordlist {} wasn't found; skipping it", wordlist);
+        }
+    }
+
+    log::trace!("exit: fingerprint -> {:?}", applied);
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// murmur3 matches known values, including unaligned tails and negative results
+    fn fingerprint_murmur3_matches_known_values() {
+        assert_eq!(murmur3(b""), 0);
+        assert_eq!(murmur3(b"hello"), 613153351);
+        assert_eq!(murmur3(b"foo"), -156908512);
+    }
+
+    #[test]
+    /// favicons are hashed as base64 split into newline-terminated lines of 76 characters
+    fn fingerprint_favicon_hash_wraps_base64() {
+        assert_eq!(favicon_hash(&[b'x'; 100]), 1090365782);
+    }
+
+    #[test]
+    /// favicons are recognized by name, wherever they're found and whatever their format
+    fn fingerprint_is_favicon_checks_last_segment() {
+        let favicon = |url| is_favicon(&Url::parse(url).unwrap());
+
+        assert!(favicon("http://localhost/favicon.ico"));
+        assert!(favicon("http://localhost/static/FAVICON.png?v=2"));
+        assert!(!favicon("http://localhost/favicon.ico/"));
+        assert!(!favicon("http://localhost/favicons"));
+        assert!(!favicon("http://localhost/"));
+    }
+
+    #[test]
+    /// any one signal is enough to detect a technology, each found signal is described
+    fn fingerprint_detect_reports_found_signals() {
+        let mut evidence = Evidence {
+            headers: vec![(String::from("x-powered-by"), String::from("php/7.4.3"))],
+            cookies: vec![String::from("laravel_session")],
+            body: String::from("<html>hello</html>"),
+            favicon: Some(-297069493),
+            ..Evidence::default()
+        };
+
+        evidence
+            .paths
+            .insert("/manager/html", String::from("<h1>tomcat manager</h1>"));
+
+        let detected: Vec<_> = detect(&evidence)
+            .into_iter()
+            .map(|(technology, found)| (technology.name, found))
+            .collect();
+
+        assert_eq!(
+            detected,
+            vec![
+                ("Laravel", vec![String::from("cookie laravel_session")]),
+                ("PHP", vec![String::from("header x-powered-by")]),
+                (
+                    "Tomcat",
+                    vec![String::from("favicon"), String::from("path /manager/html")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    /// paths that exist but don't have the expected content don't give anything away
+    fn fingerprint_detect_checks_path_content() {
+        let mut evidence = Evidence::default();
+
+        evidence
+            .paths
+            .insert("/wp-login.php", String::from("anything goes"));
+        evidence
+            .paths
+            .insert("/actuator/health", String::from("anything goes"));
+
+        assert!(detect(&evidence).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::heuristics::unique_string;
+use crate::scanner::ScanOptions;
+use crate::utils::make_request;
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+/// index pages requested to find out whether a forbidden directory exists
+const INDEX_PAGES: [&str; 4] = ["index.html", "index.htm", "index.php", "default.aspx"];
+
+/// number of 403s under a directory after which the directory itself is analyzed
+const FORBIDDEN_RUN: usize = 3;
+
+lazy_static! {
+    /// Directories analyzed so far, by url
+    static ref ANALYSES: RwLock<HashMap<String, Analysis>> = RwLock::new(HashMap::new());
+
+    /// Number of 403s seen under each directory being scanned, by url
+    static ref FORBIDDEN_COUNTS: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+}
+
+/// What a 403 says about the path that returned it (see `--analyze-403`)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Context {
+    /// the path is a directory that exists, only its listing is forbidden; worth recursing into
+    ListingForbidden,
+
+    /// everything under the path's directory is forbidden, whether it exists or not
+    Noise,
+}
+
+impl Context {
+    /// Description of the context, as shown next to a result
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Context::ListingForbidden => "listing forbidden",
+            Context::Noise => "forbidden noise",
+        }
+    }
+}
+
+/// What was found out about a directory by requesting it, a path under it that shouldn't exist,
+/// and (when needed) its index pages
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Analysis {
+    /// context of the directory itself; `None` when it isn't forbidden
+    context: Option<Context>,
+
+    /// whether or not everything under the directory is forbidden, whether it exists or not
+    forbids_children: bool,
+}
+
+/// Decide the context of a directory from what was served for the directory itself, for its
+/// index pages, and for a path under it that shouldn't exist
+///
+/// A forbidden directory with a reachable index page, or whose nonexistent child isn't
+/// forbidden, exists and only its listing is forbidden; a nonexistent child that's forbidden as
+/// well means the 403s carry no signal
+pub fn classify(
+    directory: StatusCode,
+    index_reachable: bool,
+    nonexistent: StatusCode,
+) -> Option<Context> {
+    if directory != StatusCode::FORBIDDEN {
+        None
+    } else if index_reachable || nonexistent != StatusCode::FORBIDDEN {
+        Some(Context::ListingForbidden)
+    } else {
+        Some(Context::Noise)
+    }
+}
+
+/// The given url as a directory, i.e. with a trailing slash and without a query
+fn directory_of(url: &Url) -> Url {
+    let mut directory = url.clone();
+    directory.set_query(None);
+
+    if !directory.path().ends_with('/') {
+        let path = format!("{}/", directory.path());
+        directory.set_path(&path);
+    }
+
+    directory
+}
+
+/// The directory that contains the given url, i.e. `/js/` for `/js/app` and `/js/app/`
+fn parent_of(url: &Url) -> Url {
+    let mut parent = directory_of(url);
+
+    if let Ok(mut segments) = parent.path_segments_mut() {
+        segments.pop_if_empty().pop().push("");
+    }
+
+    parent
+}
+
+/// Whether or not the last segment of the url has an extension (i.e. `login.php`); those aren't
+/// analyzed, while dotted directories like `.git` are
+fn looks_like_file(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|last| last.rfind('.'))
+        .is_some_and(|index| index > 0)
+}
+
+/// Look up an already analyzed directory
+fn known(directory: &Url) -> Option<Analysis> {
+    match ANALYSES.read() {
+        Ok(analyses) => analyses.get(directory.as_str()).copied(),
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            None
+        }
+    }
+}
+
+/// Request the given url, returning its status code; failed requests are treated as not found
+async fn status_of(url: &Url, options: &ScanOptions) -> StatusCode {
+    options.throttle().await;
+
+    match make_request(&options.client, url).await {
+        Ok(response) => response.status(),
+        Err(_) => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Request the given directory and a path under it that shouldn't exist, then remember what
+/// they say about the directory
+///
+/// Index pages are only requested when both are forbidden, and only until one is reachable
+async fn analyze(directory: &Url, options: &ScanOptions) -> Analysis {
+    log::trace!("enter: analyze({}, {:?})", directory, options);
+
+    if let Some(analysis) = known(directory) {
+        log::trace!("exit: analyze -> {:?}", analysis);
+        return analysis;
+    }
+
+    let status = status_of(directory, options).await;
+
+    let nonexistent = match directory.join(&unique_string(1)) {
+        Ok(url) => status_of(&url, options).await,
+        Err(_) => StatusCode::NOT_FOUND,
+    };
+
+    let mut index_reachable = false;
+
+    if status == StatusCode::FORBIDDEN && nonexistent == StatusCode::FORBIDDEN {
+        for page in INDEX_PAGES.iter() {
+            if let Ok(index) = directory.join(page) {
+                let index_status = status_of(&index, options).await;
+
+                if index_status.is_success() || index_status.is_redirection() {
+                    index_reachable = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    let analysis = Analysis {
+        context: classify(status, index_reachable, nonexistent),
+        forbids_children: nonexistent == StatusCode::FORBIDDEN && !index_reachable,
+    };
+
+    log::debug!("403 analysis of {}: {:?}", directory, analysis);
+
+    match ANALYSES.write() {
+        Ok(mut analyses) => {
+            analyses.insert(directory.to_string(), analysis);
+        }
+        Err(e) => {
+            log::error!("{}", e);
+        }
+    }
+
+    log::trace!("exit: analyze -> {:?}", analysis);
+    analysis
+}
+
+/// Count a 403 under the given directory, returning whether or not that made it a run of them
+fn count_forbidden(parent: &Url) -> bool {
+    match FORBIDDEN_COUNTS.lock() {
+        Ok(mut counts) => {
+            let count = counts.entry(parent.to_string()).or_insert(0);
+            *count += 1;
+            *count == FORBIDDEN_RUN
+        }
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            false
+        }
+    }
+}
+
+/// Whether or not the given directory was found to forbid everything under it
+fn forbids_children(directory: &Url) -> bool {
+    known(directory).is_some_and(|analysis| analysis.forbids_children)
+}
+
+/// Analyze the context of a 403 response (see `--analyze-403`), making requests as needed
+///
+/// Once paths under a directory consistently return 403, that directory is analyzed as well; when
+/// it forbids everything under it, the rest of its 403s are noise and aren't analyzed any further
+pub async fn analyze_response(response: &FeroxResponse, options: &ScanOptions) -> Option<Context> {
+    log::trace!("enter: analyze_response({}, {:?})", response.url(), options);
+
+    if *response.status() != StatusCode::FORBIDDEN {
+        log::trace!("exit: analyze_response -> None");
+        return None;
+    }
+
+    let parent = parent_of(response.url());
+
+    if count_forbidden(&parent) {
+        analyze(&parent, options).await;
+    }
+
+    let context = if forbids_children(&parent) {
+        Some(Context::Noise)
+    } else if looks_like_file(response.url()) {
+        None
+    } else {
+        analyze(&directory_of(response.url()), options)
+            .await
+            .context
+    };
+
+    log::trace!("exit: analyze_response -> {:?}", context);
+    context
+}
+
+/// Context of an already analyzed 403 response; used when reporting and deciding recursion
+pub fn context_of(url: &Url) -> Option<Context> {
+    if forbids_children(&parent_of(url)) {
+        Some(Context::Noise)
+    } else if looks_like_file(url) {
+        None
+    } else {
+        known(&directory_of(url)).and_then(|analysis| analysis.context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// a forbidden directory exists when its index page is reachable or a nonexistent child
+    /// isn't forbidden; it's noise when the nonexistent child is forbidden too
+    fn forbidden_classify_tells_listings_from_noise() {
+        let forbidden = StatusCode::FORBIDDEN;
+        let not_found = StatusCode::NOT_FOUND;
+
+        assert_eq!(
+            classify(forbidden, true, forbidden),
+            Some(Context::ListingForbidden)
+        );
+        assert_eq!(
+            classify(forbidden, false, not_found),
+            Some(Context::ListingForbidden)
+        );
+        assert_eq!(classify(forbidden, false, forbidden), Some(Context::Noise));
+        assert_eq!(classify(not_found, true, not_found), None);
+        assert_eq!(classify(StatusCode::OK, false, forbidden), None);
+    }
+
+    #[test]
+    /// directories end with a slash, parents drop the last segment, queries are dropped
+    fn forbidden_directory_and_parent_of_url() {
+        let url = Url::parse("http://localhost/js/app?x=1").unwrap();
+        assert_eq!(directory_of(&url).as_str(), "http://localhost/js/app/");
+        assert_eq!(parent_of(&url).as_str(), "http://localhost/js/");
+
+        let url = Url::parse("http://localhost/js/").unwrap();
+        assert_eq!(directory_of(&url).as_str(), "http://localhost/js/");
+        assert_eq!(parent_of(&url).as_str(), "http://localhost/");
+    }
+
+    #[test]
+    /// extensions make a file, dotted directories don't
+    fn forbidden_looks_like_file_checks_last_segment() {
+        let file = |url| looks_like_file(&Url::parse(url).unwrap());
+
+        assert!(file("http://localhost/login.php"));
+        assert!(file("http://localhost/a/backup.tar.gz"));
+        assert!(!file("http://localhost/.git"));
+        assert!(!file("http://localhost/.git/"));
+        assert!(!file("http://localhost/admin"));
+    }
+}
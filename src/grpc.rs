@@ -0,0 +1,670 @@
+//! gRPC service of `feroxbuster serve` (and of workers), as defined by `proto/feroxbuster.proto`
+//!
+//! The service is served on the same address as the HTTP API, which hands it every request with
+//! a gRPC content type, and wraps the same jobs (see `server`); messages are encoded and decoded
+//! by hand, as there are only a few of them with plain fields
+use crate::server;
+use crate::VERSION;
+use hyper::body::{self, Bytes, HttpBody};
+use hyper::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response};
+use serde_json::Value;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Path of the service's methods, `/PACKAGE.SERVICE/`
+const SERVICE: &str = "/feroxbuster.v1.Scans/";
+
+/// Milliseconds between checks for new results of a job being streamed
+const STREAM_INTERVAL: u64 = 500;
+
+/// gRPC status codes the service answers with
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Code {
+    Ok = 0,
+    InvalidArgument = 3,
+    NotFound = 5,
+    FailedPrecondition = 9,
+    Unimplemented = 12,
+    Internal = 13,
+    Unauthenticated = 16,
+}
+
+/// A failed call: its status code and message
+type Status = (Code, String);
+
+/// Trailers of a response, set once its body is complete
+type Trailers = Arc<Mutex<Option<HeaderMap>>>;
+
+/// Body of the api's responses: any hyper body, followed by trailers once they're set; gRPC
+/// sends the status of a call in trailers, after its messages
+#[derive(Debug)]
+pub struct ResponseBody {
+    /// the body itself
+    body: Body,
+
+    /// trailers sent once the body is complete, if any
+    trailers: Trailers,
+}
+
+impl From<Body> for ResponseBody {
+    fn from(body: Body) -> Self {
+        Self {
+            body,
+            trailers: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl HttpBody for ResponseBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.body).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(self
+            .trailers
+            .lock()
+            .ok()
+            .and_then(|mut trailers| trailers.take())))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // the trailers of a body that's complete still have to be sent
+        self.body.is_end_stream()
+            && self
+                .trailers
+                .lock()
+                .map(|trailers| trailers.is_none())
+                .unwrap_or(true)
+    }
+}
+
+/// Fields of a decoded protobuf message; fixed size fields are skipped, none of the service's
+/// messages have any
+#[derive(Debug, Default)]
+struct Fields {
+    /// varint fields, by field number
+    varints: Vec<(u32, u64)>,
+
+    /// length-delimited fields (strings, bytes, and messages), by field number
+    delimited: Vec<(u32, Vec<u8>)>,
+}
+
+impl Fields {
+    /// Value of the given varint field; the last one wins, 0 when it isn't there
+    fn uint(&self, field: u32) -> u64 {
+        self.varints
+            .iter()
+            .rev()
+            .find(|(number, _)| *number == field)
+            .map_or(0, |(_, value)| *value)
+    }
+
+    /// Every value of the given string field, in order
+    fn strings(&self, field: u32) -> Result<Vec<String>, Status> {
+        self.delimited
+            .iter()
+            .filter(|(number, _)| *number == field)
+            .map(|(_, bytes)| {
+                String::from_utf8(bytes.clone()).map_err(|_| {
+                    (
+                        Code::InvalidArgument,
+                        format!("field {} isn't utf-8", field),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Value of the given string field; the last one wins, empty when it isn't there
+    fn string(&self, field: u32) -> Result<String, Status> {
+        Ok(self.strings(field)?.pop().unwrap_or_default())
+    }
+}
+
+/// Read a varint from the start of `data`, moving past it
+fn read_varint(data: &mut &[u8]) -> Result<u64, Status> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = data
+            .split_first()
+            .ok_or_else(|| (Code::InvalidArgument, String::from("truncated varint")))?;
+        *data = rest;
+
+        value |= u64::from(byte & 0x7f) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err((Code::InvalidArgument, String::from("varint is too long")))
+}
+
+/// Decode a protobuf message into its fields
+fn decode(mut data: &[u8]) -> Result<Fields, Status> {
+    let mut fields = Fields::default();
+
+    while !data.is_empty() {
+        let key = read_varint(&mut data)?;
+        let field = (key >> 3) as u32;
+
+        let skip = match key & 7 {
+            0 => {
+                fields.varints.push((field, read_varint(&mut data)?));
+                0
+            }
+            1 => 8,
+            2 => {
+                let length = read_varint(&mut data)? as usize;
+
+                if length > data.len() {
+                    return Err((Code::InvalidArgument, String::from("truncated field")));
+                }
+
+                fields.delimited.push((field, data[..length].to_vec()));
+                length
+            }
+            5 => 4,
+            wire_type => {
+                return Err((
+                    Code::InvalidArgument,
+                    format!("unsupported wire type {}", wire_type),
+                ))
+            }
+        };
+
+        if skip > data.len() {
+            return Err((Code::InvalidArgument, String::from("truncated field")));
+        }
+
+        data = &data[skip..];
+    }
+
+    Ok(fields)
+}
+
+/// Protobuf encoding of a message, field by field; fields with their default value are left
+/// out, as proto3 does
+#[derive(Debug, Default)]
+struct Encoder {
+    /// message encoded so far
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(u64::from(field << 3 | u32::from(wire_type)));
+    }
+
+    fn uint(&mut self, field: u32, value: u64) -> &mut Self {
+        if value != 0 {
+            self.key(field, 0);
+            self.varint(value);
+        }
+
+        self
+    }
+
+    fn int32(&mut self, field: u32, value: i32) -> &mut Self {
+        // negative values are sign extended to 64 bits
+        self.uint(field, i64::from(value) as u64)
+    }
+
+    fn bool(&mut self, field: u32, value: bool) -> &mut Self {
+        self.uint(field, u64::from(value))
+    }
+
+    fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        if !value.is_empty() {
+            self.bytes(field, value.as_bytes());
+        }
+
+        self
+    }
+
+    /// A length-delimited field, written even when empty, as repeated messages are
+    fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.key(field, 2);
+        self.varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+        self
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
+}
+
+/// A message framed as gRPC sends it: uncompressed, preceded by its length
+fn frame(message: &[u8]) -> Bytes {
+    let mut framed = Vec::with_capacity(message.len() + 5);
+    framed.push(0);
+    framed.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    framed.extend_from_slice(message);
+    Bytes::from(framed)
+}
+
+/// The single message of a unary call's request body
+fn unframe(body: &[u8]) -> Result<&[u8], Status> {
+    if body.len() < 5 {
+        return Err((Code::InvalidArgument, String::from("expected a message")));
+    }
+
+    if body[0] != 0 {
+        return Err((
+            Code::Unimplemented,
+            String::from("compressed messages aren't supported"),
+        ));
+    }
+
+    let length = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+
+    if body.len() - 5 != length {
+        return Err((
+            Code::InvalidArgument,
+            String::from("expected a single message"),
+        ));
+    }
+
+    Ok(&body[5..])
+}
+
+/// `grpc-status` and `grpc-message` of the given status
+fn status_headers(code: Code, msg: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("grpc-status", HeaderValue::from(code as u16));
+
+    if !msg.is_empty() {
+        // grpc-message is percent-encoded
+        let encoded: String = msg
+            .bytes()
+            .map(|byte| match byte {
+                b' '..=b'~' if byte != b'%' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect();
+
+        if let Ok(value) = HeaderValue::from_str(&encoded) {
+            headers.insert("grpc-message", value);
+        }
+    }
+
+    headers
+}
+
+/// Response carrying the given messages, followed by the given status in its trailers
+fn respond(messages: Vec<Bytes>, code: Code, msg: &str) -> Response<ResponseBody> {
+    let body: Vec<u8> = messages
+        .iter()
+        .flat_map(|message| message.to_vec())
+        .collect();
+
+    let mut response = Response::new(ResponseBody {
+        body: Body::from(body),
+        trailers: Arc::new(Mutex::new(Some(status_headers(code, msg)))),
+    });
+
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/grpc+proto"),
+    );
+    response
+}
+
+/// Response of a failed call, without messages; the status goes in its headers, as gRPC expects
+/// of a response without messages
+fn fail((code, msg): Status) -> Response<ResponseBody> {
+    let mut response = Response::new(ResponseBody::from(Body::empty()));
+
+    *response.headers_mut() = status_headers(code, &msg);
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/grpc+proto"),
+    );
+    response
+}
+
+/// Encoding of a job, as returned by `server::job_json`, as a `Scan` message
+fn encode_scan(job: &Value) -> Vec<u8> {
+    let status = match job["status"].as_str().unwrap_or_default() {
+        "running" => 1,
+        "finished" => 2,
+        "failed" => 3,
+        "cancelled" => 4,
+        _ => 0,
+    };
+
+    let statistics = match &job["statistics"] {
+        Value::Null => String::new(),
+        statistics => statistics.to_string(),
+    };
+
+    Encoder::default()
+        .uint(1, job["id"].as_u64().unwrap_or_default())
+        .string(2, job["target_url"].as_str().unwrap_or_default())
+        .uint(3, status)
+        .int32(4, job["exit_code"].as_i64().unwrap_or_default() as i32)
+        .bool(5, !job["exit_code"].is_null())
+        .string(6, &statistics)
+        .finish()
+}
+
+/// Encoding of a result, as written by `--json`, as a `Result` message
+fn encode_result(record: &Value, line: &str) -> Vec<u8> {
+    Encoder::default()
+        .string(1, record["url"].as_str().unwrap_or_default())
+        .string(2, record["path"].as_str().unwrap_or_default())
+        .uint(3, record["status"].as_u64().unwrap_or_default())
+        .uint(4, record["content_length"].as_u64().unwrap_or_default())
+        .string(5, line)
+        .finish()
+}
+
+/// Json configuration of a job submitted with the given `SubmitScanRequest`, as taken by
+/// `server::submit`
+fn submitted_config(request: &Fields) -> Result<Value, Status> {
+    let config_json = request.string(2)?;
+
+    let mut config = if config_json.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        serde_json::from_str(&config_json)
+            .map_err(|e| (Code::InvalidArgument, format!("config_json: {}", e)))?
+    };
+
+    if !config.is_object() {
+        return Err((
+            Code::InvalidArgument,
+            String::from("config_json has to be a json object"),
+        ));
+    }
+
+    config["target_url"] = Value::from(request.string(1)?);
+
+    let words = request.strings(3)?;
+
+    if !words.is_empty() {
+        config["words"] = Value::from(words);
+    }
+
+    Ok(config)
+}
+
+/// Job of the given id, as returned by `server::job_json`, or NOT_FOUND
+fn find_job(id: u64) -> Result<Value, Status> {
+    server::job_json(id as usize).ok_or((Code::NotFound, String::from("no such scan")))
+}
+
+/// Answer a unary call to the given method with its request message
+fn call(method: &str, request: &Fields) -> Result<Vec<u8>, Status> {
+    match method {
+        "SubmitScan" => {
+            let id = server::submit(&submitted_config(request)?)
+                .map_err(|e| (Code::InvalidArgument, e))?;
+            Ok(encode_scan(&find_job(id as u64)?))
+        }
+        "GetScan" => Ok(encode_scan(&find_job(request.uint(1))?)),
+        "ListScans" => {
+            let mut response = Encoder::default();
+
+            for job in server::jobs_json() {
+                response.bytes(1, &encode_scan(&job));
+            }
+
+            Ok(response.finish())
+        }
+        "CancelScan" => {
+            let id = request.uint(1);
+            find_job(id)?;
+
+            if !server::cancel(id as usize) {
+                return Err((Code::FailedPrecondition, String::from("scan isn't running")));
+            }
+
+            Ok(encode_scan(&find_job(id)?))
+        }
+        "Health" => {
+            let running = server::jobs_json()
+                .iter()
+                .filter(|job| job["status"] == "running")
+                .count();
+
+            Ok(Encoder::default()
+                .bool(1, true)
+                .uint(2, running as u64)
+                .string(3, VERSION)
+                .finish())
+        }
+        _ => Err((Code::Unimplemented, format!("no such method: {}", method))),
+    }
+}
+
+/// Stream the results of the given job as `Result` messages as they're found, starting with
+/// those already found, until the job is done
+fn stream_results(id: usize) -> Response<ResponseBody> {
+    let (mut tx, body) = Body::channel();
+    let trailers: Trailers = Arc::new(Mutex::new(None));
+    let done = trailers.clone();
+
+    tokio::spawn(async move {
+        let mut offset = 0;
+
+        // checked ahead of reading, so that nothing written before the job ended is missed
+        while let Some((results, running)) = server::job_progress(id) {
+            for line in server::read_new_lines(&results, &mut offset) {
+                let record = match serde_json::from_str::<Value>(&line) {
+                    Ok(record) if record["type"] == "response" => record,
+                    _ => continue,
+                };
+
+                let message = frame(&encode_result(&record, &line));
+
+                if tx.send_data(message).await.is_err() {
+                    return; // the client went away
+                }
+            }
+
+            if !running {
+                break;
+            }
+
+            tokio::time::delay_for(Duration::from_millis(STREAM_INTERVAL)).await;
+        }
+
+        // set before the body ends, which is when the sender is dropped
+        if let Ok(mut trailers) = done.lock() {
+            *trailers = Some(status_headers(Code::Ok, ""));
+        }
+    });
+
+    let mut response = Response::new(ResponseBody { body, trailers });
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/grpc+proto"),
+    );
+    response
+}
+
+/// Whether or not the request is a gRPC call, going by its content type
+pub fn is_grpc(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/grpc"))
+        .unwrap_or_default()
+}
+
+/// Answer a gRPC call; like every request to the api, it has to carry the api's token (see
+/// `server::route`), as `authorization` metadata
+pub async fn route(request: Request<Body>, token: &str) -> Response<ResponseBody> {
+    log::trace!("enter: route({})", request.uri());
+
+    if !server::is_authorized(&request, token) {
+        return fail((
+            Code::Unauthenticated,
+            String::from("a valid bearer token is required"),
+        ));
+    }
+
+    let method = match request.uri().path().strip_prefix(SERVICE) {
+        Some(method) if request.method() == Method::POST => method.to_string(),
+        _ => {
+            return fail((
+                Code::Unimplemented,
+                format!("no such method: {}", request.uri().path()),
+            ))
+        }
+    };
+
+    let body = match body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(e) => return fail((Code::Internal, e.to_string())),
+    };
+
+    let fields = match unframe(&body).and_then(decode) {
+        Ok(fields) => fields,
+        Err(status) => return fail(status),
+    };
+
+    let response = if method == "StreamResults" {
+        match find_job(fields.uint(1)) {
+            Ok(_) => stream_results(fields.uint(1) as usize),
+            Err(status) => fail(status),
+        }
+    } else {
+        match call(&method, &fields) {
+            Ok(message) => respond(vec![frame(&message)], Code::Ok, ""),
+            Err(status) => fail(status),
+        }
+    };
+
+    log::trace!("exit: route");
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    /// what's encoded decodes to the same fields; defaults are left out
+    fn grpc_encode_decode_round_trip() {
+        let message = Encoder::default()
+            .uint(1, 300)
+            .string(2, "http://localhost")
+            .string(3, "admin")
+            .string(3, "login")
+            .uint(4, 0)
+            .finish();
+
+        let fields = decode(&message).unwrap();
+        assert_eq!(fields.uint(1), 300);
+        assert_eq!(fields.string(2).unwrap(), "http://localhost");
+        assert_eq!(fields.strings(3).unwrap(), vec!["admin", "login"]);
+        assert!(fields.varints.iter().all(|(field, _)| *field != 4));
+
+        // 300 as a varint, as in protobuf's own documentation
+        assert_eq!(&message[..3], &[0x08, 0xac, 0x02]);
+    }
+
+    #[test]
+    /// fixed size fields are skipped, truncated messages and groups are refused
+    fn grpc_decode_skips_fixed_and_refuses_garbage() {
+        let mut message = vec![0x09, 1, 2, 3, 4, 5, 6, 7, 8, 0x15, 1, 2, 3, 4];
+        message.extend(Encoder::default().uint(1, 7).finish());
+        assert_eq!(decode(&message).unwrap().uint(1), 7);
+
+        assert!(decode(&[0x12, 0x05, b'a']).is_err());
+        assert!(decode(&[0x0b]).is_err());
+        assert!(decode(&[0x08, 0x80]).is_err());
+    }
+
+    #[test]
+    /// a unary request is a single uncompressed message, preceded by its length
+    fn grpc_frame_and_unframe() {
+        let framed = frame(b"abc");
+        assert_eq!(&framed[..], &[0, 0, 0, 0, 3, b'a', b'b', b'c']);
+        assert_eq!(unframe(&framed).unwrap(), b"abc");
+
+        assert_eq!(
+            unframe(&[1, 0, 0, 0, 0]).unwrap_err().0,
+            Code::Unimplemented
+        );
+        assert_eq!(
+            unframe(&[0, 0, 0, 0, 5, b'a']).unwrap_err().0,
+            Code::InvalidArgument
+        );
+        assert!(unframe(&[]).is_err());
+    }
+
+    #[test]
+    /// a submitted scan's fields become the json configuration the http api takes
+    fn grpc_submitted_config_merges_fields() {
+        let message = Encoder::default()
+            .string(1, "http://localhost")
+            .string(2, r#"{"extensions": ["php"]}"#)
+            .string(3, "admin")
+            .finish();
+
+        let config = submitted_config(&decode(&message).unwrap()).unwrap();
+        assert_eq!(
+            config,
+            json!({"target_url": "http://localhost", "extensions": ["php"], "words": ["admin"]})
+        );
+
+        let message = Encoder::default().string(2, "[1]").finish();
+        assert!(submitted_config(&decode(&message).unwrap()).is_err());
+    }
+
+    #[test]
+    /// a job's status and exit code map onto the Scan message
+    fn grpc_encode_scan_maps_status() {
+        let job = json!({
+            "id": 2,
+            "target_url": "http://localhost",
+            "status": "failed",
+            "exit_code": -1,
+            "statistics": null,
+        });
+
+        let fields = decode(&encode_scan(&job)).unwrap();
+        assert_eq!(fields.uint(1), 2);
+        assert_eq!(fields.uint(3), 3);
+        assert_eq!(fields.uint(4) as i64, -1);
+        assert_eq!(fields.uint(5), 1);
+        assert_eq!(fields.string(6).unwrap(), "");
+    }
+
+    #[test]
+    /// grpc-message is percent-encoded
+    fn grpc_status_headers_encode_message() {
+        let headers = status_headers(Code::NotFound, "no such scan: 100%");
+        assert_eq!(headers["grpc-status"], "5");
+        assert_eq!(headers["grpc-message"], "no such scan: 100%25");
+    }
+}
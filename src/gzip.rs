@@ -0,0 +1,683 @@
+//! Gzip compression (RFC 1951 and RFC 1952) of saved response bodies, and DEFLATE decompression
+//! of the release archives downloaded by `--update`
+//!
+//! Data is compressed as a single DEFLATE block of fixed Huffman codes, with matches found in a
+//! 32 KiB window; data that doesn't get smaller that way is stored as is. The result is a complete
+//! gzip member, members can be concatenated into a file that `gunzip` reads as a whole
+//!
+//! Decompression reads every kind of DEFLATE block, as written by zlib and the zip tools
+
+/// Size of the window in which earlier occurrences of the data are looked for
+const WINDOW: usize = 32 * 1024;
+
+/// Shortest and longest match that DEFLATE can express
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Most earlier occurrences compared at each position; more finds longer matches, slower
+const MAX_CHAIN: usize = 64;
+
+/// Bits of the hash of the next `MIN_MATCH` bytes that earlier occurrences are looked up by
+const HASH_BITS: u32 = 15;
+
+/// Most bytes in a stored block
+const MAX_STORED: usize = 65535;
+
+/// Start of the lengths given by the length codes 257 through 285, and their number of extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Start of the distances given by the distance codes 0 through 29, and their number of extra bits
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which the code lengths of the code length alphabet are given in a dynamic block
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Longest Huffman code DEFLATE uses
+const MAX_BITS: usize = 15;
+
+/// Table of the CRC-32 of every byte (ISO 3309, as used by gzip)
+const CRC_TABLE: [u32; 256] = crc_table();
+
+/// Build `CRC_TABLE`
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut byte = 0;
+
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[byte] = crc;
+        byte += 1;
+    }
+
+    table
+}
+
+/// CRC-32 of the given data, as stored in the trailer of a gzip member
+pub fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Writes bits from the least significant one up, as DEFLATE packs them
+#[derive(Default)]
+struct BitWriter {
+    /// complete bytes written so far
+    bytes: Vec<u8>,
+
+    /// bits not yet making up a complete byte
+    buffer: u64,
+
+    /// number of bits in `buffer`
+    count: u32,
+}
+
+impl BitWriter {
+    /// Write the `count` low bits of `value`, least significant first (extra bits, headers)
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code of the given length, most significant bit first
+    fn code(&mut self, code: u32, length: u32) {
+        self.bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    /// Written bytes, the last one padded with zeros
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+
+        self.bytes
+    }
+
+    /// Write the fixed Huffman code of a literal byte (0-255), end of block (256), or length code
+    /// (257-285)
+    fn symbol(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    /// Write a match of `length` bytes found `distance` bytes back
+    fn matched(&mut self, length: usize, distance: usize) {
+        let code = LENGTH_BASE
+            .iter()
+            .rposition(|base| *base as usize <= length);
+        let code = code.unwrap_or_default();
+        self.symbol(257 + code as u32);
+        self.bits(
+            (length - LENGTH_BASE[code] as usize) as u32,
+            LENGTH_EXTRA[code] as u32,
+        );
+
+        let code = DISTANCE_BASE
+            .iter()
+            .rposition(|base| *base as usize <= distance);
+        let code = code.unwrap_or_default();
+        self.code(code as u32, 5);
+        self.bits(
+            (distance - DISTANCE_BASE[code] as usize) as u32,
+            DISTANCE_EXTRA[code] as u32,
+        );
+    }
+}
+
+/// Index of the next `MIN_MATCH` bytes at `position` in the table of earlier occurrences
+fn hash(data: &[u8], position: usize) -> usize {
+    let key = (data[position] as u32) << 16
+        | (data[position + 1] as u32) << 8
+        | data[position + 2] as u32;
+
+    (key.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Remember `position` as the most recent occurrence of the next `MIN_MATCH` bytes
+fn insert(data: &[u8], position: usize, head: &mut [usize], previous: &mut [usize]) {
+    if position + MIN_MATCH <= data.len() {
+        let hash = hash(data, position);
+        previous[position % WINDOW] = head[hash];
+        head[hash] = position;
+    }
+}
+
+/// DEFLATE data as a single block of fixed Huffman codes
+fn fixed_block(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+
+    // last block, fixed Huffman codes
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+
+    // most recent position of each hash, and the position before it with the same hash
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW];
+
+    let mut position = 0;
+
+    while position < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+
+        if position + MIN_MATCH <= data.len() {
+            let longest = (data.len() - position).min(MAX_MATCH);
+            let mut candidate = head[hash(data, position)];
+            let mut chain = 0;
+
+            // candidates are all before position; older ones than the window are left alone
+            while candidate != usize::MAX && position - candidate <= WINDOW && chain < MAX_CHAIN {
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[position..position + longest])
+                    .take_while(|(earlier, current)| earlier == current)
+                    .count();
+
+                if length > best_length {
+                    best_length = length;
+                    best_distance = position - candidate;
+
+                    if length == longest {
+                        break;
+                    }
+                }
+
+                candidate = previous[candidate % WINDOW];
+                chain += 1;
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            writer.matched(best_length, best_distance);
+
+            for matched in position..position + best_length {
+                insert(data, matched, &mut head, &mut previous);
+            }
+
+            position += best_length;
+        } else {
+            writer.symbol(data[position] as u32);
+            insert(data, position, &mut head, &mut previous);
+            position += 1;
+        }
+    }
+
+    // end of block
+    writer.symbol(256);
+    writer.finish()
+}
+
+/// DEFLATE data as stored blocks, for data that doesn't get smaller
+fn stored_blocks(data: &[u8]) -> Vec<u8> {
+    let mut blocks = Vec::with_capacity(data.len() + 5 * (data.len() / MAX_STORED + 1));
+    let mut chunks = data.chunks(MAX_STORED).peekable();
+
+    if chunks.peek().is_none() {
+        // an empty input is still a (last) block
+        blocks.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let length = chunk.len() as u16;
+
+        blocks.push(last as u8);
+        blocks.extend_from_slice(&length.to_le_bytes());
+        blocks.extend_from_slice(&(!length).to_le_bytes());
+        blocks.extend_from_slice(chunk);
+    }
+
+    blocks
+}
+
+/// Compress the data as a complete gzip member
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let compressed = fixed_block(data);
+    let stored = data.len() + 5 * data.len().div_ceil(MAX_STORED).max(1);
+
+    let deflated = if compressed.len() < stored {
+        compressed
+    } else {
+        stored_blocks(data)
+    };
+
+    // magic, deflate, no flags, no modification time, no extra flags, unknown os
+    let mut member = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    member.extend_from_slice(&deflated);
+    member.extend_from_slice(&crc32(data).to_le_bytes());
+    member.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    member
+}
+
+/// Reads bits from the least significant one up, as DEFLATE packs them
+struct BitReader<'a> {
+    /// data being read
+    data: &'a [u8],
+
+    /// index of the next bit to read
+    position: usize,
+}
+
+impl BitReader<'_> {
+    /// Read `count` bits as a number, the first one read being the least significant
+    fn bits(&mut self, count: usize) -> Result<u32, String> {
+        let mut value = 0;
+
+        for bit in 0..count {
+            let byte = self
+                .data
+                .get(self.position / 8)
+                .ok_or("compressed data ends early")?;
+
+            value |= (((byte >> (self.position % 8)) & 1) as u32) << bit;
+            self.position += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Skip to the start of the next byte, as stored blocks are
+    fn align(&mut self) {
+        self.position = self.position.div_ceil(8) * 8;
+    }
+}
+
+/// Canonical Huffman code, given by the length of each symbol's code (RFC 1951 section 3.2.2)
+struct Huffman {
+    /// number of codes of each length
+    counts: [u16; MAX_BITS + 1],
+
+    /// symbols ordered by their code
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Code in which symbol `n` has a code of `lengths[n]` bits; 0 for symbols that aren't used
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+
+        counts[0] = 0;
+
+        let mut symbols: Vec<u16> = (0..lengths.len() as u16)
+            .filter(|symbol| lengths[*symbol as usize] > 0)
+            .collect();
+
+        // codes of the same length are ordered by symbol, shorter codes come first
+        symbols.sort_by_key(|symbol| lengths[*symbol as usize]);
+
+        Self { counts, symbols }
+    }
+
+    /// Read the next symbol, one bit of its code at a time
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        // first code of the current length, and index of its symbol
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for length in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(String::from("invalid Huffman code"))
+    }
+}
+
+/// Codes of a block of fixed Huffman codes
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [8u8; 288];
+    lengths[144..256].iter_mut().for_each(|length| *length = 9);
+    lengths[256..280].iter_mut().for_each(|length| *length = 7);
+
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+/// Read the codes given at the start of a block of dynamic Huffman codes
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literals = reader.bits(5)? as usize + 257;
+    let distances = reader.bits(5)? as usize + 1;
+    let code_lengths = reader.bits(4)? as usize + 4;
+
+    let mut lengths = [0u8; 19];
+
+    for symbol in CODE_LENGTH_ORDER.iter().take(code_lengths) {
+        lengths[*symbol] = reader.bits(3)? as u8;
+    }
+
+    let code_length_code = Huffman::new(&lengths);
+
+    // literal/length and distance code lengths are given as a single sequence
+    let mut lengths = Vec::with_capacity(literals + distances);
+
+    while lengths.len() < literals + distances {
+        let (length, repeat) = match code_length_code.decode(reader)? {
+            length @ 0..=15 => (length as u8, 1),
+            16 => {
+                let previous = *lengths.last().ok_or("repeat of no code length")?;
+                (previous, 3 + reader.bits(2)?)
+            }
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+
+        lengths.extend(std::iter::repeat_n(length, repeat as usize));
+    }
+
+    if lengths.len() > literals + distances {
+        return Err(String::from("code lengths repeated past the end"));
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literals]),
+        Huffman::new(&lengths[literals..]),
+    ))
+}
+
+/// Decompress raw DEFLATE data, i.e. a zip archive's member or the body of a gzip member
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader { data, position: 0 };
+    let mut inflated = Vec::new();
+
+    loop {
+        let last = reader.bits(1)? == 1;
+
+        let (literal_code, distance_code) = match reader.bits(2)? {
+            0 => {
+                reader.align();
+
+                let length = reader.bits(16)? as usize;
+                let complement = reader.bits(16)? as usize;
+
+                if length != !complement & 0xffff {
+                    return Err(String::from(
+                        "stored block length doesn't match its complement",
+                    ));
+                }
+
+                let start = reader.position / 8;
+                let stored = data
+                    .get(start..start + length)
+                    .ok_or("compressed data ends early")?;
+
+                inflated.extend_from_slice(stored);
+                reader.position = (start + length) * 8;
+
+                if last {
+                    break;
+                }
+
+                continue;
+            }
+            1 => fixed_codes(),
+            2 => dynamic_codes(&mut reader)?,
+            _ => return Err(String::from("invalid block type")),
+        };
+
+        loop {
+            let symbol = literal_code.decode(&mut reader)? as usize;
+
+            if symbol < 256 {
+                inflated.push(symbol as u8);
+                continue;
+            }
+
+            if symbol == 256 {
+                break;
+            }
+
+            let code = symbol - 257;
+
+            if code >= LENGTH_BASE.len() {
+                return Err(String::from("invalid length code"));
+            }
+
+            let length =
+                LENGTH_BASE[code] as usize + reader.bits(LENGTH_EXTRA[code] as usize)? as usize;
+
+            let code = distance_code.decode(&mut reader)? as usize;
+
+            if code >= DISTANCE_BASE.len() {
+                return Err(String::from("invalid distance code"));
+            }
+
+            let distance =
+                DISTANCE_BASE[code] as usize + reader.bits(DISTANCE_EXTRA[code] as usize)? as usize;
+
+            if distance > inflated.len() {
+                return Err(String::from(
+                    "distance reaches before the start of the data",
+                ));
+            }
+
+            // the match may overlap the bytes it produces
+            for _ in 0..length {
+                inflated.push(inflated[inflated.len() - distance]);
+            }
+        }
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(inflated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads bits from the least significant one up
+    struct BitReader<'a> {
+        data: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: usize) -> u32 {
+            (0..count).fold(0, |value, bit| {
+                let byte = self.data[self.position / 8];
+                let set = (byte >> (self.position % 8)) & 1;
+                self.position += 1;
+                value | (set as u32) << bit
+            })
+        }
+
+        fn code(&mut self, length: usize) -> u32 {
+            (0..length).fold(0, |code, _| code << 1 | self.bits(1))
+        }
+
+        fn symbol(&mut self) -> u32 {
+            let code = self.code(7);
+
+            if code <= 0x17 {
+                return code + 256;
+            }
+
+            let code = code << 1 | self.bits(1);
+
+            match code {
+                0x30..=0xbf => code - 0x30,
+                0xc0..=0xc7 => code - 0xc0 + 280,
+                _ => (code << 1 | self.bits(1)) - 0x190 + 144,
+            }
+        }
+    }
+
+    /// Decompress a member written by `compress`
+    fn decompress(member: &[u8]) -> Vec<u8> {
+        assert_eq!(member[..3], [0x1f, 0x8b, 8]);
+
+        let deflated = &member[10..member.len() - 8];
+        let mut reader = BitReader {
+            data: deflated,
+            position: 0,
+        };
+        let mut data = Vec::new();
+
+        loop {
+            let last = reader.bits(1) == 1;
+
+            match reader.bits(2) {
+                0 => {
+                    let start = reader.position.div_ceil(8);
+                    let length = u16::from_le_bytes([deflated[start], deflated[start + 1]]);
+                    let start = start + 4;
+                    data.extend_from_slice(&deflated[start..start + length as usize]);
+                    reader.position = (start + length as usize) * 8;
+                }
+                1 => loop {
+                    match reader.symbol() {
+                        literal @ 0..=255 => data.push(literal as u8),
+                        256 => break,
+                        symbol => {
+                            let code = (symbol - 257) as usize;
+                            let length = LENGTH_BASE[code] as usize
+                                + reader.bits(LENGTH_EXTRA[code] as usize) as usize;
+                            let code = reader.code(5) as usize;
+                            let distance = DISTANCE_BASE[code] as usize
+                                + reader.bits(DISTANCE_EXTRA[code] as usize) as usize;
+
+                            for _ in 0..length {
+                                data.push(data[data.len() - distance]);
+                            }
+                        }
+                    }
+                },
+                kind => panic!("unexpected block type {}", kind),
+            }
+
+            if last {
+                break;
+            }
+        }
+
+        let trailer = &member[member.len() - 8..];
+        assert_eq!(trailer[..4], crc32(&data).to_le_bytes());
+        assert_eq!(trailer[4..], (data.len() as u32).to_le_bytes());
+        data
+    }
+
+    /// bytes of the given hex string
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    /// data deflated by zlib, with dynamic Huffman codes or stored, inflates to the original;
+    /// so does data compressed here, and damaged data is an error
+    fn gzip_inflate_reads_every_block_type() {
+        let dynamic = unhex(concat!(
+            "d5ccc10d84300c44d156a600d806b852020d987822d05a04c58ed8f237b4c08debfb9ac9ace5b7360f56",
+            "8c633b5582d0721d56441db111d6c9039546710e481bd3d7b1c770e7a387d324f116a7e509f9259f4b1f",
+            "cd4d095977a57f9ec01f",
+        ));
+        let expected = format!(
+            "{}{}",
+            "feroxbuster --update downloads the latest release, checks it, then replaces itself; "
+                .repeat(3),
+            "The Dude abides. ".repeat(4)
+        );
+        assert_eq!(inflate(&dynamic).unwrap(), expected.as_bytes());
+
+        let stored = unhex("010c00f3ff73746f726564206173206973");
+        assert_eq!(inflate(&stored).unwrap(), b"stored as is");
+
+        let page = "<html><body><div class=\"row\">not found</div></body></html>\n".repeat(400);
+        let member = compress(page.as_bytes());
+        assert_eq!(
+            inflate(&member[10..member.len() - 8]).unwrap(),
+            page.as_bytes()
+        );
+
+        assert!(inflate(&dynamic[..dynamic.len() / 2]).is_err());
+        assert!(inflate(&[0x07]).is_err());
+    }
+
+    #[test]
+    /// the crc of the standard check input is the standard check value
+    fn gzip_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    /// repetitive bodies shrink, and every kind of input decompresses to itself
+    fn gzip_compress_roundtrips() {
+        let page = "<html><body><div class=\"row\">not found</div></body></html>\n".repeat(400);
+        let member = compress(page.as_bytes());
+
+        assert!(member.len() < page.len() / 10);
+        assert_eq!(decompress(&member), page.as_bytes());
+
+        // a pseudo-random body doesn't compress, it's stored in more than one block
+        let mut state = 0x2545_f491u32;
+        let noise: Vec<u8> = (0..150_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+
+        let member = compress(&noise);
+        assert!(member.len() > noise.len());
+        assert_eq!(decompress(&member), noise);
+
+        for data in [&b""[..], b"a", b"abcabcabcabcabcd", &[0u8; 70_000][..]] {
+            assert_eq!(decompress(&compress(data)), data);
+        }
+    }
+}
@@ -1,11 +1,22 @@
 use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
-use crate::scanner::should_filter_response;
+use crate::error_log::log_request_error;
+use crate::exit_codes;
+use crate::listing;
+use crate::metadata::tag;
+use crate::progress;
+use crate::scanner::{is_directory_redirect, should_filter_response};
+use crate::targets::Target;
 use crate::utils::{
     ferox_print, format_url, get_url_path_length, make_request, module_colorizer, status_colorizer,
 };
+use crate::FeroxResponse;
 use console::style;
 use indicatif::ProgressBar;
-use reqwest::Response;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{Client, Response, Url};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::process;
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
@@ -13,6 +24,29 @@ use uuid::Uuid;
 /// length of a standard UUID, used when determining wildcard responses
 const UUID_LENGTH: u64 = 32;
 
+/// how similar (0.0 - 1.0) a page's words must be to those of the not-found page for the page to
+/// be considered a soft 404
+const SOFT_404_SIMILARITY: f64 = 0.95;
+
+lazy_static! {
+    /// contents of a page's `<title>`
+    static ref TITLE_REGEX: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+
+    /// a single `<meta>` tag
+    static ref META_REGEX: Regex = Regex::new(r"(?is)<meta\b[^>]*>").unwrap();
+
+    /// `http-equiv` attribute of a `<meta>` tag that refreshes the page
+    static ref REFRESH_REGEX: Regex = Regex::new(r#"(?i)http-equiv\s*=\s*["']?refresh"#).unwrap();
+
+    /// location given in the `content` attribute of a refreshing `<meta>` tag
+    static ref REFRESH_URL_REGEX: Regex = Regex::new(r#"(?i)\burl\s*=\s*['"]?([^'"\s>]+)"#).unwrap();
+
+    /// page titles that announce a missing page
+    static ref NOT_FOUND_REGEX: Regex = Regex::new(
+        r"(?i)\b(404|not found|cannot be found|can't be found|could not be found|does not exist|doesn't exist)\b"
+    ).unwrap();
+}
+
 /// Data holder for two pieces of data needed when auto-filtering out wildcard responses
 ///
 /// `dynamic` is the size of the response that will later be combined with the length
@@ -21,6 +55,15 @@ const UUID_LENGTH: u64 = 32;
 ///
 /// `size` is size of the response that should be included with filters passed via runtime
 /// configuration and any static wildcard lengths.
+///
+/// `status` is the status code of the wildcard responses; only responses with the same status
+/// code are filtered (0 matches any status code)
+///
+/// `unfilterable` is set when the wildcard responses couldn't be told apart from real responses
+/// by their size, in which case the directory isn't scanned at all
+///
+/// `echoes` is set when the wildcard responses are directories, i.e. the directory answers any
+/// path added to it as a directory of its own; its subdirectories are recursion traps
 #[derive(Default, Debug, PartialEq, Copy, Clone)]
 pub struct WildcardFilter {
     /// size of the response that will later be combined with the length of the path of the url
@@ -29,6 +72,210 @@ pub struct WildcardFilter {
 
     /// size of the response that should be included with filters passed via runtime configuration
     pub size: u64,
+
+    /// status code of the wildcard responses, 0 for any
+    pub status: u16,
+
+    /// whether or not the wildcard responses vary in ways that can't be filtered
+    pub unfilterable: bool,
+
+    /// whether or not the wildcard responses are directories
+    pub echoes: bool,
+}
+
+impl WildcardFilter {
+    /// Determine whether a response with the given status code and size is a wildcard response,
+    /// returning either `static wildcard` or `dynamic wildcard` if it is; `url_len` is the length
+    /// of the response's url path, see `dynamic`
+    pub fn matches(&self, status: u16, content_len: u64, url_len: u64) -> Option<&'static str> {
+        if self.status != 0 && self.status != status {
+            return None;
+        }
+
+        if self.size > 0 && self.size == content_len {
+            // static wildcard size found during testing
+            return Some("static wildcard");
+        }
+
+        if self.dynamic > 0 && url_len + self.dynamic == content_len {
+            // dynamic wildcard offset found during testing
+            return Some("dynamic wildcard");
+        }
+
+        None
+    }
+}
+
+/// Fingerprint of the page a target serves for paths that don't exist, used to filter out soft
+/// 404s; pages that read like the not-found page while answering with a different status code
+/// (usually 200)
+///
+/// `title` and `refresh` are the not-found page's title and meta refresh location (if any)
+///
+/// `words` counts the words of the not-found page, less any words reflected from the requested
+/// path
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct Soft404Filter {
+    /// lowercased `<title>` of the not-found page
+    pub title: String,
+
+    /// location the not-found page refreshes to with a `<meta http-equiv="refresh">` tag
+    pub refresh: String,
+
+    /// number of times each word appears on the not-found page
+    pub words: HashMap<String, usize>,
+}
+
+impl Soft404Filter {
+    /// Fingerprint the given not-found page, which was served for `url`
+    pub fn new(body: &str, url: &Url) -> Self {
+        Self {
+            title: page_title(body),
+            refresh: refresh_location(body),
+            words: page_words(body, url),
+        }
+    }
+
+    /// Whether or not the fingerprint holds anything to compare pages against
+    pub fn is_empty(&self) -> bool {
+        self.refresh.is_empty() && self.words.is_empty()
+    }
+
+    /// Determine whether the given page, which was served for `url`, is the not-found page;
+    /// either it refreshes to the same location or its words are nearly the same
+    pub fn matches(&self, body: &str, url: &Url) -> bool {
+        if !self.refresh.is_empty() && self.refresh == refresh_location(body) {
+            return true;
+        }
+
+        !self.words.is_empty()
+            && similarity(&self.words, &page_words(body, url)) >= SOFT_404_SIMILARITY
+    }
+
+    /// Same as `matches`, leaving the given words out of the comparison; used for pages whose
+    /// words change from one request to the next (see `--detect-dynamic`)
+    pub fn matches_except(&self, body: &str, url: &Url, volatile: &HashSet<String>) -> bool {
+        if !self.refresh.is_empty() && self.refresh == refresh_location(body) {
+            return true;
+        }
+
+        let mut stable = self.clone();
+        stable.forget(volatile);
+
+        let mut words = page_words(body, url);
+        words.retain(|word, _| !volatile.contains(word));
+
+        !stable.words.is_empty() && similarity(&stable.words, &words) >= SOFT_404_SIMILARITY
+    }
+
+    /// Leave out the words that changed when the not-found page was requested again
+    pub fn forget(&mut self, volatile: &HashSet<String>) {
+        self.words.retain(|word, _| !volatile.contains(word));
+    }
+}
+
+/// Whether or not the title of the given page announces a missing page, i.e. `404 Not Found`
+pub fn reads_not_found(body: &str) -> bool {
+    NOT_FOUND_REGEX.is_match(&page_title(body))
+}
+
+/// Lowercased `<title>` of the given page, with its whitespace collapsed; empty without a title
+fn page_title(body: &str) -> String {
+    match TITLE_REGEX.captures(body) {
+        Some(captures) => captures[1]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase(),
+        None => String::new(),
+    }
+}
+
+/// Location (without its query) to which the given page refreshes with a
+/// `<meta http-equiv="refresh">` tag; empty without one
+fn refresh_location(body: &str) -> String {
+    for meta in META_REGEX.find_iter(body) {
+        if !REFRESH_REGEX.is_match(meta.as_str()) {
+            continue;
+        }
+
+        if let Some(captures) = REFRESH_URL_REGEX.captures(meta.as_str()) {
+            let location = captures[1].split('?').next().unwrap_or_default();
+            return location.to_lowercase();
+        }
+    }
+
+    String::new()
+}
+
+/// Lowercased alphanumeric words of the given text
+fn words_of(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Count the words of a page served for `url`; words of the url's last path segment are skipped,
+/// as not-found pages often reflect the requested path
+fn page_words(body: &str, url: &Url) -> HashMap<String, usize> {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .unwrap_or_default();
+
+    let reflected: Vec<String> = words_of(segment).collect();
+
+    let mut words = HashMap::new();
+
+    for word in words_of(body).filter(|word| !reflected.contains(word)) {
+        *words.entry(word).or_insert(0) += 1;
+    }
+
+    words
+}
+
+/// Words of a page served for `url` whose number of appearances changed from one request of it
+/// to the next, i.e. timestamps, CSRF tokens, or rotating ads
+pub fn volatile_words(first: &str, second: &str, url: &Url) -> HashSet<String> {
+    let first = page_words(first, url);
+    let second = page_words(second, url);
+
+    first
+        .iter()
+        .filter(|(word, count)| second.get(*word) != Some(count))
+        .map(|(word, _)| word.to_string())
+        .chain(
+            second
+                .keys()
+                .filter(|word| !first.contains_key(*word))
+                .cloned(),
+        )
+        .collect()
+}
+
+/// Similarity of two pages' word counts, from 0.0 (no words in common) to 1.0 (the same words,
+/// the same number of times)
+fn similarity(first: &HashMap<String, usize>, second: &HashMap<String, usize>) -> f64 {
+    let mut shared = 0;
+    let mut total = 0;
+
+    for (word, count) in first {
+        let other = second.get(word).copied().unwrap_or(0);
+        shared += (*count).min(other);
+        total += (*count).max(other);
+    }
+
+    for (word, count) in second {
+        if !first.contains_key(word) {
+            total += count;
+        }
+    }
+
+    if total == 0 {
+        return 1.0;
+    }
+
+    shared as f64 / total as f64
 }
 
 /// Simple helper to return a uuid, formatted as lowercase without hyphens
@@ -36,7 +283,7 @@ pub struct WildcardFilter {
 /// `length` determines the number of uuids to string together. Each uuid
 /// is 32 characters long. So, a length of 1 return a 32 character string,
 /// a length of 2 returns a 64 character string, and so on...
-fn unique_string(length: usize) -> String {
+pub fn unique_string(length: usize) -> String {
     log::trace!("enter: unique_string({})", length);
     let mut ids = vec![];
 
@@ -56,12 +303,14 @@ fn unique_string(length: usize) -> String {
 /// [WildcardFilter](struct.WildcardFilter.html) is created and returned to the caller.
 pub async fn wildcard_test(
     target_url: &str,
+    client: &Client,
     bar: ProgressBar,
     tx_file: UnboundedSender<String>,
 ) -> Option<WildcardFilter> {
     log::trace!(
-        "enter: wildcard_test({:?}, {:?}, {:?})",
+        "enter: wildcard_test({:?}, {:?}, {:?}, {:?})",
         target_url,
+        client,
         bar,
         tx_file
     );
@@ -74,36 +323,48 @@ pub async fn wildcard_test(
 
     let clone_req_one = tx_file.clone();
     let clone_req_two = tx_file.clone();
+    let clone_req_three = tx_file.clone();
 
-    if let Some(resp_one) = make_wildcard_request(&target_url, 1, clone_req_one).await {
-        bar.inc(1);
+    if let Some(resp_one) = make_wildcard_request(target_url, client, 1, clone_req_one).await {
+        progress::inc(&bar, 1);
 
         // found a wildcard response
-        let mut wildcard = WildcardFilter::default();
+        let mut wildcard = WildcardFilter {
+            echoes: is_directory_response(&resp_one),
+            ..WildcardFilter::default()
+        };
 
         let wc_length = resp_one.content_length().unwrap_or(0);
 
         if wc_length == 0 {
+            // the second request is skipped, but still accounted for in the bar's length
+            progress::inc(&bar, 1);
             log::trace!("exit: wildcard_test -> Some({:?})", wildcard);
             return Some(wildcard);
         }
 
         // content length of wildcard is non-zero, perform additional tests:
         //   make a second request, with a known-sized (64) longer request
-        if let Some(resp_two) = make_wildcard_request(&target_url, 3, clone_req_two).await {
-            bar.inc(1);
+        if let Some(resp_two) = make_wildcard_request(target_url, client, 3, clone_req_two).await {
+            progress::inc(&bar, 1);
 
             let wc2_length = resp_two.content_length().unwrap_or(0);
+            let same_status = resp_one.status() == resp_two.status();
 
-            if wc2_length == wc_length + (UUID_LENGTH * 2) {
+            if same_status && wc2_length == wc_length + (UUID_LENGTH * 2) {
                 // second length is what we'd expect to see if the requested url is
                 // reflected in the response along with some static content; aka custom 404
-                let url_len = get_url_path_length(&resp_one.url());
+                let url_len = get_url_path_length(resp_one.url());
 
                 wildcard.dynamic = wc_length - url_len;
+                wildcard.status = resp_one.status().as_u16();
 
                 if !CONFIGURATION.quiet
-                    && !should_filter_response(&wildcard.dynamic, &resp_one.url())
+                    && !should_filter_response(
+                        &resp_one.status(),
+                        &wildcard.dynamic,
+                        resp_one.url(),
+                    )
                 {
                     let msg = format!(
                             "{} {:>10} Wildcard response is dynamic; {} ({} + url length) responses; toggle this behavior by using {}\n",
@@ -117,15 +378,17 @@ pub async fn wildcard_test(
                     ferox_print(&msg, &PROGRESS_PRINTER);
 
                     try_send_message_to_file(
-                        &msg,
+                        &wildcard_file_message(&msg, &wildcard, resp_one.url()),
                         tx_file.clone(),
                         !CONFIGURATION.output.is_empty(),
                     );
                 }
-            } else if wc_length == wc2_length {
+            } else if same_status && wc_length == wc2_length {
                 wildcard.size = wc_length;
+                wildcard.status = resp_one.status().as_u16();
 
-                if !CONFIGURATION.quiet && !should_filter_response(&wildcard.size, &resp_one.url())
+                if !CONFIGURATION.quiet
+                    && !should_filter_response(&resp_one.status(), &wildcard.size, resp_one.url())
                 {
                     let msg = format!(
                         "{} {:>10} Wildcard response is static; {} {} responses; toggle this behavior by using {}\n",
@@ -139,24 +402,76 @@ pub async fn wildcard_test(
                     ferox_print(&msg, &PROGRESS_PRINTER);
 
                     try_send_message_to_file(
-                        &msg,
+                        &wildcard_file_message(&msg, &wildcard, resp_one.url()),
                         tx_file.clone(),
                         !CONFIGURATION.output.is_empty(),
                     );
                 }
+            } else {
+                // the responses are neither the same size nor off by the difference in url
+                // length; a third request confirms that the target really answers everything
+                // before giving up on the directory
+                bar.inc_length(1);
+                progress::inc_total_length(1);
+
+                let resp_three =
+                    make_wildcard_request(target_url, client, 2, clone_req_three).await;
+                progress::inc(&bar, 1);
+
+                if resp_three.is_some() {
+                    wildcard.unfilterable = true;
+
+                    if !CONFIGURATION.quiet {
+                        let msg = format!(
+                            "{} {:>10} Wildcard response is unstable; {} {}; scan it anyway by using {}\n",
+                            status_colorizer("WLD"),
+                            "-",
+                            style("skipping").yellow(),
+                            target_url,
+                            style("--dontfilter").yellow()
+                        );
+
+                        ferox_print(&msg, &PROGRESS_PRINTER);
+
+                        try_send_message_to_file(
+                            &wildcard_file_message(&msg, &wildcard, resp_one.url()),
+                            tx_file.clone(),
+                            !CONFIGURATION.output.is_empty(),
+                        );
+                    }
+                }
             }
         } else {
-            bar.inc(2);
+            // second request failed
+            progress::inc(&bar, 1);
         }
 
         log::trace!("exit: wildcard_test -> Some({:?})", wildcard);
         return Some(wildcard);
     }
 
+    // first request failed or wasn't a wildcard, the second request is never made
+    progress::inc(&bar, 2);
+
     log::trace!("exit: wildcard_test -> None");
     None
 }
 
+/// Whether or not the given wildcard response is a directory; either a redirect to the same path
+/// plus a slash, or a 2xx for a path ending with a slash
+fn is_directory_response(response: &Response) -> bool {
+    if response.status().is_redirection() {
+        response
+            .headers()
+            .get("Location")
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| response.url().join(location).ok())
+            .is_some_and(|location| is_directory_redirect(response.url(), &location))
+    } else {
+        response.status().is_success() && response.url().path().ends_with('/')
+    }
+}
+
 /// Generates a uuid and appends it to the given target url. The reasoning is that the randomly
 /// generated unique string should not exist on and be served by the target web server.
 ///
@@ -165,12 +480,14 @@ pub async fn wildcard_test(
 /// wildcard response has a 3xx status code, that redirection location is displayed to the user.
 async fn make_wildcard_request(
     target_url: &str,
+    client: &Client,
     length: usize,
     tx_file: UnboundedSender<String>,
 ) -> Option<Response> {
     log::trace!(
-        "enter: make_wildcard_request({}, {}, {:?})",
+        "enter: make_wildcard_request({}, {:?}, {}, {:?})",
         target_url,
+        client,
         length,
         tx_file
     );
@@ -194,32 +511,35 @@ async fn make_wildcard_request(
 
     let wildcard = status_colorizer("WLD");
 
-    match make_request(&CONFIGURATION.client, &nonexistent.to_owned()).await {
+    match make_request(client, &nonexistent.to_owned()).await {
         Ok(response) => {
             if CONFIGURATION
                 .statuscodes
                 .contains(&response.status().as_u16())
             {
                 // found a wildcard response
-                let url_len = get_url_path_length(&response.url());
+                let url_len = get_url_path_length(response.url());
                 let content_len = response.content_length().unwrap_or(0);
 
-                if !CONFIGURATION.quiet && !should_filter_response(&content_len, &response.url()) {
+                if !CONFIGURATION.quiet
+                    && !should_filter_response(&response.status(), &content_len, response.url())
+                {
                     let msg = format!(
                         "{} {:>10} Got {} for {} (url length: {})\n",
                         wildcard,
                         content_len,
-                        status_colorizer(&response.status().as_str()),
+                        status_colorizer(response.status().as_str()),
                         response.url(),
                         url_len
                     );
 
                     ferox_print(&msg, &PROGRESS_PRINTER);
 
+                    // --json output gets a single `wildcard` record once the test completes
                     try_send_message_to_file(
                         &msg,
                         tx_file.clone(),
-                        !CONFIGURATION.output.is_empty(),
+                        !CONFIGURATION.output.is_empty() && !CONFIGURATION.json,
                     );
                 }
 
@@ -228,7 +548,11 @@ async fn make_wildcard_request(
                     if let Some(next_loc) = response.headers().get("Location") {
                         let next_loc_str = next_loc.to_str().unwrap_or("Unknown");
                         if !CONFIGURATION.quiet
-                            && !should_filter_response(&content_len, &response.url())
+                            && !should_filter_response(
+                                &response.status(),
+                                &content_len,
+                                response.url(),
+                            )
                         {
                             let msg = format!(
                                 "{} {:>10} {} redirects to => {}\n",
@@ -243,7 +567,7 @@ async fn make_wildcard_request(
                             try_send_message_to_file(
                                 &msg,
                                 tx_file.clone(),
-                                !CONFIGURATION.output.is_empty(),
+                                !CONFIGURATION.output.is_empty() && !CONFIGURATION.json,
                             );
                         }
                     }
@@ -254,6 +578,7 @@ async fn make_wildcard_request(
         }
         Err(e) => {
             log::warn!("{}", e);
+            log_request_error(&nonexistent, None, e.as_ref());
             log::trace!("exit: make_wildcard_request -> None");
             return None;
         }
@@ -262,17 +587,234 @@ async fn make_wildcard_request(
     None
 }
 
+/// Requests a path that shouldn't exist on the target and fingerprints the page that's served
+/// for it, whatever its status code
+///
+/// A [Soft404Filter](struct.Soft404Filter.html) is returned unless the request failed or the page
+/// was empty
+pub async fn soft_404_test(
+    target_url: &str,
+    client: &Client,
+    bar: ProgressBar,
+    tx_file: UnboundedSender<String>,
+) -> Option<Soft404Filter> {
+    log::trace!(
+        "enter: soft_404_test({:?}, {:?}, {:?}, {:?})",
+        target_url,
+        client,
+        bar,
+        tx_file
+    );
+
+    // the request isn't part of requests_per_directory, it's only made with --soft-404
+    bar.inc_length(1);
+    progress::inc_total_length(1);
+
+    let nonexistent = match format_url(
+        target_url,
+        &unique_string(2),
+        CONFIGURATION.addslash,
+        &CONFIGURATION.queries,
+        None,
+    ) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("{}", e);
+            progress::inc(&bar, 1);
+            log::trace!("exit: soft_404_test -> None");
+            return None;
+        }
+    };
+
+    let response = match make_request(client, &nonexistent).await {
+        Ok(response) => FeroxResponse::from(response, true).await,
+        Err(e) => {
+            log::warn!("{}", e);
+            log_request_error(&nonexistent, None, e.as_ref());
+            progress::inc(&bar, 1);
+            log::trace!("exit: soft_404_test -> None");
+            return None;
+        }
+    };
+
+    progress::inc(&bar, 1);
+
+    let mut filter = Soft404Filter::new(response.text(), response.url());
+
+    if CONFIGURATION.detect_dynamic {
+        // words of the not-found page that change from one request to the next say nothing
+        // about whether another page is the not-found page; the request is only made with
+        // --detect-dynamic, so it isn't part of requests_per_directory either
+        bar.inc_length(1);
+        progress::inc_total_length(1);
+
+        let again = make_request(client, &nonexistent).await;
+        progress::inc(&bar, 1);
+
+        if let Ok(again) = again {
+            let again = FeroxResponse::from(again, true).await;
+            let volatile = volatile_words(response.text(), again.text(), response.url());
+
+            if !volatile.is_empty() {
+                log::debug!("not-found page has {} volatile words", volatile.len());
+                filter.forget(&volatile);
+            }
+        }
+    }
+
+    if filter.is_empty() {
+        log::trace!("exit: soft_404_test -> None");
+        return None;
+    }
+
+    if !CONFIGURATION.quiet {
+        let title = if filter.title.is_empty() {
+            String::new()
+        } else {
+            format!(" titled {:?}", filter.title)
+        };
+
+        let msg = format!(
+            "{} {:>10} Not-found page{} is {}; {} responses that read like it\n",
+            status_colorizer("WLD"),
+            response.text().len(),
+            title,
+            status_colorizer(response.status().as_str()),
+            style("auto-filtering").yellow(),
+        );
+
+        ferox_print(&msg, &PROGRESS_PRINTER);
+
+        let file_msg = if CONFIGURATION.json {
+            let mut record = json!({
+                "type": "soft404",
+                "url": response.url().as_str(),
+                "status": response.status().as_u16(),
+                "title": filter.title,
+                "refresh": filter.refresh,
+                "words": filter.words.values().sum::<usize>(),
+            });
+
+            tag(&mut record);
+
+            format!("{}\n", record)
+        } else {
+            msg
+        };
+
+        try_send_message_to_file(&file_msg, tx_file, !CONFIGURATION.output.is_empty());
+    }
+
+    log::trace!("exit: soft_404_test -> Some({:?})", filter);
+    Some(filter)
+}
+
+/// Requests the directory itself (see `--use-listings`) and checks whether it's an open directory
+/// listing
+///
+/// When it is, the urls of the listing's entries are returned to the caller, who can request them
+/// instead of brute forcing the directory
+pub async fn listing_test(
+    target_url: &str,
+    client: &Client,
+    bar: ProgressBar,
+    tx_file: UnboundedSender<String>,
+) -> Option<Vec<Url>> {
+    log::trace!(
+        "enter: listing_test({:?}, {:?}, {:?}, {:?})",
+        target_url,
+        client,
+        bar,
+        tx_file
+    );
+
+    // the request isn't part of requests_per_directory, it's only made with --use-listings
+    bar.inc_length(1);
+    progress::inc_total_length(1);
+
+    let directory = if target_url.ends_with('/') {
+        target_url.to_string()
+    } else {
+        format!("{}/", target_url)
+    };
+
+    let url = match format_url(&directory, "", false, &CONFIGURATION.queries, None) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("{}", e);
+            progress::inc(&bar, 1);
+            log::trace!("exit: listing_test -> None");
+            return None;
+        }
+    };
+
+    let response = match make_request(client, &url).await {
+        Ok(response) => FeroxResponse::from(response, true).await,
+        Err(e) => {
+            log::warn!("{}", e);
+            log_request_error(&url, None, e.as_ref());
+            progress::inc(&bar, 1);
+            log::trace!("exit: listing_test -> None");
+            return None;
+        }
+    };
+
+    progress::inc(&bar, 1);
+
+    if !response.status().is_success() || !response.markers().listing {
+        log::trace!("exit: listing_test -> None");
+        return None;
+    }
+
+    let entries = listing::entries(response.text(), response.url());
+
+    if !CONFIGURATION.quiet {
+        let msg = format!(
+            "{} {:>10} Directory listing found at {}; {} its {} entries instead of brute forcing it\n",
+            status_colorizer("LST"),
+            response.content_length(),
+            response.url(),
+            style("requesting").yellow(),
+            style(entries.len()).cyan(),
+        );
+
+        ferox_print(&msg, &PROGRESS_PRINTER);
+
+        let file_msg = if CONFIGURATION.json {
+            let mut record = json!({
+                "type": "listing",
+                "url": response.url().as_str(),
+                "entries": entries.len(),
+            });
+
+            tag(&mut record);
+
+            format!("{}\n", record)
+        } else {
+            msg
+        };
+
+        try_send_message_to_file(&file_msg, tx_file, !CONFIGURATION.output.is_empty());
+    }
+
+    log::trace!("exit: listing_test -> Some({:?})", entries);
+    Some(entries)
+}
+
 /// Simply tries to connect to all given sites before starting to scan
 ///
 /// In the event that no sites can be reached, the program will exit.
 ///
-/// Any urls that are found to be alive are returned to the caller.
-pub async fn connectivity_test(target_urls: &[String]) -> Vec<String> {
-    log::trace!("enter: connectivity_test({:?})", target_urls);
+/// Any urls that are found to be alive are returned to the caller. Each target is connected to
+/// with its own client, i.e. through its own proxy
+pub async fn connectivity_test(targets: &[Target]) -> Vec<String> {
+    log::trace!("enter: connectivity_test({:?})", targets);
 
     let mut good_urls = vec![];
 
-    for target_url in target_urls {
+    for target in targets {
+        let target_url = &target.url;
+
         let request = match format_url(
             target_url,
             "",
@@ -287,7 +829,7 @@ pub async fn connectivity_test(target_urls: &[String]) -> Vec<String> {
             }
         };
 
-        match make_request(&CONFIGURATION.client, &request).await {
+        match make_request(&target.client(&CONFIGURATION), &request).await {
             Ok(_) => {
                 good_urls.push(target_url.to_owned());
             }
@@ -299,6 +841,7 @@ pub async fn connectivity_test(target_urls: &[String]) -> Vec<String> {
                     );
                 }
                 log::error!("{}", e);
+                log_request_error(&request, None, e.as_ref());
             }
         }
     }
@@ -312,7 +855,11 @@ pub async fn connectivity_test(target_urls: &[String]) -> Vec<String> {
             module_colorizer("heuristics::connectivity_test"),
         );
 
-        process::exit(1);
+        if exit_codes::exit_on(&CONFIGURATION.exit_on, "unreachable") {
+            process::exit(exit_codes::UNREACHABLE);
+        }
+
+        process::exit(exit_codes::ERROR);
     }
 
     log::trace!("exit: connectivity_test -> {:?}", good_urls);
@@ -320,9 +867,31 @@ pub async fn connectivity_test(target_urls: &[String]) -> Vec<String> {
     good_urls
 }
 
+/// Pick the representation of a wildcard message that's written to the output file; `--json`
+/// gets a `wildcard` record, otherwise the text printed to the terminal is used
+fn wildcard_file_message(msg: &str, wildcard: &WildcardFilter, url: &Url) -> String {
+    if CONFIGURATION.json {
+        let mut record = json!({
+            "type": "wildcard",
+            "url": url.as_str(),
+            "dynamic": wildcard.dynamic,
+            "size": wildcard.size,
+            "status": wildcard.status,
+            "unfilterable": wildcard.unfilterable,
+            "echoes": wildcard.echoes,
+        });
+
+        tag(&mut record);
+
+        format!("{}\n", record)
+    } else {
+        msg.to_string()
+    }
+}
+
 /// simple helper to keep DRY; sends a message using the transmitter side of the given mpsc channel
 /// the receiver is expected to be the side that saves the message to CONFIGURATION.output.
-fn try_send_message_to_file(msg: &str, tx_file: UnboundedSender<String>, save_output: bool) {
+pub fn try_send_message_to_file(msg: &str, tx_file: UnboundedSender<String>, save_output: bool) {
     log::trace!("enter: try_send_message_to_file({}, {:?})", msg, tx_file);
 
     if save_output {
@@ -346,6 +915,7 @@ fn try_send_message_to_file(msg: &str, tx_file: UnboundedSender<String>, save_ou
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod tests {
     use super::*;
     use crate::FeroxChannel;
@@ -367,6 +937,130 @@ mod tests {
         assert_eq!(wcf.dynamic, 0);
     }
 
+    #[test]
+    /// static and dynamic wildcards only match responses with the wildcard's status code, unless
+    /// the status code is unknown
+    fn heuristics_wildcardfilter_matches_status_and_size() {
+        let static_wcf = WildcardFilter {
+            size: 46,
+            status: 200,
+            ..WildcardFilter::default()
+        };
+        assert_eq!(static_wcf.matches(200, 46, 10), Some("static wildcard"));
+        assert_eq!(static_wcf.matches(403, 46, 10), None);
+        assert_eq!(static_wcf.matches(200, 47, 10), None);
+
+        let dynamic_wcf = WildcardFilter {
+            dynamic: 14,
+            status: 301,
+            ..WildcardFilter::default()
+        };
+        assert_eq!(dynamic_wcf.matches(301, 24, 10), Some("dynamic wildcard"));
+        assert_eq!(dynamic_wcf.matches(200, 24, 10), None);
+        assert_eq!(dynamic_wcf.matches(301, 24, 11), None);
+
+        let any_status = WildcardFilter {
+            size: 46,
+            ..WildcardFilter::default()
+        };
+        assert_eq!(any_status.matches(404, 46, 10), Some("static wildcard"));
+
+        assert_eq!(WildcardFilter::default().matches(200, 0, 0), None);
+    }
+
+    #[test]
+    /// titles announcing a missing page read as not found, whatever their case and spacing
+    fn heuristics_reads_not_found_checks_title() {
+        assert!(reads_not_found(
+            "<html><TITLE>\n  404   Not Found</TITLE></html>"
+        ));
+        assert!(reads_not_found(
+            "<title>Oops! That page doesn't exist</title>"
+        ));
+        assert!(!reads_not_found("<title>Admin Login</title>"));
+        assert!(!reads_not_found("<h1>Not Found</h1>"));
+    }
+
+    #[test]
+    /// a refreshing meta tag is found with its attributes in any order, without its query
+    fn heuristics_refresh_location_reads_meta_tag() {
+        let body = r#"<meta charset="utf-8"><meta content="0; URL='/errors/missing?from=/abc'" http-equiv="Refresh">"#;
+        assert_eq!(refresh_location(body), "/errors/missing");
+        assert_eq!(
+            refresh_location(r#"<meta http-equiv=refresh content="3;url=/home">"#),
+            "/home"
+        );
+        assert_eq!(
+            refresh_location(r#"<meta name="description" content="url=/nope">"#),
+            ""
+        );
+    }
+
+    #[test]
+    /// pages that reflect the requested path match the not-found page they were fingerprinted
+    /// from, pages with different content don't
+    fn heuristics_soft404filter_matches_similar_pages() {
+        let page = |path: &str| {
+            format!(
+                "<html><title>Acme</title><body><p>Sorry, {} went missing.</p></body></html>",
+                path
+            )
+        };
+
+        let probe = Url::parse("http://localhost/5f4dcc3b5aa765d61d8327deb882cf99").unwrap();
+        let filter = Soft404Filter::new(&page("5f4dcc3b5aa765d61d8327deb882cf99"), &probe);
+        assert_eq!(filter.title, "acme");
+        assert!(!filter.is_empty());
+
+        let admin = Url::parse("http://localhost/admin").unwrap();
+        assert!(filter.matches(&page("admin"), &admin));
+        assert!(!filter.matches(
+            "<html><title>Acme</title><body><form>Username Password</form></body></html>",
+            &admin
+        ));
+
+        let refresh = Soft404Filter::new(
+            r#"<meta http-equiv="refresh" content="0; url=/404.html">"#,
+            &probe,
+        );
+        assert!(refresh.matches(
+            r#"<META HTTP-EQUIV="REFRESH" CONTENT="0; URL=/404.html?admin">"#,
+            &admin
+        ));
+
+        assert!(Soft404Filter::new("", &probe).is_empty());
+    }
+
+    #[test]
+    /// similarity accounts for how many times each word appears
+    fn heuristics_similarity_compares_word_counts() {
+        let url = Url::parse("http://localhost/").unwrap();
+        let first = page_words("a b b c", &url);
+
+        assert_eq!(similarity(&first, &first), 1.0);
+        assert_eq!(similarity(&first, &page_words("a b c", &url)), 0.75);
+        assert_eq!(similarity(&first, &page_words("d", &url)), 0.0);
+    }
+
+    #[test]
+    /// words that change between two requests are left out of the comparison
+    fn heuristics_matches_except_volatile_words() {
+        let url = Url::parse("http://localhost/missing").unwrap();
+        let page = |ads: &str| format!("<p>sorry we could not locate that page</p><p>{}</p>", ads);
+
+        let mut filter = Soft404Filter::new(&page("qwe rty uio"), &url);
+        let volatile = volatile_words(&page("qwe rty uio"), &page("asd fgh jkl"), &url);
+
+        assert_eq!(volatile.len(), 6);
+        assert!(!filter.matches(&page("zxc vbn mnb"), &url));
+
+        filter.forget(&volatile);
+        let candidate = page("zxc vbn mnb");
+        let changed = volatile_words(&candidate, &page("poi lkj hgf"), &url);
+
+        assert!(filter.matches_except(&candidate, &url, &changed));
+    }
+
     #[tokio::test(core_threads = 1)]
     /// tests that given a message and transmitter, the function sends the message across the
     /// channel
@@ -0,0 +1,185 @@
+use console::{style, Color};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// A single entry of the `highlight` table in a config file; results matching every given
+/// criteria are printed in the rule's color and prefixed with its label
+///
+/// example:
+///     [[highlight]]
+///     status = [200]
+///     url = "*.sql"
+///     color = "red"
+///     label = "CRITICAL"
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct HighlightRule {
+    /// Status codes to match; any status matches when empty
+    #[serde(default)]
+    pub status: Vec<u16>,
+
+    /// Response sizes to match; any size matches when empty
+    #[serde(default)]
+    pub size: Vec<u64>,
+
+    /// Glob pattern (`*` and `?`) matched against the full url; any url matches when empty
+    #[serde(default)]
+    pub url: String,
+
+    /// Name of the color used for matching results (black, red, green, yellow, blue, magenta,
+    /// cyan, white)
+    #[serde(default)]
+    pub color: String,
+
+    /// Label printed in front of matching results
+    #[serde(default)]
+    pub label: String,
+}
+
+impl HighlightRule {
+    /// Whether or not a result with the given url, status, and size matches this rule
+    pub fn matches(&self, url: &Url, status: u16, size: u64) -> bool {
+        (self.status.is_empty() || self.status.contains(&status))
+            && (self.size.is_empty() || self.size.contains(&size))
+            && (self.url.is_empty() || glob_match(&self.url, url.as_str()))
+    }
+
+    /// Style the given report line using this rule's color and label
+    pub fn apply(&self, report: &str) -> String {
+        let line = if self.label.is_empty() {
+            report.to_string()
+        } else {
+            format!("[{}] {}", self.label, report)
+        };
+
+        match color_from_name(&self.color) {
+            Some(color) => style(line).fg(color).bold().to_string(),
+            None => line,
+        }
+    }
+}
+
+/// Find the first rule that matches a result with the given url, status, and size
+pub fn find_rule<'a>(
+    rules: &'a [HighlightRule],
+    url: &Url,
+    status: u16,
+    size: u64,
+) -> Option<&'a HighlightRule> {
+    rules.iter().find(|rule| rule.matches(url, status, size))
+}
+
+/// Translate the name of a color into a `console::Color`; unknown names result in `None`
+fn color_from_name(name: &str) -> Option<Color> {
+    match name.to_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        _ => None,
+    }
+}
+
+/// Match `text` against a glob `pattern`, where `*` matches any number of characters and `?`
+/// matches exactly one
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+
+    // position of the last `*` seen and the text position it's currently matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // let the last `*` swallow one more character and try again
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    // any trailing `*`s match the empty string
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// glob patterns support * and ? anywhere in the pattern
+    fn highlight_glob_match_handles_wildcards() {
+        assert!(glob_match("*.sql", "http://localhost/backup.sql"));
+        assert!(glob_match("*/admin/*", "http://localhost/admin/users"));
+        assert!(glob_match("*.ba?", "http://localhost/index.bak"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*.sql", "http://localhost/backup.sql.txt"));
+        assert!(!glob_match("*.ba?", "http://localhost/index.ba"));
+    }
+
+    #[test]
+    /// every given criteria must match, empty criteria match anything
+    fn highlight_rule_matches_all_criteria() {
+        let rule = HighlightRule {
+            status: vec![200],
+            url: String::from("*.sql"),
+            ..Default::default()
+        };
+
+        let sql = Url::parse("http://localhost/dump.sql").unwrap();
+        let php = Url::parse("http://localhost/index.php").unwrap();
+
+        assert!(rule.matches(&sql, 200, 1234));
+        assert!(!rule.matches(&sql, 403, 1234));
+        assert!(!rule.matches(&php, 200, 1234));
+    }
+
+    #[test]
+    /// the first matching rule wins and its label is prefixed to the report
+    fn highlight_find_rule_uses_first_match() {
+        let rules = vec![
+            HighlightRule {
+                size: vec![0],
+                label: String::from("EMPTY"),
+                ..Default::default()
+            },
+            HighlightRule {
+                label: String::from("ANY"),
+                color: String::from("Red"),
+                ..Default::default()
+            },
+        ];
+
+        let url = Url::parse("http://localhost/").unwrap();
+
+        let rule = find_rule(&rules, &url, 200, 0).unwrap();
+        assert_eq!(
+            rule.apply("200 0 http://localhost/"),
+            "[EMPTY] 200 0 http://localhost/"
+        );
+
+        let rule = find_rule(&rules, &url, 200, 10).unwrap();
+        assert_eq!(
+            rule.apply("200 10 http://localhost/"),
+            style("[ANY] 200 10 http://localhost/")
+                .fg(Color::Red)
+                .bold()
+                .to_string()
+        );
+
+        assert!(find_rule(&rules[..1], &url, 200, 10).is_none());
+    }
+}
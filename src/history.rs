@@ -0,0 +1,190 @@
+use crate::config::CONFIGURATION;
+use crate::metadata::{tag, SCAN_TAGS};
+use crate::reporter::{get_cached_file_handle, safe_file_write};
+use openssl::sha::sha256;
+use serde_json::{json, Value};
+
+/// Start of the header of each run in text output (see `--append-runs`)
+const TEXT_HEADER: &str = "# run ";
+
+/// Number of hex characters of the configuration's hash that are kept
+const HASH_LENGTH: usize = 16;
+
+/// Header recorded at the start of each run written to `--output` with `--append-runs`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunHeader {
+    /// id of the run, the same as its `scan_id` tag
+    pub run_id: String,
+
+    /// rfc3339 timestamp of when the run started
+    pub start_time: String,
+
+    /// hash of the run's configuration, the same for runs made with the same configuration
+    pub config_hash: String,
+
+    /// urls of the run's targets
+    pub targets: Vec<String>,
+}
+
+impl RunHeader {
+    /// Header of a run with the given tags (see `metadata`), configuration, and targets
+    pub fn new(tags: &serde_json::Map<String, Value>, config: &Value, targets: &[String]) -> Self {
+        let tag = |key: &str| {
+            tags.get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        Self {
+            run_id: tag("scan_id"),
+            start_time: tag("start_time"),
+            config_hash: config_hash(config),
+            targets: targets.to_vec(),
+        }
+    }
+
+    /// Header as a line of text output
+    ///
+    /// example:
+    ///     # run 6ef6b1f0-4f3c-4e3e-9a0d-2d1d6e4b3c6a | started 2026-10-15T13:58:22.785Z | config 3f2a9c0d1e4b5a6c | targets http://localhost
+    pub fn as_text(&self) -> String {
+        format!(
+            "{}{} | started {} | config {} | targets {}\n",
+            TEXT_HEADER,
+            self.run_id,
+            self.start_time,
+            self.config_hash,
+            self.targets.join(" ")
+        )
+    }
+
+    /// Header as a `run` record of json output
+    pub fn as_json(&self) -> Value {
+        json!({
+            "type": "run",
+            "run_id": self.run_id,
+            "start_time": self.start_time,
+            "config_hash": self.config_hash,
+            "targets": self.targets,
+        })
+    }
+}
+
+/// Hash of the given configuration; its keys are sorted, so the same configuration always hashes
+/// the same
+pub fn config_hash(config: &Value) -> String {
+    let digest = sha256(config.to_string().as_bytes());
+
+    digest
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>()[..HASH_LENGTH]
+        .to_string()
+}
+
+/// Whether or not the given line of an output file is the header of a run, in either format
+pub fn is_header(line: &str) -> bool {
+    if line.starts_with(TEXT_HEADER) {
+        return true;
+    }
+
+    // only records that may be headers are parsed
+    line.starts_with('{')
+        && line.contains("\"run\"")
+        && serde_json::from_str::<Value>(line)
+            .is_ok_and(|record| record["type"].as_str() == Some("run"))
+}
+
+/// Write the header of the run scanning the given targets to `--output`, when `--append-runs` was
+/// used; called before anything else is written to the file
+pub fn write_header(targets: &[String]) {
+    log::trace!("enter: write_header({:?})", targets);
+
+    if !CONFIGURATION.append_runs || CONFIGURATION.output.is_empty() {
+        log::trace!("exit: write_header");
+        return;
+    }
+
+    let header = RunHeader::new(&SCAN_TAGS, &CONFIGURATION.as_json(), targets);
+
+    let line = if CONFIGURATION.json {
+        let mut record = header.as_json();
+        tag(&mut record);
+        format!("{}\n", record)
+    } else {
+        header.as_text()
+    };
+
+    if let Some(file) = get_cached_file_handle(&CONFIGURATION.output) {
+        safe_file_write(&line, file);
+    }
+
+    log::trace!("exit: write_header");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// header of a run with fixed tags
+    fn header() -> RunHeader {
+        let tags = json!({
+            "scan_id": "6ef6b1f0",
+            "start_time": "2026-10-15T13:58:22.785Z",
+        });
+
+        RunHeader::new(
+            tags.as_object().unwrap(),
+            &json!({"threads": 50, "depth": 4}),
+            &[
+                String::from("http://one.local"),
+                String::from("http://two.local"),
+            ],
+        )
+    }
+
+    #[test]
+    /// the same configuration hashes the same, whatever the order of its keys
+    fn history_config_hash_is_stable() {
+        let a = config_hash(&json!({"threads": 50, "depth": 4}));
+        let b = config_hash(&serde_json::from_str(r#"{"depth":4,"threads":50}"#).unwrap());
+        let c = config_hash(&json!({"threads": 10, "depth": 4}));
+
+        assert_eq!(a.len(), HASH_LENGTH);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    /// headers record the run's id, start, configuration, and targets in both formats
+    fn history_header_formats() {
+        let header = header();
+
+        assert_eq!(
+            header.as_text(),
+            format!(
+                "# run 6ef6b1f0 | started 2026-10-15T13:58:22.785Z | config {} | targets http://one.local http://two.local\n",
+                header.config_hash
+            )
+        );
+
+        let record = header.as_json();
+        assert_eq!(record["type"], "run");
+        assert_eq!(record["run_id"], "6ef6b1f0");
+        assert_eq!(record["targets"][1], "http://two.local");
+    }
+
+    #[test]
+    /// headers are recognized in both formats, results aren't headers
+    fn history_is_header_recognizes_both_formats() {
+        let header = header();
+
+        assert!(is_header(header.as_text().trim_end()));
+        assert!(is_header(&header.as_json().to_string()));
+        assert!(!is_header("200 10 http://one.local/admin"));
+        assert!(!is_header(
+            r#"{"type":"response","url":"http://one.local/"}"#
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::scanner::{filter_reason, hash_filter_reason, redirect_filter_reason, soft_404_reason};
+use crate::targets::Target;
+use crate::{dynamic, rotation, schedule, signals, FeroxResponse};
+use lazy_static::lazy_static;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, Request, Response, Url};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    /// Hooks run on every request before it's sent, in the order they were added
+    static ref REQUEST_HOOKS: RwLock<Vec<Arc<dyn RequestHook>>> = RwLock::new(Vec::new());
+
+    /// Hooks run on every response that could be reported, in the order they were added; the
+    /// built-in filters come first
+    static ref RESPONSE_HOOKS: RwLock<Vec<Arc<dyn ResponseHook>>> =
+        RwLock::new(vec![
+            Arc::new(Filters),
+            Arc::new(Soft404),
+            Arc::new(BodyHashes),
+            Arc::new(Redirects),
+        ]);
+}
+
+/// Changes outgoing requests before they're sent, i.e. to add headers or sign them
+pub trait RequestHook: Send + Sync {
+    /// Change the request as needed; called right before it's sent
+    fn before_request(&self, request: &mut Request);
+}
+
+/// Inspects responses before they're reported, and may veto them
+pub trait ResponseHook: Send + Sync {
+    /// Why the response shouldn't be reported, if it shouldn't be; `None` lets it through
+    ///
+    /// Only the bodies of responses that may be directories or need to be inspected for other
+    /// reasons are read, the text of the rest is empty
+    fn veto(&self, response: &FeroxResponse) -> Option<String>;
+}
+
+/// Add a hook run on every request from here on, after the ones already added
+pub fn add_request_hook(hook: Arc<dyn RequestHook>) {
+    match REQUEST_HOOKS.write() {
+        Ok(mut hooks) => hooks.push(hook),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Add a hook run on every response from here on, after the ones already added
+pub fn add_response_hook(hook: Arc<dyn ResponseHook>) {
+    match RESPONSE_HOOKS.write() {
+        Ok(mut hooks) => hooks.push(hook),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Add a hook run on every response from here on, ahead of the ones already added, the built-in
+/// filters included; for hooks that must see responses the filters would veto
+pub fn add_first_response_hook(hook: Arc<dyn ResponseHook>) {
+    match RESPONSE_HOOKS.write() {
+        Ok(mut hooks) => hooks.insert(0, hook),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Run every request hook on the given request
+pub fn before_request(request: &mut Request) {
+    if let Ok(hooks) = REQUEST_HOOKS.read() {
+        for hook in hooks.iter() {
+            hook.before_request(request);
+        }
+    }
+}
+
+/// Run the response hooks on the given response until one vetoes it, returning why it did
+pub fn veto(response: &FeroxResponse) -> Option<String> {
+    match RESPONSE_HOOKS.read() {
+        Ok(hooks) => hooks.iter().find_map(|hook| hook.veto(response)),
+        Err(e) => {
+            log::error!("{}", e);
+            None
+        }
+    }
+}
+
+/// Request the given url with the given method after running the request hooks on it; every
+/// request goes through here
+///
+/// Requests wait here while they're paused by the schedule (see `--start-at` and
+/// `--pause-outside`) or by SIGUSR1, so that nothing at all is sent to the targets while paused
+///
+/// With `--proxy-file`, the request goes through the pool's next proxy instead of the given client,
+/// and through the ones after it while proxies can't be connected to
+pub async fn send(client: &Client, method: &Method, url: &Url) -> reqwest::Result<Response> {
+    schedule::wait().await;
+    signals::wait().await;
+
+    let mut attempts = 0;
+
+    loop {
+        let proxied = rotation::pick(url);
+        let client = proxied
+            .as_ref()
+            .map_or(client, |(_, member)| &member.client);
+
+        let mut request = client.request(method.clone(), url.to_owned()).build()?;
+
+        before_request(&mut request);
+
+        let result = client.execute(request).await;
+
+        if let Some((pool, member)) = &proxied {
+            rotation::record(pool, member, &result);
+            attempts += 1;
+
+            // a proxy that can't be connected to says nothing about the target
+            if result.as_ref().is_err_and(|e| e.is_connect()) && attempts < pool.proxies().len() {
+                continue;
+            }
+        }
+
+        return result;
+    }
+}
+
+/// Adds the configured headers to requests (see `--header`), along with the headers of the
+/// target a request belongs to (see `--targets`), which take precedence
+///
+/// Headers are only added to requests sent to the targets' origins
+#[derive(Debug, Default)]
+pub struct Headers {
+    /// headers added to every request
+    global: HeaderMap,
+
+    /// headers added to the requests under each target's url, by url
+    targets: Vec<(String, HeaderMap)>,
+
+    /// origins of the targets, i.e. `https://example.com:8443`
+    origins: Vec<String>,
+}
+
+impl Headers {
+    /// Headers for the given targets, on top of the given global headers
+    pub fn new(global: &HashMap<String, String>, targets: &[Target]) -> Self {
+        let mut headers = Self {
+            // try_into returns infallible as its error, unwrap is safe here
+            global: global.try_into().unwrap(),
+            ..Self::default()
+        };
+
+        for target in targets {
+            if let Ok(url) = Url::parse(&target.url) {
+                headers.origins.push(url.origin().ascii_serialization());
+            }
+
+            let target_headers = target.request_headers();
+
+            if !target_headers.is_empty() {
+                headers
+                    .targets
+                    .push((target.url.clone(), (&target_headers).try_into().unwrap()));
+            }
+        }
+
+        headers
+    }
+}
+
+impl RequestHook for Headers {
+    fn before_request(&self, request: &mut Request) {
+        if !self
+            .origins
+            .contains(&request.url().origin().ascii_serialization())
+        {
+            return;
+        }
+
+        // the most specific target the request belongs to, when targets are nested
+        let target = self
+            .targets
+            .iter()
+            .filter(|(url, _)| request.url().as_str().starts_with(url.as_str()))
+            .max_by_key(|(url, _)| url.len());
+
+        if let Some((_, headers)) = target {
+            for (name, value) in headers {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+
+        for (name, value) in &self.global {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+    }
+}
+
+/// Vetoes responses filtered by size (see `--sizefilter`) and wildcard responses
+struct Filters;
+
+impl ResponseHook for Filters {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        if dynamic::has_volatile_size(response.url()) {
+            // the size of the page changes from one request to the next, comparing it says
+            // nothing (see --detect-dynamic)
+            return None;
+        }
+
+        filter_reason(
+            response.status(),
+            &response.content_length(),
+            response.url(),
+        )
+        .map(String::from)
+    }
+}
+
+/// Vetoes soft 404s (see `--soft-404`)
+struct Soft404;
+
+impl ResponseHook for Soft404 {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        soft_404_reason(response).map(String::from)
+    }
+}
+
+/// Vetoes responses whose body has a known hash (see `--filter-hash`)
+struct BodyHashes;
+
+impl ResponseHook for BodyHashes {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        hash_filter_reason(response).map(String::from)
+    }
+}
+
+/// Vetoes responses that followed too many redirects, or stopped at a redirect loop (see
+/// `--filter-redirects` and `--filter-redirect-loops`)
+struct Redirects;
+
+impl ResponseHook for Redirects {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        redirect_filter_reason(response).map(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to run the given hook on a request to the given url, returning its headers
+    fn headers_for(hook: &Headers, url: &str) -> HeaderMap {
+        let mut request = Client::new().get(url).build().unwrap();
+        hook.before_request(&mut request);
+        request.headers().clone()
+    }
+
+    #[test]
+    /// targets' headers replace global ones under their urls, nothing is added on other origins
+    fn hooks_headers_follow_targets() {
+        let mut global = HashMap::new();
+        global.insert(String::from("Authorization"), String::from("global"));
+        global.insert(String::from("X-Scan"), String::from("yes"));
+
+        let mut app = Target::from_url("http://app.test/api");
+        app.headers
+            .insert(String::from("Authorization"), String::from("app"));
+
+        let hook = Headers::new(&global, &[app, Target::from_url("http://static.test")]);
+
+        let headers = headers_for(&hook, "http://app.test/api/users");
+        assert_eq!(headers["authorization"], "app");
+        assert_eq!(headers["x-scan"], "yes");
+
+        let headers = headers_for(&hook, "http://app.test/favicon.ico");
+        assert_eq!(headers["authorization"], "global");
+
+        let headers = headers_for(&hook, "http://static.test/js/");
+        assert_eq!(headers["authorization"], "global");
+
+        assert!(headers_for(&hook, "http://elsewhere.test/").is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+use crate::FeroxResult;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Url;
+use std::collections::{HashMap, HashSet};
+use std::fs::read_to_string;
+use std::sync::RwLock;
+
+lazy_static! {
+    /// Urls explored during the imported session (see `--import`); they're skipped by the scan
+    static ref KNOWN: RwLock<Known> = RwLock::new(Known::default());
+
+    /// Single `<item>` of a Burp sitemap
+    static ref BURP_ITEM: Regex = Regex::new(r"(?s)<item>(.*?)</item>").unwrap();
+
+    /// `<url>` of a Burp sitemap item
+    static ref BURP_URL: Regex = Regex::new(r"(?s)<url>(.*?)</url>").unwrap();
+
+    /// `<status>` of a Burp sitemap item, empty when the item was never requested
+    static ref BURP_STATUS: Regex = Regex::new(r"<status>\s*(\d+)\s*</status>").unwrap();
+
+    /// Regex of the urls a ZAP context includes
+    static ref ZAP_INCLUDED: Regex = Regex::new(r"(?s)<incregexes>(.*?)</incregexes>").unwrap();
+
+    /// Regex of the urls a ZAP context excludes
+    static ref ZAP_EXCLUDED: Regex = Regex::new(r"(?s)<excregexes>(.*?)</excregexes>").unwrap();
+}
+
+/// What was learned from a Burp sitemap or a ZAP context
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Imported {
+    /// urls to scan, in the order they were first seen
+    pub targets: Vec<String>,
+
+    /// urls already requested during the imported session
+    pub known: Vec<String>,
+
+    /// regex of the urls excluded from the imported session's scope
+    pub excluded: Vec<String>,
+}
+
+/// Urls known from the imported session, and the ones excluded from its scope
+#[derive(Debug, Default)]
+struct Known {
+    /// known urls without their trailing slash, mapped to whether or not they're directories
+    urls: HashMap<String, bool>,
+
+    /// urls matching any of these are out of scope
+    excluded: Vec<Regex>,
+}
+
+/// Url without its trailing slash, so that `/admin` and `/admin/` are one and the same
+fn normalize(url: &str) -> &str {
+    url.trim_end_matches('/')
+}
+
+/// Unescape the text of an xml element, which may be wrapped in a CDATA section
+fn unescape(text: &str) -> String {
+    let text = text.trim();
+
+    if let Some(data) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|data| data.strip_suffix("]]>"))
+    {
+        return data.to_string();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Url scans of the given url start from, i.e. `https://example.com/` for
+/// `https://example.com/admin?id=1`
+fn origin(url: &Url) -> String {
+    let mut origin = url.clone();
+    origin.set_path("/");
+    origin.set_query(None);
+    origin.set_fragment(None);
+    origin.to_string()
+}
+
+/// Targets (one per host) and known urls from a Burp sitemap, exported with "Save selected items"
+///
+/// Items that were never requested (their `<status>` is empty) still seed their target, but
+/// aren't known, since nothing was learned about them
+fn parse_burp(contents: &str) -> Imported {
+    let mut imported = Imported::default();
+    let mut targets = HashSet::new();
+
+    for item in BURP_ITEM.captures_iter(contents) {
+        let item = &item[1];
+
+        let url = match BURP_URL
+            .captures(item)
+            .and_then(|url| Url::parse(&unescape(&url[1])).ok())
+        {
+            Some(url) => url,
+            None => continue,
+        };
+
+        let target = origin(&url);
+
+        if targets.insert(target.clone()) {
+            imported.targets.push(target);
+        }
+
+        if BURP_STATUS.is_match(item) {
+            imported.known.push(url.to_string());
+        }
+    }
+
+    imported
+}
+
+/// Translate a java regex, as found in ZAP contexts, into one understood by the regex crate;
+/// `\Q...\E` quotes its contents
+fn translate(pattern: &str) -> String {
+    let mut translated = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find(r"\Q") {
+        translated.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+
+        let end = rest.find(r"\E").unwrap_or(rest.len());
+        translated.push_str(&regex::escape(&rest[..end]));
+        rest = &rest[(end + 2).min(rest.len())..];
+    }
+
+    translated.push_str(rest);
+    translated
+}
+
+/// Literal url the given regex of included urls starts with, i.e. `https://example.com/app` for
+/// `\Qhttps://example.com/app\E.*` or `https://example.com/app.*`; `None` when it doesn't start
+/// with a url
+fn literal_prefix(pattern: &str) -> Option<String> {
+    let translated = translate(pattern);
+    let mut prefix = String::new();
+    let mut chars = translated.trim_start_matches('^').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) if !escaped.is_alphanumeric() => prefix.push(escaped),
+                _ => break,
+            },
+            // an unescaped dot is a dot more often than not, unless it's repeated
+            '.' if !matches!(chars.peek(), Some('*') | Some('+') | Some('?') | Some('{')) => {
+                prefix.push(c)
+            }
+            '.' | '*' | '+' | '?' | '{' | '(' | '[' | '|' | '$' => break,
+            _ => prefix.push(c),
+        }
+    }
+
+    let url = Url::parse(&prefix).ok()?;
+
+    if url.scheme() == "http" || url.scheme() == "https" {
+        Some(prefix)
+    } else {
+        None
+    }
+}
+
+/// Targets and excluded urls of a ZAP context, exported with "Export Context"; contexts don't
+/// keep track of the urls requested, so none are known
+fn parse_zap(contents: &str) -> Imported {
+    let mut imported = Imported::default();
+
+    for included in ZAP_INCLUDED.captures_iter(contents) {
+        let pattern = unescape(&included[1]);
+
+        match literal_prefix(&pattern) {
+            Some(target) if !imported.targets.contains(&target) => imported.targets.push(target),
+            Some(_) => {}
+            None => log::warn!("Could not find a url to scan in {}", pattern),
+        }
+    }
+
+    for excluded in ZAP_EXCLUDED.captures_iter(contents) {
+        imported.excluded.push(translate(&unescape(&excluded[1])));
+    }
+
+    imported
+}
+
+/// Parse the contents of a Burp sitemap or a ZAP context, telling them apart by their root element
+pub fn parse(contents: &str) -> FeroxResult<Imported> {
+    let imported = if contents.contains("<items") {
+        parse_burp(contents)
+    } else if contents.contains("<context>") {
+        parse_zap(contents)
+    } else {
+        return Err("not a Burp sitemap or a ZAP context".into());
+    };
+
+    if imported.targets.is_empty() {
+        return Err("no urls to scan".into());
+    }
+
+    Ok(imported)
+}
+
+/// Read the Burp sitemap or ZAP context given with `--import`
+pub fn from_file(path: &str) -> FeroxResult<Imported> {
+    log::trace!("enter: from_file({})", path);
+
+    let imported = parse(&read_to_string(path)?)?;
+
+    log::info!(
+        "Imported {} targets and {} known urls from {}",
+        imported.targets.len(),
+        imported.known.len(),
+        path
+    );
+    log::trace!("exit: from_file -> {:?}", imported);
+
+    Ok(imported)
+}
+
+/// Remember the urls known from the imported session and the ones out of its scope, so that the
+/// scan skips them; fails when an excluded url's regex doesn't compile
+pub fn remember(imported: &Imported) -> FeroxResult<()> {
+    log::trace!("enter: remember({:?})", imported);
+
+    let excluded = imported
+        .excluded
+        .iter()
+        .map(|pattern| Regex::new(&format!("^(?:{})$", pattern)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Ok(mut known) = KNOWN.write() {
+        for url in &imported.known {
+            let directory = url.ends_with('/');
+            let entry = known.urls.entry(normalize(url).to_string()).or_default();
+            *entry |= directory;
+        }
+
+        known.excluded.extend(excluded);
+    }
+
+    log::trace!("exit: remember");
+    Ok(())
+}
+
+/// Whether the given url was requested during the imported session or is out of its scope, in
+/// which case it isn't requested again
+pub fn is_known(url: &Url) -> bool {
+    match KNOWN.read() {
+        Ok(known) => {
+            known.urls.contains_key(normalize(url.as_str()))
+                || known
+                    .excluded
+                    .iter()
+                    .any(|excluded| excluded.is_match(url.as_str()))
+        }
+        Err(_) => false,
+    }
+}
+
+/// The given url as a directory, when it's a directory known from the imported session; known
+/// directories are still scanned, without requesting them again
+pub fn known_directory(url: &Url) -> Option<Url> {
+    let known = KNOWN.read().ok()?;
+
+    if !known.urls.get(normalize(url.as_str())).copied()? {
+        return None;
+    }
+
+    Url::parse(&format!("{}/", normalize(url.as_str()))).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// one target per host, only requested items are known
+    fn import_parses_burp_sitemaps() {
+        let contents = r#"<?xml version="1.0"?>
+            <!DOCTYPE items [<!ELEMENT items (item*)>]>
+            <items burpVersion="2021.2" exportTime="Mon Mar 01 12:00:00 UTC 2021">
+              <item>
+                <url><![CDATA[https://app.example.com/admin/]]></url>
+                <host ip="10.0.0.1">app.example.com</host>
+                <path><![CDATA[/admin/]]></path>
+                <status>200</status>
+                <response base64="true"><![CDATA[SFRUUC8xLjEgMjAwIE9L]]></response>
+              </item>
+              <item>
+                <url>https://app.example.com/search?q=a&amp;page=2</url>
+                <status>200</status>
+              </item>
+              <item>
+                <url><![CDATA[http://static.example.com:8080/app.js]]></url>
+                <status></status>
+              </item>
+            </items>"#;
+
+        let imported = parse(contents).unwrap();
+
+        assert_eq!(
+            imported.targets,
+            vec![
+                "https://app.example.com/",
+                "http://static.example.com:8080/"
+            ]
+        );
+        assert_eq!(
+            imported.known,
+            vec![
+                "https://app.example.com/admin/",
+                "https://app.example.com/search?q=a&page=2"
+            ]
+        );
+        assert!(imported.excluded.is_empty());
+    }
+
+    #[test]
+    /// included urls are targets, quoted or not, excluded urls are translated to regex
+    fn import_parses_zap_contexts() {
+        let contents = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+            <configuration>
+              <context>
+                <name>app</name>
+                <inscope>true</inscope>
+                <incregexes>\Qhttps://app.example.com/shop\E.*</incregexes>
+                <incregexes>http://static.example.com.*</incregexes>
+                <incregexes>.*\.internal.*</incregexes>
+                <excregexes>\Qhttps://app.example.com/shop/logout\E.*</excregexes>
+              </context>
+            </configuration>"#;
+
+        let imported = parse(contents).unwrap();
+
+        assert_eq!(
+            imported.targets,
+            vec!["https://app.example.com/shop", "http://static.example.com"]
+        );
+        assert!(imported.known.is_empty());
+        assert_eq!(
+            imported.excluded,
+            vec![r"https://app\.example\.com/shop/logout.*"]
+        );
+
+        assert!(parse("<html></html>").is_err());
+        assert!(parse("<items></items>").is_err());
+    }
+
+    #[test]
+    /// known urls are skipped with or without their trailing slash, only directories are scanned
+    fn import_skips_known_and_excluded_urls() {
+        let imported = Imported {
+            targets: vec![String::from("https://known.example.com/")],
+            known: vec![
+                String::from("https://known.example.com/admin/"),
+                String::from("https://known.example.com/login.php"),
+            ],
+            excluded: vec![String::from(r"https://known\.example\.com/logout.*")],
+        };
+        remember(&imported).unwrap();
+
+        let url = |path: &str| Url::parse(&format!("https://known.example.com{}", path)).unwrap();
+
+        assert!(is_known(&url("/admin")));
+        assert!(is_known(&url("/login.php")));
+        assert!(is_known(&url("/logout?all=1")));
+        assert!(!is_known(&url("/backup")));
+
+        assert_eq!(known_directory(&url("/admin")), Some(url("/admin/")));
+        assert_eq!(known_directory(&url("/login.php")), None);
+        assert_eq!(known_directory(&url("/backup")), None);
+
+        let invalid = Imported {
+            excluded: vec![String::from("(")],
+            ..Imported::default()
+        };
+        assert!(remember(&invalid).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::config::CONFIGURATION;
+use crate::utils::{module_colorizer, status_colorizer};
+use crate::xml::escape;
+use regex::Regex;
+use reqwest::Url;
+use std::process;
+
+/// Status codes a status class (i.e. `2xx`) covers
+fn class(status: &str) -> Option<(u16, u16)> {
+    let digit = status.strip_suffix("xx")?.parse::<u16>().ok()?;
+
+    if (1..=5).contains(&digit) {
+        Some((digit * 100, digit * 100 + 99))
+    } else {
+        None
+    }
+}
+
+/// Statuses of `STATUS,...`, where each is a status code or a class (i.e. `2xx`)
+fn statuses(value: &str) -> Option<Vec<(u16, u16)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .map(|status| {
+            class(status).or_else(|| {
+                status
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|code| (100..=599).contains(code))
+                    .map(|code| (code, code))
+            })
+        })
+        .collect()
+}
+
+/// A check on the reported results that either passes or fails, reported as a test case (see
+/// `--policy`)
+///
+/// The policy fails when a result whose path matches `pattern` is reported with one of
+/// `statuses`
+#[derive(Debug, Clone)]
+pub struct Policy {
+    /// name of the policy, i.e. `no 200s under /admin`
+    pub name: String,
+
+    /// paths the policy applies to
+    pattern: Regex,
+
+    /// ranges of statuses that violate the policy
+    statuses: Vec<(u16, u16)>,
+}
+
+impl Policy {
+    /// Parse `NAME=REGEX[=STATUS,...]`; results with a 2xx status violate the policy when no
+    /// status is given
+    pub fn parse(policy: &str) -> Result<Self, String> {
+        let mut split = policy.splitn(2, '=');
+        let name = split.next().unwrap_or_default().trim();
+        let rest = split.next().unwrap_or_default();
+
+        if name.is_empty() || rest.is_empty() {
+            return Err(String::from("not of the form NAME=REGEX[=STATUS,...]"));
+        }
+
+        // the regex itself may contain =, only what follows the last one may be statuses
+        let (pattern, statuses) = match rest.rfind('=') {
+            Some(position) => match statuses(&rest[position + 1..]) {
+                Some(statuses) => (&rest[..position], statuses),
+                None => (rest, vec![(200, 299)]),
+            },
+            None => (rest, vec![(200, 299)]),
+        };
+
+        let pattern =
+            Regex::new(pattern).map_err(|e| format!("{} isn't a valid regex: {}", pattern, e))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            pattern,
+            statuses,
+        })
+    }
+
+    /// Whether or not the given result violates the policy
+    pub fn violated_by(&self, url: &Url, status: u16) -> bool {
+        self.statuses
+            .iter()
+            .any(|(low, high)| (*low..=*high).contains(&status))
+            && self.pattern.is_match(url.path())
+    }
+}
+
+/// Result that violated a policy
+#[derive(Debug, Clone)]
+struct Violation {
+    /// url of the response
+    url: Url,
+
+    /// status code of the response
+    status: u16,
+}
+
+/// Outcome of each policy given with `--policy`, rendered as a JUnit xml document once the scan
+/// completes (see `--junit-output`)
+///
+/// Each policy is a test case, which fails with the results that violated it
+///
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <testsuites name="feroxbuster" tests="2" failures="1">
+///   <testsuite name="feroxbuster" tests="2" failures="1" errors="0" skipped="0">
+///     <properties>
+///       <property name="scan_id" value="..."/>
+///     </properties>
+///     <testcase name="no 200s under /admin" classname="feroxbuster.policy">
+///       <failure message="1 result violates the policy" type="policy">200 http://localhost/admin/users</failure>
+///     </testcase>
+///     <testcase name="no .git exposure" classname="feroxbuster.policy"/>
+///   </testsuite>
+/// </testsuites>
+/// ```
+#[derive(Debug, Default)]
+pub struct JunitReport {
+    /// checked policies along with the results that violated them
+    policies: Vec<(Policy, Vec<Violation>)>,
+}
+
+impl JunitReport {
+    /// Report of the given policies, none of which are violated yet
+    pub fn new(policies: Vec<Policy>) -> Self {
+        Self {
+            policies: policies
+                .into_iter()
+                .map(|policy| (policy, Vec::new()))
+                .collect(),
+        }
+    }
+
+    /// Check a reported result against every policy
+    pub fn insert(&mut self, url: &Url, status: u16) {
+        for (policy, violations) in self.policies.iter_mut() {
+            if policy.violated_by(url, status) {
+                violations.push(Violation {
+                    url: url.clone(),
+                    status,
+                });
+            }
+        }
+    }
+
+    /// Number of policies that were violated
+    pub fn failures(&self) -> usize {
+        self.policies
+            .iter()
+            .filter(|(_, violations)| !violations.is_empty())
+            .count()
+    }
+
+    /// Render the document; `tags` are the key/value pairs attached to the scan (see `metadata`)
+    pub fn render(&self, tags: &[(String, String)]) -> String {
+        let tests = self.policies.len();
+        let failures = self.failures();
+
+        let mut document = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        document.push_str(&format!(
+            "<testsuites name=\"feroxbuster\" tests=\"{}\" failures=\"{}\">\n",
+            tests, failures
+        ));
+        document.push_str(&format!(
+            "  <testsuite name=\"feroxbuster\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+            tests, failures
+        ));
+
+        if !tags.is_empty() {
+            document.push_str("    <properties>\n");
+
+            for (key, value) in tags {
+                document.push_str(&format!(
+                    "      <property name=\"{}\" value=\"{}\"/>\n",
+                    escape(key),
+                    escape(value)
+                ));
+            }
+
+            document.push_str("    </properties>\n");
+        }
+
+        for (policy, violations) in &self.policies {
+            let name = escape(&policy.name);
+
+            if violations.is_empty() {
+                document.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"feroxbuster.policy\"/>\n",
+                    name
+                ));
+                continue;
+            }
+
+            let results: Vec<String> = violations
+                .iter()
+                .map(|violation| escape(&format!("{} {}", violation.status, violation.url)))
+                .collect();
+
+            document.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"feroxbuster.policy\">\n",
+                name
+            ));
+            document.push_str(&format!(
+                "      <failure message=\"{} result{} violate{} the policy\" type=\"policy\">{}</failure>\n",
+                violations.len(),
+                if violations.len() == 1 { "" } else { "s" },
+                if violations.len() == 1 { "s" } else { "" },
+                results.join("\n")
+            ));
+            document.push_str("    </testcase>\n");
+        }
+
+        document.push_str("  </testsuite>\n");
+        document.push_str("</testsuites>\n");
+
+        document
+    }
+}
+
+/// Policies given with `--policy`; exits when one can't be parsed
+pub fn policies() -> Vec<Policy> {
+    log::trace!("enter: policies");
+
+    let policies = CONFIGURATION
+        .policies
+        .iter()
+        .map(|policy| Policy::parse(policy).map_err(|e| format!("{}: {}", policy, e)))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "{} {} Could not parse --policy {}",
+                status_colorizer("ERROR"),
+                module_colorizer("junit::policies"),
+                e
+            );
+            process::exit(1);
+        });
+
+    log::trace!("exit: policies -> {:?}", policies);
+    policies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// url of the given path
+    fn url(path: &str) -> Url {
+        Url::parse("http://localhost").unwrap().join(path).unwrap()
+    }
+
+    #[test]
+    /// statuses are optional and may be classes, the regex may contain =
+    fn junit_policy_parse_splits_name_regex_and_statuses() {
+        let admin = Policy::parse("no 200s under /admin=^/admin(/|$)=200").unwrap();
+        assert_eq!(admin.name, "no 200s under /admin");
+        assert!(admin.violated_by(&url("/admin/users"), 200));
+        assert!(!admin.violated_by(&url("/admin/users"), 403));
+        assert!(!admin.violated_by(&url("/administrator"), 200));
+
+        let git = Policy::parse("no .git exposure=/\\.git(/|$)").unwrap();
+        assert!(git.violated_by(&url("/app/.git/HEAD"), 206));
+        assert!(!git.violated_by(&url("/app/.git/HEAD"), 301));
+
+        let query = Policy::parse("no debug=debug=1").unwrap();
+        assert!(query.violated_by(&url("/debug=1"), 200));
+
+        let classes = Policy::parse("no errors=.=4xx,500").unwrap();
+        assert!(classes.violated_by(&url("/a"), 404));
+        assert!(classes.violated_by(&url("/a"), 500));
+        assert!(!classes.violated_by(&url("/a"), 502));
+
+        assert!(Policy::parse("nameless").is_err());
+        assert!(Policy::parse("=^/admin").is_err());
+        assert!(Policy::parse("bad regex=(").is_err());
+    }
+
+    #[test]
+    /// each policy is a test case, failing with the results that violated it
+    fn junit_render_reports_policies_as_test_cases() {
+        let mut report = JunitReport::new(vec![
+            Policy::parse("no 200s under /admin=^/admin/=200").unwrap(),
+            Policy::parse("no .git exposure=/\\.git(/|$)").unwrap(),
+        ]);
+
+        report.insert(&url("/admin/users"), 200);
+        report.insert(&url("/admin/a&b"), 200);
+        report.insert(&url("/admin/login"), 302);
+        assert_eq!(report.failures(), 1);
+
+        let tags = vec![(String::from("scan_id"), String::from("abc"))];
+        let document = report.render(&tags);
+
+        assert!(document.contains("<testsuites name=\"feroxbuster\" tests=\"2\" failures=\"1\">"));
+        assert!(document.contains("<property name=\"scan_id\" value=\"abc\"/>"));
+        assert!(document.contains(
+            "<failure message=\"2 results violate the policy\" type=\"policy\">200 http://localhost/admin/users\n200 http://localhost/admin/a&amp;b</failure>"
+        ));
+        assert!(document
+            .contains("<testcase name=\"no .git exposure\" classname=\"feroxbuster.policy\"/>"));
+    }
+}
@@ -0,0 +1,87 @@
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+/// Content-Length of a response that doesn't match the number of bytes received for its body,
+/// i.e. because a middlebox or the server cut the connection short
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LengthMismatch {
+    /// length given by the response's Content-Length header
+    pub declared: u64,
+
+    /// number of bytes received for the response's body
+    pub received: u64,
+}
+
+impl LengthMismatch {
+    /// Compare the declared length of a response with the bytes received for its body
+    ///
+    /// Responses without a Content-Length, and responses that never have a body (1xx, 204, 304),
+    /// can't mismatch
+    pub fn check(status: StatusCode, declared: Option<u64>, received: u64) -> Option<Self> {
+        let declared = declared?;
+
+        if status.is_informational()
+            || status == StatusCode::NO_CONTENT
+            || status == StatusCode::NOT_MODIFIED
+            || declared == received
+        {
+            return None;
+        }
+
+        Some(Self { declared, received })
+    }
+
+    /// Whether or not fewer bytes than declared were received
+    pub fn is_truncated(&self) -> bool {
+        self.received < self.declared
+    }
+
+    /// Description of the mismatch, as shown next to a result; i.e. `truncated: 512 of 1024
+    /// bytes`
+    pub fn describe(&self) -> String {
+        if self.is_truncated() {
+            format!("truncated: {} of {} bytes", self.received, self.declared)
+        } else {
+            format!(
+                "length mismatch: {} bytes, {} declared",
+                self.received, self.declared
+            )
+        }
+    }
+
+    /// Create the `length_mismatch` entry of a result's `--json` record
+    pub fn as_json(&self) -> Value {
+        json!({
+            "declared": self.declared,
+            "received": self.received,
+            "truncated": self.is_truncated(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// only responses with a declared length that can have a body are compared
+    fn length_check_compares_declared_length() {
+        assert_eq!(LengthMismatch::check(StatusCode::OK, None, 10), None);
+        assert_eq!(LengthMismatch::check(StatusCode::OK, Some(10), 10), None);
+        assert_eq!(
+            LengthMismatch::check(StatusCode::NOT_MODIFIED, Some(10), 0),
+            None
+        );
+
+        let truncated = LengthMismatch::check(StatusCode::OK, Some(1024), 512).unwrap();
+        assert!(truncated.is_truncated());
+        assert_eq!(truncated.describe(), "truncated: 512 of 1024 bytes");
+
+        let overlong = LengthMismatch::check(StatusCode::NOT_FOUND, Some(10), 20).unwrap();
+        assert!(!overlong.is_truncated());
+        assert_eq!(
+            overlong.describe(),
+            "length mismatch: 20 bytes, 10 declared"
+        );
+    }
+}
@@ -1,17 +1,98 @@
+pub mod ban;
 pub mod banner;
+pub mod bloom;
+pub mod budget;
+pub mod buffers;
+pub mod cache;
+pub mod canary;
+pub mod catalog;
+pub mod challenge;
 pub mod client;
+pub mod compare;
+pub mod completions;
+pub mod concurrency;
 pub mod config;
+pub mod coordinator;
+pub mod digest;
+pub mod duplicates;
+pub mod dynamic;
+pub mod email;
+pub mod encryption;
+pub mod engine;
+pub mod error_log;
+pub mod evasion;
+pub mod events;
+pub mod exit_codes;
+pub mod export;
 pub mod extractor;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fingerprint;
+pub mod forbidden;
+pub mod grpc;
+pub mod gzip;
 pub mod heuristics;
+pub mod highlight;
+pub mod history;
+pub mod hooks;
+pub mod import;
+pub mod junit;
+pub mod length;
+pub mod limiter;
+pub mod listing;
 pub mod logger;
+pub mod login;
+pub mod markers;
+pub mod metadata;
+pub mod methods;
+pub mod negotiate;
+pub mod notifier;
+pub mod ntlm;
+pub mod oauth;
 pub mod parser;
 pub mod progress;
+pub mod proxy;
+pub mod redirects;
+pub mod replay;
 pub mod reporter;
+pub mod responses;
+pub mod robots;
+pub mod rollover;
+pub mod rotation;
+pub mod sarif;
 pub mod scanner;
+pub mod schedule;
+pub mod screenshot;
+pub mod security;
+pub mod server;
+pub mod signals;
+pub mod sigv4;
+pub mod slash;
+pub mod state;
+pub mod statistics;
+pub mod statsd;
+pub mod summary;
+pub mod syslog;
+pub mod targets;
+pub mod template;
+pub mod traps;
+pub mod tree;
+pub mod tui;
+pub mod update;
 pub mod utils;
+pub mod verify;
+pub mod wordlist;
+pub mod xml;
 
+use config::CONFIGURATION;
+use digest::BodyHash;
+use length::LengthMismatch;
+use markers::Markers;
+use redirects::Chain;
 use reqwest::header::HeaderMap;
 use reqwest::{Response, StatusCode, Url};
+use serde_json::{json, Value};
+use statistics::STATISTICS;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 /// Generic Result type to ease error handling in async contexts
@@ -24,11 +105,16 @@ pub type FeroxChannel<T> = (UnboundedSender<T>, UnboundedReceiver<T>);
 /// Version pulled from Cargo.toml at compile time
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Output of `git describe` for the commit the binary was built from, set by build.rs
+pub const GIT_DESCRIBE: &str = env!("FEROX_GIT_DESCRIBE");
+
 /// Default wordlist to use when `-w|--wordlist` isn't specified and not `wordlist` isn't set
 /// in a [ferox-config.toml](constant.DEFAULT_CONFIG_NAME.html) config file.
 ///
 /// defaults to kali's default install location:
 /// - `/usr/share/seclists/Discovery/Web-Content/raft-medium-directories.txt`
+///
+/// when it doesn't exist, the [embedded wordlist](wordlist/constant.EMBEDDED.html) is used instead
 pub const DEFAULT_WORDLIST: &str =
     "/usr/share/seclists/Discovery/Web-Content/raft-medium-directories.txt";
 
@@ -61,7 +147,7 @@ pub const DEFAULT_STATUS_CODES: [StatusCode; 9] = [
 pub const DEFAULT_CONFIG_NAME: &str = "ferox-config.toml";
 
 /// A `FeroxResponse`, derived from a `Response` to a submitted `Request`
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FeroxResponse {
     /// The final `Url` of this `FeroxResponse`
     url: Url,
@@ -77,6 +163,22 @@ pub struct FeroxResponse {
 
     /// The `Headers` of this `FeroxResponse`
     headers: HeaderMap,
+
+    /// What the response's text was found to be, searched once when the response was received
+    markers: Markers,
+
+    /// Hash of the response's body, only computed with `--body-hash`
+    body_hash: Option<BodyHash>,
+
+    /// Shodan-compatible hash of the response's body, only computed for favicons
+    favicon_hash: Option<i32>,
+
+    /// Content-Length that doesn't match the bytes received, only checked when the body was read
+    length_mismatch: Option<LengthMismatch>,
+
+    /// Redirects followed to get this `FeroxResponse`, only recorded with `--redirects`; boxed to
+    /// keep responses that weren't redirected small
+    redirects: Option<Box<Chain>>,
 }
 
 /// `FeroxResponse` implementation
@@ -96,11 +198,68 @@ impl FeroxResponse {
         &self.text
     }
 
+    /// Get what the response's text was found to be, i.e. a directory listing; nothing is found in
+    /// responses whose body wasn't read
+    pub fn markers(&self) -> &Markers {
+        &self.markers
+    }
+
+    /// Get the hash of the response's body, if it was hashed (see `--body-hash`)
+    pub fn body_hash(&self) -> Option<&BodyHash> {
+        self.body_hash.as_ref()
+    }
+
+    /// Get the hash of the response's body, if it's a favicon; the same hash is searchable on
+    /// Shodan, i.e. `http.favicon.hash:116323821`
+    pub fn favicon_hash(&self) -> Option<i32> {
+        self.favicon_hash
+    }
+
+    /// Get the mismatch between the response's Content-Length and the bytes received for its
+    /// body, if there was one; only responses whose body was read are checked
+    pub fn length_mismatch(&self) -> Option<LengthMismatch> {
+        self.length_mismatch
+    }
+
+    /// Get the redirects followed to get this response, if it was redirected (see `--redirects`)
+    pub fn redirects(&self) -> Option<&Chain> {
+        self.redirects.as_deref()
+    }
+
+    /// Set the redirects followed to get this response
+    pub fn set_redirects(&mut self, redirects: Option<Chain>) {
+        self.redirects = redirects.map(Box::new);
+    }
+
     /// Get the `Headers` of this `FeroxResponse`
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
+    /// Get the values of the given headers that are present in this `FeroxResponse`, in the
+    /// order given; repeated headers have their values joined by a comma
+    ///
+    /// `names` are expected to be lowercase, same as the names stored in a `HeaderMap`
+    pub fn selected_headers(&self, names: &[String]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .filter_map(|name| {
+                let values: Vec<String> = self
+                    .headers
+                    .get_all(name.as_str())
+                    .iter()
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+                    .collect();
+
+                if values.is_empty() {
+                    None
+                } else {
+                    Some((name.to_string(), values.join(", ")))
+                }
+            })
+            .collect()
+    }
+
     /// Get the content-length of this response, if known
     pub fn content_length(&self) -> u64 {
         self.content_length
@@ -108,7 +267,7 @@ impl FeroxResponse {
 
     /// Set `FeroxResponse`'s `url` attribute, has no affect if an error occurs
     pub fn set_url(&mut self, url: &str) {
-        match Url::parse(&url) {
+        match Url::parse(url) {
             Ok(url) => {
                 self.url = url;
             }
@@ -126,8 +285,8 @@ impl FeroxResponse {
     /// Additionally, inspects query parameters, as they're also often indicative of a file
     pub fn is_file(&self) -> bool {
         let has_extension = match self.url.path_segments() {
-            Some(path) => {
-                if let Some(last) = path.last() {
+            Some(mut path) => {
+                if let Some(last) = path.next_back() {
                     last.contains('.') // last segment has some sort of extension, probably
                 } else {
                     false
@@ -139,39 +298,127 @@ impl FeroxResponse {
         self.url.query_pairs().count() > 0 || has_extension
     }
 
+    /// Create a `response` record representing this `FeroxResponse`, used by `--json` output;
+    /// with `--body-hash`, the body's hash is recorded under the name of its algorithm, a favicon's
+    /// hash is recorded as `favicon_hash`, a Content-Length that doesn't match the body as
+    /// `length_mismatch`, and the redirects followed as `redirects`
+    pub fn as_json(&self) -> Value {
+        let mut record = json!({
+            "type": "response",
+            "url": self.url.as_str(),
+            "path": self.url.path(),
+            "status": self.status.as_u16(),
+            "content_length": self.content_length,
+        });
+
+        if let Some(body_hash) = &self.body_hash {
+            record[body_hash.algorithm] = Value::from(body_hash.hex.as_str());
+        }
+
+        if let Some(favicon_hash) = self.favicon_hash {
+            record["favicon_hash"] = Value::from(favicon_hash);
+        }
+
+        if let Some(length_mismatch) = self.length_mismatch {
+            record["length_mismatch"] = length_mismatch.as_json();
+        }
+
+        if let Some(redirects) = &self.redirects {
+            record["redirects"] = redirects.as_json();
+        }
+
+        record
+    }
+
     /// Create a new `FeroxResponse` from the given `Response`
     pub async fn from(response: Response, read_body: bool) -> Self {
         let url = response.url().clone();
         let status = response.status();
         let headers = response.headers().clone();
-        let content_length = response.content_length().unwrap_or(0);
-
-        let text = if read_body {
-            // .text() consumes the response, must be called last
-            // additionally, --extract-links is currently the only place we use the body of the
-            // response, so we forego the processing if not performing extraction
-            match response.text().await {
-                // await the response's body
-                Ok(text) => text,
-                Err(e) => {
+        let declared = response.content_length();
+        let content_length = declared.unwrap_or(0);
+
+        // the declared length is compared with what was received on the wire, not with a result
+        // from the response cache
+        let received_status = status;
+
+        // with --response-cache, an unchanged finding of a previous run stands in for its 304
+        let (status, content_length) =
+            cache::revalidate(&url, status).unwrap_or((status, content_length));
+
+        let (text, body_hash, favicon_hash, length_mismatch) = if read_body {
+            // reading the body consumes the response, must be called last
+            // additionally, only a few options make use of the body of the response (see
+            // Configuration::needs_body), so we forego the processing when none are in use
+            let body = budget::read_body(response).await;
+
+            // a body cut short by --body-budget was cut on purpose
+            let length_mismatch = if body.over_budget {
+                None
+            } else {
+                LengthMismatch::check(received_status, declared, body.bytes.len() as u64)
+            };
+
+            if length_mismatch.is_some() {
+                STATISTICS.add_length_mismatch();
+            }
+
+            match body.error {
+                Some(e) => {
                     log::error!("Could not parse body from response: {}", e);
-                    String::new()
+                    buffers::give(body.bytes);
+                    (String::new(), None, None, length_mismatch)
+                }
+                // the response's body is hashed as received, then always decoded as utf-8,
+                // unlike Response::text
+                None => {
+                    let body = body.bytes;
+                    let body_hash = digest::hash_body(&CONFIGURATION.body_hash, &body);
+
+                    let favicon_hash =
+                        if status.is_success() && !body.is_empty() && fingerprint::is_favicon(&url)
+                        {
+                            Some(fingerprint::favicon_hash(&body))
+                        } else {
+                            None
+                        };
+
+                    (
+                        buffers::into_text(body),
+                        body_hash,
+                        favicon_hash,
+                        length_mismatch,
+                    )
                 }
             }
         } else {
-            String::new()
+            (String::new(), None, None, None)
         };
 
+        let markers = Markers::of(&text);
+
         FeroxResponse {
             url,
             status,
             content_length,
             text,
             headers,
+            markers,
+            body_hash,
+            favicon_hash,
+            length_mismatch,
+            redirects: None,
         }
     }
 }
 
+impl Drop for FeroxResponse {
+    fn drop(&mut self) {
+        // the body's buffer is reused to read another response's body
+        buffers::give(std::mem::take(&mut self.text).into_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,4 +443,132 @@ mod tests {
     fn default_version() {
         assert_eq!(VERSION, env!("CARGO_PKG_VERSION"));
     }
+
+    #[test]
+    /// json records contain the type, url, path, status, and size of a response
+    fn ferox_response_as_json() {
+        let response = FeroxResponse {
+            url: Url::parse("http://localhost/stuff?things=1").unwrap(),
+            status: StatusCode::FORBIDDEN,
+            text: String::new(),
+            content_length: 42,
+            headers: HeaderMap::new(),
+            markers: Markers::default(),
+            body_hash: None,
+            favicon_hash: None,
+            length_mismatch: None,
+            redirects: None,
+        };
+
+        let record = response.as_json();
+
+        assert_eq!(record["type"], "response");
+        assert_eq!(record["url"], "http://localhost/stuff?things=1");
+        assert_eq!(record["path"], "/stuff");
+        assert_eq!(record["status"], 403);
+        assert_eq!(record["content_length"], 42);
+        assert!(record.get("sha256").is_none());
+    }
+
+    #[test]
+    /// json records contain the body's hash under the name of its algorithm, when hashed
+    fn ferox_response_as_json_records_body_hash() {
+        let response = FeroxResponse {
+            url: Url::parse("http://localhost/").unwrap(),
+            status: StatusCode::OK,
+            text: String::new(),
+            content_length: 0,
+            headers: HeaderMap::new(),
+            markers: Markers::default(),
+            body_hash: digest::hash_body("md5", b""),
+            favicon_hash: None,
+            length_mismatch: None,
+            redirects: None,
+        };
+
+        let record = response.as_json();
+
+        assert_eq!(record["md5"], "d41d8cd98f00b204e9800998ecf8427e");
+        assert!(record.get("favicon_hash").is_none());
+    }
+
+    #[test]
+    /// json records contain the hash of a favicon, when hashed
+    fn ferox_response_as_json_records_favicon_hash() {
+        let response = FeroxResponse {
+            url: Url::parse("http://localhost/favicon.ico").unwrap(),
+            status: StatusCode::OK,
+            text: String::new(),
+            content_length: 0,
+            headers: HeaderMap::new(),
+            markers: Markers::default(),
+            body_hash: None,
+            favicon_hash: Some(116323821),
+            length_mismatch: None,
+            redirects: None,
+        };
+
+        let record = response.as_json();
+
+        assert_eq!(record["favicon_hash"], 116323821);
+    }
+
+    #[test]
+    /// json records contain the declared and received lengths of a body that didn't match
+    fn ferox_response_as_json_records_length_mismatch() {
+        let response = FeroxResponse {
+            url: Url::parse("http://localhost/").unwrap(),
+            status: StatusCode::OK,
+            text: String::new(),
+            content_length: 1024,
+            headers: HeaderMap::new(),
+            markers: Markers::default(),
+            body_hash: None,
+            favicon_hash: None,
+            length_mismatch: LengthMismatch::check(StatusCode::OK, Some(1024), 512),
+            redirects: None,
+        };
+
+        let record = response.as_json();
+
+        assert_eq!(record["length_mismatch"]["declared"], 1024);
+        assert_eq!(record["length_mismatch"]["received"], 512);
+        assert_eq!(record["length_mismatch"]["truncated"], true);
+    }
+
+    #[test]
+    /// only headers that are present are selected, in the order requested
+    fn ferox_response_selected_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("server", "nginx".parse().unwrap());
+        headers.append("set-cookie", "a=1".parse().unwrap());
+        headers.append("set-cookie", "b=2".parse().unwrap());
+
+        let response = FeroxResponse {
+            url: Url::parse("http://localhost/").unwrap(),
+            status: StatusCode::OK,
+            text: String::new(),
+            content_length: 0,
+            headers,
+            markers: Markers::default(),
+            body_hash: None,
+            favicon_hash: None,
+            length_mismatch: None,
+            redirects: None,
+        };
+
+        let names = vec![
+            String::from("set-cookie"),
+            String::from("location"),
+            String::from("server"),
+        ];
+
+        assert_eq!(
+            response.selected_headers(&names),
+            vec![
+                (String::from("set-cookie"), String::from("a=1, b=2")),
+                (String::from("server"), String::from("nginx")),
+            ]
+        );
+    }
 }
@@ -0,0 +1,162 @@
+use crate::config::CONFIGURATION;
+use crate::schedule;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{delay_until, Instant};
+
+/// Requests allowed each second by every adjustable limiter, set while scanning from the dashboard
+/// (see `--tui`); 0 when the limit isn't adjusted
+static ADJUSTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Limit every adjustable limiter to `per_second` requests each second, in place of the limits
+/// they were created with; 0 puts those limits back
+pub fn adjust(per_second: usize) {
+    ADJUSTED.store(per_second, Ordering::Relaxed);
+}
+
+/// Requests allowed each second by every adjustable limiter, 0 when the limit isn't adjusted
+pub fn adjusted() -> usize {
+    ADJUSTED.load(Ordering::Relaxed)
+}
+
+/// Spaces requests out evenly so that no more than a given number are made each second, no
+/// matter how many tasks share the limiter
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// requests allowed each second, 0 for unlimited
+    per_second: usize,
+
+    /// whether the rate of the rate profile in effect replaces `per_second` (see `--rate-profile`)
+    scheduled: bool,
+
+    /// whether a limit set with `adjust` replaces `per_second` and the rate profile
+    adjustable: bool,
+
+    /// earliest time at which the next request may be made
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `per_second` requests each second; `None` when `per_second` is
+    /// 0, i.e. unlimited
+    pub fn new(per_second: usize) -> Option<Self> {
+        if per_second == 0 {
+            return None;
+        }
+
+        Some(Self {
+            per_second,
+            scheduled: false,
+            adjustable: false,
+            next: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Create a limiter allowing `per_second` requests each second, unless one of the rate
+    /// profiles is in effect, whose rate is used instead (see `--rate-profile`); `None` when
+    /// neither limits anything, unless the limit may be adjusted from the dashboard (see `--tui`)
+    ///
+    /// The limiter is adjustable (see `adjust`)
+    pub fn scheduled(per_second: usize) -> Option<Self> {
+        if per_second == 0 && CONFIGURATION.rate_profiles.is_empty() && !CONFIGURATION.tui {
+            return None;
+        }
+
+        Some(Self {
+            per_second,
+            scheduled: true,
+            adjustable: true,
+            next: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Make the limiter adjustable (see `adjust`)
+    pub fn adjustable(mut self) -> Self {
+        self.adjustable = true;
+        self
+    }
+
+    /// Time between two requests, `None` when they're unlimited
+    fn interval(&self) -> Option<Duration> {
+        let per_second = if self.adjustable && adjusted() > 0 {
+            adjusted()
+        } else if self.scheduled {
+            schedule::profile_rate().unwrap_or(self.per_second)
+        } else {
+            self.per_second
+        };
+
+        if per_second == 0 {
+            return None;
+        }
+
+        Some(Duration::from_secs(1) / per_second as u32)
+    }
+
+    /// Wait until the caller's request may be made
+    pub async fn wait(&self) {
+        let interval = match self.interval() {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        let scheduled = match self.next.lock() {
+            Ok(mut next) => {
+                let scheduled = (*next).max(Instant::now());
+                *next = scheduled + interval;
+                scheduled
+            }
+            Err(e) => {
+                // poisoned lock
+                log::error!("{}", e);
+                return;
+            }
+        };
+
+        delay_until(scheduled).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// a rate of zero means no limiter at all
+    fn limiter_new_with_zero_is_unlimited() {
+        assert!(RateLimiter::new(0).is_none());
+        assert_eq!(
+            RateLimiter::new(50).unwrap().interval(),
+            Some(Duration::from_millis(20))
+        );
+    }
+
+    #[test]
+    /// an adjusted limit only replaces the limit of adjustable limiters, until it's put back
+    fn limiter_adjust_only_applies_to_adjustable_limiters() {
+        let fixed = RateLimiter::new(50).unwrap();
+        let adjustable = RateLimiter::new(50).unwrap().adjustable();
+
+        adjust(10);
+        assert_eq!(fixed.interval(), Some(Duration::from_millis(20)));
+        assert_eq!(adjustable.interval(), Some(Duration::from_millis(100)));
+
+        adjust(0);
+        assert_eq!(adjustable.interval(), Some(Duration::from_millis(20)));
+    }
+
+    #[tokio::test(core_threads = 1)]
+    /// five requests at 20 per second are spaced 50ms apart, the first one isn't delayed
+    async fn limiter_wait_spaces_requests() {
+        let limiter = RateLimiter::new(20).unwrap();
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.wait().await;
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(start.elapsed() < Duration::from_millis(1000));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::markers::Markers;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Url;
+
+/// markers of the index pages served by Apache, nginx, lighttpd, IIS, and python's http.server,
+/// see `markers::Markers`
+pub(crate) const LISTING_PATTERN: &str =
+    r"(?is)<title>\s*(index of /|directory listing for /)|\[to parent directory\]";
+
+lazy_static! {
+    /// value of an `href` attribute
+    static ref HREF_REGEX: Regex = Regex::new(r#"(?i)href\s*=\s*["']?([^"'\s>]+)"#).unwrap();
+}
+
+/// Whether or not the given page is an open directory listing
+pub fn is_listing(body: &str) -> bool {
+    Markers::of(body).listing
+}
+
+/// Urls of the entries of the given directory listing, served for `url`; only the directory's
+/// immediate children are returned, which skips links to parent directories and the listing's
+/// sorting links (i.e. `?C=N;O=D`)
+///
+/// Subdirectories end with a `/`, files don't
+pub fn entries(body: &str, url: &Url) -> Vec<Url> {
+    log::trace!("enter: entries(body[{} bytes...], {})", body.len(), url);
+
+    let mut directory = url.clone();
+    directory.set_query(None);
+
+    if !directory.path().ends_with('/') {
+        let path = format!("{}/", directory.path());
+        directory.set_path(&path);
+    }
+
+    let mut entries: Vec<Url> = Vec::new();
+
+    for capture in HREF_REGEX.captures_iter(body) {
+        let entry = match directory.join(&capture[1]) {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+
+        if entry.host_str() != directory.host_str()
+            || entry.query().is_some()
+            || !entry.path().starts_with(directory.path())
+        {
+            continue;
+        }
+
+        let name = entry.path()[directory.path().len()..].trim_end_matches('/');
+
+        if name.is_empty() || name.contains('/') || entries.contains(&entry) {
+            continue;
+        }
+
+        entries.push(entry);
+    }
+
+    log::trace!("exit: entries -> {:?}", entries);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// index pages of common web servers are listings, other pages aren't
+    fn listing_is_listing_recognizes_index_pages() {
+        assert!(is_listing(
+            "<html><head><title>Index of /files</title></head>"
+        ));
+        assert!(is_listing("<title>\n Index of /</title>"));
+        assert!(is_listing("<title>Directory listing for /admin/</title>"));
+        assert!(is_listing(
+            r#"<pre><A HREF="/">[To Parent Directory]</A><br><br>"#
+        ));
+        assert!(!is_listing("<title>Admin</title><p>Index of /files</p>"));
+        assert!(!is_listing(""));
+    }
+
+    #[test]
+    /// only the directory's immediate children are entries, once each
+    fn listing_entries_returns_immediate_children() {
+        let body = r#"
+            <a href="?C=N;O=D">Name</a>
+            <a href="/">Parent Directory</a>
+            <a href="backup.zip">backup.zip</a>
+            <a href='old/'>old/</a>
+            <a href="/files/notes.txt">notes.txt</a>
+            <a href="http://elsewhere.com/files/x">x</a>
+            <a href="old/deeper/">deeper</a>
+            <A HREF="backup.zip">again</A>
+        "#;
+        let url = Url::parse("http://localhost/files?sort=1").unwrap();
+
+        let entries: Vec<String> = entries(body, &url)
+            .iter()
+            .map(|entry| entry.to_string())
+            .collect();
+
+        assert_eq!(
+            entries,
+            [
+                "http://localhost/files/backup.zip",
+                "http://localhost/files/old/",
+                "http://localhost/files/notes.txt",
+            ]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::metadata::tag;
 use crate::reporter::{get_cached_file_handle, safe_file_write};
+use crate::syslog::{get_cached_syslog_writer, send_log, Severity};
+use crate::tui;
 use console::{style, Color};
 use env_logger::Builder;
+use serde_json::json;
 use std::env;
 use std::time::Instant;
 
@@ -39,7 +43,14 @@ pub fn initialize(verbosity: u8) {
     //
     // The workaround was to have a RwLock around the file and allow both the logger and the
     // file handler to both write independent of each other.
-    let locked_file = get_cached_file_handle(&CONFIGURATION.output);
+    let locked_file = if CONFIGURATION.output_errors && CONFIGURATION.format != "sarif" {
+        // --output-errors used, otherwise the output file is reserved for results; a sarif log
+        // is written whole once the scan completes
+        get_cached_file_handle(&CONFIGURATION.output)
+    } else {
+        None
+    };
+    let syslog = get_cached_syslog_writer(&CONFIGURATION.syslog);
 
     builder
         .format(move |_, record| {
@@ -61,10 +72,43 @@ pub fn initialize(verbosity: u8) {
                 style(record.args()).dim(),
             );
 
-            PROGRESS_PRINTER.println(&msg);
+            if tui::is_active() {
+                tui::print(&msg);
+            } else if !CONFIGURATION.silent {
+                // --silent keeps stdout clean for other tools; log files are still written
+                PROGRESS_PRINTER.println(&msg);
+            }
 
             if let Some(buffered_file) = locked_file.clone() {
-                safe_file_write(&msg, buffered_file);
+                if CONFIGURATION.json {
+                    // --json used, log messages are written as `log` records
+                    let mut log_record = json!({
+                        "type": "log",
+                        "level": level_name,
+                        "time_offset": t,
+                        "module": record.target(),
+                        "message": record.args().to_string(),
+                    });
+
+                    tag(&mut log_record);
+
+                    safe_file_write(&format!("{}\n", log_record), buffered_file);
+                } else {
+                    safe_file_write(&msg, buffered_file);
+                }
+            }
+
+            if let Some(syslog) = syslog {
+                // only problems are forwarded, findings are sent by the reporter
+                match level {
+                    log::Level::Error => {
+                        send_log(syslog, Severity::Error, &record.args().to_string())
+                    }
+                    log::Level::Warn => {
+                        send_log(syslog, Severity::Warning, &record.args().to_string())
+                    }
+                    _ => {}
+                }
             }
 
             Ok(())
@@ -0,0 +1,366 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::hooks::{self, RequestHook, ResponseHook};
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE, LOCATION, SET_COOKIE};
+use reqwest::{Request, Url};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// how often the session is checked for a logout, in milliseconds
+const LOGOUT_CHECK_MS: u64 = 1000;
+
+lazy_static! {
+    /// Session cookies set by the login, by name, in the order they were first set
+    static ref SESSION: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+}
+
+/// Whether or not a response said the session was logged out since the last login
+static LOGGED_OUT: AtomicBool = AtomicBool::new(false);
+
+/// Name and value of the cookie set by the given `Set-Cookie` value, i.e. `("PHPSESSID", "abc")`
+/// for `PHPSESSID=abc; path=/; HttpOnly`; its attributes are ignored
+pub fn parse_set_cookie(value: &str) -> Option<(String, String)> {
+    let pair = value.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name.to_string(), value.trim().trim_matches('"').to_string()))
+}
+
+/// Store the cookies set by the given headers in the session; a cookie set again replaces the
+/// previous value, one set to an empty value is removed
+fn store_cookies(headers: &HeaderMap) {
+    let cookies: Vec<(String, String)> = headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| parse_set_cookie(&String::from_utf8_lossy(value.as_bytes())))
+        .collect();
+
+    match SESSION.write() {
+        Ok(mut session) => {
+            for (name, value) in cookies {
+                session.retain(|(stored, _)| *stored != name);
+
+                if !value.is_empty() {
+                    session.push((name, value));
+                }
+            }
+        }
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// The session's cookies appended to the given `Cookie` header value, if there's one
+fn cookie_header(existing: Option<&str>) -> Option<String> {
+    let session = SESSION.read().ok()?;
+
+    if session.is_empty() {
+        return None;
+    }
+
+    let cookies = session
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    match existing {
+        Some(existing) if !existing.is_empty() => Some(format!("{}; {}", existing, cookies)),
+        _ => Some(cookies),
+    }
+}
+
+/// Whether or not the given `Location` of a response to `url` sends it back to the login form,
+/// which is taken to mean the session was logged out
+pub fn is_logout_redirect(url: &Url, location: &str, login_url: &Url) -> bool {
+    match url.join(location) {
+        Ok(target) => {
+            target.origin() == login_url.origin()
+                && target.path().trim_end_matches('/') == login_url.path().trim_end_matches('/')
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether or not the login's response means it succeeded: its body or `Location` matches
+/// `--login-success-regex` when given, any 2xx or 3xx otherwise
+pub fn is_success(status: u16, location: &str, body: &str, success: Option<&Regex>) -> bool {
+    match success {
+        Some(regex) => regex.is_match(body) || regex.is_match(location),
+        None => (200..400).contains(&status),
+    }
+}
+
+/// Adds the session's cookies to the requests sent to the login form's origin
+struct SessionCookies {
+    /// origin of the login form, i.e. `https://example.com:8443`
+    origin: String,
+}
+
+impl RequestHook for SessionCookies {
+    fn before_request(&self, request: &mut Request) {
+        if request.url().origin().ascii_serialization() != self.origin {
+            return;
+        }
+
+        let existing = request
+            .headers()
+            .get(COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+
+        if let Some(cookies) = cookie_header(existing.as_deref()) {
+            if let Ok(value) = HeaderValue::from_str(&cookies) {
+                request.headers_mut().insert(COOKIE, value);
+            }
+        }
+    }
+}
+
+/// Vetoes redirects back to the login form, and flags the session as logged out
+struct LoggedOut {
+    /// url of the login form
+    login_url: Url,
+}
+
+impl ResponseHook for LoggedOut {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        if !response.status().is_redirection() {
+            return None;
+        }
+
+        let location = response.headers().get(LOCATION)?.to_str().ok()?;
+
+        if !is_logout_redirect(response.url(), location, &self.login_url) {
+            return None;
+        }
+
+        LOGGED_OUT.store(true, Ordering::Relaxed);
+        Some(String::from("logged out"))
+    }
+}
+
+/// Post `--login-data` to the login form, storing the cookies it sets; returns the status of the
+/// response when the login succeeded, why it didn't otherwise
+async fn login(url: &Url, success: Option<&Regex>) -> Result<u16, String> {
+    log::trace!("enter: login({}, {:?})", url, success);
+
+    let mut request = CONFIGURATION
+        .client
+        .post(url.to_owned())
+        .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .body(CONFIGURATION.login_data.clone())
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    hooks::before_request(&mut request);
+
+    let response = CONFIGURATION
+        .client
+        .execute(request)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status().as_u16();
+
+    store_cookies(response.headers());
+
+    let location = response
+        .headers()
+        .get(LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let body = response.text().await.unwrap_or_default();
+
+    let result = if is_success(status, &location, &body, success) {
+        Ok(status)
+    } else if success.is_some() {
+        Err(format!(
+            "{} response didn't match --login-success-regex",
+            status
+        ))
+    } else {
+        Err(format!("login was answered with a {}", status))
+    };
+
+    log::trace!("exit: login -> {:?}", result);
+    result
+}
+
+/// Print the given line about the session
+fn alert(msg: &str) {
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(msg, &PROGRESS_PRINTER);
+    }
+}
+
+/// Log in once, ahead of the connectivity test, then spawn the task that logs in again whenever
+/// the session is logged out (see `--login-url`); returns the transmitter used to stop the task
+/// along with the task's JoinHandle to be awaited, or `None` without `--login-url`
+///
+/// A failed first login exits, as scanning without the session would be pointless
+pub async fn initialize() -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.login_url.is_empty() {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let fail = |msg: String| -> ! {
+        eprintln!(
+            "{} {} {}",
+            status_colorizer("ERROR"),
+            module_colorizer("login::initialize"),
+            msg
+        );
+        process::exit(1);
+    };
+
+    let url = Url::parse(&CONFIGURATION.login_url).unwrap_or_else(|e| {
+        fail(format!(
+            "Could not parse login url {}: {}",
+            CONFIGURATION.login_url, e
+        ))
+    });
+
+    let success = if CONFIGURATION.login_success_regex.is_empty() {
+        None
+    } else {
+        Some(
+            Regex::new(&CONFIGURATION.login_success_regex).unwrap_or_else(|e| {
+                fail(format!(
+                    "Could not compile --login-success-regex {}: {}",
+                    CONFIGURATION.login_success_regex, e
+                ))
+            }),
+        )
+    };
+
+    hooks::add_request_hook(Arc::new(SessionCookies {
+        origin: url.origin().ascii_serialization(),
+    }));
+
+    if let Err(e) = login(&url, success.as_ref()).await {
+        fail(format!("Could not log in at {}: {}", url, e));
+    }
+
+    log::info!("Logged in at {}", url);
+
+    // redirects back to the login form look alike, they'd be vetoed as wildcards before this
+    hooks::add_first_response_hook(Arc::new(LoggedOut {
+        login_url: url.clone(),
+    }));
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let relogger = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(LOGOUT_CHECK_MS));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if !LOGGED_OUT.swap(false, Ordering::Relaxed) {
+                        continue;
+                    }
+
+                    let msg = match login(&url, success.as_ref()).await {
+                        Ok(status) => format!(
+                            "{} {:>10} Logged out, logged in again at {} ({})\n",
+                            status_colorizer("AUT"),
+                            "-",
+                            url,
+                            status_colorizer(&status.to_string())
+                        ),
+                        Err(e) => format!(
+                            "{} {:>10} Logged out, could not log in again at {}: {}\n",
+                            status_colorizer("ERR"),
+                            "-",
+                            url,
+                            e
+                        ),
+                    };
+
+                    log::warn!("{}", console::strip_ansi_codes(msg.trim_end()));
+                    alert(&msg);
+                }
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, relogger);
+    Some((tx_stop, relogger))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// only the name and value of a cookie are kept, cookies without a name are ignored
+    fn login_parse_set_cookie_ignores_attributes() {
+        assert_eq!(
+            parse_set_cookie("PHPSESSID=abc123; path=/; HttpOnly"),
+            Some((String::from("PHPSESSID"), String::from("abc123")))
+        );
+        assert_eq!(
+            parse_set_cookie("token=\"a=b\""),
+            Some((String::from("token"), String::from("a=b")))
+        );
+        assert_eq!(parse_set_cookie("=abc; path=/"), None);
+        assert_eq!(parse_set_cookie("HttpOnly"), None);
+    }
+
+    #[test]
+    /// relative and absolute redirects to the login form are logouts, other redirects aren't
+    fn login_is_logout_redirect_resolves_location() {
+        let url = Url::parse("http://localhost/admin/users").unwrap();
+        let login_url = Url::parse("http://localhost/login.php").unwrap();
+
+        assert!(is_logout_redirect(
+            &url,
+            "/login.php?next=/admin",
+            &login_url
+        ));
+        assert!(is_logout_redirect(&url, "../login.php", &login_url));
+        assert!(is_logout_redirect(
+            &url,
+            "http://localhost/login.php",
+            &login_url
+        ));
+        assert!(!is_logout_redirect(&url, "/admin/users/", &login_url));
+        assert!(!is_logout_redirect(
+            &url,
+            "http://sso.localhost/login.php",
+            &login_url
+        ));
+    }
+
+    #[test]
+    /// the regex is matched against the body and location when given, the status otherwise
+    fn login_is_success_uses_regex_or_status() {
+        let regex = Regex::new("dashboard").unwrap();
+
+        assert!(is_success(302, "/dashboard", "", Some(&regex)));
+        assert!(is_success(200, "", "<h1>dashboard</h1>", Some(&regex)));
+        assert!(!is_success(200, "", "invalid password", Some(&regex)));
+        assert!(is_success(302, "", "", None));
+        assert!(!is_success(401, "", "", None));
+    }
+}
@@ -1,126 +1,114 @@
-use feroxbuster::config::{CONFIGURATION, PROGRESS_PRINTER};
-use feroxbuster::scanner::scan_url;
-use feroxbuster::utils::{ferox_print, get_current_depth, module_colorizer, status_colorizer};
-use feroxbuster::{banner, heuristics, logger, reporter, FeroxResponse, FeroxResult, VERSION};
+use feroxbuster::config::{CONFIGURATION, PROGRESS_PRINTER, PROGRESS_TOTAL};
+use feroxbuster::statistics::STATISTICS;
+use feroxbuster::targets::{self, Target};
+use feroxbuster::utils::{ferox_print, module_colorizer, status_colorizer};
+use feroxbuster::{catalog, compare, coordinator, email, engine, import, server, update};
+use feroxbuster::{encryption, exit_codes, logger, reporter, state, syslog, tui, FeroxResult};
 use futures::StreamExt;
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{stderr, BufRead, BufReader};
 use std::process;
-use std::sync::Arc;
 use tokio::io;
-use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::codec::{FramedRead, LinesCodec};
 
-/// Create a HashSet of Strings from the given wordlist then stores it inside an Arc
-fn get_unique_words_from_wordlist(path: &str) -> FeroxResult<Arc<HashSet<String>>> {
-    log::trace!("enter: get_unique_words_from_wordlist({})", path);
+/// Gather the targets given with `--url`, `--stdin`, `--targets`, and `--import`; the urls known
+/// from `--import` are remembered so that the scan skips them, and the state given with
+/// `--resume-from` is scanning its own targets when none are given
+async fn get_targets() -> FeroxResult<Vec<Target>> {
+    log::trace!("enter: get_targets");
 
-    let file = match File::open(&path) {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!(
-                "{} {} {}",
-                status_colorizer("ERROR"),
-                module_colorizer("main::get_unique_words_from_wordlist"),
-                e
-            );
-            log::error!("Could not open wordlist: {}", e);
-            log::trace!("exit: get_unique_words_from_wordlist -> {}", e);
+    let mut targets = vec![];
+
+    if CONFIGURATION.stdin {
+        // got targets from stdin, i.e. cat sites | ./feroxbuster ...
+        // just need to read the targets from stdin and spawn a future for each target found
+        let stdin = io::stdin(); // tokio's stdin, not std
+        let mut reader = FramedRead::new(stdin, LinesCodec::new());
 
-            return Err(Box::new(e));
+        while let Some(line) = reader.next().await {
+            targets.push(Target::from_url(&line?));
         }
-    };
+    } else if !CONFIGURATION.target_url.is_empty() {
+        targets.push(Target::from_url(&CONFIGURATION.target_url));
+    }
 
-    let reader = BufReader::new(file);
+    if !CONFIGURATION.targets.is_empty() {
+        let listed = targets::from_file(&CONFIGURATION.targets).map_err(|e| {
+            format!(
+                "Could not read targets from {}: {}",
+                CONFIGURATION.targets, e
+            )
+        })?;
+        targets.extend(listed);
+    }
 
-    let mut words = HashSet::new();
+    if !CONFIGURATION.import.is_empty() {
+        let imported = import::from_file(&CONFIGURATION.import)
+            .map_err(|e| format!("Could not import {}: {}", CONFIGURATION.import, e))?;
 
-    for line in reader.lines() {
-        let result = line?;
+        for url in &imported.targets {
+            // a host given with --url as well is only scanned once
+            let given = targets
+                .iter()
+                .any(|target| target.url.trim_end_matches('/') == url.trim_end_matches('/'));
 
-        if result.starts_with('#') || result.is_empty() {
-            continue;
+            if !given {
+                targets.push(Target::from_url(url));
+            }
         }
 
-        words.insert(result);
+        import::remember(&imported)?;
     }
 
-    log::trace!(
-        "exit: get_unique_words_from_wordlist -> Arc<wordlist[{} words...]>",
-        words.len()
-    );
-
-    Ok(Arc::new(words))
-}
-
-/// Determine whether it's a single url scan or urls are coming from stdin, then scan as needed
-async fn scan(
-    targets: Vec<String>,
-    tx_term: UnboundedSender<FeroxResponse>,
-    tx_file: UnboundedSender<String>,
-) -> FeroxResult<()> {
-    log::trace!("enter: scan({:?}, {:?}, {:?})", targets, tx_term, tx_file);
-    // cloning an Arc is cheap (it's basically a pointer into the heap)
-    // so that will allow for cheap/safe sharing of a single wordlist across multi-target scans
-    // as well as additional directories found as part of recursion
-    let words =
-        tokio::spawn(async move { get_unique_words_from_wordlist(&CONFIGURATION.wordlist) })
-            .await??;
-
-    if words.len() == 0 {
-        eprintln!(
-            "{} {} Did not find any words in {}",
-            status_colorizer("ERROR"),
-            module_colorizer("main::scan"),
-            CONFIGURATION.wordlist
-        );
-        process::exit(1);
+    if targets.is_empty() && !CONFIGURATION.resume_from.is_empty() {
+        let saved = state::saved_targets(&CONFIGURATION.resume_from).map_err(|e| {
+            format!(
+                "Could not read targets from {}: {}",
+                CONFIGURATION.resume_from, e
+            )
+        })?;
+        targets.extend(saved.iter().map(|url| Target::from_url(url)));
     }
 
-    let mut tasks = vec![];
+    log::trace!("exit: get_targets -> {:?}", targets);
+
+    Ok(targets)
+}
 
-    for target in targets {
-        let word_clone = words.clone();
-        let term_clone = tx_term.clone();
-        let file_clone = tx_file.clone();
+/// Handle Ctrl+C: the `--state-file` is saved, output files are flushed and finished (see
+/// `--encrypt-output`), and the `--email-report` is sent, then feroxbuster exits with
+/// `exit_codes::CANCELLED` under `--exit-on cancelled`, or `exit_codes::INTERRUPTED` otherwise;
+/// only used with any of them, or with the dashboard (see `--tui`), which is closed first
+fn spawn_cancellation_handler() {
+    log::trace!("enter: spawn_cancellation_handler");
 
-        let task = tokio::spawn(async move {
-            let base_depth = get_current_depth(&target);
-            scan_url(&target, word_clone, base_depth, term_clone, file_clone).await;
-        });
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            // --tui used, the terminal is put back and what the dashboard replaced is printed
+            tui::close();
 
-        tasks.push(task);
-    }
+            log::warn!("Scan cancelled, exiting...");
 
-    // drive execution of all accumulated futures
-    futures::future::join_all(tasks).await;
-    log::trace!("exit: scan");
+            // --state-file used, the progress made so far is saved
+            state::save();
 
-    Ok(())
-}
+            // buffered results are flushed, and encrypted files are only complete once finished,
+            // i.e. before they're attached to the email report
+            reporter::close_files();
 
-async fn get_targets() -> FeroxResult<Vec<String>> {
-    log::trace!("enter: get_targets");
+            if email::is_enabled() {
+                email::report(email::Outcome::Cancelled).await;
+            }
 
-    let mut targets = vec![];
+            syslog::close();
 
-    if CONFIGURATION.stdin {
-        // got targets from stdin, i.e. cat sites | ./feroxbuster ...
-        // just need to read the targets from stdin and spawn a future for each target found
-        let stdin = io::stdin(); // tokio's stdin, not std
-        let mut reader = FramedRead::new(stdin, LinesCodec::new());
+            if exit_codes::exit_on(&CONFIGURATION.exit_on, "cancelled") {
+                process::exit(exit_codes::CANCELLED);
+            }
 
-        while let Some(line) = reader.next().await {
-            targets.push(line?);
+            process::exit(exit_codes::INTERRUPTED);
         }
-    } else {
-        targets.push(CONFIGURATION.target_url.clone());
-    }
-
-    log::trace!("exit: get_targets -> {:?}", targets);
+    });
 
-    Ok(targets)
+    log::trace!("exit: spawn_cancellation_handler");
 }
 
 #[tokio::main]
@@ -130,12 +118,72 @@ async fn main() {
 
     // can't trace main until after logger is initialized
     log::trace!("enter: main");
-    log::debug!("{:#?}", *CONFIGURATION);
+    log::debug!("{:#?}", CONFIGURATION.redacted());
+
+    if !CONFIGURATION.wordlists.is_empty() {
+        // feroxbuster wordlists; manages the curated wordlists instead of scanning
+        if let Err(e) = catalog::run(&CONFIGURATION.wordlists).await {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("catalog::run"),
+                e
+            );
+            process::exit(1);
+        }
 
-    let save_output = !CONFIGURATION.output.is_empty(); // was -o used?
+        log::trace!("exit: main");
+        return;
+    }
+
+    if CONFIGURATION.update {
+        // feroxbuster --update; replaces this binary with the latest release instead of scanning
+        if let Err(e) = update::run().await {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("update::run"),
+                e
+            );
+            process::exit(1);
+        }
+
+        log::trace!("exit: main");
+        return;
+    }
+
+    if !CONFIGURATION.compare_runs.is_empty() {
+        // feroxbuster compare; diffs two result files instead of scanning
+        if let Err(e) = compare::run(&CONFIGURATION.compare_runs) {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("compare::run"),
+                e
+            );
+            process::exit(1);
+        }
+
+        log::trace!("exit: main");
+        return;
+    }
+
+    if !CONFIGURATION.serve.is_empty() {
+        // feroxbuster serve (or --role worker); every scan submitted runs in a feroxbuster process
+        // of its own
+        if let Err(e) = server::serve(&CONFIGURATION.serve, &CONFIGURATION.api_token).await {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("server::serve"),
+                e
+            );
+            process::exit(1);
+        }
 
-    let (tx_term, tx_file, term_handle, file_handle) =
-        reporter::initialize(&CONFIGURATION.output, save_output);
+        log::trace!("exit: main");
+        return;
+    }
 
     // get targets from command line or stdin
     let targets = match get_targets().await {
@@ -156,58 +204,73 @@ async fn main() {
         }
     };
 
-    if !CONFIGURATION.quiet {
-        // only print banner if -q isn't used
-        let std_stderr = stderr(); // std::io::stderr
-        banner::initialize(&targets, &CONFIGURATION, &VERSION, std_stderr).await;
+    let urls: Vec<String> = targets.iter().map(|target| target.url.clone()).collect();
+    email::initialize(&urls);
+
+    if exit_codes::exit_on(&CONFIGURATION.exit_on, "cancelled")
+        || email::is_enabled()
+        || encryption::is_enabled()
+        || !CONFIGURATION.state_file.is_empty()
+        || CONFIGURATION.tui
+    {
+        spawn_cancellation_handler();
     }
 
-    // discard non-responsive targets
-    let live_targets = heuristics::connectivity_test(&targets).await;
+    // --tui used, the dashboard replaces the banner, progress bars, and printed results
+    tui::start();
 
-    // kick off a scan against any targets determined to be responsive
-    match scan(live_targets, tx_term.clone(), tx_file.clone()).await {
-        Ok(_) => {
-            log::info!("All scans complete!");
-        }
-        Err(e) => log::error!("An error occurred: {}", e),
+    // --role coordinator shards the scan across workers instead of scanning from here
+    let (ran, function) = if CONFIGURATION.role == "coordinator" {
+        (coordinator::run(targets).await, "coordinator::run")
+    } else {
+        (engine::run(targets).await, "engine::run")
     };
 
-    // manually drop tx in order for the rx task's while loops to eval to false
-    drop(tx_term);
-    log::trace!("dropped terminal output handler's transmitter");
+    // everything the dashboard replaced is printed from here on
+    tui::close();
 
-    log::trace!("awaiting terminal output handler's receiver");
-    // after dropping tx, we can await the future where rx lived
-    match term_handle.await {
-        Ok(_) => {}
+    let summary = match ran {
+        Ok(summary) => summary,
         Err(e) => {
-            log::error!("error awaiting terminal output handler's receiver: {}", e);
-        }
-    }
-    log::trace!("done awaiting terminal output handler's receiver");
-
-    log::trace!("tx_file: {:?}", tx_file);
-    // the same drop/await process used on the terminal handler is repeated for the file handler
-    // we drop the file transmitter every time, because it's created no matter what
-    drop(tx_file);
-
-    log::trace!("dropped file output handler's transmitter");
-    if save_output {
-        // but we only await if -o was specified
-        log::trace!("awaiting file output handler's receiver");
-        match file_handle.unwrap().await {
-            Ok(_) => {}
-            Err(e) => {
-                log::error!("error awaiting file output handler's receiver: {}", e);
-            }
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer(function),
+                e
+            );
+
+            reporter::close_files();
+            email::report(email::Outcome::Failed(e.to_string())).await;
+            syslog::close();
+            process::exit(1);
         }
-        log::trace!("done awaiting file output handler's receiver");
-    }
+    };
 
-    log::trace!("exit: main");
+    let exit_code = exit_codes::determine(
+        &CONFIGURATION.exit_on,
+        CONFIGURATION.error_threshold,
+        STATISTICS.errors(),
+        summary.unreachable,
+        STATISTICS.reported(),
+    );
+
+    log::trace!("exit: main -> {}", exit_code);
+
+    // encrypted files are only complete once finished, after the last message that may be logged
+    reporter::close_files();
+
+    // sent once the files attached to it are complete
+    email::report(email::Outcome::Complete).await;
+
+    // anything logged up to here still reaches the syslog collector
+    syslog::close();
 
     // clean-up function for the MultiProgress bar; must be called last in order to still see
     // the final trace message above
+    PROGRESS_TOTAL.finish();
     PROGRESS_PRINTER.finish();
+
+    if exit_code != exit_codes::SUCCESS {
+        process::exit(exit_code);
+    }
 }
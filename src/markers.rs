@@ -0,0 +1,60 @@
+use crate::ban::CAPTCHA_PATTERN;
+use crate::listing::LISTING_PATTERN;
+use lazy_static::lazy_static;
+use regex::RegexSet;
+
+/// index of the CAPTCHA pattern in `MARKERS`
+const CAPTCHA: usize = 0;
+
+/// index of the directory listing pattern in `MARKERS`
+const LISTING: usize = 1;
+
+lazy_static! {
+    /// every pattern looked for in response bodies, matched together in a single pass over a body
+    static ref MARKERS: RegexSet = RegexSet::new([CAPTCHA_PATTERN, LISTING_PATTERN]).unwrap();
+}
+
+/// What a response's body was found to be, from the patterns searched for in every body that's
+/// read; a body is searched once, when the response is received, however many of these are used
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Markers {
+    /// whether or not the body is a CAPTCHA or challenge page, see `ban::Outcome`
+    pub captcha: bool,
+
+    /// whether or not the body is an open directory listing, see `listing::is_listing`
+    pub listing: bool,
+}
+
+impl Markers {
+    /// Search the given body for every marker at once
+    pub fn of(body: &str) -> Self {
+        if body.is_empty() {
+            return Self::default();
+        }
+
+        let matches = MARKERS.matches(body);
+
+        Self {
+            captcha: matches.matched(CAPTCHA),
+            listing: matches.matched(LISTING),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// each marker is found on its own, or together with the others
+    fn markers_of_finds_every_marker() {
+        assert_eq!(Markers::of(""), Markers::default());
+        assert_eq!(Markers::of("<title>Admin</title>"), Markers::default());
+
+        let captcha = Markers::of(r#"<div class="g-recaptcha"></div>"#);
+        assert!(captcha.captcha && !captcha.listing);
+
+        let both = Markers::of(r#"<title>Index of /</title><div class="h-captcha"></div>"#);
+        assert!(both.captcha && both.listing);
+    }
+}
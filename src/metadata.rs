@@ -0,0 +1,112 @@
+use crate::config::CONFIGURATION;
+use crate::utils::rfc3339_timestamp;
+use crate::GIT_DESCRIBE;
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+lazy_static! {
+    /// Tags attached to every structured output record; see `scan_tags`
+    pub static ref SCAN_TAGS: Map<String, Value> =
+        scan_tags(&CONFIGURATION.wordlist, &CONFIGURATION.tags, SystemTime::now());
+}
+
+/// Build the tags that identify a single scan
+///
+/// Every scan is tagged with:
+/// - **scan_id**: a random uuid
+/// - **start_time**: rfc3339 timestamp of when the scan started
+/// - **wordlist**: file names of the wordlists used, separated by commas
+/// - **build**: `git describe` of the binary
+///
+/// followed by the `--tag` key/value pairs, which take precedence over the automatic tags
+pub fn scan_tags(
+    wordlists: &[String],
+    tags: &[(String, String)],
+    start: SystemTime,
+) -> Map<String, Value> {
+    let mut scan_tags = Map::new();
+
+    let wordlist_name = wordlists
+        .iter()
+        .map(|wordlist| {
+            Path::new(wordlist)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| wordlist.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    scan_tags.insert(
+        String::from("scan_id"),
+        Value::from(Uuid::new_v4().to_hyphenated().to_string()),
+    );
+    scan_tags.insert(
+        String::from("start_time"),
+        Value::from(rfc3339_timestamp(start)),
+    );
+    scan_tags.insert(String::from("wordlist"), Value::from(wordlist_name));
+    scan_tags.insert(String::from("build"), Value::from(GIT_DESCRIBE));
+
+    for (key, value) in tags {
+        scan_tags.insert(key.clone(), Value::from(value.as_str()));
+    }
+
+    scan_tags
+}
+
+/// Attach the scan's tags to the given json record as its `tags` object, along with the scan's id
+/// as its `run_id` with `--append-runs`
+pub fn tag(record: &mut Value) {
+    if let Some(object) = record.as_object_mut() {
+        if CONFIGURATION.append_runs {
+            let run_id = SCAN_TAGS.get("scan_id").cloned().unwrap_or_default();
+            object.insert(String::from("run_id"), run_id);
+        }
+
+        object.insert(String::from("tags"), Value::Object(SCAN_TAGS.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    /// automatic tags are always present, --tag values are added and may override them
+    fn metadata_scan_tags_includes_automatic_and_given_tags() {
+        let tags = vec![
+            (String::from("team"), String::from("red")),
+            (String::from("build"), String::from("custom")),
+        ];
+
+        let wordlists = vec![
+            String::from("/wordlists/common.txt"),
+            String::from("https://example.com/lists/raft.txt"),
+        ];
+
+        let scan_tags = scan_tags(&wordlists, &tags, UNIX_EPOCH);
+
+        assert_eq!(scan_tags["scan_id"].as_str().unwrap().len(), 36);
+        assert_eq!(scan_tags["start_time"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(scan_tags["wordlist"], "common.txt,raft.txt");
+        assert_eq!(scan_tags["team"], "red");
+        assert_eq!(scan_tags["build"], "custom");
+    }
+
+    #[test]
+    /// each scan gets its own id
+    fn metadata_scan_tags_generates_unique_ids() {
+        let wordlists = vec![String::from("common.txt")];
+
+        let first = scan_tags(&wordlists, &[], UNIX_EPOCH);
+        let second = scan_tags(&wordlists, &[], UNIX_EPOCH);
+
+        assert_ne!(first["scan_id"], second["scan_id"]);
+        assert_eq!(first["build"], GIT_DESCRIBE);
+    }
+}
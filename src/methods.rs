@@ -0,0 +1,205 @@
+use lazy_static::lazy_static;
+use reqwest::{Method, StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Methods that may be given to `--method`
+pub const METHODS: [&str; 7] = ["GET", "HEAD", "POST", "PUT", "DELETE", "OPTIONS", "PATCH"];
+
+/// Number of a directory's requests the fallback method has to be accepted for, after the
+/// configured one was rejected, before the directory's requests are made with the fallback right
+/// away; a single endpoint that only takes another method doesn't change the whole directory
+const ACCEPTED_TO_SWITCH: usize = 3;
+
+lazy_static! {
+    /// Methods that work for each directory's requests, by directory url
+    static ref DIRECTORIES: RwLock<HashMap<String, Directory>> = RwLock::new(HashMap::new());
+}
+
+/// How a directory's requests answered the fallback method
+#[derive(Debug, Default)]
+struct Directory {
+    /// number of requests that were accepted with the fallback after the configured method was
+    /// rejected
+    accepted: usize,
+
+    /// method the directory's requests are made with instead of the configured one, once the
+    /// fallback was accepted often enough
+    method: Option<Method>,
+}
+
+/// Methods a directory's requests may be switched to once they worked there; they don't change
+/// anything on the target
+const SAFE: [Method; 3] = [Method::GET, Method::HEAD, Method::OPTIONS];
+
+/// Method of the given `--method`, i.e. the first of its list; GET if the name isn't a method
+pub fn parse(methods: &str) -> Method {
+    let name = methods.split(',').next().unwrap_or_default().trim();
+    Method::from_bytes(name.to_uppercase().as_bytes()).unwrap_or(Method::GET)
+}
+
+/// Methods a request rejected with the method of the given `--method` is retried with, in order:
+/// the rest of its list (i.e. POST for `GET,POST`), or GET for a lone HEAD; any other method
+/// isn't retried unless the list asks for it
+pub fn fallbacks(methods: &str) -> Vec<Method> {
+    let listed: Vec<Method> = methods
+        .split(',')
+        .skip(1)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(parse)
+        .collect();
+
+    if listed.is_empty() && parse(methods) == Method::HEAD {
+        vec![Method::GET]
+    } else {
+        listed
+    }
+}
+
+/// Whether or not the status says the method of the request isn't accepted (405 or 501)
+pub fn rejects(status: StatusCode) -> bool {
+    status == StatusCode::METHOD_NOT_ALLOWED || status == StatusCode::NOT_IMPLEMENTED
+}
+
+/// Url of the directory the given url was requested in, i.e. `http://localhost/admin/` for
+/// `http://localhost/admin/users` and `http://localhost/admin/users/`
+pub fn directory(url: &Url) -> String {
+    let path = url.path().trim_end_matches('/');
+
+    let parent = match path.rfind('/') {
+        Some(index) => &path[..=index],
+        None => "/",
+    };
+
+    let mut directory = url.clone();
+    directory.set_path(parent);
+    directory.set_query(None);
+    directory.set_fragment(None);
+    directory.to_string()
+}
+
+/// Method the given url is requested with; the one that works for its directory, if the
+/// configured method was rejected there, otherwise the configured one
+pub fn method_for(url: &Url, configured: &Method) -> Method {
+    let remembered = match DIRECTORIES.read() {
+        Ok(directories) => directories
+            .get(&directory(url))
+            .and_then(|directory| directory.method.clone()),
+        Err(e) => {
+            log::error!("Methods of the scanned directories poisoned: {}", e);
+            None
+        }
+    };
+
+    remembered.unwrap_or_else(|| configured.clone())
+}
+
+/// Record that the given url was accepted with `method` after the configured method was
+/// rejected; once that happened `ACCEPTED_TO_SWITCH` times in a directory, its requests are made
+/// with `method` from then on, as long as it's one of the `SAFE` methods; others are only ever
+/// sent as the retry of a rejected request
+pub fn accepted(url: &Url, method: &Method) {
+    log::trace!("enter: accepted({}, {})", url, method);
+
+    let mut directories = match DIRECTORIES.write() {
+        Ok(directories) => directories,
+        Err(e) => {
+            log::error!("Methods of the scanned directories poisoned: {}", e);
+            return;
+        }
+    };
+
+    let key = directory(url);
+    let directory = directories.entry(key.clone()).or_default();
+
+    directory.accepted += 1;
+
+    if directory.accepted >= ACCEPTED_TO_SWITCH
+        && directory.method.is_none()
+        && SAFE.contains(method)
+    {
+        log::info!("Requests in {} are made with {} from now on", key, method);
+        directory.method = Some(method.clone());
+    }
+
+    log::trace!("exit: accepted");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// urls are grouped by the directory they were requested in, with or without a trailing slash
+    fn methods_directory_strips_last_segment() {
+        let url = |url: &str| Url::parse(url).unwrap();
+
+        assert_eq!(
+            directory(&url("http://localhost/admin/users")),
+            "http://localhost/admin/"
+        );
+        assert_eq!(
+            directory(&url("http://localhost/admin/users/?id=1")),
+            "http://localhost/admin/"
+        );
+        assert_eq!(
+            directory(&url("http://localhost/admin")),
+            "http://localhost/"
+        );
+        assert_eq!(directory(&url("http://localhost/")), "http://localhost/");
+    }
+
+    #[test]
+    /// only a lone HEAD falls back on its own, to GET; anything else only falls back to what the
+    /// list names; only 405 and 501 are rejections
+    fn methods_fallbacks_and_rejects() {
+        assert_eq!(fallbacks("HEAD"), vec![Method::GET]);
+        assert!(fallbacks("GET").is_empty());
+        assert!(fallbacks("PUT").is_empty());
+        assert_eq!(fallbacks("GET,POST"), vec![Method::POST]);
+        assert_eq!(
+            fallbacks("head, options,get"),
+            vec![Method::OPTIONS, Method::GET]
+        );
+
+        assert_eq!(parse("GET,POST"), Method::GET);
+
+        assert!(rejects(StatusCode::METHOD_NOT_ALLOWED));
+        assert!(rejects(StatusCode::NOT_IMPLEMENTED));
+        assert!(!rejects(StatusCode::NOT_FOUND));
+
+        assert_eq!(parse("head"), Method::HEAD);
+    }
+
+    #[test]
+    /// a directory switches to the fallback once it was accepted often enough, other directories
+    /// keep the configured method
+    fn methods_directory_remembers_accepted_fallback() {
+        let users = Url::parse("http://methods.test/admin/users").unwrap();
+        let other = Url::parse("http://methods.test/other/users").unwrap();
+
+        for _ in 1..ACCEPTED_TO_SWITCH {
+            accepted(&users, &Method::GET);
+            assert_eq!(method_for(&users, &Method::HEAD), Method::HEAD);
+        }
+
+        accepted(&users, &Method::GET);
+
+        let backup = Url::parse("http://methods.test/admin/backup").unwrap();
+        assert_eq!(method_for(&backup, &Method::HEAD), Method::GET);
+        assert_eq!(method_for(&other, &Method::HEAD), Method::HEAD);
+    }
+
+    #[test]
+    /// a directory is never switched to a method that changes things, however often it worked
+    fn methods_directory_never_switches_to_unsafe_method() {
+        let url = Url::parse("http://methods.test/forms/login").unwrap();
+
+        for _ in 0..ACCEPTED_TO_SWITCH * 2 {
+            accepted(&url, &Method::POST);
+        }
+
+        assert_eq!(method_for(&url, &Method::GET), Method::GET);
+    }
+}
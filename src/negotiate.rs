@@ -0,0 +1,432 @@
+use crate::config::CONFIGURATION;
+use crate::hooks::{self, RequestHook};
+use crate::targets::Target;
+use crate::utils::{module_colorizer, status_colorizer};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Request, Url};
+use std::process;
+use std::sync::Arc;
+
+/// Schemes requests can be authenticated with (see `--auth`)
+pub const AUTH_SCHEMES: [&str; 1] = ["negotiate"];
+
+/// Kerberos service the given url belongs to, named the way GSSAPI expects it, i.e.
+/// `HTTP@intranet.example.com`
+pub fn service_name(url: &Url) -> Option<String> {
+    url.host_str().map(|host| format!("HTTP@{}", host))
+}
+
+/// `Authorization` value carrying the given SPNEGO token
+pub fn authorization(token: &[u8]) -> String {
+    format!("Negotiate {}", openssl::base64::encode_block(token))
+}
+
+/// SPNEGO tokens from the system's GSSAPI library (MIT or Heimdal Kerberos), which is loaded at
+/// runtime so that building doesn't need its headers
+#[cfg(all(feature = "negotiate", unix))]
+mod gssapi {
+    use lazy_static::lazy_static;
+    use libc::{c_void, size_t};
+    use std::ffi::CString;
+    use std::ptr;
+
+    /// libraries looked for, in order
+    const LIBRARIES: [&str; 3] = [
+        "libgssapi_krb5.so.2",
+        "libgssapi_krb5.so",
+        "/System/Library/Frameworks/GSS.framework/GSS",
+    ];
+
+    /// GSS_C_NT_HOSTBASED_SERVICE, 1.2.840.113554.1.2.1.4
+    const HOSTBASED_SERVICE: [u8; 10] =
+        [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x12, 0x01, 0x02, 0x01, 0x04];
+
+    /// SPNEGO, 1.3.6.1.5.5.2
+    const SPNEGO: [u8; 6] = [0x2b, 0x06, 0x01, 0x05, 0x05, 0x02];
+
+    /// GSS_C_MUTUAL_FLAG
+    const MUTUAL_FLAG: u32 = 2;
+
+    /// GSS_C_GSS_CODE and GSS_C_MECH_CODE, the kinds of status gss_display_status describes
+    const GSS_CODE: i32 = 1;
+    const MECH_CODE: i32 = 2;
+
+    /// gss_buffer_desc
+    #[repr(C)]
+    struct Buffer {
+        length: size_t,
+        value: *mut c_void,
+    }
+
+    impl Buffer {
+        /// empty buffer, filled in by the library
+        fn empty() -> Self {
+            Self {
+                length: 0,
+                value: ptr::null_mut(),
+            }
+        }
+
+        /// copy of the buffer's bytes
+        fn to_vec(&self) -> Vec<u8> {
+            if self.value.is_null() || self.length == 0 {
+                return Vec::new();
+            }
+
+            // safe: the library hands out buffers of `length` bytes at `value`
+            unsafe { std::slice::from_raw_parts(self.value as *const u8, self.length).to_vec() }
+        }
+    }
+
+    /// gss_OID_desc
+    #[repr(C)]
+    struct Oid {
+        length: u32,
+        elements: *mut c_void,
+    }
+
+    impl Oid {
+        /// oid of the given DER-encoded bytes; the library only ever reads them
+        fn of(bytes: &'static [u8]) -> Self {
+            Self {
+                length: bytes.len() as u32,
+                elements: bytes.as_ptr() as *mut c_void,
+            }
+        }
+    }
+
+    type Name = *mut c_void;
+    type Context = *mut c_void;
+
+    type ImportName = unsafe extern "C" fn(*mut u32, *const Buffer, *const Oid, *mut Name) -> u32;
+    type InitSecContext = unsafe extern "C" fn(
+        *mut u32,
+        *mut c_void,
+        *mut Context,
+        Name,
+        *const Oid,
+        u32,
+        u32,
+        *const c_void,
+        *const Buffer,
+        *mut *const Oid,
+        *mut Buffer,
+        *mut u32,
+        *mut u32,
+    ) -> u32;
+    type ReleaseBuffer = unsafe extern "C" fn(*mut u32, *mut Buffer) -> u32;
+    type ReleaseName = unsafe extern "C" fn(*mut u32, *mut Name) -> u32;
+    type DeleteSecContext = unsafe extern "C" fn(*mut u32, *mut Context, *mut Buffer) -> u32;
+    type DisplayStatus =
+        unsafe extern "C" fn(*mut u32, u32, i32, *const Oid, *mut u32, *mut Buffer) -> u32;
+
+    /// Functions of the GSSAPI (see RFC 2744) used to get a token
+    struct Library {
+        import_name: ImportName,
+        init_sec_context: InitSecContext,
+        release_buffer: ReleaseBuffer,
+        release_name: ReleaseName,
+        delete_sec_context: DeleteSecContext,
+        display_status: DisplayStatus,
+    }
+
+    lazy_static! {
+        /// GSSAPI library, loaded once
+        static ref LIBRARY: Result<Library, String> = Library::load();
+    }
+
+    /// Whether or not the given major status is an error (GSS_ERROR)
+    fn is_error(major: u32) -> bool {
+        major & 0xffff_0000 != 0
+    }
+
+    impl Library {
+        /// Load the first of `LIBRARIES` found, along with its functions
+        fn load() -> Result<Self, String> {
+            let handle = LIBRARIES
+                .iter()
+                .find_map(|name| {
+                    let name = CString::new(*name).ok()?;
+
+                    // safe: name is a valid C string; the library is never unloaded, as it's used
+                    // until exit
+                    let handle = unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW) };
+
+                    if handle.is_null() {
+                        None
+                    } else {
+                        Some(handle)
+                    }
+                })
+                .ok_or_else(|| {
+                    format!("Could not load a GSSAPI library ({})", LIBRARIES.join(", "))
+                })?;
+
+            let symbol = |name: &str| {
+                let c_name = CString::new(name).unwrap_or_default();
+
+                // safe: handle was returned by dlopen, and c_name is a valid C string
+                let symbol = unsafe { libc::dlsym(handle, c_name.as_ptr()) };
+
+                if symbol.is_null() {
+                    Err(format!("GSSAPI library has no {}", name))
+                } else {
+                    Ok(symbol)
+                }
+            };
+
+            // safe: the symbols are the functions of RFC 2744, whose signatures are declared above
+            unsafe {
+                Ok(Self {
+                    import_name: std::mem::transmute::<*mut c_void, ImportName>(symbol(
+                        "gss_import_name",
+                    )?),
+                    init_sec_context: std::mem::transmute::<*mut c_void, InitSecContext>(symbol(
+                        "gss_init_sec_context",
+                    )?),
+                    release_buffer: std::mem::transmute::<*mut c_void, ReleaseBuffer>(symbol(
+                        "gss_release_buffer",
+                    )?),
+                    release_name: std::mem::transmute::<*mut c_void, ReleaseName>(symbol(
+                        "gss_release_name",
+                    )?),
+                    delete_sec_context: std::mem::transmute::<*mut c_void, DeleteSecContext>(
+                        symbol("gss_delete_sec_context")?,
+                    ),
+                    display_status: std::mem::transmute::<*mut c_void, DisplayStatus>(symbol(
+                        "gss_display_status",
+                    )?),
+                })
+            }
+        }
+
+        /// The library's description of the given statuses, i.e. `No Kerberos credentials
+        /// available (default cache: FILE:/tmp/krb5cc_0)`
+        fn describe(&self, major: u32, minor: u32) -> String {
+            let mut messages = vec![];
+
+            for (code, kind) in [(major, GSS_CODE), (minor, MECH_CODE)].iter() {
+                if *code == 0 {
+                    continue;
+                }
+
+                let mut message_context = 0;
+
+                loop {
+                    let mut ignored = 0;
+                    let mut buffer = Buffer::empty();
+
+                    // safe: every pointer is valid for the duration of the call
+                    let status = unsafe {
+                        (self.display_status)(
+                            &mut ignored,
+                            *code,
+                            *kind,
+                            ptr::null(),
+                            &mut message_context,
+                            &mut buffer,
+                        )
+                    };
+
+                    if is_error(status) {
+                        break;
+                    }
+
+                    messages.push(String::from_utf8_lossy(&buffer.to_vec()).to_string());
+
+                    // safe: buffer was filled in by the library
+                    unsafe { (self.release_buffer)(&mut ignored, &mut buffer) };
+
+                    if message_context == 0 {
+                        break;
+                    }
+                }
+            }
+
+            messages.join(": ")
+        }
+    }
+
+    /// Initial SPNEGO token for the given service (i.e. `HTTP@intranet.example.com`), using the
+    /// default credentials (i.e. the ticket cache filled by `kinit`)
+    pub fn token(service: &str) -> Result<Vec<u8>, String> {
+        let library = LIBRARY.as_ref().map_err(String::clone)?;
+
+        let mut minor = 0;
+        let mut ignored = 0;
+        let mut name: Name = ptr::null_mut();
+
+        let service_buffer = Buffer {
+            length: service.len(),
+            value: service.as_ptr() as *mut c_void,
+        };
+
+        // safe: every pointer is valid for the duration of the call, the library copies the name
+        let major = unsafe {
+            (library.import_name)(
+                &mut minor,
+                &service_buffer,
+                &Oid::of(&HOSTBASED_SERVICE),
+                &mut name,
+            )
+        };
+
+        if is_error(major) {
+            return Err(library.describe(major, minor));
+        }
+
+        let mut context: Context = ptr::null_mut();
+        let mut output = Buffer::empty();
+
+        // safe: every pointer is valid for the duration of the call, or null where the RFC allows
+        let major = unsafe {
+            (library.init_sec_context)(
+                &mut minor,
+                ptr::null_mut(),
+                &mut context,
+                name,
+                &Oid::of(&SPNEGO),
+                MUTUAL_FLAG,
+                0,
+                ptr::null(),
+                ptr::null(),
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+
+        let result = if is_error(major) {
+            Err(library.describe(major, minor))
+        } else {
+            Ok(output.to_vec())
+        };
+
+        // safe: everything released here was handed out by the library above
+        unsafe {
+            (library.release_buffer)(&mut ignored, &mut output);
+
+            if !context.is_null() {
+                (library.delete_sec_context)(&mut ignored, &mut context, ptr::null_mut());
+            }
+
+            (library.release_name)(&mut ignored, &mut name);
+        }
+
+        result
+    }
+}
+
+/// SPNEGO tokens can't be had without the `negotiate` feature, or outside of unix
+#[cfg(not(all(feature = "negotiate", unix)))]
+mod gssapi {
+    /// Always fails, explaining why
+    pub fn token(_service: &str) -> Result<Vec<u8>, String> {
+        if cfg!(feature = "negotiate") {
+            Err(String::from("GSSAPI isn't supported on this platform"))
+        } else {
+            Err(String::from(
+                "feroxbuster was built without the negotiate feature (cargo build --features negotiate)",
+            ))
+        }
+    }
+}
+
+/// Adds a fresh SPNEGO token to each request sent to the targets' origins (see `--auth negotiate`)
+///
+/// Every request gets its own token, as servers reject replayed authenticators; the service ticket
+/// behind them is cached by the library, so only the first one costs a trip to the KDC
+struct Negotiate {
+    /// origins of the targets, i.e. `https://example.com:8443`
+    origins: Vec<String>,
+}
+
+impl RequestHook for Negotiate {
+    fn before_request(&self, request: &mut Request) {
+        if !self
+            .origins
+            .contains(&request.url().origin().ascii_serialization())
+        {
+            return;
+        }
+
+        let service = match service_name(request.url()) {
+            Some(service) => service,
+            None => return,
+        };
+
+        match gssapi::token(&service) {
+            Ok(token) => {
+                if let Ok(value) = HeaderValue::from_str(&authorization(&token)) {
+                    request.headers_mut().insert(AUTHORIZATION, value);
+                }
+            }
+            Err(e) => log::debug!("Could not get a token for {}: {}", service, e),
+        }
+    }
+}
+
+/// Requests sent to the targets from here on are authenticated with the given `--auth` scheme;
+/// exits when a token can't be had for one of the targets, i.e. without a Kerberos ticket
+pub fn initialize(targets: &[Target]) {
+    log::trace!("enter: initialize({:?})", targets);
+
+    if CONFIGURATION.auth.is_empty() {
+        log::trace!("exit: initialize");
+        return;
+    }
+
+    let urls: Vec<Url> = targets
+        .iter()
+        .filter_map(|target| Url::parse(&target.url).ok())
+        .collect();
+
+    for url in &urls {
+        let service = match service_name(url) {
+            Some(service) => service,
+            None => continue,
+        };
+
+        if let Err(e) = gssapi::token(&service) {
+            eprintln!(
+                "{} {} Could not get a Kerberos token for {}: {}",
+                status_colorizer("ERROR"),
+                module_colorizer("negotiate::initialize"),
+                service,
+                e
+            );
+            process::exit(1);
+        }
+    }
+
+    hooks::add_request_hook(Arc::new(Negotiate {
+        origins: urls
+            .iter()
+            .map(|url| url.origin().ascii_serialization())
+            .collect(),
+    }));
+
+    log::trace!("exit: initialize");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// services are named after the host, without port or path
+    fn negotiate_service_name_uses_host() {
+        let url = Url::parse("https://intranet.example.com:8443/app/").unwrap();
+        assert_eq!(
+            service_name(&url),
+            Some(String::from("HTTP@intranet.example.com"))
+        );
+    }
+
+    #[test]
+    /// tokens are sent base64-encoded
+    fn negotiate_authorization_encodes_token() {
+        assert_eq!(authorization(b"\x60\x82token"), "Negotiate YIJ0b2tlbg==");
+    }
+}
@@ -0,0 +1,365 @@
+use crate::config::Configuration;
+use crate::utils::format_selected_headers;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+/// Number of seconds to wait before a partially filled batch of findings is sent anyway
+const FLUSH_INTERVAL: u64 = 30;
+
+/// Maximum number of characters sent in a single webhook message
+///
+/// Discord rejects messages over 2000 characters; slack's limit is much higher, so the smaller
+/// of the two is used (minus some breathing room for the code block markers)
+const MAX_MESSAGE_LENGTH: usize = 1900;
+
+/// Number of seconds to wait before retrying a rate-limited request when the service doesn't
+/// provide a `Retry-After` header
+const DEFAULT_RETRY_AFTER: u64 = 5;
+
+/// Chat services that feroxbuster knows how to format messages for
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NotifierKind {
+    /// https://api.slack.com/messaging/webhooks
+    Slack,
+
+    /// https://discord.com/developers/docs/resources/webhook
+    Discord,
+}
+
+impl NotifierKind {
+    /// Wrap the given text in the JSON structure expected by the webhook service
+    fn payload(self, text: &str) -> Value {
+        match self {
+            NotifierKind::Slack => json!({ "text": text }),
+            NotifierKind::Discord => json!({ "content": text }),
+        }
+    }
+}
+
+/// A single webhook destination
+#[derive(Debug, Clone, PartialEq)]
+pub struct Webhook {
+    /// service that will receive messages sent to `url`
+    pub kind: NotifierKind,
+
+    /// the incoming webhook url
+    pub url: String,
+}
+
+/// Minimal representation of a reported response; all a notifier needs to know about
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// status code of the reported response
+    pub status: StatusCode,
+
+    /// content-length of the reported response
+    pub content_length: u64,
+
+    /// url of the reported response
+    pub url: String,
+
+    /// headers selected with `--show-headers`
+    pub headers: Vec<(String, String)>,
+}
+
+impl Finding {
+    /// Single line representation of a `Finding`, mirrors the terminal's output format
+    fn as_line(&self) -> String {
+        format!(
+            "{} {:>10} {}{}",
+            self.status.as_str(),
+            self.content_length,
+            self.url,
+            format_selected_headers(&self.headers)
+        )
+    }
+}
+
+/// Gather all webhooks defined in the given `Configuration`
+pub fn webhooks_from_config(config: &Configuration) -> Vec<Webhook> {
+    let mut webhooks = vec![];
+
+    if !config.slack_webhook.is_empty() {
+        webhooks.push(Webhook {
+            kind: NotifierKind::Slack,
+            url: config.slack_webhook.clone(),
+        });
+    }
+
+    if !config.discord_webhook.is_empty() {
+        webhooks.push(Webhook {
+            kind: NotifierKind::Discord,
+            url: config.discord_webhook.clone(),
+        });
+    }
+
+    webhooks
+}
+
+/// Creates the notification handler when at least one webhook is configured and returns the
+/// transmitter side of its mpsc along with the receiver's future's JoinHandle to be awaited
+///
+/// When no webhooks are given, nothing is spawned and `(None, None)` is returned
+pub fn initialize(
+    webhooks: Vec<Webhook>,
+    batch_size: usize,
+) -> (Option<UnboundedSender<Finding>>, Option<JoinHandle<()>>) {
+    log::trace!("enter: initialize({:?}, {})", webhooks, batch_size);
+
+    if webhooks.is_empty() {
+        log::trace!("exit: initialize -> (None, None)");
+        return (None, None);
+    }
+
+    let (tx_notify, rx_notify) = mpsc::unbounded_channel::<Finding>();
+
+    let notifier =
+        tokio::spawn(async move { spawn_notifier(rx_notify, webhooks, batch_size).await });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_notify, notifier);
+    (Some(tx_notify), Some(notifier))
+}
+
+/// Spawn a single consumer task (sc side of mpsc)
+///
+/// The consumer batches up findings and sends them to every webhook whenever a batch fills up
+/// or `FLUSH_INTERVAL` seconds pass. Once the channel closes, any leftovers are sent along with
+/// a summary of everything that was found.
+async fn spawn_notifier(
+    mut notify_chan: UnboundedReceiver<Finding>,
+    webhooks: Vec<Webhook>,
+    batch_size: usize,
+) {
+    log::trace!(
+        "enter: spawn_notifier({:?}, {:?}, {})",
+        notify_chan,
+        webhooks,
+        batch_size
+    );
+
+    // the scan's client may carry target-specific headers and proxies that have no business
+    // being sent to a chat service
+    let client = Client::new();
+
+    let mut batch = vec![];
+    let mut counts = BTreeMap::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL));
+
+    loop {
+        tokio::select! {
+            finding = notify_chan.recv() => {
+                match finding {
+                    Some(finding) => {
+                        *counts.entry(finding.status.as_u16()).or_insert(0) += 1;
+                        batch.push(finding.as_line());
+
+                        if batch.len() >= batch_size.max(1) {
+                            send_batch(&client, &webhooks, &batch).await;
+                            batch.clear();
+                        }
+                    }
+                    None => break, // all transmitters dropped, scan is complete
+                }
+            }
+            _ = interval.tick() => {
+                if !batch.is_empty() {
+                    send_batch(&client, &webhooks, &batch).await;
+                    batch.clear();
+                }
+            }
+        }
+    }
+
+    if !batch.is_empty() {
+        send_batch(&client, &webhooks, &batch).await;
+    }
+
+    let summary = summary_message(&counts);
+
+    for webhook in &webhooks {
+        post(&client, webhook, &summary).await;
+    }
+
+    log::trace!("exit: spawn_notifier");
+}
+
+/// Send the given lines to each webhook, split across as many messages as necessary
+async fn send_batch(client: &Client, webhooks: &[Webhook], lines: &[String]) {
+    let header = format!("feroxbuster found {} new result(s)", lines.len());
+
+    for message in build_messages(&header, lines) {
+        for webhook in webhooks {
+            post(client, webhook, &message).await;
+        }
+    }
+}
+
+/// Split `lines` into messages no longer than `MAX_MESSAGE_LENGTH`, each wrapped in a code block
+///
+/// `header` is only prepended to the first message
+fn build_messages(header: &str, lines: &[String]) -> Vec<String> {
+    let mut messages = vec![];
+    let mut current = format!("{}\n```\n", header);
+    let mut has_lines = false;
+
+    for line in lines {
+        // a single line can be longer than the maximum allowed; it gets truncated in that case
+        let line: String = line.chars().take(MAX_MESSAGE_LENGTH / 2).collect();
+
+        // 4 accounts for the newline and closing code block marker
+        if has_lines && current.len() + line.len() + 4 > MAX_MESSAGE_LENGTH {
+            current.push_str("```");
+            messages.push(current);
+            current = String::from("```\n");
+        }
+
+        current.push_str(&line);
+        current.push('\n');
+        has_lines = true;
+    }
+
+    if has_lines {
+        current.push_str("```");
+        messages.push(current);
+    }
+
+    messages
+}
+
+/// Build the end-of-scan message, a total followed by the number of findings per status code
+fn summary_message(counts: &BTreeMap<u16, usize>) -> String {
+    let total: usize = counts.values().sum();
+
+    let mut summary = format!("feroxbuster scan complete: {} result(s)", total);
+
+    for (status, count) in counts {
+        summary.push_str(&format!("\n  {}: {}", status, count));
+    }
+
+    summary
+}
+
+/// POST a single message to the given webhook; retries once when rate limited
+async fn post(client: &Client, webhook: &Webhook, text: &str) {
+    log::trace!("enter: post({:?}, {})", webhook, text);
+
+    let payload = webhook.kind.payload(text);
+
+    for _ in 0..2 {
+        let request = client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(payload.to_string());
+
+        match request.send().await {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = response
+                    .headers()
+                    .get("Retry-After")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .map(|secs| secs.ceil() as u64)
+                    .unwrap_or(DEFAULT_RETRY_AFTER);
+
+                log::warn!(
+                    "{:?} webhook rate limited, retrying in {} seconds",
+                    webhook.kind,
+                    retry_after
+                );
+                tokio::time::delay_for(Duration::from_secs(retry_after)).await;
+            }
+            Ok(response) => {
+                if !response.status().is_success() {
+                    log::warn!(
+                        "{:?} webhook responded with {}",
+                        webhook.kind,
+                        response.status()
+                    );
+                }
+                break;
+            }
+            Err(e) => {
+                log::error!("Could not send notification to {:?}: {}", webhook.kind, e);
+                break;
+            }
+        }
+    }
+
+    log::trace!("exit: post");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// slack and discord expect the message text under different keys
+    fn notifier_payload_uses_service_specific_key() {
+        assert_eq!(NotifierKind::Slack.payload("stuff")["text"], "stuff");
+        assert_eq!(NotifierKind::Discord.payload("stuff")["content"], "stuff");
+    }
+
+    #[test]
+    /// webhooks are only created for non-empty urls
+    fn notifier_webhooks_from_config_skips_empty_urls() {
+        let mut config = Configuration::default();
+        assert!(webhooks_from_config(&config).is_empty());
+
+        config.discord_webhook = String::from("https://discord.com/api/webhooks/1/2");
+        let webhooks = webhooks_from_config(&config);
+
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].kind, NotifierKind::Discord);
+    }
+
+    #[test]
+    /// a small batch fits in a single message with the header and a code block
+    fn notifier_build_messages_single_message() {
+        let lines = vec![String::from("200 10 http://localhost/a")];
+        let messages = build_messages("header", &lines);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0], "header\n```\n200 10 http://localhost/a\n```");
+    }
+
+    #[test]
+    /// large batches are split so that no message exceeds the maximum length
+    fn notifier_build_messages_splits_large_batches() {
+        let lines: Vec<String> = (0..200)
+            .map(|i| format!("200 {:>10} http://localhost/{}", i, i))
+            .collect();
+        let messages = build_messages("header", &lines);
+
+        assert!(messages.len() > 1);
+
+        for message in &messages {
+            assert!(message.len() <= MAX_MESSAGE_LENGTH);
+            assert!(message.ends_with("```"));
+        }
+
+        let total_lines: usize = messages
+            .iter()
+            .map(|m| m.lines().filter(|l| l.starts_with("200")).count())
+            .sum();
+        assert_eq!(total_lines, 200);
+    }
+
+    #[test]
+    /// summary contains the total and a count per status code
+    fn notifier_summary_message_counts_by_status() {
+        let mut counts = BTreeMap::new();
+        counts.insert(200, 3);
+        counts.insert(403, 1);
+
+        let summary = summary_message(&counts);
+
+        assert!(summary.contains("4 result(s)"));
+        assert!(summary.contains("200: 3"));
+        assert!(summary.contains("403: 1"));
+    }
+}
@@ -0,0 +1,339 @@
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Signature every NTLM message starts with
+const SIGNATURE: &[u8; 8] = b"NTLMSSP\0";
+
+/// NEGOTIATE_UNICODE | NEGOTIATE_OEM | REQUEST_TARGET | NEGOTIATE_NTLM | NEGOTIATE_ALWAYS_SIGN |
+/// NEGOTIATE_EXTENDED_SESSIONSECURITY | NEGOTIATE_128 | NEGOTIATE_56
+const FLAGS: u32 = 0xa008_8207;
+
+/// AvId of the MsvAvTimestamp pair of a challenge's target info
+const AV_TIMESTAMP: u16 = 7;
+
+/// Seconds between 1601-01-01, where FILETIMEs start, and the unix epoch
+const FILETIME_EPOCH_SECS: u64 = 11_644_473_600;
+
+/// What the server sent in its CHALLENGE_MESSAGE (type 2)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    /// flags the server agreed to
+    pub flags: u32,
+
+    /// nonce the responses are computed over
+    pub server_challenge: [u8; 8],
+
+    /// AV pairs describing the server, echoed in the NTLMv2 response
+    pub target_info: Vec<u8>,
+}
+
+impl Challenge {
+    /// Parse the given CHALLENGE_MESSAGE; `None` when it isn't one
+    pub fn parse(message: &[u8]) -> Option<Self> {
+        if message.len() < 32 || &message[..8] != SIGNATURE || read_u32(message, 8)? != 2 {
+            return None;
+        }
+
+        let mut server_challenge = [0; 8];
+        server_challenge.copy_from_slice(&message[24..32]);
+
+        // target info is optional, older servers send a shorter message without it
+        let target_info = if message.len() >= 48 {
+            let length = read_u16(message, 40)? as usize;
+            let offset = read_u32(message, 44)? as usize;
+            message.get(offset..offset + length)?.to_vec()
+        } else {
+            Vec::new()
+        };
+
+        Some(Self {
+            flags: read_u32(message, 20)?,
+            server_challenge,
+            target_info,
+        })
+    }
+
+    /// Time of the server given in its target info, as a FILETIME
+    pub fn timestamp(&self) -> Option<u64> {
+        let mut pairs = self.target_info.as_slice();
+
+        while pairs.len() >= 4 {
+            let id = read_u16(pairs, 0)?;
+            let length = read_u16(pairs, 2)? as usize;
+            let value = pairs.get(4..4 + length)?;
+
+            match id {
+                0 => return None, // MsvAvEOL
+                AV_TIMESTAMP if length == 8 => return read_u64(value, 0),
+                _ => pairs = &pairs[4 + length..],
+            }
+        }
+
+        None
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    let mut buf = [0; 2];
+    buf.copy_from_slice(bytes.get(at..at + 2)?);
+    Some(u16::from_le_bytes(buf))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(bytes.get(at..at + 4)?);
+    Some(u32::from_le_bytes(buf))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Option<u64> {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(bytes.get(at..at + 8)?);
+    Some(u64::from_le_bytes(buf))
+}
+
+/// The given string as UTF-16LE, the way NTLM encodes strings when unicode was negotiated
+fn utf16(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect()
+}
+
+/// MD4 of the given data (RFC 1320); hand-rolled, as OpenSSL 3 only has it in its legacy provider
+pub fn md4(data: &[u8]) -> [u8; 16] {
+    let mut message = data.to_vec();
+    message.push(0x80);
+
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+    for block in message.chunks(64) {
+        let mut x = [0u32; 16];
+
+        for (i, word) in x.iter_mut().enumerate() {
+            *word = read_u32(block, i * 4).unwrap_or_default();
+        }
+
+        let mut v = state;
+
+        for round in 0..3 {
+            for i in 0..16 {
+                let (k, s, f, constant) = match round {
+                    0 => (i, [3, 7, 11, 19][i % 4], (v[1] & v[2]) | (!v[1] & v[3]), 0),
+                    1 => (
+                        (i % 4) * 4 + i / 4,
+                        [3, 5, 9, 13][i % 4],
+                        (v[1] & v[2]) | (v[1] & v[3]) | (v[2] & v[3]),
+                        0x5a82_7999,
+                    ),
+                    _ => (
+                        [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15][i],
+                        [3, 9, 11, 15][i % 4],
+                        v[1] ^ v[2] ^ v[3],
+                        0x6ed9_eba1,
+                    ),
+                };
+
+                let t = v[0]
+                    .wrapping_add(f)
+                    .wrapping_add(x[k])
+                    .wrapping_add(constant)
+                    .rotate_left(s);
+
+                v = [v[3], t, v[1], v[2]];
+            }
+        }
+
+        for (word, value) in state.iter_mut().zip(v.iter()) {
+            *word = word.wrapping_add(*value);
+        }
+    }
+
+    let mut digest = [0; 16];
+
+    for (i, word) in state.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+    }
+
+    digest
+}
+
+/// HMAC-MD5 of the given data with the given key
+fn hmac_md5(key: &[u8], data: &[u8]) -> Vec<u8> {
+    // hmac keys of any length are valid, and signing a slice doesn't fail
+    let key = PKey::hmac(key).unwrap();
+    let mut signer = Signer::new(MessageDigest::md5(), &key).unwrap();
+    signer.update(data).unwrap();
+    signer.sign_to_vec().unwrap()
+}
+
+/// NTOWFv2, the key NTLMv2 responses are computed with
+pub fn ntowfv2(domain: &str, user: &str, password: &str) -> Vec<u8> {
+    let nt_hash = md4(&utf16(password));
+    hmac_md5(
+        &nt_hash,
+        &utf16(&format!("{}{}", user.to_uppercase(), domain)),
+    )
+}
+
+/// LMv2 and NTLMv2 responses to the given challenge
+pub fn responses(
+    key: &[u8],
+    challenge: &Challenge,
+    client_challenge: &[u8; 8],
+    timestamp: u64,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut lm = hmac_md5(
+        key,
+        &[&challenge.server_challenge[..], &client_challenge[..]].concat(),
+    );
+    lm.extend_from_slice(client_challenge);
+
+    let mut blob = vec![1, 1, 0, 0, 0, 0, 0, 0];
+    blob.extend_from_slice(&timestamp.to_le_bytes());
+    blob.extend_from_slice(client_challenge);
+    blob.extend_from_slice(&[0; 4]);
+    blob.extend_from_slice(&challenge.target_info);
+    blob.extend_from_slice(&[0; 4]);
+
+    let mut nt = hmac_md5(key, &[&challenge.server_challenge[..], &blob].concat());
+    nt.extend_from_slice(&blob);
+
+    (lm, nt)
+}
+
+/// The current time as a FILETIME, for servers that don't send theirs
+fn now() -> u64 {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    (since_epoch.as_secs() + FILETIME_EPOCH_SECS) * 10_000_000
+        + u64::from(since_epoch.subsec_nanos() / 100)
+}
+
+/// NEGOTIATE_MESSAGE (type 1), which starts the handshake
+pub fn negotiate() -> Vec<u8> {
+    let mut message = SIGNATURE.to_vec();
+    message.extend_from_slice(&1u32.to_le_bytes());
+    message.extend_from_slice(&FLAGS.to_le_bytes());
+    message.extend_from_slice(&[0; 16]); // no domain or workstation is supplied
+    message
+}
+
+/// AUTHENTICATE_MESSAGE (type 3) answering the given challenge with NTLMv2 responses
+pub fn authenticate(domain: &str, user: &str, password: &str, challenge: &Challenge) -> Vec<u8> {
+    let mut client_challenge = [0; 8];
+
+    if let Err(e) = openssl::rand::rand_bytes(&mut client_challenge) {
+        log::error!("{}", e);
+    }
+
+    let timestamp = challenge.timestamp().unwrap_or_else(now);
+    let key = ntowfv2(domain, user, password);
+    let (lm, nt) = responses(&key, challenge, &client_challenge, timestamp);
+
+    // lm, nt, domain, user, workstation and session key, in the order of the message's fields
+    let fields = [lm, nt, utf16(domain), utf16(user), Vec::new(), Vec::new()];
+
+    let header_length = 64;
+    let mut header = SIGNATURE.to_vec();
+    header.extend_from_slice(&3u32.to_le_bytes());
+
+    let mut payload = Vec::new();
+
+    for field in fields.iter() {
+        let offset = (header_length + payload.len()) as u32;
+        header.extend_from_slice(&(field.len() as u16).to_le_bytes());
+        header.extend_from_slice(&(field.len() as u16).to_le_bytes());
+        header.extend_from_slice(&offset.to_le_bytes());
+        payload.extend_from_slice(field);
+    }
+
+    header.extend_from_slice(&FLAGS.to_le_bytes());
+    header.extend_from_slice(&payload);
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// hex of the given bytes
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// challenge of the NTLMv2 example of MS-NLMP 4.2.4
+    fn challenge() -> Challenge {
+        let mut target_info = vec![2, 0, 12, 0];
+        target_info.extend(utf16("Domain"));
+        target_info.extend(&[1, 0, 12, 0]);
+        target_info.extend(utf16("Server"));
+        target_info.extend(&[0, 0, 0, 0]);
+
+        Challenge {
+            flags: 0xe28a_8233,
+            server_challenge: [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef],
+            target_info,
+        }
+    }
+
+    #[test]
+    /// md4 matches the test suite of RFC 1320
+    fn ntlm_md4_matches_rfc_1320() {
+        assert_eq!(hex(&md4(b"")), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(hex(&md4(b"abc")), "a448017aaf21d8525fc10ae87aa6729d");
+        assert_eq!(
+            hex(&md4(
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            )),
+            "e33b4ddc9c38f2199c3e7b164fcc0536"
+        );
+    }
+
+    #[test]
+    /// keys and responses match the NTLMv2 example of MS-NLMP 4.2.4
+    fn ntlm_responses_match_ms_nlmp() {
+        let key = ntowfv2("Domain", "User", "Password");
+        assert_eq!(hex(&key), "0c868a403bfd7a93a3001ef22ef02e3f");
+
+        let (lm, nt) = responses(&key, &challenge(), &[0xaa; 8], 0);
+        assert_eq!(hex(&lm), "86c35097ac9cec102554764a57cccc19aaaaaaaaaaaaaaaa");
+        assert_eq!(hex(&nt[..16]), "68cd0ab851e51c96aabc927bebef6a1c");
+    }
+
+    #[test]
+    /// a challenge message is parsed along with its target info and timestamp
+    fn ntlm_challenge_parse_reads_target_info() {
+        let mut target_info = vec![7, 0, 8, 0];
+        target_info.extend(&42u64.to_le_bytes());
+        target_info.extend(&[0, 0, 0, 0]);
+
+        let mut message = SIGNATURE.to_vec();
+        message.extend(&2u32.to_le_bytes());
+        message.extend(&[0; 8]); // target name
+        message.extend(&FLAGS.to_le_bytes());
+        message.extend(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        message.extend(&[0; 8]); // reserved
+        message.extend(&(target_info.len() as u16).to_le_bytes());
+        message.extend(&(target_info.len() as u16).to_le_bytes());
+        message.extend(&48u32.to_le_bytes());
+        message.extend(&target_info);
+
+        let challenge = Challenge::parse(&message).unwrap();
+        assert_eq!(challenge.flags, FLAGS);
+        assert_eq!(challenge.server_challenge, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(challenge.target_info, target_info);
+        assert_eq!(challenge.timestamp(), Some(42));
+
+        assert!(Challenge::parse(&negotiate()).is_none());
+        assert!(Challenge::parse(b"NTLMSSP\0").is_none());
+    }
+}
@@ -0,0 +1,503 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::hooks::{self, RequestHook, ResponseHook};
+use crate::targets::Target;
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE};
+use reqwest::{Request, StatusCode, Url};
+use serde::Deserialize;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// seconds before a token expires that a new one is requested
+const REFRESH_MARGIN_SECS: u64 = 60;
+
+/// seconds between attempts to request a new token, once one failed
+const RETRY_SECS: u64 = 10;
+
+/// how often the token is checked for expiry, in milliseconds
+const EXPIRY_CHECK_MS: u64 = 1000;
+
+lazy_static! {
+    /// `Authorization` value of the current token, i.e. `Bearer eyJ...`
+    static ref AUTHORIZATION_VALUE: RwLock<Option<HeaderValue>> = RwLock::new(None);
+
+    /// Refresh token exchanged for access tokens, empty when the client-credentials grant is used;
+    /// replaced whenever the token endpoint hands out a new one
+    static ref REFRESH_TOKEN: RwLock<String> =
+        RwLock::new(CONFIGURATION.oauth_refresh_token.clone());
+}
+
+/// Whether or not a response said the token expired since it was last refreshed
+static EXPIRED: AtomicBool = AtomicBool::new(false);
+
+/// Successful response of a token endpoint (see RFC 6749, section 5.1)
+#[derive(Debug, Deserialize)]
+pub struct Token {
+    /// the token itself
+    pub access_token: String,
+
+    /// type of the token, `Bearer` when not given
+    #[serde(default = "bearer")]
+    pub token_type: String,
+
+    /// seconds the token is valid for, when given
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+
+    /// refresh token to use from here on, when the token endpoint rotates them
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// default token type
+fn bearer() -> String {
+    String::from("Bearer")
+}
+
+/// Error response of a token endpoint (see RFC 6749, section 5.2)
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    /// error code, i.e. `invalid_client`
+    error: String,
+
+    /// human-readable description of the error, when given
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl Token {
+    /// `Authorization` value of the token; tokens of any type but `Bearer` are sent as given
+    pub fn authorization(&self) -> String {
+        if self.token_type.eq_ignore_ascii_case("bearer") {
+            format!("Bearer {}", self.access_token)
+        } else {
+            format!("{} {}", self.token_type, self.access_token)
+        }
+    }
+}
+
+/// How long to wait before requesting a new token, given how long the current one is valid for
+///
+/// A new token is requested `REFRESH_MARGIN_SECS` before the current one expires, or halfway
+/// through its lifetime when it's that short; never sooner than a second from now
+pub fn refresh_in(expires_in: u64) -> Duration {
+    Duration::from_secs(
+        expires_in
+            .saturating_sub(REFRESH_MARGIN_SECS)
+            .max(expires_in / 2)
+            .max(1),
+    )
+}
+
+/// Why the token endpoint refused to hand out a token, from its error response when it sent one
+fn describe_error(status: u16, body: &str) -> String {
+    match serde_json::from_str::<TokenError>(body) {
+        Ok(TokenError {
+            error,
+            error_description: Some(description),
+        }) => format!("{} {}: {}", status, error, description),
+        Ok(TokenError { error, .. }) => format!("{} {}", status, error),
+        Err(_) => format!("token endpoint answered with a {}", status),
+    }
+}
+
+/// Whether or not the given response says the access token expired: a 401 with a
+/// `WWW-Authenticate: Bearer error="invalid_token"` (see RFC 6750), or whose body matches
+/// `--oauth-expired-regex`
+pub fn is_expired(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    expired: Option<&Regex>,
+) -> bool {
+    if status != StatusCode::UNAUTHORIZED {
+        return false;
+    }
+
+    let invalid_token = headers.get_all(WWW_AUTHENTICATE).iter().any(|value| {
+        let value = String::from_utf8_lossy(value.as_bytes()).to_lowercase();
+        value.starts_with("bearer") && value.contains("invalid_token")
+    });
+
+    invalid_token || expired.is_some_and(|regex| regex.is_match(body))
+}
+
+/// Request a token, exchanging the refresh token when there's one and using the
+/// client-credentials grant otherwise
+///
+/// The client authenticates with HTTP Basic when it has a secret, public clients only send their id
+async fn request_token(url: &Url) -> Result<Token, String> {
+    log::trace!("enter: request_token({})", url);
+
+    let scope = CONFIGURATION.oauth_scopes.join(" ");
+    let refresh_token = REFRESH_TOKEN
+        .read()
+        .map(|token| token.clone())
+        .unwrap_or_default();
+
+    let mut form = if refresh_token.is_empty() {
+        vec![("grant_type", "client_credentials")]
+    } else {
+        vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+        ]
+    };
+
+    if !scope.is_empty() {
+        form.push(("scope", &scope));
+    }
+
+    // the token endpoint isn't a target, so the request hooks aren't run on it
+    let mut request = CONFIGURATION.client.post(url.to_owned());
+
+    if !CONFIGURATION.oauth_client_secret.is_empty() {
+        request = request.basic_auth(
+            &CONFIGURATION.oauth_client_id,
+            Some(&CONFIGURATION.oauth_client_secret),
+        );
+    } else if !CONFIGURATION.oauth_client_id.is_empty() {
+        form.push(("client_id", &CONFIGURATION.oauth_client_id));
+    }
+
+    let response = request
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    let body = response.text().await.map_err(|e| e.to_string())?;
+
+    let result = if status.is_success() {
+        serde_json::from_str::<Token>(&body)
+            .map_err(|e| format!("could not read the token endpoint's response: {}", e))
+    } else {
+        Err(describe_error(status.as_u16(), &body))
+    };
+
+    log::trace!(
+        "exit: request_token -> {}",
+        if result.is_ok() { "token" } else { "error" }
+    );
+    result
+}
+
+/// Request a token, then use it (and its refresh token, if it has one) from here on
+async fn refresh(url: &Url) -> Result<Token, String> {
+    let token = request_token(url).await?;
+
+    let value = HeaderValue::from_str(&token.authorization())
+        .map_err(|e| format!("token isn't a valid header value: {}", e))?;
+
+    match AUTHORIZATION_VALUE.write() {
+        Ok(mut current) => *current = Some(value),
+        Err(e) => log::error!("{}", e),
+    }
+
+    if let Some(refresh_token) = &token.refresh_token {
+        match REFRESH_TOKEN.write() {
+            Ok(mut current) => *current = refresh_token.clone(),
+            Err(e) => log::error!("{}", e),
+        }
+    }
+
+    Ok(token)
+}
+
+/// Adds the current token to requests sent to the targets' origins, unless they already have an
+/// `Authorization` (i.e. given with `--headers`)
+struct BearerToken {
+    /// origins of the targets, i.e. `https://example.com:8443`
+    origins: Vec<String>,
+}
+
+impl RequestHook for BearerToken {
+    fn before_request(&self, request: &mut Request) {
+        if request.headers().contains_key(AUTHORIZATION)
+            || !self
+                .origins
+                .contains(&request.url().origin().ascii_serialization())
+        {
+            return;
+        }
+
+        if let Ok(current) = AUTHORIZATION_VALUE.read() {
+            if let Some(value) = current.as_ref() {
+                request.headers_mut().insert(AUTHORIZATION, value.clone());
+            }
+        }
+    }
+}
+
+/// Vetoes responses that say the token expired, and flags it for refreshing
+struct Expired {
+    /// regex matching the body of 401s sent once the token expired (see `--oauth-expired-regex`)
+    expired: Option<Regex>,
+}
+
+impl ResponseHook for Expired {
+    fn veto(&self, response: &FeroxResponse) -> Option<String> {
+        if !is_expired(
+            *response.status(),
+            response.headers(),
+            response.text(),
+            self.expired.as_ref(),
+        ) {
+            return None;
+        }
+
+        EXPIRED.store(true, Ordering::Relaxed);
+        Some(String::from("token expired"))
+    }
+}
+
+/// Print the given line about the token
+fn alert(msg: &str) {
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(msg, &PROGRESS_PRINTER);
+    }
+}
+
+/// Request a token once, ahead of the connectivity test, then spawn the task that requests a new
+/// one before it expires, or once a response says it did (see `--oauth-token-url`); returns the
+/// transmitter used to stop the task along with the task's JoinHandle to be awaited, or `None`
+/// without `--oauth-token-url`
+///
+/// Failing to get the first token exits, as scanning without it would be pointless
+pub async fn initialize(targets: &[Target]) -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize({:?})", targets);
+
+    if CONFIGURATION.oauth_token_url.is_empty() {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let fail = |msg: String| -> ! {
+        eprintln!(
+            "{} {} {}",
+            status_colorizer("ERROR"),
+            module_colorizer("oauth::initialize"),
+            msg
+        );
+        process::exit(1);
+    };
+
+    let url = Url::parse(&CONFIGURATION.oauth_token_url).unwrap_or_else(|e| {
+        fail(format!(
+            "Could not parse token url {}: {}",
+            CONFIGURATION.oauth_token_url, e
+        ))
+    });
+
+    if CONFIGURATION.oauth_refresh_token.is_empty()
+        && (CONFIGURATION.oauth_client_id.is_empty()
+            || CONFIGURATION.oauth_client_secret.is_empty())
+    {
+        fail(String::from(
+            "--oauth-token-url needs --oauth-client-id and --oauth-client-secret, or --oauth-refresh-token",
+        ));
+    }
+
+    let expired = if CONFIGURATION.oauth_expired_regex.is_empty() {
+        None
+    } else {
+        Some(
+            Regex::new(&CONFIGURATION.oauth_expired_regex).unwrap_or_else(|e| {
+                fail(format!(
+                    "Could not compile --oauth-expired-regex {}: {}",
+                    CONFIGURATION.oauth_expired_regex, e
+                ))
+            }),
+        )
+    };
+
+    let token = match refresh(&url).await {
+        Ok(token) => token,
+        Err(e) => fail(format!("Could not get a token from {}: {}", url, e)),
+    };
+
+    log::info!(
+        "Got a token from {}, valid for {:?} seconds",
+        url,
+        token.expires_in
+    );
+
+    hooks::add_request_hook(Arc::new(BearerToken {
+        origins: targets
+            .iter()
+            .filter_map(|target| Url::parse(&target.url).ok())
+            .map(|target| target.origin().ascii_serialization())
+            .collect(),
+    }));
+
+    // 401s look alike, they'd be vetoed as wildcards before this
+    hooks::add_first_response_hook(Arc::new(Expired { expired }));
+
+    // tokens without an expiry are only refreshed once a response says they expired
+    let mut refresh_at = token
+        .expires_in
+        .map(|expires_in| Instant::now() + refresh_in(expires_in));
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let refresher = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(EXPIRY_CHECK_MS));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let expired = EXPIRED.swap(false, Ordering::Relaxed);
+
+                    if !expired && refresh_at.is_none_or(|at| Instant::now() < at) {
+                        continue;
+                    }
+
+                    match refresh(&url).await {
+                        Ok(token) => {
+                            log::info!("Got a new token from {}", url);
+
+                            if expired {
+                                alert(&format!(
+                                    "{} {:>10} Token expired, got a new one from {}\n",
+                                    status_colorizer("AUT"),
+                                    "-",
+                                    url
+                                ));
+                            }
+
+                            // responses to requests sent with the old token may still say it
+                            // expired
+                            EXPIRED.store(false, Ordering::Relaxed);
+
+                            refresh_at = token
+                                .expires_in
+                                .map(|expires_in| Instant::now() + refresh_in(expires_in));
+                        }
+                        Err(e) => {
+                            let msg = format!(
+                                "{} {:>10} Could not get a new token from {}: {}; retrying in {}s\n",
+                                status_colorizer("ERR"),
+                                "-",
+                                url,
+                                e,
+                                RETRY_SECS
+                            );
+
+                            log::warn!("{}", console::strip_ansi_codes(msg.trim_end()));
+                            alert(&msg);
+
+                            refresh_at = Some(Instant::now() + Duration::from_secs(RETRY_SECS));
+                        }
+                    }
+                }
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, refresher);
+    Some((tx_stop, refresher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// tokens default to bearer, other types are sent as given
+    fn oauth_token_authorization_uses_type() {
+        let token: Token =
+            serde_json::from_str(r#"{"access_token": "abc", "expires_in": 3600}"#).unwrap();
+        assert_eq!(token.authorization(), "Bearer abc");
+        assert_eq!(token.expires_in, Some(3600));
+
+        let token: Token =
+            serde_json::from_str(r#"{"access_token": "abc", "token_type": "bearer"}"#).unwrap();
+        assert_eq!(token.authorization(), "Bearer abc");
+        assert_eq!(token.expires_in, None);
+
+        let token: Token =
+            serde_json::from_str(r#"{"access_token": "abc", "token_type": "MAC"}"#).unwrap();
+        assert_eq!(token.authorization(), "MAC abc");
+    }
+
+    #[test]
+    /// rotated refresh tokens are read along with the access token
+    fn oauth_token_reads_refresh_token() {
+        let token: Token = serde_json::from_str(
+            r#"{"access_token": "abc", "expires_in": 300, "refresh_token": "def"}"#,
+        )
+        .unwrap();
+        assert_eq!(token.refresh_token, Some(String::from("def")));
+    }
+
+    #[test]
+    /// only 401s expire tokens, when they say so in their challenge or body
+    fn oauth_is_expired_reads_challenge_and_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            WWW_AUTHENTICATE,
+            "Bearer realm=\"api\", error=\"invalid_token\", error_description=\"expired\""
+                .parse()
+                .unwrap(),
+        );
+
+        assert!(is_expired(StatusCode::UNAUTHORIZED, &headers, "", None));
+        assert!(!is_expired(StatusCode::FORBIDDEN, &headers, "", None));
+
+        let regex = Regex::new("token (is )?expired").unwrap();
+        let empty = HeaderMap::new();
+
+        assert!(!is_expired(StatusCode::UNAUTHORIZED, &empty, "", None));
+        assert!(is_expired(
+            StatusCode::UNAUTHORIZED,
+            &empty,
+            r#"{"message": "token is expired"}"#,
+            Some(&regex)
+        ));
+        assert!(!is_expired(
+            StatusCode::UNAUTHORIZED,
+            &empty,
+            r#"{"message": "missing token"}"#,
+            Some(&regex)
+        ));
+    }
+
+    #[test]
+    /// tokens are refreshed a minute before they expire, or halfway through short lifetimes
+    fn oauth_refresh_in_leaves_a_margin() {
+        assert_eq!(refresh_in(3600), Duration::from_secs(3540));
+        assert_eq!(refresh_in(90), Duration::from_secs(45));
+        assert_eq!(refresh_in(0), Duration::from_secs(1));
+    }
+
+    #[test]
+    /// error responses are described by their error code and description
+    fn oauth_describe_error_reads_error_response() {
+        assert_eq!(
+            describe_error(
+                401,
+                r#"{"error": "invalid_client", "error_description": "bad secret"}"#
+            ),
+            "401 invalid_client: bad secret"
+        );
+        assert_eq!(
+            describe_error(400, r#"{"error": "invalid_scope"}"#),
+            "400 invalid_scope"
+        );
+        assert_eq!(
+            describe_error(500, "oops"),
+            "token endpoint answered with a 500"
+        );
+    }
+}
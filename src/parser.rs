@@ -1,5 +1,8 @@
-use crate::VERSION;
-use clap::{App, Arg};
+use crate::{
+    ban, completions, digest, exit_codes, export, methods, negotiate, rotation, sarif, wordlist,
+    xml, VERSION,
+};
+use clap::{App, AppSettings, Arg, SubCommand};
 
 /// Create and return an instance of [clap::App](https://docs.rs/clap/latest/clap/struct.App.html), i.e. the Command Line Interface's configuration
 pub fn initialize() -> App<'static, 'static> {
@@ -7,23 +10,82 @@ pub fn initialize() -> App<'static, 'static> {
         .version(VERSION)
         .author("Ben 'epi' Risher (@epi052)")
         .about("A fast, simple, recursive content discovery tool written in Rust")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("wordlist")
                 .short("w")
                 .long("wordlist")
                 .value_name("FILE")
-                .help("Path to the wordlist")
+                .help("Path to the wordlist, an http(s) url from which to download it, or the name of a curated wordlist (see `feroxbuster wordlists list`); may be used more than once to combine wordlists")
+                .multiple(true)
+                .number_of_values(1)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("wordlist_cache")
+                .long("wordlist-cache")
+                .value_name("DIRECTORY")
+                .takes_value(true)
+                .help("Save wordlists downloaded from a url to the given directory, and reuse them on later runs")
+        )
+        .arg(
+            Arg::with_name("wordlist_sha256")
+                .long("wordlist-sha256")
+                .value_name("SHA256")
+                .takes_value(true)
+                .help("Refuse to use a wordlist downloaded from a url unless it has the given SHA-256 checksum")
+        )
+        .arg(
+            Arg::with_name("word_prefix")
+                .long("word-prefix")
+                .value_name("PREFIX")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Also request every word with the given prefix (ex: --word-prefix . --word-prefix _)")
+        )
+        .arg(
+            Arg::with_name("word_suffix")
+                .long("word-suffix")
+                .value_name("SUFFIX")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Also request every word with the given suffix (ex: --word-suffix ~ --word-suffix .bak)")
+        )
+        .arg(
+            Arg::with_name("word_case")
+                .long("word-case")
+                .value_name("CASE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .possible_values(&wordlist::CASES)
+                .help("Also request every word in the given case(s)")
+        )
         .arg(
             Arg::with_name("url")
                 .short("u")
                 .long("url")
-                .required_unless("stdin")
+                .required_unless_one(&["stdin", "targets", "import", "generate_completions", "dump_config", "role", "update", "resume_from"])
                 .value_name("URL")
                 .multiple(true)
                 .use_delimiter(true)
-                .help("The target URL(s) (required, unless --stdin used)"),
+                .help("The target URL(s) (required, unless --stdin, --targets, --import, or --resume-from used)"),
+        )
+        .arg(
+            Arg::with_name("targets")
+                .long("targets")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Scan the targets listed in the given toml file, each of which may override headers, wordlist, depth, and rate-limit")
+        )
+        .arg(
+            Arg::with_name("import")
+                .long("import")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Scan the hosts of a Burp sitemap or the scope of a ZAP context, skipping the urls already requested in it")
         )
         .arg(
             Arg::with_name("threads")
@@ -33,6 +95,57 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(true)
                 .help("Number of concurrent threads (default: 50)"),
         )
+        .arg(
+            Arg::with_name("rate_limit")
+                .long("rate-limit")
+                .value_name("RATE")
+                .takes_value(true)
+                .help("Maximum number of requests per second made to each target, including its recursive scans (default: 0, i.e. no limit)"),
+        )
+        .arg(
+            Arg::with_name("start_at")
+                .long("start-at")
+                .value_name("HH:MM")
+                .takes_value(true)
+                .help("Wait until the given local time of day to start making requests")
+        )
+        .arg(
+            Arg::with_name("pause_outside")
+                .long("pause-outside")
+                .value_name("HH:MM-HH:MM")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help("Pause requests outside of the given windows of local time, resuming as the next one opens (ex: --pause-outside 22:00-06:00)")
+        )
+        .arg(
+            Arg::with_name("rate_profiles")
+                .long("rate-profile")
+                .value_name("HH:MM-HH:MM=REQUESTS")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help("Rate limit used instead of --rate-limit within the given window of local time; the first open window applies (ex: --rate-profile 09:00-18:00=50,18:00-09:00=500)")
+        )
+        .arg(
+            Arg::with_name("respect_robots")
+                .long("respect-robots")
+                .takes_value(false)
+                .help("Fetch each target's robots.txt and don't request the paths it disallows; they're reported as present but skipped")
+        )
+        .arg(
+            Arg::with_name("adaptive_concurrency")
+                .long("adaptive-concurrency")
+                .takes_value(false)
+                .help("Adjust the number of requests in flight to each target from its latency and errors, up to --threads")
+        )
+        .arg(
+            Arg::with_name("body_budget")
+                .long("body-budget")
+                .value_name("MEBIBYTES")
+                .takes_value(true)
+                .help("Most mebibytes of response bodies held in memory at once while they're read; requests wait for room, and bodies larger than the budget are truncated (default: 0, i.e. no limit)"),
+        )
         .arg(
             Arg::with_name("depth")
                 .short("d")
@@ -67,6 +180,53 @@ pub fn initialize() -> App<'static, 'static> {
                     "Proxy to use for requests (ex: http(s)://host:port, socks5://host:port)",
                 ),
         )
+        .arg(
+            Arg::with_name("proxy_auth")
+                .long("proxy-auth")
+                .takes_value(true)
+                .value_name("USER:PASSWORD")
+                .requires("proxy")
+                .help("Credentials for --proxy, as user:password or DOMAIN\\user:password (Basic, or SOCKS5's username and password)"),
+        )
+        .arg(
+            Arg::with_name("proxy_ntlm")
+                .long("proxy-ntlm")
+                .takes_value(false)
+                .requires("proxy_auth")
+                .help("Authenticate to --proxy with NTLM, using the credentials of --proxy-auth"),
+        )
+        .arg(
+            Arg::with_name("proxy_file")
+                .long("proxy-file")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with("proxy")
+                .help("File of proxies, one per line, that requests rotate across; proxies that can't be connected to are dropped"),
+        )
+        .arg(
+            Arg::with_name("proxy_rotation")
+                .long("proxy-rotation")
+                .takes_value(true)
+                .value_name("STRATEGY")
+                .possible_values(&rotation::STRATEGIES)
+                .requires("proxy_file")
+                .help("How requests rotate across --proxy-file: in turn, or with each target sticking to one proxy (default: round-robin)"),
+        )
+        .arg(
+            Arg::with_name("replay_proxy")
+                .long("replay-proxy")
+                .takes_value(true)
+                .value_name("PROXY")
+                .help("Proxy through which --verify requests findings again, so that only findings end up in it (i.e. Burp)"),
+        )
+        .arg(
+            Arg::with_name("replay_proxy_auth")
+                .long("replay-proxy-auth")
+                .takes_value(true)
+                .value_name("USER:PASSWORD")
+                .requires("replay_proxy")
+                .help("Credentials for --replay-proxy, as user:password"),
+        )
         .arg(
             Arg::with_name("statuscodes")
                 .short("s")
@@ -86,6 +246,31 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(false)
                 .help("Only print URLs; Don't print status codes, response size, running config, etc...")
         )
+        .arg(
+            Arg::with_name("no_update_check")
+                .long("no-update-check")
+                .takes_value(false)
+                .help("Do not check GitHub for a newer release when printing the banner")
+        )
+        .arg(
+            Arg::with_name("update")
+                .long("update")
+                .takes_value(false)
+                .help("Replace this binary with the latest release, after checking its sha256 digest, then exit")
+        )
+        .arg(
+            Arg::with_name("silent")
+                .long("silent")
+                .takes_value(false)
+                .help("Only print URLs, one per line; no banner, progress bars, or log messages (implies --quiet)")
+        )
+        .arg(
+            Arg::with_name("tui")
+                .long("tui")
+                .takes_value(false)
+                .conflicts_with("silent")
+                .help("Show a full-screen dashboard of the running scans and their findings; everything it replaces is printed once it closes")
+        )
         .arg(
             Arg::with_name("dontfilter")
                 .short("D")
@@ -93,6 +278,79 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(false)
                 .help("Don't auto-filter wildcard responses")
         )
+        .arg(
+            Arg::with_name("soft_404")
+                .long("soft-404")
+                .takes_value(false)
+                .help("Filter responses that look like the target's not-found page (i.e. a 200 reading 'Page Not Found')")
+        )
+        .arg(
+            Arg::with_name("collapse_duplicates")
+                .long("collapse-duplicates")
+                .value_name("NUM")
+                .takes_value(true)
+                .help("Report NUM responses with the same status and body; the rest are listed together once the scan completes (default: 0, i.e. nothing is collapsed)")
+        )
+        .arg(
+            Arg::with_name("filter_hashes")
+                .long("filter-hash")
+                .value_name("HASH")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help("Filter out responses whose body has the given md5, sha1, or sha256 hash (ex: --filter-hash 5d41402abc4b2a76b9719d911017c592)")
+        )
+        .arg(
+            Arg::with_name("filter_redirects")
+                .long("filter-redirects")
+                .value_name("NUM")
+                .takes_value(true)
+                .requires("redirects")
+                .help("Filter out responses that followed more than NUM redirects (i.e. through an SSO gateway); requires --redirects")
+        )
+        .arg(
+            Arg::with_name("filter_redirect_loops")
+                .long("filter-redirect-loops")
+                .takes_value(false)
+                .requires("redirects")
+                .help("Filter out redirects that lead back to a url already requested; requires --redirects")
+        )
+        .arg(
+            Arg::with_name("detect_dynamic")
+                .long("detect-dynamic")
+                .takes_value(false)
+                .help("Request each potential finding twice; pages that change every time (timestamps, CSRF tokens) aren't compared by a size that changes, nor by the words that change")
+        )
+        .arg(
+            Arg::with_name("use_listings")
+                .long("use-listings")
+                .takes_value(false)
+                .help("Request each directory first; enumerate open directory listings instead of brute forcing them")
+        )
+        .arg(
+            Arg::with_name("analyze_403")
+                .long("analyze-403")
+                .takes_value(false)
+                .help("Probe around 403s to tell directories with forbidden listings (recursed into) from blanket 403s (noise)")
+        )
+        .arg(
+            Arg::with_name("security_headers")
+                .long("security-headers")
+                .takes_value(false)
+                .help("Send requests with an Origin, then record each finding's CORS policy (permissive ones are highlighted) and missing security headers")
+        )
+        .arg(
+            Arg::with_name("check_length")
+                .long("check-length")
+                .takes_value(false)
+                .help("Read the body of every response that may be reported, flagging bodies that don't match their Content-Length (i.e. truncated)")
+        )
+        .arg(
+            Arg::with_name("slash_probe")
+                .long("slash-probe")
+                .takes_value(false)
+                .help("Request each hit both with and without a trailing slash; the pair of statuses decides whether it's a directory worth recursing into")
+        )
         .arg(
             Arg::with_name("output")
                 .short("o")
@@ -101,6 +359,364 @@ pub fn initialize() -> App<'static, 'static> {
                 .help("Output file to write results to (default: stdout)")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("output_template")
+                .long("output-template")
+                .value_name("TEMPLATE")
+                .takes_value(true)
+                .conflicts_with_all(&["quiet", "json"])
+                .help("Format of each result's line, with {{field}}s and {{#if field}}...{{else}}...{{/if}} blocks; fields: status, size, url, path, redirect, content_type, change, listing, body_hash, favicon_hash, header.NAME (ex: --output-template '{{status}} {{size}} {{url}}{{#if redirect}} -> {{redirect}}{{/if}}')")
+        )
+        .arg(
+            Arg::with_name("output_max_size")
+                .long("output-max-size")
+                .value_name("SIZE")
+                .takes_value(true)
+                .requires("output")
+                .help("Roll --output over to numbered files (FILE.1, FILE.2, ...) once it reaches SIZE, i.e. 500M; K, M, G, and T are multiples of 1024")
+        )
+        .arg(
+            Arg::with_name("output_max_files")
+                .long("output-max-files")
+                .value_name("NUM_FILES")
+                .takes_value(true)
+                .requires("output_max_size")
+                .help("Number of rollover files of --output kept, the oldest is removed (default: 5)")
+        )
+        .arg(
+            Arg::with_name("encrypt_output")
+                .long("encrypt-output")
+                .value_name("RECIPIENT")
+                .takes_value(true)
+                .conflicts_with("append_runs")
+                .help("Encrypt --output, the other result files, and --save-responses to the given age recipient (ex: age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p); decrypt them with age -d -i KEY_FILE")
+        )
+        .arg(
+            Arg::with_name("append_runs")
+                .long("append-runs")
+                .takes_value(false)
+                .requires("output")
+                .help("Keep the history of runs in --output: each run starts with a header recording its id, start time, configuration hash, and targets, and --compare reads the last run")
+        )
+        .arg(
+            Arg::with_name("output_errors")
+                .long("output-errors")
+                .takes_value(false)
+                .help("Also write log messages (errors by default, more with -v) to --output; only results are written otherwise")
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .takes_value(false)
+                .help("Write results and log messages to --output as json records, one per line")
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&sarif::FORMATS)
+                .help("Format of the results written to --output; json is the same as --json, sarif writes a SARIF 2.1.0 log once the scan completes (default: text)")
+        )
+        .arg(
+            Arg::with_name("body_hash")
+                .long("body-hash")
+                .value_name("ALGORITHM")
+                .takes_value(true)
+                .possible_values(&digest::BODY_HASHES)
+                .help("Hash the body of each reported response, recorded in json, xml, and syslog output")
+        )
+        .arg(
+            Arg::with_name("response_cache")
+                .long("response-cache")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Cache the findings' ETag / Last-Modified in FILE; findings of previous runs are requested conditionally, unchanged ones cost a 304 instead of their body")
+        )
+        .arg(
+            Arg::with_name("compare")
+                .long("compare")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Output file of a previous run; flag results as NEW, CHANGED, or MISSING relative to it")
+        )
+        .arg(
+            Arg::with_name("save_responses")
+                .long("save-responses")
+                .value_name("DIRECTORY")
+                .takes_value(true)
+                .help("Save the body and headers of each reported response beneath the given directory")
+        )
+        .arg(
+            Arg::with_name("compress_responses")
+                .long("compress-responses")
+                .takes_value(false)
+                .requires("save_responses")
+                .help("Compress the bodies saved by --save-responses into DIRECTORY/bodies.gz, indexed by url in DIRECTORY/index.jsonl")
+        )
+        .arg(
+            Arg::with_name("screenshot")
+                .long("screenshot")
+                .value_name("DIRECTORY")
+                .takes_value(true)
+                .help("Save screenshots of each reported 2xx/401/403 page beneath the given directory, along with a gallery of them, using a headless chromium (needs the screenshots feature)")
+        )
+        .arg(
+            Arg::with_name("tree")
+                .long("tree")
+                .takes_value(false)
+                .help("Print a tree of all results, grouped by host, once the scan completes")
+        )
+        .arg(
+            Arg::with_name("tree_output")
+                .long("tree-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the tree of all results to the given file (implies --tree)")
+        )
+        .arg(
+            Arg::with_name("xml_output")
+                .long("xml-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write all results to the given file as an xml document once the scan completes")
+        )
+        .arg(
+            Arg::with_name("xml_style")
+                .long("xml-style")
+                .value_name("STYLE")
+                .takes_value(true)
+                .possible_values(&xml::XML_STYLES)
+                .help("Structure of the --xml-output document; dirb groups results by directory (default: ferox)")
+        )
+        .arg(
+            Arg::with_name("statsd")
+                .long("statsd")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .help("Push request rate, error, and findings counters to the given statsd server over udp while scanning")
+        )
+        .arg(
+            Arg::with_name("statsd_prefix")
+                .long("statsd-prefix")
+                .value_name("PREFIX")
+                .takes_value(true)
+                .requires("statsd")
+                .help("Prefix of the name of every metric pushed to --statsd (default: feroxbuster)")
+        )
+        .arg(
+            Arg::with_name("junit_output")
+                .long("junit-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the outcome of each --policy to the given file as a JUnit xml document once the scan completes")
+        )
+        .arg(
+            Arg::with_name("policies")
+                .long("policy")
+                .value_name("NAME=REGEX[=STATUS,...]")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Check that no result whose path matches REGEX is reported with one of the statuses (default: 2xx), reported as a test case of --junit-output (ex: --policy 'no 200s under /admin=^/admin/=200')")
+        )
+        .arg(
+            Arg::with_name("export_output")
+                .long("export-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the reported urls to the given file as a list of targets for other tools once the scan completes")
+        )
+        .arg(
+            Arg::with_name("export")
+                .long("export")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&export::EXPORT_FORMATS)
+                .requires("export_output")
+                .help("Format of the --export-output list; httpx collapses redirects to a trailing slash, nuclei lists the directories results were found in (default: plain)")
+        )
+        .arg(
+            Arg::with_name("export_status")
+                .long("export-status")
+                .value_name("STATUS_CODE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .requires("export_output")
+                .help("Only export the urls reported with the given status codes (default: every reported url)")
+        )
+        .arg(
+            Arg::with_name("curl_output")
+                .long("curl-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Append a ready-to-run curl command for each reported result to the given file")
+        )
+        .arg(
+            Arg::with_name("http_output")
+                .long("http-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Append a request for each reported result to the given .http file (VS Code REST Client format)")
+        )
+        .arg(
+            Arg::with_name("tags")
+                .long("tag")
+                .value_name("KEY=VALUE")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Attach a key/value pair to every json record and the statistics output (ex: --tag team=red --tag env=prod)")
+        )
+        .arg(
+            Arg::with_name("error_log")
+                .long("error-log")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write every failed request to the given file as json records, regardless of verbosity")
+        )
+        .arg(
+            Arg::with_name("log_filtered")
+                .long("log-filtered")
+                .takes_value(false)
+                .help("Also write filtered responses, and why they were filtered, to --error-log")
+        )
+        .arg(
+            Arg::with_name("stats_output")
+                .long("stats-output")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write scan statistics as JSON to the given file once the scan completes (use - for stdout)")
+        )
+        .arg(
+            Arg::with_name("stats_interval")
+                .long("stats-interval")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .help("Also rewrite --stats-output every SECONDS seconds while scanning, for live dashboards (default: 0, i.e. only once complete)")
+        )
+        .arg(
+            Arg::with_name("state_file")
+                .long("state-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Save the scan's progress to FILE every --checkpoint-interval seconds, whenever a directory's scan finishes, and on Ctrl+C")
+        )
+        .arg(
+            Arg::with_name("checkpoint_interval")
+                .long("checkpoint-interval")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .help("Seconds between saves of --state-file while scanning (default: 60; 0 only saves when a directory's scan finishes and on Ctrl+C)")
+        )
+        .arg(
+            Arg::with_name("resume_from")
+                .long("resume-from")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Pick up the scan saved to FILE by --state-file: each directory is only requested with the words it wasn't requested with yet, and results are added to the saved ones (saved back to FILE unless --state-file is used)")
+        )
+        .arg(
+            Arg::with_name("summary")
+                .long("summary")
+                .takes_value(false)
+                .help("Print a summary table once the scan completes: responses per status code grouped by class, top directories by findings, filtered responses, bytes transferred, and duration; also written to --output")
+        )
+        .arg(
+            Arg::with_name("exit_on")
+                .long("exit-on")
+                .value_name("CONDITION")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .possible_values(&exit_codes::CONDITIONS)
+                .help("Exit with a distinct non-zero code when the given condition(s) are met (see EXIT CODES below)")
+        )
+        .arg(
+            Arg::with_name("error_threshold")
+                .long("error-threshold")
+                .value_name("NUM_ERRORS")
+                .takes_value(true)
+                .help("Number of failed requests allowed before --exit-on errors takes effect (default: 0)")
+        )
+        .arg(
+            Arg::with_name("ban_policy")
+                .long("ban-policy")
+                .value_name("POLICY")
+                .takes_value(true)
+                .possible_values(&ban::POLICIES)
+                .help("Reaction to requests that look blocked by a WAF or rate limiter (default: alert)")
+        )
+        .arg(
+            Arg::with_name("canary")
+                .long("canary")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Known-good url re-requested during the scan to notice bans (default: the first 2xx finding, with --canary-interval)")
+        )
+        .arg(
+            Arg::with_name("canary_interval")
+                .long("canary-interval")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .help("Seconds between canary requests (default: 0, i.e. no canary; 30 when --canary is used)")
+        )
+        .arg(
+            Arg::with_name("canary_pause")
+                .long("canary-pause")
+                .takes_value(false)
+                .help("Pause all requests while the canary's status differs from what it was, until it recovers")
+        )
+        .arg(
+            Arg::with_name("verify")
+                .long("verify")
+                .takes_value(false)
+                .help("Request every finding again once the scan is complete, flagging the ones that no longer reproduce")
+        )
+        .arg(
+            Arg::with_name("fingerprint")
+                .long("fingerprint")
+                .takes_value(false)
+                .help("Fingerprint each target's technologies (i.e. WordPress, Tomcat, IIS) and suggest extensions and wordlists for them")
+        )
+        .arg(
+            Arg::with_name("smart")
+                .long("smart")
+                .takes_value(false)
+                .help("Fingerprint each target and scan it with the suggested extensions and wordlists, on top of the ones given")
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Path to a ferox-config.toml, read after (and taking precedence over) any found in the default locations")
+        )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .value_name("NAME")
+                .takes_value(true)
+                .help("Use the values of the named profile from a config file, i.e. [profile.stealth]")
+        )
+        .arg(
+            Arg::with_name("dump_config")
+                .long("dump-config")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .min_values(0)
+                .possible_values(&["toml", "json"])
+                .help("Print the effective configuration (defaults, config files, environment, and options combined) and exit (default: toml)")
+        )
+        .arg(
+            Arg::with_name("generate_completions")
+                .long("generate-completions")
+                .value_name("SHELL")
+                .takes_value(true)
+                .possible_values(&completions::SHELLS)
+                .help("Print a completion script for the given shell and exit")
+        )
         .arg(
             Arg::with_name("useragent")
                 .short("a")
@@ -111,6 +727,17 @@ pub fn initialize() -> App<'static, 'static> {
                     "Sets the User-Agent (default: feroxbuster/VERSION)"
                 ),
         )
+        .arg(
+            Arg::with_name("method")
+                .long("method")
+                .value_name("METHOD")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .possible_values(&methods::METHODS)
+                .case_insensitive(true)
+                .help("HTTP method of the requests made for each word, then the ones a request rejected with a 405 or 501 is retried with, in order (ex: --method GET,POST); a lone HEAD is retried with GET (default: GET)")
+        )
         .arg(
             Arg::with_name("redirects")
                 .short("r")
@@ -137,6 +764,17 @@ pub fn initialize() -> App<'static, 'static> {
                     "File extension(s) to search for (ex: -x php -x pdf js)",
                 ),
         )
+        .arg(
+            Arg::with_name("show_headers")
+                .long("show-headers")
+                .value_name("HEADER")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help(
+                    "Response header(s) to show with each result (ex: --show-headers server,location)",
+                ),
+        )
         .arg(
             Arg::with_name("headers")
                 .short("H")
@@ -149,6 +787,101 @@ pub fn initialize() -> App<'static, 'static> {
                     "Specify HTTP headers (ex: -H Header:val 'stuff: things')",
                 ),
         )
+        .arg(
+            Arg::with_name("header_order")
+                .long("header-order")
+                .value_name("ORDER")
+                .takes_value(true)
+                .help("Order in which request headers are sent: random (for every request), or header names separated by commas (ex: host,accept,user-agent)"),
+        )
+        .arg(
+            Arg::with_name("login_url")
+                .long("login-url")
+                .value_name("URL")
+                .takes_value(true)
+                .requires("login_data")
+                .help("Post --login-data to URL before scanning, send its session cookies along with every request, and log in again when redirected back to it")
+        )
+        .arg(
+            Arg::with_name("login_data")
+                .long("login-data")
+                .value_name("DATA")
+                .takes_value(true)
+                .requires("login_url")
+                .help("Urlencoded form posted to --login-url (ex: --login-data 'user=admin&pass=secret')")
+        )
+        .arg(
+            Arg::with_name("login_success_regex")
+                .long("login-success-regex")
+                .value_name("REGEX")
+                .takes_value(true)
+                .requires("login_url")
+                .help("Regex the login's response body or Location must match for it to succeed (default: any 2xx or 3xx)")
+        )
+        .arg(
+            Arg::with_name("oauth_token_url")
+                .long("oauth-token-url")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Request a bearer token from URL with the OAuth2 client-credentials grant (or --oauth-refresh-token), send it along with every request, and request a new one before it expires")
+        )
+        .arg(
+            Arg::with_name("oauth_client_id")
+                .long("oauth-client-id")
+                .value_name("ID")
+                .takes_value(true)
+                .requires("oauth_token_url")
+                .help("Client id used to request a token from --oauth-token-url")
+        )
+        .arg(
+            Arg::with_name("oauth_client_secret")
+                .long("oauth-client-secret")
+                .value_name("SECRET")
+                .takes_value(true)
+                .requires("oauth_token_url")
+                .help("Client secret used to request a token from --oauth-token-url")
+        )
+        .arg(
+            Arg::with_name("oauth_scopes")
+                .long("oauth-scopes")
+                .value_name("SCOPE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .requires("oauth_token_url")
+                .help("Scopes requested along with the token from --oauth-token-url (ex: --oauth-scopes read:users,read:orders)")
+        )
+        .arg(
+            Arg::with_name("oauth_refresh_token")
+                .long("oauth-refresh-token")
+                .value_name("TOKEN")
+                .takes_value(true)
+                .requires("oauth_token_url")
+                .help("Exchange TOKEN at --oauth-token-url for access tokens, instead of using the client-credentials grant")
+        )
+        .arg(
+            Arg::with_name("oauth_expired_regex")
+                .long("oauth-expired-regex")
+                .value_name("REGEX")
+                .takes_value(true)
+                .requires("oauth_token_url")
+                .help("Regex matching the body of 401s sent once the token expired, on top of WWW-Authenticate: Bearer error=\"invalid_token\"")
+        )
+        .arg(
+            Arg::with_name("auth")
+                .long("auth")
+                .value_name("SCHEME")
+                .takes_value(true)
+                .possible_values(&negotiate::AUTH_SCHEMES)
+                .help("Authenticate requests with SCHEME; negotiate uses the system's Kerberos credentials (needs the negotiate feature)")
+        )
+        .arg(
+            Arg::with_name("aws_sigv4")
+                .long("aws-sigv4")
+                .value_name("REGION/SERVICE")
+                .takes_value(true)
+                .help("Sign requests with AWS Signature Version 4 for REGION/SERVICE (ex: --aws-sigv4 us-east-1/execute-api), using credentials from the AWS_* environment variables or the shared credentials file")
+        )
         .arg(
             Arg::with_name("queries")
                 .short("Q")
@@ -202,7 +935,157 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(false)
                 .help("Extract links from response body (html, javascript, etc...); make new requests based on findings (default: false)")
         )
-
+        .arg(
+            Arg::with_name("slack_webhook")
+                .long("slack-webhook")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Send batches of findings and a completion summary to a Slack incoming webhook")
+        )
+        .arg(
+            Arg::with_name("discord_webhook")
+                .long("discord-webhook")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Send batches of findings and a completion summary to a Discord webhook")
+        )
+        .arg(
+            Arg::with_name("notify_batch")
+                .long("notify-batch")
+                .value_name("NUM_FINDINGS")
+                .takes_value(true)
+                .help("Number of findings to include in each webhook notification (default: 25)")
+        )
+        .arg(
+            Arg::with_name("syslog")
+                .long("syslog")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Send findings and errors to a syslog collector (udp|tcp|tls://host[:port])")
+        )
+        .arg(
+            Arg::with_name("email_report")
+                .long("email-report")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help("Email a summary of the scan, along with --output, when it completes, is cancelled, or fails (ex: --email-report alice@example.com,bob@example.com)")
+        )
+        .arg(
+            Arg::with_name("smtp")
+                .long("smtp")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Smtp server the --email-report is sent through (smtp|smtps://[user:password@]host[:port])")
+        )
+        .arg(
+            Arg::with_name("email_from")
+                .long("email-from")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .help("Address the --email-report is sent from (default: feroxbuster@<hostname>)")
+        )
+        .arg(
+            Arg::with_name("role")
+                .long("role")
+                .value_name("ROLE")
+                .takes_value(true)
+                .possible_values(&["coordinator", "worker"])
+                .help("Shard the scan across --worker(s) as its coordinator, or serve the API coordinators submit shards to as a worker")
+        )
+        .arg(
+            Arg::with_name("workers")
+                .long("worker")
+                .value_name("URL")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Url of a worker (feroxbuster --role worker) to shard the scan across; may be used more than once")
+        )
+        .arg(
+            Arg::with_name("listen")
+                .long("listen")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .default_value("127.0.0.1:8000")
+                .help("Address a worker listens on (with --role worker)")
+        )
+        .arg(
+            Arg::with_name("api_token")
+                .long("api-token")
+                .value_name("TOKEN")
+                .takes_value(true)
+                .help("Secret a coordinator sends its workers, which they require; needed with --role (FEROX_API_TOKEN keeps it out of the process list)")
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Run an HTTP API to submit, follow, and cancel scans from other tools")
+                .arg(
+                    Arg::with_name("listen")
+                        .long("listen")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .default_value("127.0.0.1:8000")
+                        .help("Address to listen on")
+                )
+                .arg(
+                    Arg::with_name("api_token")
+                        .long("api-token")
+                        .value_name("TOKEN")
+                        .takes_value(true)
+                        .help("Bearer token every request has to carry (default: a random one, printed on start; FEROX_API_TOKEN keeps it out of the process list)")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("wordlists")
+                .about("List, download, and update curated wordlists, which --wordlist then takes by name (i.e. -w raft-medium)")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the curated wordlists, and whether they were downloaded")
+                )
+                .subcommand(
+                    SubCommand::with_name("download")
+                        .about("Download the given wordlists, replacing the copies already downloaded")
+                        .arg(
+                            Arg::with_name("names")
+                                .value_name("NAME")
+                                .multiple(true)
+                                .required(true)
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("update")
+                        .about("Download every wordlist downloaded before again")
+                )
+                .subcommand(
+                    SubCommand::with_name("path")
+                        .about("Print where the given wordlists are kept, i.e. to use them with other tools")
+                        .arg(
+                            Arg::with_name("names")
+                                .value_name("NAME")
+                                .multiple(true)
+                                .required(true)
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("Compare the results of two runs (--json or normal output files), listing the new, changed, and missing results of each host")
+                .arg(
+                    Arg::with_name("old")
+                        .value_name("OLD")
+                        .required(true)
+                        .help("Results of the earlier run")
+                )
+                .arg(
+                    Arg::with_name("new")
+                        .value_name("NEW")
+                        .required(true)
+                        .help("Results of the later run")
+                )
+        )
         .after_help(r#"NOTE:
     Options that take multiple values are very flexible.  Consider the following ways of specifying
     extensions:
@@ -213,6 +1096,29 @@ pub fn initialize() -> App<'static, 'static> {
     All of the methods above (multiple flags, space separated, comma separated, etc...) are valid
     and interchangeable.  The same goes for urls, headers, status codes, queries, and size filters.
 
+EXIT CODES:
+    0   the scan completed and none of the conditions given to --exit-on were met
+    1   a fatal error occurred
+    2   no-findings: the scan completed without reporting any results
+    3   unreachable: at least one target couldn't be reached
+    4   cancelled: the scan was cancelled with Ctrl+C
+    5   errors: more requests failed than allowed by --error-threshold
+    6   requests looked blocked and --ban-policy stop ended the scan
+    130 the scan was cancelled with Ctrl+C after sending the --email-report, without --exit-on cancelled
+
+ENVIRONMENT:
+    Any option that can be set in ferox-config.toml, other than highlight rules, can also be set with an
+    environment variable named FEROX_ followed by the option's name in uppercase, i.e. FEROX_PROXY,
+    FEROX_THREADS, FEROX_EXTRACT_LINKS. Command line options take precedence over environment variables,
+    which take precedence over config files. Empty variables are ignored.
+
+    flags               true/false, 1/0, yes/no, or on/off        FEROX_INSECURE=true
+    lists               comma-separated                           FEROX_STATUSCODES=200,301
+    queries, tags       comma-separated KEY=VALUE pairs           FEROX_TAGS=team=red,env=prod
+    headers             newline-separated Name:Value pairs        FEROX_HEADERS=$'Accept: */*\nX-Key: 1'
+    FEROX_CONFIG        config file to read, same as --config
+    FEROX_PROFILE       config file profile to use, same as --profile
+
 EXAMPLES:
     Multiple headers:
         ./feroxbuster -u http://127.1 -H Accept:application/json "Authorization: Bearer {token}"
@@ -229,12 +1135,78 @@ EXAMPLES:
     Proxy traffic through a SOCKS proxy
         ./feroxbuster -u http://127.1 --proxy socks5://127.0.0.1:9050
 
+    Proxy traffic through a corporate proxy that requires NTLM
+        ./feroxbuster -u http://127.1 --proxy http://proxy.corp:3128 --proxy-auth 'CORP\alice:hunter2' --proxy-ntlm
+
     Pass auth token via query parameter
         ./feroxbuster -u http://127.1 --query token=0123456789ABCDEF
 
+    Weekly monitoring; flag what's new, changed, or missing since last week's json results
+        ./feroxbuster -u http://127.1 --json -o this-week.json --compare last-week.json
+
+    Show server banners and redirect targets alongside each result
+        ./feroxbuster -u http://127.1 --show-headers server,x-powered-by,location
+
+    Save reported responses for offline grepping
+        ./feroxbuster -u http://127.1 --save-responses responses/ && grep -ril password responses/
+
+    Summarize a deep recursive scan as a tree of results, also saved to disk
+        ./feroxbuster -u http://127.1 --depth 0 --tree-output tree.txt
+
+    Let a wrapper script decide what to do next based on the scan's statistics
+        ./feroxbuster -u http://127.1 --silent --stats-output stats.json && jq .errors.total stats.json
+
+    Fail a CI job when nothing was found or more than 10 requests failed
+        ./feroxbuster -u http://127.1 --exit-on no-findings,errors --error-threshold 10
+
+    Keep ready-to-run curl commands for manually verifying each result through Burp
+        ./feroxbuster -u http://127.1 --proxy http://127.0.0.1:8080 --curl-output verify.sh
+
+    Tag json results so that they can still be attributed after merging many scans
+        ./feroxbuster -u http://127.1 --json -o results.json --tag engagement=acme --tag tester=epi
+
+    Keep a record of every failed request and filtered response for a post-mortem of a weird scan
+        ./feroxbuster -u http://127.1 --error-log errors.json --log-filtered
+
+    Use the values of the [profile.stealth] table from a config file:
+        ./feroxbuster -u http://127.1 --profile stealth
+
+    Import results into a vulnerability management platform
+        ./feroxbuster -u http://127.1 --xml-output results.xml
+
+    See which settings apply after combining config files, environment variables, and options:
+        ./feroxbuster --profile stealth --dump-config
+
+    Download the wordlist, keeping a copy for later runs:
+        ./feroxbuster -u http://127.1 --wordlist-cache ~/.cache/feroxbuster \
+            -w https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/raft-medium-directories.txt
+
+    Enable tab completion in bash:
+        ./feroxbuster --generate-completions bash | sudo tee /etc/bash_completion.d/feroxbuster
+
+    Scan the targets in a toml file, each with its own headers, wordlist, depth, or rate limit
+        ./feroxbuster --targets targets.toml --rate-limit 100
+
+    Compose with other tools; only discovered urls are written to stdout
+        ./feroxbuster -u http://127.1 --silent | httpx | nuclei
+
     Find links in javascript/html and make additional requests based on results
         ./feroxbuster -u http://127.1 --extract-links
 
+    Send findings to a Slack channel in batches of 10
+        ./feroxbuster -u http://127.1 --slack-webhook https://hooks.slack.com/services/T0/B0/XXXX --notify-batch 10
+
+    Send findings and errors to a syslog collector over TLS
+        ./feroxbuster -u http://127.1 --syslog tls://logs.example.com:6514
+
+    Drive scans from a dashboard; see "Drive scans over HTTP" in the README for the endpoints
+        ./feroxbuster serve --listen 127.0.0.1:8000
+
+    Spread a large scan across two machines, each with its own rate limit and source ip
+        export FEROX_API_TOKEN=$(openssl rand -hex 16)    (the same secret everywhere)
+        ./feroxbuster --role worker --listen 10.0.0.2:8000    (on each worker, on the interface the coordinator reaches)
+        ./feroxbuster -u http://127.1 --role coordinator --worker http://10.0.0.2:8000 --worker http://10.0.0.3:8000 --rate-limit 50
+
     Ludicrous speed... go!
         ./feroxbuster -u http://127.1 -t 200
     "#)
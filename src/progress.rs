@@ -1,5 +1,10 @@
-use crate::config::{CONFIGURATION, PROGRESS_BAR};
+use crate::config::{CONFIGURATION, PROGRESS_BAR, PROGRESS_TOTAL};
 use indicatif::{ProgressBar, ProgressStyle};
+use std::convert::TryInto;
+
+/// Number of requests made by [wildcard_test](../heuristics/fn.wildcard_test.html) against each
+/// directory, unless `--dontfilter` is used
+const WILDCARD_REQUESTS: usize = 2;
 
 /// Add an [indicatif::ProgressBar](https://docs.rs/indicatif/latest/indicatif/struct.ProgressBar.html)
 /// to the global [PROGRESS_BAR](../config/struct.PROGRESS_BAR.html)
@@ -8,7 +13,9 @@ pub fn add_bar(prefix: &str, length: u64, hidden: bool) -> ProgressBar {
         ProgressStyle::default_bar().template("")
     } else {
         ProgressStyle::default_bar()
-            .template("[{bar:.cyan/blue}] - {elapsed:<4} {pos:>7}/{len:7} {per_sec:7} {prefix}")
+            .template(
+                "[{bar:.cyan/blue}] - {elapsed:<4} {pos:>7}/{len:7} {per_sec:7} ~{eta:<4} {prefix}",
+            )
             .progress_chars("#>-")
     };
 
@@ -16,7 +23,88 @@ pub fn add_bar(prefix: &str, length: u64, hidden: bool) -> ProgressBar {
 
     progress_bar.set_style(style);
 
-    progress_bar.set_prefix(&prefix);
+    progress_bar.set_prefix(prefix);
+
+    progress_bar
+}
+
+/// Add the bar that tracks all requests across every directory scan to the global
+/// [PROGRESS_BAR](../config/struct.PROGRESS_BAR.html)
+///
+/// Its length starts at zero and grows as scans are started, see [inc_total_length](fn.inc_total_length.html)
+pub fn add_total_bar() -> ProgressBar {
+    let style = if CONFIGURATION.quiet {
+        ProgressStyle::default_bar().template("")
+    } else {
+        ProgressStyle::default_bar()
+            .template("[{bar:.yellow/red}] - {elapsed:<4} {pos:>7}/{len:7} {per_sec:7} ~{eta:<4} {prefix}")
+            .progress_chars("#>-")
+    };
+
+    let progress_bar = PROGRESS_BAR.add(ProgressBar::new(0));
+
+    progress_bar.set_style(style);
+
+    progress_bar.set_prefix("total");
+
+    progress_bar
+}
+
+/// Add a bar that only displays its message to the global
+/// [PROGRESS_BAR](../config/struct.PROGRESS_BAR.html); used for the live statistics line
+pub fn add_status_bar() -> ProgressBar {
+    let style = if CONFIGURATION.quiet {
+        ProgressStyle::default_bar().template("")
+    } else {
+        ProgressStyle::default_bar().template("{msg}")
+    };
+
+    let progress_bar = PROGRESS_BAR.add(ProgressBar::new(0));
+
+    progress_bar.set_style(style);
 
     progress_bar
 }
+
+/// Number of requests expected to be made against a single directory; used as the length of
+/// the directory's bar
+///
+/// each word is requested once as-is and once per extension, plus any wildcard requests
+pub fn requests_per_directory(num_words: usize, num_extensions: usize, dontfilter: bool) -> u64 {
+    let wildcard = if dontfilter { 0 } else { WILDCARD_REQUESTS };
+
+    (num_words * (num_extensions + 1) + wildcard)
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+/// Increment the given directory's bar along with the total bar
+pub fn inc(bar: &ProgressBar, delta: u64) {
+    bar.inc(delta);
+    PROGRESS_TOTAL.inc(delta);
+}
+
+/// Grow the total bar by the number of requests expected from a newly started directory scan
+pub fn inc_total_length(delta: u64) {
+    PROGRESS_TOTAL.inc_length(delta);
+}
+
+/// Increment only the total bar; used for requests that aren't made as part of any directory's
+/// bar, i.e. those of `--detect-dynamic`
+pub fn inc_total(delta: u64) {
+    PROGRESS_TOTAL.inc(delta);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// words, extensions, and wildcard requests all count toward the expected requests
+    fn progress_requests_per_directory_counts_all_requests() {
+        assert_eq!(requests_per_directory(10, 0, true), 10);
+        assert_eq!(requests_per_directory(10, 0, false), 12);
+        assert_eq!(requests_per_directory(10, 2, false), 32);
+        assert_eq!(requests_per_directory(0, 2, true), 0);
+    }
+}
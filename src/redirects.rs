@@ -0,0 +1,177 @@
+use lazy_static::lazy_static;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Maximum number of redirects followed for a single request with `--redirects`
+pub const MAX_REDIRECTS: usize = 10;
+
+lazy_static! {
+    /// Chains of the requests that were redirected, by the url originally requested, until
+    /// `utils::make_redirected_request` takes them once the request is done
+    static ref CHAINS: Mutex<HashMap<Url, Chain>> = Mutex::new(HashMap::new());
+}
+
+/// Redirects followed for a single request (see `--redirects`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    /// every url requested, starting with the original one and ending with the one that was
+    /// answered
+    pub urls: Vec<Url>,
+
+    /// whether or not following the chain further would request one of its urls again; the
+    /// redirect that would have is the response
+    pub looped: bool,
+}
+
+impl Chain {
+    /// Number of redirects followed
+    pub fn hops(&self) -> usize {
+        self.urls.len().saturating_sub(1)
+    }
+
+    /// Url originally requested
+    pub fn origin(&self) -> &Url {
+        &self.urls[0]
+    }
+
+    /// Description of the chain, as shown next to a result; i.e. `2 redirects from
+    /// http://localhost/admin` or `redirect loop after 3 redirects from http://localhost/a`
+    pub fn describe(&self) -> String {
+        let hops = format!(
+            "{} redirect{} from {}",
+            self.hops(),
+            if self.hops() == 1 { "" } else { "s" },
+            self.origin()
+        );
+
+        if self.looped && self.hops() == 0 {
+            format!("redirect loop from {}", self.origin())
+        } else if self.looped {
+            format!("redirect loop after {}", hops)
+        } else {
+            hops
+        }
+    }
+
+    /// Create the `redirects` entry of a result's `--json` record
+    pub fn as_json(&self) -> Value {
+        json!({
+            "from": self.origin().as_str(),
+            "final_url": self.urls[self.urls.len() - 1].as_str(),
+            "hops": self.hops(),
+            "loop": self.looped,
+            "chain": self.urls.iter().map(Url::as_str).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Redirect policy of the client used with `--redirects`; up to `MAX_REDIRECTS` redirects are
+/// followed, and the chain of each request is recorded until the request is done
+///
+/// Unlike reqwest's own policy, a redirect back to a url already requested isn't followed; the
+/// redirect itself is the response, marked as a loop, instead of an error after `MAX_REDIRECTS`
+pub fn policy() -> Policy {
+    Policy::custom(|attempt| {
+        let looped = attempt.previous().contains(attempt.url());
+
+        if looped {
+            record(attempt.previous().to_vec(), true);
+            return attempt.stop();
+        }
+
+        if attempt.previous().len() == MAX_REDIRECTS {
+            let error = format!("too many redirects (more than {})", MAX_REDIRECTS);
+            return attempt.error(error);
+        }
+
+        let mut urls = attempt.previous().to_vec();
+        urls.push(attempt.url().to_owned());
+        record(urls, false);
+
+        attempt.follow()
+    })
+}
+
+/// Remember the chain of a request, replacing what was known of it so far
+fn record(urls: Vec<Url>, looped: bool) {
+    let origin = urls[0].clone();
+
+    match CHAINS.lock() {
+        Ok(mut chains) => {
+            chains.insert(origin, Chain { urls, looped });
+        }
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+        }
+    }
+}
+
+/// Take the chain of the request made to the given url; `None` when it wasn't redirected
+pub fn take(url: &Url) -> Option<Chain> {
+    match CHAINS.lock() {
+        Ok(mut chains) => chains.remove(url),
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to create a chain of the given paths on localhost
+    fn chain(paths: &[&str], looped: bool) -> Chain {
+        Chain {
+            urls: paths
+                .iter()
+                .map(|path| Url::parse(&format!("http://localhost{}", path)).unwrap())
+                .collect(),
+            looped,
+        }
+    }
+
+    #[test]
+    /// chains are described by their hops and origin, loops say so
+    fn redirects_chain_describes_hops_and_loops() {
+        let sso = chain(&["/admin", "/sso/authorize", "/login"], false);
+        assert_eq!(sso.hops(), 2);
+        assert_eq!(sso.describe(), "2 redirects from http://localhost/admin");
+
+        let record = sso.as_json();
+        assert_eq!(record["final_url"], "http://localhost/login");
+        assert_eq!(record["hops"], 2);
+        assert_eq!(record["chain"].as_array().unwrap().len(), 3);
+
+        let single = chain(&["/old", "/new"], false);
+        assert_eq!(single.describe(), "1 redirect from http://localhost/old");
+
+        let looped = chain(&["/a", "/b"], true);
+        assert_eq!(
+            looped.describe(),
+            "redirect loop after 1 redirect from http://localhost/a"
+        );
+        assert_eq!(looped.as_json()["loop"], true);
+
+        let itself = chain(&["/a"], true);
+        assert_eq!(itself.describe(), "redirect loop from http://localhost/a");
+    }
+
+    #[test]
+    /// chains are taken once, by the url originally requested
+    fn redirects_take_removes_chain() {
+        let urls = chain(&["/take-me", "/elsewhere"], false).urls;
+        let origin = urls[0].clone();
+
+        record(urls, false);
+
+        assert_eq!(take(&origin).unwrap().hops(), 1);
+        assert!(take(&origin).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::config::Configuration;
+use reqwest::{StatusCode, Url};
+
+/// Quote a value so that a POSIX shell treats it as a single argument
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Headers sent with every request, sorted by name so that output is stable across runs
+///
+/// values are trimmed, `-H "Cookie: a=b"` is stored with the space following the colon
+fn sorted_headers(config: &Configuration) -> Vec<(&str, &str)> {
+    let mut headers: Vec<_> = config
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.trim()))
+        .collect();
+    headers.sort();
+    headers
+}
+
+/// Build a single-line curl command that repeats the request made for `url`
+///
+/// The scan's user agent, headers, proxy, redirect, tls, and timeout settings are all carried
+/// over; a `Cookie` header is passed with `-b`
+///
+/// example: curl -i -X GET -A 'feroxbuster/1.5.3' -b 'session=abc' -x 'http://127.0.0.1:8080' -k --max-time 7 'http://localhost/admin'
+pub fn curl_command(url: &Url, config: &Configuration) -> String {
+    let mut command = vec![
+        String::from("curl -i -X GET"),
+        format!("-A {}", shell_quote(&config.useragent)),
+    ];
+
+    for (name, value) in sorted_headers(config) {
+        if name.eq_ignore_ascii_case("cookie") {
+            command.push(format!("-b {}", shell_quote(value)));
+        } else {
+            command.push(format!(
+                "-H {}",
+                shell_quote(&format!("{}: {}", name, value))
+            ));
+        }
+    }
+
+    if !config.proxy.is_empty() {
+        command.push(format!("-x {}", shell_quote(&config.proxy)));
+    }
+
+    if config.redirects {
+        command.push(String::from("-L"));
+    }
+
+    if config.insecure {
+        command.push(String::from("-k"));
+    }
+
+    command.push(format!("--max-time {}", config.timeout));
+    command.push(shell_quote(url.as_str()));
+
+    command.join(" ")
+}
+
+/// Build a request block for a `.http` file, as used by VS Code's REST Client and JetBrains'
+/// HTTP Client, that repeats the request made for `url`
+///
+/// The block's `###` separator is followed by the response's status, which editors show as the
+/// request's name
+///
+/// example:
+///     ### 200 http://localhost/admin
+///     # @no-redirect
+///     GET http://localhost/admin HTTP/1.1
+///     User-Agent: feroxbuster/1.5.3
+///     Cookie: session=abc
+pub fn http_request(url: &Url, status: StatusCode, config: &Configuration) -> String {
+    let mut request = format!("### {} {}\n", status.as_u16(), url);
+
+    if !config.redirects {
+        // editors follow redirects by default, feroxbuster doesn't unless -r is used
+        request.push_str("# @no-redirect\n");
+    }
+
+    request.push_str(&format!("GET {} HTTP/1.1\n", url));
+    request.push_str(&format!("User-Agent: {}\n", config.useragent));
+
+    for (name, value) in sorted_headers(config) {
+        request.push_str(&format!("{}: {}\n", name, value));
+    }
+
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to build a configuration with a header, cookie, and proxy
+    fn setup_config() -> Configuration {
+        let mut config = Configuration {
+            useragent: String::from("ferox"),
+            proxy: String::from("http://127.0.0.1:8080"),
+            timeout: 7,
+            ..Default::default()
+        };
+
+        config
+            .headers
+            .insert(String::from("X-Api-Key"), String::from("it's secret"));
+        config
+            .headers
+            .insert(String::from("Cookie"), String::from(" session=abc"));
+        config
+    }
+
+    #[test]
+    /// curl commands carry over headers, cookies, proxy, and timeout, quoted for a shell
+    fn replay_curl_command_includes_request_settings() {
+        let mut config = setup_config();
+        config.insecure = true;
+
+        let url = Url::parse("http://localhost/admin").unwrap();
+
+        assert_eq!(
+            curl_command(&url, &config),
+            "curl -i -X GET -A 'ferox' -b 'session=abc' -H 'X-Api-Key: it'\\''s secret' -x 'http://127.0.0.1:8080' -k --max-time 7 'http://localhost/admin'"
+        );
+    }
+
+    #[test]
+    /// .http requests are named by status and don't follow redirects unless -r was used
+    fn replay_http_request_includes_request_settings() {
+        let mut config = setup_config();
+        let url = Url::parse("http://localhost/admin").unwrap();
+
+        let expected = "\
+### 301 http://localhost/admin
+# @no-redirect
+GET http://localhost/admin HTTP/1.1
+User-Agent: ferox
+Cookie: session=abc
+X-Api-Key: it's secret
+";
+
+        assert_eq!(
+            http_request(&url, StatusCode::MOVED_PERMANENTLY, &config),
+            expected
+        );
+
+        config.redirects = true;
+        assert!(!http_request(&url, StatusCode::OK, &config).contains("@no-redirect"));
+    }
+}
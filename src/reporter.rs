@@ -1,33 +1,71 @@
+use crate::cache;
+use crate::canary;
+use crate::challenge::{self, ChallengeSummary};
+use crate::compare::{format_missing, Baseline, Change};
 use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
-use crate::utils::{ferox_print, status_colorizer};
-use crate::{FeroxChannel, FeroxResponse};
-use console::strip_ansi_codes;
+use crate::duplicates::Duplicates;
+use crate::dynamic;
+use crate::encryption::{self, OutputFile};
+use crate::events::{self, Event};
+use crate::export::Export;
+use crate::forbidden::{self, Context};
+use crate::highlight::find_rule;
+use crate::junit::{self, JunitReport};
+use crate::metadata::{tag, SCAN_TAGS};
+use crate::notifier::Finding;
+use crate::replay::{curl_command, http_request};
+use crate::responses::{archive_response, save_response};
+use crate::rollover;
+use crate::sarif::SarifReport;
+use crate::screenshot;
+use crate::security::Audit;
+use crate::slash;
+use crate::state;
+use crate::statistics::STATISTICS;
+use crate::syslog::{get_cached_syslog_writer, send_finding};
+use crate::template;
+use crate::tree::ResultTree;
+use crate::utils::{ferox_print, format_selected_headers, module_colorizer, status_colorizer};
+use crate::verify;
+use crate::xml::XmlReport;
+use crate::{FeroxChannel, FeroxResponse, VERSION};
+use console::{strip_ansi_codes, style};
+use lazy_static::lazy_static;
+use reqwest::StatusCode;
+use serde_json::{json, Map, Value};
+use std::io;
 use std::io::Write;
-use std::sync::{Arc, Once, RwLock};
-use std::{fs, io};
+use std::sync::{Arc, Mutex, Once, RwLock};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
 /// Singleton buffered file behind an Arc/RwLock; used for file writes from two locations:
 ///     - [logger::initialize](../logger/fn.initialize.html) (specifically a closure on the global logger instance)
 ///     - `reporter::spawn_file_handler`
-pub static mut LOCKED_FILE: Option<Arc<RwLock<io::BufWriter<fs::File>>>> = None;
+pub static mut LOCKED_FILE: Option<Arc<RwLock<io::BufWriter<OutputFile>>>> = None;
 
 /// An initializer Once variable used to create `LOCKED_FILE`
 static INIT: Once = Once::new();
 
+lazy_static! {
+    /// Files opened by `open_file` that need to be finished once the scan completes, only kept
+    /// with `--encrypt-output`
+    static ref OPEN_FILES: Mutex<Vec<Arc<RwLock<io::BufWriter<OutputFile>>>>> =
+        Mutex::new(Vec::new());
+}
+
 // Accessing a `static mut` is unsafe much of the time, but if we do so
 // in a synchronized fashion (e.g., write once or read all) then we're
 // good to go!
 //
 // This function will only call `open_file` once, and will
 // otherwise always return the value returned from the first invocation.
-pub fn get_cached_file_handle(filename: &str) -> Option<Arc<RwLock<io::BufWriter<fs::File>>>> {
+pub fn get_cached_file_handle(filename: &str) -> Option<Arc<RwLock<io::BufWriter<OutputFile>>>> {
     unsafe {
         INIT.call_once(|| {
-            LOCKED_FILE = open_file(&filename);
+            LOCKED_FILE = open_file(filename);
         });
-        LOCKED_FILE.clone()
+        (*std::ptr::addr_of!(LOCKED_FILE)).clone()
     }
 }
 
@@ -36,24 +74,39 @@ pub fn get_cached_file_handle(filename: &str) -> Option<Arc<RwLock<io::BufWriter
 ///
 /// Any other module that needs to write a Response to stdout or output results to a file should
 /// be passed a clone of the appropriate returned transmitter
+///
+/// When `notify_chan` is given, every reported response is also forwarded to the notification
+/// handler (see [notifier::initialize](../notifier/fn.initialize.html))
+///
+/// When `baseline` is given, every reported response is flagged as new/changed relative to the
+/// previous run and previous results that weren't found are reported once the scan completes
 pub fn initialize(
     output_file: &str,
     save_output: bool,
+    notify_chan: Option<UnboundedSender<Finding>>,
+    baseline: Option<Baseline>,
 ) -> (
     UnboundedSender<FeroxResponse>,
     UnboundedSender<String>,
     JoinHandle<()>,
     Option<JoinHandle<()>>,
 ) {
-    log::trace!("enter: initialize({}, {})", output_file, save_output);
+    log::trace!(
+        "enter: initialize({}, {}, {:?}, {:?})",
+        output_file,
+        save_output,
+        notify_chan,
+        baseline
+    );
 
     let (tx_rpt, rx_rpt): FeroxChannel<FeroxResponse> = mpsc::unbounded_channel();
     let (tx_file, rx_file): FeroxChannel<String> = mpsc::unbounded_channel();
 
     let file_clone = tx_file.clone();
 
-    let term_reporter =
-        tokio::spawn(async move { spawn_terminal_reporter(rx_rpt, file_clone, save_output).await });
+    let term_reporter = tokio::spawn(async move {
+        spawn_terminal_reporter(rx_rpt, file_clone, save_output, notify_chan, baseline).await
+    });
 
     let file_reporter = if save_output {
         // -o used, need to spawn the thread for writing to disk
@@ -83,40 +136,359 @@ async fn spawn_terminal_reporter(
     mut resp_chan: UnboundedReceiver<FeroxResponse>,
     file_chan: UnboundedSender<String>,
     save_output: bool,
+    notify_chan: Option<UnboundedSender<Finding>>,
+    mut baseline: Option<Baseline>,
 ) {
     log::trace!(
-        "enter: spawn_terminal_reporter({:?}, {:?}, {})",
+        "enter: spawn_terminal_reporter({:?}, {:?}, {}, {:?}, {:?})",
         resp_chan,
         file_chan,
-        save_output
+        save_output,
+        notify_chan,
+        baseline
     );
 
+    // only built when --tree or --tree-output is used
+    let mut tree = if CONFIGURATION.tree {
+        Some(ResultTree::default())
+    } else {
+        None
+    };
+
+    // exits when --output-template can't be parsed
+    let template = template::initialize();
+
+    // only built when --xml-output is used
+    let mut xml = if CONFIGURATION.xml_output.is_empty() {
+        None
+    } else {
+        Some(XmlReport::default())
+    };
+
+    // only built when --format sarif is used
+    let mut sarif = if CONFIGURATION.format == "sarif" {
+        Some(SarifReport::default())
+    } else {
+        None
+    };
+
+    // only built when --junit-output is used
+    let mut junit = if CONFIGURATION.junit_output.is_empty() {
+        None
+    } else {
+        Some(JunitReport::new(junit::policies()))
+    };
+
+    // only built when --export-output is used
+    let mut export = if CONFIGURATION.export_output.is_empty() {
+        None
+    } else {
+        Some(Export::default())
+    };
+
+    // only opened when --curl-output and/or --http-output are used
+    let curl_file = if CONFIGURATION.curl_output.is_empty() {
+        None
+    } else {
+        open_file(&CONFIGURATION.curl_output)
+    };
+
+    let http_file = if CONFIGURATION.http_output.is_empty() {
+        None
+    } else {
+        open_file(&CONFIGURATION.http_output)
+    };
+
+    // only collected when --verify is used
+    let mut findings = Vec::new();
+
+    // challenges of the reported 401s, summarized once all results are in
+    let mut challenges = ChallengeSummary::default();
+
+    // only collected when --collapse-duplicates is used
+    let mut duplicates = if CONFIGURATION.collapse_duplicates > 0 {
+        Some(Duplicates::new(CONFIGURATION.collapse_duplicates))
+    } else {
+        None
+    };
+
     while let Some(resp) = resp_chan.recv().await {
         log::debug!("received {} on reporting channel", resp.url());
 
-        if CONFIGURATION.statuscodes.contains(&resp.status().as_u16()) {
+        let reported = CONFIGURATION.statuscodes.contains(&resp.status().as_u16());
+
+        // --collapse-duplicates used, past the limit identical responses are listed together once
+        // all results are in
+        let reported = reported
+            && !duplicates.as_mut().is_some_and(|duplicates| {
+                duplicates.collapse(resp.status().as_u16(), resp.text(), resp.url().as_str())
+            });
+
+        if reported {
+            STATISTICS.add_reported(resp.url().as_str());
+
+            // the first 2xx finding becomes the canary, unless --canary was used
+            canary::offer(resp.url(), *resp.status());
+
+            if CONFIGURATION.verify {
+                findings.push(verify::Finding {
+                    url: resp.url().clone(),
+                    status: *resp.status(),
+                    content_length: resp.content_length(),
+                });
+            }
+
+            if let Some(tree) = tree.as_mut() {
+                tree.insert(resp.url(), resp.status().as_u16(), resp.content_length());
+            }
+
+            if let Some(xml) = xml.as_mut() {
+                xml.insert(
+                    resp.url(),
+                    resp.status().as_u16(),
+                    resp.content_length(),
+                    &resp.selected_headers(&CONFIGURATION.show_headers),
+                    resp.body_hash(),
+                    resp.favicon_hash(),
+                );
+            }
+
+            if let Some(sarif) = sarif.as_mut() {
+                sarif.insert(
+                    resp.url(),
+                    resp.status().as_u16(),
+                    resp.content_length(),
+                    resp.markers().listing,
+                );
+            }
+
+            if let Some(junit) = junit.as_mut() {
+                junit.insert(resp.url(), resp.status().as_u16());
+            }
+
+            if let Some(export) = export.as_mut() {
+                let status = resp.status().as_u16();
+
+                if CONFIGURATION.export_status.is_empty()
+                    || CONFIGURATION.export_status.contains(&status)
+                {
+                    export.insert(resp.url());
+                }
+            }
+
+            let change = baseline.as_mut().map(|baseline| {
+                // --compare used, flag the result based on the previous run
+                baseline.classify(
+                    resp.url().as_str(),
+                    resp.status().as_u16(),
+                    resp.content_length(),
+                )
+            });
+
+            // --show-headers used, empty otherwise
+            let headers = resp.selected_headers(&CONFIGURATION.show_headers);
+
+            // only directories' bodies are read for this, see scanner::needs_body
+            let listing = resp.status().is_success() && resp.markers().listing;
+
+            // only analyzed with --analyze-403, see forbidden::analyze_response
+            let forbidden = if *resp.status() == StatusCode::FORBIDDEN {
+                forbidden::context_of(resp.url())
+            } else {
+                None
+            };
+
+            // only probed with --slash-probe, see slash::probe_response
+            let probe = slash::probe_of(resp.url());
+
+            // only requested again with --detect-dynamic, see dynamic::check_response
+            let volatility = dynamic::volatility_of(resp.url());
+
+            // how to authenticate, when a 401 says so
+            let offered = if *resp.status() == StatusCode::UNAUTHORIZED {
+                challenge::challenges_of(resp.headers())
+            } else {
+                Vec::new()
+            };
+
+            challenges.insert(&offered);
+
+            // only audited with --security-headers
+            let audit = if CONFIGURATION.security_headers {
+                Some(Audit::of_response(resp.url(), resp.headers()))
+            } else {
+                None
+            };
+
             let report = if CONFIGURATION.quiet {
                 // -q used, just need the url
                 format!("{}\n", resp.url())
+            } else if let Some(template) = &template {
+                // --output-template used, the line is exactly what it renders to
+                format!("{}\n", template.render_response(&resp, change, listing))
             } else {
+                // highlight rules from the config file; the first matching rule restyles the
+                // entire line, so the status code is left uncolored
+                let rule = find_rule(
+                    &CONFIGURATION.highlight,
+                    resp.url(),
+                    resp.status().as_u16(),
+                    resp.content_length(),
+                );
+
                 // normal printing with status and size
-                let status = status_colorizer(&resp.status().as_str());
-                format!(
+                let status = match rule {
+                    Some(_) => resp.status().as_str().to_string(),
+                    None => status_colorizer(resp.status().as_str()),
+                };
+
+                let line = format!(
                     // example output
                     // 200       3280 https://localhost.com/FAQ
-                    "{} {:>10} {}\n",
+                    // NEW     200       3280 https://localhost.com/FAQ  (--compare)
+                    // 200       3280 https://localhost.com/FAQ [server: nginx]  (--show-headers)
+                    // [CRITICAL] 200       3280 https://localhost.com/db.sql  (highlight rule)
+                    // 200        913 https://localhost.com/files/ (directory listing)
+                    // 403        199 https://localhost.com/admin (listing forbidden)
+                    // 200       1406 https://localhost.com/favicon.ico (favicon hash 116323821)
+                    // 403        199 https://localhost.com/admin (no slash 403, slash 200, directory)
+                    // 401        381 https://localhost.com/manager (Basic realm="Tomcat Manager")
+                    // 200       1024 https://localhost.com/app.js (truncated: 512 of 1024 bytes)
+                    // 200       2051 https://sso.localhost.com/login (2 redirects from https://localhost.com/admin)
+                    // 200       5120 https://localhost.com/news (dynamic: 3 volatile words)
+                    // 200         42 https://localhost.com/api (cors: reflected with credentials; 3 security headers missing)
+                    "{}{} {:>10} {}{}{}{}{}{}{}{}{}{}{}",
+                    change.map(Change::tag).unwrap_or_default(),
                     status,
                     resp.content_length(),
-                    resp.url()
-                )
+                    resp.url(),
+                    format_selected_headers(&headers),
+                    if listing {
+                        format!(" {}", style("(directory listing)").magenta())
+                    } else {
+                        String::new()
+                    },
+                    match forbidden {
+                        Some(Context::ListingForbidden) => {
+                            format!(" {}", style("(listing forbidden)").yellow())
+                        }
+                        Some(Context::Noise) => format!(" {}", style("(forbidden noise)").dim()),
+                        None => String::new(),
+                    },
+                    match resp.favicon_hash() {
+                        Some(hash) =>
+                            format!(" {}", style(format!("(favicon hash {})", hash)).cyan()),
+                        None => String::new(),
+                    },
+                    match probe {
+                        Some(probe) =>
+                            format!(" {}", style(format!("({})", probe.describe())).blue()),
+                        None => String::new(),
+                    },
+                    if offered.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            " {}",
+                            style(format!("({})", challenge::describe(&offered))).magenta()
+                        )
+                    },
+                    match resp.length_mismatch() {
+                        Some(mismatch) =>
+                            format!(" {}", style(format!("({})", mismatch.describe())).red()),
+                        None => String::new(),
+                    },
+                    match resp.redirects() {
+                        Some(chain) =>
+                            format!(" {}", style(format!("({})", chain.describe())).cyan()),
+                        None => String::new(),
+                    },
+                    match &volatility {
+                        Some(volatility) => format!(
+                            " {}",
+                            style(format!("({})", volatility.describe())).yellow()
+                        ),
+                        None => String::new(),
+                    },
+                    match &audit {
+                        Some(audit) if audit.is_permissive() => {
+                            format!(" {}", style(format!("({})", audit.describe())).red().bold())
+                        }
+                        Some(audit) if !audit.describe().is_empty() => {
+                            format!(" {}", style(format!("({})", audit.describe())).dim())
+                        }
+                        _ => String::new(),
+                    }
+                );
+
+                match rule {
+                    Some(rule) => format!("{}\n", rule.apply(&line)),
+                    None => format!("{}\n", line),
+                }
             };
 
-            // print to stdout
-            ferox_print(&report, &PROGRESS_PRINTER);
+            if CONFIGURATION.silent {
+                // --silent used, progress bars are hidden along with anything printed through
+                // them, so the url goes straight to stdout
+                print!("{}", report);
+            } else {
+                // print to stdout
+                ferox_print(&report, &PROGRESS_PRINTER);
+            }
 
             if save_output {
                 // -o used, need to send the report to be written out to disk
-                match file_chan.send(report.to_string()) {
+                let file_report = if CONFIGURATION.json {
+                    let mut record = resp.as_json();
+
+                    if let Some(change) = change {
+                        record["change"] = Value::from(change.as_str());
+                    }
+
+                    if listing {
+                        record["listing"] = Value::from(true);
+                    }
+
+                    if let Some(context) = forbidden {
+                        record["forbidden"] = Value::from(context.as_str());
+                    }
+
+                    if let Some(probe) = probe {
+                        record["slash_probe"] = probe.as_json();
+                    }
+
+                    if let Some(volatility) = &volatility {
+                        record["dynamic"] = volatility.as_json();
+                    }
+
+                    if let Some(audit) = &audit {
+                        audit.add_to(&mut record);
+                    }
+
+                    if !offered.is_empty() {
+                        record["www_authenticate"] = offered
+                            .iter()
+                            .map(|challenge| challenge.as_json())
+                            .collect();
+                    }
+
+                    if !CONFIGURATION.show_headers.is_empty() {
+                        let selected: Map<String, Value> = headers
+                            .iter()
+                            .map(|(name, value)| (name.clone(), Value::from(value.as_str())))
+                            .collect();
+
+                        record["headers"] = Value::Object(selected);
+                    }
+
+                    tag(&mut record);
+
+                    format!("{}\n", record)
+                } else {
+                    report
+                };
+
+                match file_chan.send(file_report) {
                     Ok(_) => {
                         log::debug!("Sent {} to file handler", resp.url());
                     }
@@ -125,12 +497,270 @@ async fn spawn_terminal_reporter(
                     }
                 }
             }
+
+            if !CONFIGURATION.save_responses.is_empty() {
+                // --save-responses used, into an archive with --compress-responses
+                let saved = if CONFIGURATION.compress_responses {
+                    archive_response(&CONFIGURATION.save_responses, &resp).map(|_| ())
+                } else {
+                    save_response(&CONFIGURATION.save_responses, &resp).map(|_| ())
+                };
+
+                if let Err(e) = saved {
+                    log::warn!("Could not save response for {}: {}", resp.url(), e);
+                }
+            }
+
+            if !CONFIGURATION.screenshot.is_empty() && screenshot::is_interesting(*resp.status()) {
+                // --screenshot used, taken in the background
+                screenshot::capture(resp.url(), *resp.status());
+            }
+
+            if let Some(file) = &curl_file {
+                // --curl-output used
+                let command = curl_command(resp.url(), &CONFIGURATION);
+                safe_file_write(&format!("{}\n", command), file.clone());
+            }
+
+            if let Some(file) = &http_file {
+                // --http-output used; a blank line keeps requests visually separated
+                let request = http_request(resp.url(), *resp.status(), &CONFIGURATION);
+                safe_file_write(&format!("{}\n", request), file.clone());
+            }
+
+            if let Some(notifier) = &notify_chan {
+                // --slack-webhook and/or --discord-webhook used
+                let finding = Finding {
+                    status: *resp.status(),
+                    content_length: resp.content_length(),
+                    url: resp.url().to_string(),
+                    headers: headers.clone(),
+                };
+
+                if let Err(e) = notifier.send(finding) {
+                    log::error!("Could not send {} to notifier: {}", resp.url(), e);
+                }
+            }
+
+            if let Some(syslog) = get_cached_syslog_writer(&CONFIGURATION.syslog) {
+                // --syslog used
+                send_finding(syslog, &resp, &headers);
+            }
         }
         log::debug!("report complete: {}", resp.url());
+
+        if reported {
+            // --response-cache used, remembered to be requested conditionally by the next run
+            cache::record(
+                resp.url(),
+                resp.status().as_u16(),
+                resp.content_length(),
+                resp.headers(),
+            );
+
+            // --state-file used, saved along with the rest of the scan's progress
+            state::record(&resp);
+
+            events::emit(Event::ResultReported(resp));
+        }
     }
+
+    if CONFIGURATION.verify {
+        // all results are in; transient errors and rate limiting may have produced some of them
+        verify::verify(&findings, &file_chan, save_output).await;
+    }
+
+    if !challenges.is_empty() {
+        // all results are in; how the 401s may be authenticated against
+        challenges.report(&file_chan, save_output);
+    }
+
+    if let Some(duplicates) = duplicates.filter(|duplicates| !duplicates.is_empty()) {
+        // all results are in; the identical responses that weren't reported
+        duplicates.report(&file_chan, save_output);
+    }
+
+    if let Some(baseline) = baseline {
+        // all results are in; anything from the previous run that wasn't seen went missing
+        report_missing(&baseline, &file_chan, save_output);
+    }
+
+    if let Some(tree) = tree {
+        report_tree(&tree);
+    }
+
+    if let Some(xml) = xml {
+        report_xml(&xml);
+    }
+
+    if let Some(sarif) = sarif {
+        report_sarif(&sarif);
+    }
+
+    if let Some(junit) = junit {
+        report_junit(&junit);
+    }
+
+    if let Some(export) = export {
+        report_export(&export);
+    }
+
     log::trace!("exit: spawn_terminal_reporter");
 }
 
+/// Print the tree of all reported results and write it to `--tree-output`, if used
+fn report_tree(tree: &ResultTree) {
+    log::trace!("enter: report_tree({:?})", tree);
+
+    if tree.is_empty() {
+        log::trace!("exit: report_tree");
+        return;
+    }
+
+    let rendered = tree.render();
+
+    if !CONFIGURATION.quiet {
+        ferox_print(&rendered, &PROGRESS_PRINTER);
+    }
+
+    if !CONFIGURATION.tree_output.is_empty() {
+        if let Err(e) = encryption::write(
+            &CONFIGURATION.tree_output,
+            strip_ansi_codes(&rendered).as_ref(),
+        ) {
+            log::error!(
+                "Could not write result tree to {}: {}",
+                CONFIGURATION.tree_output,
+                e
+            );
+        }
+    }
+
+    log::trace!("exit: report_tree");
+}
+
+/// Write all reported results to `--xml-output`
+fn report_xml(xml: &XmlReport) {
+    log::trace!("enter: report_xml({:?})", xml);
+
+    let tags: Vec<(String, String)> = SCAN_TAGS
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().to_string()))
+        .collect();
+
+    let document = xml.render(&CONFIGURATION.xml_style, VERSION, &tags);
+
+    if let Err(e) = encryption::write(&CONFIGURATION.xml_output, document) {
+        log::error!(
+            "Could not write xml results to {}: {}",
+            CONFIGURATION.xml_output,
+            e
+        );
+    }
+
+    log::trace!("exit: report_xml");
+}
+
+/// Write all reported results to `--output` as a SARIF log
+fn report_sarif(sarif: &SarifReport) {
+    log::trace!("enter: report_sarif({:?})", sarif);
+
+    let tags: Vec<(String, String)> = SCAN_TAGS
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().to_string()))
+        .collect();
+
+    if let Err(e) = encryption::write(&CONFIGURATION.output, sarif.render(VERSION, &tags)) {
+        log::error!(
+            "Could not write sarif results to {}: {}",
+            CONFIGURATION.output,
+            e
+        );
+    }
+
+    log::trace!("exit: report_sarif");
+}
+
+/// Write the outcome of each policy to `--junit-output`
+fn report_junit(junit: &JunitReport) {
+    log::trace!("enter: report_junit({:?})", junit);
+
+    let tags: Vec<(String, String)> = SCAN_TAGS
+        .iter()
+        .map(|(key, value)| (key.clone(), value.as_str().unwrap_or_default().to_string()))
+        .collect();
+
+    if let Err(e) = encryption::write(&CONFIGURATION.junit_output, junit.render(&tags)) {
+        log::error!(
+            "Could not write junit results to {}: {}",
+            CONFIGURATION.junit_output,
+            e
+        );
+    }
+
+    log::trace!("exit: report_junit");
+}
+
+/// Write the reported urls to `--export-output`, in the `--export` format
+fn report_export(export: &Export) {
+    log::trace!("enter: report_export({:?})", export);
+
+    let list = export.render(&CONFIGURATION.export);
+
+    if let Err(e) = encryption::write(&CONFIGURATION.export_output, list) {
+        log::error!(
+            "Could not export results to {}: {}",
+            CONFIGURATION.export_output,
+            e
+        );
+    }
+
+    log::trace!("exit: report_export");
+}
+
+/// Print previous results that weren't found during the current run and write them to the
+/// output file, if one is in use
+fn report_missing(baseline: &Baseline, file_chan: &UnboundedSender<String>, save_output: bool) {
+    log::trace!(
+        "enter: report_missing({:?}, {:?}, {})",
+        baseline,
+        file_chan,
+        save_output
+    );
+
+    for (url, result) in baseline.missing() {
+        let report = format_missing(url, &result);
+
+        if !CONFIGURATION.quiet {
+            ferox_print(&report, &PROGRESS_PRINTER);
+        }
+
+        if save_output {
+            let file_report = if CONFIGURATION.json {
+                let mut record = json!({
+                    "type": "response",
+                    "url": url,
+                    "status": result.status,
+                    "content_length": result.content_length,
+                    "change": Change::Missing.as_str(),
+                });
+
+                tag(&mut record);
+
+                format!("{}\n", record)
+            } else {
+                report
+            };
+
+            if let Err(e) = file_chan.send(file_report) {
+                log::error!("Could not send {} to file handler: {}", url, e);
+            }
+        }
+    }
+
+    log::trace!("exit: report_missing");
+}
+
 /// Spawn a single consumer task (sc side of mpsc)
 ///
 /// The consumer simply receives responses and writes them to the given output file if they meet
@@ -152,6 +782,16 @@ async fn spawn_file_reporter(mut report_channel: UnboundedReceiver<String>, outp
 
     log::info!("Writing scan results to {}", output_file);
 
+    if CONFIGURATION.json {
+        // the first record describes the configuration that produced the rest
+        let mut record = json!({
+            "type": "configuration",
+            "configuration": CONFIGURATION.as_json(),
+        });
+        tag(&mut record);
+        safe_file_write(&format!("{}\n", record), buffered_file.clone());
+    }
+
     while let Some(report) = report_channel.recv().await {
         safe_file_write(&report, buffered_file.clone());
     }
@@ -161,21 +801,26 @@ async fn spawn_file_reporter(mut report_channel: UnboundedReceiver<String>, outp
 
 /// Given the path to a file, open the file in append mode (create it if it doesn't exist) and
 /// return a reference to the file that is buffered and locked
-fn open_file(filename: &str) -> Option<Arc<RwLock<io::BufWriter<fs::File>>>> {
+///
+/// With `--encrypt-output`, the file is truncated and encrypted instead (see `encryption`); it's
+/// remembered to be finished by `close_files`
+pub fn open_file(filename: &str) -> Option<Arc<RwLock<io::BufWriter<OutputFile>>>> {
     log::trace!("enter: open_file({})", filename);
 
-    match fs::OpenOptions::new() // std fs
-        .create(true)
-        .append(true)
-        .open(filename)
-    {
+    match OutputFile::open(filename) {
         Ok(file) => {
             let writer = io::BufWriter::new(file); // std io
 
-            let locked_file = Some(Arc::new(RwLock::new(writer)));
+            let locked_file = Arc::new(RwLock::new(writer));
+
+            if encryption::is_enabled() {
+                if let Ok(mut open_files) = OPEN_FILES.lock() {
+                    open_files.push(locked_file.clone());
+                }
+            }
 
             log::trace!("exit: open_file -> {:?}", locked_file);
-            locked_file
+            Some(locked_file)
         }
         Err(e) => {
             log::error!("{}", e);
@@ -185,15 +830,44 @@ fn open_file(filename: &str) -> Option<Arc<RwLock<io::BufWriter<fs::File>>>> {
     }
 }
 
+/// Finish every file opened by `open_file`; encrypted files are only complete once finished,
+/// whatever is written to them afterwards is discarded
+pub fn close_files() {
+    log::trace!("enter: close_files");
+
+    let open_files = match OPEN_FILES.lock() {
+        Ok(mut open_files) => std::mem::take(&mut *open_files),
+        Err(_) => Vec::new(),
+    };
+
+    for locked_file in open_files {
+        if let Ok(mut handle) = locked_file.write() {
+            if let Err(e) = handle.flush().and_then(|_| handle.get_mut().finish()) {
+                eprintln!(
+                    "{} {} Could not finish an output file: {}",
+                    status_colorizer("ERROR"),
+                    module_colorizer("reporter::close_files"),
+                    e
+                );
+            }
+        }
+    }
+
+    log::trace!("exit: close_files");
+}
+
 /// Given a string and a reference to a locked buffered file, write the contents and flush
 /// the buffer to disk.
-pub fn safe_file_write(contents: &str, locked_file: Arc<RwLock<io::BufWriter<fs::File>>>) {
+pub fn safe_file_write(contents: &str, locked_file: Arc<RwLock<io::BufWriter<OutputFile>>>) {
     // note to future self: adding logging of anything other than error to this function
     // is a bad idea. we call this function while processing records generated by the logger.
     // If we then call log::... while already processing some logging output, it results in
     // the second log entry being injected into the first.
 
-    let contents = strip_ansi_codes(&contents);
+    let contents = strip_ansi_codes(contents);
+
+    // --output-max-size used, checked once the write is complete and logged once the lock is free
+    let mut rolled_over = Ok(());
 
     if let Ok(mut handle) = locked_file.write() {
         // write lock acquired
@@ -213,10 +887,32 @@ pub fn safe_file_write(contents: &str, locked_file: Arc<RwLock<io::BufWriter<fs:
                 log::error!("error writing to file: {}", e);
             }
         }
+
+        if rollover::is_enabled() && is_output_file(&locked_file) {
+            rolled_over = rollover::roll_over_if_full(&mut handle);
+        }
+    }
+
+    if let Err(e) = rolled_over {
+        log::error!(
+            "Could not roll over {}, it grows unbounded from now on: {}",
+            CONFIGURATION.output,
+            e
+        );
+    }
+}
+
+/// Whether or not the given file is `--output` (see `get_cached_file_handle`)
+fn is_output_file(locked_file: &Arc<RwLock<io::BufWriter<OutputFile>>>) -> bool {
+    unsafe {
+        (*std::ptr::addr_of!(LOCKED_FILE))
+            .as_ref()
+            .is_some_and(|output| Arc::ptr_eq(output, locked_file))
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_borrow)]
 mod tests {
     use super::*;
 
@@ -0,0 +1,277 @@
+use crate::encryption::{self, OutputFile};
+use crate::gzip;
+use crate::reporter::{open_file, safe_file_write};
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::fs::create_dir_all;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Extension given to saved response bodies
+const BODY_EXTENSION: &str = "body";
+
+/// Extension given to saved response headers
+const HEADERS_EXTENSION: &str = "headers";
+
+/// File name used for urls that end in a `/` (or have no path at all)
+const INDEX_NAME: &str = "index";
+
+/// File the bodies are compressed into with `--compress-responses`, as one gzip member each
+const ARCHIVE_NAME: &str = "bodies.gz";
+
+/// File mapping the url of each body in `ARCHIVE_NAME` to its member, as json lines
+const ARCHIVE_INDEX_NAME: &str = "index.jsonl";
+
+lazy_static! {
+    /// Archive of `--compress-responses`, opened along with the first body archived
+    static ref ARCHIVE: Mutex<Option<Archive>> = Mutex::new(None);
+}
+
+/// Archive of compressed bodies, along with its index
+struct Archive {
+    /// `ARCHIVE_NAME`
+    bodies: Arc<RwLock<BufWriter<OutputFile>>>,
+
+    /// `ARCHIVE_INDEX_NAME`
+    index: Arc<RwLock<BufWriter<OutputFile>>>,
+
+    /// offset in `bodies` of the next member
+    offset: u64,
+}
+
+impl Archive {
+    /// Open the archive and its index beneath `directory`; when they already exist, archiving
+    /// continues at their end, unless they're encrypted
+    fn open(directory: &str) -> io::Result<Self> {
+        create_dir_all(directory)?;
+
+        let open = |name: &str| {
+            let path = Path::new(directory).join(name);
+            let path = path.to_string_lossy();
+
+            open_file(&path).ok_or_else(|| io::Error::other(format!("could not open {}", path)))
+        };
+
+        let bodies = open(ARCHIVE_NAME)?;
+        let index = open(ARCHIVE_INDEX_NAME)?;
+
+        // encrypted files are truncated when opened (see encryption::OutputFile)
+        let offset = match bodies.read() {
+            Ok(handle) if !encryption::is_enabled() => handle.get_ref().metadata()?.len(),
+            _ => 0,
+        };
+
+        Ok(Self {
+            bodies,
+            index,
+            offset,
+        })
+    }
+}
+
+/// Replace characters that aren't allowed in file names on common filesystems
+fn sanitize(segment: &str) -> String {
+    let sanitized: String = segment
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    match sanitized.as_str() {
+        "" => String::from(INDEX_NAME),
+        "." | ".." => String::from("_"),
+        _ => sanitized,
+    }
+}
+
+/// Determine where the given url's response is saved beneath `directory`; the returned path has
+/// no extension, the body and headers files are created by adding one
+///
+/// The directory structure mirrors the url: `directory/HOST[_PORT]/PATH/TO/LAST_SEGMENT`. Every
+/// saved file gets an extension, so a response for `/js` (saved as `js.body`) never collides with
+/// responses saved beneath `/js/` (saved in the directory `js`)
+///
+/// example: http://localhost:8080/js/app.js?v=1 -> directory/localhost_8080/js/app.js_v=1
+pub fn response_path(directory: &Path, url: &Url) -> PathBuf {
+    let host = match url.port() {
+        Some(port) => format!("{}_{}", url.host_str().unwrap_or(INDEX_NAME), port),
+        None => url.host_str().unwrap_or(INDEX_NAME).to_string(),
+    };
+
+    let mut path = directory.join(sanitize(&host));
+
+    let mut segments: Vec<&str> = match url.path_segments() {
+        Some(segments) => segments.collect(),
+        None => vec![],
+    };
+
+    let last = segments.pop().unwrap_or_default();
+
+    for segment in segments {
+        path.push(sanitize(segment));
+    }
+
+    let file_name = match url.query() {
+        Some(query) => format!("{}?{}", last, query),
+        None => last.to_string(),
+    };
+
+    path.push(sanitize(&file_name));
+    path
+}
+
+/// Format the url, status line, and headers of a response in the style of a raw HTTP response
+fn format_headers(response: &FeroxResponse) -> String {
+    let mut formatted = format!("{}\n{}\n", response.url(), response.status());
+
+    for (name, value) in response.headers() {
+        formatted.push_str(&format!(
+            "{}: {}\n",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+
+    formatted
+}
+
+/// Write the body and headers of the given response beneath `directory` (see `response_path`)
+///
+/// returns the path of the saved body
+pub fn save_response(directory: &str, response: &FeroxResponse) -> io::Result<PathBuf> {
+    log::trace!("enter: save_response({}, {})", directory, response.url());
+
+    let path = response_path(Path::new(directory), response.url());
+
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    let body_path = path.with_file_name(format!(
+        "{}.{}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        BODY_EXTENSION
+    ));
+
+    let headers_path = path.with_file_name(format!(
+        "{}.{}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        HEADERS_EXTENSION
+    ));
+
+    encryption::write(&body_path, response.text())?;
+    encryption::write(&headers_path, format_headers(response))?;
+
+    log::trace!("exit: save_response -> {}", body_path.display());
+    Ok(body_path)
+}
+
+/// Record of the index of the archive for the body of the given response, which is `length` bytes
+/// of the archive from `offset` once compressed
+///
+/// example:
+///     {"url":"http://localhost/","status":200,"headers":[["server","nginx"]],"offset":0,"length":412,"size":1024}
+fn index_record(response: &FeroxResponse, offset: u64, length: usize) -> Value {
+    // a list of pairs, some headers are sent more than once
+    let headers: Vec<Value> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| json!([name.as_str(), String::from_utf8_lossy(value.as_bytes())]))
+        .collect();
+
+    json!({
+        "url": response.url().as_str(),
+        "status": response.status().as_u16(),
+        "headers": headers,
+        "offset": offset,
+        "length": length,
+        "size": response.text().len(),
+    })
+}
+
+/// Compress the body of the given response into the archive beneath `directory`, and record its
+/// url, status, headers, and place in the archive in the archive's index (`--compress-responses`)
+///
+/// returns the offset of the body in the archive
+pub fn archive_response(directory: &str, response: &FeroxResponse) -> io::Result<u64> {
+    log::trace!("enter: archive_response({}, {})", directory, response.url());
+
+    let member = gzip::compress(response.text().as_bytes());
+
+    let mut archive = ARCHIVE
+        .lock()
+        .map_err(|_| io::Error::other("the archive's lock is poisoned"))?;
+
+    if archive.is_none() {
+        *archive = Some(Archive::open(directory)?);
+    }
+
+    let archive = match archive.as_mut() {
+        Some(archive) => archive,
+        None => return Err(io::Error::other("the archive isn't open")),
+    };
+
+    let offset = archive.offset;
+
+    match archive.bodies.write() {
+        Ok(mut handle) => {
+            handle.write_all(&member)?;
+            handle.flush()?;
+        }
+        Err(_) => return Err(io::Error::other("the archive's lock is poisoned")),
+    }
+
+    archive.offset += member.len() as u64;
+
+    let record = index_record(response, offset, member.len());
+    safe_file_write(&format!("{}\n", record), archive.index.clone());
+
+    log::trace!("exit: archive_response -> {}", offset);
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the saved path mirrors the url, including its port and query
+    fn responses_response_path_mirrors_url() {
+        let url = Url::parse("http://localhost:8080/js/app.js?v=1").unwrap();
+        let path = response_path(Path::new("saved"), &url);
+
+        assert_eq!(
+            path,
+            Path::new("saved")
+                .join("localhost_8080")
+                .join("js")
+                .join("app.js_v=1")
+        );
+    }
+
+    #[test]
+    /// urls without a final path segment are saved as index
+    fn responses_response_path_uses_index_for_directories() {
+        let url = Url::parse("https://localhost/admin/").unwrap();
+        let path = response_path(Path::new("saved"), &url);
+        assert_eq!(path, Path::new("saved/localhost/admin/index"));
+
+        let url = Url::parse("https://localhost").unwrap();
+        let path = response_path(Path::new("saved"), &url);
+        assert_eq!(path, Path::new("saved/localhost/index"));
+    }
+
+    #[test]
+    /// characters that aren't allowed in file names are replaced
+    fn responses_sanitize_replaces_invalid_characters() {
+        assert_eq!(sanitize("a:b*c?d\"e<f>g|h\\i"), "a_b_c_d_e_f_g_h_i");
+        assert_eq!(sanitize(".."), "_");
+        assert_eq!(sanitize(""), INDEX_NAME);
+    }
+}
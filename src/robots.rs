@@ -0,0 +1,420 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::heuristics::try_send_message_to_file;
+use crate::metadata::tag;
+use crate::utils::{ferox_print, make_request, status_colorizer};
+use console::style;
+use lazy_static::lazy_static;
+use reqwest::{Client, Url};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+
+lazy_static! {
+    /// Rules of every origin whose robots.txt was fetched, by origin
+    static ref ROBOTS: RwLock<HashMap<String, Robots>> = RwLock::new(HashMap::new());
+
+    /// Held while a robots.txt is fetched, so that scans of the same origin starting at once
+    /// fetch it only once
+    static ref LOADING: Mutex<()> = Mutex::new(());
+}
+
+/// Single `Allow` or `Disallow` rule
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// whether the rule allows the paths it matches
+    allow: bool,
+
+    /// path the rule matches; may contain `*` wildcards and end with `$`
+    pattern: String,
+}
+
+/// Rules of a robots.txt that apply to feroxbuster (see `--respect-robots`)
+///
+/// Rules are picked and matched as described in RFC 9309: the group naming feroxbuster's
+/// user-agent is used when there is one, otherwise the `*` group; the longest rule matching a
+/// path decides, with `Allow` winning a tie
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Robots {
+    /// rules of the group that applies
+    rules: Vec<Rule>,
+}
+
+impl Robots {
+    /// Rules that disallow everything, used when a robots.txt couldn't be fetched
+    pub fn disallow_all() -> Self {
+        Robots {
+            rules: vec![Rule {
+                allow: false,
+                pattern: String::from("/"),
+            }],
+        }
+    }
+
+    /// Parse the rules of the group that applies to the given user-agent from a robots.txt
+    ///
+    /// Only the product token of the user-agent is compared, i.e. `feroxbuster` for
+    /// `feroxbuster/1.1.1`, ignoring case
+    pub fn parse(contents: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        let mut agent_rules = Vec::new();
+        let mut wildcard_rules = Vec::new();
+
+        // agents of the group being read, and whether its rules were reached yet
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim().to_lowercase(), value.trim()),
+                None => continue,
+            };
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        // a user-agent after rules starts the next group
+                        agents.clear();
+                        in_rules = false;
+                    }
+
+                    agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+
+                    if value.is_empty() {
+                        // an empty disallow allows everything, same as no rule at all
+                        continue;
+                    }
+
+                    let rule = Rule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    };
+
+                    if !token.is_empty() && agents.contains(&token) {
+                        agent_rules.push(rule.clone());
+                    }
+
+                    if agents.iter().any(|agent| agent == "*") {
+                        wildcard_rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let rules = if agent_rules.is_empty() {
+            wildcard_rules
+        } else {
+            agent_rules
+        };
+
+        Robots { rules }
+    }
+
+    /// Whether or not the given path (with its query, if any) may be requested
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    /// Paths named by the `Disallow` rules, which robots.txt gives away as present; rules with
+    /// wildcards, or that disallow everything, don't name a path
+    pub fn disallowed_paths(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .filter(|rule| !rule.allow && !rule.pattern.contains('*'))
+            .map(|rule| rule.pattern.trim_end_matches('$'))
+            .filter(|path| *path != "/")
+            .collect()
+    }
+}
+
+/// Whether or not the given robots.txt pattern matches the path; `*` matches any run of
+/// characters and a trailing `$` anchors the pattern to the end of the path
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let mut rest = match path.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+
+    if parts.is_empty() {
+        return !anchored || rest.is_empty();
+    }
+
+    for (index, part) in parts.iter().enumerate() {
+        if anchored && index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+
+        match rest.find(part) {
+            Some(start) => rest = &rest[start + part.len()..],
+            None => return false,
+        }
+    }
+
+    true
+}
+
+/// Path of the url as matched against robots.txt rules, with its query if it has one
+fn robots_path(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// Whether or not the given url may be requested; always true without `--respect-robots`, or
+/// when the robots.txt of its origin wasn't fetched
+pub fn allows(url: &Url) -> bool {
+    if !CONFIGURATION.respect_robots {
+        return true;
+    }
+
+    match ROBOTS.read() {
+        Ok(robots) => robots
+            .get(&url.origin().ascii_serialization())
+            .is_none_or(|rules| rules.allows(&robots_path(url))),
+        Err(e) => {
+            log::error!("{}", e);
+            true
+        }
+    }
+}
+
+/// Whether or not the given directory may be scanned, i.e. whether the paths under it may be
+/// requested; `/js` is scanned as `/js/`, which `Disallow: /js/` disallows
+pub fn allows_directory(directory: &str) -> bool {
+    match Url::parse(&format!("{}/", directory.trim_end_matches('/'))) {
+        Ok(url) => allows(&url),
+        Err(_) => true,
+    }
+}
+
+/// Fetch the robots.txt of the given target's origin, once per origin, and report the paths it
+/// disallows as present but skipped (see `--respect-robots`)
+///
+/// A robots.txt that doesn't exist (4xx) allows everything; one that couldn't be fetched (5xx or
+/// an error) disallows everything, as RFC 9309 asks
+pub async fn load(target_url: &str, client: &Client, tx_file: UnboundedSender<String>) {
+    log::trace!("enter: load({}, {:?}, {:?})", target_url, client, tx_file);
+
+    if !CONFIGURATION.respect_robots {
+        log::trace!("exit: load");
+        return;
+    }
+
+    let origin = match Url::parse(target_url) {
+        Ok(url) => url.origin(),
+        Err(e) => {
+            log::error!("Could not parse {} into a Url: {}", target_url, e);
+            log::trace!("exit: load");
+            return;
+        }
+    };
+
+    let _loading = LOADING.lock().await;
+
+    let key = origin.ascii_serialization();
+
+    if ROBOTS.read().is_ok_and(|robots| robots.contains_key(&key)) {
+        log::trace!("exit: load");
+        return;
+    }
+
+    let robots_url = match Url::parse(&format!("{}/robots.txt", key)) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("Could not parse the robots.txt url of {}: {}", key, e);
+            log::trace!("exit: load");
+            return;
+        }
+    };
+
+    let robots = match make_request(client, &robots_url).await {
+        Ok(response) if response.status().is_success() => {
+            let contents = response.text().await.unwrap_or_default();
+            Robots::parse(&contents, &CONFIGURATION.useragent)
+        }
+        Ok(response) if response.status().is_client_error() => Robots::default(),
+        Ok(response) => {
+            report_unreachable(&robots_url, response.status().as_str(), tx_file.clone());
+            Robots::disallow_all()
+        }
+        Err(e) => {
+            report_unreachable(&robots_url, &e.to_string(), tx_file.clone());
+            Robots::disallow_all()
+        }
+    };
+
+    for path in robots.disallowed_paths() {
+        if let Ok(url) = robots_url.join(path) {
+            report_skipped(&url, tx_file.clone());
+        }
+    }
+
+    match ROBOTS.write() {
+        Ok(mut loaded) => {
+            loaded.insert(key, robots);
+        }
+        Err(e) => {
+            log::error!("{}", e);
+        }
+    }
+
+    log::trace!("exit: load");
+}
+
+/// Print the given message, and write it to the output file as a `robots` record of the given url
+fn report(msg: String, url: &Url, tx_file: UnboundedSender<String>) {
+    if CONFIGURATION.quiet {
+        return;
+    }
+
+    ferox_print(&msg, &PROGRESS_PRINTER);
+
+    let file_msg = if CONFIGURATION.json {
+        let mut record = json!({
+            "type": "robots",
+            "url": url.as_str(),
+        });
+
+        tag(&mut record);
+
+        format!("{}\n", record)
+    } else {
+        msg
+    };
+
+    try_send_message_to_file(&file_msg, tx_file, !CONFIGURATION.output.is_empty());
+}
+
+/// Let the user know that the given url is disallowed by robots.txt and won't be requested
+fn report_skipped(url: &Url, tx_file: UnboundedSender<String>) {
+    log::info!("{} is disallowed by robots.txt, skipping it", url);
+
+    // example output
+    // RBT          - http://localhost/admin/ present in robots.txt; skipped
+    let msg = format!(
+        "{} {:>10} {} present in robots.txt; {}\n",
+        status_colorizer("RBT"),
+        "-",
+        url,
+        style("skipped").yellow(),
+    );
+
+    report(msg, url, tx_file);
+}
+
+/// Let the user know that nothing of the origin is requested because its robots.txt couldn't be
+/// fetched
+fn report_unreachable(robots_url: &Url, reason: &str, tx_file: UnboundedSender<String>) {
+    log::warn!("Could not fetch {}: {}", robots_url, reason);
+
+    let msg = format!(
+        "{} {:>10} Could not fetch {} ({}); {} anything on its host\n",
+        status_colorizer("RBT"),
+        "-",
+        robots_url,
+        reason,
+        style("not requesting").yellow(),
+    );
+
+    report(msg, robots_url, tx_file);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// robots.txt with a group for feroxbuster, one for everyone, and one for someone else
+    const ROBOTS_TXT: &str = "\
+# comments are ignored
+User-agent: googlebot
+Disallow: /google-only
+
+User-agent: *
+Disallow: /admin/
+Disallow: /*.bak$
+Allow: /admin/public
+Disallow:
+
+User-agent: FeroxBuster
+User-agent: other
+Disallow: /private # trailing comment
+";
+
+    #[test]
+    /// the group naming feroxbuster is picked over the wildcard group, which is used otherwise
+    fn robots_parse_picks_group_of_user_agent() {
+        let robots = Robots::parse(ROBOTS_TXT, "feroxbuster/1.1.1");
+        assert!(!robots.allows("/private"));
+        assert!(robots.allows("/admin/"));
+
+        let robots = Robots::parse(ROBOTS_TXT, "Mozilla/5.0");
+        assert!(robots.allows("/private"));
+        assert!(robots.allows("/google-only"));
+        assert!(!robots.allows("/admin/"));
+    }
+
+    #[test]
+    /// the longest matching rule decides, wildcards match any run of characters, and $ anchors
+    fn robots_allows_longest_match() {
+        let robots = Robots::parse(ROBOTS_TXT, "curl/7.0");
+
+        assert!(!robots.allows("/admin/users"));
+        assert!(robots.allows("/admin/public/index.html"));
+        assert!(robots.allows("/admin"));
+        assert!(!robots.allows("/backup/db.bak"));
+        assert!(robots.allows("/backup/db.bak.txt"));
+        assert!(robots.allows("/index.html"));
+        assert!(Robots::default().allows("/anything"));
+        assert!(!Robots::disallow_all().allows("/anything"));
+    }
+
+    #[test]
+    /// equally long allow and disallow rules favor allow
+    fn robots_allows_favors_allow_on_ties() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /page\nAllow: /page\n", "");
+        assert!(robots.allows("/page"));
+    }
+
+    #[test]
+    /// only disallowed paths without wildcards name a path
+    fn robots_disallowed_paths_skip_patterns() {
+        let robots = Robots::parse(ROBOTS_TXT, "curl/7.0");
+        assert_eq!(robots.disallowed_paths(), vec!["/admin/"]);
+        assert!(Robots::disallow_all().disallowed_paths().is_empty());
+    }
+
+    #[test]
+    /// queries are matched along with the path
+    fn robots_path_includes_query() {
+        let url = Url::parse("http://localhost/search?q=1").unwrap();
+        assert_eq!(robots_path(&url), "/search?q=1");
+    }
+}
@@ -0,0 +1,160 @@
+use crate::config::CONFIGURATION;
+use crate::encryption::OutputFile;
+use lazy_static::lazy_static;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+lazy_static! {
+    /// Size in bytes at which `--output` rolls over, 0 when it never does
+    static ref MAX_SIZE: u64 = parse_size(&CONFIGURATION.output_max_size).unwrap_or(0);
+}
+
+/// Whether or not rolling over failed; `--output` grows unbounded from then on, rather than
+/// failing at every write
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Parse a size given to `--output-max-size`, i.e. `500M`; a number of bytes, optionally
+/// followed by a `K`, `M`, `G`, or `T` multiple of 1024 (`KB` and `KiB` are the same as `K`)
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+
+    let (number, unit) = size.split_at(digits);
+
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("{} doesn't start with a number", size))?;
+
+    let shift = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 10,
+        "m" | "mb" | "mib" => 20,
+        "g" | "gb" | "gib" => 30,
+        "t" | "tb" | "tib" => 40,
+        _ => return Err(format!("{} isn't a unit; use K, M, G, or T", unit.trim())),
+    };
+
+    match number.checked_mul(1 << shift) {
+        Some(0) => Err(String::from("the size must be more than 0")),
+        Some(bytes) => Ok(bytes),
+        None => Err(format!("{} is too large", size)),
+    }
+}
+
+/// Whether or not `--output` rolls over once it reaches `--output-max-size`
+pub fn is_enabled() -> bool {
+    *MAX_SIZE > 0 && !FAILED.load(Ordering::Relaxed)
+}
+
+/// Path of the given rollover of `output`, i.e. `scan.json.2`
+fn rollover_path(output: &str, number: usize) -> String {
+    format!("{}.{}", output, number)
+}
+
+/// Move `output` to its first rollover, shifting the previous rollovers by one; the oldest of
+/// `max_files` rollovers is removed, and no rollovers are kept when `max_files` is 0
+pub fn rotate_files(output: &str, max_files: usize) -> io::Result<()> {
+    if max_files == 0 {
+        return fs::remove_file(output);
+    }
+
+    let oldest = rollover_path(output, max_files);
+
+    if Path::new(&oldest).exists() {
+        fs::remove_file(&oldest)?;
+    }
+
+    for number in (1..max_files).rev() {
+        let rollover = rollover_path(output, number);
+
+        if Path::new(&rollover).exists() {
+            fs::rename(&rollover, rollover_path(output, number + 1))?;
+        }
+    }
+
+    fs::rename(output, rollover_path(output, 1))
+}
+
+/// Roll `--output` over once it reached `--output-max-size`: its contents move to `output.1`, and
+/// writing continues in a new, empty `output`
+///
+/// `writer` is the output file's, whose lock is held by the caller; nothing may be logged here, as
+/// the logger writes to the same file (see `reporter::safe_file_write`). Rolling over is disabled
+/// after it fails once
+pub fn roll_over_if_full(writer: &mut BufWriter<OutputFile>) -> io::Result<()> {
+    let rolled_over = roll_over(writer);
+
+    if rolled_over.is_err() {
+        FAILED.store(true, Ordering::Relaxed);
+    }
+
+    rolled_over
+}
+
+/// See `roll_over_if_full`
+fn roll_over(writer: &mut BufWriter<OutputFile>) -> io::Result<()> {
+    if writer.get_ref().metadata()?.len() < *MAX_SIZE {
+        return Ok(());
+    }
+
+    // encrypted files are only complete once finished, each rollover is a file of its own
+    writer.flush()?;
+    writer.get_mut().finish()?;
+
+    rotate_files(&CONFIGURATION.output, CONFIGURATION.output_max_files)?;
+
+    *writer = BufWriter::new(OutputFile::open(&CONFIGURATION.output)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    /// sizes are bytes, optionally followed by a multiple of 1024
+    fn rollover_parse_size_accepts_units() {
+        assert_eq!(parse_size("2048"), Ok(2048));
+        assert_eq!(parse_size("10K"), Ok(10 * 1024));
+        assert_eq!(parse_size("500M"), Ok(500 * 1024 * 1024));
+        assert_eq!(parse_size("500mb"), Ok(500 * 1024 * 1024));
+        assert_eq!(parse_size("2 GiB"), Ok(2 * 1024 * 1024 * 1024));
+
+        assert!(parse_size("").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("0M").is_err());
+        assert!(parse_size("5X").is_err());
+        assert!(parse_size("1.5G").is_err());
+        assert!(parse_size("99999999999T").is_err());
+    }
+
+    #[test]
+    /// rollovers are shifted by one, the oldest is dropped
+    fn rollover_rotate_files_keeps_max_files() {
+        let tmp_dir = TempDir::new().unwrap();
+        let output = tmp_dir.path().join("scan.json");
+        let output = output.to_str().unwrap();
+        let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+
+        for run in 1..=4 {
+            fs::write(output, format!("run {}", run)).unwrap();
+            rotate_files(output, 2).unwrap();
+        }
+
+        assert!(!Path::new(output).exists());
+        assert_eq!(read(&rollover_path(output, 1)), "run 4");
+        assert_eq!(read(&rollover_path(output, 2)), "run 3");
+        assert!(!Path::new(&rollover_path(output, 3)).exists());
+
+        fs::write(output, "run 5").unwrap();
+        rotate_files(output, 0).unwrap();
+
+        assert!(!Path::new(output).exists());
+        assert_eq!(read(&rollover_path(output, 1)), "run 4");
+    }
+}
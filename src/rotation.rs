@@ -0,0 +1,329 @@
+use crate::client;
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use lazy_static::lazy_static;
+use reqwest::{Client, Response, Url};
+use std::collections::HashMap;
+use std::fs;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Strategies that may be given to `--proxy-rotation`
+pub const STRATEGIES: [&str; 2] = ["round-robin", "affinity"];
+
+/// Consecutive requests that failed to connect before a proxy is considered dead
+const DEAD_AFTER: usize = 3;
+
+lazy_static! {
+    /// Pool of `--proxy-file`, when given
+    static ref POOL: RwLock<Option<Arc<Pool>>> = RwLock::new(None);
+}
+
+/// A proxy of the pool, along with the client that goes through it
+#[derive(Debug)]
+pub struct Member {
+    /// url of the proxy, as given in the file
+    pub proxy: String,
+
+    /// client whose requests go through the proxy
+    pub client: Client,
+
+    /// requests in a row that failed to connect
+    failures: AtomicUsize,
+}
+
+/// Proxies requests rotate across (see `--proxy-file`)
+#[derive(Debug)]
+pub struct Pool {
+    /// proxies that are still alive, in the order of the file
+    members: RwLock<Vec<Arc<Member>>>,
+
+    /// position of the next proxy handed out, round-robin
+    next: AtomicUsize,
+
+    /// proxy each target's origin sticks to, with `affinity`
+    affinity: Option<RwLock<HashMap<String, String>>>,
+}
+
+impl Pool {
+    /// Pool of the given proxies and clients; each target's origin sticks to one proxy with
+    /// `affinity`, requests go through the proxies in turn otherwise
+    pub fn new(members: Vec<(String, Client)>, affinity: bool) -> Self {
+        Self {
+            members: RwLock::new(
+                members
+                    .into_iter()
+                    .map(|(proxy, client)| {
+                        Arc::new(Member {
+                            proxy,
+                            client,
+                            failures: AtomicUsize::new(0),
+                        })
+                    })
+                    .collect(),
+            ),
+            next: AtomicUsize::new(0),
+            affinity: if affinity {
+                Some(RwLock::new(HashMap::new()))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Proxies still alive
+    pub fn proxies(&self) -> Vec<String> {
+        match self.members.read() {
+            Ok(members) => members.iter().map(|member| member.proxy.clone()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Next proxy in turn
+    fn next(&self, members: &[Arc<Member>]) -> Option<Arc<Member>> {
+        if members.is_empty() {
+            return None;
+        }
+
+        let position = self.next.fetch_add(1, Ordering::Relaxed) % members.len();
+        Some(members[position].clone())
+    }
+
+    /// Proxy the request to the given url goes through
+    pub fn pick(&self, url: &Url) -> Option<Arc<Member>> {
+        let members = self.members.read().ok()?;
+
+        let affinity = match &self.affinity {
+            Some(affinity) => affinity,
+            None => return self.next(&members),
+        };
+
+        let origin = url.origin().ascii_serialization();
+
+        if let Some(proxy) = affinity.read().ok()?.get(&origin) {
+            if let Some(member) = members.iter().find(|member| member.proxy == *proxy) {
+                return Some(member.clone());
+            }
+        }
+
+        // first request to the origin, or its proxy died
+        let member = self.next(&members)?;
+
+        if let Ok(mut affinity) = affinity.write() {
+            affinity.insert(origin, member.proxy.clone());
+        }
+
+        Some(member)
+    }
+
+    /// Record whether or not a request through the given proxy reached it; returns the number of
+    /// proxies left when the proxy was found dead and removed
+    ///
+    /// The last proxy is never removed, requests would otherwise go out without a proxy
+    pub fn record(&self, member: &Member, connected: bool) -> Option<usize> {
+        if connected {
+            member.failures.store(0, Ordering::Relaxed);
+            return None;
+        }
+
+        // origins move on to the next proxy rather than waiting for this one to be found dead
+        if let Some(Ok(mut affinity)) = self.affinity.as_ref().map(RwLock::write) {
+            affinity.retain(|_, proxy| *proxy != member.proxy);
+        }
+
+        if member.failures.fetch_add(1, Ordering::Relaxed) + 1 < DEAD_AFTER {
+            return None;
+        }
+
+        let mut members = self.members.write().ok()?;
+
+        if members.len() <= 1 || !members.iter().any(|alive| alive.proxy == member.proxy) {
+            return None;
+        }
+
+        members.retain(|alive| alive.proxy != member.proxy);
+        Some(members.len())
+    }
+}
+
+/// Proxies of the given `--proxy-file` contents: one per line, blank lines and lines starting
+/// with `#` are skipped
+pub fn parse(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+/// Proxy of the pool the request to the given url goes through, if there's a pool
+pub fn pick(url: &Url) -> Option<(Arc<Pool>, Arc<Member>)> {
+    let pool = POOL.read().ok()?.clone()?;
+    let member = pool.pick(url)?;
+    Some((pool, member))
+}
+
+/// Record the outcome of a request made through the given proxy; proxies that can't be
+/// connected to `DEAD_AFTER` times in a row are removed from the pool
+pub fn record(pool: &Pool, member: &Member, result: &reqwest::Result<Response>) {
+    let connected = match result {
+        Ok(_) => true,
+        Err(e) => !e.is_connect(),
+    };
+
+    if let Some(left) = pool.record(member, connected) {
+        let msg = format!(
+            "{} {:>10} Proxy {} is dead, removed it from the pool ({} left)\n",
+            status_colorizer("ERR"),
+            "-",
+            member.proxy,
+            left
+        );
+
+        log::warn!("{}", console::strip_ansi_codes(msg.trim_end()));
+
+        if CONFIGURATION.silent {
+            eprint!("{}", msg);
+        } else {
+            ferox_print(&msg, &PROGRESS_PRINTER);
+        }
+    }
+}
+
+/// Build a client for each proxy of `--proxy-file` and rotate requests across them; exits when
+/// the file can't be read or lists no proxy
+pub fn initialize() {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.proxy_file.is_empty() {
+        log::trace!("exit: initialize");
+        return;
+    }
+
+    let fail = |msg: String| -> ! {
+        eprintln!(
+            "{} {} {}",
+            status_colorizer("ERROR"),
+            module_colorizer("rotation::initialize"),
+            msg
+        );
+        process::exit(1);
+    };
+
+    let contents = fs::read_to_string(&CONFIGURATION.proxy_file).unwrap_or_else(|e| {
+        fail(format!(
+            "Could not read --proxy-file {}: {}",
+            CONFIGURATION.proxy_file, e
+        ))
+    });
+
+    let proxies = parse(&contents);
+
+    if proxies.is_empty() {
+        fail(format!(
+            "--proxy-file {} lists no proxy",
+            CONFIGURATION.proxy_file
+        ));
+    }
+
+    let members = proxies
+        .into_iter()
+        .map(|proxy| {
+            let client = client::initialize(
+                CONFIGURATION.timeout,
+                &CONFIGURATION.useragent,
+                CONFIGURATION.redirects,
+                CONFIGURATION.insecure,
+                Some(&proxy),
+            );
+
+            (proxy, client)
+        })
+        .collect();
+
+    let pool = Pool::new(members, CONFIGURATION.proxy_rotation == "affinity");
+
+    log::info!("rotating requests across {:?}", pool.proxies());
+
+    match POOL.write() {
+        Ok(mut global) => *global = Some(Arc::new(pool)),
+        Err(e) => log::error!("{}", e),
+    }
+
+    log::trace!("exit: initialize");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// pool of the given proxies
+    fn pool(proxies: &[&str], affinity: bool) -> Pool {
+        Pool::new(
+            proxies
+                .iter()
+                .map(|proxy| (proxy.to_string(), Client::new()))
+                .collect(),
+            affinity,
+        )
+    }
+
+    /// proxy picked for the given url
+    fn picked(pool: &Pool, url: &str) -> String {
+        pool.pick(&Url::parse(url).unwrap()).unwrap().proxy.clone()
+    }
+
+    #[test]
+    /// comments and blank lines are skipped
+    fn rotation_parse_skips_comments() {
+        let contents = "# egress\nhttp://10.0.0.1:3128\n\n  socks5://10.0.0.2:1080  \n#http://x\n";
+        assert_eq!(
+            parse(contents),
+            vec!["http://10.0.0.1:3128", "socks5://10.0.0.2:1080"]
+        );
+    }
+
+    #[test]
+    /// round-robin goes through the proxies in turn, affinity sticks to one per origin
+    fn rotation_pool_picks_by_strategy() {
+        let round_robin = pool(&["a", "b", "c"], false);
+        let picks: Vec<_> = (0..4)
+            .map(|_| picked(&round_robin, "http://localhost/x"))
+            .collect();
+        assert_eq!(picks, vec!["a", "b", "c", "a"]);
+
+        let affinity = pool(&["a", "b", "c"], true);
+        assert_eq!(picked(&affinity, "http://one/x"), "a");
+        assert_eq!(picked(&affinity, "http://two/x"), "b");
+        assert_eq!(picked(&affinity, "http://one/y"), "a");
+        assert_eq!(picked(&affinity, "http://two/y"), "b");
+    }
+
+    #[test]
+    /// proxies are removed after failing to connect in a row, except for the last one
+    fn rotation_pool_removes_dead_proxies() {
+        let pool = pool(&["a", "b"], true);
+        let url = Url::parse("http://one/x").unwrap();
+        let a = pool.pick(&url).unwrap();
+
+        assert_eq!(pool.record(&a, false), None);
+        assert_eq!(pool.record(&a, true), None); // a success resets the count
+        assert_eq!(pool.record(&a, false), None);
+        assert_eq!(pool.record(&a, false), None);
+        assert_eq!(pool.record(&a, false), Some(1));
+        assert_eq!(pool.proxies(), vec!["b"]);
+
+        // the origin moves on to a live proxy
+        assert_eq!(picked(&pool, "http://one/y"), "b");
+
+        let b = pool.pick(&url).unwrap();
+
+        for _ in 0..DEAD_AFTER {
+            assert_eq!(pool.record(&b, false), None);
+        }
+
+        assert_eq!(pool.proxies(), vec!["b"]);
+    }
+}
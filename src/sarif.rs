@@ -0,0 +1,230 @@
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+
+/// Formats the results written to `--output` can take (see `--format`)
+pub const FORMATS: [&str; 3] = ["text", "json", "sarif"];
+
+/// Kinds of findings, each a rule of the SARIF log, as (id, short description, level)
+const RULES: [(&str, &str, &str); 7] = [
+    ("directory-listing", "Directory listing enabled", "warning"),
+    ("exposed-content", "Content is accessible", "warning"),
+    ("redirect", "Content redirects elsewhere", "note"),
+    ("unauthorized", "Content requires authentication", "note"),
+    ("forbidden", "Content exists but is forbidden", "note"),
+    (
+        "client-error",
+        "Content answered with a client error",
+        "note",
+    ),
+    (
+        "server-error",
+        "Content answered with a server error",
+        "warning",
+    ),
+];
+
+/// Single reported result
+#[derive(Debug, Clone)]
+struct SarifResult {
+    /// url of the response
+    url: Url,
+
+    /// status code of the response
+    status: u16,
+
+    /// content-length of the response
+    content_length: u64,
+
+    /// position of the result's rule in `RULES`
+    rule: usize,
+}
+
+/// Position in `RULES` of the rule a response with the given status belongs to
+fn rule_of(status: u16, listing: bool) -> usize {
+    match status {
+        _ if listing => 0,
+        200..=299 => 1,
+        300..=399 => 2,
+        401 => 3,
+        403 => 4,
+        500..=599 => 6,
+        _ => 5,
+    }
+}
+
+/// All reported results, rendered as a SARIF 2.1.0 log once the scan completes (see
+/// `--format sarif`)
+///
+/// Each kind of finding is a rule of the log, and each result's location is its url
+///
+/// ```text
+/// {
+///   "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+///   "version": "2.1.0",
+///   "runs": [{
+///     "tool": { "driver": { "name": "feroxbuster", "rules": [...] } },
+///     "results": [{
+///       "ruleId": "exposed-content",
+///       "level": "warning",
+///       "message": { "text": "200 http://localhost/admin (1024 bytes)" },
+///       "locations": [{ "physicalLocation": { "artifactLocation": { "uri": "http://localhost/admin" } } }]
+///     }]
+///   }]
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct SarifReport {
+    /// results in the order they were reported
+    results: Vec<SarifResult>,
+}
+
+impl SarifReport {
+    /// Add a reported result; `listing` is whether or not it's a directory listing
+    pub fn insert(&mut self, url: &Url, status: u16, content_length: u64, listing: bool) {
+        self.results.push(SarifResult {
+            url: url.clone(),
+            status,
+            content_length,
+            rule: rule_of(status, listing),
+        });
+    }
+
+    /// Render the log; `tags` are the key/value pairs attached to the scan (see `metadata`)
+    pub fn render(&self, version: &str, tags: &[(String, String)]) -> String {
+        let rules: Vec<Value> = RULES
+            .iter()
+            .map(|(id, description, level)| {
+                json!({
+                    "id": id,
+                    "shortDescription": { "text": description },
+                    "defaultConfiguration": { "level": level },
+                })
+            })
+            .collect();
+
+        let results: Vec<Value> = self
+            .results
+            .iter()
+            .map(|result| {
+                let (id, _, level) = RULES[result.rule];
+
+                json!({
+                    "ruleId": id,
+                    "ruleIndex": result.rule,
+                    "level": level,
+                    "message": {
+                        "text": format!(
+                            "{} {} ({} bytes)",
+                            result.status, result.url, result.content_length
+                        ),
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": result.url.as_str() },
+                        },
+                    }],
+                    "partialFingerprints": {
+                        "feroxbuster/v1": format!("{}:{}", id, result.url),
+                    },
+                    "properties": {
+                        "status": result.status,
+                        "content_length": result.content_length,
+                    },
+                })
+            })
+            .collect();
+
+        let properties: Map<String, Value> = tags
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect();
+
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "feroxbuster",
+                        "version": version,
+                        "informationUri": "https://github.com/epi052/feroxbuster",
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+                "properties": properties,
+            }],
+        });
+
+        // serializing a Value doesn't fail
+        let mut rendered = serde_json::to_string_pretty(&log).unwrap_or_default();
+        rendered.push('\n');
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// statuses and listings map to their rules
+    fn sarif_rule_of_classifies_findings() {
+        let id = |status, listing| RULES[rule_of(status, listing)].0;
+
+        assert_eq!(id(200, true), "directory-listing");
+        assert_eq!(id(204, false), "exposed-content");
+        assert_eq!(id(301, false), "redirect");
+        assert_eq!(id(401, false), "unauthorized");
+        assert_eq!(id(403, false), "forbidden");
+        assert_eq!(id(405, false), "client-error");
+        assert_eq!(id(503, false), "server-error");
+    }
+
+    #[test]
+    /// the log is SARIF 2.1.0, with a result per finding located at its url
+    fn sarif_render_locates_results_at_urls() {
+        let mut report = SarifReport::default();
+        report.insert(
+            &Url::parse("http://localhost/admin").unwrap(),
+            200,
+            1024,
+            false,
+        );
+        report.insert(
+            &Url::parse("http://localhost/files/").unwrap(),
+            200,
+            512,
+            true,
+        );
+
+        let tags = vec![(String::from("scan_id"), String::from("abc"))];
+        let log: Value = serde_json::from_str(&report.render("1.1.1", &tags)).unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "feroxbuster");
+        assert_eq!(run["tool"]["driver"]["version"], "1.1.1");
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            RULES.len()
+        );
+        assert_eq!(run["properties"]["scan_id"], "abc");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "exposed-content");
+        assert_eq!(results[0]["ruleIndex"], 1);
+        assert_eq!(
+            results[0]["message"]["text"],
+            "200 http://localhost/admin (1024 bytes)"
+        );
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "http://localhost/admin"
+        );
+        assert_eq!(results[1]["ruleId"], "directory-listing");
+        assert_eq!(results[1]["level"], "warning");
+    }
+}
@@ -1,17 +1,32 @@
-use crate::config::{CONFIGURATION, PROGRESS_BAR};
+use crate::bloom::BloomFilter;
+use crate::concurrency::{AdaptiveConcurrency, Signal};
+use crate::config::{Configuration, CONFIGURATION, PROGRESS_BAR};
+use crate::error_log::{log_filtered, log_request_error};
+use crate::events::{self, Event};
 use crate::extractor::get_links;
-use crate::heuristics::WildcardFilter;
-use crate::utils::{format_url, get_current_depth, get_url_path_length, make_request};
-use crate::{heuristics, progress, FeroxChannel, FeroxResponse};
+use crate::forbidden::{self, Context};
+use crate::heuristics::{Soft404Filter, WildcardFilter};
+use crate::limiter::RateLimiter;
+use crate::redirects::Chain;
+use crate::slash::Verdict;
+use crate::statistics::STATISTICS;
+use crate::utils::{
+    format_url, get_current_depth, get_url_path_length, make_redirected_request, UrlBuilder,
+};
+use crate::wordlist::Wordlist;
+use crate::{
+    ban, canary, digest, dynamic, fingerprint, heuristics, hooks, import, methods, progress,
+    robots, signals, slash, state, traps, FeroxChannel, FeroxResponse, FeroxResult,
+};
 use futures::future::{BoxFuture, FutureExt};
 use futures::{stream, StreamExt};
 use lazy_static::lazy_static;
-use reqwest::Url;
+use reqwest::{Client, Method, Response, StatusCode, Url};
 use std::collections::HashSet;
 use std::convert::TryInto;
-use std::ops::Deref;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
@@ -22,8 +37,189 @@ lazy_static! {
     /// Set of urls that have been sent to [scan_url](fn.scan_url.html), used for deduplication
     static ref SCANNED_URLS: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
 
+    /// Links found with `--extract-links` that have been requested, used for deduplication of
+    /// requests; kept in a bloom filter as they can number in the millions, unlike the
+    /// directories scanned
+    static ref REQUESTED_LINKS: RwLock<BloomFilter> = RwLock::new(BloomFilter::default());
+
+    /// Links found with `--extract-links` whose response was reported, used for deduplication of
+    /// results; kept exactly, so that the bloom filter's false positives never decide whether a
+    /// result is a duplicate
+    static ref REPORTED_LINKS: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
+
     /// Vector of WildcardFilters that have been ID'd through heuristics
     static ref WILDCARD_FILTERS: Arc<RwLock<Vec<Arc<WildcardFilter>>>> = Arc::new(RwLock::new(Vec::<Arc<WildcardFilter>>::new()));
+
+    /// Vector of Soft404Filters that have been ID'd through heuristics (only with --soft-404)
+    static ref SOFT_404_FILTERS: Arc<RwLock<Vec<Arc<Soft404Filter>>>> = Arc::new(RwLock::new(Vec::<Arc<Soft404Filter>>::new()));
+
+    /// Urls of the scans cancelled while running (see `cancel`)
+    static ref CANCELLED_SCANS: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
+}
+
+/// Options that may differ from one target to the next (see `--targets`); scans started by
+/// recursion share the options of the scan that found them
+#[derive(Debug)]
+pub struct ScanOptions {
+    /// client with which the scan's requests are made
+    pub client: Client,
+
+    /// maximum recursion depth, 0 for infinite
+    pub depth: usize,
+
+    /// limits the requests made per second by the scan and its recursive scans, when given
+    pub limiter: Option<RateLimiter>,
+
+    /// adjusts the number of requests in flight for the scan and its recursive scans, when given
+    /// (see `--adaptive-concurrency`)
+    pub concurrency: Option<AdaptiveConcurrency>,
+
+    /// extensions appended to each word
+    pub extensions: Vec<String>,
+
+    /// method of the requests made for each word (see `--method`)
+    pub method: Method,
+
+    /// methods a request rejected with `method` is retried with, in order (see `--method`)
+    pub fallbacks: Vec<Method>,
+}
+
+/// Response to a request, the redirects followed to get it (see `--redirects`), and the response
+/// that rejected the configured method, when the response is to one of its fallbacks
+pub type Requested = (Response, Option<Chain>, Option<Response>);
+
+impl Default for ScanOptions {
+    /// Options taken from the global configuration
+    fn default() -> Self {
+        Self {
+            client: CONFIGURATION.client.clone(),
+            depth: CONFIGURATION.depth,
+            limiter: RateLimiter::scheduled(CONFIGURATION.rate_limit),
+            concurrency: adaptive_concurrency(&CONFIGURATION),
+            extensions: CONFIGURATION.extensions.clone(),
+            method: methods::parse(&CONFIGURATION.method),
+            fallbacks: methods::fallbacks(&CONFIGURATION.method),
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Wait until the next request may be made, according to the rate limit (if any) and any
+    /// reaction to the scan looking blocked (see `--ban-policy` and `--canary-pause`)
+    ///
+    /// Requests paused by SIGUSR1 wait ahead of the rate limit, and give up their turn when paused
+    /// while waiting on it, so that they aren't let through all at once on SIGUSR2
+    pub async fn throttle(&self) {
+        loop {
+            signals::wait().await;
+            ban::wait().await;
+            canary::wait().await;
+
+            if let Some(limiter) = &self.limiter {
+                limiter.wait().await;
+            }
+
+            if !signals::is_paused() {
+                break;
+            }
+        }
+    }
+
+    /// Request the given url once it may be made (see `throttle`); with `--adaptive-concurrency`,
+    /// the request also waits for a free slot, and its outcome adjusts the number of slots
+    ///
+    /// The response comes with the redirects followed to get it, if any (see `--redirects`)
+    ///
+    /// A request rejected with a 405 or 501 is retried with each of the fallback methods (see
+    /// `methods::fallbacks`) until one is accepted; the accepted response is used, and the
+    /// rejected one comes along with it so that it's still reported. A directory's requests are
+    /// made with a safe fallback from then on once it worked for a few of them (see
+    /// `methods::accepted`)
+    pub async fn request(&self, url: &Url) -> FeroxResult<Requested> {
+        let method = methods::method_for(url, &self.method);
+
+        let result = self.request_with(&method, url).await;
+
+        // only the configured method falls back; one remembered for the directory already did
+        let rejected = match &result {
+            Ok((response, _)) => method == self.method && methods::rejects(response.status()),
+            Err(_) => false,
+        };
+
+        if !rejected || self.fallbacks.is_empty() {
+            return result.map(|(response, chain)| (response, chain, None));
+        }
+
+        for fallback in &self.fallbacks {
+            log::debug!("{} rejected {}, retrying with {}", url, method, fallback);
+
+            match self.request_with(fallback, url).await {
+                Ok((response, chain)) if !methods::rejects(response.status()) => {
+                    methods::accepted(url, fallback);
+                    let rejected = result.ok().map(|(rejected, _)| rejected);
+                    return Ok((response, chain, rejected));
+                }
+                _ => continue,
+            }
+        }
+
+        result.map(|(response, chain)| (response, chain, None))
+    }
+
+    /// Request the given url with the given method once it may be made; see `request`
+    async fn request_with(
+        &self,
+        method: &Method,
+        url: &Url,
+    ) -> FeroxResult<(Response, Option<Chain>)> {
+        self.throttle().await;
+
+        let concurrency = match &self.concurrency {
+            Some(concurrency) => concurrency,
+            None => return make_redirected_request(&self.client, method, url).await,
+        };
+
+        let _slot = concurrency.acquire().await;
+        let start = Instant::now();
+
+        let result = make_redirected_request(&self.client, method, url).await;
+
+        let signal = match &result {
+            Ok((response, _)) => Signal::from_status(response.status()),
+            Err(_) => Signal::Overloaded,
+        };
+
+        concurrency.record(start.elapsed(), signal);
+        result
+    }
+}
+
+/// Controller of the requests in flight for the given configuration, if it asks for one
+pub fn adaptive_concurrency(config: &Configuration) -> Option<AdaptiveConcurrency> {
+    if config.adaptive_concurrency {
+        Some(AdaptiveConcurrency::new(config.threads))
+    } else {
+        None
+    }
+}
+
+/// Cancel the running scan of the given url: it requests none of the words it has left, and
+/// finishes once the requests in flight complete; scans of the directories it found keep going
+pub fn cancel(url: &str) {
+    match CANCELLED_SCANS.write() {
+        Ok(mut cancelled) => {
+            cancelled.insert(url.to_string());
+        }
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// Whether or not the scan of the given url was cancelled (see `cancel`)
+fn is_cancelled(url: &str) -> bool {
+    CANCELLED_SCANS
+        .read()
+        .map(|cancelled| cancelled.contains(url))
+        .unwrap_or_default()
 }
 
 /// Adds the given url to `SCANNED_URLS`
@@ -63,12 +259,100 @@ fn add_url_to_list_of_scanned_urls(resp: &str, scanned_urls: &RwLock<HashSet<Str
     }
 }
 
-/// Adds the given WildcardFilter to `WILDCARD_FILTERS`
+/// Every url in `SCANNED_URLS`, sorted; saved with `--state-file`
+pub(crate) fn scanned_urls() -> Vec<String> {
+    let mut urls: Vec<String> = match SCANNED_URLS.read() {
+        Ok(urls) => urls.iter().cloned().collect(),
+        Err(e) => {
+            log::error!("Set of scanned urls poisoned: {}", e);
+            Vec::new()
+        }
+    };
+
+    urls.sort();
+    urls
+}
+
+/// Copy of `REQUESTED_LINKS`; saved with `--state-file`
+pub(crate) fn requested_links() -> BloomFilter {
+    match REQUESTED_LINKS.read() {
+        Ok(links) => links.clone(),
+        Err(e) => {
+            log::error!("Filter of requested links poisoned: {}", e);
+            BloomFilter::default()
+        }
+    }
+}
+
+/// Replace `REQUESTED_LINKS` with the links requested by a resumed run (see `--resume-from`)
+pub(crate) fn restore_requested_links(links: BloomFilter) {
+    match REQUESTED_LINKS.write() {
+        Ok(mut requested_links) => *requested_links = links,
+        Err(e) => log::error!("Filter of requested links poisoned: {}", e),
+    }
+}
+
+/// Normalize a link found with `--extract-links` the same way as the urls in `SCANNED_URLS`
+fn normalize_link(link: &str) -> String {
+    if link.ends_with('/') {
+        link.to_string()
+    } else {
+        format!("{}/", link)
+    }
+}
+
+/// Adds the given link, found with `--extract-links`, to `REQUESTED_LINKS`
+///
+/// If the link isn't a url already in `SCANNED_URLS` or `REPORTED_LINKS`, and `REQUESTED_LINKS`
+/// did not (probably) contain it already, return true; otherwise return false.  Links already
+/// scanned or reported are known exactly, only the requests of the others are de-duplicated by
+/// the bloom filter
+fn add_link_to_requested_links(
+    link: &str,
+    scanned_urls: &RwLock<HashSet<String>>,
+    reported_links: &RwLock<HashSet<String>>,
+    requested_links: &RwLock<BloomFilter>,
+) -> bool {
+    log::trace!("enter: add_link_to_requested_links({})", link);
+
+    let normalized_link = normalize_link(link);
+
+    let scanned = match scanned_urls.read() {
+        Ok(urls) => urls.contains(&normalized_link),
+        Err(e) => {
+            log::error!("Set of scanned urls poisoned: {}", e);
+            true
+        }
+    };
+
+    let reported = match reported_links.read() {
+        Ok(links) => links.contains(&normalized_link),
+        Err(e) => {
+            log::error!("Set of reported links poisoned: {}", e);
+            true
+        }
+    };
+
+    let response = !scanned
+        && !reported
+        && match requested_links.write() {
+            Ok(mut links) => links.insert(&normalized_link),
+            Err(e) => {
+                log::error!("Filter of requested links poisoned: {}", e);
+                false
+            }
+        };
+
+    log::trace!("exit: add_link_to_requested_links -> {}", response);
+    response
+}
+
+/// Adds the given filter to a list of filters, i.e. a WildcardFilter to `WILDCARD_FILTERS`
 ///
-/// If `WILDCARD_FILTERS` did not already contain the filter, return true; otherwise return false
-fn add_filter_to_list_of_wildcard_filters(
-    filter: Arc<WildcardFilter>,
-    wildcard_filters: Arc<RwLock<Vec<Arc<WildcardFilter>>>>,
+/// If the list did not already contain the filter, return true; otherwise return false
+fn add_filter_to_list_of_wildcard_filters<T: PartialEq + std::fmt::Debug>(
+    filter: Arc<T>,
+    wildcard_filters: Arc<RwLock<Vec<Arc<T>>>>,
 ) -> bool {
     log::trace!(
         "enter: add_filter_to_list_of_wildcard_filters({:?}, {:?})",
@@ -104,16 +388,18 @@ fn add_filter_to_list_of_wildcard_filters(
 /// The consumer simply receives Urls and scans them
 fn spawn_recursion_handler(
     mut recursion_channel: UnboundedReceiver<String>,
-    wordlist: Arc<HashSet<String>>,
+    wordlist: Arc<Wordlist>,
     base_depth: usize,
+    options: Arc<ScanOptions>,
     tx_term: UnboundedSender<FeroxResponse>,
     tx_file: UnboundedSender<String>,
 ) -> BoxFuture<'static, Vec<JoinHandle<()>>> {
     log::trace!(
-        "enter: spawn_recursion_handler({:?}, wordlist[{} words...], {}, {:?}, {:?})",
+        "enter: spawn_recursion_handler({:?}, wordlist[{} words...], {}, {:?}, {:?}, {:?})",
         recursion_channel,
         wordlist.len(),
         base_depth,
+        options,
         tx_term,
         tx_file
     );
@@ -130,16 +416,26 @@ fn spawn_recursion_handler(
 
             log::info!("received {} on recursion channel", resp);
 
+            if let Ok(url) = Url::parse(&resp) {
+                if let Some(reason) = traps::find(&url) {
+                    // one misbehaving route could otherwise consume the entire run
+                    traps::report(&url, reason, tx_file.clone());
+                    continue;
+                }
+            }
+
             let term_clone = tx_term.clone();
             let file_clone = tx_file.clone();
             let resp_clone = resp.clone();
             let list_clone = wordlist.clone();
+            let options_clone = options.clone();
 
             scans.push(tokio::spawn(async move {
                 scan_url(
                     resp_clone.to_owned().as_str(),
                     list_clone,
                     base_depth,
+                    options_clone,
                     term_clone,
                     file_clone,
                 )
@@ -160,34 +456,22 @@ fn spawn_recursion_handler(
 ///
 /// If any extensions were passed to the program, each extension will add a
 /// (base_url + word + ext) Url to the vector
-fn create_urls(target_url: &str, word: &str, extensions: &[String]) -> Vec<Url> {
+fn create_urls(builder: &UrlBuilder, word: &str, extensions: &[String]) -> Vec<Url> {
     log::trace!(
-        "enter: create_urls({}, {}, {:?})",
-        target_url,
+        "enter: create_urls({:?}, {}, {:?})",
+        builder,
         word,
         extensions
     );
 
-    let mut urls = vec![];
+    let mut urls = Vec::with_capacity(extensions.len() + 1);
 
-    if let Ok(url) = format_url(
-        &target_url,
-        &word,
-        CONFIGURATION.addslash,
-        &CONFIGURATION.queries,
-        None,
-    ) {
+    if let Ok(url) = builder.build(word, None) {
         urls.push(url); // default request, i.e. no extension
     }
 
     for ext in extensions.iter() {
-        if let Ok(url) = format_url(
-            &target_url,
-            &word,
-            CONFIGURATION.addslash,
-            &CONFIGURATION.queries,
-            Some(ext),
-        ) {
+        if let Ok(url) = builder.build(word, Some(ext)) {
             urls.push(url); // any extensions passed in
         }
     }
@@ -196,12 +480,40 @@ fn create_urls(target_url: &str, word: &str, extensions: &[String]) -> Vec<Url>
     urls
 }
 
+/// Whether or not `location` is the same path as `url` plus a trailing slash, on the same host;
+/// i.e. `/js` to `/js/`, which is how web servers redirect requests for directories
+pub fn is_directory_redirect(url: &Url, location: &Url) -> bool {
+    url.host_str() == location.host_str() && location.path() == format!("{}/", url.path())
+}
+
 /// Helper function to determine suitability for recursion
 ///
 /// handles 2xx and 3xx responses by either checking if the url ends with a / (2xx)
-/// or if the Location header is present and matches the base url + / (3xx)
-fn response_is_directory(response: &FeroxResponse) -> bool {
-    log::trace!("enter: is_directory({:?})", response);
+/// or if the Location header is present and points to the same path + / (3xx); 403 responses
+/// are directories when `--analyze-403` found that only their listing is forbidden; with
+/// `--slash-probe`, the statuses of the path with and without a trailing slash decide first
+///
+/// `requested` is the url that was requested; when redirects are followed and the response came
+/// from an unrelated location (i.e. a login page), the response isn't a directory
+fn response_is_directory(response: &FeroxResponse, requested: &Url) -> bool {
+    log::trace!("enter: is_directory({:?}, {})", response, requested);
+
+    match slash::probe_of(requested).map(|probe| probe.verdict()) {
+        Some(Verdict::Directory) => {
+            log::debug!("{} is directory according to its slash probe", requested);
+            log::trace!("exit: is_directory -> true");
+            return true;
+        }
+        Some(Verdict::NotDirectory) => {
+            log::debug!(
+                "{} isn't a directory according to its slash probe",
+                requested
+            );
+            log::trace!("exit: is_directory -> false");
+            return false;
+        }
+        Some(Verdict::Unknown) | None => {}
+    }
 
     if response.status().is_redirection() {
         // status code is 3xx
@@ -213,9 +525,9 @@ fn response_is_directory(response: &FeroxResponse) -> bool {
 
                 if let Ok(loc_str) = loc.to_str() {
                     if let Ok(abs_url) = response.url().join(loc_str) {
-                        if format!("{}/", response.url()) == abs_url.as_str() {
-                            // if current response's Url + / == the absolute redirection
-                            // location, we've found a directory suitable for recursion
+                        if is_directory_redirect(response.url(), &abs_url) {
+                            // if the redirection location is the current response's path + /,
+                            // we've found a directory suitable for recursion
                             log::debug!(
                                 "found directory suitable for recursion: {}",
                                 response.url()
@@ -223,6 +535,8 @@ fn response_is_directory(response: &FeroxResponse) -> bool {
                             log::trace!("exit: is_directory -> true");
                             return true;
                         }
+
+                        log::debug!("{} redirects elsewhere: {}", response.url(), abs_url);
                     }
                 }
             }
@@ -236,12 +550,32 @@ fn response_is_directory(response: &FeroxResponse) -> bool {
             }
         }
     } else if response.status().is_success() {
+        let redirected = response.url().host_str() != requested.host_str()
+            || response.url().path() != requested.path();
+
+        if redirected && !is_directory_redirect(requested, response.url()) {
+            // redirects were followed to some unrelated location
+            log::debug!("{} redirected elsewhere: {}", requested, response.url());
+            log::trace!("exit: is_directory -> false");
+            return false;
+        }
+
         // status code is 2xx, need to check if it ends in /
-        if response.url().as_str().ends_with('/') {
+        if response.url().path().ends_with('/') {
             log::debug!("{} is directory suitable for recursion", response.url());
             log::trace!("exit: is_directory -> true");
             return true;
         }
+    } else if *response.status() == StatusCode::FORBIDDEN
+        && forbidden::context_of(response.url()) == Some(Context::ListingForbidden)
+    {
+        // status code is 403, and --analyze-403 found a directory whose listing is forbidden
+        log::debug!(
+            "{} is forbidden directory suitable for recursion",
+            response.url()
+        );
+        log::trace!("exit: is_directory -> true");
+        return true;
     }
 
     log::trace!("exit: is_directory -> false");
@@ -281,18 +615,22 @@ fn reached_max_depth(url: &Url, base_depth: usize, max_depth: usize) -> bool {
 /// When a recursion opportunity is found, the new url is sent across the recursion channel
 async fn try_recursion(
     response: &FeroxResponse,
+    requested: &Url,
     base_depth: usize,
+    max_depth: usize,
     transmitter: UnboundedSender<String>,
 ) {
     log::trace!(
-        "enter: try_recursion({:?}, {}, {:?})",
+        "enter: try_recursion({:?}, {}, {}, {}, {:?})",
         response,
+        requested,
         base_depth,
+        max_depth,
         transmitter
     );
 
-    if !reached_max_depth(response.url(), base_depth, CONFIGURATION.depth)
-        && response_is_directory(&response)
+    if !reached_max_depth(response.url(), base_depth, max_depth)
+        && response_is_directory(response, requested)
     {
         if CONFIGURATION.redirects {
             // response is 2xx can simply send it because we're following redirects
@@ -311,7 +649,11 @@ async fn try_recursion(
                 }
             }
         } else {
-            let new_url = String::from(response.url().as_str());
+            // the directory is scanned without the redirect's query; any --query options are
+            // added back to each request
+            let mut directory = response.url().clone();
+            directory.set_query(None);
+            let new_url = String::from(directory.as_str());
 
             log::info!("Added new directory to recursive scan: {}", new_url);
 
@@ -332,50 +674,209 @@ async fn try_recursion(
 
 /// Simple helper to stay DRY; determines whether or not a given `FeroxResponse` should be reported
 /// to the user or not.
-pub fn should_filter_response(content_len: &u64, url: &Url) -> bool {
+pub fn should_filter_response(status: &StatusCode, content_len: &u64, url: &Url) -> bool {
+    filter_reason(status, content_len, url).is_some()
+}
+
+/// Determine why a given `FeroxResponse` shouldn't be reported to the user, if it shouldn't be
+///
+/// returns one of `size`, `static wildcard`, or `dynamic wildcard`
+pub fn filter_reason(status: &StatusCode, content_len: &u64, url: &Url) -> Option<&'static str> {
     if CONFIGURATION.sizefilters.contains(content_len) {
         // filtered value from --sizefilters, move on to the next url
         log::debug!("size filter: filtered out {}", url);
-        return true;
+        return Some("size");
+    }
+
+    if CONFIGURATION.dontfilter {
+        // quick return if dontfilter is set
+        return None;
     }
 
     match WILDCARD_FILTERS.read() {
         Ok(filters) => {
+            // I'm about to manually split this url path instead of using reqwest::Url's
+            // builtin parsing. The reason is that they call .split() on the url path
+            // except that I don't want an empty string taking up the last index in the
+            // event that the url ends with a forward slash.  It's ugly enough to be split
+            // into its own function for readability.
+            let url_len = get_url_path_length(url);
+
             for filter in filters.iter() {
-                if CONFIGURATION.dontfilter {
-                    // quick return if dontfilter is set
-                    return false;
+                if let Some(reason) = filter.matches(status.as_u16(), *content_len, url_len) {
+                    log::debug!("{}: filtered out {}", reason, url);
+                    return Some(reason);
                 }
+            }
+        }
+        Err(e) => {
+            log::error!("{}", e);
+        }
+    }
+    None
+}
 
-                if filter.size > 0 && filter.size == *content_len {
-                    // static wildcard size found during testing
-                    // size isn't default, size equals response length, and auto-filter is on
-                    log::debug!("static wildcard: filtered out {}", url);
-                    return true;
-                }
+/// Determine whether a given `FeroxResponse` is a soft 404, i.e. a page that reads like the
+/// target's not-found page; only checked with `--soft-404`. The words of a dynamic page that
+/// change from one request to the next aren't compared (see `--detect-dynamic`)
+///
+/// returns `soft 404` if it is
+pub fn soft_404_reason(response: &FeroxResponse) -> Option<&'static str> {
+    if !CONFIGURATION.soft_404 {
+        return None;
+    }
 
-                if filter.dynamic > 0 {
-                    // dynamic wildcard offset found during testing
+    if heuristics::reads_not_found(response.text()) {
+        log::debug!("not found title: filtered out {}", response.url());
+        return Some("soft 404");
+    }
 
-                    // I'm about to manually split this url path instead of using reqwest::Url's
-                    // builtin parsing. The reason is that they call .split() on the url path
-                    // except that I don't want an empty string taking up the last index in the
-                    // event that the url ends with a forward slash.  It's ugly enough to be split
-                    // into its own function for readability.
-                    let url_len = get_url_path_length(&url);
+    // words of a dynamic page that change from one request to the next are left out
+    let volatile = dynamic::volatility_of(response.url())
+        .map(|volatility| volatility.words)
+        .unwrap_or_default();
 
-                    if url_len + filter.dynamic == *content_len {
-                        log::debug!("dynamic wildcard: filtered out {}", url);
-                        return true;
-                    }
-                }
+    match SOFT_404_FILTERS.read() {
+        Ok(filters) => {
+            if filters
+                .iter()
+                .any(|filter| filter.matches_except(response.text(), response.url(), &volatile))
+            {
+                log::debug!("soft 404: filtered out {}", response.url());
+                return Some("soft 404");
             }
         }
         Err(e) => {
             log::error!("{}", e);
         }
     }
-    false
+    None
+}
+
+/// Determine whether the body of a given `FeroxResponse` has one of the hashes given with
+/// `--filter-hash`; bodies are hashed as the text they're decoded to, which is the same as the
+/// bytes received for any utf-8 body
+///
+/// returns `hash` if it does
+pub fn hash_filter_reason(response: &FeroxResponse) -> Option<&'static str> {
+    if CONFIGURATION.filter_hashes.is_empty() {
+        return None;
+    }
+
+    if digest::matches_any(response.text().as_bytes(), &CONFIGURATION.filter_hashes) {
+        log::debug!("body hash: filtered out {}", response.url());
+        return Some("hash");
+    }
+
+    None
+}
+
+/// Determine whether a given `FeroxResponse` followed more redirects than allowed by
+/// `--filter-redirects`, or stopped at a redirect loop with `--filter-redirect-loops`
+///
+/// returns `redirect loop` or `redirects` if it did
+pub fn redirect_filter_reason(response: &FeroxResponse) -> Option<&'static str> {
+    let chain = response.redirects()?;
+
+    if CONFIGURATION.filter_redirect_loops && chain.looped {
+        log::debug!("redirect loop: filtered out {}", chain.origin());
+        return Some("redirect loop");
+    }
+
+    if CONFIGURATION.filter_redirects > 0 && chain.hops() > CONFIGURATION.filter_redirects {
+        log::debug!(
+            "{} redirects: filtered out {}",
+            chain.hops(),
+            chain.origin()
+        );
+        return Some("redirects");
+    }
+
+    None
+}
+
+/// Whether or not the body of the given response needs to be read; on top of the options that
+/// make use of bodies (see `Configuration::needs_body`), directories are read so that open
+/// directory listings can be flagged, 403/429s are read to look for CAPTCHA pages, and responses
+/// that may be reported are read to hash their bodies with `--body-hash` or `--filter-hash`, to
+/// compare them with their Content-Length with `--check-length`, or to compare them with a second
+/// request with `--detect-dynamic`, as are favicons
+fn needs_body(response: &Response) -> bool {
+    let status = response.status();
+
+    CONFIGURATION.needs_body()
+        || response.url().path().ends_with('/')
+        || fingerprint::is_favicon(response.url())
+        || (CONFIGURATION.ban_policy != "off" && ban::is_block_status(status))
+        || ((!CONFIGURATION.body_hash.is_empty()
+            || !CONFIGURATION.filter_hashes.is_empty()
+            || CONFIGURATION.check_length
+            || CONFIGURATION.detect_dynamic)
+            && CONFIGURATION.statuscodes.contains(&status.as_u16()))
+        || (!CONFIGURATION.oauth_expired_regex.is_empty() && status == StatusCode::UNAUTHORIZED)
+}
+
+/// Request a single entry of an open directory listing (see `--use-listings`) and report it;
+/// entries that are directories are scanned recursively
+async fn request_listing_entry(
+    url: &Url,
+    base_depth: usize,
+    options: &ScanOptions,
+    dir_chan: UnboundedSender<String>,
+    report_chan: UnboundedSender<FeroxResponse>,
+) {
+    log::trace!(
+        "enter: request_listing_entry({}, {}, {:?}, {:?}, {:?})",
+        url,
+        base_depth,
+        options,
+        dir_chan,
+        report_chan
+    );
+
+    if !robots::allows(url) {
+        log::debug!("Skipping {}, disallowed by robots.txt", url);
+        log::trace!("exit: request_listing_entry");
+        return;
+    }
+
+    let response = match options.request(url).await {
+        Ok((response, chain, rejected)) => {
+            report_rejected(rejected, None, report_chan.clone()).await;
+
+            let read_body = needs_body(&response);
+            let mut response = FeroxResponse::from(response, read_body).await;
+            response.set_redirects(chain);
+
+            if CONFIGURATION.detect_dynamic {
+                dynamic::check_response(&response, options).await;
+            }
+
+            response
+        }
+        Err(e) => {
+            ban::record(ban::Outcome::from_error(e.as_ref()));
+            log_request_error(url, None, e.as_ref());
+            log::trace!("exit: request_listing_entry");
+            return;
+        }
+    };
+
+    ban::record(ban::Outcome::from_response(&response));
+
+    if !CONFIGURATION.norecursion {
+        try_recursion(&response, url, base_depth, options.depth, dir_chan).await;
+    }
+
+    if let Some(reason) = hooks::veto(&response) {
+        STATISTICS.add_filtered();
+        log_filtered(response.url(), None, response.content_length(), &reason);
+        log::trace!("exit: request_listing_entry");
+        return;
+    }
+
+    send_report(report_chan, response);
+    log::trace!("exit: request_listing_entry");
 }
 
 /// Wrapper for [make_request](fn.make_request.html)
@@ -384,122 +885,266 @@ pub fn should_filter_response(content_len: &u64, url: &Url) -> bool {
 ///
 /// Attempts recursion when appropriate and sends Responses to the report handler for processing
 async fn make_requests(
-    target_url: &str,
+    builder: &UrlBuilder,
     word: &str,
     base_depth: usize,
+    options: &ScanOptions,
     dir_chan: UnboundedSender<String>,
     report_chan: UnboundedSender<FeroxResponse>,
 ) {
     log::trace!(
-        "enter: make_requests({}, {}, {}, {:?}, {:?})",
-        target_url,
+        "enter: make_requests({:?}, {}, {}, {:?}, {:?}, {:?})",
+        builder,
         word,
         base_depth,
+        options,
         dir_chan,
         report_chan
     );
 
-    let urls = create_urls(&target_url, &word, &CONFIGURATION.extensions);
+    let urls = create_urls(builder, word, &options.extensions);
 
     for url in urls {
-        if let Ok(response) = make_request(&CONFIGURATION.client, &url).await {
-            // response came back without error, convert it to FeroxResponse
-            let ferox_response = FeroxResponse::from(response, CONFIGURATION.extract_links).await;
+        if !robots::allows(&url) {
+            log::debug!("Skipping {}, disallowed by robots.txt", url);
+            continue;
+        }
 
-            // do recursion if appropriate
-            if !CONFIGURATION.norecursion {
-                try_recursion(&ferox_response, base_depth, dir_chan.clone()).await;
+        if import::is_known(&url) {
+            // requested during the imported session already (see --import); a known directory is
+            // still scanned, it just isn't requested again
+            log::debug!("Skipping {}, known from the imported session", url);
+
+            if let Some(directory) = import::known_directory(&url) {
+                if !CONFIGURATION.norecursion
+                    && !reached_max_depth(&directory, base_depth, options.depth)
+                {
+                    log::info!("Added known directory to recursive scan: {}", directory);
+                    dir_chan.send(directory.to_string()).unwrap_or_default();
+                }
             }
 
-            // purposefully doing recursion before filtering. the thought process is that
-            // even though this particular url is filtered, subsequent urls may not
-
-            let content_len = &ferox_response.content_length();
+            continue;
+        }
 
-            if should_filter_response(content_len, &ferox_response.url()) {
+        let (response, chain, rejected) = match options.request(&url).await {
+            Ok(response) => response,
+            Err(e) => {
+                ban::record(ban::Outcome::from_error(e.as_ref()));
+                log_request_error(&url, Some(word), e.as_ref());
+                events::emit(Event::Error {
+                    url: url.clone(),
+                    error: e.to_string(),
+                });
                 continue;
             }
+        };
 
-            if CONFIGURATION.extract_links && !ferox_response.status().is_redirection() {
-                let new_links = get_links(&ferox_response).await;
+        report_rejected(rejected, Some(word), report_chan.clone()).await;
 
-                for new_link in new_links {
-                    let unknown = add_url_to_list_of_scanned_urls(&new_link, &SCANNED_URLS);
+        // response came back without error, convert it to FeroxResponse
+        let read_body = needs_body(&response);
+        let mut ferox_response = FeroxResponse::from(response, read_body).await;
+        ferox_response.set_redirects(chain);
 
-                    if !unknown {
-                        // not unknown, i.e. we've seen the url before and don't need to scan again
-                        continue;
-                    }
+        if CONFIGURATION.detect_dynamic {
+            // the volatility found is used when filtering and reporting
+            dynamic::check_response(&ferox_response, options).await;
+        }
 
-                    // create a url based on the given command line options, continue on error
-                    let new_url = match format_url(
-                        &new_link,
-                        &"",
-                        CONFIGURATION.addslash,
-                        &CONFIGURATION.queries,
-                        None,
-                    ) {
-                        Ok(url) => url,
-                        Err(_) => continue,
-                    };
+        events::emit(Event::ResponseReceived {
+            url: ferox_response.url().clone(),
+            status: *ferox_response.status(),
+            content_length: ferox_response.content_length(),
+        });
 
-                    // make the request and store the response
-                    let new_response = match make_request(&CONFIGURATION.client, &new_url).await {
-                        Ok(resp) => resp,
-                        Err(_) => continue,
-                    };
+        ban::record(ban::Outcome::from_response(&ferox_response));
 
-                    let mut new_ferox_response =
-                        FeroxResponse::from(new_response, CONFIGURATION.extract_links).await;
+        if CONFIGURATION.analyze_403 {
+            // the context found is used when reporting and deciding recursion
+            forbidden::analyze_response(&ferox_response, options).await;
+        }
 
-                    // filter if necessary
-                    let new_content_len = &new_ferox_response.content_length();
-                    if should_filter_response(new_content_len, &new_ferox_response.url()) {
-                        continue;
-                    }
+        if CONFIGURATION.slash_probe {
+            // the statuses found are used when reporting and deciding recursion
+            slash::probe_response(&ferox_response, &url, options).await;
+        }
 
-                    if new_ferox_response.is_file() {
-                        // very likely a file, simply request and report
-                        log::debug!(
-                            "Singular extraction: {} ({})",
-                            new_ferox_response.url(),
-                            new_ferox_response.status().as_str(),
-                        );
+        // do recursion if appropriate
+        if !CONFIGURATION.norecursion {
+            try_recursion(
+                &ferox_response,
+                &url,
+                base_depth,
+                options.depth,
+                dir_chan.clone(),
+            )
+            .await;
+        }
 
-                        send_report(report_chan.clone(), new_ferox_response);
+        // purposefully doing recursion before filtering. the thought process is that
+        // even though this particular url is filtered, subsequent urls may not
+
+        if let Some(reason) = hooks::veto(&ferox_response) {
+            STATISTICS.add_filtered();
+            log_filtered(
+                ferox_response.url(),
+                Some(word),
+                ferox_response.content_length(),
+                &reason,
+            );
+            continue;
+        }
+
+        if CONFIGURATION.extract_links && !ferox_response.status().is_redirection() {
+            let new_links = get_links(&ferox_response).await;
+
+            for new_link in new_links {
+                let unknown = add_link_to_requested_links(
+                    &new_link,
+                    &SCANNED_URLS,
+                    &REPORTED_LINKS,
+                    &REQUESTED_LINKS,
+                );
+
+                if !unknown {
+                    // not unknown, i.e. we've seen the url before and don't need to scan again
+                    continue;
+                }
 
+                // create a url based on the given command line options, continue on error
+                let new_url = match format_url(
+                    &new_link,
+                    "",
+                    CONFIGURATION.addslash,
+                    &CONFIGURATION.queries,
+                    None,
+                ) {
+                    Ok(url) => url,
+                    Err(_) => continue,
+                };
+
+                if import::is_known(&new_url) || !robots::allows(&new_url) {
+                    continue;
+                }
+
+                // make the request and store the response
+                let (new_response, chain, rejected) = match options.request(&new_url).await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        ban::record(ban::Outcome::from_error(e.as_ref()));
+                        log_request_error(&new_url, None, e.as_ref());
                         continue;
                     }
+                };
+
+                let read_body = needs_body(&new_response);
+                let mut new_ferox_response = FeroxResponse::from(new_response, read_body).await;
+                new_ferox_response.set_redirects(chain);
+
+                if CONFIGURATION.detect_dynamic {
+                    dynamic::check_response(&new_ferox_response, options).await;
+                }
+
+                ban::record(ban::Outcome::from_response(&new_ferox_response));
+
+                // filter if necessary
+                if let Some(reason) = hooks::veto(&new_ferox_response) {
+                    STATISTICS.add_filtered();
+                    log_filtered(
+                        new_ferox_response.url(),
+                        None,
+                        new_ferox_response.content_length(),
+                        &reason,
+                    );
+                    continue;
+                }
 
-                    if !CONFIGURATION.norecursion {
-                        log::debug!(
-                            "Recursive extraction: {} ({})",
-                            new_ferox_response.url(),
-                            new_ferox_response.status().as_str()
-                        );
-
-                        if new_ferox_response.status().is_success()
-                            && !new_ferox_response.url().as_str().ends_with('/')
-                        {
-                            // since all of these are 2xx, recursion is only attempted if the
-                            // url ends in a /. I am actually ok with adding the slash and not
-                            // adding it, as both have merit.  Leaving it in for now to see how
-                            // things turn out (current as of: v1.1.0)
-                            new_ferox_response.set_url(&format!("{}/", new_ferox_response.url()));
+                if new_ferox_response.is_file() {
+                    // very likely a file, simply request and report
+                    log::debug!(
+                        "Singular extraction: {} ({})",
+                        new_ferox_response.url(),
+                        new_ferox_response.status().as_str(),
+                    );
+
+                    // only the first of two tasks that requested the same link reports it
+                    let first = match REPORTED_LINKS.write() {
+                        Ok(mut links) => links.insert(normalize_link(&new_link)),
+                        Err(e) => {
+                            log::error!("Set of reported links poisoned: {}", e);
+                            true
                         }
+                    };
 
-                        try_recursion(&new_ferox_response, base_depth, dir_chan.clone()).await;
+                    if first {
+                        report_rejected(rejected, None, report_chan.clone()).await;
+                        send_report(report_chan.clone(), new_ferox_response);
                     }
+
+                    continue;
                 }
-            }
 
-            // everything else should be reported
-            send_report(report_chan.clone(), ferox_response);
+                if !CONFIGURATION.norecursion {
+                    log::debug!(
+                        "Recursive extraction: {} ({})",
+                        new_ferox_response.url(),
+                        new_ferox_response.status().as_str()
+                    );
+
+                    if new_ferox_response.status().is_success()
+                        && !new_ferox_response.url().as_str().ends_with('/')
+                    {
+                        // since all of these are 2xx, recursion is only attempted if the
+                        // url ends in a /. I am actually ok with adding the slash and not
+                        // adding it, as both have merit.  Leaving it in for now to see how
+                        // things turn out (current as of: v1.1.0)
+                        new_ferox_response.set_url(&format!("{}/", new_ferox_response.url()));
+                    }
+
+                    // extracted links aren't scanned again by recursion
+                    add_url_to_list_of_scanned_urls(&new_link, &SCANNED_URLS);
+
+                    try_recursion(
+                        &new_ferox_response,
+                        &new_url,
+                        base_depth,
+                        options.depth,
+                        dir_chan.clone(),
+                    )
+                    .await;
+                }
+            }
         }
+
+        // everything else should be reported
+        send_report(report_chan.clone(), ferox_response);
     }
     log::trace!("exit: make_requests");
 }
 
+/// Report the response that rejected the configured method of a request whose fallback method
+/// was accepted (see `ScanOptions::request`), unless it's filtered like any other response; the
+/// rejection is as much a finding as the fallback's response
+async fn report_rejected(
+    rejected: Option<Response>,
+    word: Option<&str>,
+    report_chan: UnboundedSender<FeroxResponse>,
+) {
+    let rejected = match rejected {
+        Some(rejected) => FeroxResponse::from(rejected, false).await,
+        None => return,
+    };
+
+    if let Some(reason) = hooks::veto(&rejected) {
+        STATISTICS.add_filtered();
+        log_filtered(rejected.url(), word, rejected.content_length(), &reason);
+        return;
+    }
+
+    send_report(report_chan, rejected);
+}
+
 /// Simple helper to send a `FeroxResponse` over the tx side of an `mpsc::unbounded_channel`
 fn send_report(report_sender: UnboundedSender<FeroxResponse>, response: FeroxResponse) {
     log::trace!("enter: send_report({:?}, {:?}", report_sender, response);
@@ -514,39 +1159,53 @@ fn send_report(report_sender: UnboundedSender<FeroxResponse>, response: FeroxRes
     log::trace!("exit: send_report");
 }
 
-/// Scan a given url using a given wordlist
+/// Scan a given url using a given wordlist and options
 ///
 /// This is the primary entrypoint for the scanner
 pub async fn scan_url(
     target_url: &str,
-    wordlist: Arc<HashSet<String>>,
+    wordlist: Arc<Wordlist>,
     base_depth: usize,
+    options: Arc<ScanOptions>,
     tx_term: UnboundedSender<FeroxResponse>,
     tx_file: UnboundedSender<String>,
 ) {
     log::trace!(
-        "enter: scan_url({:?}, wordlist[{} words...], {}, {:?}, {:?})",
+        "enter: scan_url({:?}, wordlist[{} words...], {}, {:?}, {:?}, {:?})",
         target_url,
         wordlist.len(),
         base_depth,
+        options,
         tx_term,
         tx_file
     );
 
     log::info!("Starting scan against: {}", target_url);
 
+    STATISTICS.scan_started(target_url);
+
     let (tx_dir, rx_dir): FeroxChannel<String> = mpsc::unbounded_channel();
 
-    let num_reqs_expected: u64 = if CONFIGURATION.extensions.is_empty() {
-        wordlist.len().try_into().unwrap()
-    } else {
-        let total = wordlist.len() * (CONFIGURATION.extensions.len() + 1);
-        total.try_into().unwrap()
-    };
+    let num_reqs_expected = progress::requests_per_directory(
+        wordlist.len(),
+        options.extensions.len(),
+        CONFIGURATION.dontfilter,
+    );
+
+    events::emit(Event::ScanStarted {
+        url: target_url.to_string(),
+        requests: num_reqs_expected,
+    });
 
-    let progress_bar = progress::add_bar(&target_url, num_reqs_expected, false);
+    // requests made per word; one for the word itself plus one per extension
+    let num_reqs_per_word: u64 = (options.extensions.len() + 1).try_into().unwrap();
+
+    let progress_bar = progress::add_bar(target_url, num_reqs_expected, false);
     progress_bar.reset_elapsed();
 
+    // every scan, including those started by recursion, adds its requests to the total bar
+    progress::inc_total_length(num_reqs_expected);
+
     if CALL_COUNT.load(Ordering::Relaxed) == 0 {
         // join can only be called once, otherwise it causes the thread to panic
         tokio::task::spawn_blocking(move || PROGRESS_BAR.join().unwrap());
@@ -554,7 +1213,7 @@ pub async fn scan_url(
 
         // this protection around join also allows us to add the first scanned url to SCANNED_URLS
         // from within the scan_url function instead of the recursion handler
-        add_url_to_list_of_scanned_urls(&target_url, &SCANNED_URLS);
+        add_url_to_list_of_scanned_urls(target_url, &SCANNED_URLS);
     }
 
     // Arc clones to be passed around to the various scans
@@ -563,43 +1222,173 @@ pub async fn scan_url(
     let recurser_term_clone = tx_term.clone();
     let recurser_file_clone = tx_file.clone();
     let recurser_words = wordlist.clone();
-    let looping_words = wordlist.clone();
+    let recurser_options = options.clone();
 
     let recurser = tokio::spawn(async move {
         spawn_recursion_handler(
             rx_dir,
             recurser_words,
             base_depth,
+            recurser_options,
             recurser_term_clone,
             recurser_file_clone,
         )
         .await
     });
 
-    let filter =
-        match heuristics::wildcard_test(&target_url, wildcard_bar, heuristics_file_clone).await {
+    if !CONFIGURATION.norecursion {
+        // directories the runs resumed with --resume-from found under this one are scanned again,
+        // for the words they weren't requested with
+        for directory in state::resumed_directories(target_url) {
+            match Url::parse(&directory) {
+                Ok(url) if !reached_max_depth(&url, base_depth, options.depth) => {
+                    tx_dir.send(directory).unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    robots::load(target_url, &options.client, tx_file.clone()).await;
+
+    // with --respect-robots, a directory robots.txt disallows isn't requested at all
+    let allowed = robots::allows_directory(target_url);
+
+    let listing = if CONFIGURATION.use_listings && allowed {
+        heuristics::listing_test(
+            target_url,
+            &options.client,
+            progress_bar.clone(),
+            tx_file.clone(),
+        )
+        .await
+    } else {
+        None
+    };
+
+    let filter = if listing.is_some() || !allowed {
+        // the listing's entries exist, no need to tell them apart from wildcard responses
+        Arc::new(WildcardFilter::default())
+    } else {
+        match heuristics::wildcard_test(
+            target_url,
+            &options.client,
+            wildcard_bar,
+            heuristics_file_clone,
+        )
+        .await
+        {
             Some(f) => Arc::new(f),
             None => Arc::new(WildcardFilter::default()),
-        };
+        }
+    };
+
+    if filter.echoes {
+        // every word would look like a directory, none of them are scanned recursively
+        traps::add_echoing(target_url);
+    }
 
-    add_filter_to_list_of_wildcard_filters(filter.clone(), WILDCARD_FILTERS.clone());
+    let words = if let Some(entries) = listing {
+        // the listing names everything in the directory, so its entries are requested instead
+        // of the words; the requests expected for the words are counted as done and the bar
+        // grows by the number of entries
+        let remaining = progress_bar.length() - progress_bar.position();
+        progress::inc(&progress_bar, remaining);
+
+        let num_entries = entries.len().try_into().unwrap_or(u64::MAX);
+        progress_bar.inc_length(num_entries);
+        progress::inc_total_length(num_entries);
+
+        stream::iter(entries)
+            .for_each_concurrent(CONFIGURATION.threads, |entry| {
+                let txd = tx_dir.clone();
+                let txr = tx_term.clone();
+                let bar = progress_bar.clone();
+                let opts = options.clone();
+
+                async move {
+                    request_listing_entry(&entry, base_depth, &opts, txd, txr).await;
+                    progress::inc(&bar, 1);
+                }
+            })
+            .await;
+
+        None
+    } else if !allowed || filter.unfilterable {
+        // every response would look like a wildcard response (or robots.txt disallows the
+        // directory), so no words are requested; the directory's requests are counted as done
+        let remaining = progress_bar.length() - progress_bar.position();
+        progress::inc(&progress_bar, remaining);
+        None
+    } else {
+        add_filter_to_list_of_wildcard_filters(filter.clone(), WILDCARD_FILTERS.clone());
+
+        if CONFIGURATION.soft_404 {
+            if let Some(soft_404) = heuristics::soft_404_test(
+                target_url,
+                &options.client,
+                progress_bar.clone(),
+                tx_file.clone(),
+            )
+            .await
+            {
+                add_filter_to_list_of_wildcard_filters(
+                    Arc::new(soft_404),
+                    SOFT_404_FILTERS.clone(),
+                );
+            }
+        }
+
+        // streamed from wherever the words are kept, every scan reads them on its own
+        Some(wordlist.iter())
+    };
+
+    // the directory's url is parsed once, instead of once per request
+    let builder = Arc::new(UrlBuilder::new(
+        target_url,
+        CONFIGURATION.addslash,
+        &CONFIGURATION.queries,
+    ));
+
+    // words already requested in this directory by a resumed run (see --resume-from) are counted
+    // as done instead
+    let mut skipped = 0;
+    let words = words.into_iter().flatten().filter(|word| {
+        let requested = state::was_requested(target_url, word);
+
+        if requested {
+            skipped += 1;
+            progress::inc(&progress_bar, num_reqs_per_word);
+        }
+
+        !requested
+    });
+
+    // a cancelled scan requests none of the words it has left (see `cancel`)
+    let words = words.take_while(|_| !is_cancelled(target_url));
 
     // producer tasks (mp of mpsc); responsible for making requests
-    let producers = stream::iter(looping_words.deref().to_owned())
+    let producers = stream::iter(words)
         .map(|word| {
             let txd = tx_dir.clone();
             let txr = tx_term.clone();
             let pb = progress_bar.clone(); // progress bar is an Arc around internal state
-            let tgt = target_url.to_string(); // done to satisfy 'static lifetime below
+            let builder = builder.clone(); // done to satisfy 'static lifetime below
+            let opts = options.clone();
             (
-                tokio::spawn(async move { make_requests(&tgt, &word, base_depth, txd, txr).await }),
+                tokio::spawn(async move {
+                    make_requests(&builder, &word, base_depth, &opts, txd, txr).await;
+                    word
+                }),
                 pb,
             )
         })
         .for_each_concurrent(CONFIGURATION.threads, |(resp, bar)| async move {
             match resp.await {
-                Ok(_) => {
-                    bar.inc(1);
+                Ok(word) => {
+                    // --state-file used, the word isn't requested here again when resumed
+                    state::word_requested(target_url, &word);
+                    progress::inc(&bar, num_reqs_per_word);
                 }
                 Err(e) => {
                     log::error!("error awaiting a response: {}", e);
@@ -612,7 +1401,24 @@ pub async fn scan_url(
     producers.await;
     log::trace!("done awaiting scan producers");
 
-    progress_bar.finish();
+    if skipped > 0 {
+        log::info!(
+            "Skipped {} words already requested in {} by a resumed run",
+            skipped,
+            target_url
+        );
+    }
+
+    // only active scans keep a bar around; the total bar tracks overall progress
+    progress_bar.finish_and_clear();
+    STATISTICS.scan_completed(target_url);
+
+    // a finished directory is the largest unit of progress, saved right away
+    state::scan_completed(target_url);
+
+    events::emit(Event::ScanFinished {
+        url: target_url.to_string(),
+    });
 
     // manually drop tx in order for the rx task's while loops to eval to false
     log::trace!("dropped recursion handler's transmitter");
@@ -627,20 +1433,29 @@ pub async fn scan_url(
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 mod tests {
     use super::*;
 
     #[test]
     /// sending url + word without any extensions should get back one url with the joined word
     fn create_urls_no_extension_returns_base_url_with_word() {
-        let urls = create_urls("http://localhost", "turbo", &[]);
+        let urls = create_urls(
+            &UrlBuilder::new("http://localhost", false, &[]),
+            "turbo",
+            &[],
+        );
         assert_eq!(urls, [Url::parse("http://localhost/turbo").unwrap()])
     }
 
     #[test]
     /// sending url + word + 1 extension should get back two urls, one base and one with extension
     fn create_urls_one_extension_returns_two_urls() {
-        let urls = create_urls("http://localhost", "turbo", &[String::from("js")]);
+        let urls = create_urls(
+            &UrlBuilder::new("http://localhost", false, &[]),
+            "turbo",
+            &[String::from("js")],
+        );
         assert_eq!(
             urls,
             [
@@ -679,7 +1494,11 @@ mod tests {
         ];
 
         for (i, ext_set) in ext_vec.into_iter().enumerate() {
-            let urls = create_urls("http://localhost", "turbo", &ext_set);
+            let urls = create_urls(
+                &UrlBuilder::new("http://localhost", false, &[]),
+                "turbo",
+                &ext_set,
+            );
             assert_eq!(urls, expected[i]);
         }
     }
@@ -759,6 +1578,51 @@ mod tests {
         assert_eq!(add_url_to_list_of_scanned_urls(url, &urls), false);
     }
 
+    #[test]
+    /// links are requested once, and never when they're urls that were already scanned
+    fn add_link_to_requested_links_skips_known_links() {
+        let urls = RwLock::new(HashSet::<String>::new());
+        let reported = RwLock::new(HashSet::<String>::new());
+        let links = RwLock::new(BloomFilter::default());
+
+        assert!(urls
+            .write()
+            .unwrap()
+            .insert(String::from("http://localhost/js/")));
+
+        assert!(!add_link_to_requested_links(
+            "http://localhost/js",
+            &urls,
+            &reported,
+            &links
+        ));
+        assert!(add_link_to_requested_links(
+            "http://localhost/js/app.js",
+            &urls,
+            &reported,
+            &links
+        ));
+        assert!(!add_link_to_requested_links(
+            "http://localhost/js/app.js/",
+            &urls,
+            &reported,
+            &links
+        ));
+
+        // reported links are known exactly, whatever the bloom filter says
+        assert!(reported
+            .write()
+            .unwrap()
+            .insert(String::from("http://localhost/css/site.css/")));
+
+        assert!(!add_link_to_requested_links(
+            "http://localhost/css/site.css",
+            &urls,
+            &reported,
+            &links
+        ));
+    }
+
     #[test]
     /// add a wildcard filter with the `size` attribute set to WILDCARD_FILTERS and ensure that
     /// should_filter_response correctly returns true
@@ -768,7 +1632,7 @@ mod tests {
         filter.size = 18;
         let filter = Arc::new(filter);
         add_filter_to_list_of_wildcard_filters(filter, WILDCARD_FILTERS.clone());
-        let result = should_filter_response(&18, &url);
+        let result = should_filter_response(&StatusCode::OK, &18, &url);
         assert!(result);
     }
 
@@ -781,7 +1645,25 @@ mod tests {
         filter.dynamic = 9;
         let filter = Arc::new(filter);
         add_filter_to_list_of_wildcard_filters(filter, WILDCARD_FILTERS.clone());
-        let result = should_filter_response(&18, &url);
+        let result = should_filter_response(&StatusCode::OK, &18, &url);
         assert!(result);
     }
+
+    #[test]
+    /// only redirects to the same path + / on the same host are directory redirects; the
+    /// redirect's scheme and query don't matter
+    fn is_directory_redirect_requires_same_path_plus_slash() {
+        let url = Url::parse("http://localhost/js?a=b").unwrap();
+        let redirect = |location: &str| is_directory_redirect(&url, &url.join(location).unwrap());
+
+        assert!(redirect("/js/"));
+        assert!(redirect("js/?a=b"));
+        assert!(redirect("https://localhost/js/"));
+
+        assert!(!redirect("/login"));
+        assert!(!redirect("/login/"));
+        assert!(!redirect("/"));
+        assert!(!redirect("/js"));
+        assert!(!redirect("http://example.com/js/"));
+    }
 }
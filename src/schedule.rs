@@ -0,0 +1,494 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::utils::{ferox_print, module_colorizer, status_colorizer};
+use console::style;
+use std::fmt;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// seconds in a day
+const DAY: u32 = 24 * 60 * 60;
+
+/// how often the clock is checked against the schedule, in milliseconds
+const CHECK_MS: u64 = 1000;
+
+/// how often paused requests check whether they may be made, in milliseconds
+const PAUSE_CHECK_MS: u64 = 500;
+
+/// Whether or not requests are paused, waiting for the scan to start or for an allowed window
+/// (see `--start-at` and `--pause-outside`)
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// value of `PROFILE_RATE` while no rate profile is in effect
+const NO_PROFILE: usize = usize::MAX;
+
+/// Requests per second of the rate profile in effect, `NO_PROFILE` while none is (see
+/// `--rate-profile`)
+static PROFILE_RATE: AtomicUsize = AtomicUsize::new(NO_PROFILE);
+
+/// Parse a time of day given as `HH:MM` into seconds since midnight
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(hours * 3600 + minutes * 60)
+}
+
+/// Hours of the day in which requests may be made, in local time, i.e. `22:00-06:00`; a window
+/// that ends before it starts runs past midnight
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Window {
+    /// seconds since midnight at which the window opens
+    start: u32,
+
+    /// seconds since midnight at which the window closes
+    end: u32,
+}
+
+impl Window {
+    /// Parse a window given as `HH:MM-HH:MM`
+    pub fn parse(window: &str) -> Option<Self> {
+        let (start, end) = window.split_once('-')?;
+
+        Some(Self {
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        })
+    }
+
+    /// Whether or not the window is open at the given number of seconds since midnight; a window
+    /// that closes when it opens is always open
+    pub fn contains(&self, time_of_day: u32) -> bool {
+        match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Less => self.start <= time_of_day && time_of_day < self.end,
+            std::cmp::Ordering::Greater => time_of_day >= self.start || time_of_day < self.end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+/// windows are shown the way they're given, i.e. `22:00-06:00`
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 3600,
+            self.start % 3600 / 60,
+            self.end / 3600,
+            self.end % 3600 / 60
+        )
+    }
+}
+
+/// Rate limit used instead of `--rate-limit` while its window is open, i.e. 500 requests per
+/// second overnight
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RateProfile {
+    /// hours of the day in which the profile is in effect
+    window: Window,
+
+    /// requests per second per target while in effect, 0 for unlimited
+    per_second: usize,
+}
+
+impl RateProfile {
+    /// Parse a profile given as `HH:MM-HH:MM=REQUESTS`
+    pub fn parse(profile: &str) -> Option<Self> {
+        let (window, per_second) = profile.split_once('=')?;
+
+        Some(Self {
+            window: Window::parse(window)?,
+            per_second: per_second.trim().parse().ok()?,
+        })
+    }
+}
+
+/// Description of a rate limit of the given number of requests per second, 0 being unlimited
+fn describe_rate(per_second: usize) -> String {
+    match per_second {
+        0 => String::from("no rate limit"),
+        _ => format!("{} requests/second per target", per_second),
+    }
+}
+
+/// When requests may be made: not before the scan's start, and only within its windows, if any;
+/// along with the rate profiles changing how many may be made as the day goes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// when the scan starts, `None` to start right away
+    start: Option<SystemTime>,
+
+    /// windows in which requests may be made, empty to allow them at any time
+    windows: Vec<Window>,
+
+    /// rate limits used instead of `--rate-limit` within their windows, the first open one applies
+    profiles: Vec<RateProfile>,
+}
+
+impl Schedule {
+    /// Schedule starting at the next occurrence of `start_at` (`HH:MM`, empty to start right away)
+    /// and only allowing requests within the given windows (`HH:MM-HH:MM`); `now` is the current
+    /// time, `time_of_day` the number of seconds since midnight it stands for in local time
+    pub fn new(
+        start_at: &str,
+        windows: &[String],
+        now: SystemTime,
+        time_of_day: u32,
+    ) -> Result<Self, String> {
+        let start = if start_at.is_empty() {
+            None
+        } else {
+            let at = parse_time(start_at)
+                .ok_or_else(|| format!("Could not parse start time {} (HH:MM)", start_at))?;
+            // counted from the start of the current minute, so that it starts right away
+            // during the minute it's given for
+            let seconds = time_of_day % 60;
+            let wait = ((at + DAY - (time_of_day - seconds)) % DAY).saturating_sub(seconds);
+            Some(now + Duration::from_secs(wait.into()))
+        };
+
+        let windows = windows
+            .iter()
+            .map(|window| {
+                Window::parse(window)
+                    .ok_or_else(|| format!("Could not parse window {} (HH:MM-HH:MM)", window))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            start,
+            windows,
+            profiles: Vec::new(),
+        })
+    }
+
+    /// Add the given rate profiles (`HH:MM-HH:MM=REQUESTS`) to the schedule
+    pub fn with_profiles(mut self, profiles: &[String]) -> Result<Self, String> {
+        self.profiles = profiles
+            .iter()
+            .map(|profile| {
+                RateProfile::parse(profile).ok_or_else(|| {
+                    format!(
+                        "Could not parse rate profile {} (HH:MM-HH:MM=REQUESTS)",
+                        profile
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(self)
+    }
+
+    /// Rate profile in effect at the given number of seconds since midnight in local time, if any;
+    /// the first one whose window is open
+    pub fn profile_at(&self, time_of_day: u32) -> Option<&RateProfile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.window.contains(time_of_day))
+    }
+
+    /// Whether or not requests may be made at the given time, standing for the given number of
+    /// seconds since midnight in local time
+    pub fn allows(&self, now: SystemTime, time_of_day: u32) -> bool {
+        let started = self.start.is_none_or(|start| now >= start);
+        let in_window = self.windows.is_empty()
+            || self
+                .windows
+                .iter()
+                .any(|window| window.contains(time_of_day));
+
+        started && in_window
+    }
+}
+
+/// Number of seconds since midnight, in local time, at the given time; falls back to UTC where
+/// the local time zone can't be determined
+fn local_time_of_day(time: SystemTime) -> u32 {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    #[cfg(unix)]
+    {
+        let timestamp = secs as libc::time_t;
+
+        // safe: both pointers are valid for the duration of the call, and localtime_r doesn't
+        // keep either of them around
+        let mut local: libc::tm = unsafe { std::mem::zeroed() };
+        let converted = unsafe { libc::localtime_r(&timestamp, &mut local) };
+
+        if !converted.is_null() {
+            return (local.tm_hour * 3600 + local.tm_min * 60 + local.tm_sec) as u32;
+        }
+    }
+
+    (secs % u64::from(DAY)) as u32
+}
+
+/// Wait until requests may be made again, when they're paused (see `--start-at` and
+/// `--pause-outside`)
+pub async fn wait() {
+    while PAUSED.load(Ordering::Relaxed) {
+        tokio::time::delay_for(Duration::from_millis(PAUSE_CHECK_MS)).await;
+    }
+}
+
+/// Requests per second of the rate profile in effect, if any (see `--rate-profile`); 0 is
+/// unlimited
+pub fn profile_rate() -> Option<usize> {
+    match PROFILE_RATE.load(Ordering::Relaxed) {
+        NO_PROFILE => None,
+        per_second => Some(per_second),
+    }
+}
+
+/// Print the given schedule message; it's meant to be hard to miss
+fn announce(msg: &str) {
+    log::warn!("Schedule: {}", msg);
+
+    let msg = format!(
+        "{} {:>10} Schedule: {}\n",
+        style("SCH").yellow().bold(),
+        "-",
+        style(msg).yellow()
+    );
+
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(&msg, &PROGRESS_PRINTER);
+    }
+}
+
+/// Pause or resume requests and switch rate profiles, according to the schedule at the given time
+fn apply(schedule: &Schedule, now: SystemTime) {
+    let time_of_day = local_time_of_day(now);
+
+    apply_pause(schedule, now, time_of_day);
+    apply_profile(schedule, time_of_day);
+}
+
+/// Switch to the rate profile in effect at the given time of day, or back to `--rate-limit`
+fn apply_profile(schedule: &Schedule, time_of_day: u32) {
+    let profile = schedule.profile_at(time_of_day);
+    let rate = profile.map_or(NO_PROFILE, |profile| profile.per_second);
+
+    if PROFILE_RATE.swap(rate, Ordering::Relaxed) == rate {
+        return;
+    }
+
+    let msg = match profile {
+        Some(profile) => format!(
+            "rate profile {} in effect, {}",
+            profile.window,
+            describe_rate(profile.per_second)
+        ),
+        None => format!(
+            "outside of every rate profile, back to {}",
+            describe_rate(CONFIGURATION.rate_limit)
+        ),
+    };
+
+    announce(&msg);
+}
+
+/// Pause or resume requests, according to the schedule at the given time
+fn apply_pause(schedule: &Schedule, now: SystemTime, time_of_day: u32) {
+    let allowed = schedule.allows(now, time_of_day);
+    let paused = !allowed;
+
+    if PAUSED.swap(paused, Ordering::Relaxed) == paused {
+        return;
+    }
+
+    let msg = if allowed {
+        String::from("within the allowed schedule, resuming")
+    } else if schedule.start.is_some_and(|start| now < start) {
+        format!("waiting until {} to start", CONFIGURATION.start_at)
+    } else {
+        format!(
+            "outside of the allowed hours ({}), pausing",
+            CONFIGURATION.pause_outside.join(", ")
+        )
+    };
+
+    announce(&msg);
+}
+
+/// Spawn the task that pauses and resumes requests as the clock enters and leaves the allowed
+/// schedule (see `--start-at` and `--pause-outside`), and switches between rate profiles (see
+/// `--rate-profile`); returns the transmitter used to stop the task along with the task's
+/// JoinHandle to be awaited, or `None` when there's no schedule
+///
+/// Requests are paused right away when the schedule doesn't allow them yet
+pub fn initialize() -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.start_at.is_empty()
+        && CONFIGURATION.pause_outside.is_empty()
+        && CONFIGURATION.rate_profiles.is_empty()
+    {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let now = SystemTime::now();
+
+    let schedule = match Schedule::new(
+        &CONFIGURATION.start_at,
+        &CONFIGURATION.pause_outside,
+        now,
+        local_time_of_day(now),
+    )
+    .and_then(|schedule| schedule.with_profiles(&CONFIGURATION.rate_profiles))
+    {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            eprintln!(
+                "{} {} {}",
+                status_colorizer("ERROR"),
+                module_colorizer("schedule::initialize"),
+                e
+            );
+            process::exit(1);
+        }
+    };
+
+    apply(&schedule, now);
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let checker = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(CHECK_MS));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => apply(&schedule, SystemTime::now()),
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, checker);
+    Some((tx_stop, checker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to get the number of seconds since midnight of the given time of day
+    fn at(time: &str) -> u32 {
+        parse_time(time).unwrap()
+    }
+
+    #[test]
+    /// times and windows are parsed from HH:MM and HH:MM-HH:MM
+    fn schedule_parses_times_and_windows() {
+        assert_eq!(parse_time("06:30"), Some(6 * 3600 + 30 * 60));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("6"), None);
+
+        assert_eq!(
+            Window::parse("22:00-06:00"),
+            Some(Window {
+                start: at("22:00"),
+                end: at("06:00")
+            })
+        );
+        assert_eq!(Window::parse("22:00"), None);
+    }
+
+    #[test]
+    /// windows may run past midnight, a window closing when it opens is always open
+    fn schedule_windows_wrap_around_midnight() {
+        let night = Window::parse("22:00-06:00").unwrap();
+        assert!(night.contains(at("23:59")));
+        assert!(night.contains(at("00:00")));
+        assert!(night.contains(at("05:59")));
+        assert!(!night.contains(at("06:00")));
+        assert!(!night.contains(at("12:00")));
+
+        let lunch = Window::parse("12:00-13:00").unwrap();
+        assert!(lunch.contains(at("12:30")));
+        assert!(!lunch.contains(at("13:00")));
+
+        assert!(Window::parse("00:00-00:00").unwrap().contains(at("17:00")));
+    }
+
+    #[test]
+    /// requests wait for the next occurrence of the start time, then for any of the windows
+    fn schedule_allows_after_start_within_windows() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let windows = vec![String::from("22:00-06:00"), String::from("12:00-13:00")];
+
+        // 20:00 now, starting at 22:00 is two hours away
+        let schedule = Schedule::new("22:00", &windows, now, at("20:00")).unwrap();
+        assert!(!schedule.allows(now, at("20:00")));
+        assert!(schedule.allows(now + Duration::from_secs(7200), at("22:00")));
+        assert!(!schedule.allows(now + Duration::from_secs(14 * 3600), at("10:00")));
+        assert!(schedule.allows(now + Duration::from_secs(16 * 3600), at("12:00")));
+
+        // 23:00 now, starting at 22:00 is tomorrow
+        let schedule = Schedule::new("22:00", &[], now, at("23:00")).unwrap();
+        assert!(!schedule.allows(now + Duration::from_secs(3600), at("00:00")));
+        assert!(schedule.allows(now + Duration::from_secs(23 * 3600), at("22:00")));
+
+        // within the minute it's given for, it starts right away
+        let schedule = Schedule::new("22:00", &[], now, at("22:00") + 30).unwrap();
+        assert!(schedule.allows(now, at("22:00") + 30));
+
+        assert!(Schedule::new("", &[], now, 0).unwrap().allows(now, 0));
+        assert!(Schedule::new("10pm", &[], now, 0).is_err());
+        assert!(Schedule::new("", &[String::from("nights")], now, 0).is_err());
+    }
+
+    #[test]
+    /// rate profiles are parsed from HH:MM-HH:MM=REQUESTS and shown as their window was given
+    fn schedule_parses_rate_profiles() {
+        let profile = RateProfile::parse("18:00-09:00=500").unwrap();
+        assert_eq!(profile.window.to_string(), "18:00-09:00");
+        assert_eq!(profile.per_second, 500);
+
+        assert_eq!(RateProfile::parse("18:00-09:00=0").unwrap().per_second, 0);
+        assert_eq!(RateProfile::parse("18:00-09:00"), None);
+        assert_eq!(RateProfile::parse("18:00-09:00=fast"), None);
+    }
+
+    #[test]
+    /// the first rate profile whose window is open applies, none applies outside of them all
+    fn schedule_profile_at_picks_first_open_window() {
+        let now = UNIX_EPOCH;
+        let profiles = vec![
+            String::from("09:00-18:00=50"),
+            String::from("12:00-13:00=10"),
+            String::from("22:00-06:00=500"),
+        ];
+
+        let schedule = Schedule::new("", &[], now, 0)
+            .unwrap()
+            .with_profiles(&profiles)
+            .unwrap();
+
+        let rate = |time| schedule.profile_at(at(time)).map(|p| p.per_second);
+        assert_eq!(rate("10:00"), Some(50));
+        assert_eq!(rate("12:30"), Some(50));
+        assert_eq!(rate("23:00"), Some(500));
+        assert_eq!(rate("20:00"), None);
+
+        let bad = vec![String::from("nights=500")];
+        assert!(Schedule::new("", &[], now, 0)
+            .unwrap()
+            .with_profiles(&bad)
+            .is_err());
+    }
+}
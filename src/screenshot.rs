@@ -0,0 +1,332 @@
+use crate::config::CONFIGURATION;
+use crate::responses::response_path;
+use crate::utils::{module_colorizer, status_colorizer};
+use crate::xml::escape;
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, Url};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+/// Browsers looked for on the `PATH`, in order
+#[cfg(feature = "screenshots")]
+const BROWSERS: [&str; 5] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+];
+
+/// most browsers running at once; each of them is a few processes and a couple hundred MB
+const MAX_BROWSERS: usize = 4;
+
+/// size of the browser's window, which is the size of every screenshot
+const WINDOW_SIZE: &str = "1280,800";
+
+/// longest a browser may take to load a page and take its screenshot, in seconds
+const CAPTURE_TIMEOUT: u64 = 30;
+
+/// name of the gallery written next to the screenshots once the scan completes
+const GALLERY_NAME: &str = "index.html";
+
+lazy_static! {
+    /// Browser taking the screenshots, found once
+    static ref BROWSER: Option<PathBuf> = find_browser();
+
+    /// Slots for the browsers running at once (see `MAX_BROWSERS`)
+    static ref SLOTS: Semaphore = Semaphore::new(MAX_BROWSERS);
+
+    /// Screenshots being taken
+    static ref CAPTURES: Mutex<Vec<JoinHandle<Option<Screenshot>>>> = Mutex::new(Vec::new());
+}
+
+/// Screenshot of a reported url
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screenshot {
+    /// url in the screenshot
+    pub url: Url,
+
+    /// status code the url was reported with
+    pub status: u16,
+
+    /// where the screenshot was saved, relative to the `--screenshot` directory
+    pub file: PathBuf,
+}
+
+/// Find a headless-capable Chromium on the `PATH`
+#[cfg(feature = "screenshots")]
+fn find_browser() -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths)
+        .flat_map(|dir| BROWSERS.iter().map(move |browser| dir.join(browser)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Find a headless-capable Chromium on the `PATH`; never found without the `screenshots` feature
+#[cfg(not(feature = "screenshots"))]
+fn find_browser() -> Option<PathBuf> {
+    None
+}
+
+/// Whether or not a response with the given status is worth a screenshot: pages that were
+/// served (2xx) or that ask for credentials (401, 403); redirects show another page altogether
+pub fn is_interesting(status: StatusCode) -> bool {
+    status.is_success() || status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+}
+
+/// Where the screenshot of the given url is saved within `directory`, mirroring the url like
+/// `--save-responses` does
+///
+/// example: http://localhost:8080/js/app.js?v=1 -> directory/localhost_8080/js/app.js_v=1.png
+pub fn screenshot_path(directory: &Path, url: &Url) -> PathBuf {
+    let path = response_path(directory, url);
+
+    path.with_file_name(format!(
+        "{}.png",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+/// Make sure screenshots can be taken when `--screenshot` is used, exiting otherwise
+pub fn initialize() {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.screenshot.is_empty() || BROWSER.is_some() {
+        log::trace!("exit: initialize");
+        return;
+    }
+
+    let reason = if cfg!(feature = "screenshots") {
+        "Could not find chromium or google-chrome on the PATH"
+    } else {
+        "feroxbuster was built without the screenshots feature (cargo build --features screenshots)"
+    };
+
+    eprintln!(
+        "{} {} {}, needed by --screenshot",
+        status_colorizer("ERROR"),
+        module_colorizer("screenshot::initialize"),
+        reason
+    );
+    process::exit(1);
+}
+
+/// Arguments given to the browser to take the screenshot of the given url, saved to `path`
+fn browser_args(url: &Url, path: &Path) -> Vec<String> {
+    let mut args = vec![
+        String::from("--headless"),
+        String::from("--disable-gpu"),
+        String::from("--hide-scrollbars"),
+        format!("--window-size={}", WINDOW_SIZE),
+        format!("--screenshot={}", path.display()),
+    ];
+
+    if CONFIGURATION.insecure {
+        args.push(String::from("--ignore-certificate-errors"));
+    }
+
+    if !CONFIGURATION.proxy.is_empty() {
+        args.push(format!("--proxy-server={}", CONFIGURATION.proxy));
+    }
+
+    #[cfg(unix)]
+    {
+        // chromium refuses to run as root with its sandbox enabled
+        if unsafe { libc::geteuid() } == 0 {
+            args.push(String::from("--no-sandbox"));
+        }
+    }
+
+    args.push(url.to_string());
+    args
+}
+
+/// Take the screenshot of the given url, once a browser slot is free
+async fn take(url: Url, status: u16) -> Option<Screenshot> {
+    log::trace!("enter: take({}, {})", url, status);
+
+    let browser = BROWSER.as_ref()?;
+    let directory = Path::new(&CONFIGURATION.screenshot);
+    let path = screenshot_path(directory, &url);
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            log::warn!("Could not take the screenshot of {}: {}", url, e);
+            return None;
+        }
+    }
+
+    let _slot = SLOTS.acquire().await;
+
+    let output = tokio::process::Command::new(browser)
+        .args(browser_args(&url, &path))
+        .kill_on_drop(true)
+        .output();
+
+    match tokio::time::timeout(Duration::from_secs(CAPTURE_TIMEOUT), output).await {
+        Ok(Ok(output)) if output.status.success() && path.exists() => {}
+        Ok(Ok(output)) => {
+            log::warn!(
+                "Could not take the screenshot of {}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Ok(Err(e)) => {
+            log::warn!("Could not take the screenshot of {}: {}", url, e);
+            return None;
+        }
+        Err(_) => {
+            log::warn!(
+                "Could not take the screenshot of {}: timed out after {}s",
+                url,
+                CAPTURE_TIMEOUT
+            );
+            return None;
+        }
+    }
+
+    let screenshot = Screenshot {
+        file: path.strip_prefix(directory).unwrap_or(&path).to_path_buf(),
+        url,
+        status,
+    };
+
+    log::trace!("exit: take -> {:?}", screenshot);
+    Some(screenshot)
+}
+
+/// Take the screenshot of the given reported url in the background (see `--screenshot`)
+pub fn capture(url: &Url, status: StatusCode) {
+    let handle = tokio::spawn(take(url.clone(), status.as_u16()));
+
+    if let Ok(mut captures) = CAPTURES.lock() {
+        captures.push(handle);
+    }
+}
+
+/// Relative link to the given screenshot file; characters a browser would decode or cut the link
+/// at are percent-encoded
+fn link(file: &Path) -> String {
+    file.to_string_lossy()
+        .replace('\\', "/")
+        .replace('%', "%25")
+        .replace('#', "%23")
+        .replace(' ', "%20")
+}
+
+/// Render the gallery of the given screenshots, each a thumbnail linking to the full screenshot
+pub fn render_gallery(screenshots: &[Screenshot]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>feroxbuster screenshots</title>\n<style>\n\
+         body { font-family: sans-serif; background: #222; color: #ddd; }\n\
+         figure { display: inline-block; margin: 8px; width: 320px; vertical-align: top; }\n\
+         img { width: 320px; border: 1px solid #555; }\n\
+         figcaption { font-size: 12px; word-break: break-all; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+
+    for screenshot in screenshots {
+        let file = escape(&link(&screenshot.file));
+
+        html.push_str(&format!(
+            "<figure><a href=\"{}\"><img src=\"{}\" loading=\"lazy\"></a><figcaption>{} <a href=\"{}\">{}</a></figcaption></figure>\n",
+            file,
+            file,
+            screenshot.status,
+            escape(screenshot.url.as_str()),
+            escape(screenshot.url.as_str())
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Wait for every screenshot to be taken, then write their gallery to `--screenshot`'s index.html
+pub async fn finish() {
+    log::trace!("enter: finish");
+
+    let captures = match CAPTURES.lock() {
+        Ok(mut captures) => captures.drain(..).collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+
+    if captures.is_empty() {
+        log::trace!("exit: finish");
+        return;
+    }
+
+    let screenshots: Vec<Screenshot> = futures::future::join_all(captures)
+        .await
+        .into_iter()
+        .filter_map(|taken| taken.ok().flatten())
+        .collect();
+
+    let gallery = Path::new(&CONFIGURATION.screenshot).join(GALLERY_NAME);
+
+    if let Err(e) = fs::write(&gallery, render_gallery(&screenshots)) {
+        log::error!("Could not write {}: {}", gallery.display(), e);
+    }
+
+    log::trace!("exit: finish");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// served pages and pages asking for credentials are interesting, redirects and errors aren't
+    fn screenshot_only_interesting_statuses() {
+        assert!(is_interesting(StatusCode::OK));
+        assert!(is_interesting(StatusCode::NO_CONTENT));
+        assert!(is_interesting(StatusCode::UNAUTHORIZED));
+        assert!(is_interesting(StatusCode::FORBIDDEN));
+        assert!(!is_interesting(StatusCode::MOVED_PERMANENTLY));
+        assert!(!is_interesting(StatusCode::NOT_FOUND));
+        assert!(!is_interesting(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[test]
+    /// screenshots mirror the url, with a png extension
+    fn screenshot_path_mirrors_url() {
+        let url = Url::parse("http://localhost:8080/js/app.js?v=1").unwrap();
+        assert_eq!(
+            screenshot_path(Path::new("shots"), &url),
+            Path::new("shots/localhost_8080/js/app.js_v=1.png")
+        );
+
+        let url = Url::parse("https://localhost/admin/").unwrap();
+        assert_eq!(
+            screenshot_path(Path::new("shots"), &url),
+            Path::new("shots/localhost/admin/index.png")
+        );
+    }
+
+    #[test]
+    /// every screenshot is a thumbnail linking to itself, captioned with its escaped url; links to
+    /// files with percent signs in their name are encoded
+    fn screenshot_render_gallery_links_screenshots() {
+        let screenshots = vec![Screenshot {
+            url: Url::parse("http://localhost/search?q=<a>").unwrap(),
+            status: 200,
+            file: PathBuf::from("localhost/search_q=%3Ca%3E.png"),
+        }];
+
+        let gallery = render_gallery(&screenshots);
+
+        assert!(gallery.contains(
+            "<a href=\"localhost/search_q=%253Ca%253E.png\"><img src=\"localhost/search_q=%253Ca%253E.png\""
+        ));
+        assert!(gallery.contains("200 <a href=\"http://localhost/search?q=%3Ca%3E\">"));
+        assert!(render_gallery(&[]).contains("<body>\n</body>"));
+    }
+}
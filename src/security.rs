@@ -0,0 +1,256 @@
+use crate::config::CONFIGURATION;
+use crate::hooks::{self, RequestHook};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN,
+};
+use reqwest::{Request, Url};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Origin sent with requests that don't have one, to find endpoints that reflect any origin
+/// (see `--security-headers`); `.invalid` is reserved, it can't belong to the target
+pub const PROBE_ORIGIN: &str = "https://feroxbuster.invalid";
+
+/// Security headers expected on every response, Strict-Transport-Security only over https
+const SECURITY_HEADERS: [&str; 5] = [
+    "content-security-policy",
+    "strict-transport-security",
+    "x-content-type-options",
+    "x-frame-options",
+    "referrer-policy",
+];
+
+/// Who a response's `Access-Control-Allow-Origin` lets read it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Cors {
+    /// `*`, any origin without credentials
+    Wildcard,
+
+    /// the origin of the request, whatever it was
+    Reflected,
+
+    /// `null`, i.e. sandboxed iframes and local files
+    Null,
+
+    /// a specific origin, other than the request's
+    Allowlisted,
+}
+
+impl Cors {
+    /// Description of the policy, as shown next to a result
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Cors::Wildcard => "wildcard",
+            Cors::Reflected => "reflected",
+            Cors::Null => "null",
+            Cors::Allowlisted => "allowlisted",
+        }
+    }
+
+    /// Whether or not origins that aren't trusted may read the response
+    pub fn is_permissive(&self) -> bool {
+        *self != Cors::Allowlisted
+    }
+}
+
+/// CORS policy and missing security headers of a finding (see `--security-headers`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audit {
+    /// value of `Access-Control-Allow-Origin`, if given
+    pub allow_origin: Option<String>,
+
+    /// what `Access-Control-Allow-Origin` allows, if given
+    pub cors: Option<Cors>,
+
+    /// whether or not `Access-Control-Allow-Credentials` is `true`
+    pub allow_credentials: bool,
+
+    /// security headers the response doesn't have
+    pub missing: Vec<&'static str>,
+}
+
+/// Origin requests are sent with; an `Origin` given with `--headers` takes the place of
+/// `PROBE_ORIGIN`
+fn request_origin() -> String {
+    CONFIGURATION
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("origin"))
+        .map_or_else(|| PROBE_ORIGIN.to_string(), |(_, value)| value.clone())
+}
+
+/// Decide what the given `Access-Control-Allow-Origin` allows, given the origin of the request
+pub fn classify_cors(allow_origin: &str, origin: &str) -> Cors {
+    let allow_origin = allow_origin.trim();
+
+    if allow_origin == "*" {
+        Cors::Wildcard
+    } else if allow_origin.eq_ignore_ascii_case("null") {
+        Cors::Null
+    } else if allow_origin.trim_end_matches('/') == origin.trim_end_matches('/') {
+        Cors::Reflected
+    } else {
+        Cors::Allowlisted
+    }
+}
+
+impl Audit {
+    /// Audit the headers of the response to the given url, requested from the given origin
+    pub fn of(url: &Url, headers: &HeaderMap, origin: &str) -> Self {
+        let allow_origin = headers
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string());
+
+        let allow_credentials = headers
+            .get(ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"true"));
+
+        let missing = SECURITY_HEADERS
+            .iter()
+            .filter(|name| **name != "strict-transport-security" || url.scheme() == "https")
+            .filter(|name| !headers.contains_key(**name))
+            .copied()
+            .collect();
+
+        Self {
+            cors: allow_origin
+                .as_deref()
+                .map(|allow_origin| classify_cors(allow_origin, origin)),
+            allow_origin,
+            allow_credentials,
+            missing,
+        }
+    }
+
+    /// Audit the headers of the response to the given url, requested with `--security-headers`
+    pub fn of_response(url: &Url, headers: &HeaderMap) -> Self {
+        Self::of(url, headers, &request_origin())
+    }
+
+    /// Whether or not origins that aren't trusted may read the response
+    pub fn is_permissive(&self) -> bool {
+        self.cors.is_some_and(|cors| cors.is_permissive())
+    }
+
+    /// Description of the audit, as shown next to a result; i.e. `cors: reflected with
+    /// credentials; 3 security headers missing`
+    pub fn describe(&self) -> String {
+        let mut parts = vec![];
+
+        if let Some(cors) = self.cors {
+            parts.push(format!(
+                "cors: {}{}",
+                cors.as_str(),
+                if self.allow_credentials {
+                    " with credentials"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        match self.missing.len() {
+            0 => {}
+            1 => parts.push(format!("{} missing", self.missing[0])),
+            count => parts.push(format!("{} security headers missing", count)),
+        }
+
+        parts.join("; ")
+    }
+
+    /// Create the `cors` and `missing_security_headers` entries of a result's `--json` record
+    pub fn add_to(&self, record: &mut Value) {
+        if let Some(cors) = self.cors {
+            record["cors"] = json!({
+                "allow_origin": self.allow_origin,
+                "allow_credentials": self.allow_credentials,
+                "policy": cors.as_str(),
+                "permissive": cors.is_permissive(),
+            });
+        }
+
+        record["missing_security_headers"] = json!(self.missing);
+    }
+}
+
+/// Sends requests without an `Origin` from `PROBE_ORIGIN`, so that servers answer with their
+/// CORS policy
+struct ProbeOrigin;
+
+impl RequestHook for ProbeOrigin {
+    fn before_request(&self, request: &mut Request) {
+        if !request.headers().contains_key(ORIGIN) {
+            request
+                .headers_mut()
+                .insert(ORIGIN, HeaderValue::from_static(PROBE_ORIGIN));
+        }
+    }
+}
+
+/// Requests made from here on are sent with an `Origin` when they don't have one (see
+/// `--security-headers`)
+pub fn initialize() {
+    if !CONFIGURATION.security_headers {
+        return;
+    }
+
+    hooks::add_request_hook(Arc::new(ProbeOrigin));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// wildcards, null, and the request's own origin are permissive, any other origin isn't
+    fn security_classify_cors_policies() {
+        let origin = "https://feroxbuster.invalid";
+
+        assert_eq!(classify_cors("*", origin), Cors::Wildcard);
+        assert_eq!(classify_cors("null", origin), Cors::Null);
+        assert_eq!(
+            classify_cors("https://feroxbuster.invalid/", origin),
+            Cors::Reflected
+        );
+        assert_eq!(
+            classify_cors("https://app.example.com", origin),
+            Cors::Allowlisted
+        );
+        assert!(!Cors::Allowlisted.is_permissive());
+    }
+
+    #[test]
+    /// the cors policy is read along with the missing headers; hsts is only expected over https
+    fn security_audit_reads_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            "https://feroxbuster.invalid".parse().unwrap(),
+        );
+        headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, "true".parse().unwrap());
+        headers.insert("x-frame-options", "DENY".parse().unwrap());
+        headers.insert("referrer-policy", "no-referrer".parse().unwrap());
+
+        let url = Url::parse("http://localhost/api").unwrap();
+        let audit = Audit::of(&url, &headers, PROBE_ORIGIN);
+
+        assert_eq!(audit.cors, Some(Cors::Reflected));
+        assert!(audit.allow_credentials);
+        assert!(audit.is_permissive());
+        assert_eq!(
+            audit.missing,
+            vec!["content-security-policy", "x-content-type-options"]
+        );
+        assert_eq!(
+            audit.describe(),
+            "cors: reflected with credentials; 2 security headers missing"
+        );
+
+        let url = Url::parse("https://localhost/api").unwrap();
+        let audit = Audit::of(&url, &HeaderMap::new(), PROBE_ORIGIN);
+
+        assert_eq!(audit.cors, None);
+        assert_eq!(audit.missing.len(), 5);
+        assert_eq!(audit.describe(), "5 security headers missing");
+    }
+}
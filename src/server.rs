@@ -0,0 +1,770 @@
+use crate::config::PROGRESS_PRINTER;
+use crate::grpc::{self, ResponseBody};
+use crate::utils::{ferox_print, status_colorizer};
+use crate::FeroxResult;
+use hyper::body::{self, Bytes};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::env::{current_exe, temp_dir};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::{self, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// Number of milliseconds between checks for new results of a job being streamed
+const STREAM_INTERVAL: u64 = 500;
+
+/// Seconds between rewrites of a running job's statistics
+const STATS_INTERVAL: &str = "1";
+
+/// Configuration keys a job may set; anything naming a file, or a destination other than the
+/// target (webhooks, syslog, email, replay proxies), is left to the server's own configuration,
+/// as is anything scanning with the server's own credentials (i.e. `auth` and `aws_sigv4`)
+pub const JOB_KEYS: [&str; 60] = [
+    "statuscodes",
+    "threads",
+    "timeout",
+    "useragent",
+    "redirects",
+    "insecure",
+    "extensions",
+    "headers",
+    "queries",
+    "norecursion",
+    "extract_links",
+    "addslash",
+    "depth",
+    "sizefilters",
+    "dontfilter",
+    "show_headers",
+    "tags",
+    "word_prefixes",
+    "word_suffixes",
+    "word_cases",
+    "rate_limit",
+    "soft_404",
+    "use_listings",
+    "ban_policy",
+    "analyze_403",
+    "canary",
+    "canary_interval",
+    "canary_pause",
+    "verify",
+    "fingerprint",
+    "smart",
+    "body_budget",
+    "adaptive_concurrency",
+    "start_at",
+    "pause_outside",
+    "body_hash",
+    "respect_robots",
+    "rate_profiles",
+    "slash_probe",
+    "check_length",
+    "security_headers",
+    "login_url",
+    "login_data",
+    "login_success_regex",
+    "oauth_token_url",
+    "oauth_client_id",
+    "oauth_client_secret",
+    "oauth_scopes",
+    "oauth_refresh_token",
+    "oauth_expired_regex",
+    "proxy",
+    "proxy_auth",
+    "proxy_ntlm",
+    "header_order",
+    "collapse_duplicates",
+    "filter_hashes",
+    "filter_redirects",
+    "filter_redirect_loops",
+    "detect_dynamic",
+    "method",
+];
+
+lazy_static! {
+    /// Every job submitted since the server started, by id
+    static ref JOBS: Mutex<BTreeMap<usize, Job>> = Mutex::new(BTreeMap::new());
+}
+
+/// Id of the next job submitted
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Where a job is at
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JobStatus {
+    /// the scan is running
+    Running,
+
+    /// the scan completed
+    Finished,
+
+    /// the scan couldn't be started or exited with a non-zero exit code
+    Failed,
+
+    /// the scan was cancelled with `DELETE /scans/ID`, or when the server stopped
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Name of the status, as used in the api's responses
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Finished => "finished",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A scan submitted to the server, run by its own feroxbuster process
+///
+/// The configuration is global to a process, so every job gets a process of its own; it writes
+/// its results and statistics to the job's directory, where the server picks them up
+#[derive(Debug)]
+struct Job {
+    /// id of the job, used in its urls
+    id: usize,
+
+    /// url being scanned
+    target_url: String,
+
+    /// directory holding the job's configuration, results, and statistics
+    dir: PathBuf,
+
+    /// where the job is at
+    status: JobStatus,
+
+    /// exit code of the job's process, once it exited
+    exit_code: Option<i32>,
+
+    /// transmitter used to cancel the job while it's running
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+impl Job {
+    /// `--json` results of the job, as written by its process
+    fn results(&self) -> PathBuf {
+        self.dir.join("results.json")
+    }
+
+    /// `--stats-output` of the job, rewritten by its process while it's running
+    fn statistics(&self) -> PathBuf {
+        self.dir.join("statistics.json")
+    }
+
+    /// The job, as returned by the api; statistics are `null` until first written
+    fn as_json(&self) -> Value {
+        let statistics = fs::read_to_string(self.statistics())
+            .ok()
+            .and_then(|written| serde_json::from_str::<Value>(&written).ok())
+            .unwrap_or(Value::Null);
+
+        json!({
+            "id": self.id,
+            "target_url": self.target_url,
+            "status": self.status.as_str(),
+            "exit_code": self.exit_code,
+            "results": format!("/scans/{}/results", self.id),
+            "statistics": statistics,
+        })
+    }
+}
+
+/// Directory holding every job's directory; one per server process
+fn jobs_dir() -> PathBuf {
+    temp_dir().join(format!("feroxbuster-serve-{}", process::id()))
+}
+
+/// Print a message about the server or one of its jobs
+fn announce(msg: &str) {
+    // example output
+    // SRV          - job 1 started: http://localhost
+    ferox_print(
+        &format!("{} {:>10} {}\n", status_colorizer("SRV"), "-", msg),
+        &PROGRESS_PRINTER,
+    );
+}
+
+/// A job's json configuration, split up
+#[derive(Debug)]
+struct JobConfig {
+    /// url to scan
+    target_url: String,
+
+    /// words to scan with instead of a wordlist, i.e. a coordinator's shard (see `--role`)
+    words: Option<Vec<String>>,
+
+    /// the rest of the configuration, as the contents of a ferox-config.toml
+    toml: String,
+}
+
+/// Split a job's json configuration into its target, its words if given, and the rest of its
+/// configuration; a job is always a plain scan, and may only set the keys of `JOB_KEYS`
+fn job_config(submitted: &Value) -> Result<JobConfig, String> {
+    let mut config = match submitted {
+        Value::Object(config) => config.clone(),
+        _ => return Err(String::from("expected a json object")),
+    };
+
+    let target_url = match config.remove("target_url") {
+        Some(Value::String(url)) if !url.is_empty() => url,
+        _ => return Err(String::from("target_url is required")),
+    };
+
+    let words = match config.remove("words") {
+        Some(words) => Some(
+            serde_json::from_value::<Vec<String>>(words)
+                .map_err(|_| String::from("words has to be a list of strings"))?,
+        ),
+        None => None,
+    };
+
+    // toml has no null, leaving a key out gives it its default
+    config.retain(|_, value| !value.is_null());
+
+    if let Some(key) = config.keys().find(|key| !JOB_KEYS.contains(&key.as_str())) {
+        return Err(format!("{} can't be set by a job", key));
+    }
+
+    // going through toml's own Value puts tables after plain values, as toml requires
+    let table = toml::Value::try_from(Value::Object(config)).map_err(|e| e.to_string())?;
+    let toml = toml::to_string(&table).map_err(|e| e.to_string())?;
+
+    Ok(JobConfig {
+        target_url,
+        words,
+        toml,
+    })
+}
+
+/// Start a job in its own feroxbuster process, configured by the given json object (same keys
+/// as ferox-config.toml, plus `target_url`); returns the job's id
+pub(crate) fn submit(submitted: &Value) -> Result<usize, String> {
+    log::trace!("enter: submit({})", submitted);
+
+    let submitted = job_config(submitted)?;
+
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let dir = jobs_dir().join(id.to_string());
+    let config = dir.join("ferox-config.toml");
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(&config, submitted.toml).map_err(|e| e.to_string())?;
+
+    let wordlist = match submitted.words {
+        Some(words) => {
+            let wordlist = dir.join("wordlist.txt");
+            fs::write(&wordlist, words.join("\n")).map_err(|e| e.to_string())?;
+            Some(wordlist)
+        }
+        None => None,
+    };
+
+    let mut job = Job {
+        id,
+        target_url: submitted.target_url,
+        dir,
+        status: JobStatus::Running,
+        exit_code: None,
+        cancel: None,
+    };
+
+    let exe = current_exe().map_err(|e| e.to_string())?;
+
+    // the job's own options are read from its config file, what the server relies on is given on
+    // the command line, which takes precedence
+    let mut command = Command::new(exe);
+
+    if let Some(wordlist) = wordlist {
+        command.arg("--wordlist").arg(wordlist);
+    }
+
+    let mut child = command
+        .arg("--config")
+        .arg(&config)
+        .arg("--url")
+        .arg(&job.target_url)
+        .arg("--quiet")
+        .arg("--json")
+        .arg("--output")
+        .arg(job.results())
+        .arg("--stats-output")
+        .arg(job.statistics())
+        .arg("--stats-interval")
+        .arg(STATS_INTERVAL)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    let (tx_cancel, rx_cancel) = oneshot::channel::<()>();
+    job.cancel = Some(tx_cancel);
+
+    announce(&format!("job {} started: {}", id, job.target_url));
+
+    match JOBS.lock() {
+        Ok(mut jobs) => jobs.insert(id, job),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    tokio::spawn(async move {
+        let (status, exit_code) = tokio::select! {
+            exited = &mut child => match exited.ok().and_then(|status| status.code()) {
+                Some(0) => (JobStatus::Finished, Some(0)),
+                code => (JobStatus::Failed, code),
+            },
+            _ = rx_cancel => {
+                child.kill().unwrap_or_default();
+                let code = child.await.ok().and_then(|status| status.code());
+                (JobStatus::Cancelled, code)
+            }
+        };
+
+        if let Ok(mut jobs) = JOBS.lock() {
+            if let Some(job) = jobs.get_mut(&id) {
+                job.status = status;
+                job.exit_code = exit_code;
+                job.cancel = None;
+            }
+        }
+
+        announce(&format!("job {} {}", id, status.as_str()));
+    });
+
+    log::trace!("exit: submit -> {}", id);
+    Ok(id)
+}
+
+/// Cancel the given job; false if it isn't running
+pub(crate) fn cancel(id: usize) -> bool {
+    let cancel = JOBS
+        .lock()
+        .ok()
+        .and_then(|mut jobs| jobs.get_mut(&id).and_then(|job| job.cancel.take()));
+
+    match cancel {
+        Some(tx_cancel) => tx_cancel.send(()).is_ok(),
+        None => false,
+    }
+}
+
+/// The given job as returned by the api, if there's such a job
+pub(crate) fn job_json(id: usize) -> Option<Value> {
+    JOBS.lock().ok()?.get(&id).map(Job::as_json)
+}
+
+/// Every job, as returned by the api
+pub(crate) fn jobs_json() -> Vec<Value> {
+    match JOBS.lock() {
+        Ok(jobs) => jobs.values().map(Job::as_json).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Results file of the given job and whether the job is still running
+pub(crate) fn job_progress(id: usize) -> Option<(PathBuf, bool)> {
+    let jobs = JOBS.lock().ok()?;
+    let job = jobs.get(&id)?;
+
+    Some((job.results(), job.status == JobStatus::Running))
+}
+
+/// Read the complete lines written to the given file since `offset`, moving `offset` past them
+pub(crate) fn read_new_lines(path: &PathBuf, offset: &mut u64) -> Vec<String> {
+    let mut lines = vec![];
+
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return lines, // nothing written yet
+    };
+
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return lines;
+    }
+
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+
+    while let Ok(read) = reader.read_line(&mut line) {
+        if read == 0 || !line.ends_with('\n') {
+            // a partial line is read again once it's complete
+            break;
+        }
+
+        *offset += read as u64;
+        lines.push(line.trim_end().to_string());
+        line.clear();
+    }
+
+    lines
+}
+
+/// A server-sent event with the given name and data
+fn sse_event(event: &str, data: &str) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+/// Stream the given job's results as server-sent events: a `result` event per result as they're
+/// found, starting with those already found, then an `end` event with the job once it's done
+fn stream_results(id: usize) -> Body {
+    let (mut tx, body) = Body::channel();
+
+    tokio::spawn(async move {
+        let mut offset = 0;
+
+        // checked ahead of reading, so that nothing written before the job ended is missed
+        while let Some((results, running)) = job_progress(id) {
+            for line in read_new_lines(&results, &mut offset) {
+                let is_result = serde_json::from_str::<Value>(&line)
+                    .map(|record| record["type"] == "response")
+                    .unwrap_or_default();
+
+                if is_result && tx.send_data(sse_event("result", &line)).await.is_err() {
+                    return; // the client went away
+                }
+            }
+
+            if !running {
+                let job = job_json(id).unwrap_or(Value::Null);
+                tx.send_data(sse_event("end", &job.to_string()))
+                    .await
+                    .unwrap_or_default();
+                break;
+            }
+
+            tokio::time::delay_for(Duration::from_millis(STREAM_INTERVAL)).await;
+        }
+    });
+
+    body
+}
+
+/// A json response with the given status code
+fn respond(status: StatusCode, value: &Value) -> Response<Body> {
+    let mut response = Response::new(Body::from(value.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// A json error response with the given status code
+fn error(status: StatusCode, msg: &str) -> Response<Body> {
+    respond(status, &json!({ "error": msg }))
+}
+
+/// Whether or not the request carries the api's bearer token; compared in constant time
+pub(crate) fn is_authorized(request: &Request<Body>, token: &str) -> bool {
+    let given = request
+        .headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |differ, (a, b)| differ | (a ^ b))
+            == 0
+}
+
+/// Whether or not the request's body is declared as json; a browser can't send one cross-site
+/// without asking first, unlike a form
+fn is_json(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(hyper::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"))
+        .unwrap_or_default()
+}
+
+/// Route a request to the api's endpoints; every request has to carry the api's token as
+/// `Authorization: Bearer TOKEN`
+///
+/// - `POST /scans` submits a job, configured by the json body (`Content-Type: application/json`)
+/// - `GET /scans` lists every job
+/// - `GET /scans/ID` gets a job, with its live statistics
+/// - `GET /scans/ID/results` streams a job's results as server-sent events
+/// - `DELETE /scans/ID` cancels a job
+///
+/// gRPC calls, told apart by their content type, go to the `Scans` service instead (see `grpc`)
+async fn route(
+    request: Request<Body>,
+    token: Arc<String>,
+) -> Result<Response<ResponseBody>, Infallible> {
+    log::trace!("enter: route({} {})", request.method(), request.uri());
+
+    if grpc::is_grpc(&request) {
+        let response = grpc::route(request, &token).await;
+        log::trace!("exit: route -> {}", response.status());
+        return Ok(response);
+    }
+
+    if !is_authorized(&request, &token) {
+        let mut response = error(StatusCode::UNAUTHORIZED, "a valid bearer token is required");
+        response.headers_mut().insert(
+            hyper::header::WWW_AUTHENTICATE,
+            hyper::header::HeaderValue::from_static("Bearer"),
+        );
+        log::trace!("exit: route -> {}", response.status());
+        return Ok(response.map(ResponseBody::from));
+    }
+
+    let segments: Vec<String> = request
+        .uri()
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(String::from)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let id = segments.get(1).and_then(|id| id.parse::<usize>().ok());
+
+    let response = match (request.method().clone(), segments.as_slice(), id) {
+        (Method::POST, ["scans"], _) if !is_json(&request) => error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "expected Content-Type: application/json",
+        ),
+        (Method::POST, ["scans"], _) => {
+            let submitted = body::to_bytes(request.into_body())
+                .await
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok());
+
+            match submitted {
+                Some(submitted) => match submit(&submitted) {
+                    Ok(id) => respond(StatusCode::CREATED, &job_json(id).unwrap_or_default()),
+                    Err(e) => error(StatusCode::BAD_REQUEST, &e),
+                },
+                None => error(StatusCode::BAD_REQUEST, "expected a json object"),
+            }
+        }
+        (Method::GET, ["scans"], _) => respond(StatusCode::OK, &Value::from(jobs_json())),
+        (Method::GET, ["scans", _], Some(id)) => match job_json(id) {
+            Some(job) => respond(StatusCode::OK, &job),
+            None => error(StatusCode::NOT_FOUND, "no such scan"),
+        },
+        (Method::GET, ["scans", _, "results"], Some(id)) if job_json(id).is_some() => {
+            let mut response = Response::new(stream_results(id));
+            response.headers_mut().insert(
+                hyper::header::CONTENT_TYPE,
+                hyper::header::HeaderValue::from_static("text/event-stream"),
+            );
+            response
+        }
+        (Method::DELETE, ["scans", _], Some(id)) => match job_json(id) {
+            Some(_) if cancel(id) => respond(StatusCode::ACCEPTED, &job_json(id).unwrap()),
+            Some(_) => error(StatusCode::CONFLICT, "scan isn't running"),
+            None => error(StatusCode::NOT_FOUND, "no such scan"),
+        },
+        _ => error(StatusCode::NOT_FOUND, "no such endpoint"),
+    };
+
+    log::trace!("exit: route -> {}", response.status());
+    Ok(response.map(ResponseBody::from))
+}
+
+/// Serve the api on the given address until Ctrl+C is pressed, which cancels every running job
+///
+/// Requests have to carry the given token (see `route`); when it's empty, a random one is made up
+/// and printed
+pub async fn serve(listen: &str, token: &str) -> FeroxResult<()> {
+    log::trace!("enter: serve({})", listen);
+
+    let addr: SocketAddr = listen.parse()?;
+
+    let token = if token.is_empty() {
+        let token = Uuid::new_v4().to_simple().to_string();
+        announce(&format!("api token: {}", token));
+        token
+    } else {
+        token.to_string()
+    };
+    let token = Arc::new(token);
+
+    let make_service = make_service_fn(move |_| {
+        let token = token.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| route(request, token.clone()))) }
+    });
+    let server = Server::try_bind(&addr)?.serve(make_service);
+
+    announce(&format!(
+        "listening on http://{}, jobs are kept in {}",
+        addr,
+        jobs_dir().display()
+    ));
+
+    server
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.unwrap_or_default();
+        })
+        .await?;
+
+    // the server stopped, so do the jobs; their processes are killed and awaited
+    let running: Vec<usize> = match JOBS.lock() {
+        Ok(jobs) => jobs.keys().copied().collect(),
+        Err(_) => vec![],
+    };
+
+    for id in running {
+        cancel(id);
+    }
+
+    while JOBS
+        .lock()
+        .map(|jobs| jobs.values().any(|job| job.status == JobStatus::Running))
+        .unwrap_or_default()
+    {
+        tokio::time::delay_for(Duration::from_millis(STREAM_INTERVAL)).await;
+    }
+
+    log::trace!("exit: serve");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the target is split from the rest of the configuration, which is written as toml
+    fn server_job_config_splits_target() {
+        let submitted = json!({
+            "target_url": "http://localhost",
+            "headers": {"X-Scan": "yes"},
+            "extensions": ["php"],
+            "threads": 10,
+        });
+
+        let config = job_config(&submitted).unwrap();
+        assert_eq!(config.target_url, "http://localhost");
+        assert!(config.words.is_none());
+
+        let parsed: toml::Value = toml::from_str(&config.toml).unwrap();
+        assert_eq!(parsed["threads"].as_integer(), Some(10));
+        assert_eq!(parsed["headers"]["X-Scan"].as_str(), Some("yes"));
+        assert!(parsed.get("target_url").is_none());
+    }
+
+    #[test]
+    /// words are split from the configuration; nulls are left out
+    fn server_job_config_splits_words() {
+        let submitted = json!({
+            "target_url": "http://localhost",
+            "words": ["admin", "login"],
+            "proxy": null,
+        });
+
+        let config = job_config(&submitted).unwrap();
+        assert_eq!(config.words.unwrap(), vec!["admin", "login"]);
+
+        let parsed: toml::Value = toml::from_str(&config.toml).unwrap();
+        assert!(parsed.as_table().unwrap().is_empty());
+
+        assert!(job_config(&json!({"target_url": "http://localhost", "words": [1]})).is_err());
+    }
+
+    #[test]
+    /// a job can't name files, other destinations, or make itself a coordinator
+    fn server_job_config_rejects_keys_outside_allowlist() {
+        for key in [
+            "wordlist",
+            "save_responses",
+            "error_log",
+            "output",
+            "state_file",
+            "resume_from",
+            "slack_webhook",
+            "smtp",
+            "role",
+            "workers",
+            "auth",
+        ]
+        .iter()
+        {
+            let submitted = json!({"target_url": "http://localhost", *key: "/etc/shadow"});
+            let error = job_config(&submitted).unwrap_err();
+            assert!(error.starts_with(key), "{}", error);
+        }
+    }
+
+    #[test]
+    /// every key a job may set is one a config file understands
+    fn server_job_keys_are_config_file_keys() {
+        let keys = serde_json::to_value(crate::config::Configuration::default()).unwrap();
+
+        for key in JOB_KEYS.iter() {
+            assert!(keys.get(key).is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    /// requests need the exact bearer token, and a json body has to say so
+    fn server_checks_token_and_content_type() {
+        let request = |authorization: &str, content_type: &str| {
+            Request::post("/scans")
+                .header(hyper::header::AUTHORIZATION, authorization)
+                .header(hyper::header::CONTENT_TYPE, content_type)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert!(is_authorized(&request("Bearer abc", ""), "abc"));
+        assert!(!is_authorized(&request("Bearer abd", ""), "abc"));
+        assert!(!is_authorized(&request("Bearer ab", ""), "abc"));
+        assert!(!is_authorized(&request("abc", ""), "abc"));
+        assert!(!is_authorized(&Request::new(Body::empty()), "abc"));
+
+        assert!(is_json(&request("", "application/json; charset=utf-8")));
+        assert!(!is_json(&request("", "application/x-www-form-urlencoded")));
+        assert!(!is_json(&request("", "text/plain")));
+    }
+
+    #[test]
+    /// jobs need a target and a json object
+    fn server_job_config_needs_target() {
+        assert!(job_config(&json!({"threads": 10})).is_err());
+        assert!(job_config(&json!({"target_url": ""})).is_err());
+        assert!(job_config(&json!(["http://localhost"])).is_err());
+    }
+
+    #[test]
+    /// only complete lines are read, partial ones are read once they're complete
+    fn server_read_new_lines_skips_partial_lines() {
+        let path = temp_dir().join(format!("ferox-server-lines-{}", process::id()));
+        fs::write(&path, "one\ntwo\nthr").unwrap();
+
+        let mut offset = 0;
+        assert_eq!(read_new_lines(&path, &mut offset), vec!["one", "two"]);
+        assert_eq!(offset, 8);
+
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(read_new_lines(&path, &mut offset), vec!["three"]);
+        assert!(read_new_lines(&path, &mut offset).is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
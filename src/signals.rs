@@ -0,0 +1,165 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::statistics::STATISTICS;
+use crate::utils::ferox_print;
+use console::style;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// how often paused requests check whether they may be made, in milliseconds
+const PAUSE_CHECK_MS: u64 = 500;
+
+/// width of the rules above and below the paused banner
+const BANNER_WIDTH: usize = 80;
+
+/// Whether or not requests are paused by SIGUSR1, until SIGUSR2 resumes them
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Whether or not requests are paused by SIGUSR1
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+/// Pause or resume requests, as SIGUSR1 and SIGUSR2 do, without announcing it; used by the
+/// dashboard (see `--tui`), which shows it instead
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// Wait until requests may be made again, when they're paused by SIGUSR1
+pub async fn wait() {
+    while is_paused() {
+        tokio::time::delay_for(Duration::from_millis(PAUSE_CHECK_MS)).await;
+    }
+}
+
+/// Banner printed when requests are paused, showing the scan's statistics at the time and how to
+/// resume it
+///
+/// example:
+///     ─────────────────────────────── Scan paused ────────────────────────────────
+///      1234 req (411/s) | errors: 3 timeout 0 connect 0 redirect 0 other | ...
+///      Requests in flight complete; send SIGUSR2 to resume (kill -USR2 4242)
+///     ─────────────────────────────────────────────────────────────────────────────
+pub fn paused_banner(summary: &str, pid: u32) -> String {
+    let title = " Scan paused ";
+    let left = (BANNER_WIDTH - title.len()) / 2;
+    let right = BANNER_WIDTH - title.len() - left;
+
+    format!(
+        "{}{}{}\n {}\n Requests in flight complete; send SIGUSR2 to resume (kill -USR2 {})\n{}\n",
+        "─".repeat(left),
+        style(title).yellow().bold(),
+        "─".repeat(right),
+        summary,
+        pid,
+        "─".repeat(BANNER_WIDTH)
+    )
+}
+
+/// Print the given message; it's meant to be hard to miss
+fn announce(msg: &str) {
+    if CONFIGURATION.silent {
+        eprint!("{}", msg);
+    } else {
+        ferox_print(msg, &PROGRESS_PRINTER);
+    }
+}
+
+/// Pause requests and print the paused banner; `start` is when the scan started
+fn pause(start: Instant) {
+    if PAUSED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    log::warn!("SIGUSR1 received, pausing");
+
+    announce(&paused_banner(
+        &STATISTICS.summary(start.elapsed()),
+        std::process::id(),
+    ));
+}
+
+/// Resume paused requests
+fn resume() {
+    if !PAUSED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    log::warn!("SIGUSR2 received, resuming");
+
+    announce(&format!(
+        "{} {:>10} {}\n",
+        style("SIG").yellow().bold(),
+        "-",
+        style("SIGUSR2 received, resuming").yellow()
+    ));
+}
+
+/// Spawn the task pausing requests on SIGUSR1 and resuming them on SIGUSR2; returns the
+/// transmitter used to stop the task along with the task's JoinHandle to be awaited, or `None`
+/// when the signals can't be listened for
+///
+/// `start` is when the scan started, used for the number of requests per second in the banner
+#[cfg(unix)]
+pub fn initialize(start: Instant) -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    log::trace!("enter: initialize({:?})", start);
+
+    let signals = signal(SignalKind::user_defined1())
+        .and_then(|usr1| Ok((usr1, signal(SignalKind::user_defined2())?)));
+
+    let (mut usr1, mut usr2) = match signals {
+        Ok(signals) => signals,
+        Err(e) => {
+            log::warn!("Could not listen for SIGUSR1/SIGUSR2: {}", e);
+            log::trace!("exit: initialize -> None");
+            return None;
+        }
+    };
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let listener = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = usr1.recv() => pause(start),
+                _ = usr2.recv() => resume(),
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, listener);
+    Some((tx_stop, listener))
+}
+
+/// Spawn the task pausing requests on SIGUSR1 and resuming them on SIGUSR2; there are no such
+/// signals outside of unix
+#[cfg(not(unix))]
+pub fn initialize(start: Instant) -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize({:?})", start);
+    log::trace!("exit: initialize -> None");
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the banner shows the statistics along with how to resume, between two rules of equal width
+    fn signals_paused_banner_shows_statistics() {
+        let banner = console::strip_ansi_codes(&paused_banner("12 req (3/s)", 4242)).to_string();
+        let lines: Vec<&str> = banner.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains(" Scan paused "));
+        assert_eq!(lines[0].chars().count(), BANNER_WIDTH);
+        assert_eq!(lines[1], " 12 req (3/s)");
+        assert!(lines[2].ends_with("send SIGUSR2 to resume (kill -USR2 4242)"));
+        assert_eq!(lines[3].chars().count(), BANNER_WIDTH);
+    }
+}
@@ -0,0 +1,527 @@
+use crate::config::CONFIGURATION;
+use crate::hooks::{self, RequestHook};
+use crate::targets::Target;
+use crate::utils::{module_colorizer, rfc3339_timestamp, status_colorizer};
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Request, Url};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Name of the signing algorithm, as given in the `Authorization` header
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Payload hash of requests whose body can't be read ahead of sending them
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Credentials requests are signed with
+#[derive(Clone, PartialEq)]
+pub struct Credentials {
+    /// access key id, i.e. `AKIDEXAMPLE`
+    pub access_key_id: String,
+
+    /// secret access key
+    pub secret_access_key: String,
+
+    /// session token of temporary credentials, sent as `X-Amz-Security-Token`
+    pub session_token: Option<String>,
+}
+
+impl std::fmt::Debug for Credentials {
+    /// Credentials without their secrets, for logging
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Credentials given with `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and optionally
+    /// `AWS_SESSION_TOKEN`
+    fn from_env() -> Option<Self> {
+        let non_empty = |name| env::var(name).ok().filter(|value| !value.is_empty());
+
+        Some(Self {
+            access_key_id: non_empty("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: non_empty("AWS_SECRET_ACCESS_KEY")?,
+            session_token: non_empty("AWS_SESSION_TOKEN"),
+        })
+    }
+
+    /// Credentials of the given profile in the given shared credentials file contents, i.e.
+    /// `~/.aws/credentials`
+    pub fn from_profile(contents: &str, profile: &str) -> Option<Self> {
+        let mut in_profile = false;
+        let mut access_key_id = None;
+        let mut secret_access_key = None;
+        let mut session_token = None;
+
+        for line in contents.lines() {
+            let line = line.trim();
+
+            if line.starts_with('#') || line.starts_with(';') || line.is_empty() {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_profile = section.trim() == profile;
+                continue;
+            }
+
+            if !in_profile {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let value = Some(value.trim().to_string());
+
+                match key.trim() {
+                    "aws_access_key_id" => access_key_id = value,
+                    "aws_secret_access_key" => secret_access_key = value,
+                    "aws_session_token" => session_token = value,
+                    _ => {}
+                }
+            }
+        }
+
+        Some(Self {
+            access_key_id: access_key_id?,
+            secret_access_key: secret_access_key?,
+            session_token,
+        })
+    }
+
+    /// Credentials of the standard chain: the environment first, then the profile given with
+    /// `AWS_PROFILE` (`default` otherwise) in the shared credentials file, which is
+    /// `~/.aws/credentials` unless given with `AWS_SHARED_CREDENTIALS_FILE`
+    pub fn load() -> Option<Self> {
+        if let Some(credentials) = Self::from_env() {
+            return Some(credentials);
+        }
+
+        let path = match env::var("AWS_SHARED_CREDENTIALS_FILE") {
+            Ok(path) if !path.is_empty() => PathBuf::from(path),
+            _ => dirs::home_dir()?.join(".aws").join("credentials"),
+        };
+
+        let profile = env::var("AWS_PROFILE")
+            .ok()
+            .filter(|profile| !profile.is_empty())
+            .unwrap_or_else(|| String::from("default"));
+
+        Self::from_profile(&fs::read_to_string(path).ok()?, &profile)
+    }
+}
+
+/// Lowercase hex of the given bytes
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Lowercase hex of the SHA-256 digest of the given bytes
+fn sha256_hex(data: &[u8]) -> String {
+    // sha256 of a slice doesn't fail
+    hex(&hash(MessageDigest::sha256(), data).unwrap())
+}
+
+/// HMAC-SHA256 of the given data with the given key
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    // hmac keys of any length are valid, and signing a slice doesn't fail
+    let key = PKey::hmac(key).unwrap();
+    let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+    signer.update(data).unwrap();
+    signer.sign_to_vec().unwrap()
+}
+
+/// Percent-encode everything but unreserved characters, as SigV4 expects; slashes are kept when
+/// encoding a path
+pub fn uri_encode(value: &str, keep_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b'/' if keep_slash => String::from("/"),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Decode the percent-encoded bytes of the given value; anything else is kept as is
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let digit = |byte: u8| (byte as char).to_digit(16);
+
+            if let (Some(high), Some(low)) = (digit(bytes[index + 1]), digit(bytes[index + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                index += 3;
+                continue;
+            }
+        }
+
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Path of the given url as signed; every service but S3 expects it encoded twice
+fn canonical_uri(url: &Url, service: &str) -> String {
+    let encoded = uri_encode(&percent_decode(url.path()), true);
+
+    if service == "s3" {
+        encoded
+    } else {
+        uri_encode(&encoded, true)
+    }
+}
+
+/// Query of the given url as signed: every parameter encoded, then sorted
+fn canonical_query(url: &Url) -> String {
+    let mut params: Vec<(String, String)> = url
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .map(|param| {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+
+            (
+                uri_encode(&percent_decode(name), false),
+                uri_encode(&percent_decode(value), false),
+            )
+        })
+        .collect();
+
+    params.sort();
+
+    params
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// `Host` header of the given url, with its port unless it's the scheme's default
+fn host_of(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// Region and service requests are signed for, along with the credentials they're signed with
+#[derive(Debug)]
+pub struct SigV4 {
+    /// region, i.e. `us-east-1`
+    pub region: String,
+
+    /// service, i.e. `execute-api` or `s3`
+    pub service: String,
+
+    /// credentials requests are signed with
+    pub credentials: Credentials,
+}
+
+impl SigV4 {
+    /// Region and service of the given `region/service`, i.e. `us-east-1/execute-api`
+    pub fn parse(scope: &str, credentials: Credentials) -> Option<Self> {
+        let (region, service) = scope.split_once('/')?;
+
+        if region.is_empty() || service.is_empty() || service.contains('/') {
+            return None;
+        }
+
+        Some(Self {
+            region: region.to_string(),
+            service: service.to_string(),
+            credentials,
+        })
+    }
+
+    /// `Authorization` value of a request with the given method, url, and payload hash, signed at
+    /// the given `YYYYMMDDTHHMMSSZ` time along with the given headers (lowercase names)
+    pub fn authorization(
+        &self,
+        method: &str,
+        url: &Url,
+        payload_hash: &str,
+        amz_date: &str,
+        headers: &[(String, String)],
+    ) -> String {
+        let mut headers = headers.to_vec();
+        headers.sort();
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method,
+            canonical_uri(url, &self.service),
+            canonical_query(url),
+            canonical_headers,
+            signed_headers,
+            payload_hash
+        );
+
+        let date = &amz_date[..8];
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            ALGORITHM,
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let secret = format!("AWS4{}", self.credentials.secret_access_key);
+        let key = hmac(secret.as_bytes(), date.as_bytes());
+        let key = hmac(&key, self.region.as_bytes());
+        let key = hmac(&key, self.service.as_bytes());
+        let key = hmac(&key, b"aws4_request");
+
+        format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM,
+            self.credentials.access_key_id,
+            scope,
+            signed_headers,
+            hex(&hmac(&key, string_to_sign.as_bytes()))
+        )
+    }
+}
+
+/// The given time as SigV4 expects it, i.e. `20150830T123600Z`
+fn amz_date(time: SystemTime) -> String {
+    // 2015-08-30T12:36:00.000Z
+    let timestamp = rfc3339_timestamp(time);
+    format!("{}Z", timestamp[..19].replace(['-', ':'], ""))
+}
+
+/// Signs requests sent to the targets' origins (see `--aws-sigv4`)
+struct Signer4 {
+    /// region, service, and credentials requests are signed with
+    sigv4: SigV4,
+
+    /// origins of the targets, i.e. `https://example.com:8443`
+    origins: Vec<String>,
+}
+
+impl RequestHook for Signer4 {
+    fn before_request(&self, request: &mut Request) {
+        if !self
+            .origins
+            .contains(&request.url().origin().ascii_serialization())
+        {
+            return;
+        }
+
+        let payload_hash = match request.body() {
+            None => sha256_hex(b""),
+            Some(body) => body
+                .as_bytes()
+                .map_or_else(|| String::from(UNSIGNED_PAYLOAD), sha256_hex),
+        };
+
+        let date = amz_date(SystemTime::now());
+
+        let mut headers = vec![
+            (String::from("host"), host_of(request.url())),
+            (String::from("x-amz-content-sha256"), payload_hash.clone()),
+            (String::from("x-amz-date"), date.clone()),
+        ];
+
+        if let Some(token) = &self.sigv4.credentials.session_token {
+            headers.push((String::from("x-amz-security-token"), token.clone()));
+        }
+
+        let authorization = self.sigv4.authorization(
+            request.method().as_str(),
+            request.url(),
+            &payload_hash,
+            &date,
+            &headers,
+        );
+
+        // host is set from the url when the request is sent
+        for (name, value) in headers.iter().skip(1) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                request.headers_mut().insert(name, value);
+            }
+        }
+
+        if let Ok(value) = HeaderValue::from_str(&authorization) {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+    }
+}
+
+/// Requests sent to the targets from here on are signed (see `--aws-sigv4`); exits when the
+/// region and service can't be parsed or no credentials can be found
+///
+/// Added after every other request hook, as the signature covers the request as it's sent
+pub fn initialize(targets: &[Target]) {
+    log::trace!("enter: initialize({:?})", targets);
+
+    if CONFIGURATION.aws_sigv4.is_empty() {
+        log::trace!("exit: initialize");
+        return;
+    }
+
+    let fail = |msg: &str| -> ! {
+        eprintln!(
+            "{} {} {}",
+            status_colorizer("ERROR"),
+            module_colorizer("sigv4::initialize"),
+            msg
+        );
+        process::exit(1);
+    };
+
+    let credentials = Credentials::load().unwrap_or_else(|| {
+        fail("Could not find AWS credentials in AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY or the shared credentials file")
+    });
+
+    let sigv4 = SigV4::parse(&CONFIGURATION.aws_sigv4, credentials).unwrap_or_else(|| {
+        fail(&format!(
+            "Could not parse --aws-sigv4 {}, expected REGION/SERVICE (i.e. us-east-1/execute-api)",
+            CONFIGURATION.aws_sigv4
+        ))
+    });
+
+    log::info!(
+        "Signing requests for {} in {} as {}",
+        sigv4.service,
+        sigv4.region,
+        sigv4.credentials.access_key_id
+    );
+
+    hooks::add_request_hook(Arc::new(Signer4 {
+        sigv4,
+        origins: targets
+            .iter()
+            .filter_map(|target| Url::parse(&target.url).ok())
+            .map(|target| target.origin().ascii_serialization())
+            .collect(),
+    }));
+
+    log::trace!("exit: initialize");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// signer of the AWS SigV4 test suite
+    fn example_signer() -> SigV4 {
+        SigV4::parse(
+            "us-east-1/service",
+            Credentials {
+                access_key_id: String::from("AKIDEXAMPLE"),
+                secret_access_key: String::from("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY"),
+                session_token: None,
+            },
+        )
+        .unwrap()
+    }
+
+    /// signature of a GET of the given url in the AWS SigV4 test suite
+    fn example_signature(url: &str) -> String {
+        let url = Url::parse(url).unwrap();
+        let headers = vec![
+            (String::from("host"), host_of(&url)),
+            (String::from("x-amz-date"), String::from("20150830T123600Z")),
+        ];
+
+        example_signer().authorization("GET", &url, &sha256_hex(b""), "20150830T123600Z", &headers)
+    }
+
+    #[test]
+    /// signatures match the ones of the AWS SigV4 test suite
+    fn sigv4_authorization_matches_test_suite() {
+        assert_eq!(
+            example_signature("https://example.amazonaws.com/"),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        assert!(
+            example_signature("https://example.amazonaws.com/?Param2=value2&Param1=value1")
+                .ends_with(
+                    "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+                )
+        );
+    }
+
+    #[test]
+    /// queries are encoded and sorted, paths are encoded twice for every service but s3
+    fn sigv4_canonical_uri_and_query() {
+        let url = Url::parse("https://example.com/a b/c?b=2&a=x y&a=1").unwrap();
+
+        assert_eq!(canonical_uri(&url, "s3"), "/a%20b/c");
+        assert_eq!(canonical_uri(&url, "execute-api"), "/a%2520b/c");
+        assert_eq!(canonical_query(&url), "a=1&a=x%20y&b=2");
+    }
+
+    #[test]
+    /// only the given profile's keys are read from the shared credentials file
+    fn sigv4_credentials_from_profile() {
+        let contents = "[default]\naws_access_key_id = AKIDDEFAULT\naws_secret_access_key = secret\n\n# comment\n[scan]\naws_access_key_id=AKIDSCAN\naws_secret_access_key=s3cr3t\naws_session_token=token\n";
+
+        let scan = Credentials::from_profile(contents, "scan").unwrap();
+        assert_eq!(scan.access_key_id, "AKIDSCAN");
+        assert_eq!(scan.secret_access_key, "s3cr3t");
+        assert_eq!(scan.session_token, Some(String::from("token")));
+
+        let default = Credentials::from_profile(contents, "default").unwrap();
+        assert_eq!(default.access_key_id, "AKIDDEFAULT");
+        assert_eq!(default.session_token, None);
+
+        assert!(Credentials::from_profile(contents, "missing").is_none());
+    }
+
+    #[test]
+    /// region and service are both required
+    fn sigv4_parse_requires_region_and_service() {
+        let credentials = example_signer().credentials;
+
+        assert!(SigV4::parse("us-east-1", credentials.clone()).is_none());
+        assert!(SigV4::parse("/s3", credentials.clone()).is_none());
+        assert!(SigV4::parse("us-east-1/s3/x", credentials.clone()).is_none());
+        assert_eq!(
+            SigV4::parse("eu-west-1/s3", credentials).unwrap().service,
+            "s3"
+        );
+        assert_eq!(
+            amz_date(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_440_938_160)),
+            "20150830T123600Z"
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::config::CONFIGURATION;
+use crate::scanner::ScanOptions;
+use crate::utils::make_request;
+use crate::FeroxResponse;
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, Url};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+lazy_static! {
+    /// Paths probed so far, by url without a trailing slash or query
+    static ref PROBES: RwLock<HashMap<String, Probe>> = RwLock::new(HashMap::new());
+}
+
+/// What a path's statuses with and without a trailing slash say about it (see `--slash-probe`)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Verdict {
+    /// the path is a directory; worth recursing into
+    Directory,
+
+    /// the path isn't a directory, nothing is served with a trailing slash
+    NotDirectory,
+
+    /// both are served the same way, or in a way that says nothing either way
+    Unknown,
+}
+
+impl Verdict {
+    /// Description of the verdict, as shown next to a result
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Directory => "directory",
+            Verdict::NotDirectory => "not a directory",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+/// Statuses of a path requested both with and without a trailing slash, i.e. `/admin` and
+/// `/admin/`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Probe {
+    /// status of the path without a trailing slash
+    pub without_slash: StatusCode,
+
+    /// status of the path with a trailing slash
+    pub with_slash: StatusCode,
+}
+
+impl Probe {
+    /// What the pair of statuses says about the path
+    pub fn verdict(&self) -> Verdict {
+        classify(self.without_slash, self.with_slash)
+    }
+
+    /// Description of the probe, as shown next to a result; i.e. `no slash 403, slash 200,
+    /// directory`
+    pub fn describe(&self) -> String {
+        format!(
+            "no slash {}, slash {}, {}",
+            self.without_slash.as_u16(),
+            self.with_slash.as_u16(),
+            self.verdict().as_str()
+        )
+    }
+
+    /// Create the `slash_probe` entry of a result's `--json` record
+    pub fn as_json(&self) -> Value {
+        json!({
+            "without_slash": self.without_slash.as_u16(),
+            "with_slash": self.with_slash.as_u16(),
+            "verdict": self.verdict().as_str(),
+        })
+    }
+}
+
+/// Decide what a path is from its statuses without and with a trailing slash
+///
+/// Nothing served with the slash means there's no directory (i.e. `/login` 200 and `/login/`
+/// 404); something served with the slash that's served differently without it is a directory
+/// (i.e. `/admin` 403 and `/admin/` 200, or `/js` 301 and `/js/` 200). Servers that ignore the
+/// trailing slash serve both the same way, which says nothing either way
+pub fn classify(without_slash: StatusCode, with_slash: StatusCode) -> Verdict {
+    if with_slash == StatusCode::NOT_FOUND || with_slash == StatusCode::GONE {
+        Verdict::NotDirectory
+    } else if (with_slash.is_success()
+        || with_slash == StatusCode::UNAUTHORIZED
+        || with_slash == StatusCode::FORBIDDEN)
+        && without_slash != with_slash
+    {
+        Verdict::Directory
+    } else {
+        Verdict::Unknown
+    }
+}
+
+/// The given url's path without a trailing slash or query, used to look up its probe
+fn key_of(url: &Url) -> String {
+    let mut bare = url.clone();
+    bare.set_query(None);
+    bare.set_fragment(None);
+
+    let path = bare.path().trim_end_matches('/').to_string();
+    bare.set_path(&path);
+
+    bare.to_string().trim_end_matches('/').to_string()
+}
+
+/// The given url with its trailing slash toggled, i.e. `/admin` for `/admin/` and vice versa;
+/// the query is kept as is. The root has no counterpart
+fn toggle_slash(url: &Url) -> Option<Url> {
+    let path = url.path();
+
+    if path == "/" {
+        return None;
+    }
+
+    let mut toggled = url.clone();
+
+    match path.strip_suffix('/') {
+        Some(bare) => toggled.set_path(bare),
+        None => toggled.set_path(&format!("{}/", path)),
+    }
+
+    Some(toggled)
+}
+
+/// Probe of an already probed path; used when reporting and deciding recursion
+pub fn probe_of(url: &Url) -> Option<Probe> {
+    match PROBES.read() {
+        Ok(probes) => probes.get(&key_of(url)).copied(),
+        Err(e) => {
+            // poisoned lock
+            log::error!("{}", e);
+            None
+        }
+    }
+}
+
+/// Request the counterpart of a hit, i.e. `/admin/` for `/admin`, then remember both statuses
+/// (see `--slash-probe`)
+///
+/// `requested` is the url that was requested; only responses that may be reported are probed,
+/// and each path is probed once
+pub async fn probe_response(
+    response: &FeroxResponse,
+    requested: &Url,
+    options: &ScanOptions,
+) -> Option<Probe> {
+    log::trace!(
+        "enter: probe_response({}, {}, {:?})",
+        response.url(),
+        requested,
+        options
+    );
+
+    if !CONFIGURATION
+        .statuscodes
+        .contains(&response.status().as_u16())
+    {
+        log::trace!("exit: probe_response -> None");
+        return None;
+    }
+
+    if let Some(probe) = probe_of(requested) {
+        log::trace!("exit: probe_response -> {:?}", probe);
+        return Some(probe);
+    }
+
+    let counterpart = match toggle_slash(requested) {
+        Some(counterpart) => counterpart,
+        None => {
+            log::trace!("exit: probe_response -> None");
+            return None;
+        }
+    };
+
+    options.throttle().await;
+
+    // failed requests are treated as not found
+    let status = match make_request(&options.client, &counterpart).await {
+        Ok(counterpart_response) => counterpart_response.status(),
+        Err(_) => StatusCode::NOT_FOUND,
+    };
+
+    let probe = if requested.path().ends_with('/') {
+        Probe {
+            without_slash: status,
+            with_slash: *response.status(),
+        }
+    } else {
+        Probe {
+            without_slash: *response.status(),
+            with_slash: status,
+        }
+    };
+
+    log::debug!("slash probe of {}: {:?}", requested, probe);
+
+    match PROBES.write() {
+        Ok(mut probes) => {
+            probes.insert(key_of(requested), probe);
+        }
+        Err(e) => {
+            log::error!("{}", e);
+        }
+    }
+
+    log::trace!("exit: probe_response -> {:?}", probe);
+    Some(probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// a path served differently with a trailing slash is a directory, one that's missing with a
+    /// slash isn't, and one served the same either way is unknown
+    fn slash_classify_pairs_of_statuses() {
+        let verdict = |without: u16, with: u16| {
+            classify(
+                StatusCode::from_u16(without).unwrap(),
+                StatusCode::from_u16(with).unwrap(),
+            )
+        };
+
+        assert_eq!(verdict(403, 200), Verdict::Directory);
+        assert_eq!(verdict(301, 200), Verdict::Directory);
+        assert_eq!(verdict(301, 403), Verdict::Directory);
+        assert_eq!(verdict(200, 404), Verdict::NotDirectory);
+        assert_eq!(verdict(301, 404), Verdict::NotDirectory);
+        assert_eq!(verdict(200, 200), Verdict::Unknown);
+        assert_eq!(verdict(403, 403), Verdict::Unknown);
+        assert_eq!(verdict(404, 301), Verdict::Unknown);
+    }
+
+    #[test]
+    /// the trailing slash is added or removed, the query is kept, the root is left alone
+    fn slash_toggle_slash_adds_or_removes_it() {
+        let toggle = |url| toggle_slash(&Url::parse(url).unwrap()).map(|url| url.to_string());
+
+        assert_eq!(
+            toggle("http://localhost/admin?a=1"),
+            Some(String::from("http://localhost/admin/?a=1"))
+        );
+        assert_eq!(
+            toggle("http://localhost/admin/"),
+            Some(String::from("http://localhost/admin"))
+        );
+        assert_eq!(toggle("http://localhost/"), None);
+    }
+
+    #[test]
+    /// both forms of a path share a probe
+    fn slash_key_of_ignores_slash_and_query() {
+        let key = |url| key_of(&Url::parse(url).unwrap());
+
+        assert_eq!(key("http://localhost/admin/?a=1"), "http://localhost/admin");
+        assert_eq!(key("http://localhost/admin"), "http://localhost/admin");
+    }
+}
@@ -0,0 +1,576 @@
+//! The scan's progress, saved to `--state-file` as json every `--checkpoint-interval` seconds,
+//! whenever a directory's scan finishes, and on Ctrl+C
+//!
+//! Every save replaces the previous one through a temporary file renamed over it, so an OOM-kill
+//! or power loss costs at most one interval of progress, never the file itself
+//!
+//! A saved state is picked up with `--resume-from`: each directory it holds is scanned again,
+//! but only for the words it wasn't requested with yet, and the results found are added to the
+//! ones it already holds
+use crate::bloom::BloomFilter;
+use crate::config::CONFIGURATION;
+use crate::scanner;
+use crate::statistics::STATISTICS;
+use crate::wordlist::Wordlist;
+use crate::{FeroxResponse, FeroxResult, VERSION};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+lazy_static! {
+    /// Progress the scanner doesn't keep itself, only recorded with `--state-file`; held while
+    /// the file is written, so that saves take turns
+    static ref PROGRESS: Mutex<Progress> = Mutex::new(Progress::default());
+}
+
+/// Progress recorded as the scan goes
+#[derive(Debug, Default)]
+struct Progress {
+    /// when the scan started; `None` until `initialize` is called
+    started: Option<Instant>,
+
+    /// urls given to scan
+    targets: Vec<String>,
+
+    /// directories whose every request was made
+    completed: Vec<String>,
+
+    /// results reported so far, as their `--json` records
+    results: Vec<Value>,
+
+    /// words requested in each directory, by this run and the runs it resumed
+    requested: Requested,
+
+    /// directories of the resumed runs, by the nearest of them they were found under; they're
+    /// scanned again once that one is
+    resumed: HashMap<String, Vec<String>>,
+}
+
+/// Words requested in each directory; the words are kept once, each directory only keeps a bit
+/// per word
+#[derive(Debug, Default)]
+struct Requested {
+    /// every word requested, or about to be, in any directory
+    words: Vec<String>,
+
+    /// index of each word in `words`
+    indexes: HashMap<String, usize>,
+
+    /// bit set of the indexes of the words requested, by directory
+    directories: HashMap<String, Vec<u64>>,
+}
+
+impl Requested {
+    /// Index of the given word, which is added if it's new
+    fn index(&mut self, word: &str) -> usize {
+        if let Some(index) = self.indexes.get(word) {
+            return *index;
+        }
+
+        self.words.push(word.to_string());
+        self.indexes.insert(word.to_string(), self.words.len() - 1);
+        self.words.len() - 1
+    }
+
+    /// Record that the given word was requested in the given directory
+    fn insert(&mut self, directory: &str, word: &str) {
+        let index = self.index(word);
+        let bits = self
+            .directories
+            .entry(directory_key(directory))
+            .or_default();
+
+        if bits.len() <= index / 64 {
+            bits.resize(index / 64 + 1, 0);
+        }
+
+        bits[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Whether or not the given word was requested in the given directory
+    fn contains(&self, directory: &str, word: &str) -> bool {
+        let index = match self.indexes.get(word) {
+            Some(index) => *index,
+            None => return false,
+        };
+
+        self.directories
+            .get(&directory_key(directory))
+            .and_then(|bits| bits.get(index / 64))
+            .is_some_and(|bits| bits & (1 << (index % 64)) != 0)
+    }
+
+    /// Ranges of the indexes set in the given bit set, end excluded; words are requested in order,
+    /// so a directory's words come down to a few ranges
+    fn ranges(bits: &[u64]) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+        for index in
+            (0..bits.len() * 64).filter(|index| bits[index / 64] & (1 << (index % 64)) != 0)
+        {
+            match ranges.last_mut() {
+                Some(last) if last.1 == index => last.1 += 1,
+                _ => ranges.push((index, index + 1)),
+            }
+        }
+
+        ranges
+    }
+
+    /// Bit set of the indexes in the given ranges, end excluded
+    fn bits(ranges: &[(usize, usize)]) -> Vec<u64> {
+        let mut bits = vec![
+            0;
+            ranges
+                .iter()
+                .map(|(_, end)| end / 64 + 1)
+                .max()
+                .unwrap_or(0)
+        ];
+
+        for index in ranges.iter().flat_map(|(start, end)| *start..*end) {
+            bits[index / 64] |= 1 << (index % 64);
+        }
+
+        bits
+    }
+}
+
+/// Directory's url with a trailing slash, the same way the scanner's `SCANNED_URLS` keeps them
+fn directory_key(url: &str) -> String {
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+/// Nearest of the given directories the given one is found under, if any
+fn nearest_parent<'a>(directory: &str, directories: &'a HashSet<String>) -> Option<&'a String> {
+    let mut path = directory.trim_end_matches('/');
+
+    while let Some(position) = path.rfind('/') {
+        path = &path[..position];
+
+        if path.ends_with('/') {
+            // reached the scheme's //
+            return None;
+        }
+
+        if let Some(parent) = directories.get(&format!("{}/", path)) {
+            return Some(parent);
+        }
+    }
+
+    None
+}
+
+/// Everything saved to the state file
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ScanState {
+    /// version of feroxbuster that saved the state
+    pub version: String,
+
+    /// urls given to scan
+    pub targets: Vec<String>,
+
+    /// directories scanned or being scanned, the scanner's `SCANNED_URLS`
+    pub scanned_urls: Vec<String>,
+
+    /// directories whose every request was made
+    pub completed: Vec<String>,
+
+    /// links requested with `--extract-links`, the scanner's `REQUESTED_LINKS`
+    pub requested_links: BloomFilter,
+
+    /// the scan's statistics when saved, the same ones `--stats-output` writes
+    pub statistics: Value,
+
+    /// results reported so far, as their `--json` records
+    pub results: Vec<Value>,
+
+    /// every word requested in any directory, saved once; directories refer to them by index
+    #[serde(default)]
+    pub words: Vec<String>,
+
+    /// ranges of the indexes of the words requested (end excluded), by directory
+    #[serde(default)]
+    pub requested: BTreeMap<String, Vec<(usize, usize)>>,
+}
+
+impl ScanState {
+    /// Read a state saved with `--state-file`
+    pub fn from_file(path: &str) -> FeroxResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// Write `contents` to `path` through a temporary file in the same directory, which is then
+/// renamed over it; a write interrupted midway leaves the previous file as it was
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file"))?
+        .to_string_lossy();
+    let temporary = path.with_file_name(format!(".{}.tmp-{}", file_name, process::id()));
+
+    let written = File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temporary, path));
+
+    if written.is_err() {
+        fs::remove_file(&temporary).unwrap_or_default();
+    }
+
+    written
+}
+
+/// Save the progress made so far to the given file
+fn save_to(path: &str) -> io::Result<()> {
+    // held until written, so that a finished scan, the interval and Ctrl+C don't write at once
+    let progress = PROGRESS
+        .lock()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    let elapsed = progress
+        .started
+        .map(|started| started.elapsed())
+        .unwrap_or_default();
+
+    let state = ScanState {
+        version: VERSION.to_string(),
+        targets: progress.targets.clone(),
+        scanned_urls: scanner::scanned_urls(),
+        completed: progress.completed.clone(),
+        requested_links: scanner::requested_links(),
+        statistics: STATISTICS.as_json(elapsed),
+        results: progress.results.clone(),
+        words: progress.requested.words.clone(),
+        requested: progress
+            .requested
+            .directories
+            .iter()
+            .map(|(directory, bits)| (directory.clone(), Requested::ranges(bits)))
+            .collect(),
+    };
+
+    write_atomically(Path::new(path), &serde_json::to_vec(&state)?)
+}
+
+/// Save the progress made so far to `--state-file`, if given
+pub fn save() {
+    if CONFIGURATION.state_file.is_empty() {
+        return;
+    }
+
+    match save_to(&CONFIGURATION.state_file) {
+        Ok(_) => log::debug!("Saved the scan's progress to {}", CONFIGURATION.state_file),
+        Err(e) => log::error!(
+            "Could not save the scan's progress to {}: {}",
+            CONFIGURATION.state_file,
+            e
+        ),
+    }
+}
+
+/// Record a reported result, to be saved with the rest of the progress
+pub fn record(response: &FeroxResponse) {
+    if CONFIGURATION.state_file.is_empty() {
+        return;
+    }
+
+    if let Ok(mut progress) = PROGRESS.lock() {
+        progress.results.push(response.as_json());
+    }
+}
+
+/// Record that every request of the given directory was made, then save the progress right away;
+/// a finished directory is the largest unit of progress
+pub fn scan_completed(url: &str) {
+    if CONFIGURATION.state_file.is_empty() {
+        return;
+    }
+
+    if let Ok(mut progress) = PROGRESS.lock() {
+        if !progress.completed.iter().any(|completed| completed == url) {
+            progress.completed.push(url.to_string());
+        }
+    }
+
+    save();
+}
+
+/// Index the words of the given wordlist, so that the words requested in each directory can be
+/// recorded; only done with `--state-file`
+pub fn add_words(words: &Wordlist) {
+    if CONFIGURATION.state_file.is_empty() {
+        return;
+    }
+
+    if let Ok(mut progress) = PROGRESS.lock() {
+        for word in words.iter() {
+            progress.requested.index(&word);
+        }
+    }
+}
+
+/// Record that the given word was requested in the given directory
+pub fn word_requested(directory: &str, word: &str) {
+    if CONFIGURATION.state_file.is_empty() {
+        return;
+    }
+
+    if let Ok(mut progress) = PROGRESS.lock() {
+        progress.requested.insert(directory, word);
+    }
+}
+
+/// Whether or not the given word was already requested in the given directory, by this run or a
+/// run it resumed (see `--resume-from`)
+pub fn was_requested(directory: &str, word: &str) -> bool {
+    if CONFIGURATION.state_file.is_empty() {
+        return false;
+    }
+
+    PROGRESS
+        .lock()
+        .map(|progress| progress.requested.contains(directory, word))
+        .unwrap_or_default()
+}
+
+/// Directories of the resumed runs found under the given one, to be scanned again now that it is;
+/// each is only given once
+pub fn resumed_directories(directory: &str) -> Vec<String> {
+    if CONFIGURATION.resume_from.is_empty() {
+        return Vec::new();
+    }
+
+    PROGRESS
+        .lock()
+        .ok()
+        .and_then(|mut progress| progress.resumed.remove(&directory_key(directory)))
+        .unwrap_or_default()
+}
+
+/// Urls the state saved to the given file was scanning
+pub fn saved_targets(path: &str) -> FeroxResult<Vec<String>> {
+    Ok(ScanState::from_file(path)?.targets)
+}
+
+/// Pick up where the state saved to the given file left off: its words are skipped in the
+/// directories they were requested in, its directories are scanned again, and its results are
+/// kept along with the ones found from here on; returns the number of results it holds
+pub fn resume(path: &str) -> FeroxResult<usize> {
+    log::trace!("enter: resume({})", path);
+
+    let state = ScanState::from_file(path)?;
+
+    let mut directories: HashSet<String> = state
+        .scanned_urls
+        .iter()
+        .chain(state.requested.keys())
+        .map(|directory| directory_key(directory))
+        .collect();
+
+    // targets have no parent, so that they're scanned whether or not they're given again
+    for target in &state.targets {
+        directories.remove(&directory_key(target));
+    }
+
+    let mut resumed: HashMap<String, Vec<String>> = HashMap::new();
+
+    for directory in &directories {
+        let parent = nearest_parent(directory, &directories)
+            .cloned()
+            .or_else(|| {
+                state
+                    .targets
+                    .iter()
+                    .map(|target| directory_key(target))
+                    .find(|target| directory.starts_with(target.as_str()))
+            });
+
+        if let Some(parent) = parent {
+            resumed.entry(parent).or_default().push(directory.clone());
+        }
+    }
+
+    let mut progress = PROGRESS
+        .lock()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    progress.requested = Requested::default();
+
+    for word in &state.words {
+        progress.requested.index(word);
+    }
+
+    for (directory, ranges) in &state.requested {
+        progress
+            .requested
+            .directories
+            .insert(directory_key(directory), Requested::bits(ranges));
+    }
+
+    progress.resumed = resumed;
+    progress.completed = state.completed;
+    progress.results = state.results;
+
+    // links requested by the resumed runs aren't requested again
+    scanner::restore_requested_links(state.requested_links);
+
+    log::info!(
+        "Resumed {} directories and {} results from {}",
+        progress.requested.directories.len(),
+        progress.results.len(),
+        path
+    );
+    log::trace!("exit: resume -> {}", progress.results.len());
+
+    Ok(progress.results.len())
+}
+
+/// Start recording the progress of a scan of the given urls when `--state-file` is given; with a
+/// `--checkpoint-interval`, also spawns a task that saves it on that interval, returning the
+/// transmitter used to stop the task along with the task's JoinHandle to be awaited
+pub fn initialize(targets: &[String]) -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize({:?})", targets);
+
+    if CONFIGURATION.state_file.is_empty() {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    if let Ok(mut progress) = PROGRESS.lock() {
+        progress.started = Some(Instant::now());
+        progress.targets = targets.to_vec();
+    }
+
+    if CONFIGURATION.checkpoint_interval == 0 {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+    let every = Duration::from_secs(CONFIGURATION.checkpoint_interval);
+
+    let writer = tokio::spawn(async move {
+        // nothing worth saving yet when the scan starts
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => save(),
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, writer);
+    Some((tx_stop, writer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    /// the file is replaced whole, and no temporary file is left behind
+    fn state_write_atomically_replaces_file() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("state.json");
+
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    /// the saved progress is json that reads back, requested links included
+    fn state_save_to_writes_readable_json() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("state.json");
+
+        PROGRESS
+            .lock()
+            .unwrap()
+            .completed
+            .push(String::from("http://localhost/"));
+
+        save_to(path.to_str().unwrap()).unwrap();
+
+        let state: ScanState = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(state.version, VERSION);
+        assert!(state.completed.contains(&String::from("http://localhost/")));
+        assert_eq!(state.statistics["type"], "statistics");
+        assert!(!state.requested_links.contains("http://localhost/admin"));
+    }
+
+    #[test]
+    /// words are recorded per directory, with or without its trailing slash, and saved as ranges
+    fn state_requested_words_round_trip_as_ranges() {
+        let mut requested = Requested::default();
+
+        for word in ["admin", "login", "images", "js"].iter() {
+            requested.index(word);
+        }
+
+        requested.insert("http://localhost", "admin");
+        requested.insert("http://localhost/", "login");
+        requested.insert("http://localhost/", "js");
+
+        assert!(requested.contains("http://localhost/", "admin"));
+        assert!(requested.contains("http://localhost", "login"));
+        assert!(!requested.contains("http://localhost/", "images"));
+        assert!(!requested.contains("http://localhost/admin/", "admin"));
+        assert!(!requested.contains("http://localhost/", "css"));
+
+        let bits = &requested.directories["http://localhost/"];
+        let ranges = Requested::ranges(bits);
+        assert_eq!(ranges, vec![(0, 2), (3, 4)]);
+        assert_eq!(&Requested::bits(&ranges), bits);
+
+        let many: Vec<(usize, usize)> = vec![(0, 100), (130, 131)];
+        assert_eq!(Requested::ranges(&Requested::bits(&many)), many);
+    }
+
+    #[test]
+    /// a directory's nearest saved parent is found, however deep it is
+    fn state_nearest_parent_skips_unsaved_directories() {
+        let directories: HashSet<String> = [
+            "http://localhost/",
+            "http://localhost/admin/",
+            "http://localhost/admin/a/b/",
+        ]
+        .iter()
+        .map(|directory| directory.to_string())
+        .collect();
+
+        assert_eq!(
+            nearest_parent("http://localhost/admin/a/b/", &directories).unwrap(),
+            "http://localhost/admin/"
+        );
+        assert_eq!(
+            nearest_parent("http://localhost/admin/", &directories).unwrap(),
+            "http://localhost/"
+        );
+        assert_eq!(nearest_parent("http://localhost/", &directories), None);
+    }
+}
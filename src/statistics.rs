@@ -0,0 +1,602 @@
+use crate::config::CONFIGURATION;
+use crate::encryption;
+use crate::error_log::ErrorClass;
+use crate::metadata::tag;
+use crate::progress;
+use reqwest::{Error, StatusCode, Url};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Number of milliseconds between updates of the live statistics line
+const UPDATE_INTERVAL: u64 = 1000;
+
+/// Global statistics, updated as requests are made and scans start/finish
+pub static STATISTICS: Stats = Stats::new();
+
+/// Counters that describe the state of the overall scan
+///
+/// Counters are atomic and collections are behind a `Mutex`, so the global instance can be updated
+/// from any task
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// number of requests sent
+    requests: AtomicUsize,
+
+    /// number of requests that timed out
+    timeouts: AtomicUsize,
+
+    /// number of requests that failed to connect
+    connection_errors: AtomicUsize,
+
+    /// number of requests that failed due to too many/looping redirects
+    redirection_errors: AtomicUsize,
+
+    /// number of requests that failed for any other reason
+    request_errors: AtomicUsize,
+
+    /// number of responses with a 2xx status code
+    successes: AtomicUsize,
+
+    /// number of responses with a 3xx status code
+    redirects: AtomicUsize,
+
+    /// number of responses with a 4xx status code
+    client_errors: AtomicUsize,
+
+    /// number of responses with a 5xx status code
+    server_errors: AtomicUsize,
+
+    /// number of responses with a 403 status code (also counted in `client_errors`)
+    forbidden: AtomicUsize,
+
+    /// number of responses filtered out by size or wildcard filters
+    filtered: AtomicUsize,
+
+    /// number of responses whose body didn't match their Content-Length
+    length_mismatches: AtomicUsize,
+
+    /// number of responses reported as results
+    reported: AtomicUsize,
+
+    /// number of directory scans that have started
+    scans_started: AtomicUsize,
+
+    /// number of directory scans that have completed
+    scans_completed: AtomicUsize,
+
+    /// sum of the Content-Length of every response
+    bytes: AtomicU64,
+
+    /// number of responses per status code
+    statuses: Mutex<BTreeMap<u16, usize>>,
+
+    /// when each directory scan started and, once complete, how long it took
+    directories: Mutex<Vec<DirectoryTiming>>,
+
+    /// number of reported results per directory they were found in
+    directory_findings: Mutex<BTreeMap<String, usize>>,
+}
+
+/// Start time and duration of a single directory scan
+#[derive(Debug)]
+struct DirectoryTiming {
+    /// url being scanned
+    url: String,
+
+    /// when the scan started
+    started: Instant,
+
+    /// how long the scan took; `None` while it's still running
+    elapsed: Option<Duration>,
+}
+
+impl Stats {
+    /// Create a new `Stats` with every counter set to zero
+    pub const fn new() -> Self {
+        Stats {
+            requests: AtomicUsize::new(0),
+            timeouts: AtomicUsize::new(0),
+            connection_errors: AtomicUsize::new(0),
+            redirection_errors: AtomicUsize::new(0),
+            request_errors: AtomicUsize::new(0),
+            successes: AtomicUsize::new(0),
+            redirects: AtomicUsize::new(0),
+            client_errors: AtomicUsize::new(0),
+            server_errors: AtomicUsize::new(0),
+            forbidden: AtomicUsize::new(0),
+            filtered: AtomicUsize::new(0),
+            length_mismatches: AtomicUsize::new(0),
+            reported: AtomicUsize::new(0),
+            scans_started: AtomicUsize::new(0),
+            scans_completed: AtomicUsize::new(0),
+            bytes: AtomicU64::new(0),
+            statuses: Mutex::new(BTreeMap::new()),
+            directories: Mutex::new(Vec::new()),
+            directory_findings: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Simple helper to read a counter
+    fn get(counter: &AtomicUsize) -> usize {
+        counter.load(Ordering::Relaxed)
+    }
+
+    /// Record that a request was sent
+    pub fn add_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the status code and Content-Length of a response
+    pub fn add_response(&self, status: StatusCode, content_length: u64) {
+        self.bytes.fetch_add(content_length, Ordering::Relaxed);
+
+        if let Ok(mut statuses) = self.statuses.lock() {
+            *statuses.entry(status.as_u16()).or_insert(0) += 1;
+        }
+
+        let counter = if status.is_success() {
+            &self.successes
+        } else if status.is_redirection() {
+            &self.redirects
+        } else if status.is_client_error() {
+            &self.client_errors
+        } else if status.is_server_error() {
+            &self.server_errors
+        } else {
+            return; // informational; not tracked
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        if status == StatusCode::FORBIDDEN {
+            self.forbidden.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a failed request, categorized by the type of error
+    pub fn add_error(&self, error: &Error) {
+        let counter = match ErrorClass::from_error(error) {
+            ErrorClass::Timeout => &self.timeouts,
+            ErrorClass::Connection => &self.connection_errors,
+            ErrorClass::Redirection => &self.redirection_errors,
+            ErrorClass::Other => &self.request_errors,
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a response that was filtered out
+    pub fn add_filtered(&self) {
+        self.filtered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a response whose body didn't match its Content-Length
+    pub fn add_length_mismatch(&self) {
+        self.length_mismatches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a response that was reported as a result, found at the given url
+    pub fn add_reported(&self, url: &str) {
+        self.reported.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut findings) = self.directory_findings.lock() {
+            *findings.entry(directory_of(url)).or_insert(0) += 1;
+        }
+    }
+
+    /// Number of responses reported as results
+    pub fn reported(&self) -> usize {
+        Self::get(&self.reported)
+    }
+
+    /// Number of requests sent
+    pub fn requests(&self) -> usize {
+        Self::get(&self.requests)
+    }
+
+    /// Number of responses filtered out
+    pub fn filtered(&self) -> usize {
+        Self::get(&self.filtered)
+    }
+
+    /// Sum of the Content-Length of every response
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Number of responses per status code
+    pub fn statuses(&self) -> BTreeMap<u16, usize> {
+        match self.statuses.lock() {
+            Ok(statuses) => statuses.clone(),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+
+    /// The given number of directories with the most reported results, along with their number
+    /// of results; most results first, ties sorted by url
+    pub fn top_directories(&self, count: usize) -> Vec<(String, usize)> {
+        let mut directories: Vec<(String, usize)> = match self.directory_findings.lock() {
+            Ok(findings) => findings
+                .iter()
+                .map(|(url, found)| (url.clone(), *found))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        // stable, so ties keep the order of their urls
+        directories.sort_by_key(|(_, found)| std::cmp::Reverse(*found));
+        directories.truncate(count);
+        directories
+    }
+
+    /// Record the start of a directory scan
+    pub fn scan_started(&self, url: &str) {
+        self.scans_started.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut directories) = self.directories.lock() {
+            directories.push(DirectoryTiming {
+                url: url.to_string(),
+                started: Instant::now(),
+                elapsed: None,
+            });
+        }
+    }
+
+    /// Record the completion of a directory scan
+    pub fn scan_completed(&self, url: &str) {
+        self.scans_completed.fetch_add(1, Ordering::Relaxed);
+
+        if let Ok(mut directories) = self.directories.lock() {
+            if let Some(timing) = directories
+                .iter_mut()
+                .find(|timing| timing.url == url && timing.elapsed.is_none())
+            {
+                timing.elapsed = Some(timing.started.elapsed());
+            }
+        }
+    }
+
+    /// Total number of failed requests
+    pub fn errors(&self) -> usize {
+        Self::get(&self.timeouts)
+            + Self::get(&self.connection_errors)
+            + Self::get(&self.redirection_errors)
+            + Self::get(&self.request_errors)
+    }
+
+    /// Current value of every counter, by its name as pushed to statsd (see `statsd`)
+    pub fn counters(&self) -> Vec<(&'static str, u64)> {
+        let get = |counter: &AtomicUsize| Self::get(counter) as u64;
+
+        vec![
+            ("requests", get(&self.requests)),
+            ("errors.timeout", get(&self.timeouts)),
+            ("errors.connection", get(&self.connection_errors)),
+            ("errors.redirection", get(&self.redirection_errors)),
+            ("errors.other", get(&self.request_errors)),
+            ("responses.2xx", get(&self.successes)),
+            ("responses.3xx", get(&self.redirects)),
+            ("responses.4xx", get(&self.client_errors)),
+            ("responses.403", get(&self.forbidden)),
+            ("responses.5xx", get(&self.server_errors)),
+            ("filtered", get(&self.filtered)),
+            ("findings", get(&self.reported)),
+            ("scans.started", get(&self.scans_started)),
+            ("scans.completed", get(&self.scans_completed)),
+            ("bytes", self.bytes.load(Ordering::Relaxed)),
+        ]
+    }
+
+    /// Single line summary of the current state of the scan, `elapsed` is used to calculate the
+    /// number of requests per second
+    ///
+    /// example:
+    ///     1234 req (411/s) | errors: 3 timeout 0 connect 0 redirect 0 other | 2xx: 10 3xx: 4 4xx: 1200 (403: 12) 5xx: 0 | 40 filtered | scans: 2 running 5 done
+    ///
+    /// responses whose body didn't match their Content-Length are only shown once there are any,
+    /// i.e. `| 40 filtered 2 length mismatches |`
+    pub fn summary(&self, elapsed: Duration) -> String {
+        let requests = Self::get(&self.requests);
+        let started = Self::get(&self.scans_started);
+        let completed = Self::get(&self.scans_completed);
+
+        let per_second = if elapsed.as_secs_f64() > 0.0 {
+            (requests as f64 / elapsed.as_secs_f64()).round() as usize
+        } else {
+            0
+        };
+
+        format!(
+            "{} req ({}/s) | errors: {} timeout {} connect {} redirect {} other | 2xx: {} 3xx: {} 4xx: {} (403: {}) 5xx: {} | {} filtered{} | scans: {} running {} done",
+            requests,
+            per_second,
+            Self::get(&self.timeouts),
+            Self::get(&self.connection_errors),
+            Self::get(&self.redirection_errors),
+            Self::get(&self.request_errors),
+            Self::get(&self.successes),
+            Self::get(&self.redirects),
+            Self::get(&self.client_errors),
+            Self::get(&self.forbidden),
+            Self::get(&self.server_errors),
+            Self::get(&self.filtered),
+            match Self::get(&self.length_mismatches) {
+                0 => String::new(),
+                1 => String::from(" 1 length mismatch"),
+                mismatches => format!(" {} length mismatches", mismatches),
+            },
+            started.saturating_sub(completed),
+            completed
+        )
+    }
+
+    /// All statistics as a single JSON object, `elapsed` is the wall time of the scan
+    ///
+    /// example:
+    ///     {"type":"statistics","requests":1234,"wall_time":3.0,"requests_per_second":411.3,
+    ///      "bytes":56789,"statuses":{"200":10,"403":12},"errors":{"total":3,"timeout":3,...},
+    ///      "filtered":40,"length_mismatches":0,"reported":14,"directories":[{"url":"http://localhost/","duration":2.98}]}
+    pub fn as_json(&self, elapsed: Duration) -> Value {
+        let requests = Self::get(&self.requests);
+        let wall_time = elapsed.as_secs_f64();
+
+        let per_second = if wall_time > 0.0 {
+            requests as f64 / wall_time
+        } else {
+            0.0
+        };
+
+        // json object keys must be strings
+        let statuses: BTreeMap<String, usize> = match self.statuses.lock() {
+            Ok(statuses) => statuses
+                .iter()
+                .map(|(status, count)| (status.to_string(), *count))
+                .collect(),
+            Err(_) => BTreeMap::new(),
+        };
+
+        let directories: Vec<Value> = match self.directories.lock() {
+            Ok(directories) => directories
+                .iter()
+                .map(|timing| {
+                    json!({
+                        "url": timing.url,
+                        "duration": timing.elapsed.map(|elapsed| elapsed.as_secs_f64()),
+                    })
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        json!({
+            "type": "statistics",
+            "requests": requests,
+            "wall_time": wall_time,
+            "requests_per_second": per_second,
+            "bytes": self.bytes.load(Ordering::Relaxed),
+            "statuses": statuses,
+            "errors": {
+                "total": self.errors(),
+                "timeout": Self::get(&self.timeouts),
+                "connection": Self::get(&self.connection_errors),
+                "redirection": Self::get(&self.redirection_errors),
+                "other": Self::get(&self.request_errors),
+            },
+            "filtered": Self::get(&self.filtered),
+            "length_mismatches": Self::get(&self.length_mismatches),
+            "reported": self.reported(),
+            "directories": directories,
+        })
+    }
+}
+
+/// Url of the directory the given url is in, i.e. `http://localhost/admin/` for both
+/// `http://localhost/admin/login.php` and `http://localhost/admin/js/`
+fn directory_of(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut parsed) => {
+            let path = parsed.path().trim_end_matches('/');
+            let directory = path[..path.rfind('/').map_or(0, |slash| slash + 1)].to_string();
+
+            parsed.set_path(&directory);
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Write the global statistics as JSON to `destination`; `-` writes them to stdout
+pub fn write_statistics(destination: &str, elapsed: Duration) -> io::Result<()> {
+    log::trace!("enter: write_statistics({}, {:?})", destination, elapsed);
+
+    let mut statistics = STATISTICS.as_json(elapsed);
+    statistics["configuration"] = CONFIGURATION.as_json();
+    tag(&mut statistics);
+
+    let result = if destination == "-" {
+        println!("{}", statistics);
+        Ok(())
+    } else {
+        encryption::write(destination, format!("{}\n", statistics))
+    };
+
+    log::trace!("exit: write_statistics -> {:?}", result);
+    result
+}
+
+/// Creates the live statistics line and a task that keeps it updated; returns the transmitter
+/// used to stop the task along with the task's JoinHandle to be awaited
+///
+/// The line is added to the global progress bar immediately, so it's displayed above any bars
+/// added afterwards
+pub fn initialize() -> (oneshot::Sender<()>, JoinHandle<()>) {
+    log::trace!("enter: initialize");
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let bar = progress::add_status_bar();
+    let start = Instant::now();
+
+    // --stats-interval rewrites --stats-output while scanning; never stdout, which would repeat it
+    let rewrite = match CONFIGURATION.stats_output.as_str() {
+        "" | "-" => None,
+        _ if CONFIGURATION.stats_interval > 0 => {
+            Some(Duration::from_secs(CONFIGURATION.stats_interval))
+        }
+        _ => None,
+    };
+
+    let updater = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(UPDATE_INTERVAL));
+        let mut written = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    bar.set_message(&STATISTICS.summary(start.elapsed()));
+
+                    if rewrite.is_some_and(|every| written.elapsed() >= every) {
+                        if let Err(e) = write_statistics(&CONFIGURATION.stats_output, start.elapsed()) {
+                            log::warn!("Could not write statistics to {}: {}", CONFIGURATION.stats_output, e);
+                        }
+
+                        written = Instant::now();
+                    }
+                }
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+
+        // leave the final numbers on screen
+        bar.finish_with_message(&STATISTICS.summary(start.elapsed()));
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, updater);
+    (tx_stop, updater)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// responses are counted by status class, 403s are counted on their own as well
+    fn statistics_add_response_counts_by_class() {
+        let stats = Stats::new();
+
+        stats.add_response(StatusCode::OK, 10);
+        stats.add_response(StatusCode::NO_CONTENT, 10);
+        stats.add_response(StatusCode::MOVED_PERMANENTLY, 10);
+        stats.add_response(StatusCode::FORBIDDEN, 10);
+        stats.add_response(StatusCode::NOT_FOUND, 10);
+        stats.add_response(StatusCode::BAD_GATEWAY, 10);
+        stats.add_response(StatusCode::CONTINUE, 10);
+
+        assert_eq!(Stats::get(&stats.successes), 2);
+        assert_eq!(Stats::get(&stats.redirects), 1);
+        assert_eq!(Stats::get(&stats.client_errors), 2);
+        assert_eq!(Stats::get(&stats.forbidden), 1);
+        assert_eq!(Stats::get(&stats.server_errors), 1);
+        assert_eq!(stats.bytes.load(Ordering::Relaxed), 70);
+    }
+
+    #[test]
+    /// the summary line reflects requests per second, filtered responses, and running scans
+    fn statistics_summary_shows_rate_and_scans() {
+        let stats = Stats::new();
+
+        for _ in 0..10 {
+            stats.add_request();
+        }
+
+        stats.add_filtered();
+        stats.scan_started("http://localhost/");
+        stats.scan_started("http://localhost/js/");
+        stats.scan_completed("http://localhost/");
+
+        let summary = stats.summary(Duration::from_secs(2));
+
+        assert!(summary.starts_with("10 req (5/s)"));
+        assert!(summary.contains("| 1 filtered |"));
+        assert!(summary.ends_with("scans: 1 running 1 done"));
+        assert_eq!(stats.errors(), 0);
+
+        stats.add_length_mismatch();
+        assert!(stats
+            .summary(Duration::from_secs(2))
+            .contains("| 1 filtered 1 length mismatch |"));
+    }
+
+    #[tokio::test(core_threads = 1)]
+    /// failed requests are categorized by the type of error
+    async fn statistics_add_error_categorizes_errors() {
+        let stats = Stats::new();
+
+        // nothing should be listening on port 1
+        let client = reqwest::Client::new();
+        let error = client.get("http://127.0.0.1:1").send().await.unwrap_err();
+
+        stats.add_error(&error);
+
+        assert_eq!(stats.errors(), 1);
+        assert_eq!(Stats::get(&stats.connection_errors), 1);
+    }
+
+    #[test]
+    /// the json statistics include per-status counts, bytes, errors, and directory timings
+    fn statistics_as_json_includes_everything() {
+        let stats = Stats::new();
+
+        stats.add_request();
+        stats.add_request();
+        stats.add_response(StatusCode::OK, 100);
+        stats.add_response(StatusCode::FORBIDDEN, 20);
+        stats.scan_started("http://localhost/");
+        stats.scan_started("http://localhost/admin/");
+        stats.scan_completed("http://localhost/");
+
+        let json = stats.as_json(Duration::from_secs(1));
+
+        assert_eq!(json["type"], "statistics");
+        assert_eq!(json["requests"], 2);
+        assert_eq!(json["requests_per_second"], 2.0);
+        assert_eq!(json["bytes"], 120);
+        assert_eq!(json["statuses"]["200"], 1);
+        assert_eq!(json["statuses"]["403"], 1);
+        assert_eq!(json["errors"]["total"], 0);
+        assert_eq!(json["directories"][0]["url"], "http://localhost/");
+        assert!(json["directories"][0]["duration"].is_f64());
+        assert!(json["directories"][1]["duration"].is_null());
+    }
+
+    #[test]
+    /// results are counted in the directory they were found in, busiest directories first
+    fn statistics_top_directories_counts_findings() {
+        let stats = Stats::new();
+
+        stats.add_reported("http://localhost/admin/login.php");
+        stats.add_reported("http://localhost/admin/js/");
+        stats.add_reported("http://localhost/admin/?page=1");
+        stats.add_reported("http://localhost/index.html");
+        stats.add_reported("http://localhost/backup/");
+        stats.add_reported("http://localhost/api/v1/users");
+
+        assert_eq!(stats.reported(), 6);
+        assert_eq!(
+            stats.top_directories(3),
+            vec![
+                (String::from("http://localhost/"), 3),
+                (String::from("http://localhost/admin/"), 2),
+                (String::from("http://localhost/api/v1/"), 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::config::CONFIGURATION;
+use crate::statistics::STATISTICS;
+use crate::utils::{module_colorizer, status_colorizer};
+use std::collections::HashMap;
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::process;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Number of milliseconds between pushes of the metrics
+const PUSH_INTERVAL: u64 = 1000;
+
+/// Largest datagram sent, small enough not to be fragmented on common networks
+const MAX_DATAGRAM: usize = 512;
+
+/// Pushes the global statistics to a statsd server over udp (see `--statsd`)
+///
+/// Counters are sent as the change since the previous push (`feroxbuster.requests:42|c`), the
+/// request rate and running scans as gauges (`feroxbuster.requests_per_second:42|g`)
+#[derive(Debug)]
+pub struct Statsd {
+    /// socket connected to the server
+    socket: UdpSocket,
+
+    /// prefix of every metric's name, without the trailing `.`
+    prefix: String,
+
+    /// value of each counter as of the previous push
+    previous: HashMap<&'static str, u64>,
+}
+
+impl Statsd {
+    /// Connect to the statsd server at the given `host:port`
+    pub fn new(address: &str, prefix: &str) -> io::Result<Self> {
+        let target = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the host has no address"))?;
+
+        let socket = UdpSocket::bind(if target.is_ipv6() {
+            "[::]:0"
+        } else {
+            "0.0.0.0:0"
+        })?;
+        socket.connect(target)?;
+
+        Ok(Self {
+            socket,
+            prefix: prefix.trim_end_matches('.').to_string(),
+            previous: HashMap::new(),
+        })
+    }
+
+    /// Lines of the given counters and the request rate over the given time since the previous
+    /// push; counters that didn't change are left out
+    fn lines(&mut self, counters: &[(&'static str, u64)], since: Duration) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut requests = 0;
+        let mut running = 0;
+
+        for (name, value) in counters {
+            let delta = value.saturating_sub(self.previous.insert(name, *value).unwrap_or(0));
+
+            match *name {
+                "requests" => requests = delta,
+                "scans.started" => running += *value as i64,
+                "scans.completed" => running -= *value as i64,
+                _ => {}
+            }
+
+            if delta > 0 {
+                lines.push(format!("{}.{}:{}|c", self.prefix, name, delta));
+            }
+        }
+
+        let per_second = if since.as_secs_f64() > 0.0 {
+            requests as f64 / since.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        lines.push(format!(
+            "{}.requests_per_second:{:.0}|g",
+            self.prefix, per_second
+        ));
+        lines.push(format!(
+            "{}.scans.running:{}|g",
+            self.prefix,
+            running.max(0)
+        ));
+
+        lines
+    }
+
+    /// Push the given counters; `since` is the time since the previous push
+    ///
+    /// The rate is taken over at least `PUSH_INTERVAL`, so that the final push of a scan, made
+    /// however soon after the previous one, doesn't report a burst
+    pub fn push(&mut self, counters: &[(&'static str, u64)], since: Duration) -> io::Result<()> {
+        let since = since.max(Duration::from_millis(PUSH_INTERVAL));
+
+        for datagram in datagrams(&self.lines(counters, since)) {
+            self.socket.send(datagram.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Datagrams of the given lines, each holding as many lines as fit in `MAX_DATAGRAM`
+fn datagrams(lines: &[String]) -> Vec<String> {
+    let mut datagrams: Vec<String> = Vec::new();
+
+    for line in lines {
+        match datagrams.last_mut() {
+            Some(datagram) if datagram.len() + 1 + line.len() <= MAX_DATAGRAM => {
+                datagram.push('\n');
+                datagram.push_str(line);
+            }
+            _ => datagrams.push(line.clone()),
+        }
+    }
+
+    datagrams
+}
+
+/// Spawns a task that pushes the global statistics to `--statsd` every `PUSH_INTERVAL`, and once
+/// more when stopped; returns the transmitter used to stop the task along with the task's
+/// JoinHandle to be awaited, or `None` when `--statsd` wasn't given
+///
+/// Exits when the server's address can't be resolved
+pub fn initialize() -> Option<(oneshot::Sender<()>, JoinHandle<()>)> {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.statsd.is_empty() {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let mut statsd = Statsd::new(&CONFIGURATION.statsd, &CONFIGURATION.statsd_prefix)
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "{} {} Could not reach --statsd {}: {}",
+                status_colorizer("ERROR"),
+                module_colorizer("statsd::initialize"),
+                CONFIGURATION.statsd,
+                e
+            );
+            process::exit(1);
+        });
+
+    let (tx_stop, mut rx_stop) = oneshot::channel::<()>();
+
+    let pusher = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(PUSH_INTERVAL));
+        interval.tick().await; // the first tick completes right away
+        let mut pushed = Instant::now();
+
+        let mut push = |statsd: &mut Statsd| {
+            // udp doesn't wait on the server; failures are only local, i.e. no route
+            if let Err(e) = statsd.push(&STATISTICS.counters(), pushed.elapsed()) {
+                log::debug!("Could not push metrics to {}: {}", CONFIGURATION.statsd, e);
+            }
+
+            pushed = Instant::now();
+        };
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => push(&mut statsd),
+                _ = &mut rx_stop => break, // all scans complete
+            }
+        }
+
+        // the final counts, from the last push to the end of the scan
+        push(&mut statsd);
+    });
+
+    log::trace!("exit: initialize -> ({:?}, {:?})", tx_stop, pusher);
+    Some((tx_stop, pusher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// counters are sent as their change since the previous push, gauges as they are
+    fn statsd_lines_send_counter_deltas() {
+        let mut statsd = Statsd::new("127.0.0.1:8125", "ferox.").unwrap();
+
+        let counters = [
+            ("requests", 20),
+            ("errors.timeout", 0),
+            ("findings", 2),
+            ("scans.started", 3),
+            ("scans.completed", 1),
+        ];
+        assert_eq!(
+            statsd.lines(&counters, Duration::from_secs(2)),
+            vec![
+                "ferox.requests:20|c",
+                "ferox.findings:2|c",
+                "ferox.scans.started:3|c",
+                "ferox.scans.completed:1|c",
+                "ferox.requests_per_second:10|g",
+                "ferox.scans.running:2|g",
+            ]
+        );
+
+        let counters = [
+            ("requests", 25),
+            ("errors.timeout", 1),
+            ("findings", 2),
+            ("scans.started", 3),
+            ("scans.completed", 3),
+        ];
+        assert_eq!(
+            statsd.lines(&counters, Duration::from_secs(1)),
+            vec![
+                "ferox.requests:5|c",
+                "ferox.errors.timeout:1|c",
+                "ferox.scans.completed:2|c",
+                "ferox.requests_per_second:5|g",
+                "ferox.scans.running:0|g",
+            ]
+        );
+    }
+
+    #[test]
+    /// lines are packed into as few datagrams as fit
+    fn statsd_datagrams_pack_lines() {
+        let short = vec![String::from("a:1|c"), String::from("b:2|c")];
+        assert_eq!(datagrams(&short), vec!["a:1|c\nb:2|c"]);
+
+        let long: Vec<String> = (0..100).map(|i| format!("metric{:03}:1|c", i)).collect();
+        let packed = datagrams(&long);
+
+        assert!(packed.len() > 1);
+        assert!(packed.iter().all(|datagram| datagram.len() <= MAX_DATAGRAM));
+        assert_eq!(packed.join("\n"), long.join("\n"));
+    }
+
+    #[test]
+    /// pushed metrics arrive at the server
+    fn statsd_push_sends_datagrams() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let address = server.local_addr().unwrap().to_string();
+        let mut statsd = Statsd::new(&address, "feroxbuster").unwrap();
+        statsd
+            .push(&[("requests", 3)], Duration::from_secs(1))
+            .unwrap();
+
+        let mut buffer = [0u8; 1024];
+        let received = server.recv(&mut buffer).unwrap();
+        let datagram = String::from_utf8_lossy(&buffer[..received]);
+
+        assert!(datagram.starts_with("feroxbuster.requests:3|c\n"));
+        assert!(datagram.contains("feroxbuster.requests_per_second:3|g"));
+    }
+}
@@ -0,0 +1,365 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::metadata::tag;
+use crate::reporter::{get_cached_file_handle, safe_file_write};
+use crate::statistics::{Stats, STATISTICS};
+use crate::utils::{ferox_print, status_colorizer};
+use console::style;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Number of directories listed in the summary
+const TOP_DIRECTORIES: usize = 5;
+
+/// Width of the rules above and below the summary
+const WIDTH: usize = 80;
+
+/// Width of the column of counts
+const COUNT_WIDTH: usize = 10;
+
+/// Summary of a complete scan, printed and written to `--output` with `--summary`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanSummary {
+    /// number of responses per status code
+    statuses: BTreeMap<u16, usize>,
+
+    /// directories with the most findings along with their number of findings, most first
+    directories: Vec<(String, usize)>,
+
+    /// number of requests sent
+    requests: usize,
+
+    /// number of results reported
+    findings: usize,
+
+    /// number of responses filtered out
+    filtered: usize,
+
+    /// sum of the Content-Length of every response
+    bytes: u64,
+
+    /// wall time of the scan
+    elapsed: Duration,
+}
+
+/// Given number of bytes in the largest unit that keeps it at or above 1, i.e. `5.4 MiB`
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
+
+/// Given duration in hours, minutes, and seconds, i.e. `1h 2m 3s`; under a minute, it keeps
+/// tenths of a second, i.e. `4.2s`
+fn format_duration(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+
+    match seconds {
+        0..=59 => format!("{:.1}s", elapsed.as_secs_f64()),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!(
+            "{}h {}m {}s",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        ),
+    }
+}
+
+/// Rule with the given title in its middle
+fn rule(title: &str) -> String {
+    let left = (WIDTH - title.len()) / 2;
+    let right = WIDTH - title.len() - left;
+
+    format!(
+        "{}{}{}\n",
+        "─".repeat(left),
+        style(title).bold(),
+        "─".repeat(right)
+    )
+}
+
+impl ScanSummary {
+    /// Summary of the given statistics of a scan that took `elapsed`
+    pub fn new(stats: &Stats, elapsed: Duration) -> Self {
+        Self {
+            statuses: stats.statuses(),
+            directories: stats.top_directories(TOP_DIRECTORIES),
+            requests: stats.requests(),
+            findings: stats.reported(),
+            filtered: stats.filtered(),
+            bytes: stats.bytes(),
+            elapsed,
+        }
+    }
+
+    /// Number of responses per status class (`2xx`, ...), along with the status codes of each
+    fn classes(&self) -> BTreeMap<String, (usize, Vec<(u16, usize)>)> {
+        let mut classes: BTreeMap<String, (usize, Vec<(u16, usize)>)> = BTreeMap::new();
+
+        for (status, count) in &self.statuses {
+            let class = classes
+                .entry(format!("{}xx", status / 100))
+                .or_insert((0, Vec::new()));
+
+            class.0 += count;
+            class.1.push((*status, *count));
+        }
+
+        classes
+    }
+
+    /// Summary as a table
+    ///
+    /// example:
+    ///     ───────────────────────────────── Summary ──────────────────────────────────
+    ///      Responses                                                            1216
+    ///        2xx                                                                  12
+    ///          200                                                                10
+    ///          204                                                                 2
+    ///        4xx                                                                1204
+    ///          403                                                                12
+    ///          404                                                              1192
+    ///      Top directories by findings
+    ///        http://localhost/admin/                                               6
+    ///        http://localhost/                                                     4
+    ///      Requests                                                             1234
+    ///      Findings                                                               10
+    ///      Filtered                                                               40
+    ///      Transferred                                                       5.4 MiB
+    ///      Duration                                                            3m 2s
+    ///     ────────────────────────────────────────────────────────────────────────────
+    pub fn render(&self) -> String {
+        // label, plain width of the label, value
+        let mut rows: Vec<(String, usize, String)> = Vec::new();
+        let mut row = |label: String, width: usize, value: String| rows.push((label, width, value));
+
+        let responses: usize = self.statuses.values().sum();
+        row(String::from(" Responses"), 10, responses.to_string());
+
+        for (class, (count, statuses)) in self.classes() {
+            row(
+                format!("   {}", status_colorizer(&class)),
+                3 + class.len(),
+                count.to_string(),
+            );
+
+            for (status, count) in statuses {
+                let status = status.to_string();
+                row(
+                    format!("     {}", status_colorizer(&status)),
+                    5 + status.len(),
+                    count.to_string(),
+                );
+            }
+        }
+
+        if !self.directories.is_empty() {
+            row(
+                String::from(" Top directories by findings"),
+                28,
+                String::new(),
+            );
+
+            for (url, count) in &self.directories {
+                row(format!("   {}", url), 3 + url.len(), count.to_string());
+            }
+        }
+
+        row(String::from(" Requests"), 9, self.requests.to_string());
+        row(String::from(" Findings"), 9, self.findings.to_string());
+        row(String::from(" Filtered"), 9, self.filtered.to_string());
+        row(String::from(" Transferred"), 12, format_bytes(self.bytes));
+        row(String::from(" Duration"), 9, format_duration(self.elapsed));
+
+        let mut table = rule(" Summary ");
+
+        for (label, width, value) in rows {
+            if value.is_empty() {
+                table.push_str(&format!("{}\n", label));
+                continue;
+            }
+
+            // colors aren't counted in the width; long urls push their count to the right
+            let padding = WIDTH.saturating_sub(width + 1).max(COUNT_WIDTH);
+            table.push_str(&format!(
+                "{}{:>padding$}\n",
+                label,
+                value,
+                padding = padding
+            ));
+        }
+
+        table.push_str(&"─".repeat(WIDTH));
+        table.push('\n');
+        table
+    }
+
+    /// Summary as a `summary` record of json output
+    pub fn as_json(&self) -> Value {
+        // json object keys must be strings
+        let statuses: BTreeMap<String, usize> = self
+            .statuses
+            .iter()
+            .map(|(status, count)| (status.to_string(), *count))
+            .collect();
+
+        let classes: BTreeMap<String, usize> = self
+            .classes()
+            .into_iter()
+            .map(|(class, (count, _))| (class, count))
+            .collect();
+
+        let directories: Vec<Value> = self
+            .directories
+            .iter()
+            .map(|(url, count)| json!({"url": url, "findings": count}))
+            .collect();
+
+        json!({
+            "type": "summary",
+            "status_classes": classes,
+            "statuses": statuses,
+            "top_directories": directories,
+            "requests": self.requests,
+            "findings": self.findings,
+            "filtered": self.filtered,
+            "bytes": self.bytes,
+            "duration": self.elapsed.as_secs_f64(),
+        })
+    }
+}
+
+/// Print the summary of the scan that took `elapsed`, and write it to `--output`, when
+/// `--summary` was used; called once every result was reported
+pub fn report(elapsed: Duration) {
+    log::trace!("enter: report({:?})", elapsed);
+
+    if !CONFIGURATION.summary {
+        log::trace!("exit: report");
+        return;
+    }
+
+    let summary = ScanSummary::new(&STATISTICS, elapsed);
+    let table = summary.render();
+
+    if !CONFIGURATION.silent {
+        ferox_print(&table, &PROGRESS_PRINTER);
+    }
+
+    // --format sarif writes a single document to --output, there's no room for the summary
+    if !CONFIGURATION.output.is_empty() && CONFIGURATION.format != "sarif" {
+        let contents = if CONFIGURATION.json {
+            let mut record = summary.as_json();
+            tag(&mut record);
+            format!("{}\n", record)
+        } else {
+            table
+        };
+
+        if let Some(file) = get_cached_file_handle(&CONFIGURATION.output) {
+            safe_file_write(&contents, file);
+        }
+    }
+
+    log::trace!("exit: report");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    /// summary of a small scan
+    fn summary() -> ScanSummary {
+        let stats = Stats::new();
+
+        for _ in 0..4 {
+            stats.add_request();
+        }
+
+        stats.add_response(StatusCode::OK, 1024);
+        stats.add_response(StatusCode::NO_CONTENT, 0);
+        stats.add_response(StatusCode::MOVED_PERMANENTLY, 0);
+        stats.add_response(StatusCode::NOT_FOUND, 4096);
+        stats.add_filtered();
+        stats.add_reported("http://localhost/admin/login.php");
+        stats.add_reported("http://localhost/admin/");
+        stats.add_reported("http://localhost/admin/js/");
+
+        ScanSummary::new(&stats, Duration::from_secs(62))
+    }
+
+    #[test]
+    /// bytes and durations are written in the largest unit that fits
+    fn summary_formats_bytes_and_durations() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(5 * 1024 * 1024 + 400 * 1024), "5.4 MiB");
+        assert_eq!(format_duration(Duration::from_millis(4200)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(182)), "3m 2s");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1h 2m 3s");
+    }
+
+    #[test]
+    /// the table groups status codes by class and lists directories by findings
+    fn summary_render_groups_statuses_by_class() {
+        let table = console::strip_ansi_codes(&summary().render()).to_string();
+        let lines: Vec<&str> = table.lines().map(str::trim_end).collect();
+
+        assert!(lines[0].contains(" Summary "));
+        assert!(lines.iter().all(|line| line.chars().count() <= WIDTH));
+
+        let row = |label: &str| {
+            lines
+                .iter()
+                .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+                .find(|tokens| tokens.first() == Some(&label))
+                .unwrap()
+        };
+
+        assert_eq!(row("Responses"), ["Responses", "4"]);
+        assert_eq!(row("2xx"), ["2xx", "2"]);
+        assert_eq!(row("204"), ["204", "1"]);
+        assert_eq!(row("4xx"), ["4xx", "1"]);
+        assert_eq!(
+            row("http://localhost/admin/"),
+            ["http://localhost/admin/", "2"]
+        );
+        assert_eq!(row("http://localhost/"), ["http://localhost/", "1"]);
+        assert_eq!(row("Filtered"), ["Filtered", "1"]);
+        assert_eq!(row("Transferred"), ["Transferred", "5.0", "KiB"]);
+        assert_eq!(row("Duration"), ["Duration", "1m", "2s"]);
+    }
+
+    #[test]
+    /// the json record holds the same numbers as the table
+    fn summary_as_json_includes_everything() {
+        let record = summary().as_json();
+
+        assert_eq!(record["type"], "summary");
+        assert_eq!(record["status_classes"]["2xx"], 2);
+        assert_eq!(record["status_classes"]["3xx"], 1);
+        assert_eq!(record["statuses"]["404"], 1);
+        assert_eq!(
+            record["top_directories"][0]["url"],
+            "http://localhost/admin/"
+        );
+        assert_eq!(record["top_directories"][0]["findings"], 2);
+        assert_eq!(record["requests"], 4);
+        assert_eq!(record["findings"], 3);
+        assert_eq!(record["filtered"], 1);
+        assert_eq!(record["bytes"], 5120);
+        assert_eq!(record["duration"], 62.0);
+    }
+}
@@ -0,0 +1,596 @@
+use crate::utils::{
+    format_selected_headers, module_colorizer, rfc3339_timestamp, status_colorizer,
+};
+use crate::FeroxResponse;
+use native_tls::{TlsConnector, TlsStream};
+use reqwest::Url;
+use std::io::{self, Write};
+use std::net::{TcpStream, UdpSocket};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Mutex, Once};
+use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
+
+/// Syslog facility used for all messages (1 == user-level messages)
+const FACILITY: u8 = 1;
+
+/// IANA private enterprise number reserved for documentation, used as the SD-ID suffix of
+/// feroxbuster's structured data elements (RFC 5424 section 7.2.2)
+const ENTERPRISE_ID: u32 = 32473;
+
+/// Name reported in the APP-NAME field of every message
+const APP_NAME: &str = "feroxbuster";
+
+/// Singleton handle to the thread that owns the syslog connection; used from two locations:
+///     - [logger::initialize](../logger/fn.initialize.html) (errors and warnings)
+///     - [reporter](../reporter/index.html) (findings)
+static mut SYSLOG_WRITER: Option<&'static SyslogHandle> = None;
+
+/// An initializer Once variable used to create `SYSLOG_WRITER`
+static INIT: Once = Once::new();
+
+/// Syslog message severities used by feroxbuster (RFC 5424 section 6.2.1)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Severity {
+    /// error conditions
+    Error = 3,
+
+    /// warning conditions
+    Warning = 4,
+
+    /// normal but significant condition
+    Notice = 5,
+}
+
+/// Supported transports for delivering messages to a collector
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Protocol {
+    /// RFC 5426, one message per datagram
+    Udp,
+
+    /// RFC 6587, octet-counted framing
+    Tcp,
+
+    /// RFC 5425, octet-counted framing over TLS
+    Tls,
+}
+
+/// An open connection to a collector
+enum Transport {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+/// A message waiting to be sent by the writer's thread
+struct Entry {
+    /// severity of the message
+    severity: Severity,
+
+    /// when the message was queued, used as its TIMESTAMP
+    time: SystemTime,
+
+    /// value for the MSGID field, also the SD-ID of the structured data
+    msg_id: &'static str,
+
+    /// structured data parameters, as name/value pairs
+    data: Vec<(String, String)>,
+
+    /// free-form message
+    msg: String,
+}
+
+/// Hands messages to a dedicated thread that owns the connection to the collector; connecting
+/// and writing are blocking, and must not hold up the async tasks that log or report findings
+pub struct SyslogHandle {
+    /// transmitter of the writer's thread, taken when closed
+    tx: Mutex<Option<Sender<Entry>>>,
+
+    /// the writer's thread, joined when closed
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SyslogHandle {
+    /// Move the given writer to its own thread, which sends every queued message in order
+    fn spawn(mut writer: SyslogWriter) -> io::Result<Self> {
+        let (tx, rx) = channel::<Entry>();
+
+        let thread = thread::Builder::new()
+            .name(String::from("syslog"))
+            .spawn(move || {
+                for entry in rx {
+                    let data: Vec<(&str, String)> = entry
+                        .data
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.clone()))
+                        .collect();
+
+                    let sent =
+                        writer.send_at(entry.severity, entry.time, entry.msg_id, &data, &entry.msg);
+
+                    // log messages that can't be sent are dropped, logging them would only queue
+                    // another one
+                    if let (Err(e), "finding") = (sent, entry.msg_id) {
+                        let url = data.iter().find(|(name, _)| *name == "url");
+
+                        log::error!(
+                            "Could not send {} to syslog collector: {}",
+                            url.map_or("a finding", |(_, url)| url.as_str()),
+                            e
+                        );
+                    }
+                }
+            })?;
+
+        Ok(SyslogHandle {
+            tx: Mutex::new(Some(tx)),
+            thread: Mutex::new(Some(thread)),
+        })
+    }
+
+    /// Queue a message for the writer's thread; dropped once the handle was closed
+    fn queue(&self, entry: Entry) {
+        if let Ok(tx) = self.tx.lock() {
+            if let Some(tx) = tx.as_ref() {
+                tx.send(entry).unwrap_or_default();
+            }
+        }
+    }
+
+    /// Stop accepting messages, then wait for those already queued to be sent
+    fn close(&self) {
+        if let Ok(mut tx) = self.tx.lock() {
+            tx.take();
+        }
+
+        let thread = self.thread.lock().ok().and_then(|mut thread| thread.take());
+
+        if let Some(thread) = thread {
+            thread.join().unwrap_or_default();
+        }
+    }
+}
+
+/// Writes RFC 5424 formatted messages to a syslog collector
+pub struct SyslogWriter {
+    /// transport used to reach the collector
+    protocol: Protocol,
+
+    /// host portion of the collector's address
+    host: String,
+
+    /// port portion of the collector's address
+    port: u16,
+
+    /// the open connection, reopened on write failure for stream based transports
+    transport: Option<Transport>,
+
+    /// value for the HOSTNAME field
+    hostname: String,
+}
+
+impl SyslogWriter {
+    /// Create a new `SyslogWriter` from a destination of the form `[udp|tcp|tls]://host[:port]`
+    ///
+    /// A destination without a scheme is treated as udp. When no port is given, the standard
+    /// port for the transport is used (udp: 514, tcp: 601, tls: 6514)
+    pub fn new(destination: &str) -> io::Result<Self> {
+        let (protocol, host, port) = parse_destination(destination)?;
+
+        let mut writer = SyslogWriter {
+            protocol,
+            host,
+            port,
+            transport: None,
+            hostname: local_hostname(),
+        };
+
+        writer.transport = Some(writer.connect()?);
+
+        Ok(writer)
+    }
+
+    /// Open a new connection to the collector
+    fn connect(&self) -> io::Result<Transport> {
+        let address = (self.host.as_str(), self.port);
+
+        match self.protocol {
+            Protocol::Udp => {
+                let socket = UdpSocket::bind(if self.host.contains(':') {
+                    "[::]:0"
+                } else {
+                    "0.0.0.0:0"
+                })?;
+                socket.connect(address)?;
+                Ok(Transport::Udp(socket))
+            }
+            Protocol::Tcp => Ok(Transport::Tcp(TcpStream::connect(address)?)),
+            Protocol::Tls => {
+                let stream = TcpStream::connect(address)?;
+                let connector = TlsConnector::new().map_err(to_io_error)?;
+                let tls_stream = connector.connect(&self.host, stream).map_err(to_io_error)?;
+                Ok(Transport::Tls(Box::new(tls_stream)))
+            }
+        }
+    }
+
+    /// Format and send a single message; stream transports are reconnected once on failure
+    pub fn send(
+        &mut self,
+        severity: Severity,
+        msg_id: &str,
+        data: &[(&str, String)],
+        msg: &str,
+    ) -> io::Result<()> {
+        self.send_at(severity, SystemTime::now(), msg_id, data, msg)
+    }
+
+    /// Same as `send`, for a message that happened at the given time
+    fn send_at(
+        &mut self,
+        severity: Severity,
+        time: SystemTime,
+        msg_id: &str,
+        data: &[(&str, String)],
+        msg: &str,
+    ) -> io::Result<()> {
+        let message = format_message(severity, time, &self.hostname, msg_id, data, msg);
+
+        let frame = match self.protocol {
+            Protocol::Udp => message.into_bytes(),
+            Protocol::Tcp | Protocol::Tls => frame_message(&message),
+        };
+
+        if self.write_frame(&frame).is_err() && self.protocol != Protocol::Udp {
+            // the collector may have dropped an idle connection, try once more
+            self.transport = Some(self.connect()?);
+            return self.write_frame(&frame);
+        }
+
+        Ok(())
+    }
+
+    /// Write the given bytes using the current transport
+    fn write_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        match self.transport.as_mut() {
+            Some(Transport::Udp(socket)) => socket.send(frame).map(|_| ()),
+            Some(Transport::Tcp(stream)) => stream.write_all(frame).and_then(|_| stream.flush()),
+            Some(Transport::Tls(stream)) => stream.write_all(frame).and_then(|_| stream.flush()),
+            None => Err(io::Error::new(io::ErrorKind::NotConnected, "not connected")),
+        }
+    }
+}
+
+/// Simple helper to convert any displayable error into an `io::Error`
+fn to_io_error<E: std::fmt::Display>(error: E) -> io::Error {
+    io::Error::other(error.to_string())
+}
+
+/// Split a destination into its protocol, host, and port
+fn parse_destination(destination: &str) -> io::Result<(Protocol, String, u16)> {
+    let destination = if destination.contains("://") {
+        destination.to_string()
+    } else {
+        format!("udp://{}", destination)
+    };
+
+    let url = Url::parse(&destination).map_err(to_io_error)?;
+
+    let (protocol, default_port) = match url.scheme() {
+        "udp" => (Protocol::Udp, 514),
+        "tcp" => (Protocol::Tcp, 601),
+        "tls" => (Protocol::Tls, 6514),
+        scheme => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported syslog protocol: {}", scheme),
+            ));
+        }
+    };
+
+    let host = match url.host_str() {
+        Some(host) if !host.is_empty() => host.trim_matches(|c| c == '[' || c == ']'),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no host found in {}", destination),
+            ));
+        }
+    };
+
+    Ok((
+        protocol,
+        host.to_string(),
+        url.port().unwrap_or(default_port),
+    ))
+}
+
+/// Build an RFC 5424 message
+///
+/// `<PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA MSG`
+fn format_message(
+    severity: Severity,
+    time: SystemTime,
+    hostname: &str,
+    msg_id: &str,
+    data: &[(&str, String)],
+    msg: &str,
+) -> String {
+    let priority = FACILITY as u16 * 8 + severity as u16;
+
+    let structured_data = if data.is_empty() {
+        String::from("-") // NILVALUE
+    } else {
+        let params: Vec<String> = data
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape_param_value(value)))
+            .collect();
+
+        format!("[{}@{} {}]", msg_id, ENTERPRISE_ID, params.join(" "))
+    };
+
+    format!(
+        "<{}>1 {} {} {} {} {} {} {}",
+        priority,
+        rfc3339_timestamp(time),
+        hostname,
+        APP_NAME,
+        std::process::id(),
+        msg_id,
+        structured_data,
+        msg.trim_end()
+    )
+}
+
+/// Escape the characters that RFC 5424 requires to be escaped inside a PARAM-VALUE (", \, and ])
+fn escape_param_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c == '"' || c == '\\' || c == ']' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Prefix the message with its length in octets (RFC 6587 section 3.4.1)
+fn frame_message(message: &str) -> Vec<u8> {
+    format!("{} {}", message.len(), message).into_bytes()
+}
+
+/// Determine the value of the HOSTNAME field; NILVALUE when it can't be determined
+pub fn local_hostname() -> String {
+    #[cfg(unix)]
+    {
+        let mut buffer = [0u8; 256];
+
+        // safe: the buffer is valid for the given length and gethostname null terminates the
+        // result when it fits
+        let result =
+            unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+
+        if result == 0 {
+            let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+            let hostname = String::from_utf8_lossy(&buffer[..end]).to_string();
+
+            if !hostname.is_empty() {
+                return hostname;
+            }
+        }
+    }
+
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| String::from("-"))
+}
+
+// This function will only try to connect once, and will otherwise always return the value
+// returned from the first invocation (see reporter::get_cached_file_handle).
+//
+// Connection errors are written directly to stderr, since this function is called from within
+// the logger, and calling log::... while already processing some logging output causes the
+// second entry to be injected into the first.
+pub fn get_cached_syslog_writer(destination: &str) -> Option<&'static SyslogHandle> {
+    unsafe {
+        INIT.call_once(|| {
+            if destination.is_empty() {
+                return;
+            }
+
+            match SyslogWriter::new(destination).and_then(SyslogHandle::spawn) {
+                Ok(handle) => {
+                    SYSLOG_WRITER = Some(Box::leak(Box::new(handle)));
+                }
+                Err(e) => {
+                    eprintln!(
+                        "{} {} Could not connect to syslog collector {}: {}",
+                        status_colorizer("ERROR"),
+                        module_colorizer("syslog::get_cached_syslog_writer"),
+                        destination,
+                        e
+                    );
+                }
+            }
+        });
+        *std::ptr::addr_of!(SYSLOG_WRITER)
+    }
+}
+
+/// Wait for every message queued so far to be sent to the syslog collector; messages queued
+/// afterwards are dropped
+pub fn close() {
+    if let Some(handle) = unsafe { *std::ptr::addr_of!(SYSLOG_WRITER) } {
+        handle.close();
+    }
+}
+
+/// Queue a reported response to be sent to the syslog collector as a `finding` message
+///
+/// `headers` are the headers selected with `--show-headers`, sent as `header.NAME` parameters
+pub fn send_finding(writer: &SyslogHandle, response: &FeroxResponse, headers: &[(String, String)]) {
+    let mut data = vec![
+        (
+            String::from("status"),
+            response.status().as_str().to_string(),
+        ),
+        (String::from("size"), response.content_length().to_string()),
+        (String::from("url"), response.url().to_string()),
+    ];
+
+    if let Some(body_hash) = response.body_hash() {
+        data.push((body_hash.algorithm.to_string(), body_hash.hex.clone()));
+    }
+
+    for (name, value) in headers {
+        // PARAM-NAMEs are limited to 32 characters (RFC 5424 section 6.3.3)
+        let name = format!("header.{}", name).chars().take(32).collect();
+        data.push((name, value.clone()));
+    }
+
+    let msg = format!(
+        "{} {} {}{}",
+        response.status().as_str(),
+        response.content_length(),
+        response.url(),
+        format_selected_headers(headers)
+    );
+
+    writer.queue(Entry {
+        severity: Severity::Notice,
+        time: SystemTime::now(),
+        msg_id: "finding",
+        data,
+        msg,
+    });
+}
+
+/// Queue a log record's message to be sent to the syslog collector as a `log` message
+///
+/// Only called from within the logger, so errors can't be logged (see `get_cached_syslog_writer`)
+pub fn send_log(writer: &SyslogHandle, severity: Severity, msg: &str) {
+    writer.queue(Entry {
+        severity,
+        time: SystemTime::now(),
+        msg_id: "log",
+        data: Vec::new(),
+        msg: msg.to_string(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    /// a destination without a scheme defaults to udp on the standard port
+    fn syslog_parse_destination_defaults_to_udp() {
+        let (protocol, host, port) = parse_destination("127.0.0.1").unwrap();
+        assert_eq!(protocol, Protocol::Udp);
+        assert_eq!(host, "127.0.0.1");
+        assert_eq!(port, 514);
+    }
+
+    #[test]
+    /// each scheme maps to its protocol and standard port, explicit ports are respected
+    fn syslog_parse_destination_with_schemes() {
+        let (protocol, _, port) = parse_destination("tcp://collector").unwrap();
+        assert_eq!((protocol, port), (Protocol::Tcp, 601));
+
+        let (protocol, _, port) = parse_destination("tls://collector").unwrap();
+        assert_eq!((protocol, port), (Protocol::Tls, 6514));
+
+        let (protocol, host, port) = parse_destination("udp://[::1]:1514").unwrap();
+        assert_eq!(
+            (protocol, host.as_str(), port),
+            (Protocol::Udp, "::1", 1514)
+        );
+    }
+
+    #[test]
+    /// an unknown scheme is an error
+    fn syslog_parse_destination_with_unknown_scheme() {
+        assert!(parse_destination("http://collector").is_err());
+    }
+
+    #[test]
+    /// messages follow the RFC 5424 layout, including structured data
+    fn syslog_format_message_with_structured_data() {
+        let time = UNIX_EPOCH + Duration::from_secs(1);
+        let data = [
+            ("status", String::from("200")),
+            ("url", String::from("http://localhost/\"]")),
+        ];
+        let msg = format_message(Severity::Notice, time, "box", "finding", &data, "stuff\n");
+
+        assert_eq!(
+            msg,
+            format!(
+                "<13>1 1970-01-01T00:00:01.000Z box feroxbuster {} finding [finding@32473 status=\"200\" url=\"http://localhost/\\\"\\]\"] stuff",
+                std::process::id()
+            )
+        );
+    }
+
+    #[test]
+    /// messages without structured data use the NILVALUE
+    fn syslog_format_message_without_structured_data() {
+        let msg = format_message(Severity::Error, UNIX_EPOCH, "box", "log", &[], "bad");
+        assert!(msg.starts_with("<11>1 "));
+        assert!(msg.ends_with(" log - bad"));
+    }
+
+    #[test]
+    /// stream transports prefix the message with its length
+    fn syslog_frame_message_uses_octet_counting() {
+        assert_eq!(frame_message("<13>1 stuff"), b"11 <13>1 stuff".to_vec());
+    }
+
+    #[test]
+    /// a message sent over udp arrives as a single datagram
+    fn syslog_writer_sends_udp_datagram() {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        collector
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let address = format!("udp://{}", collector.local_addr().unwrap());
+
+        let mut writer = SyslogWriter::new(&address).unwrap();
+        writer
+            .send(Severity::Warning, "log", &[], "The Dude abides.")
+            .unwrap();
+
+        let mut buffer = [0u8; 1024];
+        let received = collector.recv(&mut buffer).unwrap();
+        let message = String::from_utf8_lossy(&buffer[..received]);
+
+        assert!(message.starts_with("<12>1 "));
+        assert!(message.ends_with("The Dude abides."));
+    }
+
+    #[test]
+    /// queued messages are sent by the writer's thread, by the time the handle is closed
+    fn syslog_handle_sends_queued_messages_before_closing() {
+        let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+        collector
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let address = format!("udp://{}", collector.local_addr().unwrap());
+
+        let handle = SyslogHandle::spawn(SyslogWriter::new(&address).unwrap()).unwrap();
+        send_log(&handle, Severity::Error, "first");
+        send_log(&handle, Severity::Warning, "second");
+        handle.close();
+
+        // dropped, the thread is gone
+        send_log(&handle, Severity::Error, "third");
+
+        let mut buffer = [0u8; 1024];
+
+        for expected in &["first", "second"] {
+            let received = collector.recv(&mut buffer).unwrap();
+            assert!(String::from_utf8_lossy(&buffer[..received]).ends_with(expected));
+        }
+    }
+}
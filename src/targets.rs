@@ -0,0 +1,313 @@
+use crate::client;
+use crate::config::{string_or_list, Configuration};
+use crate::limiter::RateLimiter;
+use crate::methods;
+use crate::proxy;
+use crate::scanner::{adaptive_concurrency, ScanOptions};
+use crate::FeroxResult;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+
+/// A url to scan, along with any options that should differ from the global configuration
+/// when scanning it
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Target {
+    /// url to scan
+    pub url: String,
+
+    /// headers added to (or replacing) the global headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// cookies sent as the target's `Cookie` header, along with any it already has
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
+
+    /// proxy used instead of the global proxy; an empty string sends requests directly
+    pub proxy: Option<String>,
+
+    /// whether or not invalid certificates are accepted, instead of the global `--insecure`
+    pub insecure: Option<bool>,
+
+    /// wordlist(s) used instead of the global wordlist(s)
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub wordlist: Vec<String>,
+
+    /// extension(s) used instead of the global extensions
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub extensions: Vec<String>,
+
+    /// maximum recursion depth used instead of the global depth
+    pub depth: Option<usize>,
+
+    /// maximum number of requests per second used instead of the global rate limit
+    pub rate_limit: Option<usize>,
+}
+
+/// Layout of a `--targets` file, i.e.
+///
+/// ```toml
+/// [[target]]
+/// url = "https://app.example.com"
+/// depth = 2
+/// headers = { Authorization = "Bearer abc" }
+/// cookies = { session = "0xdeadbeef" }
+/// proxy = "http://127.0.0.1:8080"
+/// insecure = true
+///
+/// [[target]]
+/// url = "https://static.example.com"
+/// wordlist = "/wordlists/small.txt"
+/// extensions = ["js", "css"]
+/// rate_limit = 20
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TargetsFile {
+    /// every `[[target]]` table
+    #[serde(default)]
+    target: Vec<Target>,
+}
+
+impl Target {
+    /// A target without any overrides, i.e. one given with `--url` or `--stdin`
+    pub fn from_url(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Wordlist(s) with which to scan the target
+    pub fn wordlist<'a>(&'a self, config: &'a Configuration) -> &'a [String] {
+        if self.wordlist.is_empty() {
+            &config.wordlist
+        } else {
+            &self.wordlist
+        }
+    }
+
+    /// Extension(s) with which to scan the target
+    pub fn extensions<'a>(&'a self, config: &'a Configuration) -> &'a [String] {
+        if self.extensions.is_empty() {
+            &config.extensions
+        } else {
+            &self.extensions
+        }
+    }
+
+    /// Headers added to the target's requests, with its cookies joined into the `Cookie` header
+    pub fn request_headers(&self) -> HashMap<String, String> {
+        let mut headers = self.headers.clone();
+
+        if self.cookies.is_empty() {
+            return headers;
+        }
+
+        let mut cookies: Vec<String> = self
+            .cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        cookies.sort();
+
+        // a Cookie header given along with the cookies keeps its place in front of them
+        let existing = headers
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case("cookie"))
+            .cloned();
+
+        if let Some(value) = existing.and_then(|name| headers.remove(&name)) {
+            cookies.insert(0, value);
+        }
+
+        headers.insert(String::from("Cookie"), cookies.join("; "));
+        headers
+    }
+
+    /// Client with which to scan the target; the global client is shared unless the target
+    /// overrides its proxy or `--insecure`
+    pub fn client(&self, config: &Configuration) -> Client {
+        if self.proxy.is_none() && self.insecure.is_none() {
+            return config.client.clone();
+        }
+
+        // --proxy-auth belongs to --proxy, a target's own proxy carries its credentials in its url
+        let proxy = match &self.proxy {
+            Some(proxy) => proxy.clone(),
+            None => proxy::resolve(&config.proxy, &config.proxy_auth, config.proxy_ntlm),
+        };
+
+        client::initialize(
+            config.timeout,
+            &config.useragent,
+            config.redirects,
+            self.insecure.unwrap_or(config.insecure),
+            Some(&proxy),
+        )
+    }
+
+    /// Options with which to scan the target; the target's headers and cookies are added to its
+    /// requests by the [Headers](../hooks/struct.Headers.html) hook
+    pub fn scan_options(&self, config: &Configuration) -> ScanOptions {
+        log::trace!("enter: scan_options({:?})", self);
+
+        let options = ScanOptions {
+            client: self.client(config),
+            depth: self.depth.unwrap_or(config.depth),
+            // a target's own rate limit isn't replaced by the rate profiles
+            limiter: match self.rate_limit {
+                Some(rate_limit) => RateLimiter::new(rate_limit).map(RateLimiter::adjustable),
+                None => RateLimiter::scheduled(config.rate_limit),
+            },
+            concurrency: adaptive_concurrency(config),
+            extensions: self.extensions(config).to_vec(),
+            method: methods::parse(&config.method),
+            fallbacks: methods::fallbacks(&config.method),
+        };
+
+        log::trace!("exit: scan_options -> {:?}", options);
+        options
+    }
+}
+
+/// Parse the targets from the contents of a `--targets` file; every target needs a url
+pub fn parse(contents: &str) -> FeroxResult<Vec<Target>> {
+    let parsed: TargetsFile = toml::from_str(contents)?;
+
+    if let Some(index) = parsed.target.iter().position(|t| t.url.trim().is_empty()) {
+        return Err(format!("target #{} doesn't have a url", index + 1).into());
+    }
+
+    Ok(parsed.target)
+}
+
+/// Read the targets listed in a `--targets` file
+pub fn from_file(path: &str) -> FeroxResult<Vec<Target>> {
+    log::trace!("enter: from_file({})", path);
+
+    let targets = parse(&read_to_string(path)?)?;
+
+    log::info!("Loaded {} targets from {}", targets.len(), path);
+    log::trace!("exit: from_file -> {:?}", targets);
+
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// every override is optional, wordlist may be a single path or a list
+    fn targets_parse_reads_overrides() {
+        let contents = r#"
+            [[target]]
+            url = "https://app.example.com"
+            depth = 2
+            rate_limit = 20
+            wordlist = "/wordlists/api.txt"
+            headers = { Authorization = "Bearer abc" }
+            cookies = { session = "abc" }
+            proxy = ""
+            insecure = true
+
+            [[target]]
+            url = "https://static.example.com"
+            wordlist = ["/wordlists/one.txt", "/wordlists/two.txt"]
+            extensions = "js"
+        "#;
+
+        let targets = parse(contents).unwrap();
+        assert_eq!(targets.len(), 2);
+
+        assert_eq!(targets[0].url, "https://app.example.com");
+        assert_eq!(targets[0].depth, Some(2));
+        assert_eq!(targets[0].rate_limit, Some(20));
+        assert_eq!(targets[0].wordlist, vec!["/wordlists/api.txt"]);
+        assert_eq!(targets[0].headers["Authorization"], "Bearer abc");
+        assert_eq!(targets[0].cookies["session"], "abc");
+        assert_eq!(targets[0].proxy.as_deref(), Some(""));
+        assert_eq!(targets[0].insecure, Some(true));
+        assert!(targets[0].extensions.is_empty());
+
+        assert_eq!(targets[1].depth, None);
+        assert_eq!(targets[1].rate_limit, None);
+        assert!(targets[1].headers.is_empty());
+        assert!(targets[1].cookies.is_empty());
+        assert_eq!(targets[1].proxy, None);
+        assert_eq!(targets[1].insecure, None);
+        assert_eq!(
+            targets[1].wordlist,
+            vec!["/wordlists/one.txt", "/wordlists/two.txt"]
+        );
+        assert_eq!(targets[1].extensions, vec!["js"]);
+    }
+
+    #[test]
+    /// targets without a url and misspelled options are errors
+    fn targets_parse_rejects_bad_targets() {
+        assert!(parse("[[target]]\ndepth = 2\n").is_err());
+        assert!(parse("[[target]]\nurl = \"\"\n").is_err());
+        assert!(parse("[[target]]\nurl = \"http://localhost\"\ndpeth = 2\n").is_err());
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    /// targets fall back to the global configuration for anything they don't override
+    fn targets_scan_options_fall_back_to_configuration() {
+        let config = Configuration {
+            depth: 4,
+            rate_limit: 100,
+            wordlist: vec![String::from("/global.txt")],
+            extensions: vec![String::from("php")],
+            ..Configuration::default()
+        };
+
+        let target = Target::from_url("http://localhost");
+        let options = target.scan_options(&config);
+        assert_eq!(options.depth, 4);
+        assert!(options.limiter.is_some());
+        assert_eq!(target.wordlist(&config), ["/global.txt"]);
+        assert_eq!(options.extensions, ["php"]);
+
+        let target = Target {
+            depth: Some(1),
+            rate_limit: Some(0),
+            wordlist: vec![String::from("/mine.txt")],
+            extensions: vec![String::from("jsp")],
+            ..target
+        };
+        let options = target.scan_options(&config);
+        assert_eq!(options.depth, 1);
+        assert!(options.limiter.is_none());
+        assert_eq!(target.wordlist(&config), ["/mine.txt"]);
+        assert_eq!(options.extensions, ["jsp"]);
+    }
+
+    #[test]
+    /// cookies are joined into the Cookie header, after any cookie header given as a header
+    fn targets_request_headers_join_cookies() {
+        let mut target = Target::from_url("http://localhost");
+        assert!(target.request_headers().is_empty());
+
+        target
+            .cookies
+            .insert(String::from("session"), String::from("abc"));
+        target
+            .cookies
+            .insert(String::from("lang"), String::from("en"));
+        assert_eq!(target.request_headers()["Cookie"], "lang=en; session=abc");
+
+        target
+            .headers
+            .insert(String::from("cookie"), String::from("tracking=1"));
+        let headers = target.request_headers();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["Cookie"], "tracking=1; lang=en; session=abc");
+    }
+}
@@ -0,0 +1,303 @@
+use crate::compare::Change;
+use crate::config::CONFIGURATION;
+use crate::utils::{module_colorizer, status_colorizer};
+use crate::FeroxResponse;
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use std::process;
+
+/// Fields that may be used in `--output-template`, along with `header.NAME` for any header
+pub const FIELDS: [&str; 12] = [
+    "status",
+    "size",
+    "url",
+    "path",
+    "redirect",
+    "content_type",
+    "change",
+    "listing",
+    "body_hash",
+    "favicon_hash",
+    "hops",
+    "redirected_from",
+];
+
+/// Prefix of the fields that are a response's header, i.e. `header.server`
+const HEADER_FIELD: &str = "header.";
+
+/// Part of a parsed template
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// text written as-is
+    Text(String),
+
+    /// value of a field, `{{field}}`
+    Field(String),
+
+    /// `{{#if field}}then{{else}}otherwise{{/if}}`; a field is true when it isn't empty
+    If {
+        /// field the condition is on
+        field: String,
+
+        /// written when the field isn't empty
+        then: Vec<Node>,
+
+        /// written when the field is empty
+        otherwise: Vec<Node>,
+    },
+}
+
+/// Format of each result's line, given with `--output-template`
+///
+/// Fields are written as `{{field}}`, and `{{#if field}}...{{else}}...{{/if}}` only writes what
+/// applies, i.e. `{{status}} {{size}} {{url}}{{#if redirect}} -> {{redirect}}{{/if}}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// parsed template
+    nodes: Vec<Node>,
+}
+
+/// Whether or not the given name is that of a field
+fn is_field(name: &str) -> bool {
+    FIELDS.contains(&name) || (name.starts_with(HEADER_FIELD) && name.len() > HEADER_FIELD.len())
+}
+
+/// Nodes parsed from `template` until the `{{else}}` or `{{/if}}` closing the current block, if
+/// any; returns them along with the tag that ended them (`None` at the end of the template)
+fn parse_nodes<'a>(template: &mut &'a str) -> Result<(Vec<Node>, Option<&'a str>), String> {
+    let mut nodes = Vec::new();
+
+    loop {
+        let start = match template.find("{{") {
+            Some(start) => start,
+            None => {
+                if !template.is_empty() {
+                    nodes.push(Node::Text(template.to_string()));
+                }
+                *template = "";
+                return Ok((nodes, None));
+            }
+        };
+
+        if start > 0 {
+            nodes.push(Node::Text(template[..start].to_string()));
+        }
+
+        let end = template[start..]
+            .find("}}")
+            .ok_or_else(|| format!("{{{{ at {} is never closed", &template[start..]))?;
+
+        let tag = template[start + 2..start + end].trim();
+        *template = &template[start + end + 2..];
+
+        if tag == "else" || tag == "/if" {
+            return Ok((nodes, Some(tag)));
+        }
+
+        if let Some(field) = tag.strip_prefix("#if ") {
+            let field = field.trim();
+
+            if !is_field(field) {
+                return Err(format!("{} isn't a field", field));
+            }
+
+            let (then, ended_by) = parse_nodes(template)?;
+
+            let otherwise = match ended_by {
+                Some("/if") => Vec::new(),
+                Some(_) => match parse_nodes(template)? {
+                    (otherwise, Some("/if")) => otherwise,
+                    _ => return Err(format!("{{{{#if {}}}}} is never closed", field)),
+                },
+                None => return Err(format!("{{{{#if {}}}}} is never closed", field)),
+            };
+
+            nodes.push(Node::If {
+                field: field.to_string(),
+                then,
+                otherwise,
+            });
+            continue;
+        }
+
+        if !is_field(tag) {
+            return Err(format!("{} isn't a field", tag));
+        }
+
+        nodes.push(Node::Field(tag.to_string()));
+    }
+}
+
+/// Write the given nodes, with the value of each field given by `value`
+fn render_nodes(nodes: &[Node], value: &dyn Fn(&str) -> String, rendered: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => rendered.push_str(text),
+            Node::Field(field) => rendered.push_str(&value(field)),
+            Node::If {
+                field,
+                then,
+                otherwise,
+            } => {
+                if value(field).is_empty() {
+                    render_nodes(otherwise, value, rendered);
+                } else {
+                    render_nodes(then, value, rendered);
+                }
+            }
+        }
+    }
+}
+
+impl Template {
+    /// Parse the given template; fails on unknown fields and unbalanced blocks
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut rest = template;
+
+        match parse_nodes(&mut rest)? {
+            (nodes, None) => Ok(Self { nodes }),
+            (_, Some(tag)) => Err(format!("{{{{{}}}}} doesn't close any {{{{#if}}}}", tag)),
+        }
+    }
+
+    /// Render the template, with the value of each field given by `value`; fields without a
+    /// value are empty
+    pub fn render(&self, value: &dyn Fn(&str) -> String) -> String {
+        let mut rendered = String::new();
+        render_nodes(&self.nodes, value, &mut rendered);
+        rendered
+    }
+
+    /// Render the template for the given response, flagged with the given change when compared
+    /// to a previous run; `listing` is whether or not the response is a directory listing
+    pub fn render_response(
+        &self,
+        response: &FeroxResponse,
+        change: Option<Change>,
+        listing: bool,
+    ) -> String {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        self.render(&|field| match field {
+            "status" => response.status().as_str().to_string(),
+            "size" => response.content_length().to_string(),
+            "url" => response.url().to_string(),
+            "path" => response.url().path().to_string(),
+            "redirect" => header(LOCATION.as_str()),
+            "content_type" => header(CONTENT_TYPE.as_str()),
+            "change" => change
+                .map(|change| change.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            "listing" if listing => String::from("listing"),
+            "body_hash" => response
+                .body_hash()
+                .map(|hash| hash.hex.clone())
+                .unwrap_or_default(),
+            "favicon_hash" => response
+                .favicon_hash()
+                .map(|hash| hash.to_string())
+                .unwrap_or_default(),
+            "hops" => response
+                .redirects()
+                .map(|chain| chain.hops().to_string())
+                .unwrap_or_default(),
+            "redirected_from" => response
+                .redirects()
+                .map(|chain| chain.origin().to_string())
+                .unwrap_or_default(),
+            _ => match field.strip_prefix(HEADER_FIELD) {
+                Some(name) => header(name),
+                None => String::new(),
+            },
+        })
+    }
+}
+
+/// Template given with `--output-template`, if any; exits when it can't be parsed
+pub fn initialize() -> Option<Template> {
+    log::trace!("enter: initialize");
+
+    if CONFIGURATION.output_template.is_empty() {
+        log::trace!("exit: initialize -> None");
+        return None;
+    }
+
+    let template = Template::parse(&CONFIGURATION.output_template).unwrap_or_else(|e| {
+        eprintln!(
+            "{} {} Could not parse --output-template {}: {}",
+            status_colorizer("ERROR"),
+            module_colorizer("template::initialize"),
+            CONFIGURATION.output_template,
+            e
+        );
+        process::exit(1);
+    });
+
+    log::trace!("exit: initialize -> {:?}", template);
+    Some(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// value of the fields of a redirect
+    fn redirect(field: &str) -> String {
+        match field {
+            "status" => String::from("301"),
+            "size" => String::from("0"),
+            "url" => String::from("http://localhost/admin"),
+            "redirect" => String::from("/admin/"),
+            "header.server" => String::from("nginx"),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    /// fields are replaced by their values, blocks only write what applies
+    fn template_render_fields_and_blocks() {
+        let template =
+            Template::parse("{{status}} {{size}} {{url}}{{#if redirect}} -> {{redirect}}{{/if}}")
+                .unwrap();
+
+        assert_eq!(
+            template.render(&redirect),
+            "301 0 http://localhost/admin -> /admin/"
+        );
+        assert_eq!(
+            template.render(&|field| if field == "redirect" {
+                String::new()
+            } else {
+                redirect(field)
+            }),
+            "301 0 http://localhost/admin"
+        );
+
+        let template = Template::parse(
+            "[{{ header.server }}] {{#if change}}{{change}}{{else}}-{{/if}}{{#if status}}{{#if listing}} dir{{/if}}!{{/if}}",
+        )
+        .unwrap();
+
+        assert_eq!(template.render(&redirect), "[nginx] -!");
+    }
+
+    #[test]
+    /// unknown fields and unbalanced blocks are refused
+    fn template_parse_refuses_bad_templates() {
+        assert!(Template::parse("plain text").is_ok());
+        assert!(Template::parse("{{bogus}}").is_err());
+        assert!(Template::parse("{{header.}}").is_err());
+        assert!(Template::parse("{{#if bogus}}x{{/if}}").is_err());
+        assert!(Template::parse("{{#if status}}x").is_err());
+        assert!(Template::parse("{{#if status}}x{{else}}y").is_err());
+        assert!(Template::parse("x{{/if}}").is_err());
+        assert!(Template::parse("{{status").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::heuristics::try_send_message_to_file;
+use crate::metadata::tag;
+use crate::utils::{ferox_print, status_colorizer};
+use console::style;
+use lazy_static::lazy_static;
+use reqwest::Url;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::RwLock;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// number of times a block of segments has to repeat at the end of a path to be a trap, i.e.
+/// `/a/a/a/` or `/a/b/a/b/a/b/`
+const REPEATS: usize = 3;
+
+/// number of consecutive numeric segments that makes a path calendar-style; `/2020/01/31/` is a
+/// date, anything longer is a calendar counting on and on
+const NUMERIC_RUN: usize = 4;
+
+lazy_static! {
+    /// Directories that answer any path added to them as a directory of its own, by url
+    static ref ECHOING: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
+
+    /// Parent directories whose trap was already reported, by url; later traps under the same
+    /// parent are only logged
+    static ref REPORTED: RwLock<HashSet<String>> = RwLock::new(HashSet::new());
+}
+
+/// Remember that the given directory answers any path added to it as a directory, which was found
+/// out by the wildcard test; none of its subdirectories are scanned recursively
+pub fn add_echoing(directory: &str) {
+    match ECHOING.write() {
+        Ok(mut echoing) => {
+            echoing.insert(directory.trim_end_matches('/').to_string());
+        }
+        Err(e) => {
+            log::error!("{}", e);
+        }
+    }
+}
+
+/// Whether or not the given directory was found to echo any path added to it
+fn is_echoing(directory: &str) -> bool {
+    match ECHOING.read() {
+        Ok(echoing) => echoing.contains(directory.trim_end_matches('/')),
+        Err(e) => {
+            log::error!("{}", e);
+            false
+        }
+    }
+}
+
+/// Non-empty segments of the url's path
+fn segments(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Whether or not the path ends with a block of segments repeated `REPEATS` times
+fn repeats(segments: &[&str]) -> bool {
+    (1..=segments.len() / REPEATS).any(|block| {
+        let tail = &segments[segments.len() - block * REPEATS..];
+        tail.chunks(block).all(|chunk| chunk == &tail[..block])
+    })
+}
+
+/// Whether or not the segment is a year, i.e. `2020`
+fn is_year(segment: &str) -> bool {
+    segment.len() == 4
+        && (segment.starts_with("19") || segment.starts_with("20"))
+        && segment.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Whether or not the path looks like a calendar counting on and on; either a long run of numeric
+/// segments, or more than one year
+fn is_calendar(segments: &[&str]) -> bool {
+    let mut run = 0;
+
+    for segment in segments {
+        if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            run += 1;
+
+            if run >= NUMERIC_RUN {
+                return true;
+            }
+        } else {
+            run = 0;
+        }
+    }
+
+    segments.iter().filter(|segment| is_year(segment)).count() > 1
+}
+
+/// Determine whether the given directory is a recursion trap, returning why it is if so
+///
+/// returns one of `repeating path segments`, `calendar-style path`, or `echoing parent directory`
+pub fn find(url: &Url) -> Option<&'static str> {
+    let segments = segments(url);
+
+    if repeats(&segments) {
+        return Some("repeating path segments");
+    }
+
+    if is_calendar(&segments) {
+        return Some("calendar-style path");
+    }
+
+    let mut parent = url.clone();
+
+    if let Ok(mut parent_segments) = parent.path_segments_mut() {
+        parent_segments.pop_if_empty().pop();
+    }
+
+    if is_echoing(parent.as_str()) {
+        return Some("echoing parent directory");
+    }
+
+    None
+}
+
+/// Let the user know that the given directory isn't scanned recursively because it's a trap; only
+/// the first trap found under each parent directory is printed, the rest are logged
+pub fn report(url: &Url, reason: &str, tx_file: UnboundedSender<String>) {
+    log::trace!("enter: report({}, {}, {:?})", url, reason, tx_file);
+    log::warn!("Not scanning {} recursively; {}", url, reason);
+
+    let mut parent = url.clone();
+
+    if let Ok(mut segments) = parent.path_segments_mut() {
+        segments.pop_if_empty().pop();
+    }
+
+    let first = match REPORTED.write() {
+        Ok(mut reported) => reported.insert(parent.to_string()),
+        Err(e) => {
+            log::error!("{}", e);
+            false
+        }
+    };
+
+    if first && !CONFIGURATION.quiet {
+        let msg = format!(
+            "{} {:>10} Recursion trap found at {} ({}); {} it and others like it\n",
+            status_colorizer("TRP"),
+            "-",
+            url,
+            reason,
+            style("not scanning").yellow(),
+        );
+
+        ferox_print(&msg, &PROGRESS_PRINTER);
+
+        let file_msg = if CONFIGURATION.json {
+            let mut record = json!({
+                "type": "trap",
+                "url": url.as_str(),
+                "reason": reason,
+            });
+
+            tag(&mut record);
+
+            format!("{}\n", record)
+        } else {
+            msg
+        };
+
+        try_send_message_to_file(&file_msg, tx_file, !CONFIGURATION.output.is_empty());
+    }
+
+    log::trace!("exit: report");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// helper to run `find` against a url given as a str
+    fn trap(url: &str) -> Option<&'static str> {
+        find(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    /// a single segment or a block of segments repeated three times is a trap, twice isn't
+    fn traps_find_repeating_segments() {
+        assert_eq!(
+            trap("http://localhost/a/a/a/"),
+            Some("repeating path segments")
+        );
+        assert_eq!(
+            trap("http://localhost/x/a/b/a/b/a/b"),
+            Some("repeating path segments")
+        );
+        assert_eq!(trap("http://localhost/docs/docs/"), None);
+        assert_eq!(trap("http://localhost/a/b/a/b/"), None);
+        assert_eq!(trap("http://localhost/a/b/c/a/"), None);
+    }
+
+    #[test]
+    /// dates are fine, counting on past them or repeating years isn't
+    fn traps_find_calendar_style_paths() {
+        assert_eq!(trap("http://localhost/blog/2020/01/31/"), None);
+        assert_eq!(trap("http://localhost/api/v1/users/1/posts/2/"), None);
+        assert_eq!(
+            trap("http://localhost/cal/2020/01/31/1/"),
+            Some("calendar-style path")
+        );
+        assert_eq!(
+            trap("http://localhost/cal/2020/next/2021/"),
+            Some("calendar-style path")
+        );
+    }
+
+    #[test]
+    /// subdirectories of a directory that echoes any path are traps, the directory itself isn't
+    fn traps_find_echoing_parent() {
+        add_echoing("http://echoing.test/search/");
+
+        assert_eq!(
+            trap("http://echoing.test/search/stuff/"),
+            Some("echoing parent directory")
+        );
+        assert_eq!(
+            trap("http://echoing.test/search/things"),
+            Some("echoing parent directory")
+        );
+        assert_eq!(trap("http://echoing.test/search/"), None);
+    }
+}
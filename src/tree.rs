@@ -0,0 +1,182 @@
+use crate::utils::status_colorizer;
+use reqwest::Url;
+use std::collections::BTreeMap;
+
+/// A single path segment in a `ResultTree`
+#[derive(Debug, Default)]
+struct Node {
+    /// (status, content-length) of every result reported for this exact path
+    ///
+    /// more than one is possible, i.e. `/admin` (301) and `/admin/` (200)
+    results: Vec<(u16, u64)>,
+
+    /// path segments found beneath this one
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    /// Get the node for the given path segments, creating any that don't exist yet
+    fn descend(&mut self, segments: &[String]) -> &mut Node {
+        match segments.split_first() {
+            Some((first, rest)) => self
+                .children
+                .entry(first.to_string())
+                .or_default()
+                .descend(rest),
+            None => self,
+        }
+    }
+
+    /// Format the results of this node, i.e. ` (200, 1234)`
+    fn annotation(&self) -> String {
+        self.results
+            .iter()
+            .map(|(status, size)| format!(" ({}, {})", status_colorizer(&status.to_string()), size))
+            .collect()
+    }
+
+    /// Render all children of this node, each line prefixed with `prefix`
+    fn render_children(&self, prefix: &str, output: &mut String) {
+        let count = self.children.len();
+
+        for (index, (name, child)) in self.children.iter().enumerate() {
+            let last = index == count - 1;
+
+            let (branch, extension) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            let slash = if child.children.is_empty() { "" } else { "/" };
+
+            output.push_str(&format!(
+                "{}{}{}{}{}\n",
+                prefix,
+                branch,
+                name,
+                slash,
+                child.annotation()
+            ));
+
+            child.render_children(&format!("{}{}", prefix, extension), output);
+        }
+    }
+}
+
+/// All reported results, grouped by host and arranged by path; rendered once the scan completes
+#[derive(Debug, Default)]
+pub struct ResultTree {
+    /// one tree per scheme://host[:port]
+    hosts: BTreeMap<String, Node>,
+}
+
+impl ResultTree {
+    /// Add a reported result to the tree
+    pub fn insert(&mut self, url: &Url, status: u16, content_length: u64) {
+        let origin = url.origin().ascii_serialization();
+
+        let mut segments: Vec<String> = match url.path_segments() {
+            Some(segments) => segments
+                .filter(|segment| !segment.is_empty())
+                .map(|segment| segment.to_string())
+                .collect(),
+            None => vec![],
+        };
+
+        if let Some(query) = url.query() {
+            match segments.last_mut() {
+                Some(last) => last.push_str(&format!("?{}", query)),
+                None => segments.push(format!("?{}", query)),
+            }
+        }
+
+        self.hosts
+            .entry(origin)
+            .or_default()
+            .descend(&segments)
+            .results
+            .push((status, content_length));
+    }
+
+    /// Whether or not any results were added
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// Render the tree; one section per host, each followed by an empty line
+    ///
+    /// example:
+    ///     http://localhost
+    ///     ├── admin/ (301, 0)
+    ///     │   └── login.php (200, 1234)
+    ///     └── robots.txt (200, 55)
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        for (host, root) in &self.hosts {
+            output.push_str(&format!("{}{}\n", host, root.annotation()));
+            root.render_children("", &mut output);
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::strip_ansi_codes;
+
+    /// helper to insert a result using a string url
+    fn insert(tree: &mut ResultTree, url: &str, status: u16, size: u64) {
+        tree.insert(&Url::parse(url).unwrap(), status, size);
+    }
+
+    #[test]
+    /// results are grouped by host and nested by path
+    fn tree_render_groups_by_host_and_path() {
+        let mut tree = ResultTree::default();
+        assert!(tree.is_empty());
+
+        insert(&mut tree, "http://localhost/robots.txt", 200, 55);
+        insert(&mut tree, "http://localhost/admin", 301, 0);
+        insert(&mut tree, "http://localhost/admin/login.php", 200, 1234);
+        insert(&mut tree, "http://localhost/admin/users/", 403, 12);
+        insert(&mut tree, "http://localhost:8080/", 200, 10);
+
+        let expected = "\
+http://localhost
+├── admin/ (301, 0)
+│   ├── login.php (200, 1234)
+│   └── users (403, 12)
+└── robots.txt (200, 55)
+
+http://localhost:8080 (200, 10)
+
+";
+
+        assert!(!tree.is_empty());
+        assert_eq!(strip_ansi_codes(&tree.render()), expected);
+    }
+
+    #[test]
+    /// results for the same path are all kept, query strings stay with their path
+    fn tree_render_keeps_duplicates_and_queries() {
+        let mut tree = ResultTree::default();
+
+        insert(&mut tree, "http://localhost/js", 301, 0);
+        insert(&mut tree, "http://localhost/js/", 403, 5);
+        insert(&mut tree, "http://localhost/index.php?id=1", 200, 7);
+
+        let expected = "\
+http://localhost
+├── index.php?id=1 (200, 7)
+└── js (301, 0) (403, 5)
+
+";
+
+        assert_eq!(strip_ansi_codes(&tree.render()), expected);
+    }
+}
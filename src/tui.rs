@@ -0,0 +1,957 @@
+//! Full-screen dashboard shown while scanning with `--tui`, in place of the banner, the progress
+//! bars, and the printed results
+//!
+//! The dashboard follows the scan's events (see `events::subscribe`): the scans running, their
+//! progress, the findings, and the last lines of output; everything that would've been printed
+//! meanwhile is printed once the dashboard closes. Its keys cancel a scan (see `scanner::cancel`),
+//! pause requests (as SIGUSR1 does), and adjust the rate limit (see `limiter::adjust`)
+use crate::config::{CONFIGURATION, PROGRESS_BAR, PROGRESS_PRINTER};
+use crate::events::{self, Event};
+use crate::utils::{ferox_print, status_colorizer};
+use crate::{limiter, scanner, signals, VERSION};
+use console::{strip_ansi_codes, style, truncate_str, user_attended, Key, Term};
+use indicatif::ProgressDrawTarget;
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// Milliseconds between redraws of the dashboard
+const REDRAW_MS: u64 = 250;
+
+/// Number of lines of output shown at the bottom of the dashboard
+const OUTPUT_LINES: usize = 4;
+
+/// Width of each scan's progress bar
+const BAR_WIDTH: u64 = 20;
+
+/// Switches the terminal to its alternate screen, which keeps what was on it before
+const ENTER_SCREEN: &str = "\x1b[?1049h";
+
+/// Switches the terminal back from its alternate screen
+const LEAVE_SCREEN: &str = "\x1b[?1049l";
+
+/// Whether or not the dashboard is shown
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// Everything printed while the dashboard is shown, printed once it closes
+    static ref PRINTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    /// Held while the dashboard is drawn or closed, so that it's never drawn over what's printed
+    /// once it closed
+    static ref SCREEN: Mutex<()> = Mutex::new(());
+}
+
+/// Whether or not the dashboard is shown; messages go to it instead of being printed meanwhile
+/// (see `utils::ferox_print`)
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Keep the given message, meant to be printed, for the dashboard's output and for printing once
+/// the dashboard closes
+pub fn print(msg: &str) {
+    match PRINTED.lock() {
+        Ok(mut printed) => printed.push(msg.to_string()),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+/// A scan running, as shown by the dashboard
+#[derive(Debug)]
+struct ScanRow {
+    /// url being scanned
+    url: String,
+
+    /// number of requests the scan is expected to make
+    expected: u64,
+
+    /// number of responses received
+    requests: u64,
+
+    /// number of failed requests
+    errors: u64,
+
+    /// when the scan started
+    started: Instant,
+
+    /// whether the scan was cancelled from the dashboard, and is finishing the requests in flight
+    cancelled: bool,
+}
+
+/// A reported result, as shown by the dashboard
+#[derive(Debug)]
+struct Finding {
+    /// status code of the response
+    status: u16,
+
+    /// size of the response
+    content_length: u64,
+
+    /// url requested
+    url: String,
+}
+
+/// What's being typed at the bottom of the dashboard, if anything
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Input {
+    /// nothing, keys are bindings
+    None,
+
+    /// text the findings' urls have to contain
+    Search,
+
+    /// status codes the findings have to have, comma-separated
+    Statuses,
+}
+
+/// Something the dashboard's keys ask of the scan
+#[derive(Debug, PartialEq)]
+enum Action {
+    /// cancel the scan of the given url
+    Cancel(String),
+
+    /// pause (true) or resume (false) requests
+    Pause(bool),
+
+    /// limit requests to the given number per second, 0 for the configured limit
+    Rate(usize),
+}
+
+/// State of the dashboard, updated by the scan's events and the keys pressed
+#[derive(Debug)]
+struct Dashboard {
+    /// scans running, in the order they started
+    scans: Vec<ScanRow>,
+
+    /// number of scans that finished
+    finished: usize,
+
+    /// index of the scan selected in `scans`
+    selected: usize,
+
+    /// every result reported, in the order they were
+    findings: Vec<Finding>,
+
+    /// number of responses received across scans
+    requests: u64,
+
+    /// number of failed requests across scans
+    errors: u64,
+
+    /// when the dashboard started
+    started: Instant,
+
+    /// text the findings' urls have to contain to be shown, empty for any
+    search: String,
+
+    /// status codes the findings have to have to be shown, empty for any
+    statuses: Vec<u16>,
+
+    /// what's being typed, if anything
+    input: Input,
+
+    /// text typed so far
+    typed: String,
+
+    /// whether requests are paused
+    paused: bool,
+
+    /// rate limit set from the dashboard, 0 while it's the configured one
+    rate: usize,
+
+    /// rate limit of the configuration (see `--rate-limit`), 0 for unlimited
+    configured_rate: usize,
+}
+
+impl Dashboard {
+    /// Create an empty dashboard for a scan limited to `configured_rate` requests per second
+    fn new(configured_rate: usize) -> Self {
+        Self {
+            scans: Vec::new(),
+            finished: 0,
+            selected: 0,
+            findings: Vec::new(),
+            requests: 0,
+            errors: 0,
+            started: Instant::now(),
+            search: String::new(),
+            statuses: Vec::new(),
+            input: Input::None,
+            typed: String::new(),
+            paused: false,
+            rate: 0,
+            configured_rate,
+        }
+    }
+
+    /// The scan running whose url is the closest parent of the given url
+    fn scan_of(&mut self, url: &str) -> Option<&mut ScanRow> {
+        self.scans
+            .iter_mut()
+            .filter(|scan| {
+                let directory = scan.url.trim_end_matches('/');
+                url == directory || url.starts_with(&format!("{}/", directory))
+            })
+            .max_by_key(|scan| scan.url.trim_end_matches('/').len())
+    }
+
+    /// Update the dashboard with one of the scan's events
+    fn handle(&mut self, event: Event) {
+        match event {
+            Event::ScanStarted { url, requests } => self.scans.push(ScanRow {
+                url,
+                expected: requests,
+                requests: 0,
+                errors: 0,
+                started: Instant::now(),
+                cancelled: false,
+            }),
+            Event::ResponseReceived { url, .. } => {
+                self.requests += 1;
+
+                if let Some(scan) = self.scan_of(url.as_str()) {
+                    scan.requests += 1;
+                }
+            }
+            Event::Error { url, .. } => {
+                self.errors += 1;
+
+                if let Some(scan) = self.scan_of(url.as_str()) {
+                    scan.errors += 1;
+                }
+            }
+            Event::ResultReported(response) => self.findings.push(Finding {
+                status: response.status().as_u16(),
+                content_length: response.content_length(),
+                url: response.url().to_string(),
+            }),
+            Event::ScanFinished { url } => {
+                if let Some(index) = self.scans.iter().position(|scan| scan.url == url) {
+                    self.scans.remove(index);
+                    self.finished += 1;
+
+                    if self.selected >= self.scans.len() {
+                        self.selected = self.scans.len().saturating_sub(1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Findings shown, i.e. those matching the search and status codes typed
+    fn shown_findings(&self) -> Vec<&Finding> {
+        self.findings
+            .iter()
+            .filter(|finding| self.statuses.is_empty() || self.statuses.contains(&finding.status))
+            .filter(|finding| finding.url.contains(&self.search))
+            .collect()
+    }
+
+    /// Requests made each second since the dashboard started
+    fn observed_rate(&self) -> usize {
+        let elapsed = self.started.elapsed().as_secs_f64().max(1.0);
+        (self.requests as f64 / elapsed) as usize
+    }
+
+    /// Rate limit in effect: the one set from the dashboard, else the configured one; 0 for
+    /// unlimited
+    fn rate_limit(&self) -> usize {
+        if self.rate > 0 {
+            self.rate
+        } else {
+            self.configured_rate
+        }
+    }
+
+    /// Update the dashboard with a key pressed, returning what it asks of the scan, if anything
+    fn key(&mut self, key: Key) -> Option<Action> {
+        if self.input != Input::None {
+            match key {
+                Key::Char(c) => self.typed.push(c),
+                Key::Backspace => {
+                    self.typed.pop();
+                }
+                Key::Enter => {
+                    let typed = std::mem::take(&mut self.typed);
+
+                    if self.input == Input::Search {
+                        self.search = typed;
+                    } else {
+                        self.statuses = typed
+                            .split(',')
+                            .filter_map(|status| status.trim().parse().ok())
+                            .collect();
+                    }
+
+                    self.input = Input::None;
+                }
+                Key::Escape => {
+                    self.typed.clear();
+                    self.input = Input::None;
+                }
+                _ => {}
+            }
+
+            return None;
+        }
+
+        match key {
+            Key::ArrowUp | Key::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            Key::ArrowDown | Key::Char('j') => {
+                if self.selected + 1 < self.scans.len() {
+                    self.selected += 1;
+                }
+                None
+            }
+            Key::Char('c') => {
+                let scan = self.scans.get_mut(self.selected)?;
+
+                if scan.cancelled {
+                    return None;
+                }
+
+                scan.cancelled = true;
+                Some(Action::Cancel(scan.url.clone()))
+            }
+            Key::Char('p') => {
+                self.paused = !self.paused;
+                Some(Action::Pause(self.paused))
+            }
+            Key::Char('+') | Key::Char('=') | Key::Char('-') => {
+                let current = match self.rate_limit() {
+                    0 => self.observed_rate().max(1),
+                    limit => limit,
+                };
+
+                self.rate = if key == Key::Char('-') {
+                    (current * 3 / 4).max(1)
+                } else {
+                    (current * 5 / 4).max(current + 1)
+                };
+
+                Some(Action::Rate(self.rate))
+            }
+            Key::Char('0') => {
+                self.rate = 0;
+                Some(Action::Rate(0))
+            }
+            Key::Char('/') => {
+                self.input = Input::Search;
+                self.typed = self.search.clone();
+                None
+            }
+            Key::Char('s') => {
+                self.input = Input::Statuses;
+                self.typed = self
+                    .statuses
+                    .iter()
+                    .map(|status| status.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                None
+            }
+            Key::Escape => {
+                self.search.clear();
+                self.statuses.clear();
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Lines of the dashboard for a terminal of the given size, along with the given lines of
+    /// output; lines may be wider than the terminal
+    fn render(&self, width: usize, height: usize, output: &[String]) -> Vec<String> {
+        let elapsed = self.started.elapsed().as_secs_f64().max(1.0);
+
+        let rate = match self.rate_limit() {
+            0 => String::from("unlimited"),
+            limit => format!("{}/s", limit),
+        };
+
+        let mut header = format!(
+            "{} {} │ {} running, {} done │ {} requests ({}/s) │ {} findings │ {} errors │ rate limit: {}",
+            style("feroxbuster").bold(),
+            VERSION,
+            self.scans.len(),
+            self.finished,
+            self.requests,
+            (self.requests as f64 / elapsed) as u64,
+            self.findings.len(),
+            self.errors,
+            rate
+        );
+
+        if self.paused {
+            header.push_str(&format!(" │ {}", style("PAUSED").yellow().bold()));
+        }
+
+        let rule = |title: &str| {
+            let title = format!("── {} ", title);
+            // a column short of the width, so that the rule isn't truncated
+            let fill = width.saturating_sub(console::measure_text_width(&title) + 1);
+            format!("{}{}", style(title).bold(), "─".repeat(fill))
+        };
+
+        // header, three rules, the output, and the footer
+        let fixed = 5 + OUTPUT_LINES;
+        let room = height.saturating_sub(fixed);
+        let scan_rows = self.scans.len().min((room / 2).max(1));
+        let finding_rows = room.saturating_sub(scan_rows);
+
+        let mut lines = vec![header, rule("Scans")];
+
+        let first = (self.selected + 1).saturating_sub(scan_rows);
+
+        for (index, scan) in self.scans.iter().enumerate().skip(first).take(scan_rows) {
+            // a scan expected to make no requests is done
+            let done = (scan.requests * BAR_WIDTH)
+                .checked_div(scan.expected)
+                .map_or(BAR_WIDTH, |done| done.min(BAR_WIDTH));
+
+            let percent = (scan.requests * 100)
+                .checked_div(scan.expected)
+                .map_or(100, |percent| percent.min(100));
+
+            let per_second = scan.requests as f64 / scan.started.elapsed().as_secs_f64().max(1.0);
+
+            let mut line = format!(
+                "{} [{}{}] {:>3}% {:>7}/{:<7} {:>5}/s {:>4} errors  {}",
+                if index == self.selected { ">" } else { " " },
+                style("#".repeat(done as usize)).cyan(),
+                "-".repeat((BAR_WIDTH - done) as usize),
+                percent,
+                scan.requests,
+                scan.expected,
+                per_second as u64,
+                scan.errors,
+                scan.url
+            );
+
+            if scan.cancelled {
+                line.push_str(&format!(" {}", style("(cancelling)").yellow()));
+            }
+
+            if index == self.selected {
+                line = style(line).bold().to_string();
+            }
+
+            lines.push(line);
+        }
+
+        let shown = self.shown_findings();
+
+        let mut title = format!("Findings ({} of {}", shown.len(), self.findings.len());
+
+        if !self.statuses.is_empty() {
+            let statuses: Vec<String> = self.statuses.iter().map(u16::to_string).collect();
+            title.push_str(&format!(", status {}", statuses.join(",")));
+        }
+
+        if !self.search.is_empty() {
+            title.push_str(&format!(", matching \"{}\"", self.search));
+        }
+
+        title.push(')');
+        lines.push(rule(&title));
+
+        let skipped = shown.len().saturating_sub(finding_rows);
+
+        for finding in shown.iter().skip(skipped) {
+            lines.push(format!(
+                "{} {:>9}c {}",
+                status_colorizer(&finding.status.to_string()),
+                finding.content_length,
+                finding.url
+            ));
+        }
+
+        // the findings are padded so that the output stays at the bottom
+        while lines.len() < 3 + scan_rows + finding_rows {
+            lines.push(String::new());
+        }
+
+        lines.push(rule("Output"));
+
+        let skipped = output.len().saturating_sub(OUTPUT_LINES);
+
+        for line in output.iter().skip(skipped) {
+            lines.push(style(line).dim().to_string());
+        }
+
+        while lines.len() < height.saturating_sub(1) {
+            lines.push(String::new());
+        }
+
+        let footer = match self.input {
+            Input::Search => format!("search urls: {}_", self.typed),
+            Input::Statuses => format!("status codes (i.e. 200,301): {}_", self.typed),
+            Input::None => style(
+                "↑/↓ select  c cancel  p pause  +/- rate  0 reset rate  / search  s status  \
+                 esc clear  ctrl+c quit",
+            )
+            .dim()
+            .to_string(),
+        };
+
+        lines.push(footer);
+        lines
+    }
+}
+
+/// Last lines printed while the dashboard is shown, without colors
+fn output_lines() -> Vec<String> {
+    let printed = match PRINTED.lock() {
+        Ok(printed) => printed,
+        Err(_) => return vec![],
+    };
+
+    let mut lines = vec![];
+
+    // only the last few messages are needed to fill the output
+    for msg in printed.iter().rev() {
+        for line in strip_ansi_codes(msg).lines().rev() {
+            if !line.trim().is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+
+        if lines.len() >= OUTPUT_LINES {
+            break;
+        }
+    }
+
+    lines.truncate(OUTPUT_LINES);
+    lines.reverse();
+    lines
+}
+
+/// Draw the dashboard over the whole terminal
+fn draw(term: &Term, dashboard: &Dashboard) {
+    let _screen = match SCREEN.lock() {
+        Ok(screen) => screen,
+        Err(_) => return,
+    };
+
+    if !is_active() {
+        return; // closed meanwhile
+    }
+
+    let (height, width) = term.size();
+    let (height, width) = (height as usize, width as usize);
+
+    let mut frame = String::new();
+
+    for (row, line) in dashboard
+        .render(width, height, &output_lines())
+        .iter()
+        .take(height)
+        .enumerate()
+    {
+        // every line is positioned on its own and cleared past its end, so that nothing is left
+        // of the previous frame
+        frame.push_str(&format!(
+            "\x1b[{};1H{}\x1b[K",
+            row + 1,
+            truncate_str(line, width, "…")
+        ));
+    }
+
+    frame.push_str("\x1b[J");
+
+    term.write_str(&frame).unwrap_or_default();
+    term.flush().unwrap_or_default();
+}
+
+/// Apply what a key asks of the scan
+fn apply(action: Action) {
+    match action {
+        Action::Cancel(url) => {
+            log::warn!("Cancelling the scan of {} from the dashboard", url);
+            scanner::cancel(&url);
+        }
+        Action::Pause(paused) => signals::set_paused(paused),
+        Action::Rate(per_second) => limiter::adjust(per_second),
+    }
+}
+
+/// Bytes read from the terminal, as keys
+fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    let mut keys = vec![];
+
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+
+                match chars.next() {
+                    Some('A') => Key::ArrowUp,
+                    Some('B') => Key::ArrowDown,
+                    _ => Key::Unknown,
+                }
+            }
+            '\x1b' => Key::Escape,
+            '\r' | '\n' => Key::Enter,
+            '\x7f' | '\x08' => Key::Backspace,
+            c => Key::Char(c),
+        };
+
+        keys.push(key);
+    }
+
+    keys
+}
+
+#[cfg(unix)]
+lazy_static! {
+    /// Terminal whose keys are read, along with its settings to put back once the dashboard
+    /// closes
+    static ref TERMINAL: Mutex<Option<(libc::c_int, libc::termios)>> = Mutex::new(None);
+}
+
+/// Read the keys pressed on the terminal from a thread of their own, sending them to the given
+/// transmitter; keys are read as they're pressed, without being echoed, while Ctrl+C still
+/// interrupts the scan
+#[cfg(unix)]
+fn spawn_key_reader(tx_keys: UnboundedSender<Key>) {
+    use std::fs::File;
+    use std::os::unix::io::IntoRawFd;
+
+    // with --stdin, the targets were read from stdin, so the keys are read from the terminal
+    let fd = if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        libc::STDIN_FILENO
+    } else {
+        match File::open("/dev/tty") {
+            Ok(tty) => tty.into_raw_fd(),
+            Err(e) => {
+                log::warn!("Could not read keys from the terminal: {}", e);
+                return;
+            }
+        }
+    };
+
+    let mut settings: libc::termios = unsafe { std::mem::zeroed() };
+
+    if unsafe { libc::tcgetattr(fd, &mut settings) } != 0 {
+        log::warn!("Could not read keys from the terminal");
+        return;
+    }
+
+    let mut keys = settings;
+    keys.c_lflag &= !(libc::ICANON | libc::ECHO);
+    keys.c_cc[libc::VMIN] = 1;
+    keys.c_cc[libc::VTIME] = 0;
+
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &keys) } != 0 {
+        log::warn!("Could not read keys from the terminal");
+        return;
+    }
+
+    if let Ok(mut terminal) = TERMINAL.lock() {
+        *terminal = Some((fd, settings));
+    }
+
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 32];
+
+        loop {
+            let read = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+
+            if read <= 0 {
+                break;
+            }
+
+            for key in parse_keys(&buf[..read as usize]) {
+                if tx_keys.send(key).is_err() {
+                    return; // the dashboard closed
+                }
+            }
+        }
+    });
+}
+
+/// Keys can't be read outside of unix; the dashboard is only shown
+#[cfg(not(unix))]
+fn spawn_key_reader(_tx_keys: UnboundedSender<Key>) {}
+
+/// Put back the settings of the terminal whose keys were read
+#[cfg(unix)]
+fn restore_terminal() {
+    if let Some((fd, settings)) = TERMINAL
+        .lock()
+        .ok()
+        .and_then(|mut terminal| terminal.take())
+    {
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, &settings) };
+    }
+}
+
+#[cfg(not(unix))]
+fn restore_terminal() {}
+
+/// Close the dashboard when feroxbuster exits without doing so, i.e. with `--ban-policy stop`
+#[cfg(unix)]
+extern "C" fn close_at_exit() {
+    close();
+}
+
+/// Show the dashboard in place of the banner, the progress bars, and the printed results, until
+/// `close` is called; only with `--tui`, when a terminal is attached
+pub fn start() {
+    log::trace!("enter: start");
+
+    if !CONFIGURATION.tui || is_active() {
+        log::trace!("exit: start");
+        return;
+    }
+
+    if !user_attended() {
+        log::warn!("--tui needs a terminal, printing the scan as usual");
+        log::trace!("exit: start");
+        return;
+    }
+
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    // the bars are still kept up to date, just not drawn
+    PROGRESS_BAR.set_draw_target(ProgressDrawTarget::hidden());
+
+    let term = Term::stdout();
+    term.write_str(ENTER_SCREEN).unwrap_or_default();
+    term.hide_cursor().unwrap_or_default();
+
+    #[cfg(unix)]
+    unsafe {
+        libc::atexit(close_at_exit);
+    }
+
+    let mut rx_events = events::subscribe();
+    let (tx_keys, mut rx_keys) = mpsc::unbounded_channel();
+    spawn_key_reader(tx_keys);
+
+    tokio::spawn(async move {
+        let mut dashboard = Dashboard::new(CONFIGURATION.rate_limit);
+        let mut redraw = tokio::time::interval(Duration::from_millis(REDRAW_MS));
+        let mut events_open = true;
+        let mut keys_open = true;
+
+        while is_active() {
+            tokio::select! {
+                event = rx_events.recv(), if events_open => match event {
+                    Some(event) => dashboard.handle(event),
+                    None => events_open = false, // all scans are complete
+                },
+                key = rx_keys.recv(), if keys_open => match key {
+                    Some(key) => {
+                        if let Some(action) = dashboard.key(key) {
+                            apply(action);
+                        }
+                        draw(&term, &dashboard);
+                    }
+                    None => keys_open = false,
+                },
+                _ = redraw.tick() => {
+                    // requests may also be paused and resumed with SIGUSR1 and SIGUSR2
+                    dashboard.paused = signals::is_paused();
+                    draw(&term, &dashboard);
+                }
+            }
+        }
+    });
+
+    log::trace!("exit: start");
+}
+
+/// Close the dashboard, putting the terminal back as it was, then print everything that was
+/// printed while it was shown
+pub fn close() {
+    let screen = SCREEN.lock();
+
+    if !ACTIVE.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    restore_terminal();
+
+    let term = Term::stdout();
+    term.write_str(LEAVE_SCREEN).unwrap_or_default();
+    term.show_cursor().unwrap_or_default();
+
+    drop(screen);
+
+    PROGRESS_BAR.set_draw_target(ProgressDrawTarget::stdout());
+
+    let printed = match PRINTED.lock() {
+        Ok(mut printed) => std::mem::take(&mut *printed),
+        Err(_) => vec![],
+    };
+
+    for msg in printed {
+        ferox_print(&msg, &PROGRESS_PRINTER);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::{StatusCode, Url};
+
+    /// helper to start a scan of `url` expected to make `requests` requests
+    fn started(dashboard: &mut Dashboard, url: &str, requests: u64) {
+        dashboard.handle(Event::ScanStarted {
+            url: url.to_string(),
+            requests,
+        });
+    }
+
+    /// helper to receive a response to `url`
+    fn received(dashboard: &mut Dashboard, url: &str) {
+        dashboard.handle(Event::ResponseReceived {
+            url: Url::parse(url).unwrap(),
+            status: StatusCode::OK,
+            content_length: 0,
+        });
+    }
+
+    #[test]
+    /// responses and errors count toward the closest scan; finished scans go away
+    fn tui_dashboard_follows_scans() {
+        let mut dashboard = Dashboard::new(0);
+        started(&mut dashboard, "http://localhost", 4);
+        started(&mut dashboard, "http://localhost/admin/", 2);
+
+        received(&mut dashboard, "http://localhost/login");
+        received(&mut dashboard, "http://localhost/admin/users");
+        received(&mut dashboard, "http://localhost/administrator");
+        dashboard.handle(Event::Error {
+            url: Url::parse("http://localhost/admin/x").unwrap(),
+            error: String::from("timed out"),
+        });
+
+        assert_eq!(dashboard.requests, 3);
+        assert_eq!(dashboard.errors, 1);
+        assert_eq!(dashboard.scans[0].requests, 2);
+        assert_eq!(dashboard.scans[1].requests, 1);
+        assert_eq!(dashboard.scans[1].errors, 1);
+
+        dashboard.selected = 1;
+        dashboard.handle(Event::ScanFinished {
+            url: String::from("http://localhost/admin/"),
+        });
+        assert_eq!(dashboard.scans.len(), 1);
+        assert_eq!(dashboard.finished, 1);
+        assert_eq!(dashboard.selected, 0);
+    }
+
+    #[test]
+    /// findings are filtered by the status codes and search typed; escape clears both
+    fn tui_dashboard_filters_findings() {
+        let mut dashboard = Dashboard::new(0);
+
+        for (status, url) in [(200, "/admin"), (301, "/login"), (200, "/logout")].iter() {
+            dashboard.findings.push(Finding {
+                status: *status,
+                content_length: 0,
+                url: format!("http://localhost{}", url),
+            });
+        }
+
+        for key in "s200\n/log\n".chars() {
+            assert_eq!(
+                dashboard.key(parse_keys(key.to_string().as_bytes())[0]),
+                None
+            );
+        }
+
+        assert_eq!(dashboard.statuses, vec![200]);
+        assert_eq!(dashboard.search, "log");
+        let shown: Vec<&str> = dashboard
+            .shown_findings()
+            .iter()
+            .map(|finding| finding.url.as_str())
+            .collect();
+        assert_eq!(shown, vec!["http://localhost/logout"]);
+
+        dashboard.key(Key::Escape);
+        assert_eq!(dashboard.shown_findings().len(), 3);
+    }
+
+    #[test]
+    /// keys cancel the selected scan once, toggle pausing, and step the rate limit
+    fn tui_dashboard_keys_ask_for_actions() {
+        let mut dashboard = Dashboard::new(100);
+        started(&mut dashboard, "http://localhost", 4);
+        started(&mut dashboard, "http://localhost/admin/", 2);
+
+        assert_eq!(dashboard.key(Key::ArrowDown), None);
+        assert_eq!(
+            dashboard.key(Key::Char('c')),
+            Some(Action::Cancel(String::from("http://localhost/admin/")))
+        );
+        assert_eq!(dashboard.key(Key::Char('c')), None);
+
+        assert_eq!(dashboard.key(Key::Char('p')), Some(Action::Pause(true)));
+        assert_eq!(dashboard.key(Key::Char('p')), Some(Action::Pause(false)));
+
+        assert_eq!(dashboard.key(Key::Char('-')), Some(Action::Rate(75)));
+        assert_eq!(dashboard.key(Key::Char('+')), Some(Action::Rate(93)));
+        assert_eq!(dashboard.key(Key::Char('0')), Some(Action::Rate(0)));
+        assert_eq!(dashboard.rate_limit(), 100);
+    }
+
+    #[test]
+    /// the dashboard fills the terminal, with the output and the key bindings at the bottom
+    fn tui_dashboard_renders_every_section() {
+        let mut dashboard = Dashboard::new(0);
+        started(&mut dashboard, "http://localhost", 4);
+        received(&mut dashboard, "http://localhost/admin");
+        dashboard.findings.push(Finding {
+            status: 200,
+            content_length: 1337,
+            url: String::from("http://localhost/admin"),
+        });
+
+        let output = vec![String::from("INF scan started")];
+        let lines: Vec<String> = dashboard
+            .render(100, 24, &output)
+            .iter()
+            .map(|line| strip_ansi_codes(line).to_string())
+            .collect();
+
+        assert_eq!(lines.len(), 24);
+        assert!(lines[0].contains("1 running, 0 done"));
+        assert!(lines[0].contains("rate limit: unlimited"));
+        assert!(lines[2].contains("[#####---------------]  25%"));
+        assert!(lines[2].ends_with("http://localhost"));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("1337c http://localhost/admin")));
+        assert_eq!(lines[19], "INF scan started");
+        assert!(lines[23].contains("c cancel"));
+    }
+
+    #[test]
+    /// arrows, escape, enter, backspace, and characters are told apart
+    fn tui_parse_keys_reads_escape_codes() {
+        assert_eq!(
+            parse_keys(b"\x1b[A\x1b[Bq\x1b\r\x7f\xc3\xa9"),
+            vec![
+                Key::ArrowUp,
+                Key::ArrowDown,
+                Key::Char('q'),
+                Key::Escape,
+                Key::Enter,
+                Key::Backspace,
+                Key::Char('é'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! `feroxbuster --update`; replaces the running binary with the one of the latest release
+//!
+//! The release's archive for the running platform is downloaded, checked against the sha256
+//! digest GitHub publishes for it, and unpacked next to the running binary, which is then
+//! replaced by renaming the new binary over it; an interrupted update leaves the old binary as
+//! it was
+use crate::banner::UPDATE_URL;
+use crate::gzip::{crc32, inflate};
+use crate::utils::status_colorizer;
+use crate::{FeroxResult, VERSION};
+use openssl::sha::sha256;
+use reqwest::Client;
+use serde_json::Value;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Signatures of the zip records read while unpacking an archive
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+
+/// Name of the release asset holding the binary for the running platform, i.e.
+/// `x86_64-linux-feroxbuster.zip`; `None` when no binary is released for it
+pub fn asset_name() -> Option<String> {
+    let arch = match env::consts::ARCH {
+        "x86_64" => "x86_64",
+        "x86" => "x86",
+        "aarch64" => "aarch64",
+        "arm" => "armv7",
+        _ => return None,
+    };
+
+    let (os, extension) = match env::consts::OS {
+        "linux" => ("linux", ""),
+        "macos" => ("macos", ""),
+        "windows" => ("windows", ".exe"),
+        _ => return None,
+    };
+
+    Some(format!("{}-{}-feroxbuster{}.zip", arch, os, extension))
+}
+
+/// Whether or not the release tagged `latest` (i.e. `v2.0.0`) is newer than `current`; versions
+/// are compared number by number
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['.', '-'])
+            .map_while(|number| number.parse().ok())
+            .collect()
+    };
+
+    numbers(latest) > numbers(current)
+}
+
+/// Sha256 digest of the given asset of a release, as published by GitHub (`sha256:<hex>`)
+fn published_sha256(asset: &Value) -> Option<String> {
+    asset["digest"]
+        .as_str()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .map(str::to_lowercase)
+}
+
+/// Little-endian number of `N` bytes found at `offset`
+fn read_le<const N: usize>(data: &[u8], offset: usize) -> Result<u64, String> {
+    let bytes = data
+        .get(offset..offset + N)
+        .ok_or("zip archive ends early")?;
+
+    Ok(bytes
+        .iter()
+        .rev()
+        .fold(0, |value, byte| value << 8 | *byte as u64))
+}
+
+/// Unpack the file named `name` (in any directory) from the given zip archive; its checksum is
+/// checked against the one recorded by the archive
+pub fn unzip(archive: &[u8], name: &str) -> Result<Vec<u8>, String> {
+    // the end of central directory record is last, followed by a comment of up to 64 KiB
+    let end = (0..archive.len().saturating_sub(21))
+        .rev()
+        .take(65557)
+        .find(|offset| read_le::<4>(archive, *offset) == Ok(END_OF_CENTRAL_DIRECTORY as u64))
+        .ok_or("not a zip archive")?;
+
+    let entries = read_le::<2>(archive, end + 10)?;
+    let mut offset = read_le::<4>(archive, end + 16)? as usize;
+
+    for _ in 0..entries {
+        if read_le::<4>(archive, offset)? != CENTRAL_DIRECTORY_HEADER as u64 {
+            return Err(String::from("damaged zip central directory"));
+        }
+
+        let method = read_le::<2>(archive, offset + 10)?;
+        let crc = read_le::<4>(archive, offset + 16)? as u32;
+        let compressed = read_le::<4>(archive, offset + 20)? as usize;
+        let size = read_le::<4>(archive, offset + 24)? as usize;
+        let name_length = read_le::<2>(archive, offset + 28)? as usize;
+        let extra_length = read_le::<2>(archive, offset + 30)? as usize;
+        let comment_length = read_le::<2>(archive, offset + 32)? as usize;
+        let local = read_le::<4>(archive, offset + 42)? as usize;
+
+        let path = archive
+            .get(offset + 46..offset + 46 + name_length)
+            .ok_or("zip archive ends early")?;
+        let path = String::from_utf8_lossy(path);
+
+        offset += 46 + name_length + extra_length + comment_length;
+
+        if path.rsplit('/').next() != Some(name) {
+            continue;
+        }
+
+        if read_le::<4>(archive, local)? != LOCAL_FILE_HEADER as u64 {
+            return Err(format!("damaged zip entry {}", path));
+        }
+
+        // the local header's name and extra field may differ in length from the central one's
+        let start = local
+            + 30
+            + read_le::<2>(archive, local + 26)? as usize
+            + read_le::<2>(archive, local + 28)? as usize;
+        let data = archive
+            .get(start..start + compressed)
+            .ok_or("zip archive ends early")?;
+
+        let contents = match method {
+            0 => data.to_vec(),
+            8 => inflate(data)?,
+            _ => {
+                return Err(format!(
+                    "{} is compressed with unsupported method {}",
+                    path, method
+                ))
+            }
+        };
+
+        if contents.len() != size || crc32(&contents) != crc {
+            return Err(format!("{} is damaged, its checksum doesn't match", path));
+        }
+
+        return Ok(contents);
+    }
+
+    Err(format!("{} wasn't found in the archive", name))
+}
+
+/// Write the new binary next to `current`, with the same permissions, then rename it over
+/// `current`; the new binary is only ever seen whole
+fn replace_binary(current: &Path, binary: &[u8]) -> FeroxResult<()> {
+    let directory = current
+        .parent()
+        .ok_or("the running binary has no directory")?;
+    let file_name = current
+        .file_name()
+        .ok_or("the running binary has no name")?
+        .to_string_lossy();
+    let temporary = directory.join(format!(".{}.update-{}", file_name, process::id()));
+
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temporary)
+        .and_then(|mut file| {
+            file.write_all(binary)?;
+            file.set_permissions(fs::metadata(current)?.permissions())?;
+            file.sync_all()
+        });
+
+    if let Err(e) = written {
+        fs::remove_file(&temporary).unwrap_or_default();
+        return Err(format!("Could not write {}: {}", temporary.display(), e).into());
+    }
+
+    // a running binary can't be replaced on windows, but it can be moved out of the way
+    #[cfg(windows)]
+    fs::rename(current, current.with_extension("old"))?;
+
+    if let Err(e) = fs::rename(&temporary, current) {
+        fs::remove_file(&temporary).unwrap_or_default();
+        return Err(format!("Could not replace {}: {}", current.display(), e).into());
+    }
+
+    Ok(())
+}
+
+/// Replace the running binary with the one of the latest release, if it's newer; returns the
+/// version the binary was updated to, if it was
+pub async fn run() -> FeroxResult<Option<String>> {
+    log::trace!("enter: run");
+
+    // its own client; the scan's may carry headers or a proxy meant for the target
+    let client = Client::builder()
+        .user_agent(format!("feroxbuster/{}", VERSION))
+        .build()?;
+
+    let body = client
+        .get(UPDATE_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let release: Value = serde_json::from_str(&body)?;
+
+    let latest = release["tag_name"]
+        .as_str()
+        .ok_or("Could not get the latest release's version")?;
+
+    if !is_newer(latest, VERSION) {
+        println!(
+            "{} feroxbuster {} is the latest release",
+            status_colorizer("OK"),
+            VERSION
+        );
+        log::trace!("exit: run -> None");
+        return Ok(None);
+    }
+
+    let name = asset_name().ok_or("No binary is released for this platform")?;
+    let asset = release["assets"]
+        .as_array()
+        .and_then(|assets| assets.iter().find(|asset| asset["name"] == name.as_str()))
+        .ok_or_else(|| format!("Release {} has no {}", latest, name))?;
+
+    // an archive that can't be checked isn't installed
+    let expected = published_sha256(asset)
+        .ok_or_else(|| format!("No sha256 digest was published for {}", name))?;
+
+    let url = asset["browser_download_url"]
+        .as_str()
+        .ok_or_else(|| format!("No download url was published for {}", name))?;
+
+    println!("Downloading {}", url);
+
+    let archive = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let actual: String = sha256(&archive)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    if actual != expected {
+        return Err(format!(
+            "Checksum of {} ({}) doesn't match the published one ({})",
+            name, actual, expected
+        )
+        .into());
+    }
+
+    let binary_name = name.trim_end_matches(".zip").rsplit('-').next();
+    let binary = unzip(&archive, binary_name.unwrap_or("feroxbuster"))?;
+
+    let current: PathBuf = env::current_exe()?;
+    replace_binary(&current, &binary)?;
+
+    println!(
+        "{} Updated {} from {} to {}",
+        status_colorizer("OK"),
+        current.display(),
+        VERSION,
+        latest.trim_start_matches('v')
+    );
+
+    log::trace!("exit: run -> {}", latest);
+    Ok(Some(latest.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    /// versions are compared number by number, not as strings
+    fn update_is_newer_compares_numbers() {
+        assert!(is_newer("v1.10.0", "1.9.3"));
+        assert!(is_newer("v2.0.0", "1.1.1"));
+        assert!(!is_newer("v1.1.1", "1.1.1"));
+        assert!(!is_newer("v1.0.9", "1.1.1"));
+    }
+
+    #[test]
+    /// the released archive's name follows the platform
+    fn update_asset_name_matches_platform() {
+        if let Some(name) = asset_name() {
+            assert!(name.contains(env::consts::OS));
+            assert!(name.ends_with("feroxbuster.zip") || name.ends_with("feroxbuster.exe.zip"));
+        }
+    }
+
+    #[test]
+    /// only sha256 digests are used
+    fn update_published_sha256_reads_digest() {
+        let asset = serde_json::json!({"digest": "sha256:ABCDEF"});
+        assert_eq!(published_sha256(&asset), Some(String::from("abcdef")));
+
+        let asset = serde_json::json!({"digest": "sha512:abcdef"});
+        assert_eq!(published_sha256(&asset), None);
+        assert_eq!(published_sha256(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    /// a file is found in a zip archive by name, whatever its directory; deflated and stored
+    /// files are unpacked, damaged ones aren't
+    fn update_unzip_finds_binary() {
+        // zip of x86_64-linux/feroxbuster (deflated) and README (stored), made by python's
+        // zipfile module
+        let archive = include_bytes!("../tests/testdata/release.zip");
+
+        let binary = unzip(archive, "feroxbuster").unwrap();
+        assert_eq!(
+            binary,
+            "#!/bin/sh\necho feroxbuster\n".repeat(50).as_bytes()
+        );
+
+        assert_eq!(unzip(archive, "README").unwrap(), b"release notes\n");
+
+        assert!(unzip(archive, "feroxbuster.exe").is_err());
+        assert!(unzip(b"not a zip archive", "feroxbuster").is_err());
+
+        let mut damaged = archive.to_vec();
+        let position = damaged
+            .windows(6)
+            .position(|window| window == b"README")
+            .unwrap();
+        damaged[position + 6 + 2] ^= 0xff;
+        assert!(unzip(&damaged, "README").is_err());
+    }
+
+    #[test]
+    /// the running binary is replaced whole, keeping its permissions, and nothing is left behind
+    fn update_replace_binary_renames_over_current() {
+        let directory = TempDir::new().unwrap();
+        let current = directory.path().join("feroxbuster");
+        fs::write(&current, b"old").unwrap();
+
+        replace_binary(&current, b"new").unwrap();
+
+        assert_eq!(fs::read(&current).unwrap(), b"new");
+        assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 1);
+    }
+}
@@ -1,9 +1,20 @@
+use crate::hooks;
+use crate::redirects::{self, Chain};
+use crate::statistics::STATISTICS;
+use crate::tui;
 use crate::FeroxResult;
 use console::{strip_ansi_codes, style, user_attended};
 use indicatif::ProgressBar;
 use reqwest::Url;
-use reqwest::{Client, Response};
+use reqwest::{Client, Method, Response};
+use std::cell::RefCell;
 use std::convert::TryInto;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    /// Buffer in which `UrlBuilder` assembles urls, reused from one url to the next
+    static URL_BUFFER: RefCell<String> = RefCell::new(String::with_capacity(256));
+}
 
 /// Helper function that determines the current depth of a given url
 ///
@@ -68,7 +79,13 @@ pub fn status_colorizer(status: &str) -> String {
         Some('4') => style(status).red().to_string(),  // client error
         Some('5') => style(status).red().to_string(),  // server error
         Some('W') => style(status).cyan().to_string(), // wildcard
+        Some('L') => style(status).magenta().to_string(), // directory listing
+        Some('T') => style(status).yellow().to_string(), // recursion trap
+        Some('F') => style(status).blue().to_string(), // fingerprint
+        Some('R') => style(status).yellow().to_string(), // robots.txt
+        Some('A') => style(status).magenta().to_string(), // authentication
         Some('E') => style(status).red().to_string(),  // error
+        Some('D') => style(status).dim().to_string(),  // collapsed duplicates
         _ => status.to_string(),                       // ¯\_(ツ)_/¯
     }
 }
@@ -88,14 +105,14 @@ pub fn get_url_path_length(url: &Url) -> u64 {
 
     let path = url.path();
 
-    let segments = if path.starts_with('/') {
-        path[1..].split_terminator('/')
+    let mut segments = if let Some(stripped) = path.strip_prefix('/') {
+        stripped.split_terminator('/')
     } else {
         log::trace!("exit: get_url_path_length -> 0");
         return 0;
     };
 
-    if let Some(last) = segments.last() {
+    if let Some(last) = segments.next_back() {
         // failure on conversion should be very unlikely. While a usize can absolutely overflow a
         // u64, the generally accepted maximum for the length of a url is ~2000.  so the value we're
         // putting into the u64 should never realistically be anywhere close to producing an
@@ -124,9 +141,14 @@ pub fn get_url_path_length(url: &Url) -> u64 {
 /// If a terminal is not attached, `msg` is printed to stdout, with its ansi
 /// color codes stripped.
 ///
+/// While the dashboard is shown (see `--tui`), `msg` goes to the dashboard instead, which prints
+/// it once it closes
+///
 /// additionally, provides a location for future printing options (no color, etc) to be handled
 pub fn ferox_print(msg: &str, bar: &ProgressBar) {
-    if user_attended() {
+    if tui::is_active() {
+        tui::print(msg);
+    } else if user_attended() {
         bar.println(msg);
     } else {
         let stripped = strip_ansi_codes(msg);
@@ -134,6 +156,57 @@ pub fn ferox_print(msg: &str, bar: &ProgressBar) {
     }
 }
 
+/// Format headers selected with `--show-headers` for text output
+///
+/// example: ` [server: nginx, location: /login]`; empty when no headers are given
+pub fn format_selected_headers(headers: &[(String, String)]) -> String {
+    if headers.is_empty() {
+        return String::new();
+    }
+
+    let formatted: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect();
+
+    format!(" [{}]", formatted.join(", "))
+}
+
+/// Format the given `SystemTime` as an RFC 3339 timestamp in UTC with millisecond precision
+///
+/// example: 2020-10-23T13:37:00.123Z
+pub fn rfc3339_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    let secs = since_epoch.as_secs();
+    let days = (secs / 86_400) as i64;
+    let secs_of_day = secs % 86_400;
+
+    // civil-from-days algorithm, as described by Howard Hinnant
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
 /// Simple helper to generate a `Url`
 ///
 /// Errors during parsing `url` or joining `word` are propagated up the call stack
@@ -173,8 +246,8 @@ pub fn format_url(
     let base_url = reqwest::Url::parse(&url)?;
 
     // extensions and slashes are mutually exclusive cases
-    let word = if extension.is_some() {
-        format!("{}.{}", word, extension.unwrap())
+    let word = if let Some(ext) = extension {
+        format!("{}.{}", word, ext)
     } else if addslash && !word.ends_with('/') {
         // -f used, and word doesn't already end with a /
         format!("{}/", word)
@@ -215,18 +288,151 @@ pub fn format_url(
     }
 }
 
-/// Initiate request to the given `Url` using `Client`
+/// Whether or not the given word (or extension) can be appended to a directory's url as is, and
+/// parse to the same url that `Url::join` would give; words with characters that `join` treats
+/// specially (i.e. `/`, `?`, `%2e`, leading spaces) aren't
+fn is_plain_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && segment
+            .chars()
+            .all(|c| c > ' ' && c != '\u{7f}' && !"/\\:?#%".contains(c))
+}
+
+/// Builds the urls requested for the words of a single directory; the same urls as
+/// [format_url](fn.format_url.html), without parsing the directory's url and joining the word
+/// to it for every single request
+///
+/// Plain words are appended to the directory's url in a reused buffer, then parsed once; other
+/// words go through `format_url`
+#[derive(Debug, Clone)]
+pub struct UrlBuilder {
+    /// url of the directory, as given
+    url: String,
+
+    /// whether or not a slash is appended to words (see `--add-slash`)
+    addslash: bool,
+
+    /// query parameters added to every url (see `--query`)
+    queries: Vec<(String, String)>,
+
+    /// url of the directory, ending with a slash, when words can be appended to it as is
+    prefix: Option<String>,
+
+    /// `queries`, serialized with their leading `?`; empty without any
+    query: String,
+}
+
+impl UrlBuilder {
+    /// Builder of the urls of the directory at `url`, see `format_url` for the other parameters
+    pub fn new(url: &str, addslash: bool, queries: &[(String, String)]) -> Self {
+        let directory = if url.ends_with('/') {
+            Url::parse(url)
+        } else {
+            Url::parse(&format!("{}/", url))
+        };
+
+        // urls with a query or fragment would keep them as a prefix, join replaces them
+        let directory = directory.ok().filter(|directory| {
+            !directory.cannot_be_a_base()
+                && directory.query().is_none()
+                && directory.fragment().is_none()
+        });
+
+        let query = match &directory {
+            Some(directory) if !queries.is_empty() => {
+                let mut with_queries = directory.clone();
+                with_queries.query_pairs_mut().extend_pairs(queries);
+                format!("?{}", with_queries.query().unwrap_or_default())
+            }
+            _ => String::new(),
+        };
+
+        Self {
+            url: url.to_string(),
+            addslash,
+            queries: queries.to_vec(),
+            prefix: directory.map(String::from),
+            query,
+        }
+    }
+
+    /// Url of the given word, with the given extension
+    ///
+    /// Errors are the same as `format_url`'s
+    pub fn build(&self, word: &str, extension: Option<&str>) -> FeroxResult<Url> {
+        log::trace!("enter: build({}, {:?})", word, extension);
+
+        let prefix = match &self.prefix {
+            Some(prefix) if is_plain_segment(word) && extension.is_none_or(is_plain_segment) => {
+                prefix
+            }
+            _ => return format_url(&self.url, word, self.addslash, &self.queries, extension),
+        };
+
+        let url = URL_BUFFER.with(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+
+            buffer.clear();
+            buffer.push_str(prefix);
+            buffer.push_str(word);
+
+            // extensions and slashes are mutually exclusive cases
+            if let Some(ext) = extension {
+                buffer.push('.');
+                buffer.push_str(ext);
+            } else if self.addslash {
+                buffer.push('/');
+            }
+
+            buffer.push_str(&self.query);
+
+            Url::parse(&buffer)
+        })?;
+
+        log::trace!("exit: build -> {}", url);
+        Ok(url)
+    }
+}
+
+/// Initiate a GET request to the given `Url` using `Client`
 pub async fn make_request(client: &Client, url: &Url) -> FeroxResult<Response> {
-    log::trace!("enter: make_request(CONFIGURATION.Client, {})", url);
+    make_redirected_request(client, &Method::GET, url)
+        .await
+        .map(|(response, _)| response)
+}
+
+/// Same as [make_request](fn.make_request.html) with the given method, along with the redirects
+/// followed to get the response, if any (see `--redirects`)
+pub async fn make_redirected_request(
+    client: &Client,
+    method: &Method,
+    url: &Url,
+) -> FeroxResult<(Response, Option<Chain>)> {
+    log::trace!(
+        "enter: make_redirected_request(CONFIGURATION.Client, {}, {})",
+        method,
+        url
+    );
+
+    STATISTICS.add_request();
+
+    let sent = hooks::send(client, method, url).await;
+
+    // the chain is taken whatever the outcome, so that none are left behind
+    let chain = redirects::take(url);
 
-    match client.get(url.to_owned()).send().await {
+    match sent {
         Ok(resp) => {
+            STATISTICS.add_response(resp.status(), resp.content_length().unwrap_or(0));
             log::debug!("requested Url: {}", resp.url());
-            log::trace!("exit: make_request -> {:?}", resp);
-            Ok(resp)
+            log::trace!("exit: make_redirected_request -> {:?}", resp);
+            Ok((resp, chain))
         }
         Err(e) => {
-            log::trace!("exit: make_request -> {}", e);
+            STATISTICS.add_error(&e);
+            log::trace!("exit: make_redirected_request -> {}", e);
             if e.to_string().contains("operation timed out") {
                 // only warn for timeouts, while actual errors are still left as errors
                 log::warn!("Error while making request: {}", e);
@@ -352,6 +558,99 @@ mod tests {
         );
     }
 
+    #[test]
+    /// the builder gives the same urls as format_url, including for words that join treats
+    /// specially and directories that can't simply be appended to
+    fn url_builder_matches_format_url() {
+        let queries = [
+            (String::from("stuff"), String::from("th ings")),
+            (String::from("a&b"), String::from("=")),
+        ];
+
+        let words = [
+            "admin",
+            "/admin",
+            "admin/",
+            ".",
+            "..",
+            "..%2f",
+            "%2e%2e",
+            " admin",
+            "admin ",
+            "a b",
+            "a:b",
+            "http://other",
+            "//other",
+            "a?b",
+            "a#b",
+            "a\\b",
+            "\u{e9}t\u{e9}",
+            "~user",
+            ".git",
+            "a\tb",
+            "[x]",
+            "",
+        ];
+
+        for url in [
+            "http://localhost",
+            "http://LOCALHOST:80/js",
+            "https://localhost/js/",
+            "http://localhost/js?id=1",
+            "http://localhost/js#top",
+        ]
+        .iter()
+        {
+            for addslash in [false, true].iter() {
+                for queries in [&[][..], &queries[..]].iter() {
+                    let builder = UrlBuilder::new(url, *addslash, queries);
+
+                    for word in words.iter() {
+                        for extension in [None, Some("php"), Some("tar.gz"), Some("p?p")].iter() {
+                            assert_eq!(
+                                builder.build(word, *extension).ok(),
+                                format_url(url, word, *addslash, queries, *extension).ok(),
+                                "{} {} {} {:?}",
+                                url,
+                                word,
+                                addslash,
+                                extension
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    /// selected headers are shown in brackets, nothing is shown when none were selected
+    fn format_selected_headers_with_and_without_headers() {
+        assert_eq!(format_selected_headers(&[]), "");
+
+        let headers = vec![
+            (String::from("server"), String::from("nginx")),
+            (String::from("location"), String::from("/login")),
+        ];
+        assert_eq!(
+            format_selected_headers(&headers),
+            " [server: nginx, location: /login]"
+        );
+    }
+
+    #[test]
+    /// the unix epoch is formatted correctly
+    fn rfc3339_timestamp_formats_epoch() {
+        assert_eq!(rfc3339_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    /// a known point in time (including a leap day) is formatted correctly
+    fn rfc3339_timestamp_formats_known_time() {
+        let time = UNIX_EPOCH + std::time::Duration::from_millis(1_582_977_600_250);
+        assert_eq!(rfc3339_timestamp(time), "2020-02-29T12:00:00.250Z");
+    }
+
     #[test]
     /// status colorizer uses red for 500s
     fn status_colorizer_uses_red_for_500s() {
@@ -376,6 +675,18 @@ mod tests {
         assert_eq!(status_colorizer("WLD"), style("WLD").cyan().to_string());
     }
 
+    #[test]
+    /// status colorizer uses magenta for directory listings
+    fn status_colorizer_uses_magenta_for_listings() {
+        assert_eq!(status_colorizer("LST"), style("LST").magenta().to_string());
+    }
+
+    #[test]
+    /// status colorizer dims collapsed duplicates
+    fn status_colorizer_dims_duplicates() {
+        assert_eq!(status_colorizer("DUP"), style("DUP").dim().to_string());
+    }
+
     #[test]
     /// status colorizer uses blue for 100s
     fn status_colorizer_uses_blue_for_100s() {
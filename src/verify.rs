@@ -0,0 +1,178 @@
+use crate::client;
+use crate::config::{CONFIGURATION, PROGRESS_PRINTER};
+use crate::hooks;
+use crate::limiter::RateLimiter;
+use crate::metadata::tag;
+use crate::proxy;
+use crate::utils::{ferox_print, status_colorizer};
+use console::style;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// requests per second made while verifying findings; kept low so that rate limiting doesn't
+/// cause the very artifacts being weeded out
+const VERIFY_RATE: usize = 5;
+
+/// A reported finding, as needed to request it again
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// url of the finding
+    pub url: Url,
+
+    /// status code the finding was reported with
+    pub status: StatusCode,
+
+    /// size the finding was reported with
+    pub content_length: u64,
+}
+
+/// Whether or not a finding reproduced; it does when requesting it again gives the same status
+/// code, sizes of dynamic pages are expected to vary
+///
+/// `now` is `None` when the request failed
+pub fn reproduces(finding: &Finding, now: Option<StatusCode>) -> bool {
+    now == Some(finding.status)
+}
+
+/// Client used to verify findings; findings go through `--replay-proxy` when given, instead of
+/// `--proxy`
+fn verify_client() -> Client {
+    if CONFIGURATION.replay_proxy.is_empty() {
+        CONFIGURATION.client.clone()
+    } else {
+        client::initialize(
+            CONFIGURATION.timeout,
+            &CONFIGURATION.useragent,
+            CONFIGURATION.redirects,
+            CONFIGURATION.insecure,
+            Some(&proxy::resolve(
+                &CONFIGURATION.replay_proxy,
+                &CONFIGURATION.replay_proxy_auth,
+                false,
+            )),
+        )
+    }
+}
+
+/// Print a message and send it to the output file, if one is in use; `record` is written instead
+/// with `--json`
+fn report(msg: &str, mut record: Value, file_chan: &UnboundedSender<String>, save_output: bool) {
+    if !CONFIGURATION.quiet {
+        ferox_print(msg, &PROGRESS_PRINTER);
+    }
+
+    if save_output {
+        let file_report = if CONFIGURATION.json {
+            tag(&mut record);
+            format!("{}\n", record)
+        } else {
+            msg.to_string()
+        };
+
+        if let Err(e) = file_chan.send(file_report) {
+            log::error!("Could not send verification to file handler: {}", e);
+        }
+    }
+}
+
+/// Request every finding again, once the scan is complete (see `--verify`), and report the ones
+/// that no longer reproduce along with a summary
+///
+/// Verification requests aren't part of the scan and aren't counted in its statistics
+pub async fn verify(findings: &[Finding], file_chan: &UnboundedSender<String>, save_output: bool) {
+    log::trace!(
+        "enter: verify(findings[{}...], {:?}, {})",
+        findings.len(),
+        file_chan,
+        save_output
+    );
+
+    let client = verify_client();
+    let limiter = RateLimiter::new(VERIFY_RATE);
+    let mut reproduced = 0;
+
+    for finding in findings {
+        if let Some(limiter) = &limiter {
+            limiter.wait().await;
+        }
+
+        let now = match hooks::send(&client, &Method::GET, &finding.url).await {
+            Ok(response) => Some(response.status()),
+            Err(e) => {
+                log::warn!("Could not verify {}: {}", finding.url, e);
+                None
+            }
+        };
+
+        if reproduces(finding, now) {
+            reproduced += 1;
+            continue;
+        }
+
+        let now_text = now.map_or_else(
+            || String::from("no response"),
+            |status| status_colorizer(status.as_str()),
+        );
+
+        // example output
+        // UNV     200       3280 http://localhost/FAQ no longer reproduces; now 429
+        let msg = format!(
+            "{} {} {:>10} {} no longer reproduces; now {}\n",
+            style(format!("{:<7}", "UNV")).red(),
+            status_colorizer(finding.status.as_str()),
+            finding.content_length,
+            finding.url,
+            now_text
+        );
+
+        let record = json!({
+            "type": "verification",
+            "url": finding.url.as_str(),
+            "status": finding.status.as_u16(),
+            "content_length": finding.content_length,
+            "reproduced": false,
+            "now": now.map(|status| status.as_u16()),
+        });
+
+        report(&msg, record, file_chan, save_output);
+    }
+
+    let msg = format!(
+        "{} {:>10} {} of {} findings reproduced\n",
+        style("VRF").cyan(),
+        "-",
+        reproduced,
+        findings.len()
+    );
+
+    let record = json!({
+        "type": "verification_summary",
+        "findings": findings.len(),
+        "reproduced": reproduced,
+    });
+
+    report(&msg, record, file_chan, save_output);
+
+    log::trace!("exit: verify");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// only the same status code reproduces a finding, a failed request never does
+    fn verify_reproduces_requires_same_status() {
+        let finding = Finding {
+            url: Url::parse("http://localhost/admin").unwrap(),
+            status: StatusCode::OK,
+            content_length: 42,
+        };
+
+        assert!(reproduces(&finding, Some(StatusCode::OK)));
+        assert!(!reproduces(&finding, Some(StatusCode::NO_CONTENT)));
+        assert!(!reproduces(&finding, Some(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!reproduces(&finding, None));
+    }
+}
@@ -0,0 +1,685 @@
+use crate::{FeroxResult, VERSION};
+use openssl::sha::sha256;
+use reqwest::{Client, Url};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader};
+use std::iter;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+/// Name that refers to the wordlist compiled into feroxbuster, i.e. `-w embedded`
+pub const EMBEDDED: &str = "embedded";
+
+/// Curated list of common words compiled into the binary (see the `embedded-wordlist` feature)
+#[cfg(feature = "embedded-wordlist")]
+const EMBEDDED_WORDS: &str = include_str!("../wordlists/embedded.txt");
+
+/// Contents of the embedded wordlist, if feroxbuster was built with one
+#[cfg(feature = "embedded-wordlist")]
+pub fn embedded() -> Option<&'static str> {
+    Some(EMBEDDED_WORDS)
+}
+
+/// Contents of the embedded wordlist, if feroxbuster was built with one
+#[cfg(not(feature = "embedded-wordlist"))]
+pub fn embedded() -> Option<&'static str> {
+    None
+}
+
+/// Whether or not the given wordlist refers to the embedded wordlist (and there is one)
+pub fn is_embedded(wordlist: &str) -> bool {
+    wordlist == EMBEDDED && embedded().is_some()
+}
+
+/// Whether or not the given wordlist should be downloaded instead of read from disk
+pub fn is_remote(wordlist: &str) -> bool {
+    wordlist.starts_with("http://") || wordlist.starts_with("https://")
+}
+
+/// Whether or not the given line (trimmed) is a word: empty lines and comments (lines starting
+/// with `#`) aren't
+fn is_word(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with('#')
+}
+
+/// 64 bit hash of a word, used to tell words apart while a wordlist is indexed without holding
+/// on to the words themselves
+fn word_hash(word: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    word.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Set of line numbers, one bit per line
+#[derive(Debug, Default)]
+struct LineSet {
+    /// bits of the lines, 64 lines per element
+    bits: Vec<u64>,
+}
+
+impl LineSet {
+    /// Add the given line to the set
+    fn insert(&mut self, line: usize) {
+        let (index, bit) = (line / 64, line % 64);
+
+        if index >= self.bits.len() {
+            self.bits.resize(index + 1, 0);
+        }
+
+        self.bits[index] |= 1 << bit;
+    }
+
+    /// Whether or not the given line is in the set
+    fn contains(&self, line: usize) -> bool {
+        self.bits
+            .get(line / 64)
+            .is_some_and(|bits| bits & (1 << (line % 64)) != 0)
+    }
+}
+
+/// Where the words of a wordlist are read from, every time they're iterated over
+#[derive(Debug)]
+pub enum Origin {
+    /// a file on disk, streamed through a buffer
+    File(PathBuf),
+
+    /// contents held in memory, i.e. the embedded wordlist or a downloaded one
+    Memory(Cow<'static, [u8]>),
+}
+
+impl Origin {
+    /// Buffered reader of the contents, from the start
+    fn reader(&self) -> io::Result<Box<dyn BufRead + Send + '_>> {
+        match self {
+            Origin::File(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            Origin::Memory(contents) => Ok(Box::new(contents.as_ref())),
+        }
+    }
+}
+
+/// Part of a wordlist: the lines of an origin that hold words to use, or words in memory
+#[derive(Debug)]
+enum Source {
+    /// lines of the origin that are words not found on an earlier line (or source)
+    Lines { origin: Origin, words: LineSet },
+
+    /// words held in memory, i.e. transformed ones
+    Words(Vec<String>),
+}
+
+impl Source {
+    /// Words of the source, in order; reading stops at the first error, which is logged
+    fn words(&self) -> Box<dyn Iterator<Item = String> + Send + '_> {
+        match self {
+            Source::Words(words) => Box::new(words.iter().cloned()),
+            Source::Lines { origin, words } => {
+                let reader = match origin.reader() {
+                    Ok(reader) => reader,
+                    Err(e) => {
+                        log::error!("Could not read wordlist {:?}: {}", origin, e);
+                        return Box::new(iter::empty());
+                    }
+                };
+
+                let lines = reader.lines().map_while(move |line| {
+                    line.map_err(|e| log::error!("Could not read wordlist {:?}: {}", origin, e))
+                        .ok()
+                });
+
+                Box::new(
+                    lines
+                        .enumerate()
+                        .filter(move |(number, _)| words.contains(*number))
+                        .map(|(_, line)| line.trim().to_string()),
+                )
+            }
+        }
+    }
+}
+
+/// Unique words of one or more lists, read from where they're kept every time they're iterated
+/// over instead of being held in memory
+///
+/// Lists are indexed once as they're added: every line is read to find which ones are words not
+/// seen before, and only their line numbers are kept. Words are told apart by a 64 bit hash while
+/// indexing, which holds far less than the words themselves; two different words with the same
+/// hash would see the second one skipped, which is vanishingly unlikely.
+#[derive(Debug, Default)]
+pub struct Wordlist {
+    /// the lists the words are read from, in order
+    sources: Vec<Source>,
+
+    /// number of unique words across every source
+    len: usize,
+}
+
+impl Wordlist {
+    /// Wordlist of the unique words of a single origin
+    pub fn read(origin: Origin) -> io::Result<Self> {
+        let mut wordlist = Self::default();
+        wordlist.add(origin, &mut HashSet::new())?;
+        Ok(wordlist)
+    }
+
+    /// Add the words of the given origin that aren't already part of the list; `seen` holds the
+    /// hashes of the words added so far, and is shared by every call that builds the same list
+    ///
+    /// Surrounding whitespace is trimmed, and empty lines and comments are skipped. Returns the
+    /// number of words added.
+    pub fn add(&mut self, origin: Origin, seen: &mut HashSet<u64>) -> io::Result<usize> {
+        log::trace!("enter: add({:?}, [{} seen...])", origin, seen.len());
+
+        let mut reader = origin.reader()?;
+        let mut words = LineSet::default();
+        let mut line = String::new();
+        let mut number = 0;
+        let mut added = 0;
+
+        while reader.read_line(&mut line)? > 0 {
+            let word = line.trim();
+
+            if is_word(word) && seen.insert(word_hash(word)) {
+                words.insert(number);
+                added += 1;
+            }
+
+            line.clear();
+            number += 1;
+        }
+
+        drop(reader);
+
+        self.sources.push(Source::Lines { origin, words });
+        self.len += added;
+
+        log::trace!("exit: add -> {}", added);
+        Ok(added)
+    }
+
+    /// Number of unique words
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether or not the list has no words at all
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Words of the list, in the order of their lists and lines
+    pub fn iter(&self) -> impl Iterator<Item = String> + Send + '_ {
+        self.sources.iter().flat_map(Source::words)
+    }
+
+    /// Apply the given transformations to every word (see `transform`); the transformed words
+    /// are held in memory, the list is kept as it is when no word would change
+    pub fn transform(self, transforms: &Transforms) -> Self {
+        let needs_expansion = self
+            .iter()
+            .any(|word| word.contains(YEAR) || word.contains(EXT));
+
+        if transforms.is_noop() && !needs_expansion {
+            return self;
+        }
+
+        let words = transform(self.iter().collect(), transforms);
+
+        Self {
+            len: words.len(),
+            sources: vec![Source::Words(words.into_iter().collect())],
+        }
+    }
+}
+
+/// Placeholder replaced by each year of `Transforms::years`, i.e. `backup-%year%.zip`
+pub const YEAR: &str = "%year%";
+
+/// Placeholder replaced by each of the `-x` extensions, i.e. `index.%ext%`; words containing it
+/// are skipped when no extensions are given
+pub const EXT: &str = "%ext%";
+
+/// Casings in which words may be added, see `Transforms::cases`
+pub const CASES: [&str; 3] = ["lower", "upper", "capitalize"];
+
+/// Transformations applied to every word as the wordlist(s) are loaded
+#[derive(Debug)]
+pub struct Transforms<'a> {
+    /// strings with which every word is also requested, prepended
+    pub prefixes: &'a [String],
+
+    /// strings with which every word is also requested, appended
+    pub suffixes: &'a [String],
+
+    /// casings (lower, upper, capitalize) in which every word is also requested
+    pub cases: &'a [String],
+
+    /// replacements for the `%ext%` placeholder
+    pub extensions: &'a [String],
+
+    /// replacements for the `%year%` placeholder
+    pub years: RangeInclusive<u32>,
+}
+
+impl Transforms<'_> {
+    /// Whether or not any word could be changed by these transformations
+    fn is_noop(&self) -> bool {
+        self.prefixes.is_empty() && self.suffixes.is_empty() && self.cases.is_empty()
+    }
+}
+
+/// Replace the placeholders in `word`, one word per combination of replacements
+fn expand_placeholders(word: &str, transforms: &Transforms) -> Vec<String> {
+    let mut expanded = vec![word.to_string()];
+
+    if word.contains(YEAR) {
+        expanded = transforms
+            .years
+            .clone()
+            .map(|year| word.replace(YEAR, &year.to_string()))
+            .collect();
+    }
+
+    if word.contains(EXT) {
+        expanded = expanded
+            .iter()
+            .flat_map(|word| {
+                transforms
+                    .extensions
+                    .iter()
+                    .map(move |ext| word.replace(EXT, ext))
+            })
+            .collect();
+    }
+
+    expanded
+}
+
+/// `word` in the given case (one of lower, upper, or capitalize)
+fn with_case(word: &str, case: &str) -> Option<String> {
+    match case {
+        "lower" => Some(word.to_lowercase()),
+        "upper" => Some(word.to_uppercase()),
+        "capitalize" => {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                })
+                .map(|capitalized| capitalized.collect())
+        }
+        _ => None,
+    }
+}
+
+/// Apply the given transformations to every word
+///
+/// Placeholders are replaced first, then each resulting word is added in every casing, then each
+/// of those is added with every prefix and, separately, with every suffix. Words are kept as they
+/// are alongside their variants, and variants that collide are only kept once.
+pub fn transform(words: HashSet<String>, transforms: &Transforms) -> HashSet<String> {
+    log::trace!(
+        "enter: transform([{} words...], {:?})",
+        words.len(),
+        transforms
+    );
+
+    let needs_expansion = words
+        .iter()
+        .any(|word| word.contains(YEAR) || word.contains(EXT));
+
+    if transforms.is_noop() && !needs_expansion {
+        log::trace!("exit: transform -> [{} words...]", words.len());
+        return words;
+    }
+
+    for case in transforms.cases {
+        if !CASES.contains(&case.as_str()) {
+            log::warn!("Ignoring unknown word case: {}", case);
+        }
+    }
+
+    let mut transformed = HashSet::new();
+
+    for word in &words {
+        for expanded in expand_placeholders(word, transforms) {
+            let mut cased: Vec<String> = transforms
+                .cases
+                .iter()
+                .filter_map(|case| with_case(&expanded, case))
+                .collect();
+            cased.push(expanded);
+
+            for word in cased {
+                for prefix in transforms.prefixes {
+                    transformed.insert(format!("{}{}", prefix, word));
+                }
+
+                for suffix in transforms.suffixes {
+                    transformed.insert(format!("{}{}", word, suffix));
+                }
+
+                transformed.insert(word);
+            }
+        }
+    }
+
+    log::trace!("exit: transform -> [{} words...]", transformed.len());
+    transformed
+}
+
+/// Lowercase hex encoding of the SHA-256 digest of `contents`
+fn sha256_hex(contents: &[u8]) -> String {
+    sha256(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Location of the given url's cached copy within `cache_dir`
+///
+/// The file's name starts with a digest of the full url, so that lists with the same name from
+/// different places don't collide, i.e. CACHE_DIR/9f86d081884c7d65-raft-medium-directories.txt
+fn cache_path(cache_dir: &str, url: &str) -> PathBuf {
+    let name = Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back().map(String::from))
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| String::from("wordlist.txt"));
+
+    let digest = sha256_hex(url.as_bytes());
+
+    Path::new(cache_dir).join(format!("{}-{}", &digest[..16], name))
+}
+
+/// Whether or not `contents` matches the expected checksum; anything matches when no checksum
+/// is expected
+fn checksum_matches(contents: &[u8], expected: &str) -> bool {
+    expected.is_empty() || sha256_hex(contents).eq_ignore_ascii_case(expected.trim())
+}
+
+/// Download the wordlist at `url` and return its contents
+///
+/// When `cache_dir` is given, a previously downloaded copy is used if one exists (and matches
+/// `sha256`, when given); otherwise the list is downloaded and saved there for next time. When
+/// `sha256` is given, the downloaded list must match it.
+///
+/// The download doesn't use the scan's client, so none of the headers, cookies, or proxy meant
+/// for the target are sent to the wordlist's host.
+pub async fn fetch(url: &str, cache_dir: &str, sha256: &str) -> FeroxResult<Vec<u8>> {
+    log::trace!("enter: fetch({}, {}, {})", url, cache_dir, sha256);
+
+    let cached = if cache_dir.is_empty() {
+        None
+    } else {
+        Some(cache_path(cache_dir, url))
+    };
+
+    if let Some(cached) = &cached {
+        if let Ok(contents) = fs::read(cached) {
+            if checksum_matches(&contents, sha256) {
+                log::info!("Using cached copy of {} at {}", url, cached.display());
+                log::trace!("exit: fetch -> [{} bytes]", contents.len());
+                return Ok(contents);
+            }
+
+            log::warn!(
+                "Cached copy of {} at {} doesn't match the expected checksum, downloading again",
+                url,
+                cached.display()
+            );
+        }
+    }
+
+    log::info!("Downloading wordlist from {}", url);
+
+    let client = Client::builder()
+        .user_agent(format!("feroxbuster/{}", VERSION))
+        .build()?;
+
+    let contents = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec();
+
+    if !checksum_matches(&contents, sha256) {
+        let message = format!(
+            "Checksum of {} ({}) doesn't match the expected checksum ({})",
+            url,
+            sha256_hex(&contents),
+            sha256.trim()
+        );
+        log::trace!("exit: fetch -> {}", message);
+        return Err(message.into());
+    }
+
+    if let Some(cached) = &cached {
+        // a failure to cache the list shouldn't stop the scan
+        let saved = fs::create_dir_all(cache_dir).and_then(|_| fs::write(cached, &contents));
+
+        if let Err(e) = saved {
+            log::warn!("Could not cache {} at {}: {}", url, cached.display(), e);
+        }
+    }
+
+    log::trace!("exit: fetch -> [{} bytes]", contents.len());
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// only http(s) urls are downloaded
+    fn wordlist_is_remote_checks_scheme() {
+        assert!(is_remote(
+            "https://raw.githubusercontent.com/x/raft-medium.txt"
+        ));
+        assert!(is_remote("http://127.0.0.1/words.txt"));
+        assert!(!is_remote("/usr/share/wordlists/dirb/common.txt"));
+        assert!(!is_remote("httpwords.txt"));
+    }
+
+    /// helper to build a wordlist from the given contents
+    fn wordlist(contents: &[&'static str]) -> Wordlist {
+        let mut wordlist = Wordlist::default();
+        let mut seen = HashSet::new();
+
+        for contents in contents {
+            let origin = Origin::Memory(Cow::Borrowed(contents.as_bytes()));
+            wordlist.add(origin, &mut seen).unwrap();
+        }
+
+        wordlist
+    }
+
+    #[test]
+    /// comments and empty lines are skipped, duplicates are removed after trimming
+    fn wordlist_unique_words_skips_comments_and_duplicates() {
+        let contents = "# comment\nadmin\n\n  admin\r\n \t\n  # indented comment\nlogin \n";
+        let words = wordlist(&[contents]);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words.iter().collect::<Vec<_>>(), ["admin", "login"]);
+    }
+
+    #[test]
+    /// words found in an earlier list are skipped, every list is read in order each time the
+    /// words are iterated over
+    fn wordlist_streams_unique_words_across_lists() {
+        let words = wordlist(&["admin\nlogin", "backup\nadmin\n#login\nlogin\nimages"]);
+        let expected = ["admin", "login", "backup", "images"];
+
+        assert_eq!(words.len(), 4);
+        assert_eq!(words.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(words.iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    /// words of a file are read from disk each time, a file gone missing yields no words
+    fn wordlist_streams_words_from_file() {
+        let path = std::env::temp_dir().join(format!("ferox-wordlist-{}", std::process::id()));
+        fs::write(&path, "admin\n\nlogin\nadmin\n").unwrap();
+
+        let mut words = Wordlist::default();
+        let added = words.add(Origin::File(path.clone()), &mut HashSet::new());
+        assert_eq!(added.unwrap(), 2);
+        assert_eq!(words.iter().collect::<Vec<_>>(), ["admin", "login"]);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(words.iter().count(), 0);
+        assert_eq!(words.len(), 2);
+    }
+
+    #[test]
+    /// the embedded wordlist only has unique words, and isn't empty, when it's compiled in
+    fn wordlist_embedded_has_words() {
+        match embedded() {
+            Some(contents) => {
+                let words = wordlist(&[contents]);
+                let lines = contents.lines().filter(|line| !line.starts_with('#'));
+
+                assert!(is_embedded(EMBEDDED));
+                assert!(!words.is_empty());
+                assert_eq!(words.len(), lines.filter(|line| !line.is_empty()).count());
+            }
+            None => assert!(!is_embedded(EMBEDDED)),
+        }
+    }
+
+    #[test]
+    /// transformed words are held in memory, untouched lists are kept as they are
+    fn wordlist_transform_replaces_words() {
+        let extensions = vec![String::from("php")];
+
+        let words = wordlist(&["admin"]).transform(&transforms(&extensions));
+        assert!(matches!(words.sources[0], Source::Lines { .. }));
+
+        let words = wordlist(&["admin\nindex.%ext%"]).transform(&transforms(&extensions));
+        let mut transformed: Vec<_> = words.iter().collect();
+        transformed.sort();
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(transformed, ["admin", "index.php"]);
+    }
+
+    /// helper to build a HashSet of words from the given strs
+    fn words(words: &[&str]) -> HashSet<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// helper to build transforms for a two year window, without prefixes, suffixes, or cases
+    fn transforms(extensions: &[String]) -> Transforms<'_> {
+        Transforms {
+            prefixes: &[],
+            suffixes: &[],
+            cases: &[],
+            extensions,
+            years: 2019..=2020,
+        }
+    }
+
+    #[test]
+    /// words without placeholders are untouched when there's nothing to do
+    fn wordlist_transform_without_transforms_keeps_words() {
+        let original = words(&["admin", "login"]);
+        assert_eq!(transform(original.clone(), &transforms(&[])), original);
+    }
+
+    #[test]
+    /// %year% and %ext% are replaced with every combination of years and extensions, words with
+    /// %ext% are skipped without extensions
+    fn wordlist_transform_replaces_placeholders() {
+        let extensions = vec![String::from("php"), String::from("bak")];
+        let original = words(&["backup-%year%.%ext%", "admin"]);
+
+        let transformed = transform(original.clone(), &transforms(&extensions));
+        let expected = words(&[
+            "backup-2019.php",
+            "backup-2019.bak",
+            "backup-2020.php",
+            "backup-2020.bak",
+            "admin",
+        ]);
+        assert_eq!(transformed, expected);
+
+        let transformed = transform(original, &transforms(&[]));
+        assert_eq!(transformed, words(&["admin"]));
+    }
+
+    #[test]
+    /// casings are added, then prefixes and suffixes (separately) to each of them; the original
+    /// words are kept and collisions are only kept once
+    fn wordlist_transform_adds_cases_prefixes_and_suffixes() {
+        let prefixes = vec![String::from("_")];
+        let suffixes = vec![String::from("~")];
+        let cases = vec![
+            String::from("upper"),
+            String::from("capitalize"),
+            String::from("lower"),
+            String::from("unknown"),
+        ];
+
+        let transforms = Transforms {
+            prefixes: &prefixes,
+            suffixes: &suffixes,
+            cases: &cases,
+            ..transforms(&[])
+        };
+
+        let transformed = transform(words(&["admin", "ADMIN"]), &transforms);
+        let expected = words(&[
+            "admin", "_admin", "admin~", "ADMIN", "_ADMIN", "ADMIN~", "Admin", "_Admin", "Admin~",
+        ]);
+        assert_eq!(transformed, expected);
+    }
+
+    #[test]
+    /// every casing is applied to each character as expected
+    fn wordlist_with_case_changes_case() {
+        assert_eq!(with_case("wp-Admin", "lower").unwrap(), "wp-admin");
+        assert_eq!(with_case("wp-Admin", "upper").unwrap(), "WP-ADMIN");
+        assert_eq!(with_case("wp-Admin", "capitalize").unwrap(), "Wp-admin");
+        assert_eq!(with_case("", "capitalize"), None);
+        assert_eq!(with_case("admin", "title"), None);
+    }
+
+    #[test]
+    /// cached copies are named after the list, prefixed with a digest of the url
+    fn wordlist_cache_path_uses_url_digest_and_name() {
+        let first = cache_path("/cache", "https://a.com/lists/raft-medium.txt");
+        let second = cache_path("/cache", "https://b.com/lists/raft-medium.txt");
+
+        assert_ne!(first, second);
+        assert!(first.starts_with("/cache"));
+        assert!(first.to_string_lossy().ends_with("-raft-medium.txt"));
+
+        let nameless = cache_path("/cache", "https://a.com/");
+        assert!(nameless.to_string_lossy().ends_with("-wordlist.txt"));
+    }
+
+    #[test]
+    /// checksums are compared without regard to case, no checksum always matches
+    fn wordlist_checksum_matches_sha256() {
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+        assert!(checksum_matches(b"hello", hello));
+        assert!(checksum_matches(b"hello", &hello.to_uppercase()));
+        assert!(checksum_matches(b"hello", ""));
+        assert!(!checksum_matches(b"hello!", hello));
+    }
+}
@@ -0,0 +1,320 @@
+use crate::digest::BodyHash;
+use reqwest::Url;
+use std::collections::BTreeMap;
+
+/// Styles of xml document that can be produced
+pub const XML_STYLES: [&str; 2] = ["ferox", "dirb"];
+
+/// Escape a value for use within an xml attribute or element
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if c.is_control() && c != '\t' && c != '\n' => {} // not allowed in xml 1.0
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Single reported result
+#[derive(Debug, Clone)]
+struct XmlResult {
+    /// url of the response
+    url: Url,
+
+    /// status code of the response
+    status: u16,
+
+    /// content-length of the response
+    content_length: u64,
+
+    /// headers selected with `--show-headers`
+    headers: Vec<(String, String)>,
+
+    /// hash of the response's body, with `--body-hash`
+    body_hash: Option<BodyHash>,
+
+    /// Shodan-compatible hash of a favicon's body
+    favicon_hash: Option<i32>,
+}
+
+/// All reported results, rendered as an xml document once the scan completes
+///
+/// The `ferox` style is documented in the README; results are listed in the order they were
+/// reported, with the hash of their body as an attribute named after its algorithm (i.e.
+/// `sha256="..."`) when `--body-hash` is used, and the hash of favicons as `favicon_hash="..."`
+///
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <feroxbuster version="1.1.1">
+///   <tags>
+///     <tag key="scan_id" value="..."/>
+///   </tags>
+///   <results>
+///     <result url="http://localhost/admin" path="/admin" status="301" content_length="0">
+///       <header name="location" value="/admin/"/>
+///     </result>
+///   </results>
+/// </feroxbuster>
+/// ```
+///
+/// The `dirb` style groups results by the directory in which they were found, similar to dirb's
+/// text output
+///
+/// ```text
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <dirb>
+///   <directory url="http://localhost/">
+///     <entry url="http://localhost/admin" code="301" size="0"/>
+///   </directory>
+/// </dirb>
+/// ```
+#[derive(Debug, Default)]
+pub struct XmlReport {
+    /// results in the order they were reported
+    results: Vec<XmlResult>,
+}
+
+impl XmlReport {
+    /// Add a reported result
+    pub fn insert(
+        &mut self,
+        url: &Url,
+        status: u16,
+        content_length: u64,
+        headers: &[(String, String)],
+        body_hash: Option<&BodyHash>,
+        favicon_hash: Option<i32>,
+    ) {
+        self.results.push(XmlResult {
+            url: url.clone(),
+            status,
+            content_length,
+            headers: headers.to_vec(),
+            body_hash: body_hash.cloned(),
+            favicon_hash,
+        });
+    }
+
+    /// Render the document in the given style, anything other than `dirb` is rendered in the
+    /// `ferox` style
+    ///
+    /// `tags` are the key/value pairs attached to the scan (see `metadata`), only used by the
+    /// `ferox` style
+    pub fn render(&self, style: &str, version: &str, tags: &[(String, String)]) -> String {
+        match style {
+            "dirb" => self.render_dirb(),
+            _ => self.render_ferox(version, tags),
+        }
+    }
+
+    /// Render the document in the `ferox` style
+    fn render_ferox(&self, version: &str, tags: &[(String, String)]) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<feroxbuster version=\"{}\">\n", escape(version)));
+
+        xml.push_str("  <tags>\n");
+        for (key, value) in tags {
+            xml.push_str(&format!(
+                "    <tag key=\"{}\" value=\"{}\"/>\n",
+                escape(key),
+                escape(value)
+            ));
+        }
+        xml.push_str("  </tags>\n");
+
+        xml.push_str("  <results>\n");
+        for result in &self.results {
+            let mut attributes = format!(
+                "url=\"{}\" path=\"{}\" status=\"{}\" content_length=\"{}\"",
+                escape(result.url.as_str()),
+                escape(result.url.path()),
+                result.status,
+                result.content_length
+            );
+
+            if let Some(body_hash) = &result.body_hash {
+                attributes.push_str(&format!(" {}=\"{}\"", body_hash.algorithm, body_hash.hex));
+            }
+
+            if let Some(favicon_hash) = result.favicon_hash {
+                attributes.push_str(&format!(" favicon_hash=\"{}\"", favicon_hash));
+            }
+
+            if result.headers.is_empty() {
+                xml.push_str(&format!("    <result {}/>\n", attributes));
+                continue;
+            }
+
+            xml.push_str(&format!("    <result {}>\n", attributes));
+            for (name, value) in &result.headers {
+                xml.push_str(&format!(
+                    "      <header name=\"{}\" value=\"{}\"/>\n",
+                    escape(name),
+                    escape(value)
+                ));
+            }
+            xml.push_str("    </result>\n");
+        }
+        xml.push_str("  </results>\n");
+
+        xml.push_str("</feroxbuster>\n");
+        xml
+    }
+
+    /// Render the document in the `dirb` style
+    fn render_dirb(&self) -> String {
+        let mut directories: BTreeMap<String, Vec<&XmlResult>> = BTreeMap::new();
+
+        for result in &self.results {
+            directories
+                .entry(parent_directory(&result.url))
+                .or_default()
+                .push(result);
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<dirb>\n");
+
+        for (directory, results) in directories {
+            xml.push_str(&format!("  <directory url=\"{}\">\n", escape(&directory)));
+
+            for result in results {
+                xml.push_str(&format!(
+                    "    <entry url=\"{}\" code=\"{}\" size=\"{}\"/>\n",
+                    escape(result.url.as_str()),
+                    result.status,
+                    result.content_length
+                ));
+            }
+
+            xml.push_str("  </directory>\n");
+        }
+
+        xml.push_str("</dirb>\n");
+        xml
+    }
+}
+
+/// Get the directory in which the given url was found, i.e. http://localhost/js/app.js and
+/// http://localhost/js/ were both found in http://localhost/js/ and http://localhost/ respectively
+pub fn parent_directory(url: &Url) -> String {
+    let mut parent = url.clone();
+    parent.set_query(None);
+
+    let path = url.path().trim_end_matches('/');
+
+    match path.rfind('/') {
+        Some(index) => parent.set_path(&path[..=index]),
+        None => parent.set_path("/"),
+    }
+
+    parent.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest;
+
+    /// helper to build a report with a couple of results
+    fn setup_report() -> XmlReport {
+        let mut report = XmlReport::default();
+
+        report.insert(
+            &Url::parse("http://localhost/admin").unwrap(),
+            301,
+            0,
+            &[(String::from("location"), String::from("/admin/"))],
+            None,
+            None,
+        );
+        report.insert(
+            &Url::parse("http://localhost/admin/login.php?a=1&b=2").unwrap(),
+            200,
+            1234,
+            &[],
+            digest::hash_body("md5", b"").as_ref(),
+            None,
+        );
+        report.insert(
+            &Url::parse("http://localhost/favicon.ico").unwrap(),
+            200,
+            1406,
+            &[],
+            None,
+            Some(116323821),
+        );
+
+        report
+    }
+
+    #[test]
+    /// the ferox style lists every result with its headers, along with the scan's tags
+    fn xml_render_ferox_style() {
+        let tags = vec![(String::from("team"), String::from("red & blue"))];
+
+        let expected = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<feroxbuster version=\"1.1.1\">
+  <tags>
+    <tag key=\"team\" value=\"red &amp; blue\"/>
+  </tags>
+  <results>
+    <result url=\"http://localhost/admin\" path=\"/admin\" status=\"301\" content_length=\"0\">
+      <header name=\"location\" value=\"/admin/\"/>
+    </result>
+    <result url=\"http://localhost/admin/login.php?a=1&amp;b=2\" path=\"/admin/login.php\" status=\"200\" content_length=\"1234\" md5=\"d41d8cd98f00b204e9800998ecf8427e\"/>
+    <result url=\"http://localhost/favicon.ico\" path=\"/favicon.ico\" status=\"200\" content_length=\"1406\" favicon_hash=\"116323821\"/>
+  </results>
+</feroxbuster>
+";
+
+        assert_eq!(setup_report().render("ferox", "1.1.1", &tags), expected);
+    }
+
+    #[test]
+    /// the dirb style groups results by the directory they were found in
+    fn xml_render_dirb_style() {
+        let expected = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<dirb>
+  <directory url=\"http://localhost/\">
+    <entry url=\"http://localhost/admin\" code=\"301\" size=\"0\"/>
+    <entry url=\"http://localhost/favicon.ico\" code=\"200\" size=\"1406\"/>
+  </directory>
+  <directory url=\"http://localhost/admin/\">
+    <entry url=\"http://localhost/admin/login.php?a=1&amp;b=2\" code=\"200\" size=\"1234\"/>
+  </directory>
+</dirb>
+";
+
+        assert_eq!(setup_report().render("dirb", "1.1.1", &[]), expected);
+    }
+
+    #[test]
+    /// directories are found in their parent directory
+    fn xml_parent_directory_handles_trailing_slashes() {
+        let url = Url::parse("http://localhost/js/").unwrap();
+        assert_eq!(parent_directory(&url), "http://localhost/");
+
+        let url = Url::parse("http://localhost/").unwrap();
+        assert_eq!(parent_directory(&url), "http://localhost/");
+    }
+
+    #[test]
+    /// characters with special meaning in xml are escaped, invalid ones are dropped
+    fn xml_escape_replaces_special_characters() {
+        assert_eq!(
+            escape("<a href=\"x\">'&'</a>\u{1}"),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+        );
+    }
+}
@@ -25,3 +25,57 @@ fn read_in_config_file_for_settings() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+/// --dump-config prints the combination of config file, environment, and command line as toml
+fn dump_config_prints_effective_configuration() -> Result<(), Box<dyn std::error::Error>> {
+    let (tmp_dir, _) = setup_tmp_directory(
+        &[
+            "threads = 37".to_string(),
+            "depth = 2".to_string(),
+            "timeout = 3".to_string(),
+        ],
+        "ferox-config.toml",
+    )?;
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .current_dir(&tmp_dir)
+        .env("FEROX_DEPTH", "5")
+        .env("FEROX_TIMEOUT", "9")
+        .arg("--timeout")
+        .arg("11")
+        .arg("--dump-config")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("threads = 37\n")
+                .and(predicate::str::contains("depth = 5\n"))
+                .and(predicate::str::contains("timeout = 11\n"))
+                .and(predicate::str::contains("─┬─").not()),
+        );
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// --dump-config json prints the effective configuration as a json object
+fn dump_config_prints_json() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg("http://localhost")
+        .arg("--dump-config")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("{").and(predicate::str::contains(
+                "\"target_url\": \"http://localhost\"",
+            )),
+        );
+
+    Ok(())
+}
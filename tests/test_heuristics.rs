@@ -435,3 +435,180 @@ fn heuristics_wildcard_test_with_redirect_as_response_code(
     assert_eq!(mock2.times_called(), 1);
     Ok(())
 }
+
+#[test]
+/// test finds a wildcard whose size can't be predicted, expect the directory to be skipped
+fn heuristics_wildcard_test_with_unstable_wildcard_skips_directory(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mut mocks = vec![];
+
+    for (length, body) in &[(32, "short"), (64, "a bit longer"), (96, "the longest one")] {
+        mocks.push(
+            Mock::new()
+                .expect_method(GET)
+                .expect_path_matches(Regex::new(&format!("/[a-zA-Z0-9]{{{}}}/", length)).unwrap())
+                .return_status(200)
+                .return_body(body)
+                .create_on(&srv),
+        );
+    }
+
+    let license = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE/")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--addslash")
+        .unwrap();
+
+    teardown_tmp_directory(tmp_dir);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("WLD")
+            .and(predicate::str::contains(
+                "Wildcard response is unstable; skipping",
+            ))
+            .and(predicate::str::contains("--dontfilter")),
+    );
+
+    for mock in mocks {
+        assert_eq!(mock.times_called(), 1);
+    }
+    assert_eq!(license.times_called(), 0);
+    Ok(())
+}
+
+#[test]
+/// test finds a static wildcard, expect responses with the same size but a different status
+/// code to still be reported
+fn heuristics_static_wildcard_only_filters_same_status() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("/[a-zA-Z0-9]{32}/").unwrap())
+        .return_status(200)
+        .return_body("this is a testAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
+        .create_on(&srv);
+
+    let mock2 = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("/[a-zA-Z0-9]{96}/").unwrap())
+        .return_status(200)
+        .return_body("this is a testAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
+        .create_on(&srv);
+
+    let license = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE/")
+        .return_status(403)
+        .return_body("this is a testAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--addslash")
+        .unwrap();
+
+    teardown_tmp_directory(tmp_dir);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Wildcard response is static; auto-filtering 46")
+            .and(predicate::str::contains("403"))
+            .and(predicate::str::contains("/LICENSE/")),
+    );
+
+    assert_eq!(mock.times_called(), 1);
+    assert_eq!(mock2.times_called(), 1);
+    assert_eq!(license.times_called(), 1);
+    Ok(())
+}
+
+#[test]
+/// test fingerprints a not-found page served with a 200, expect pages that read like it and pages
+/// titled `Not Found` to be filtered, while other pages are reported
+fn heuristics_soft_404_filters_pages_like_the_not_found_page(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(
+        &[
+            "admin".to_string(),
+            "missing".to_string(),
+            "old".to_string(),
+        ],
+        "wordlist",
+    )?;
+
+    let not_found = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("/[a-zA-Z0-9]{64}$").unwrap())
+        .return_status(200)
+        .return_body(
+            "<html><title>Acme</title><p>Sorry, that page went missing. Go home.</p></html>",
+        )
+        .create_on(&srv);
+
+    let missing = Mock::new()
+        .expect_method(GET)
+        .expect_path("/missing")
+        .return_status(200)
+        .return_body(
+            "<html><title>Acme</title><p>Sorry, that page went missing. Go home.</p></html>",
+        )
+        .create_on(&srv);
+
+    let old = Mock::new()
+        .expect_method(GET)
+        .expect_path("/old")
+        .return_status(200)
+        .return_body("<html><title>404 - Not Found</title></html>")
+        .create_on(&srv);
+
+    let admin = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin")
+        .return_status(200)
+        .return_body("<html><title>Acme</title><form>Username Password Login</form></html>")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--soft-404")
+        .arg("--dontfilter")
+        .unwrap();
+
+    teardown_tmp_directory(tmp_dir);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Not-found page titled \"acme\" is 200")
+            .and(predicate::str::contains("/admin"))
+            .and(predicate::str::contains("/missing").not())
+            .and(predicate::str::contains("/old").not()),
+    );
+
+    assert_eq!(not_found.times_called(), 1);
+    assert_eq!(missing.times_called(), 1);
+    assert_eq!(old.times_called(), 1);
+    assert_eq!(admin.times_called(), 1);
+    Ok(())
+}
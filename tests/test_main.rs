@@ -27,7 +27,7 @@ fn main_use_root_owned_file_as_wordlist() -> Result<(), Box<dyn std::error::Erro
         .assert()
         .success()
         .stderr(predicate::str::contains(
-            "ERROR main::get_unique_words_from_wordlist Permission denied (os error 13)",
+            "ERROR engine::get_unique_words_from_wordlist Permission denied (os error 13)",
         ));
 
     // connectivity test hits it once
@@ -58,7 +58,7 @@ fn main_use_empty_wordlist() -> Result<(), Box<dyn std::error::Error>> {
         .assert()
         .failure()
         .stderr(predicate::str::contains(
-            "ERROR main::scan Did not find any words in",
+            "ERROR engine::scan Did not find any words in",
         ));
 
     assert_eq!(mock.times_called(), 1);
@@ -96,3 +96,230 @@ fn main_use_empty_stdin_targets() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+/// --generate-completions prints a completion script and exits without requiring --url
+fn main_generate_completions_without_url() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--generate-completions")
+        .arg("zsh")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("#compdef feroxbuster")
+                .and(predicate::str::contains("]:FILE:_files"))
+                .and(predicate::str::contains(
+                    "(no-findings unreachable cancelled errors)",
+                )),
+        );
+    Ok(())
+}
+
+#[test]
+/// download the wordlist from a url, cache it, and use its words to scan the target
+fn main_use_remote_wordlist_with_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, _) = setup_tmp_directory(&[], "unused")?;
+    let cache = tmp_dir.path().join("cache");
+
+    let wordlist_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/lists/words.txt")
+        .return_status(200)
+        .return_body("# comment\nLICENSE\n")
+        .create_on(&srv);
+
+    let found_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    for _ in 0..2 {
+        Command::cargo_bin("feroxbuster")
+            .unwrap()
+            .arg("--url")
+            .arg(srv.url("/"))
+            .arg("--wordlist")
+            .arg(srv.url("/lists/words.txt"))
+            .arg("--wordlist-cache")
+            .arg(cache.as_os_str())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("/LICENSE"));
+    }
+
+    // the second run uses the cached copy
+    assert_eq!(wordlist_mock.times_called(), 1);
+    assert_eq!(found_mock.times_called(), 2);
+
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// a downloaded wordlist that doesn't match --wordlist-sha256 is refused
+fn main_use_remote_wordlist_with_wrong_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+
+    let wordlist_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/words.txt")
+        .return_status(200)
+        .return_body("LICENSE\n")
+        .create_on(&srv);
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(srv.url("/words.txt"))
+        .arg("--wordlist-sha256")
+        .arg("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "doesn't match the expected checksum",
+        ));
+
+    assert_eq!(wordlist_mock.times_called(), 1);
+    Ok(())
+}
+
+#[test]
+/// combine multiple wordlists, requesting words found in more than one list only once
+fn main_use_multiple_wordlists() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, first) =
+        setup_tmp_directory(&["LICENSE".to_string(), "  shared  ".to_string()], "first")?;
+    let second = tmp_dir.path().join("second");
+    std::fs::write(&second, "# comment\nshared\nREADME\n")?;
+
+    let mut mocks = vec![];
+
+    for path in &["/LICENSE", "/shared", "/README"] {
+        mocks.push(
+            Mock::new()
+                .expect_method(GET)
+                .expect_path(path)
+                .return_status(200)
+                .return_body("this is a test")
+                .create_on(&srv),
+        );
+    }
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(first.as_os_str())
+        .arg("--wordlist")
+        .arg(second.as_os_str())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("/LICENSE")
+                .and(predicate::str::contains("/shared"))
+                .and(predicate::str::contains("/README")),
+        );
+
+    for mock in mocks {
+        assert_eq!(mock.times_called(), 1);
+    }
+
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// scan with the embedded wordlist, no wordlist file needed
+fn main_use_embedded_wordlist() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg("embedded")
+        .arg("--no-recursion")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/admin"));
+
+    assert_eq!(mock.times_called(), 1);
+    Ok(())
+}
+
+#[test]
+/// scan targets from a --targets file, each with its own wordlist and headers
+fn main_use_targets_file_with_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    let first = MockServer::start();
+    let second = MockServer::start();
+
+    let (tmp_dir, wordlist) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+    let other_wordlist = tmp_dir.path().join("other-wordlist");
+    std::fs::write(&other_wordlist, "README\n")?;
+
+    let targets = tmp_dir.path().join("targets.toml");
+    std::fs::write(
+        &targets,
+        format!(
+            "[[target]]\nurl = \"{}\"\nheaders = {{ X-Target = \"first\" }}\n\n\
+             [[target]]\nurl = \"{}\"\nwordlist = \"{}\"\n",
+            first.url("/"),
+            second.url("/"),
+            other_wordlist.to_string_lossy()
+        ),
+    )?;
+
+    let first_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .expect_header("X-Target", "first")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&first);
+
+    let second_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/README")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&second);
+
+    // the second target uses its own wordlist, so it never sees the global one
+    let unexpected = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .create_on(&second);
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--targets")
+        .arg(targets.as_os_str())
+        .arg("--wordlist")
+        .arg(wordlist.as_os_str())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/LICENSE").and(predicate::str::contains("/README")));
+
+    assert_eq!(first_mock.times_called(), 1);
+    assert_eq!(second_mock.times_called(), 1);
+    assert_eq!(unexpected.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
@@ -1,7 +1,7 @@
 mod utils;
 use assert_cmd::prelude::*;
-use httpmock::Method::GET;
-use httpmock::{Mock, MockServer};
+use httpmock::Method::{GET, HEAD, POST};
+use httpmock::{Mock, MockServer, Regex};
 use predicates::prelude::*;
 use std::process::Command;
 use utils::{setup_tmp_directory, teardown_tmp_directory};
@@ -211,6 +211,233 @@ fn scanner_single_request_scan_with_file_output() -> Result<(), Box<dyn std::err
     assert!(contents.contains("200"));
     assert!(contents.contains("14"));
 
+    // log messages are only written with --output-errors
+    assert!(!contents.contains("TRC"));
+
+    assert_eq!(mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// send a single valid request with --output-errors, expect results and log messages on disk
+fn scanner_single_request_scan_with_output_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let outfile = tmp_dir.path().join("output");
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("-vv")
+        .arg("--output-errors")
+        .arg("-o")
+        .arg(outfile.as_os_str())
+        .unwrap();
+
+    let contents = std::fs::read_to_string(outfile)?;
+
+    assert!(contents.contains("/LICENSE"));
+    assert!(contents.contains("INF"));
+    assert!(contents.contains("Starting scan against"));
+
+    assert_eq!(mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// send a few valid requests with --json and --compare, expect new/changed/missing results
+fn scanner_scan_with_json_output_and_compare() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let urls = ["LICENSE".to_string(), "README".to_string()];
+    let (tmp_dir, file) = setup_tmp_directory(&urls, "wordlist")?;
+
+    let license_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let readme_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/README")
+        .return_status(200)
+        .return_body("readme")
+        .create_on(&srv);
+
+    let previous = tmp_dir.path().join("previous");
+    std::fs::write(
+        &previous,
+        format!(
+            "200         14 {}\n403          0 {}\n200          1 {}\n",
+            srv.url("/LICENSE"),
+            srv.url("/README"),
+            srv.url("/gone")
+        ),
+    )?;
+
+    let outfile = tmp_dir.path().join("output");
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--json")
+        .arg("--compare")
+        .arg(previous.as_os_str())
+        .arg("-o")
+        .arg(outfile.as_os_str())
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("CHANGED")
+            .and(predicate::str::contains("MISSING"))
+            .and(predicate::str::contains("/gone")),
+    );
+
+    let contents = std::fs::read_to_string(outfile)?;
+
+    assert!(contents.contains(r#""change":"unchanged""#));
+    assert!(contents.contains(r#""change":"changed""#));
+    assert!(contents.contains(r#""change":"missing""#));
+    assert!(contents.contains(r#""type":"response""#));
+
+    assert_eq!(license_mock.times_called(), 1);
+    assert_eq!(readme_mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// send a single valid request with --show-headers, expect the selected headers in the output
+fn scanner_single_request_scan_with_show_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_header("Server", "ferox-test")
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let outfile = tmp_dir.path().join("output");
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--show-headers")
+        .arg("Server,X-Powered-By")
+        .arg("--json")
+        .arg("-o")
+        .arg(outfile.as_os_str())
+        .unwrap();
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[server: ferox-test]"));
+
+    let contents = std::fs::read_to_string(outfile)?;
+
+    assert!(contents.contains(r#""headers":{"server":"ferox-test"}"#));
+
+    assert_eq!(mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// send a single valid request with --save-responses, expect the body and headers on disk
+fn scanner_single_request_scan_with_save_responses() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_header("Server", "ferox-test")
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let responses = tmp_dir.path().join("responses");
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--save-responses")
+        .arg(responses.as_os_str())
+        .unwrap();
+
+    let host = format!("{}_{}", srv.host(), srv.port());
+    let body = std::fs::read_to_string(responses.join(&host).join("LICENSE.body"))?;
+    let headers = std::fs::read_to_string(responses.join(&host).join("LICENSE.headers"))?;
+
+    assert_eq!(body, "this is a test");
+    assert!(headers.contains("200 OK"));
+    assert!(headers.contains("server: ferox-test"));
+
+    assert_eq!(mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// send a valid request with --tree-output, expect the tree on stdout and on disk
+fn scanner_single_request_scan_with_tree_output() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let outfile = tmp_dir.path().join("tree");
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--tree-output")
+        .arg(outfile.as_os_str())
+        .unwrap();
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("└── LICENSE (200, 14)"));
+
+    let contents = std::fs::read_to_string(outfile)?;
+
+    assert!(contents.starts_with(&srv.url("")));
+    assert!(contents.contains("└── LICENSE (200, 14)"));
+
     assert_eq!(mock.times_called(), 1);
     teardown_tmp_directory(tmp_dir);
     Ok(())
@@ -326,6 +553,42 @@ fn scanner_single_request_quiet_scan() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[test]
+/// send a single valid request using --silent, expect nothing but the url on stdout
+fn scanner_single_request_silent_scan() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--silent")
+        .arg("-vv")
+        .unwrap();
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::similar(format!(
+            "{}\n",
+            srv.url("/LICENSE")
+        )))
+        .stderr(predicate::str::is_empty());
+
+    assert_eq!(mock.times_called(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
 #[test]
 /// send single valid request, get back a 301 without a Location header, expect false
 fn scanner_single_request_returns_301_without_location_header(
@@ -410,3 +673,560 @@ fn scanner_single_request_scan_with_filtered_result() -> Result<(), Box<dyn std:
     teardown_tmp_directory(tmp_dir);
     Ok(())
 }
+
+#[test]
+/// send requests that are redirected to an unrelated location while following redirects, expect
+/// the location not to be scanned recursively
+fn scanner_recursive_request_scan_ignores_unrelated_redirects(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let urls = ["admin".to_string(), "file.js".to_string()];
+    let (tmp_dir, file) = setup_tmp_directory(&urls, "wordlist")?;
+
+    let admin_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin")
+        .return_status(302)
+        .return_header("Location", &srv.url("/login/"))
+        .create_on(&srv);
+
+    let login_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/login/")
+        .return_status(200)
+        .return_body("please log in")
+        .create_on(&srv);
+
+    let login_file_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/login/file.js")
+        .return_status(200)
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("-t")
+        .arg("1")
+        .arg("--redirects")
+        .unwrap();
+
+    cmd.assert().success();
+
+    assert_eq!(admin_mock.times_called(), 1);
+    assert_eq!(login_mock.times_called(), 1);
+    assert_eq!(login_file_mock.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// send a request to a directory that lists its contents while using --use-listings, expect the
+/// listing's entries to be requested and reported instead of the wordlist's words
+fn scanner_use_listings_requests_entries_instead_of_words() -> Result<(), Box<dyn std::error::Error>>
+{
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let listing_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/")
+        .return_status(200)
+        .return_body(
+            r#"<html><title>Index of /</title><a href="?C=N;O=D">Name</a><a href="backup.zip">backup.zip</a></html>"#,
+        )
+        .create_on(&srv);
+
+    let backup_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/backup.zip")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let license_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--use-listings")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Directory listing found at")
+            .and(predicate::str::is_match("200.*backup.zip").unwrap()),
+    );
+
+    // connectivity test and listing test
+    assert_eq!(listing_mock.times_called(), 2);
+    assert_eq!(backup_mock.times_called(), 1);
+    assert_eq!(license_mock.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// a scan whose requests are answered with CAPTCHA pages ends with exit code 6 when
+/// --ban-policy is stop
+fn scanner_ban_policy_stop_ends_scan_on_captcha_pages() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let words: Vec<String> = (0..10).map(|i| format!("word{}", i)).collect();
+    let (tmp_dir, file) = setup_tmp_directory(&words, "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("^/word").unwrap())
+        .return_status(403)
+        .return_body(r#"<html><div class="g-recaptcha" data-sitekey="abc"></div></html>"#)
+        .create_on(&srv);
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--threads")
+        .arg("1")
+        .arg("--ban-policy")
+        .arg("stop")
+        .assert()
+        .code(6)
+        .stdout(predicate::str::contains(
+            "Requests look blocked (CAPTCHA pages)",
+        ));
+
+    assert!(mock.times_called() >= 5);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// a 403 for a directory whose nonexistent children aren't forbidden is flagged as a forbidden
+/// listing when --analyze-403 is used
+fn scanner_analyze_403_flags_forbidden_listings() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["admin".to_string()], "wordlist")?;
+
+    let admin_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin")
+        .return_status(403)
+        .return_body("forbidden")
+        .create_on(&srv);
+
+    let directory_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin/")
+        .return_status(403)
+        .return_body("forbidden")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--analyze-403")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::is_match("403.*/admin \\(listing forbidden\\)")
+            .unwrap()
+            .and(predicate::str::contains("/admin/admin").not()),
+    );
+
+    assert_eq!(admin_mock.times_called(), 1);
+    assert!(directory_mock.times_called() >= 1);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// a directory that answers any path added to it as a directory is a recursion trap; its
+/// subdirectories aren't scanned
+fn scanner_recursion_stops_at_echoing_directories() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["echo".to_string()], "wordlist")?;
+
+    let echo_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/echo/")
+        .return_status(200)
+        .return_body("echoed")
+        .create_on(&srv);
+
+    let child_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("^/echo/[^/]+/$").unwrap())
+        .return_status(200)
+        .return_body("echoed")
+        .create_on(&srv);
+
+    let grandchild_mock = Mock::new()
+        .expect_method(GET)
+        .expect_path_matches(Regex::new("^/echo/[^/]+/[^/]+/$").unwrap())
+        .return_status(200)
+        .return_body("echoed")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--addslash")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Recursion trap found at")
+            .and(predicate::str::contains("echoing parent directory")),
+    );
+
+    assert_eq!(echo_mock.times_called(), 1);
+    // two wildcard requests and the word itself
+    assert_eq!(child_mock.times_called(), 3);
+    assert_eq!(grandchild_mock.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// with --verify, every finding is requested again once the scan is complete and a summary of
+/// the ones that reproduced is printed
+fn scanner_verify_requests_findings_again() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--verify")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/LICENSE")
+            .and(predicate::str::contains("1 of 1 findings reproduced"))
+            .and(predicate::str::contains("no longer reproduces").not()),
+    );
+
+    assert_eq!(mock.times_called(), 2);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// send a single valid request to a target whose Server header gives Tomcat away; --smart should
+/// fingerprint it and scan it with the jsp extension as well
+fn scanner_smart_applies_fingerprinted_extensions() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let root = Mock::new()
+        .expect_method(GET)
+        .expect_path("/")
+        .return_status(200)
+        .return_header("Server", "Apache-Coyote/1.1")
+        .create_on(&srv);
+
+    let mock = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE.jsp")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--smart")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("fingerprinted as Tomcat (header server)")
+            .and(predicate::str::contains("applying extensions [jsp]"))
+            .and(predicate::str::contains("/LICENSE.jsp")),
+    );
+
+    assert!(root.times_called() >= 1);
+    assert_eq!(mock.times_called(), 1);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// with --respect-robots, a word robots.txt disallows isn't requested and is reported as present
+/// but skipped, while allowed words are requested as usual
+fn scanner_respect_robots_skips_disallowed_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let urls = ["LICENSE".to_string(), "secret".to_string()];
+    let (tmp_dir, file) = setup_tmp_directory(&urls, "wordlist")?;
+
+    let robots = Mock::new()
+        .expect_method(GET)
+        .expect_path("/robots.txt")
+        .return_status(200)
+        .return_body("User-agent: *\nDisallow: /secret\n")
+        .create_on(&srv);
+
+    let allowed = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let disallowed = Mock::new()
+        .expect_method(GET)
+        .expect_path("/secret")
+        .return_status(200)
+        .return_body("this is a secret")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--respect-robots")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/secret present in robots.txt; skipped")
+            .and(predicate::str::contains("/LICENSE")),
+    );
+
+    assert_eq!(robots.times_called(), 1);
+    assert_eq!(allowed.times_called(), 1);
+    assert_eq!(disallowed.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// test passes if a 403 whose path is served with a trailing slash is recursed into, and a file
+/// that isn't served with one isn't
+fn scanner_slash_probe_recurses_into_directories() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let urls = ["LICENSE".to_string(), "admin".to_string()];
+    let (tmp_dir, file) = setup_tmp_directory(&urls, "wordlist")?;
+
+    let forbidden = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin")
+        .return_status(403)
+        .create_on(&srv);
+
+    let directory = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin/")
+        .return_status(200)
+        .return_body("welcome")
+        .create_on(&srv);
+
+    let license = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let nested = Mock::new()
+        .expect_method(GET)
+        .expect_path("/admin/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--slash-probe")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/admin (no slash 403, slash 200, directory)").and(
+            predicate::str::contains("/LICENSE (no slash 200, slash 404, not a directory)"),
+        ),
+    );
+
+    assert_eq!(forbidden.times_called(), 1);
+    assert!(directory.times_called() >= 1);
+    assert_eq!(license.times_called(), 1);
+    assert_eq!(nested.times_called(), 1);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// with --method HEAD, a request rejected with a 405 is retried with GET, and the GET response
+/// is reported along with the 405
+fn scanner_falls_back_to_get_when_method_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let rejected = Mock::new()
+        .expect_method(HEAD)
+        .expect_path("/LICENSE")
+        .return_status(405)
+        .create_on(&srv);
+
+    let accepted = Mock::new()
+        .expect_method(GET)
+        .expect_path("/LICENSE")
+        .return_status(200)
+        .return_body("this is a test")
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--method")
+        .arg("HEAD")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/LICENSE")
+            .and(predicate::str::contains("200"))
+            .and(predicate::str::contains("405")),
+    );
+
+    assert_eq!(rejected.times_called(), 1);
+    assert_eq!(accepted.times_called(), 1);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// a GET rejected with a 405 isn't retried with anything unless --method lists a fallback
+fn scanner_never_falls_back_from_get_on_its_own() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["login".to_string()], "wordlist")?;
+
+    let rejected = Mock::new()
+        .expect_method(GET)
+        .expect_path("/login")
+        .return_status(405)
+        .create_on(&srv);
+
+    let posted = Mock::new()
+        .expect_method(POST)
+        .expect_path("/login")
+        .return_status(200)
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/login")
+            .and(predicate::str::contains("405"))
+            .and(predicate::str::contains("200").not()),
+    );
+
+    assert_eq!(rejected.times_called(), 1);
+    assert_eq!(posted.times_called(), 0);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
+
+#[test]
+/// --method GET,POST retries a GET rejected with a 405 with POST, reporting both responses
+fn scanner_falls_back_to_listed_method() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["login".to_string()], "wordlist")?;
+
+    let rejected = Mock::new()
+        .expect_method(GET)
+        .expect_path("/login")
+        .return_status(405)
+        .create_on(&srv);
+
+    let posted = Mock::new()
+        .expect_method(POST)
+        .expect_path("/login")
+        .return_status(200)
+        .create_on(&srv);
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--method")
+        .arg("GET,POST")
+        .unwrap();
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("/login")
+            .and(predicate::str::contains("405"))
+            .and(predicate::str::contains("200")),
+    );
+
+    assert_eq!(rejected.times_called(), 1);
+    assert_eq!(posted.times_called(), 1);
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}